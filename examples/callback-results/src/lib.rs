@@ -1,9 +1,10 @@
 use near_sdk::require;
-use near_sdk::{env, near, Promise, PromiseError};
+use near_sdk::{env, near, AccountId, Promise, PromiseError};
 
 const A_VALUE: u8 = 8;
 
 #[near(contract_state)]
+#[derive(Default)]
 pub struct Callback;
 
 #[near]
@@ -59,6 +60,50 @@ impl Callback {
         }
         (b.is_err(), c.is_err(), d.is_err())
     }
+
+    /// Calls function b, guarded against reentrancy for the caller until `handle_guarded` is
+    /// called back. The callback panics if `fail_callback` is true, and runs out of gas if
+    /// `exhaust_callback_gas` is true, leaving the guard set.
+    #[non_reentrant(then = "handle_guarded")]
+    pub fn call_guarded(
+        &mut self,
+        fail_b: bool,
+        fail_callback: bool,
+        exhaust_callback_gas: bool,
+    ) -> Promise {
+        Self::ext(env::current_account_id()).b(fail_b).then(
+            Self::ext(env::current_account_id()).handle_guarded(
+                env::predecessor_account_id(),
+                fail_callback,
+                exhaust_callback_gas,
+            ),
+        )
+    }
+
+    /// Clears the guard of `call_guarded` for `account_id` and returns whether b failed.
+    #[private]
+    #[non_reentrant(callback, key = "account_id")]
+    pub fn handle_guarded(
+        &mut self,
+        account_id: AccountId,
+        fail: bool,
+        exhaust_gas: bool,
+        #[callback_result] b: Result<String, PromiseError>,
+    ) -> bool {
+        require!(!fail, "failed within handle_guarded");
+        if exhaust_gas {
+            loop {
+                env::keccak256(account_id.as_bytes());
+            }
+        }
+        b.is_err()
+    }
+
+    /// Clears the guard of `call_guarded` left set for `account_id` by a failed callback.
+    #[private]
+    pub fn clear_guard(&mut self, account_id: AccountId) -> bool {
+        near_sdk::utils::clear_non_reentrant(&account_id)
+    }
 }
 
 #[cfg(all(test, not(target_arch = "wasm32")))]
@@ -120,4 +165,38 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn non_reentrant_callback_failures() -> anyhow::Result<()> {
+        let wasm = near_workspaces::compile_project("./").await?;
+        let worker = near_workspaces::sandbox().await?;
+        let contract = worker.dev_deploy(&wasm).await?;
+        let alice = worker.dev_create_account().await?;
+        let bob = worker.dev_create_account().await?;
+
+        let call_guarded = |account: &near_workspaces::Account, args: (bool, bool, bool)| {
+            account.call(contract.id(), "call_guarded").args_json(args).max_gas().transact()
+        };
+
+        // A failed promise is handled by the callback, which clears the guard.
+        assert!(call_guarded(&alice, (true, false, false)).await?.json::<bool>()?);
+        assert!(!call_guarded(&alice, (false, false, false)).await?.json::<bool>()?);
+
+        // A callback which panics, then one which runs out of gas.
+        for (fail_callback, exhaust_callback_gas) in [(true, false), (false, true)] {
+            let res = call_guarded(&alice, (false, fail_callback, exhaust_callback_gas)).await?;
+            assert!(res.is_failure());
+
+            // The guard of alice stays set, but not the ones of the other callers.
+            let err = call_guarded(&alice, (false, false, false)).await?.into_result().unwrap_err();
+            assert!(format!("{:?}", err).contains("Method call_guarded cannot be reentered"));
+            assert!(!call_guarded(&bob, (false, false, false)).await?.json::<bool>()?);
+
+            let res = contract.call("clear_guard").args_json((alice.id(),)).transact().await?;
+            assert!(res.json::<bool>()?);
+            assert!(!call_guarded(&alice, (false, false, false)).await?.json::<bool>()?);
+        }
+
+        Ok(())
+    }
 }
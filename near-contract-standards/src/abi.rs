@@ -0,0 +1,233 @@
+use crate::contract_metadata::Standard;
+use near_sdk::serde_json::{self, Map, Value};
+use std::fmt;
+
+/// ABI of the methods of a standard interface, in the format of the body of a contract ABI
+/// generated by `cargo near abi`: a `functions` list, and a `root_schema` holding the definitions
+/// of the types the functions refer to.
+///
+/// The ABI of a contract only contains the methods it defines in its `#[near]` impl blocks, so the
+/// methods of a standard implemented through a component or through the default implementations
+/// of a trait can be missing from it. These fragments can be merged into the generated ABI with
+/// [`merge_abi_fragments`], so that wallets and other clients see the whole standard interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AbiFragment {
+    /// Name of the standard, in lowercase NEP format, e.g. `"nep141"`.
+    pub standard: &'static str,
+    /// Version of the standard the fragment describes.
+    pub version: &'static str,
+    /// The fragment as JSON, also shipped in the `abi` directory of the crate.
+    pub json: &'static str,
+}
+
+/// ABI of the core fungible token methods of [NEP-141](https://nomicon.io/Standards/Tokens/FungibleToken/Core).
+pub const NEP141_ABI: AbiFragment =
+    AbiFragment { standard: "nep141", version: "1.0.0", json: include_str!("../abi/nep141.json") };
+
+/// ABI of the storage management methods of [NEP-145](https://nomicon.io/Standards/StorageManagement).
+pub const NEP145_ABI: AbiFragment =
+    AbiFragment { standard: "nep145", version: "1.0.0", json: include_str!("../abi/nep145.json") };
+
+/// ABI of the core non-fungible token methods of [NEP-171](https://nomicon.io/Standards/Tokens/NonFungibleToken/Core).
+pub const NEP171_ABI: AbiFragment =
+    AbiFragment { standard: "nep171", version: "1.0.0", json: include_str!("../abi/nep171.json") };
+
+/// All the ABI fragments shipped with the crate.
+pub const STANDARD_ABI_FRAGMENTS: &[AbiFragment] = &[NEP141_ABI, NEP145_ABI, NEP171_ABI];
+
+impl AbiFragment {
+    /// Returns the fragment of a standard listed in the
+    /// [`ContractSourceMetadata`](crate::contract_metadata::ContractSourceMetadata) of a contract,
+    /// if one is shipped with the crate for this version of the standard.
+    pub fn for_standard(standard: &Standard) -> Option<Self> {
+        STANDARD_ABI_FRAGMENTS.iter().copied().find(|fragment| {
+            fragment.standard == standard.standard && fragment.version == standard.version
+        })
+    }
+
+    /// Parses the fragment JSON.
+    pub fn to_value(&self) -> Value {
+        // The fragments are checked to be valid JSON by the tests of the crate.
+        serde_json::from_str(self.json).expect("ABI fragments are valid JSON")
+    }
+}
+
+/// Merges the `fragments` into the JSON `abi` of a contract, as generated by `cargo near abi`.
+///
+/// Functions of the fragments are added to the ABI unless it already has a function with the same
+/// name, in which case the function of the contract is kept. Type definitions are added in the
+/// same way. Returns the names of the added functions.
+///
+/// # Examples
+///
+/// ```
+/// use near_contract_standards::abi::{merge_abi_fragments, NEP141_ABI, NEP145_ABI};
+/// use near_sdk::serde_json::json;
+///
+/// let mut abi = json!({
+///     "schema_version": "0.4.0",
+///     "metadata": { "name": "token" },
+///     "body": {
+///         "functions": [{ "name": "new", "kind": "call", "modifiers": ["init"] }],
+///         "root_schema": { "definitions": {} }
+///     }
+/// });
+///
+/// let added = merge_abi_fragments(&mut abi, &[NEP141_ABI, NEP145_ABI]).unwrap();
+/// assert!(added.contains(&"ft_transfer".to_string()));
+/// assert!(abi["body"]["root_schema"]["definitions"]["StorageBalance"].is_object());
+/// ```
+pub fn merge_abi_fragments(
+    abi: &mut Value,
+    fragments: &[AbiFragment],
+) -> Result<Vec<String>, MergeAbiError> {
+    let body = abi
+        .get_mut("body")
+        .and_then(Value::as_object_mut)
+        .ok_or(MergeAbiError { kind: MergeAbiErrorKind::MissingBody })?;
+
+    let mut functions = match body.remove("functions") {
+        Some(Value::Array(functions)) => functions,
+        None => Vec::new(),
+        Some(_) => return Err(MergeAbiError { kind: MergeAbiErrorKind::InvalidFunctions }),
+    };
+    let root_schema = body
+        .entry("root_schema")
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .ok_or(MergeAbiError { kind: MergeAbiErrorKind::InvalidRootSchema })?;
+    let definitions = root_schema
+        .entry("definitions")
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .ok_or(MergeAbiError { kind: MergeAbiErrorKind::InvalidRootSchema })?;
+
+    let mut added = Vec::new();
+    for fragment in fragments {
+        let fragment = fragment.to_value();
+        for function in fragment["functions"].as_array().into_iter().flatten() {
+            let name = function["name"].as_str().unwrap_or_default();
+            if !functions.iter().any(|existing| existing["name"] == name) {
+                added.push(name.to_string());
+                functions.push(function.clone());
+            }
+        }
+        for (name, definition) in
+            fragment["root_schema"]["definitions"].as_object().into_iter().flatten()
+        {
+            definitions.entry(name.clone()).or_insert_with(|| definition.clone());
+        }
+    }
+
+    body.insert("functions".to_string(), Value::Array(functions));
+    Ok(added)
+}
+
+/// Error returned by [`merge_abi_fragments`] if the contract ABI does not have the expected
+/// format.
+#[derive(Debug)]
+pub struct MergeAbiError {
+    kind: MergeAbiErrorKind,
+}
+
+#[derive(Debug)]
+enum MergeAbiErrorKind {
+    MissingBody,
+    InvalidFunctions,
+    InvalidRootSchema,
+}
+
+impl fmt::Display for MergeAbiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            MergeAbiErrorKind::MissingBody => f.write_str("ABI has no `body` object"),
+            MergeAbiErrorKind::InvalidFunctions => f.write_str("ABI `functions` is not an array"),
+            MergeAbiErrorKind::InvalidRootSchema => {
+                f.write_str("ABI `root_schema` or its `definitions` is not an object")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MergeAbiError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::serde_json::json;
+
+    fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    refs.push(reference);
+                }
+                map.values().for_each(|value| collect_refs(value, refs));
+            }
+            Value::Array(values) => values.iter().for_each(|value| collect_refs(value, refs)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn fragments_are_self_contained() {
+        for fragment in STANDARD_ABI_FRAGMENTS {
+            let value = fragment.to_value();
+            let functions = value["functions"].as_array().unwrap();
+            assert!(!functions.is_empty(), "{} has no functions", fragment.standard);
+            for function in functions {
+                assert!(function["name"].is_string());
+                assert!(matches!(function["kind"].as_str(), Some("view" | "call")));
+            }
+
+            let mut refs = Vec::new();
+            collect_refs(&value, &mut refs);
+            let definitions = value["root_schema"]["definitions"].as_object().unwrap();
+            for reference in refs {
+                let name = reference.strip_prefix("#/definitions/").unwrap();
+                assert!(
+                    definitions.contains_key(name),
+                    "{} refers to undefined {}",
+                    fragment.standard,
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn merge_keeps_contract_functions() {
+        let mut abi = json!({
+            "body": {
+                "functions": [{ "name": "ft_balance_of", "kind": "view", "doc": "custom" }],
+                "root_schema": { "definitions": { "AccountId": { "type": "string" } } }
+            }
+        });
+        let added = merge_abi_fragments(&mut abi, &[NEP141_ABI, NEP141_ABI]).unwrap();
+        assert_eq!(
+            added,
+            ["ft_transfer", "ft_transfer_call", "ft_total_supply", "ft_resolve_transfer"]
+        );
+
+        let functions = abi["body"]["functions"].as_array().unwrap();
+        assert_eq!(functions.len(), 5);
+        assert_eq!(functions[0]["doc"], "custom");
+        assert_eq!(
+            abi["body"]["root_schema"]["definitions"]["AccountId"],
+            json!({ "type": "string" })
+        );
+
+        assert!(merge_abi_fragments(&mut json!({}), &[NEP141_ABI]).is_err());
+    }
+
+    #[test]
+    fn fragment_for_standard() {
+        let standard = |standard: &str, version: &str| Standard {
+            standard: standard.to_string(),
+            version: version.to_string(),
+        };
+        assert_eq!(AbiFragment::for_standard(&standard("nep171", "1.0.0")), Some(NEP171_ABI));
+        assert_eq!(AbiFragment::for_standard(&standard("nep171", "2.0.0")), None);
+        assert_eq!(AbiFragment::for_standard(&standard("nep999", "1.0.0")), None);
+    }
+}
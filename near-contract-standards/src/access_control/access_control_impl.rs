@@ -0,0 +1,184 @@
+use near_sdk::store::{LookupMap, LookupSet};
+use near_sdk::{env, near, require, AccountId, HasRoles, IntoStorageKey};
+
+/// Admin role of the roles whose admin role is not set, which is its own admin role.
+pub const DEFAULT_ADMIN_ROLE: &str = "admin";
+
+/// Changes of the roles of an [`AccessControl`].
+#[near(event_json(standard = "acl"))]
+pub enum AccessControlEvent<'a> {
+    /// `account_id` was granted `role` by `sender_id`.
+    #[event_version("1.0.0")]
+    RoleGranted { role: &'a str, account_id: &'a AccountId, sender_id: &'a AccountId },
+    /// `role` was revoked from `account_id` by `sender_id`, or renounced if they are the same.
+    #[event_version("1.0.0")]
+    RoleRevoked { role: &'a str, account_id: &'a AccountId, sender_id: &'a AccountId },
+    /// The admin role of `role` was changed from `previous_admin_role` to `admin_role`.
+    #[event_version("1.0.0")]
+    RoleAdminChanged { role: &'a str, previous_admin_role: &'a str, admin_role: &'a str },
+}
+
+/// Implementation of role-based access control.
+///
+/// The methods prefixed with `internal_` change the roles without checking the predecessor, e.g.
+/// to grant the first admin role in the init method of the contract.
+#[near]
+pub struct AccessControl {
+    /// (Role, Account ID) pairs of the members of the roles.
+    pub members: LookupSet<(String, AccountId)>,
+    /// Role -> Its admin role, if not [`DEFAULT_ADMIN_ROLE`].
+    pub admin_roles: LookupMap<String, String>,
+}
+
+impl AccessControl {
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let prefix = prefix.into_storage_key();
+        Self {
+            members: LookupSet::new([prefix.as_slice(), b"m"].concat()),
+            admin_roles: LookupMap::new([prefix.as_slice(), b"a"].concat()),
+        }
+    }
+
+    /// Whether `account_id` has `role`.
+    pub fn has_role(&self, role: &str, account_id: &AccountId) -> bool {
+        self.members.contains(&(role.to_string(), account_id.clone()))
+    }
+
+    /// Panics unless `account_id` has `role`.
+    pub fn assert_role(&self, role: &str, account_id: &AccountId) {
+        if !self.has_role(role, account_id) {
+            env::panic_str(&format!("Account {} doesn't have the role {}", account_id, role));
+        }
+    }
+
+    /// Returns the role whose members can grant and revoke `role`.
+    pub fn role_admin(&self, role: &str) -> String {
+        self.admin_roles.get(role).cloned().unwrap_or_else(|| DEFAULT_ADMIN_ROLE.to_string())
+    }
+
+    /// Sets the role whose members can grant and revoke `role`.
+    pub fn set_role_admin(&mut self, role: &str, admin_role: &str) {
+        require!(
+            role != DEFAULT_ADMIN_ROLE,
+            "The admin role of the default admin role can't be changed"
+        );
+        let previous_admin_role = self.role_admin(role);
+        if admin_role == DEFAULT_ADMIN_ROLE {
+            self.admin_roles.remove(role);
+        } else {
+            self.admin_roles.insert(role.to_string(), admin_role.to_string());
+        }
+        AccessControlEvent::RoleAdminChanged {
+            role,
+            previous_admin_role: &previous_admin_role,
+            admin_role,
+        }
+        .emit();
+    }
+
+    /// Grants `role` to `account_id` if the predecessor has the admin role of `role`. Returns
+    /// whether the account didn't have the role yet.
+    pub fn grant_role(&mut self, role: &str, account_id: &AccountId) -> bool {
+        self.assert_role(&self.role_admin(role), &env::predecessor_account_id());
+        self.internal_grant_role(role, account_id)
+    }
+
+    /// Revokes `role` from `account_id` if the predecessor has the admin role of `role`. Returns
+    /// whether the account had the role.
+    pub fn revoke_role(&mut self, role: &str, account_id: &AccountId) -> bool {
+        self.assert_role(&self.role_admin(role), &env::predecessor_account_id());
+        self.internal_revoke_role(role, account_id)
+    }
+
+    /// Grants `role` to `account_id`. Returns whether the account didn't have the role yet.
+    pub fn internal_grant_role(&mut self, role: &str, account_id: &AccountId) -> bool {
+        let granted = self.members.insert((role.to_string(), account_id.clone()));
+        if granted {
+            AccessControlEvent::RoleGranted {
+                role,
+                account_id,
+                sender_id: &env::predecessor_account_id(),
+            }
+            .emit();
+        }
+        granted
+    }
+
+    /// Revokes `role` from `account_id`. Returns whether the account had the role.
+    pub fn internal_revoke_role(&mut self, role: &str, account_id: &AccountId) -> bool {
+        let revoked = self.members.remove(&(role.to_string(), account_id.clone()));
+        if revoked {
+            AccessControlEvent::RoleRevoked {
+                role,
+                account_id,
+                sender_id: &env::predecessor_account_id(),
+            }
+            .emit();
+        }
+        revoked
+    }
+}
+
+impl HasRoles for AccessControl {
+    fn has_role(&self, role: &str, account_id: &AccountId) -> bool {
+        AccessControl::has_role(self, role, account_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn set_caller(account_id: AccountId) {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(account_id).build());
+    }
+
+    fn setup() -> AccessControl {
+        set_caller(accounts(0));
+        let mut acl = AccessControl::new(b"a");
+        acl.internal_grant_role(DEFAULT_ADMIN_ROLE, &accounts(0));
+        acl
+    }
+
+    #[test]
+    fn grant_and_revoke() {
+        let mut acl = setup();
+        assert!(acl.grant_role("minter", &accounts(1)));
+        assert!(!acl.grant_role("minter", &accounts(1)));
+        assert!(acl.has_role("minter", &accounts(1)));
+        assert!(!acl.has_role("minter", &accounts(2)));
+        assert_eq!(
+            get_logs().last().unwrap(),
+            r#"EVENT_JSON:{"standard":"acl","version":"1.0.0","event":"role_granted","data":{"role":"minter","account_id":"bob","sender_id":"alice"}}"#
+        );
+
+        assert!(acl.revoke_role("minter", &accounts(1)));
+        assert!(!acl.revoke_role("minter", &accounts(1)));
+        assert!(!acl.has_role("minter", &accounts(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Account bob doesn't have the role admin")]
+    fn grant_without_admin_role() {
+        let mut acl = setup();
+        set_caller(accounts(1));
+        acl.grant_role("minter", &accounts(1));
+    }
+
+    #[test]
+    fn role_admin() {
+        let mut acl = setup();
+        acl.grant_role("minter_admin", &accounts(1));
+        acl.set_role_admin("minter", "minter_admin");
+        assert_eq!(acl.role_admin("minter"), "minter_admin");
+
+        set_caller(accounts(1));
+        assert!(acl.grant_role("minter", &accounts(2)));
+        assert!(acl.has_role("minter", &accounts(2)));
+    }
+}
@@ -0,0 +1,58 @@
+use near_sdk::{ext_contract, AccountId};
+
+/// The contract methods of role-based access control.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{env, near, AccountId, PanicOnDefault};
+/// use near_contract_standards::access_control::{AccessControl, AccessControlCore};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     acl: AccessControl,
+/// }
+///
+/// #[near]
+/// impl AccessControlCore for Contract {
+///     fn acl_has_role(&self, role: String, account_id: AccountId) -> bool {
+///         self.acl.has_role(&role, &account_id)
+///     }
+///
+///     fn acl_get_role_admin(&self, role: String) -> String {
+///         self.acl.role_admin(&role)
+///     }
+///
+///     fn acl_grant_role(&mut self, role: String, account_id: AccountId) -> bool {
+///         self.acl.grant_role(&role, &account_id)
+///     }
+///
+///     fn acl_revoke_role(&mut self, role: String, account_id: AccountId) -> bool {
+///         self.acl.revoke_role(&role, &account_id)
+///     }
+///
+///     fn acl_renounce_role(&mut self, role: String) -> bool {
+///         self.acl.internal_revoke_role(&role, &env::predecessor_account_id())
+///     }
+/// }
+/// ```
+#[ext_contract(ext_access_control)]
+pub trait AccessControlCore {
+    /// Whether `account_id` has `role`.
+    fn acl_has_role(&self, role: String, account_id: AccountId) -> bool;
+
+    /// Returns the role whose members can grant and revoke `role`.
+    fn acl_get_role_admin(&self, role: String) -> String;
+
+    /// Grants `role` to `account_id`. Can only be called by the members of the admin role of
+    /// `role`. Returns whether the account didn't have the role yet.
+    fn acl_grant_role(&mut self, role: String, account_id: AccountId) -> bool;
+
+    /// Revokes `role` from `account_id`. Can only be called by the members of the admin role of
+    /// `role`. Returns whether the account had the role.
+    fn acl_revoke_role(&mut self, role: String, account_id: AccountId) -> bool;
+
+    /// Gives up `role` of the predecessor. Returns whether the predecessor had the role.
+    fn acl_renounce_role(&mut self, role: String) -> bool;
+}
@@ -0,0 +1,50 @@
+//! Role-based access control component.
+//!
+//! An [`AccessControl`] stores which accounts have which roles, in collections under a storage
+//! prefix of its own. Roles are named by strings, e.g. `"minter"` or `"pauser"`. The members of
+//! a role are granted and revoked by the members of its admin role, which is
+//! [`DEFAULT_ADMIN_ROLE`] unless set with [`AccessControl::set_role_admin`], and every change is
+//! logged as an [`AccessControlEvent`].
+//!
+//! Methods of the contract are restricted to a role with `#[only(role = "...")]`, which checks
+//! the role of the predecessor through the [`HasRoles`](near_sdk::HasRoles) implementation of
+//! the contract, delegating to its [`AccessControl`]:
+//!
+//! ```
+//! use near_sdk::{near, AccountId, HasRoles, PanicOnDefault};
+//! use near_contract_standards::access_control::{AccessControl, DEFAULT_ADMIN_ROLE};
+//!
+//! #[near(contract_state)]
+//! #[derive(PanicOnDefault)]
+//! pub struct Contract {
+//!     acl: AccessControl,
+//!     supply: u64,
+//! }
+//!
+//! impl HasRoles for Contract {
+//!     fn has_role(&self, role: &str, account_id: &AccountId) -> bool {
+//!         self.acl.has_role(role, account_id)
+//!     }
+//! }
+//!
+//! #[near]
+//! impl Contract {
+//!     #[init]
+//!     pub fn new(admin_id: AccountId) -> Self {
+//!         let mut acl = AccessControl::new(b"a");
+//!         acl.internal_grant_role(DEFAULT_ADMIN_ROLE, &admin_id);
+//!         Self { acl, supply: 0 }
+//!     }
+//!
+//!     #[only(role = "minter")]
+//!     pub fn mint(&mut self, amount: u64) {
+//!         self.supply += amount;
+//!     }
+//! }
+//! ```
+
+mod access_control_impl;
+mod core;
+
+pub use self::core::{ext_access_control, AccessControlCore};
+pub use access_control_impl::{AccessControl, AccessControlEvent, DEFAULT_ADMIN_ROLE};
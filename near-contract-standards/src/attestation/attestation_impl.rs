@@ -0,0 +1,387 @@
+use near_sdk::collections::{LookupMap, UnorderedSet};
+use near_sdk::json_types::{Base58CryptoHash, U128, U64};
+use near_sdk::{env, near, require, AccountId, CryptoHash, CurveType, IntoStorageKey, PublicKey};
+
+/// Domain tag of the messages signed by issuers, see [`AttestationRegistry::signed_message`].
+pub const ATTESTATION_DOMAIN_TAG: &str = "near-attestation:v1";
+
+/// Claim signed by an issuer about a subject. The issuer signs the payload with its ed25519 key,
+/// as the message returned by [`AttestationRegistry::signed_message`].
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttestationPayload {
+    pub issuer_id: AccountId,
+    pub subject_id: AccountId,
+    /// Hash of the claim, which is kept off-chain.
+    pub claim_hash: Base58CryptoHash,
+    /// Block timestamp in nanoseconds after which the attestation is no longer valid.
+    pub expires_at: Option<U64>,
+    /// Arbitrary number chosen by the issuer, to be able to attest the same claim again.
+    pub nonce: U64,
+}
+
+/// Attestation stored in an [`AttestationRegistry`].
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attestation {
+    pub payload: AttestationPayload,
+    /// Block timestamp in nanoseconds at which the attestation was submitted.
+    pub submitted_at: U64,
+    /// Block timestamp in nanoseconds at which the issuer revoked the attestation.
+    pub revoked_at: Option<U64>,
+}
+
+/// State of an [`Attestation`], derived from the block timestamp.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttestationStatus {
+    Valid,
+    Expired,
+    Revoked,
+}
+
+/// Attestation with its identifier and current state, as returned by views.
+#[near(serializers=[json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttestationView {
+    pub id: Base58CryptoHash,
+    pub attestation: Attestation,
+    pub status: AttestationStatus,
+}
+
+/// Implementation of an attestation registry with ed25519-signed attestations, revocation and
+/// expiry.
+///
+/// Managing issuers is left to the contract, e.g. to its owner or a DAO, through
+/// [`AttestationRegistry::internal_set_issuer`].
+#[near]
+pub struct AttestationRegistry {
+    /// Issuer -> Public key the issuer signs attestations with.
+    pub issuers: LookupMap<AccountId, PublicKey>,
+    /// Attestation ID -> Attestation.
+    pub attestations: LookupMap<CryptoHash, Attestation>,
+    /// Subject -> IDs of the attestations about the subject.
+    pub attestations_per_subject: LookupMap<AccountId, UnorderedSet<CryptoHash>>,
+    prefix: Vec<u8>,
+}
+
+impl AttestationRegistry {
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let prefix = prefix.into_storage_key();
+        Self {
+            issuers: LookupMap::new([prefix.as_slice(), b"i"].concat()),
+            attestations: LookupMap::new([prefix.as_slice(), b"a"].concat()),
+            attestations_per_subject: LookupMap::new([prefix.as_slice(), b"s"].concat()),
+            prefix,
+        }
+    }
+
+    /// Returns the identifier of the attestation with the given payload, which is the SHA-256
+    /// hash of the signed message.
+    pub fn attestation_id(payload: &AttestationPayload) -> CryptoHash {
+        env::sha256_array(&Self::signed_message(payload)).into()
+    }
+
+    /// Returns the message an issuer signs to attest `payload` to the registry of the current
+    /// account: the Borsh serialization of [`ATTESTATION_DOMAIN_TAG`], the current account ID
+    /// and the payload. The signature of an attestation can't be replayed on the registries of
+    /// other contracts, or as the signature of another kind of message.
+    pub fn signed_message(payload: &AttestationPayload) -> Vec<u8> {
+        near_sdk::borsh::to_vec(&(ATTESTATION_DOMAIN_TAG, env::current_account_id(), payload))
+            .unwrap_or_else(|_| env::abort())
+    }
+
+    /// Returns `true` if `signature` is a valid ed25519 signature of `message` by `public_key`.
+    /// Returns `false` for keys of other curves and signatures of the wrong length.
+    pub fn verify_signature(public_key: &PublicKey, message: &[u8], signature: &[u8]) -> bool {
+        if public_key.curve_type() != CurveType::ED25519 {
+            return false;
+        }
+        match (<&[u8; 64]>::try_from(signature), <&[u8; 32]>::try_from(&public_key.as_bytes()[1..]))
+        {
+            (Ok(signature), Ok(public_key)) => env::ed25519_verify(signature, message, public_key),
+            _ => false,
+        }
+    }
+
+    /// Registers `issuer_id` with the ed25519 `public_key` it signs attestations with, or
+    /// removes the issuer if `public_key` is `None`. Attestations made by a removed issuer stay
+    /// valid until they expire or are revoked.
+    pub fn internal_set_issuer(&mut self, issuer_id: &AccountId, public_key: Option<PublicKey>) {
+        match public_key {
+            Some(public_key) => {
+                require!(
+                    public_key.curve_type() == CurveType::ED25519,
+                    "Issuer keys must be ed25519 keys"
+                );
+                self.issuers.insert(issuer_id, &public_key);
+            }
+            None => {
+                self.issuers.remove(issuer_id);
+            }
+        }
+    }
+
+    /// Stores the attestation signed by its issuer and returns its identifier.
+    pub fn internal_attest(&mut self, payload: AttestationPayload, signature: &[u8]) -> CryptoHash {
+        let public_key = self
+            .issuers
+            .get(&payload.issuer_id)
+            .unwrap_or_else(|| env::panic_str("The issuer is not registered"));
+        let message = Self::signed_message(&payload);
+        require!(
+            Self::verify_signature(&public_key, &message, signature),
+            "Invalid attestation signature"
+        );
+        let now = env::block_timestamp();
+        if let Some(expires_at) = payload.expires_at {
+            require!(now < expires_at.0, "The attestation has already expired");
+        }
+
+        let id = CryptoHash::from(env::sha256_array(&message));
+        require!(self.attestations.get(&id).is_none(), "The attestation already exists");
+
+        let mut subject_attestations =
+            self.attestations_per_subject.get(&payload.subject_id).unwrap_or_else(|| {
+                let subject_hash = env::sha256(payload.subject_id.as_bytes());
+                UnorderedSet::new([self.prefix.as_slice(), b"p", subject_hash.as_slice()].concat())
+            });
+        subject_attestations.insert(&id);
+        self.attestations_per_subject.insert(&payload.subject_id, &subject_attestations);
+
+        let attestation = Attestation { payload, submitted_at: U64(now), revoked_at: None };
+        self.attestations.insert(&id, &attestation);
+        id
+    }
+
+    /// Revokes the attestation `id` made by `issuer_id`.
+    pub fn internal_revoke(&mut self, issuer_id: &AccountId, id: &CryptoHash) {
+        let mut attestation = self
+            .attestations
+            .get(id)
+            .unwrap_or_else(|| env::panic_str("The attestation does not exist"));
+        require!(
+            &attestation.payload.issuer_id == issuer_id,
+            "Only the issuer can revoke the attestation"
+        );
+        require!(attestation.revoked_at.is_none(), "The attestation is already revoked");
+        attestation.revoked_at = Some(U64(env::block_timestamp()));
+        self.attestations.insert(id, &attestation);
+    }
+
+    /// Returns the current state of `attestation`.
+    pub fn status_of(attestation: &Attestation) -> AttestationStatus {
+        if attestation.revoked_at.is_some() {
+            AttestationStatus::Revoked
+        } else if attestation
+            .payload
+            .expires_at
+            .map_or(false, |expires_at| env::block_timestamp() >= expires_at.0)
+        {
+            AttestationStatus::Expired
+        } else {
+            AttestationStatus::Valid
+        }
+    }
+
+    /// Returns the attestation `id` with its current state.
+    pub fn attestation(&self, id: &CryptoHash) -> Option<AttestationView> {
+        self.attestations.get(id).map(|attestation| AttestationView {
+            id: (*id).into(),
+            status: Self::status_of(&attestation),
+            attestation,
+        })
+    }
+
+    /// Returns `true` if the attestation `id` exists and is neither expired nor revoked.
+    pub fn is_valid(&self, id: &CryptoHash) -> bool {
+        self.attestations
+            .get(id)
+            .map_or(false, |attestation| Self::status_of(&attestation) == AttestationStatus::Valid)
+    }
+
+    /// Returns the attestations about `subject_id`, paginated by `from_index` and `limit`.
+    pub fn attestations_of(
+        &self,
+        subject_id: &AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<AttestationView> {
+        let ids = match self.attestations_per_subject.get(subject_id) {
+            Some(ids) => ids,
+            None => return vec![],
+        };
+        let limit = limit.map(|v| v as usize).unwrap_or(usize::MAX);
+        require!(limit != 0, "Cannot provide limit of 0.");
+        let start_index: u128 = from_index.map(From::from).unwrap_or_default();
+        ids.iter()
+            .skip(start_index as usize)
+            .take(limit)
+            .map(|id| self.attestation(&id).unwrap_or_else(|| env::abort()))
+            .collect()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    const SECOND: u64 = 1_000_000_000;
+
+    fn payload() -> AttestationPayload {
+        AttestationPayload {
+            issuer_id: accounts(0),
+            subject_id: accounts(1),
+            claim_hash: [1; 32].into(),
+            expires_at: None,
+            nonce: U64(0),
+        }
+    }
+
+    fn issuer_key() -> PublicKey {
+        "ed25519:GmaDrppBC7P5ARKV8g3djiwP89vz1jLK23V2GBjuAEGB".parse().unwrap()
+    }
+
+    /// Payload with `nonce` and its signature by the key of `issuer_key` for the registry of
+    /// `alice.near`.
+    fn signed(nonce: u64) -> (AttestationPayload, Vec<u8>) {
+        let (expires_at, signature) = match nonce {
+            0 => (None, "4iJqFPhAwk2WoXQwnCUSccZKeTFbuEMircX1d9DF459ghdjsDDkyW1FuZiTFivGUsH1em8N1gBeKDKygVeVnr5wC"),
+            1 => (None, "yg6SYQJ7E3wVKqNAFP7AhaungWeiRXtx5J3d2eLLJTXm3BBx83h6t4fxs5XYhrR7k7iNFm1NBvcw2Z2PcL9zKx1"),
+            2 => (None, "2Ju5KK7xJaeUiPJmyStSbjt1qMdfyCuY3b3ZB8zA4KmZqc7qqmj4hVxdBWu6UefKnSLbEoBuLrTs9JZgHN3KowWF"),
+            3 => (Some(U64(100 * SECOND)), "31aZAaxuvdTM4VKqib3D45LM8kpuXpk82f5aosCDRyc6V4kubNPAsnJ6Sb5FwqNaF2bFeguGakqhg7HQHTzmb7TS"),
+            _ => unreachable!(),
+        };
+        let payload = AttestationPayload { expires_at, nonce: U64(nonce), ..payload() };
+        (payload, near_sdk::bs58::decode(signature).into_vec().unwrap())
+    }
+
+    fn setup(current_account_id: &str, seconds: u64) -> AttestationRegistry {
+        set_context(current_account_id, seconds);
+        let mut registry = AttestationRegistry::new(b"a");
+        registry.internal_set_issuer(&accounts(0), Some(issuer_key()));
+        registry
+    }
+
+    fn set_context(current_account_id: &str, seconds: u64) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(current_account_id.parse().unwrap())
+            .block_timestamp(seconds * SECOND)
+            .build());
+    }
+
+    #[test]
+    fn test_verify_signature_rejects_malformed_input() {
+        let message = AttestationRegistry::signed_message(&payload());
+        assert!(!AttestationRegistry::verify_signature(&issuer_key(), &message, &[0; 63]));
+        assert!(!AttestationRegistry::verify_signature(&issuer_key(), &message, &[0; 64]));
+        let secp_key: PublicKey = "secp256k1:qMoRgcoXai4mBPsdbHi1wfyxF9TdbPCF4qSDQTRP3TfescSRoUdSx6nmeQoN3aiwGzwMyGXAb1gUjBTv5AY8DXj".parse().unwrap();
+        assert!(!AttestationRegistry::verify_signature(&secp_key, &message, &[0; 64]));
+    }
+
+    #[test]
+    fn test_attest_and_revoke() {
+        let mut registry = setup("alice.near", 0);
+        let (payload, signature) = signed(0);
+        let id = registry.internal_attest(payload.clone(), &signature);
+        assert_eq!(id, AttestationRegistry::attestation_id(&payload));
+        assert!(registry.is_valid(&id));
+        let view = registry.attestation(&id).unwrap();
+        assert_eq!(view.status, AttestationStatus::Valid);
+        assert_eq!(view.attestation.payload, payload);
+
+        set_context("alice.near", 10);
+        registry.internal_revoke(&accounts(0), &id);
+        assert!(!registry.is_valid(&id));
+        let view = registry.attestation(&id).unwrap();
+        assert_eq!(view.status, AttestationStatus::Revoked);
+        assert_eq!(view.attestation.revoked_at, Some(U64(10 * SECOND)));
+    }
+
+    #[test]
+    fn test_expiry() {
+        let mut registry = setup("alice.near", 99);
+        let (payload, signature) = signed(3);
+        let id = registry.internal_attest(payload, &signature);
+        assert!(registry.is_valid(&id));
+
+        set_context("alice.near", 100);
+        assert!(!registry.is_valid(&id));
+        assert_eq!(registry.attestation(&id).unwrap().status, AttestationStatus::Expired);
+    }
+
+    #[test]
+    #[should_panic(expected = "The attestation has already expired")]
+    fn test_attest_expired() {
+        let mut registry = setup("alice.near", 100);
+        let (payload, signature) = signed(3);
+        registry.internal_attest(payload, &signature);
+    }
+
+    #[test]
+    fn test_attestations_of_pagination() {
+        let mut registry = setup("alice.near", 0);
+        let mut ids: Vec<_> = (0..3)
+            .map(|nonce| {
+                let (payload, signature) = signed(nonce);
+                Base58CryptoHash::from(registry.internal_attest(payload, &signature))
+            })
+            .collect();
+        assert!(registry.attestations_of(&accounts(2), None, None).is_empty());
+
+        let first = registry.attestations_of(&accounts(1), None, Some(2));
+        let rest = registry.attestations_of(&accounts(1), Some(U128(2)), Some(2));
+        assert_eq!((first.len(), rest.len()), (2, 1));
+        let mut listed: Vec<_> = first.iter().chain(&rest).map(|view| view.id).collect();
+        listed.sort();
+        ids.sort();
+        assert_eq!(listed, ids);
+        assert!(registry.attestations_of(&accounts(1), Some(U128(3)), None).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "The attestation already exists")]
+    fn test_attest_twice() {
+        let mut registry = setup("alice.near", 0);
+        let (payload, signature) = signed(0);
+        registry.internal_attest(payload.clone(), &signature);
+        registry.internal_attest(payload, &signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid attestation signature")]
+    fn test_attest_replayed_on_other_registry() {
+        let mut registry = setup("other.near", 0);
+        let (payload, signature) = signed(0);
+        registry.internal_attest(payload, &signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the issuer can revoke the attestation")]
+    fn test_revoke_not_issuer() {
+        let mut registry = setup("alice.near", 0);
+        let (payload, signature) = signed(0);
+        let id = registry.internal_attest(payload, &signature);
+        registry.internal_revoke(&accounts(1), &id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid attestation signature")]
+    fn test_attest_invalid_signature() {
+        let mut registry = setup("alice.near", 0);
+        registry.internal_attest(payload(), &[0; 64]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The issuer is not registered")]
+    fn test_attest_unknown_issuer() {
+        let mut registry = AttestationRegistry::new(b"a");
+        registry.internal_attest(payload(), &[0; 64]);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::attestation::{AttestationPayload, AttestationView};
+use near_sdk::json_types::{Base58CryptoHash, Base64VecU8, U128};
+use near_sdk::{ext_contract, AccountId};
+
+/// The contract methods of an attestation registry.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{env, near, AccountId, PanicOnDefault};
+/// use near_sdk::json_types::{Base58CryptoHash, Base64VecU8, U128};
+/// use near_contract_standards::attestation::{
+///     AttestationPayload, AttestationRegistry, AttestationRegistryCore, AttestationView,
+/// };
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     attestations: AttestationRegistry,
+/// }
+///
+/// #[near]
+/// impl AttestationRegistryCore for Contract {
+///     fn attestation_submit(
+///         &mut self,
+///         payload: AttestationPayload,
+///         signature: Base64VecU8,
+///     ) -> Base58CryptoHash {
+///         self.attestations.internal_attest(payload, &signature.0).into()
+///     }
+///
+///     fn attestation_revoke(&mut self, id: Base58CryptoHash) {
+///         self.attestations.internal_revoke(&env::predecessor_account_id(), &id.into());
+///     }
+///
+///     fn attestation_get(&self, id: Base58CryptoHash) -> Option<AttestationView> {
+///         self.attestations.attestation(&id.into())
+///     }
+///
+///     fn attestation_is_valid(&self, id: Base58CryptoHash) -> bool {
+///         self.attestations.is_valid(&id.into())
+///     }
+///
+///     fn attestations_for_subject(
+///         &self,
+///         subject_id: AccountId,
+///         from_index: Option<U128>,
+///         limit: Option<u64>,
+///     ) -> Vec<AttestationView> {
+///         self.attestations.attestations_of(&subject_id, from_index, limit)
+///     }
+/// }
+/// ```
+#[ext_contract(ext_attestation_registry)]
+pub trait AttestationRegistryCore {
+    /// Stores an attestation signed by its issuer with the ed25519 key the issuer is registered
+    /// with, and returns its identifier. Can be submitted by any account.
+    fn attestation_submit(
+        &mut self,
+        payload: AttestationPayload,
+        signature: Base64VecU8,
+    ) -> Base58CryptoHash;
+
+    /// Revokes the attestation `id`. Can only be called by the issuer of the attestation.
+    fn attestation_revoke(&mut self, id: Base58CryptoHash);
+
+    /// Returns the attestation `id` with its current state.
+    fn attestation_get(&self, id: Base58CryptoHash) -> Option<AttestationView>;
+
+    /// Returns `true` if the attestation `id` exists and is neither expired nor revoked.
+    fn attestation_is_valid(&self, id: Base58CryptoHash) -> bool;
+
+    /// Returns the attestations about `subject_id`, paginated by `from_index` and `limit`.
+    fn attestations_for_subject(
+        &self,
+        subject_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<AttestationView>;
+}
@@ -0,0 +1,20 @@
+//! Registry component for attestations that issuers make about subjects.
+//!
+//! An attestation is a claim an issuer makes about a subject account, for example that the
+//! subject passed a KYC check or holds a credential. Only the hash of the claim is stored
+//! on-chain, the claim itself is kept off-chain by the subject and can be checked against the
+//! stored hash.
+//!
+//! Issuers are registered with an ed25519 public key by the contract. Attestations are signed
+//! off-chain by the issuer (see [`AttestationPayload`]) and can be submitted by anyone, e.g. by
+//! the subject or a relayer, as the registry verifies the issuer's signature. Issuers can revoke
+//! their attestations, and attestations can expire (see [`AttestationStatus`]).
+
+mod attestation_impl;
+mod core;
+
+pub use self::core::{ext_attestation_registry, AttestationRegistryCore};
+pub use attestation_impl::{
+    Attestation, AttestationPayload, AttestationRegistry, AttestationStatus, AttestationView,
+    ATTESTATION_DOMAIN_TAG,
+};
@@ -0,0 +1,52 @@
+use near_sdk::{ext_contract, AccountId, NearToken};
+
+/// Callback of the factory checking the result of a [`ChildDeployment`](crate::factory::ChildDeployment).
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{env, near, AccountId, NearToken, PanicOnDefault, Promise};
+/// use near_contract_standards::factory::{ChildKeys, Factory, FactoryResolver};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     code: Vec<u8>,
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     #[payable]
+///     pub fn create(&mut self, name: String) -> Promise {
+///         Factory::deploy_child(&name)
+///             .with_code(self.code.clone())
+///             .with_keys(ChildKeys::FullAccess(env::signer_account_pk()))
+///             .deploy()
+///     }
+/// }
+///
+/// #[near]
+/// impl FactoryResolver for Contract {
+///     #[private]
+///     fn factory_on_child_deployed(
+///         &mut self,
+///         account_id: AccountId,
+///         refund_to: AccountId,
+///         deposit: NearToken,
+///     ) -> bool {
+///         Factory::resolve_deployment(&account_id, refund_to, deposit)
+///     }
+/// }
+/// ```
+#[ext_contract(ext_factory_resolver)]
+pub trait FactoryResolver {
+    /// Called after the deployment of `account_id`. Should refund `deposit` to `refund_to` if the
+    /// deployment failed, which [`Factory::resolve_deployment`](crate::factory::Factory::resolve_deployment)
+    /// does. Returns whether the deployment succeeded.
+    fn factory_on_child_deployed(
+        &mut self,
+        account_id: AccountId,
+        refund_to: AccountId,
+        deposit: NearToken,
+    ) -> bool;
+}
@@ -0,0 +1,309 @@
+use crate::factory::ext_factory_resolver;
+use near_sdk::serde::Serialize;
+use near_sdk::{
+    env, require, serde_json, AccountId, Allowance, Gas, NearToken, Promise, PromiseResult,
+    PublicKey, TokenFormat,
+};
+
+const GAS_FOR_RESOLVE_DEPLOYMENT: Gas = Gas::from_tgas(5);
+
+/// Storage used by an account, `num_bytes_account` in the runtime config.
+const ACCOUNT_STORAGE_BYTES: u64 = 100;
+/// Storage overhead of every record of an account, i.e. of its code and of each access key and
+/// state entry, `num_extra_bytes_record` in the runtime config.
+const RECORD_STORAGE_BYTES: u64 = 40;
+/// Borsh size of the nonce and of the permission tag of an access key.
+const ACCESS_KEY_STORAGE_BYTES: u64 = 9;
+/// Borsh size of the allowance of a function call access key.
+const ALLOWANCE_STORAGE_BYTES: u64 = 17;
+
+/// Access keys added to a child account by a [`ChildDeployment`].
+#[derive(Clone)]
+pub enum ChildKeys {
+    /// No access keys, the child account can only act through its contract.
+    None,
+    /// A full access key, e.g. of the account requesting the deployment, which then owns the
+    /// child account.
+    FullAccess(PublicKey),
+    /// A function call access key for a key held by the operator of the factory, which can only
+    /// call `method_names` of the child contract. All methods can be called if `method_names` is
+    /// empty.
+    FactoryControlled { public_key: PublicKey, allowance: Allowance, method_names: Vec<String> },
+}
+
+impl ChildKeys {
+    /// Returns the number of bytes the keys take in the storage of the child account.
+    fn storage_bytes(&self, account_id: &AccountId) -> u64 {
+        let key_bytes = |public_key: &PublicKey| {
+            public_key.as_bytes().len() as u64 + ACCESS_KEY_STORAGE_BYTES + RECORD_STORAGE_BYTES
+        };
+        match self {
+            ChildKeys::None => 0,
+            ChildKeys::FullAccess(public_key) => key_bytes(public_key),
+            ChildKeys::FactoryControlled { public_key, method_names, .. } => {
+                key_bytes(public_key)
+                    + ALLOWANCE_STORAGE_BYTES
+                    + 4
+                    + account_id.as_str().len() as u64
+                    + 4
+                    + method_names.iter().map(|name| 4 + name.len() as u64).sum::<u64>()
+            }
+        }
+    }
+}
+
+/// Entry point of the factory component, see the [module documentation](crate::factory).
+pub struct Factory;
+
+impl Factory {
+    /// Starts the deployment of a child contract on the `name` sub-account of the current account.
+    /// The deposit attached to the current call is transferred to the child account, and refunded
+    /// to the predecessor if the deployment fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid single part of an account ID, e.g. if it contains a `.`.
+    pub fn deploy_child(name: &str) -> ChildDeployment {
+        require!(!name.is_empty() && !name.contains('.'), "Invalid child account name");
+        let account_id: AccountId = format!("{}.{}", name, env::current_account_id())
+            .parse()
+            .unwrap_or_else(|_| env::panic_str("Invalid child account name"));
+        ChildDeployment {
+            account_id,
+            code: Vec::new(),
+            deposit: env::attached_deposit(),
+            keys: ChildKeys::None,
+            init: None,
+            refund_to: env::predecessor_account_id(),
+            state_bytes: 0,
+        }
+    }
+
+    /// Estimates the deposit a child account needs to cover the storage of its code, keys and
+    /// the `state_bytes` written by its init method, at the storage price of the current protocol.
+    pub fn storage_cost(
+        account_id: &AccountId,
+        code_len: usize,
+        keys: &ChildKeys,
+        state_bytes: u64,
+    ) -> NearToken {
+        let bytes = ACCOUNT_STORAGE_BYTES
+            + code_len as u64
+            + RECORD_STORAGE_BYTES
+            + keys.storage_bytes(account_id)
+            + state_bytes;
+        env::storage_byte_cost().saturating_mul(bytes.into())
+    }
+
+    /// Implementation of [`FactoryResolver::factory_on_child_deployed`](crate::factory::FactoryResolver::factory_on_child_deployed):
+    /// refunds `deposit` to `refund_to` if the deployment of `account_id` failed.
+    /// Returns whether the deployment succeeded.
+    pub fn resolve_deployment(
+        account_id: &AccountId,
+        refund_to: AccountId,
+        deposit: NearToken,
+    ) -> bool {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => true,
+            PromiseResult::Failed => {
+                env::log_str(&format!(
+                    "Deployment of {} failed, refunding {} to {}",
+                    account_id,
+                    TokenFormat::new(deposit),
+                    refund_to
+                ));
+                if !deposit.is_zero() {
+                    Promise::new(refund_to).transfer(deposit);
+                }
+                false
+            }
+        }
+    }
+}
+
+struct InitCall {
+    method_name: String,
+    args: Vec<u8>,
+    gas: Gas,
+}
+
+/// Builder of the deployment of a child contract, created with [`Factory::deploy_child`].
+#[must_use = "the child is only deployed by `deploy`"]
+pub struct ChildDeployment {
+    account_id: AccountId,
+    code: Vec<u8>,
+    deposit: NearToken,
+    keys: ChildKeys,
+    init: Option<InitCall>,
+    refund_to: AccountId,
+    state_bytes: u64,
+}
+
+impl ChildDeployment {
+    /// Returns the account ID of the child.
+    pub fn account_id(&self) -> &AccountId {
+        &self.account_id
+    }
+
+    /// Sets the WASM code deployed on the child account.
+    pub fn with_code(mut self, code: Vec<u8>) -> Self {
+        self.code = code;
+        self
+    }
+
+    /// Sets the deposit transferred to the child account, the deposit attached to the current
+    /// call by default. It has to cover the [storage cost](Factory::storage_cost) of the child.
+    pub fn with_deposit(mut self, deposit: NearToken) -> Self {
+        self.deposit = deposit;
+        self
+    }
+
+    /// Sets the access keys added to the child account, [`ChildKeys::None`] by default.
+    pub fn with_keys(mut self, keys: ChildKeys) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// Calls the init method `method_name` of the child with serialized `args`, such as the
+    /// arguments built by the `init_args` function generated for `#[init(schema)]` methods, e.g.
+    /// `.with_init_call(Child::INIT_METHOD_NAME, Child::init_args(owner_id), gas)`.
+    pub fn with_init_call(mut self, method_name: &str, args: Vec<u8>, gas: Gas) -> Self {
+        self.init = Some(InitCall { method_name: method_name.to_string(), args, gas });
+        self
+    }
+
+    /// Calls the init method `method_name` of the child with `args` serialized to JSON.
+    pub fn with_init_json<A: Serialize>(self, method_name: &str, args: &A, gas: Gas) -> Self {
+        let args = serde_json::to_vec(args)
+            .unwrap_or_else(|_| env::panic_str("Failed to serialize the init arguments"));
+        self.with_init_call(method_name, args, gas)
+    }
+
+    /// Sets the account the deposit is refunded to if the deployment fails, the predecessor by
+    /// default.
+    pub fn with_refund_to(mut self, refund_to: AccountId) -> Self {
+        self.refund_to = refund_to;
+        self
+    }
+
+    /// Sets the number of bytes of state written by the init method, included in the storage cost
+    /// the deposit is checked against.
+    pub fn with_state_bytes(mut self, state_bytes: u64) -> Self {
+        self.state_bytes = state_bytes;
+        self
+    }
+
+    /// Schedules the deployment, followed by the
+    /// [`factory_on_child_deployed`](crate::factory::FactoryResolver::factory_on_child_deployed)
+    /// callback on the current account.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the code is not set, or if the deposit does not cover the storage cost of the
+    /// child.
+    pub fn deploy(self) -> Promise {
+        require!(!self.code.is_empty(), "The code of the child contract is not set");
+        let storage_cost =
+            Factory::storage_cost(&self.account_id, self.code.len(), &self.keys, self.state_bytes);
+        require!(
+            self.deposit >= storage_cost,
+            format!(
+                "Must attach {} to cover the storage of the child account",
+                TokenFormat::new(storage_cost).round_up()
+            )
+        );
+
+        let mut promise =
+            Promise::new(self.account_id.clone()).create_account().transfer(self.deposit);
+        promise = match self.keys {
+            ChildKeys::None => promise,
+            ChildKeys::FullAccess(public_key) => promise.add_full_access_key(public_key),
+            ChildKeys::FactoryControlled { public_key, allowance, method_names } => promise
+                .add_access_key_allowance(
+                    public_key,
+                    allowance,
+                    self.account_id.clone(),
+                    method_names.join(","),
+                ),
+        };
+        promise = promise.deploy_contract(self.code);
+        if let Some(init) = self.init {
+            promise = promise.function_call(
+                init.method_name,
+                init.args,
+                NearToken::from_yoctonear(0),
+                init.gas,
+            );
+        }
+
+        promise.then(
+            ext_factory_resolver::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RESOLVE_DEPLOYMENT)
+                .factory_on_child_deployed(self.account_id, self.refund_to, self.deposit),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::mock::MockAction;
+    use near_sdk::test_utils::{accounts, get_created_receipts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn context() -> VMContextBuilder {
+        let mut context = VMContextBuilder::new();
+        context
+            .current_account_id("factory.near".parse().unwrap())
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(NearToken::from_near(1));
+        context
+    }
+
+    #[test]
+    fn deploy_child() {
+        testing_env!(context().build());
+        let public_key: PublicKey =
+            "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp".parse().unwrap();
+        let deployment = Factory::deploy_child("token")
+            .with_code(vec![0; 100])
+            .with_keys(ChildKeys::FullAccess(public_key))
+            .with_init_json(
+                "new",
+                &serde_json::json!({ "owner_id": accounts(0) }),
+                Gas::from_tgas(10),
+            );
+        assert_eq!(deployment.account_id().as_str(), "token.factory.near");
+        drop(deployment.deploy());
+
+        let receipts = get_created_receipts();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].receiver_id.as_str(), "token.factory.near");
+        assert!(matches!(
+            receipts[0].actions[..],
+            [
+                MockAction::CreateAccount { .. },
+                MockAction::Transfer { .. },
+                MockAction::AddKeyWithFullAccess { .. },
+                MockAction::DeployContract { .. },
+                MockAction::FunctionCallWeight { .. },
+            ]
+        ));
+        assert_eq!(receipts[1].receiver_id.as_str(), "factory.near");
+        assert_eq!(receipts[1].receipt_indices, [0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "to cover the storage of the child account")]
+    fn deploy_child_without_storage_deposit() {
+        testing_env!(context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        let _ = Factory::deploy_child("token").with_code(vec![0; 100]).deploy();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid child account name")]
+    fn deploy_child_invalid_name() {
+        testing_env!(context().build());
+        let _ = Factory::deploy_child("token.nested");
+    }
+}
@@ -0,0 +1,18 @@
+//! Factory component for contracts that deploy child contracts on their sub-accounts.
+//!
+//! [`Factory::deploy_child`] returns a [`ChildDeployment`] builder covering the whole lifecycle
+//! of a deployment:
+//!
+//! - the sub-account name is validated and the deposit is checked to cover the storage of the
+//!   code, access keys and initial state of the child (see [`Factory::storage_cost`]),
+//! - the child account is created with one of the key policies of [`ChildKeys`],
+//! - the code is deployed and the init method is called with its arguments, for example built
+//!   with the `init_args` function generated by `#[init(schema)]` in the child contract crate,
+//! - and a callback on the factory (see [`FactoryResolver`]) checks that the deployment succeeded,
+//!   refunding the deposit otherwise.
+
+mod core;
+mod factory_impl;
+
+pub use self::core::{ext_factory_resolver, FactoryResolver};
+pub use factory_impl::{ChildDeployment, ChildKeys, Factory};
@@ -0,0 +1,96 @@
+use near_sdk::ext_contract;
+use near_sdk::json_types::U128;
+use near_sdk::AccountId;
+use near_sdk::PromiseOrValue;
+
+/// Batched transfers of a fungible token, so that a payout to `N` receivers is a single call
+/// instead of `N` calls with their own receipts. They are not part of NEP-141, which
+/// [`FungibleTokenCore`](crate::fungible_token::FungibleTokenCore) implements.
+///
+/// Each transfer of a batch is validated and applied as by `ft_transfer`, and the whole batch
+/// fails if one of them fails. An `ft_transfer` event is emitted with an entry per transfer.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{near, PanicOnDefault, AccountId, PromiseOrValue};
+/// use near_sdk::json_types::U128;
+/// use near_contract_standards::fungible_token::{
+///     FungibleToken, FungibleTokenBatch, FungibleTokenBatchResolver,
+/// };
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     token: FungibleToken,
+/// }
+///
+/// #[near]
+/// impl FungibleTokenBatch for Contract {
+///     #[payable]
+///     fn ft_transfer_batch(&mut self, transfers: Vec<(AccountId, U128, Option<String>)>) {
+///         self.token.ft_transfer_batch(transfers)
+///     }
+///
+///     #[payable]
+///     fn ft_transfer_call_batch(
+///         &mut self,
+///         transfers: Vec<(AccountId, U128, Option<String>)>,
+///         msg: String,
+///     ) -> PromiseOrValue<Vec<U128>> {
+///         self.token.ft_transfer_call_batch(transfers, msg)
+///     }
+/// }
+///
+/// #[near]
+/// impl FungibleTokenBatchResolver for Contract {
+///     #[private]
+///     fn ft_resolve_transfer_batch(
+///         &mut self,
+///         sender_id: AccountId,
+///         transfers: Vec<(AccountId, U128)>,
+///     ) -> Vec<U128> {
+///         self.token.ft_resolve_transfer_batch(sender_id, transfers)
+///     }
+/// }
+/// ```
+#[ext_contract(ext_ft_batch)]
+pub trait FungibleTokenBatch {
+    /// Transfers positive amounts of tokens from the `env::predecessor_account_id` to each
+    /// receiver of `transfers`, given as `(receiver_id, amount, memo)`.
+    /// Exactly 1 yoctoNEAR must be attached, for the whole batch.
+    fn ft_transfer_batch(&mut self, transfers: Vec<(AccountId, U128, Option<String>)>);
+
+    /// Transfers positive amounts of tokens from the `env::predecessor_account_id` to each
+    /// receiver contract of `transfers`, given as `(receiver_id, amount, memo)`, then calls
+    /// `ft_on_transfer` with `msg` on all of them and attaches a single callback resolving the
+    /// transfers, which refunds the unused tokens of each receiver to the sender.
+    ///
+    /// The gas left after the gas of the callback is split evenly between the receivers, along
+    /// with the gas left unused by the call.
+    /// Exactly 1 yoctoNEAR must be attached, for the whole batch.
+    ///
+    /// Returns a promise which will result in the amounts of tokens used by each receiver, in the
+    /// order of `transfers`.
+    fn ft_transfer_call_batch(
+        &mut self,
+        transfers: Vec<(AccountId, U128, Option<String>)>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>>;
+}
+
+/// Resolves the transfers of [`FungibleTokenBatch::ft_transfer_call_batch`], see
+/// [`FungibleTokenBatch`] for an example implementation.
+#[ext_contract(ext_ft_batch_resolver)]
+pub trait FungibleTokenBatchResolver {
+    /// Refunds the unused tokens of each receiver of `transfers`, given as
+    /// `(receiver_id, amount)`, as `ft_resolve_transfer` does for a single transfer, reading the
+    /// result of the `ft_on_transfer` call of each receiver in the order of `transfers`.
+    ///
+    /// Returns the amounts of tokens used by each receiver.
+    fn ft_resolve_transfer_batch(
+        &mut self,
+        sender_id: AccountId,
+        transfers: Vec<(AccountId, U128)>,
+    ) -> Vec<U128>;
+}
@@ -1,18 +1,34 @@
+use crate::fungible_token::batch::{
+    ext_ft_batch_resolver, FungibleTokenBatch, FungibleTokenBatchResolver,
+};
 use crate::fungible_token::core::FungibleTokenCore;
 use crate::fungible_token::events::{FtBurn, FtTransfer};
+use crate::fungible_token::policy::TransferPolicy;
 use crate::fungible_token::receiver::ext_ft_receiver;
 use crate::fungible_token::resolver::{ext_ft_resolver, FungibleTokenResolver};
+use crate::non_transferable::{assert_transferable, NonTransferableEvent};
+use crate::settings::{collection_prefix, read_setting, setting_key, write_setting};
 use near_sdk::collections::LookupMap;
 use near_sdk::json_types::U128;
 use near_sdk::{
-    assert_one_yocto, env, log, near, require, AccountId, Gas, IntoStorageKey, PromiseOrValue,
+    env, log, near, require, AccountId, Gas, IntoStorageKey, Promise, PromiseOrValue,
     PromiseResult, StorageUsage,
 };
 
-const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas::from_tgas(5);
-const GAS_FOR_FT_TRANSFER_CALL: Gas = Gas::from_tgas(30);
+/// Default and minimum gas of the `ft_resolve_transfer` callback of `ft_transfer_call`.
+pub const MIN_GAS_FOR_RESOLVE_TRANSFER: Gas = Gas::from_tgas(5);
+/// Default gas `ft_transfer_call` keeps for itself and its callback, out of the prepaid gas.
+pub const DEFAULT_GAS_FOR_FT_TRANSFER_CALL: Gas = Gas::from_tgas(30);
+/// Minimum gas `ft_transfer_call` keeps for its own execution, on top of the gas of its callback.
+const MIN_GAS_FOR_FT_TRANSFER_CALL_EXECUTION: Gas = Gas::from_tgas(25);
+
+/// Name of the setting of the gas of `ft_transfer_call`, see [`crate::settings`].
+const TRANSFER_CALL_GAS_SETTING: &str = "transfer_call_gas";
+/// Name of the setting of the `non_transferable` option, see [`crate::settings`].
+const NON_TRANSFERABLE_SETTING: &str = "non_transferable";
 
 const ERR_TOTAL_SUPPLY_OVERFLOW: &str = "Total supply overflow";
+const ERR_EMPTY_BATCH: &str = "The batch should have at least one transfer";
 
 pub type Balance = u128;
 
@@ -24,6 +40,9 @@ pub type Balance = u128;
 ///     - StorageManager -- interface for NEP-145 for allocating storage per account. FungibleToken provides methods for it.
 ///     - AccountRegistrar -- interface for an account to register and unregister
 ///
+/// The gas of `ft_transfer_call` is stored next to the state of the token, under its own storage
+/// key, see [`FungibleToken::set_transfer_call_gas`].
+///
 /// For example usage, see examples/fungible-token/src/lib.rs.
 #[near]
 pub struct FungibleToken {
@@ -48,6 +67,67 @@ impl FungibleToken {
         this
     }
 
+    /// Sets the gas of `ft_transfer_call` and its batched variant, for tokens whose resolution
+    /// needs more gas than the defaults, e.g. because of a [`TransferPolicy`].
+    ///
+    /// `gas_for_resolve_transfer` is the static gas of the `ft_resolve_transfer` callback, per
+    /// transfer, and `gas_for_ft_transfer_call` the gas kept out of the prepaid gas for the
+    /// execution of `ft_transfer_call` and its callback, which the call requires on top of the gas
+    /// of the receiver. The receiver gets the rest of the prepaid gas along with all the gas left
+    /// unused by `ft_transfer_call`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `gas_for_resolve_transfer` is less than [`MIN_GAS_FOR_RESOLVE_TRANSFER`], or if
+    /// `gas_for_ft_transfer_call` does not exceed it by at least 25 TGas.
+    pub fn set_transfer_call_gas(
+        &mut self,
+        gas_for_ft_transfer_call: Gas,
+        gas_for_resolve_transfer: Gas,
+    ) {
+        require!(
+            gas_for_resolve_transfer >= MIN_GAS_FOR_RESOLVE_TRANSFER,
+            "The gas for resolve_transfer is less than the minimum"
+        );
+        require!(
+            gas_for_ft_transfer_call
+                >= gas_for_resolve_transfer.saturating_add(MIN_GAS_FOR_FT_TRANSFER_CALL_EXECUTION),
+            "The gas for ft_transfer_call does not leave enough gas for its execution"
+        );
+        write_setting(
+            &self.setting_key(TRANSFER_CALL_GAS_SETTING),
+            &(gas_for_ft_transfer_call, gas_for_resolve_transfer),
+        );
+    }
+
+    /// Returns the gas of `ft_transfer_call` and of its `ft_resolve_transfer` callback, see
+    /// [`Self::set_transfer_call_gas`].
+    pub fn transfer_call_gas(&self) -> (Gas, Gas) {
+        read_setting(&self.setting_key(TRANSFER_CALL_GAS_SETTING))
+            .unwrap_or((DEFAULT_GAS_FOR_FT_TRANSFER_CALL, MIN_GAS_FOR_RESOLVE_TRANSFER))
+    }
+
+    /// Returns the storage key of the setting `name` of the token, in the namespace of `accounts`.
+    pub(crate) fn setting_key(&self, name: &str) -> Vec<u8> {
+        setting_key(&collection_prefix(&self.accounts), name)
+    }
+
+    /// Sets whether the token is non-transferable, disabling the transfer methods of
+    /// [`FungibleTokenCore`] and [`FungibleTokenBatch`] and their `*_with_policy` variants.
+    /// Minting and burning are not restricted. See the
+    /// [`non_transferable`](crate::non_transferable) module.
+    pub fn set_non_transferable(&mut self, non_transferable: bool) {
+        if self.is_non_transferable() != non_transferable {
+            write_setting(&self.setting_key(NON_TRANSFERABLE_SETTING), &non_transferable);
+            NonTransferableEvent::new("nep141", non_transferable).emit();
+        }
+    }
+
+    /// Returns whether the token is non-transferable, see [`Self::set_non_transferable`].
+    pub fn is_non_transferable(&self) -> bool {
+        read_setting(&self.setting_key(NON_TRANSFERABLE_SETTING)).unwrap_or(false)
+    }
+
     fn measure_account_storage_usage(&mut self) {
         let initial_storage_usage = env::storage_usage();
         let tmp_account_id = "a".repeat(64).parse().unwrap();
@@ -98,17 +178,122 @@ impl FungibleToken {
         amount: Balance,
         memo: Option<String>,
     ) {
-        require!(sender_id != receiver_id, "Sender and receiver should be different");
-        require!(amount > 0, "The amount should be a positive number");
-        self.internal_withdraw(sender_id, amount);
-        self.internal_deposit(receiver_id, amount);
+        self.internal_transfer_with_policy(sender_id, receiver_id, amount, memo, &mut ());
+    }
+
+    /// Transfers `amount` from `sender_id` to `receiver_id` as
+    /// [`internal_transfer`](Self::internal_transfer) does, once `policy` accepted it.
+    /// Returns the amount credited to the receiver, see [`TransferPolicy::before_transfer`].
+    pub fn internal_transfer_with_policy<P: TransferPolicy + ?Sized>(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: Balance,
+        memo: Option<String>,
+        policy: &mut P,
+    ) -> Balance {
+        let received = self.internal_apply_transfer(sender_id, receiver_id, amount, policy);
         FtTransfer {
             old_owner_id: sender_id,
             new_owner_id: receiver_id,
-            amount: U128(amount),
+            amount: U128(received),
             memo: memo.as_deref(),
         }
         .emit();
+        self.internal_after_transfer(sender_id, receiver_id, received, amount - received, policy);
+        received
+    }
+
+    /// Transfers the `(receiver_id, amount, memo)` of `transfers` from `sender_id`, as
+    /// [`internal_transfer`](Self::internal_transfer) does, emitting a single `ft_transfer` event
+    /// with an entry per transfer.
+    pub fn internal_transfer_batch(
+        &mut self,
+        sender_id: &AccountId,
+        transfers: &[(AccountId, Balance, Option<String>)],
+    ) {
+        self.internal_transfer_batch_with_policy(sender_id, transfers, &mut ());
+    }
+
+    /// Transfers the `(receiver_id, amount, memo)` of `transfers` from `sender_id` as
+    /// [`internal_transfer_batch`](Self::internal_transfer_batch) does, once `policy` accepted
+    /// each of them. Returns the amounts credited to the receivers.
+    pub fn internal_transfer_batch_with_policy<P: TransferPolicy + ?Sized>(
+        &mut self,
+        sender_id: &AccountId,
+        transfers: &[(AccountId, Balance, Option<String>)],
+        policy: &mut P,
+    ) -> Vec<Balance> {
+        require!(!transfers.is_empty(), ERR_EMPTY_BATCH);
+        let received: Vec<Balance> = transfers
+            .iter()
+            .map(|(receiver_id, amount, _)| {
+                self.internal_apply_transfer(sender_id, receiver_id, *amount, policy)
+            })
+            .collect();
+        let events: Vec<_> = transfers
+            .iter()
+            .zip(&received)
+            .map(|((receiver_id, _, memo), received)| FtTransfer {
+                old_owner_id: sender_id,
+                new_owner_id: receiver_id,
+                amount: U128(*received),
+                memo: memo.as_deref(),
+            })
+            .collect();
+        FtTransfer::emit_many(&events);
+        for ((receiver_id, amount, _), received) in transfers.iter().zip(&received) {
+            self.internal_after_transfer(
+                sender_id,
+                receiver_id,
+                *received,
+                amount - received,
+                policy,
+            );
+        }
+        received
+    }
+
+    /// Checks the transfer with `policy` and moves the balances, without emitting its event.
+    /// Returns the amount credited to the receiver.
+    fn internal_apply_transfer<P: TransferPolicy + ?Sized>(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: Balance,
+        policy: &mut P,
+    ) -> Balance {
+        require!(sender_id != receiver_id, "Sender and receiver should be different");
+        require!(amount > 0, "The amount should be a positive number");
+        let received = policy
+            .before_transfer(self, sender_id, receiver_id, amount)
+            .unwrap_or_else(|reason| env::panic_str(&reason));
+        require!(
+            received > 0 && received <= amount,
+            "The received amount should be positive and at most the transferred amount"
+        );
+        self.internal_withdraw(sender_id, amount);
+        self.internal_deposit(receiver_id, received);
+        received
+    }
+
+    /// Calls [`TransferPolicy::after_transfer`] and emits an `ft_burn` event for the part of the
+    /// `withheld` amount the policy did not deposit back.
+    fn internal_after_transfer<P: TransferPolicy + ?Sized>(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        received: Balance,
+        withheld: Balance,
+        policy: &mut P,
+    ) {
+        let total_supply = self.total_supply;
+        policy.after_transfer(self, sender_id, receiver_id, received, withheld);
+        let deposited = self.total_supply.saturating_sub(total_supply);
+        let burned = withheld.saturating_sub(deposited);
+        if burned > 0 {
+            FtBurn { owner_id: sender_id, amount: U128(burned), memo: None }.emit();
+        }
     }
 
     pub fn internal_register_account(&mut self, account_id: &AccountId) {
@@ -120,49 +305,150 @@ impl FungibleToken {
 
 impl FungibleTokenCore for FungibleToken {
     fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
-        assert_one_yocto();
+        self.ft_transfer_with_policy(receiver_id, amount, memo, &mut ());
+    }
+
+    fn ft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        self.ft_transfer_call_with_policy(receiver_id, amount, memo, msg, &mut ())
+    }
+
+    fn ft_total_supply(&self) -> U128 {
+        self.total_supply.into()
+    }
+
+    fn ft_balance_of(&self, account_id: AccountId) -> U128 {
+        self.accounts.get(&account_id).unwrap_or(0).into()
+    }
+}
+
+impl FungibleToken {
+    /// [`FungibleTokenCore::ft_transfer`] with the transfer checked by `policy`, see
+    /// [`TransferPolicy`].
+    pub fn ft_transfer_with_policy<P: TransferPolicy + ?Sized>(
+        &mut self,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+        policy: &mut P,
+    ) {
+        assert_transferable(self.is_non_transferable());
+        self.internal_register_receivers([&receiver_id]);
         let sender_id = env::predecessor_account_id();
         let amount: Balance = amount.into();
-        self.internal_transfer(&sender_id, &receiver_id, amount, memo);
+        self.internal_transfer_with_policy(&sender_id, &receiver_id, amount, memo, policy);
     }
 
-    fn ft_transfer_call(
+    /// [`FungibleTokenCore::ft_transfer_call`] with the transfer checked by `policy`, see
+    /// [`TransferPolicy`]. The receiver is called with the amount credited to it.
+    pub fn ft_transfer_call_with_policy<P: TransferPolicy + ?Sized>(
         &mut self,
         receiver_id: AccountId,
         amount: U128,
         memo: Option<String>,
         msg: String,
+        policy: &mut P,
     ) -> PromiseOrValue<U128> {
-        assert_one_yocto();
-        require!(env::prepaid_gas() > GAS_FOR_FT_TRANSFER_CALL, "More gas is required");
+        assert_transferable(self.is_non_transferable());
+        let (gas_for_ft_transfer_call, gas_for_resolve_transfer) = self.transfer_call_gas();
+        require!(env::prepaid_gas() > gas_for_ft_transfer_call, "More gas is required");
+        self.internal_register_receivers([&receiver_id]);
         let sender_id = env::predecessor_account_id();
         let amount: Balance = amount.into();
-        self.internal_transfer(&sender_id, &receiver_id, amount, memo);
+        let amount =
+            self.internal_transfer_with_policy(&sender_id, &receiver_id, amount, memo, policy);
         let receiver_gas = env::prepaid_gas()
-            .checked_sub(GAS_FOR_FT_TRANSFER_CALL)
+            .checked_sub(gas_for_ft_transfer_call)
             .unwrap_or_else(|| env::panic_str("Prepaid gas overflow"));
-        // Initiating receiver's call and the callback
+        // Initiating receiver's call and the callback, the receiver getting all the unused gas
         ext_ft_receiver::ext(receiver_id.clone())
             .with_static_gas(receiver_gas)
+            .with_unused_gas_weight(1)
             .ft_on_transfer(sender_id.clone(), amount.into(), msg)
             .then(
                 ext_ft_resolver::ext(env::current_account_id())
-                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .with_static_gas(gas_for_resolve_transfer)
+                    .with_unused_gas_weight(0)
                     .ft_resolve_transfer(sender_id, receiver_id, amount.into()),
             )
             .into()
     }
 
-    fn ft_total_supply(&self) -> U128 {
-        self.total_supply.into()
+    /// [`FungibleTokenBatch::ft_transfer_batch`] with each transfer checked by `policy`, see
+    /// [`TransferPolicy`].
+    pub fn ft_transfer_batch_with_policy<P: TransferPolicy + ?Sized>(
+        &mut self,
+        transfers: Vec<(AccountId, U128, Option<String>)>,
+        policy: &mut P,
+    ) {
+        assert_transferable(self.is_non_transferable());
+        self.internal_register_receivers(transfers.iter().map(|(receiver_id, _, _)| receiver_id));
+        let sender_id = env::predecessor_account_id();
+        let transfers: Vec<_> = transfers
+            .into_iter()
+            .map(|(receiver_id, amount, memo)| (receiver_id, amount.into(), memo))
+            .collect();
+        self.internal_transfer_batch_with_policy(&sender_id, &transfers, policy);
     }
 
-    fn ft_balance_of(&self, account_id: AccountId) -> U128 {
-        self.accounts.get(&account_id).unwrap_or(0).into()
+    /// [`FungibleTokenBatch::ft_transfer_call_batch`] with each transfer checked by `policy`, see
+    /// [`TransferPolicy`]. The receivers are called with the amounts credited to them.
+    pub fn ft_transfer_call_batch_with_policy<P: TransferPolicy + ?Sized>(
+        &mut self,
+        transfers: Vec<(AccountId, U128, Option<String>)>,
+        msg: String,
+        policy: &mut P,
+    ) -> PromiseOrValue<Vec<U128>> {
+        assert_transferable(self.is_non_transferable());
+        let count = transfers.len() as u64;
+        let (gas_for_ft_transfer_call, gas_for_resolve_transfer) = self.transfer_call_gas();
+        let gas_for_resolve = gas_for_resolve_transfer.saturating_mul(count);
+        let reserved_gas = gas_for_ft_transfer_call.saturating_add(gas_for_resolve);
+        require!(env::prepaid_gas() > reserved_gas, "More gas is required");
+        self.internal_register_receivers(transfers.iter().map(|(receiver_id, _, _)| receiver_id));
+        let sender_id = env::predecessor_account_id();
+        let transfers: Vec<(AccountId, Balance, Option<String>)> = transfers
+            .into_iter()
+            .map(|(receiver_id, amount, memo)| (receiver_id, amount.into(), memo))
+            .collect();
+        let received = self.internal_transfer_batch_with_policy(&sender_id, &transfers, policy);
+        let receiver_gas = env::prepaid_gas()
+            .checked_sub(reserved_gas)
+            .unwrap_or_else(|| env::panic_str("Prepaid gas overflow"))
+            .saturating_div(count);
+        // Initiating the calls of the receivers, which share the unused gas evenly, joined for the
+        // single callback
+        let receiver_calls = transfers
+            .iter()
+            .zip(&received)
+            .map(|((receiver_id, _, _), amount)| {
+                ext_ft_receiver::ext(receiver_id.clone())
+                    .with_static_gas(receiver_gas)
+                    .with_unused_gas_weight(1)
+                    .ft_on_transfer(sender_id.clone(), U128(*amount), msg.clone())
+            })
+            .reduce(Promise::and)
+            .unwrap_or_else(|| env::panic_str(ERR_EMPTY_BATCH));
+        let resolved_transfers = transfers
+            .into_iter()
+            .zip(received)
+            .map(|((receiver_id, _, _), amount)| (receiver_id, U128(amount)))
+            .collect();
+        receiver_calls
+            .then(
+                ext_ft_batch_resolver::ext(env::current_account_id())
+                    .with_static_gas(gas_for_resolve)
+                    .with_unused_gas_weight(0)
+                    .ft_resolve_transfer_batch(sender_id, resolved_transfers),
+            )
+            .into()
     }
-}
 
-impl FungibleToken {
     /// Internal method that returns the amount of burned tokens in a corner case when the sender
     /// has deleted (unregistered) their account while the `ft_transfer_call` was still in flight.
     /// Returns (Used token amount, Burned token amount)
@@ -171,11 +457,44 @@ impl FungibleToken {
         sender_id: &AccountId,
         receiver_id: AccountId,
         amount: U128,
+    ) -> (u128, u128) {
+        self.internal_ft_resolve_transfer_result(0, sender_id, receiver_id, amount)
+    }
+
+    /// Resolves the transfers of an `ft_transfer_call_batch`, as
+    /// [`internal_ft_resolve_transfer`](Self::internal_ft_resolve_transfer) does for each
+    /// `(receiver_id, amount)` of `transfers` with the promise result of the same index.
+    /// Returns (Used token amount, Burned token amount) of each transfer.
+    pub fn internal_ft_resolve_transfer_batch(
+        &mut self,
+        sender_id: &AccountId,
+        transfers: Vec<(AccountId, U128)>,
+    ) -> Vec<(u128, u128)> {
+        transfers
+            .into_iter()
+            .enumerate()
+            .map(|(result_index, (receiver_id, amount))| {
+                self.internal_ft_resolve_transfer_result(
+                    result_index as u64,
+                    sender_id,
+                    receiver_id,
+                    amount,
+                )
+            })
+            .collect()
+    }
+
+    fn internal_ft_resolve_transfer_result(
+        &mut self,
+        result_index: u64,
+        sender_id: &AccountId,
+        receiver_id: AccountId,
+        amount: U128,
     ) -> (u128, u128) {
         let amount: Balance = amount.into();
 
         // Get the unused amount from the `ft_on_transfer` call result.
-        let unused_amount = match env::promise_result(0) {
+        let unused_amount = match env::promise_result(result_index) {
             PromiseResult::Successful(value) => {
                 if let Ok(unused_amount) = near_sdk::serde_json::from_slice::<U128>(&value) {
                     std::cmp::min(amount, unused_amount.0)
@@ -245,3 +564,346 @@ impl FungibleTokenResolver for FungibleToken {
         self.internal_ft_resolve_transfer(&sender_id, receiver_id, amount).0.into()
     }
 }
+
+impl FungibleTokenBatch for FungibleToken {
+    fn ft_transfer_batch(&mut self, transfers: Vec<(AccountId, U128, Option<String>)>) {
+        self.ft_transfer_batch_with_policy(transfers, &mut ());
+    }
+
+    fn ft_transfer_call_batch(
+        &mut self,
+        transfers: Vec<(AccountId, U128, Option<String>)>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>> {
+        self.ft_transfer_call_batch_with_policy(transfers, msg, &mut ())
+    }
+}
+
+impl FungibleTokenBatchResolver for FungibleToken {
+    fn ft_resolve_transfer_batch(
+        &mut self,
+        sender_id: AccountId,
+        transfers: Vec<(AccountId, U128)>,
+    ) -> Vec<U128> {
+        self.internal_ft_resolve_transfer_batch(&sender_id, transfers)
+            .into_iter()
+            .map(|(used_amount, _)| used_amount.into())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fungible_token::StorageAutoRegister;
+    use crate::test_utils::{alice, bob, charlie};
+    use near_sdk::test_utils::get_logs;
+    use near_sdk::{testing_env, NearToken, RuntimeFeesConfig};
+
+    #[test]
+    fn transfer_batch() {
+        let (alice, bob, charlie) =
+            (alice().with_ft_balance(100), bob().with_ft_balance(0), charlie().with_ft_balance(0));
+        let mut token = FungibleToken::new(b"t");
+        for persona in [&alice, &bob, &charlie] {
+            persona.setup_ft(&mut token);
+        }
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer_batch(vec![
+            (bob.account_id().clone(), U128(30), None),
+            (charlie.account_id().clone(), U128(20), Some("payout".to_string())),
+        ]);
+        assert_eq!(token.ft_balance_of(alice.account_id().clone()), U128(50));
+        assert_eq!(token.ft_balance_of(bob.account_id().clone()), U128(30));
+        assert_eq!(token.ft_balance_of(charlie.account_id().clone()), U128(20));
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","amount":"30"},{"old_owner_id":"alice","new_owner_id":"charlie","amount":"20","memo":"payout"}]}"#
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The batch should have at least one transfer")]
+    fn transfer_empty_batch() {
+        let alice = alice().with_ft_balance(100);
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer_batch(vec![]);
+    }
+
+    #[test]
+    fn resolve_transfer_batch() {
+        let (alice, bob, charlie) =
+            (alice().with_ft_balance(50), bob().with_ft_balance(30), charlie().with_ft_balance(20));
+        let mut token = FungibleToken::new(b"t");
+        for persona in [&alice, &bob, &charlie] {
+            persona.setup_ft(&mut token);
+        }
+
+        // Bob used 20 of the 30 tokens, the call of charlie failed.
+        testing_env!(
+            alice.context().build(),
+            near_sdk::test_vm_config(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(b"\"10\"".to_vec()), PromiseResult::Failed],
+        );
+        let used_amounts = token.ft_resolve_transfer_batch(
+            alice.account_id().clone(),
+            vec![(bob.account_id().clone(), U128(30)), (charlie.account_id().clone(), U128(20))],
+        );
+        assert_eq!(used_amounts, vec![U128(20), U128(0)]);
+        assert_eq!(token.ft_balance_of(alice.account_id().clone()), U128(80));
+        assert_eq!(token.ft_balance_of(bob.account_id().clone()), U128(20));
+        assert_eq!(token.ft_balance_of(charlie.account_id().clone()), U128(0));
+    }
+
+    /// Rejects the transfers to `blocked` and withholds a fee of 10% of the other ones for
+    /// `collector`.
+    struct FeePolicy {
+        blocked: AccountId,
+        collector: AccountId,
+    }
+
+    impl TransferPolicy for FeePolicy {
+        fn before_transfer(
+            &mut self,
+            _token: &FungibleToken,
+            _sender_id: &AccountId,
+            receiver_id: &AccountId,
+            amount: Balance,
+        ) -> Result<Balance, String> {
+            if receiver_id == &self.blocked {
+                return Err("The receiver is blocked".to_string());
+            }
+            Ok(amount - amount / 10)
+        }
+
+        fn after_transfer(
+            &mut self,
+            token: &mut FungibleToken,
+            _sender_id: &AccountId,
+            _receiver_id: &AccountId,
+            _received: Balance,
+            withheld: Balance,
+        ) {
+            token.internal_deposit(&self.collector, withheld);
+        }
+    }
+
+    #[test]
+    fn transfer_with_policy() {
+        let (alice, bob, charlie) =
+            (alice().with_ft_balance(100), bob().with_ft_balance(0), charlie().with_ft_balance(0));
+        let mut token = FungibleToken::new(b"t");
+        for persona in [&alice, &bob, &charlie] {
+            persona.setup_ft(&mut token);
+        }
+        let mut policy = FeePolicy {
+            blocked: "mallory".parse().unwrap(),
+            collector: charlie.account_id().clone(),
+        };
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer_with_policy(bob.account_id().clone(), U128(50), None, &mut policy);
+        assert_eq!(token.ft_balance_of(alice.account_id().clone()), U128(50));
+        assert_eq!(token.ft_balance_of(bob.account_id().clone()), U128(45));
+        assert_eq!(token.ft_balance_of(charlie.account_id().clone()), U128(5));
+        assert_eq!(token.ft_total_supply(), U128(100));
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","amount":"45"}]}"#
+            ]
+        );
+
+        token.ft_transfer_batch_with_policy(
+            vec![(bob.account_id().clone(), U128(20), None)],
+            &mut policy,
+        );
+        assert_eq!(token.ft_balance_of(bob.account_id().clone()), U128(63));
+        assert_eq!(token.ft_balance_of(charlie.account_id().clone()), U128(7));
+    }
+
+    /// Withholds a fee of 10% of the transfers, half of which goes to `collector` and the rest is
+    /// burned.
+    struct HalfBurnPolicy {
+        collector: AccountId,
+    }
+
+    impl TransferPolicy for HalfBurnPolicy {
+        fn before_transfer(
+            &mut self,
+            _token: &FungibleToken,
+            _sender_id: &AccountId,
+            _receiver_id: &AccountId,
+            amount: Balance,
+        ) -> Result<Balance, String> {
+            Ok(amount - amount / 10)
+        }
+
+        fn after_transfer(
+            &mut self,
+            token: &mut FungibleToken,
+            _sender_id: &AccountId,
+            _receiver_id: &AccountId,
+            _received: Balance,
+            withheld: Balance,
+        ) {
+            token.internal_deposit(&self.collector, withheld / 2);
+        }
+    }
+
+    #[test]
+    fn transfer_with_policy_burning_fee() {
+        let (alice, bob, charlie) =
+            (alice().with_ft_balance(100), bob().with_ft_balance(0), charlie().with_ft_balance(0));
+        let mut token = FungibleToken::new(b"t");
+        for persona in [&alice, &bob, &charlie] {
+            persona.setup_ft(&mut token);
+        }
+        let mut policy = HalfBurnPolicy { collector: charlie.account_id().clone() };
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer_with_policy(bob.account_id().clone(), U128(50), None, &mut policy);
+        assert_eq!(token.ft_balance_of(bob.account_id().clone()), U128(45));
+        assert_eq!(token.ft_balance_of(charlie.account_id().clone()), U128(2));
+        assert_eq!(token.ft_total_supply(), U128(97));
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","amount":"45"}]}"#,
+                r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_burn","data":[{"owner_id":"alice","amount":"3"}]}"#
+            ]
+        );
+
+        token.ft_transfer_batch_with_policy(
+            vec![(bob.account_id().clone(), U128(20), None)],
+            &mut policy,
+        );
+        assert_eq!(token.ft_total_supply(), U128(96));
+        assert_eq!(
+            get_logs().last().map(String::as_str),
+            Some(
+                r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_burn","data":[{"owner_id":"alice","amount":"1"}]}"#
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The receiver is blocked")]
+    fn transfer_rejected_by_policy() {
+        let (alice, bob) = (alice().with_ft_balance(100), bob().with_ft_balance(0));
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+        bob.setup_ft(&mut token);
+        let mut policy =
+            FeePolicy { blocked: bob.account_id().clone(), collector: alice.account_id().clone() };
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer_with_policy(bob.account_id().clone(), U128(50), None, &mut policy);
+    }
+
+    #[test]
+    fn set_transfer_call_gas() {
+        let mut token = FungibleToken::new(b"t");
+        assert_eq!(
+            token.transfer_call_gas(),
+            (DEFAULT_GAS_FOR_FT_TRANSFER_CALL, MIN_GAS_FOR_RESOLVE_TRANSFER)
+        );
+
+        token.set_transfer_call_gas(Gas::from_tgas(40), Gas::from_tgas(15));
+        assert_eq!(token.transfer_call_gas(), (Gas::from_tgas(40), Gas::from_tgas(15)));
+    }
+
+    #[test]
+    #[should_panic(expected = "The gas for resolve_transfer is less than the minimum")]
+    fn set_transfer_call_gas_below_minimum() {
+        let mut token = FungibleToken::new(b"t");
+        token.set_transfer_call_gas(Gas::from_tgas(30), Gas::from_tgas(4));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The gas for ft_transfer_call does not leave enough gas for its execution"
+    )]
+    fn set_transfer_call_gas_without_execution_margin() {
+        let mut token = FungibleToken::new(b"t");
+        token.set_transfer_call_gas(Gas::from_tgas(30), Gas::from_tgas(10));
+    }
+
+    /// The serialized state of the token before its settings were added, which they don't change.
+    #[near(serializers=[borsh])]
+    struct FungibleTokenState {
+        accounts: LookupMap<AccountId, Balance>,
+        total_supply: Balance,
+        account_storage_usage: StorageUsage,
+    }
+
+    #[test]
+    fn read_state_without_settings() {
+        let alice = alice().with_ft_balance(100);
+        alice.set_as_caller();
+        let mut accounts = LookupMap::new(b"t");
+        accounts.insert(alice.account_id(), &100);
+        let state = FungibleTokenState { accounts, total_supply: 100, account_storage_usage: 122 };
+        let serialized = near_sdk::borsh::to_vec(&state).unwrap();
+
+        let token: FungibleToken = near_sdk::borsh::from_slice(&serialized).unwrap();
+        assert_eq!(token.ft_balance_of(alice.account_id().clone()), U128(100));
+        assert_eq!(token.ft_total_supply(), U128(100));
+        assert_eq!(token.account_storage_usage, 122);
+        assert_eq!(
+            token.transfer_call_gas(),
+            (DEFAULT_GAS_FOR_FT_TRANSFER_CALL, MIN_GAS_FOR_RESOLVE_TRANSFER)
+        );
+        assert_eq!(token.storage_auto_register(), StorageAutoRegister::Disabled);
+        assert!(!token.is_non_transferable());
+        assert_eq!(near_sdk::borsh::to_vec(&token).unwrap(), serialized);
+    }
+
+    #[test]
+    fn non_transferable() {
+        let (alice, bob) = (alice().with_ft_balance(100), bob().with_ft_balance(0));
+        alice.set_as_caller();
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+        bob.setup_ft(&mut token);
+
+        token.set_non_transferable(true);
+        token.set_non_transferable(true);
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"non_transferable","version":"1.0.0","event":"transfers_disabled","data":{"standard":"nep141"}}"#
+            ]
+        );
+        // Minting and burning are not restricted.
+        token.internal_deposit(bob.account_id(), 10);
+        token.internal_withdraw(alice.account_id(), 10);
+        assert_eq!(token.ft_total_supply(), U128(100));
+
+        token.set_non_transferable(false);
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer(bob.account_id().clone(), U128(50), None);
+        assert_eq!(token.ft_balance_of(bob.account_id().clone()), U128(60));
+    }
+
+    #[test]
+    #[should_panic(expected = "The token is non-transferable")]
+    fn non_transferable_transfer() {
+        let (alice, bob) = (alice().with_ft_balance(100), bob().with_ft_balance(0));
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+        bob.setup_ft(&mut token);
+        token.set_non_transferable(true);
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer_batch(vec![(bob.account_id().clone(), U128(50), None)]);
+    }
+}
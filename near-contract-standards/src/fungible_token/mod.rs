@@ -4,17 +4,23 @@
 //!
 //! # Examples
 //! See [`FungibleTokenCore`] and [`FungibleTokenResolver`] for example usage and [`FungibleToken`]
-//! for core standard implementation.
+//! for core standard implementation, [`FungibleTokenBatch`] for batched transfers and
+//! [`TransferPolicy`] for transfer fees, blocklists or pausing.
 
+pub mod batch;
 pub mod core;
 pub mod core_impl;
 pub mod events;
 pub mod macros;
 pub mod metadata;
+pub mod policy;
 pub mod receiver;
 pub mod resolver;
 pub mod storage_impl;
 
+pub use crate::fungible_token::batch::{FungibleTokenBatch, FungibleTokenBatchResolver};
 pub use crate::fungible_token::core::FungibleTokenCore;
 pub use core_impl::{Balance, FungibleToken};
+pub use policy::TransferPolicy;
 pub use resolver::FungibleTokenResolver;
+pub use storage_impl::StorageAutoRegister;
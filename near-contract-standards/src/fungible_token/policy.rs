@@ -0,0 +1,140 @@
+use crate::fungible_token::{Balance, FungibleToken};
+use near_sdk::AccountId;
+
+/// Hooks into the transfers of a [`FungibleToken`], so that a contract can charge transfer fees,
+/// block accounts or pause transfers without reimplementing the token.
+///
+/// The policy is passed to the `*_with_policy` methods of [`FungibleToken`], e.g.
+/// [`FungibleToken::ft_transfer_with_policy`], which the contract calls from its implementation
+/// of [`FungibleTokenCore`](crate::fungible_token::FungibleTokenCore) instead of the default
+/// methods. It is usually stored next to the token in the contract state. Refunds of
+/// `ft_transfer_call` are not transfers of the policy.
+///
+/// The default implementation of the hooks accepts every transfer unchanged, as `()` does.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{near, AccountId, PanicOnDefault, PromiseOrValue};
+/// use near_sdk::json_types::U128;
+/// use near_sdk::store::LookupSet;
+/// use near_contract_standards::fungible_token::events::FtTransfer;
+/// use near_contract_standards::fungible_token::{
+///     Balance, FungibleToken, FungibleTokenCore, TransferPolicy,
+/// };
+///
+/// /// Rejects the transfers of blocked accounts and withholds a fee of 1% of the other ones.
+/// #[near]
+/// pub struct Policy {
+///     blocked: LookupSet<AccountId>,
+///     fee_collector: AccountId,
+/// }
+///
+/// impl TransferPolicy for Policy {
+///     fn before_transfer(
+///         &mut self,
+///         _token: &FungibleToken,
+///         sender_id: &AccountId,
+///         receiver_id: &AccountId,
+///         amount: Balance,
+///     ) -> Result<Balance, String> {
+///         if self.blocked.contains(sender_id) || self.blocked.contains(receiver_id) {
+///             return Err("The account is blocked".to_string());
+///         }
+///         Ok(amount - amount / 100)
+///     }
+///
+///     fn after_transfer(
+///         &mut self,
+///         token: &mut FungibleToken,
+///         sender_id: &AccountId,
+///         _receiver_id: &AccountId,
+///         _received: Balance,
+///         withheld: Balance,
+///     ) {
+///         if withheld > 0 {
+///             token.internal_deposit(&self.fee_collector, withheld);
+///             FtTransfer {
+///                 old_owner_id: sender_id,
+///                 new_owner_id: &self.fee_collector,
+///                 amount: U128(withheld),
+///                 memo: Some("fee"),
+///             }
+///             .emit();
+///         }
+///     }
+/// }
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     token: FungibleToken,
+///     policy: Policy,
+/// }
+///
+/// #[near]
+/// impl FungibleTokenCore for Contract {
+///     #[payable]
+///     fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
+///         self.token.ft_transfer_with_policy(receiver_id, amount, memo, &mut self.policy)
+///     }
+///
+///     #[payable]
+///     fn ft_transfer_call(
+///         &mut self,
+///         receiver_id: AccountId,
+///         amount: U128,
+///         memo: Option<String>,
+///         msg: String,
+///     ) -> PromiseOrValue<U128> {
+///         self.token.ft_transfer_call_with_policy(receiver_id, amount, memo, msg, &mut self.policy)
+///     }
+///
+///     fn ft_total_supply(&self) -> U128 {
+///         self.token.ft_total_supply()
+///     }
+///
+///     fn ft_balance_of(&self, account_id: AccountId) -> U128 {
+///         self.token.ft_balance_of(account_id)
+///     }
+/// }
+/// ```
+pub trait TransferPolicy {
+    /// Called before `amount` is transferred from `sender_id` to `receiver_id`, once the amount
+    /// is known to be positive.
+    ///
+    /// Returns the amount credited to the receiver, which should be positive and at most
+    /// `amount`, or the reason to reject the transfer, with which the call panics. The whole
+    /// `amount` is withdrawn from the sender, and the difference is passed to
+    /// [`after_transfer`](Self::after_transfer) as the withheld amount.
+    fn before_transfer(
+        &mut self,
+        _token: &FungibleToken,
+        _sender_id: &AccountId,
+        _receiver_id: &AccountId,
+        amount: Balance,
+    ) -> Result<Balance, String> {
+        Ok(amount)
+    }
+
+    /// Called once the transfer is applied and its `ft_transfer` event emitted, with the amount
+    /// `received` by the receiver and the amount `withheld` from the sender.
+    ///
+    /// The withheld amount is burned unless the policy deposits it, e.g. to a fee collector with
+    /// [`FungibleToken::internal_deposit`], in which case the policy emits the `ft_transfer` event
+    /// to the collector. The token emits the `ft_burn` event of the sender for the part of the
+    /// withheld amount which is not deposited back.
+    fn after_transfer(
+        &mut self,
+        _token: &mut FungibleToken,
+        _sender_id: &AccountId,
+        _receiver_id: &AccountId,
+        _received: Balance,
+        _withheld: Balance,
+    ) {
+    }
+}
+
+/// Accepts every transfer unchanged, which is the policy of the methods of the NEP-141 traits
+/// implemented by [`FungibleToken`].
+impl TransferPolicy for () {}
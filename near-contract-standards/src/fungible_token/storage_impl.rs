@@ -1,10 +1,190 @@
 use crate::fungible_token::{Balance, FungibleToken};
+use crate::settings::{read_setting, write_setting};
 use crate::storage_management::{StorageBalance, StorageBalanceBounds, StorageManagement};
-use near_sdk::{assert_one_yocto, env, log, AccountId, NearToken, Promise};
+use near_sdk::collections::{LookupMap, LookupSet};
+use near_sdk::{
+    assert_one_yocto, env, log, near, require, AccountId, NearToken, Promise, TokenFormat,
+};
+
+/// Names of the settings of the auto-registration, see [`crate::settings`].
+const STORAGE_AUTO_REGISTER_SETTING: &str = "storage_auto_register";
+const SPONSOR_POOL_SETTING: &str = "sponsor_pool";
+const SPONSORED_ACCOUNTS_SETTING: &str = "sponsored_accounts";
+const SPONSOR_LIMIT_PER_SENDER_SETTING: &str = "sponsor_limit_per_sender";
+const SPONSORED_REGISTRATIONS_SETTING: &str = "sponsored_registrations";
+
+/// Number of receivers each sender can register with the sponsor pool, unless set with
+/// [`FungibleToken::set_sponsor_limit_per_sender`].
+pub const DEFAULT_SPONSOR_LIMIT_PER_SENDER: u32 = 5;
+
+/// How the transfers of a [`FungibleToken`] to accounts that are not registered with it are
+/// handled, see [`FungibleToken::set_storage_auto_register`].
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageAutoRegister {
+    /// The transfers panic, the receivers have to be registered with `storage_deposit` first.
+    Disabled,
+    /// The receivers are registered by the transfers, whose attached deposit must cover the
+    /// storage of the receivers instead of being exactly 1 yoctoNEAR. The rest of the deposit is
+    /// refunded to the sender.
+    AttachedDeposit,
+    /// The receivers are registered by the transfers, and their storage is paid out of the
+    /// sponsor pool of the token, see [`FungibleToken::internal_fund_sponsor_pool`]. The
+    /// registrations paid by the pool are recorded, and their storage deposit goes back to the
+    /// pool when the receivers unregister.
+    ///
+    /// With an attached deposit, the storage deposit of a registered receiver is its own, as if it
+    /// called `storage_deposit`, and is refunded to it when it unregisters.
+    ///
+    /// Transfers of a single unit of the token to fresh account IDs would otherwise drain the pool
+    /// for the cost of the gas, so each sender can only register a limited number of receivers
+    /// with the pool over its lifetime, see [`FungibleToken::set_sponsor_limit_per_sender`], and
+    /// the accounts registered by the pool can't register others with it. Registering its own
+    /// account with `storage_deposit` thus lets an account drain at most the storage of that
+    /// many receivers from the pool.
+    SponsorPool,
+}
 
 impl FungibleToken {
+    /// Sets how the transfers to accounts that are not registered with the token are handled,
+    /// [`StorageAutoRegister::Disabled`] by default. The mode is stored under its own storage key,
+    /// out of the serialized state of the token.
+    pub fn set_storage_auto_register(&mut self, storage_auto_register: StorageAutoRegister) {
+        write_setting(&self.setting_key(STORAGE_AUTO_REGISTER_SETTING), &storage_auto_register);
+    }
+
+    /// Returns how the transfers to accounts that are not registered with the token are handled,
+    /// see [`Self::set_storage_auto_register`].
+    pub fn storage_auto_register(&self) -> StorageAutoRegister {
+        read_setting(&self.setting_key(STORAGE_AUTO_REGISTER_SETTING))
+            .unwrap_or(StorageAutoRegister::Disabled)
+    }
+
+    /// Returns the balance of the sponsor pool paying for the storage of the receivers registered
+    /// by transfers in the [`StorageAutoRegister::SponsorPool`] mode.
+    pub fn sponsor_pool(&self) -> NearToken {
+        read_setting(&self.setting_key(SPONSOR_POOL_SETTING)).unwrap_or(NearToken::from_near(0))
+    }
+
+    fn set_sponsor_pool(&mut self, sponsor_pool: NearToken) {
+        write_setting(&self.setting_key(SPONSOR_POOL_SETTING), &sponsor_pool);
+    }
+
+    /// Accounts whose registration was paid by the sponsor pool.
+    fn sponsored_accounts(&self) -> LookupSet<AccountId> {
+        LookupSet::new(self.setting_key(SPONSORED_ACCOUNTS_SETTING))
+    }
+
+    /// Sets the number of receivers each sender can register with the sponsor pool over its
+    /// lifetime, [`DEFAULT_SPONSOR_LIMIT_PER_SENDER`] by default, see
+    /// [`StorageAutoRegister::SponsorPool`].
+    pub fn set_sponsor_limit_per_sender(&mut self, limit: u32) {
+        write_setting(&self.setting_key(SPONSOR_LIMIT_PER_SENDER_SETTING), &limit);
+    }
+
+    /// Returns the number of receivers each sender can register with the sponsor pool, see
+    /// [`Self::set_sponsor_limit_per_sender`].
+    pub fn sponsor_limit_per_sender(&self) -> u32 {
+        read_setting(&self.setting_key(SPONSOR_LIMIT_PER_SENDER_SETTING))
+            .unwrap_or(DEFAULT_SPONSOR_LIMIT_PER_SENDER)
+    }
+
+    /// Number of receivers registered with the sponsor pool by each sender.
+    fn sponsored_registrations(&self) -> LookupMap<AccountId, u32> {
+        LookupMap::new(self.setting_key(SPONSORED_REGISTRATIONS_SETTING))
+    }
+
+    /// Adds `amount` to the sponsor pool paying for the storage of the receivers registered by
+    /// transfers in the [`StorageAutoRegister::SponsorPool`] mode, e.g. the deposit of the call
+    /// funding it.
+    pub fn internal_fund_sponsor_pool(&mut self, amount: NearToken) {
+        self.set_sponsor_pool(self.sponsor_pool().saturating_add(amount));
+    }
+
+    /// Takes `amount` out of the sponsor pool, to be transferred back by the contract.
+    pub fn internal_withdraw_sponsor_pool(&mut self, amount: NearToken) {
+        let sponsor_pool = self
+            .sponsor_pool()
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("The sponsor pool doesn't have enough balance"));
+        self.set_sponsor_pool(sponsor_pool);
+    }
+
+    /// Checks the deposit attached to a transfer to `receiver_ids` and registers the receivers
+    /// that are not registered yet, as set by [`StorageAutoRegister`].
+    ///
+    /// Without receivers to register, exactly 1 yoctoNEAR must be attached. The setting is only
+    /// read if one of the receivers isn't registered.
+    pub fn internal_register_receivers<'a>(
+        &mut self,
+        receiver_ids: impl IntoIterator<Item = &'a AccountId>,
+    ) {
+        let mut unregistered: Vec<&AccountId> = vec![];
+        for receiver_id in receiver_ids {
+            if !self.accounts.contains_key(receiver_id) && !unregistered.contains(&receiver_id) {
+                unregistered.push(receiver_id);
+            }
+        }
+        if unregistered.is_empty() {
+            assert_one_yocto();
+            return;
+        }
+        let storage_auto_register = self.storage_auto_register();
+        if storage_auto_register == StorageAutoRegister::Disabled {
+            // The transfer panics on the receivers that are not registered.
+            assert_one_yocto();
+            return;
+        }
+
+        let storage_cost =
+            self.storage_balance_bounds().min.saturating_mul(unregistered.len() as u128);
+        if storage_auto_register == StorageAutoRegister::AttachedDeposit {
+            let deposit = env::attached_deposit();
+            if deposit < storage_cost {
+                env::panic_str(&format!(
+                    "The attached deposit should cover the storage of the receivers, {}",
+                    TokenFormat::new(storage_cost).round_up()
+                ));
+            }
+            let refund = deposit.saturating_sub(storage_cost);
+            if refund > NearToken::from_near(0) {
+                Promise::new(env::predecessor_account_id()).transfer(refund);
+            }
+        } else {
+            assert_one_yocto();
+            let sender_id = env::predecessor_account_id();
+            let mut sponsored_accounts = self.sponsored_accounts();
+            require!(
+                !sponsored_accounts.contains(&sender_id),
+                "The accounts registered by the sponsor pool can't register receivers with it"
+            );
+            let mut sponsored_registrations = self.sponsored_registrations();
+            let registrations = sponsored_registrations
+                .get(&sender_id)
+                .unwrap_or(0)
+                .saturating_add(unregistered.len() as u32);
+            require!(
+                registrations <= self.sponsor_limit_per_sender(),
+                "The sender can't register more receivers with the sponsor pool"
+            );
+            sponsored_registrations.insert(&sender_id, &registrations);
+            let sponsor_pool = self.sponsor_pool().checked_sub(storage_cost).unwrap_or_else(|| {
+                env::panic_str("The sponsor pool can't cover the storage of the receivers")
+            });
+            self.set_sponsor_pool(sponsor_pool);
+            for receiver_id in &unregistered {
+                sponsored_accounts.insert(receiver_id);
+            }
+        }
+        for receiver_id in unregistered {
+            self.internal_register_account(receiver_id);
+            log!("The account {} was registered by the transfer", receiver_id);
+        }
+    }
+
     /// Internal method that returns the Account ID and the balance in case the account was
-    /// unregistered.
+    /// unregistered. The storage deposit of an account registered by the sponsor pool goes back
+    /// to the pool rather than to the account.
     pub fn internal_storage_unregister(
         &mut self,
         force: Option<bool>,
@@ -16,9 +196,13 @@ impl FungibleToken {
             if balance == 0 || force {
                 self.accounts.remove(&account_id);
                 self.total_supply -= balance;
-                Promise::new(account_id.clone()).transfer(
-                    self.storage_balance_bounds().min.saturating_add(NearToken::from_yoctonear(1)),
-                );
+                let mut storage_deposit = self.storage_balance_bounds().min;
+                if self.sponsored_accounts().remove(&account_id) {
+                    self.internal_fund_sponsor_pool(storage_deposit);
+                    storage_deposit = NearToken::from_near(0);
+                }
+                Promise::new(account_id.clone())
+                    .transfer(storage_deposit.saturating_add(NearToken::from_yoctonear(1)));
                 Some((account_id, balance))
             } else {
                 env::panic_str(
@@ -61,7 +245,10 @@ impl StorageManagement for FungibleToken {
         } else {
             let min_balance = self.storage_balance_bounds().min;
             if amount < min_balance {
-                env::panic_str("The attached deposit is less than the minimum storage balance");
+                env::panic_str(&format!(
+                    "The attached deposit is less than the minimum storage balance of {}",
+                    TokenFormat::new(min_balance).round_up()
+                ));
             }
 
             self.internal_register_account(&account_id);
@@ -110,3 +297,164 @@ impl StorageManagement for FungibleToken {
         self.internal_storage_balance_of(&account_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fungible_token::FungibleTokenCore;
+    use crate::test_utils::{alice, bob, charlie};
+    use near_sdk::json_types::U128;
+    use near_sdk::mock::MockAction;
+    use near_sdk::test_utils::{accounts, get_created_receipts, get_logs};
+    use near_sdk::testing_env;
+
+    #[test]
+    fn auto_register_with_attached_deposit() {
+        let alice = alice().with_ft_balance(100);
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+        token.set_storage_auto_register(StorageAutoRegister::AttachedDeposit);
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_near(1)).build());
+        token.ft_transfer(bob().account_id().clone(), U128(40), None);
+        assert_eq!(token.ft_balance_of(bob().account_id().clone()), U128(40));
+        assert!(token.storage_balance_of(bob().account_id().clone()).is_some());
+        assert_eq!(get_logs()[0], "The account bob was registered by the transfer");
+
+        // Bob is registered now, exactly 1 yoctoNEAR is attached as usual.
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer(bob().account_id().clone(), U128(10), None);
+        assert_eq!(token.ft_balance_of(bob().account_id().clone()), U128(50));
+    }
+
+    #[test]
+    #[should_panic(expected = "The attached deposit should cover the storage of the receivers")]
+    fn auto_register_with_insufficient_deposit() {
+        let alice = alice().with_ft_balance(100);
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+        token.set_storage_auto_register(StorageAutoRegister::AttachedDeposit);
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer(bob().account_id().clone(), U128(40), None);
+    }
+
+    #[test]
+    fn auto_register_with_sponsor_pool() {
+        let alice = alice().with_ft_balance(100);
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+        token.set_storage_auto_register(StorageAutoRegister::SponsorPool);
+        let storage_cost = token.storage_balance_bounds().min;
+        token.internal_fund_sponsor_pool(storage_cost.saturating_mul(3));
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer_batch(vec![
+            (bob().account_id().clone(), U128(30), None),
+            (charlie().account_id().clone(), U128(20), None),
+            (bob().account_id().clone(), U128(10), None),
+        ]);
+        assert_eq!(token.ft_balance_of(bob().account_id().clone()), U128(40));
+        assert_eq!(token.ft_balance_of(charlie().account_id().clone()), U128(20));
+        assert_eq!(token.sponsor_pool(), storage_cost);
+    }
+
+    #[test]
+    fn unregister_returns_sponsored_deposit_to_pool() {
+        let alice = alice().with_ft_balance(100);
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+        token.set_storage_auto_register(StorageAutoRegister::SponsorPool);
+        let storage_cost = token.storage_balance_bounds().min;
+        token.internal_fund_sponsor_pool(storage_cost);
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer(bob().account_id().clone(), U128(1), None);
+        assert_eq!(token.sponsor_pool(), NearToken::from_near(0));
+
+        // Bob only gets the attached yoctoNEAR back, the storage deposit returns to the pool.
+        testing_env!(bob().context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        assert!(token.storage_unregister(Some(true)));
+        assert_eq!(token.sponsor_pool(), storage_cost);
+        let receipts = get_created_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id.as_str(), "bob");
+        assert!(matches!(
+            receipts[0].actions[..],
+            [MockAction::Transfer { deposit, .. }] if deposit == NearToken::from_yoctonear(1)
+        ));
+
+        // Registered again with its own deposit, bob gets it back when unregistering.
+        testing_env!(bob().context().attached_deposit(storage_cost).build());
+        token.storage_deposit(None, None);
+        testing_env!(bob().context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        assert!(token.storage_unregister(None));
+        assert_eq!(token.sponsor_pool(), storage_cost);
+        let receipts = get_created_receipts();
+        assert!(matches!(
+            receipts[0].actions[..],
+            [MockAction::Transfer { deposit, .. }]
+                if deposit == storage_cost.saturating_add(NearToken::from_yoctonear(1))
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "The sponsor pool can't cover the storage of the receivers")]
+    fn auto_register_with_empty_sponsor_pool() {
+        let alice = alice().with_ft_balance(100);
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+        token.set_storage_auto_register(StorageAutoRegister::SponsorPool);
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer(bob().account_id().clone(), U128(40), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The sender can't register more receivers with the sponsor pool")]
+    fn sponsor_pool_limit_per_sender() {
+        let alice = alice().with_ft_balance(100);
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+        token.set_storage_auto_register(StorageAutoRegister::SponsorPool);
+        token.set_sponsor_limit_per_sender(2);
+        let storage_cost = token.storage_balance_bounds().min;
+        token.internal_fund_sponsor_pool(storage_cost.saturating_mul(3));
+
+        // Dust transfers to fresh accounts only drain the pool up to the limit of the sender.
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer(bob().account_id().clone(), U128(1), None);
+        token.ft_transfer(charlie().account_id().clone(), U128(1), None);
+        assert_eq!(token.sponsor_pool(), storage_cost);
+        token.ft_transfer(accounts(3), U128(1), None);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The accounts registered by the sponsor pool can't register receivers with it"
+    )]
+    fn sponsored_account_cannot_register_receivers() {
+        let alice = alice().with_ft_balance(100);
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+        token.set_storage_auto_register(StorageAutoRegister::SponsorPool);
+        let storage_cost = token.storage_balance_bounds().min;
+        token.internal_fund_sponsor_pool(storage_cost.saturating_mul(2));
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer(bob().account_id().clone(), U128(10), None);
+        testing_env!(bob().context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer(charlie().account_id().clone(), U128(1), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account bob is not registered")]
+    fn transfer_to_unregistered_account() {
+        let alice = alice().with_ft_balance(100);
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.ft_transfer(bob().account_id().clone(), U128(40), None);
+    }
+}
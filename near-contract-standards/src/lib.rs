@@ -10,14 +10,55 @@ pub mod non_fungible_token;
 /// Storage management deals with handling [state storage](https://docs.near.org/docs/concepts/storage-staking) on NEAR. This follows the [storage management standard](https://nomicon.io/Standards/StorageManagement.html).
 pub mod storage_management;
 
+/// Non-transferable option of the fungible and non-fungible tokens, for soulbound tokens.
+pub mod non_transferable;
+
+/// Token sale component for fixed-price and bonding curve distributions of fungible tokens.
+pub mod sale;
+
+/// Wrapped NEAR component, holding native NEAR as a fungible token balance of the contract.
+pub mod wrap_near;
+
+/// Token lockup component vesting fungible tokens to accounts along linear and cliff schedules.
+pub mod token_lockup;
+
+/// Name registry component with expiring registrations and commit-reveal registration.
+pub mod registry;
+
+/// Attestation registry component for issuer-signed claims about accounts.
+pub mod attestation;
+
+/// Factory component deploying child contracts on sub-accounts, with refunds on failure.
+pub mod factory;
+
+/// Role-based access control component, restricting methods to roles with `#[only(role = "...")]`.
+pub mod access_control;
+
+/// Ownable component with a two-step ownership transfer, restricting methods with `#[only_owner]`.
+pub mod ownable;
+
+/// Upgradable component staging the next code of the contract behind a timelock, replacing the
+/// deprecated `upgrade` module.
+pub mod upgradable;
+
 /// This upgrade standard is a use case where a staging area exists for a WASM
 /// blob, allowing it to be stored for a period of time before deployed.
 #[deprecated(
     since = "4.1.0",
-    note = "This was removed because there is no standard (NEP) for upgradable contracts."
+    note = "This was removed because there is no standard (NEP) for upgradable contracts, use the `upgradable` component instead."
 )]
 pub mod upgrade;
 
 pub(crate) mod event;
 
+pub(crate) mod settings;
+
 pub mod contract_metadata;
+
+/// Account fixtures setting up the context and the state of test accounts in the components.
+#[cfg(all(any(test, feature = "unit-testing"), not(target_arch = "wasm32")))]
+pub mod test_utils;
+
+/// Canonical ABI fragments of the standard interfaces, to complete the generated ABI of contracts
+/// implementing them through components or default trait implementations.
+pub mod abi;
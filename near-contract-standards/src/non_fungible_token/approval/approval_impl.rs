@@ -1,16 +1,20 @@
-use crate::non_fungible_token::approval::ext_nft_approval_receiver;
 /// Common implementation of the [approval management standard](https://nomicon.io/Standards/NonFungibleToken/ApprovalManagement.html) for NFTs.
 /// on the contract/account that has just been approved. This is not required to implement.
-use crate::non_fungible_token::approval::NonFungibleTokenApproval;
+use crate::non_fungible_token::approval::{
+    ext_nft_approval_receiver, ext_nft_approval_resolver, NonFungibleTokenApproval,
+    NonFungibleTokenApprovalResolver,
+};
 use crate::non_fungible_token::token::TokenId;
 use crate::non_fungible_token::utils::{
     assert_at_least_one_yocto, bytes_for_approved_account_id, refund_approved_account_ids,
     refund_approved_account_ids_iter, refund_deposit,
 };
 use crate::non_fungible_token::NonFungibleToken;
-use near_sdk::{assert_one_yocto, env, require, AccountId, Gas, Promise};
+use near_sdk::{assert_one_yocto, env, require, AccountId, Gas, Promise, PromiseResult};
 
 const GAS_FOR_NFT_APPROVE: Gas = Gas::from_tgas(10);
+/// Gas of the `nft_resolve_approve` callback of `NonFungibleToken::nft_approve_with_resolve`.
+pub const GAS_FOR_RESOLVE_APPROVE: Gas = Gas::from_tgas(5);
 
 fn expect_token_found<T>(option: Option<T>) -> T {
     option.unwrap_or_else(|| env::panic_str("Token not found"))
@@ -20,42 +24,94 @@ fn expect_approval<T>(option: Option<T>) -> T {
     option.unwrap_or_else(|| env::panic_str("next_approval_by_id must be set for approval ext"))
 }
 
-impl NonFungibleTokenApproval for NonFungibleToken {
-    fn nft_approve(
+impl NonFungibleToken {
+    /// Approve `account_id` for `token_id`, checking that the predecessor is the owner of the
+    /// token and that enough deposit was attached to pay for storage.
+    /// Return the owner of the token and the approval ID.
+    fn internal_approve(
         &mut self,
-        token_id: TokenId,
-        account_id: AccountId,
-        msg: Option<String>,
-    ) -> Option<Promise> {
+        #[allow(clippy::ptr_arg)] token_id: &TokenId,
+        account_id: &AccountId,
+    ) -> (AccountId, u64) {
         assert_at_least_one_yocto();
         let approvals_by_id = self
             .approvals_by_id
             .as_mut()
             .unwrap_or_else(|| env::panic_str("NFT does not support Approval Management"));
 
-        let owner_id = expect_token_found(self.owner_by_id.get(&token_id));
+        let owner_id = expect_token_found(self.owner_by_id.get(token_id));
 
         require!(env::predecessor_account_id() == owner_id, "Predecessor must be token owner.");
 
         let next_approval_id_by_id = expect_approval(self.next_approval_id_by_id.as_mut());
         // update HashMap of approvals for this token
-        let approved_account_ids = &mut approvals_by_id.get(&token_id).unwrap_or_default();
-        let approval_id: u64 = next_approval_id_by_id.get(&token_id).unwrap_or(1u64);
+        let approved_account_ids = &mut approvals_by_id.get(token_id).unwrap_or_default();
+        let approval_id: u64 = next_approval_id_by_id.get(token_id).unwrap_or(1u64);
         let old_approval_id = approved_account_ids.insert(account_id.clone(), approval_id);
 
         // save updated approvals HashMap to contract's LookupMap
-        approvals_by_id.insert(&token_id, approved_account_ids);
+        approvals_by_id.insert(token_id, approved_account_ids);
 
         // increment next_approval_id for this token
-        next_approval_id_by_id.insert(&token_id, &(approval_id + 1));
+        next_approval_id_by_id.insert(token_id, &(approval_id + 1));
 
         // If this approval replaced existing for same account, no storage was used.
         // Otherwise, require that enough deposit was attached to pay for storage, and refund
         // excess.
         let storage_used =
-            if old_approval_id.is_none() { bytes_for_approved_account_id(&account_id) } else { 0 };
+            if old_approval_id.is_none() { bytes_for_approved_account_id(account_id) } else { 0 };
         refund_deposit(storage_used);
 
+        (owner_id, approval_id)
+    }
+
+    /// [`NonFungibleTokenApproval::nft_approve`] calling `nft_on_approve` with `receiver_gas`,
+    /// and then `nft_resolve_approve`, which revokes the approval if the call failed, see
+    /// [`NonFungibleTokenApprovalResolver`]. The contract learns whether the approved account
+    /// accepted the approval from the result of the returned promise.
+    ///
+    /// If `receiver_gas` is not given, `nft_on_approve` gets all the gas left, after the gas of
+    /// the resolver.
+    pub fn nft_approve_with_resolve(
+        &mut self,
+        token_id: TokenId,
+        account_id: AccountId,
+        msg: Option<String>,
+        receiver_gas: Option<Gas>,
+    ) -> Option<Promise> {
+        let (owner_id, approval_id) = self.internal_approve(&token_id, &account_id);
+
+        // if given `msg`, schedule call to `nft_on_approve` and its callback and return them.
+        // Else, return None.
+        msg.map(|msg| {
+            let reserved_gas = GAS_FOR_NFT_APPROVE
+                .saturating_add(GAS_FOR_RESOLVE_APPROVE)
+                .saturating_add(receiver_gas.unwrap_or(Gas::from_gas(0)));
+            require!(env::prepaid_gas() > reserved_gas, "More gas is required");
+            let receiver = ext_nft_approval_receiver::ext(account_id.clone());
+            let receiver = match receiver_gas {
+                Some(gas) => receiver.with_static_gas(gas).with_unused_gas_weight(0),
+                None => receiver.with_unused_gas_weight(1),
+            };
+            receiver.nft_on_approve(token_id.clone(), owner_id.clone(), approval_id, msg).then(
+                ext_nft_approval_resolver::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_APPROVE)
+                    .with_unused_gas_weight(0)
+                    .nft_resolve_approve(owner_id, token_id, account_id, approval_id),
+            )
+        })
+    }
+}
+
+impl NonFungibleTokenApproval for NonFungibleToken {
+    fn nft_approve(
+        &mut self,
+        token_id: TokenId,
+        account_id: AccountId,
+        msg: Option<String>,
+    ) -> Option<Promise> {
+        let (owner_id, approval_id) = self.internal_approve(&token_id, &account_id);
+
         // if given `msg`, schedule call to `nft_on_approve` and return it. Else, return None.
         msg.map(|msg| {
             ext_nft_approval_receiver::ext(account_id)
@@ -151,3 +207,137 @@ impl NonFungibleTokenApproval for NonFungibleToken {
         }
     }
 }
+
+impl NonFungibleTokenApprovalResolver for NonFungibleToken {
+    /// Returns true if the approved account accepted the approval.
+    fn nft_resolve_approve(
+        &mut self,
+        owner_id: AccountId,
+        token_id: TokenId,
+        account_id: AccountId,
+        approval_id: u64,
+    ) -> bool {
+        // if call succeeded, return early
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return true;
+        }
+
+        // OTHERWISE, revoke the approval, unless the token was transferred or burned, or the
+        // account was approved again, since
+        if self.owner_by_id.get(&token_id).as_ref() != Some(&owner_id) {
+            return false;
+        }
+        let approvals_by_id = self
+            .approvals_by_id
+            .as_mut()
+            .unwrap_or_else(|| env::panic_str("NFT does not support Approval Management"));
+        if let Some(approved_account_ids) = &mut approvals_by_id.get(&token_id) {
+            if approved_account_ids.get(&account_id) == Some(&approval_id) {
+                approved_account_ids.remove(&account_id);
+                refund_approved_account_ids_iter(owner_id, core::iter::once(&account_id));
+                if approved_account_ids.is_empty() {
+                    approvals_by_id.remove(&token_id);
+                } else {
+                    approvals_by_id.insert(&token_id, approved_account_ids);
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{alice, bob, Persona};
+    use near_sdk::test_utils::accounts;
+    use near_sdk::{testing_env, NearToken, RuntimeFeesConfig};
+
+    /// Alice owns the token "0" and approves bob with a call to `nft_on_approve`.
+    fn setup() -> (NonFungibleToken, u64) {
+        let alice = alice().with_nft_tokens(["0"]);
+        alice.set_as_caller();
+        let mut token =
+            NonFungibleToken::new(b"n", accounts(0), None::<Vec<u8>>, None::<Vec<u8>>, Some(b"a"));
+        alice.setup_nft(&mut token);
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_near(1)).build());
+        let promise = token.nft_approve_with_resolve(
+            "0".into(),
+            bob().account_id().clone(),
+            Some("list".into()),
+            Some(Gas::from_tgas(50)),
+        );
+        assert!(promise.is_some());
+        (token, 1)
+    }
+
+    fn set_promise_result(persona: &Persona, result: PromiseResult) {
+        testing_env!(
+            persona.context().build(),
+            near_sdk::test_vm_config(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+    }
+
+    #[test]
+    fn approval_accepted() {
+        let (mut token, approval_id) = setup();
+        let bob = bob().account_id().clone();
+        set_promise_result(&alice(), PromiseResult::Successful(b"\"listed\"".to_vec()));
+        assert!(token.nft_resolve_approve(
+            alice().account_id().clone(),
+            "0".into(),
+            bob.clone(),
+            approval_id
+        ));
+        assert!(token.nft_is_approved("0".into(), bob, Some(approval_id)));
+    }
+
+    #[test]
+    fn approval_rejected() {
+        let (mut token, approval_id) = setup();
+        let bob = bob().account_id().clone();
+        set_promise_result(&alice(), PromiseResult::Failed);
+        assert!(!token.nft_resolve_approve(
+            alice().account_id().clone(),
+            "0".into(),
+            bob.clone(),
+            approval_id
+        ));
+        assert!(!token.nft_is_approved("0".into(), bob, None));
+        assert!(token.approvals_by_id.as_ref().unwrap().get(&"0".to_string()).is_none());
+    }
+
+    #[test]
+    fn approval_rejected_after_approving_again() {
+        let (mut token, approval_id) = setup();
+        let bob = bob().account_id().clone();
+        testing_env!(alice().context().attached_deposit(NearToken::from_near(1)).build());
+        token.nft_approve("0".into(), bob.clone(), None);
+
+        set_promise_result(&alice(), PromiseResult::Failed);
+        assert!(!token.nft_resolve_approve(
+            alice().account_id().clone(),
+            "0".into(),
+            bob.clone(),
+            approval_id
+        ));
+        assert!(token.nft_is_approved("0".into(), bob, Some(approval_id + 1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "More gas is required")]
+    fn approve_with_too_much_receiver_gas() {
+        let (mut token, _) = setup();
+        testing_env!(alice().context().attached_deposit(NearToken::from_near(1)).build());
+        token.nft_approve_with_resolve(
+            "0".into(),
+            bob().account_id().clone(),
+            Some("list".into()),
+            Some(Gas::from_tgas(300)),
+        );
+    }
+}
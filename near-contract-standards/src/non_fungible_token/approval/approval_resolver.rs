@@ -0,0 +1,81 @@
+use crate::non_fungible_token::token::TokenId;
+use near_sdk::{ext_contract, AccountId};
+
+/// Used when an account is approved using `NonFungibleToken::nft_approve_with_resolve`. This is
+/// the method that's called after `nft_on_approve`. This trait is implemented on the NFT contract.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{near, AccountId, Gas, PanicOnDefault, Promise};
+/// use near_contract_standards::non_fungible_token::approval::NonFungibleTokenApprovalResolver;
+/// use near_contract_standards::non_fungible_token::{NonFungibleToken, TokenId};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///    tokens: NonFungibleToken,
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     #[payable]
+///     pub fn nft_approve_call(
+///         &mut self,
+///         token_id: TokenId,
+///         account_id: AccountId,
+///         msg: String,
+///         receiver_gas: Option<Gas>,
+///     ) -> Option<Promise> {
+///         self.tokens.nft_approve_with_resolve(token_id, account_id, Some(msg), receiver_gas)
+///     }
+/// }
+///
+/// #[near]
+/// impl NonFungibleTokenApprovalResolver for Contract {
+///     #[private]
+///     fn nft_resolve_approve(
+///         &mut self,
+///         owner_id: AccountId,
+///         token_id: TokenId,
+///         account_id: AccountId,
+///         approval_id: u64,
+///     ) -> bool {
+///         self.tokens.nft_resolve_approve(owner_id, token_id, account_id, approval_id)
+///     }
+/// }
+/// ```
+///
+#[ext_contract(ext_nft_approval_resolver)]
+pub trait NonFungibleTokenApprovalResolver {
+    /// Finalize an approval with a call to `nft_on_approve`.
+    ///
+    /// The process:
+    ///
+    /// 1. Owner calls a method of the NFT contract approving an account with a `msg`
+    /// 2. NFT contract adds the approval
+    /// 3. NFT contract calls `nft_on_approve` on the approved contract
+    /// 4+. [approved contract may make other cross-contract calls]
+    /// N. NFT contract resolves promise chain with `nft_resolve_approve`, and may
+    ///    revoke the approval
+    ///
+    /// Requirements:
+    /// * Contract MUST forbid calls to this function by any account except self
+    /// * If promise chain failed, contract MUST revoke the approval, unless the token was
+    ///   transferred or the account approved again since, and refund its storage to the owner
+    ///
+    /// Arguments:
+    /// * `owner_id`: the owner of the token when it was approved
+    /// * `token_id`: the token for which the approval was added
+    /// * `account_id`: the approved account
+    /// * `approval_id`: the approval ID of the approval
+    ///
+    /// Returns true if the approved account accepted the approval.
+    fn nft_resolve_approve(
+        &mut self,
+        owner_id: AccountId,
+        token_id: TokenId,
+        account_id: AccountId,
+        approval_id: u64,
+    ) -> bool;
+}
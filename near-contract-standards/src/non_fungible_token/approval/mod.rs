@@ -1,7 +1,10 @@
 mod approval_impl;
 mod approval_receiver;
+mod approval_resolver;
 
+pub use approval_impl::GAS_FOR_RESOLVE_APPROVE;
 pub use approval_receiver::*;
+pub use approval_resolver::*;
 use near_sdk::ext_contract;
 
 use crate::non_fungible_token::token::TokenId;
@@ -2,23 +2,39 @@ use super::resolver::NonFungibleTokenResolver;
 use crate::non_fungible_token::core::receiver::ext_nft_receiver;
 use crate::non_fungible_token::core::resolver::ext_nft_resolver;
 use crate::non_fungible_token::core::NonFungibleTokenCore;
-use crate::non_fungible_token::events::{NftMint, NftTransfer};
+use crate::non_fungible_token::enumeration::ReceivedIndex;
+use crate::non_fungible_token::events::{NftBurn, NftMint, NftTransfer};
 use crate::non_fungible_token::metadata::TokenMetadata;
 use crate::non_fungible_token::token::{Token, TokenId};
-use crate::non_fungible_token::utils::{refund_approved_account_ids, refund_deposit_to_account};
+use crate::non_fungible_token::utils::{
+    bytes_for_approved_account_id, refund_approved_account_ids, refund_deposit_to_account,
+};
+use crate::non_transferable::{assert_transferable, NonTransferableEvent};
+use crate::settings::{read_setting, setting_key, tree_map_values_prefix, write_setting};
 use near_sdk::borsh::BorshSerialize;
 use near_sdk::collections::{LookupMap, TreeMap, UnorderedSet};
 use near_sdk::json_types::Base64VecU8;
 use near_sdk::{
-    assert_one_yocto, env, near, require, AccountId, BorshStorageKey, Gas, IntoStorageKey,
+    assert_one_yocto, env, near, require, AccountId, BorshStorageKey, Gas, IntoStorageKey, Promise,
     PromiseOrValue, PromiseResult, StorageUsage,
 };
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::ops::Deref;
 
 const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas::from_tgas(5);
 const GAS_FOR_NFT_TRANSFER_CALL: Gas = Gas::from_tgas(30);
 
+/// Name of the setting of the `non_transferable` option, see [`crate::settings`].
+const NON_TRANSFERABLE_SETTING: &str = "non_transferable";
+
+/// Maximum length, in bytes, of the token and account IDs logged in a single event of a batch.
+/// Larger batches are logged as several events, to keep each log well below the log length
+/// limit of the runtime.
+const MAX_BATCH_EVENT_DATA_LEN: usize = 4 * 1024;
+/// Length of the JSON around the IDs of an event entry, counted towards the maximum above.
+const BATCH_EVENT_ENTRY_OVERHEAD: usize = 64;
+
 /// Implementation of the non-fungible token standard.
 /// Allows to include NEP-171 compatible token to any contract.
 /// There are next traits that any contract may implement:
@@ -93,6 +109,27 @@ impl NonFungibleToken {
         this
     }
 
+    /// Sets whether the tokens are non-transferable, disabling `nft_transfer`, `nft_transfer_call`
+    /// and `nft_transfer_payout`. Minting, burning, approvals and enumeration are not restricted.
+    /// See the [`non_transferable`](crate::non_transferable) module.
+    pub fn set_non_transferable(&mut self, non_transferable: bool) {
+        if self.is_non_transferable() != non_transferable {
+            write_setting(&self.setting_key(NON_TRANSFERABLE_SETTING), &non_transferable);
+            NonTransferableEvent::new("nep171", non_transferable).emit();
+        }
+    }
+
+    /// Returns whether the tokens are non-transferable, see [`Self::set_non_transferable`].
+    pub fn is_non_transferable(&self) -> bool {
+        read_setting(&self.setting_key(NON_TRANSFERABLE_SETTING)).unwrap_or(false)
+    }
+
+    /// Returns the storage key of the setting `name` of the token, in the namespace of the values
+    /// of `owner_by_id`.
+    fn setting_key(&self, name: &str) -> Vec<u8> {
+        setting_key(&tree_map_values_prefix(&self.owner_by_id), name)
+    }
+
     // TODO: does this seem reasonable?
     fn measure_min_token_storage_cost(&mut self) {
         let initial_storage_usage = env::storage_usage();
@@ -125,6 +162,7 @@ impl NonFungibleToken {
             let u = &mut UnorderedSet::new(StorageKey::TokensPerOwner {
                 account_hash: env::sha256(tmp_owner_id.as_bytes()),
             });
+            ReceivedIndex::new(tokens_per_owner).add(&tmp_owner_id, u, &tmp_token_id);
             u.insert(&tmp_token_id);
             tokens_per_owner.insert(&tmp_owner_id, u);
         }
@@ -149,6 +187,7 @@ impl NonFungibleToken {
         }
         if let Some(tokens_per_owner) = &mut self.tokens_per_owner {
             let mut u = tokens_per_owner.remove(&tmp_owner_id).unwrap();
+            ReceivedIndex::new(tokens_per_owner).remove(&tmp_owner_id, &u, &tmp_token_id);
             u.remove(&tmp_token_id);
         }
         if let Some(token_metadata_by_id) = &mut self.token_metadata_by_id {
@@ -175,6 +214,8 @@ impl NonFungibleToken {
             let mut owner_tokens = tokens_per_owner.get(from).unwrap_or_else(|| {
                 env::panic_str("Unable to access tokens per owner in unguarded call.")
             });
+            let mut received_index = ReceivedIndex::new(tokens_per_owner);
+            received_index.remove(from, &owner_tokens, token_id);
             owner_tokens.remove(token_id);
             if owner_tokens.is_empty() {
                 tokens_per_owner.remove(from);
@@ -187,6 +228,7 @@ impl NonFungibleToken {
                     account_hash: env::sha256(to.as_bytes()),
                 })
             });
+            received_index.add(to, &receiver_tokens, token_id);
             receiver_tokens.insert(token_id);
             tokens_per_owner.insert(to, &receiver_tokens);
         }
@@ -202,6 +244,24 @@ impl NonFungibleToken {
         #[allow(clippy::ptr_arg)] token_id: &TokenId,
         approval_id: Option<u64>,
         memo: Option<String>,
+    ) -> (AccountId, Option<HashMap<AccountId, u64>>) {
+        let (owner_id, approved_account_ids) =
+            self.internal_transfer_unlogged(sender_id, receiver_id, token_id, approval_id);
+
+        let sender_id = Some(sender_id).filter(|sender_id| *sender_id != &owner_id);
+        NonFungibleToken::emit_transfer(&owner_id, receiver_id, token_id, sender_id, memo);
+
+        // return previous owner & approvals
+        (owner_id, approved_account_ids)
+    }
+
+    /// `internal_transfer` without the transfer event, for batches logging a single event.
+    fn internal_transfer_unlogged(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        #[allow(clippy::ptr_arg)] token_id: &TokenId,
+        approval_id: Option<u64>,
     ) -> (AccountId, Option<HashMap<AccountId, u64>>) {
         let owner_id =
             self.owner_by_id.get(token_id).unwrap_or_else(|| env::panic_str("Token not found"));
@@ -211,11 +271,89 @@ impl NonFungibleToken {
         let approved_account_ids =
             self.approvals_by_id.as_mut().map(|by_id| by_id.remove(token_id).unwrap_or_default());
 
+        Self::check_authorized(sender_id, &owner_id, approved_account_ids.as_ref(), approval_id);
+
+        require!(&owner_id != receiver_id, "Current and next owner must differ");
+
+        self.internal_transfer_unguarded(token_id, &owner_id, receiver_id);
+
+        (owner_id, approved_account_ids)
+    }
+
+    /// Transfer a batch of tokens from the predecessor, as `nft_transfer` does for each
+    /// `(token_id, receiver_id)` of `transfers`. Not part of official standard.
+    /// Consuming contract expected to wrap this with an `nft_batch_transfer` function.
+    ///
+    /// Requirements:
+    /// * Caller of the method must attach a deposit of 1 yoctoⓃ, for the whole batch.
+    /// * Caller must be the owner or an approved account of each token.
+    ///
+    /// The storage of the cleared approvals is refunded once to each previous owner, and a
+    /// single transfer event is emitted for the batch.
+    pub fn nft_batch_transfer(&mut self, transfers: Vec<(TokenId, AccountId)>) {
+        assert_one_yocto();
+        assert_transferable(self.is_non_transferable());
+        require!(!transfers.is_empty(), "The batch should have at least one transfer");
+        let sender_id = env::predecessor_account_id();
+
+        let mut owner_ids = Vec::with_capacity(transfers.len());
+        let mut approvals_released: HashMap<AccountId, u64> = HashMap::new();
+        for (token_id, receiver_id) in &transfers {
+            let (owner_id, approved_account_ids) =
+                self.internal_transfer_unlogged(&sender_id, receiver_id, token_id, None);
+            if let Some(approved_account_ids) = approved_account_ids {
+                let storage_released: u64 =
+                    approved_account_ids.keys().map(bytes_for_approved_account_id).sum();
+                *approvals_released.entry(owner_id.clone()).or_default() += storage_released;
+            }
+            owner_ids.push(owner_id);
+        }
+
+        for (owner_id, storage_released) in approvals_released {
+            if storage_released > 0 {
+                Promise::new(owner_id)
+                    .transfer(env::storage_byte_cost().saturating_mul(storage_released.into()));
+            }
+        }
+
+        // One entry per previous and new owner, with all the tokens transferred between them
+        let tokens = transfers.iter().zip(&owner_ids).map(|((token_id, receiver_id), owner_id)| {
+            ((owner_id, receiver_id), token_id.as_str())
+        });
+        let key_len = |(owner_id, receiver_id): &(&AccountId, &AccountId)| {
+            owner_id.as_str().len() + receiver_id.as_str().len() + sender_id.as_str().len()
+        };
+        emit_batch_events(tokens, key_len, |entries| {
+            let events: Vec<NftTransfer> = entries
+                .iter()
+                .map(|((owner_id, receiver_id), token_ids)| NftTransfer {
+                    old_owner_id: owner_id,
+                    new_owner_id: receiver_id,
+                    token_ids,
+                    authorized_id: Some(&sender_id)
+                        .filter(|sender_id| sender_id != owner_id)
+                        .map(|f| f.deref()),
+                    memo: None,
+                })
+                .collect();
+            NftTransfer::emit_many(&events);
+        });
+    }
+
+    /// Checks that `sender_id` is allowed to transfer or burn a token of `owner_id`, i.e. is the
+    /// owner or, if the approval extension is used, one of the `approved_account_ids` of the
+    /// token, with `approval_id` if given.
+    /// Returns the sender if it is an approved account.
+    fn check_authorized<'a>(
+        sender_id: &'a AccountId,
+        owner_id: &AccountId,
+        approved_account_ids: Option<&HashMap<AccountId, u64>>,
+        approval_id: Option<u64>,
+    ) -> Option<&'a AccountId> {
         // check if authorized
-        let sender_id = if sender_id != &owner_id {
+        if sender_id != owner_id {
             // Panic if approval extension is NOT being used
             let app_acc_ids = approved_account_ids
-                .as_ref()
                 .unwrap_or_else(|| env::panic_str("Approval extension is disabled"));
 
             // Approval extension is being used; get approval_id for sender.
@@ -237,16 +375,7 @@ impl NonFungibleToken {
             Some(sender_id)
         } else {
             None
-        };
-
-        require!(&owner_id != receiver_id, "Current and next owner must differ");
-
-        self.internal_transfer_unguarded(token_id, &owner_id, receiver_id);
-
-        NonFungibleToken::emit_transfer(&owner_id, receiver_id, token_id, sender_id, memo);
-
-        // return previous owner & approvals
-        (owner_id, approved_account_ids)
+        }
     }
 
     fn emit_transfer(
@@ -353,6 +482,7 @@ impl NonFungibleToken {
                     account_hash: env::sha256(owner_id.as_bytes()),
                 })
             });
+            ReceivedIndex::new(tokens_per_owner).add(&owner_id, &token_ids, &token_id);
             token_ids.insert(&token_id);
             tokens_per_owner.insert(&owner_id, &token_ids);
         }
@@ -369,6 +499,146 @@ impl NonFungibleToken {
 
         Token { token_id, owner_id, metadata: token_metadata, approved_account_ids }
     }
+
+    /// Mint a batch of tokens, given as `(token_id, token_owner_id, token_metadata)`.
+    /// Not part of official standard. Consuming contract expected to wrap this with an
+    /// `nft_batch_mint` function.
+    ///
+    /// Requirements:
+    /// * Caller must be the `owner_id` set during contract initialization.
+    /// * Caller of the method must attach a deposit covering the storage of all the tokens.
+    /// * The requirements of `internal_mint` for each token.
+    ///
+    /// Returns the newly minted tokens, see [`internal_batch_mint`](Self::internal_batch_mint).
+    pub fn nft_batch_mint(
+        &mut self,
+        tokens: Vec<(TokenId, AccountId, Option<TokenMetadata>)>,
+    ) -> Vec<Token> {
+        require!(env::predecessor_account_id() == self.owner_id, "Unauthorized");
+        self.internal_batch_mint(tokens, Some(env::predecessor_account_id()))
+    }
+
+    /// Mint a batch of tokens, given as `(token_id, token_owner_id, token_metadata)`, without
+    /// checking whether the caller id is equal to the `owner_id`.
+    ///
+    /// The storage of all the tokens is computed once, and the left over balance is refunded to
+    /// `refund_id` as in `internal_mint_with_refund`. A single mint event is emitted for the
+    /// batch.
+    ///
+    /// Returns the newly minted tokens.
+    pub fn internal_batch_mint(
+        &mut self,
+        tokens: Vec<(TokenId, AccountId, Option<TokenMetadata>)>,
+        refund_id: Option<AccountId>,
+    ) -> Vec<Token> {
+        require!(!tokens.is_empty(), "The batch should have at least one token");
+        let initial_storage_usage = refund_id.map(|account_id| (account_id, env::storage_usage()));
+
+        let tokens: Vec<Token> = tokens
+            .into_iter()
+            .map(|(token_id, token_owner_id, token_metadata)| {
+                self.internal_mint_with_refund(token_id, token_owner_id, token_metadata, None)
+            })
+            .collect();
+
+        if let Some((id, storage_usage)) = initial_storage_usage {
+            refund_deposit_to_account(env::storage_usage() - storage_usage, id)
+        }
+
+        // One entry per owner, with all the tokens minted to it
+        let minted = tokens.iter().map(|token| (&token.owner_id, token.token_id.as_str()));
+        emit_batch_events(
+            minted,
+            |owner_id| owner_id.as_str().len(),
+            |entries| {
+                let events: Vec<NftMint> = entries
+                    .iter()
+                    .map(|(owner_id, token_ids)| NftMint { owner_id, token_ids, memo: None })
+                    .collect();
+                NftMint::emit_many(&events);
+            },
+        );
+        tokens
+    }
+
+    /// Burn a token. Not part of official standard, but needed in most situations.
+    /// Consuming contract expected to wrap this with an `nft_burn` function.
+    ///
+    /// Requirements:
+    /// * Caller of the method must attach a deposit of 1 yoctoⓃ for security purposes.
+    /// * Caller must be the owner of the token or, if using the Approval Management extension,
+    ///   one of its approved accounts.
+    ///
+    /// Returns the previous owner of the token, see [`internal_burn`](Self::internal_burn).
+    pub fn nft_burn(&mut self, token_id: TokenId) -> AccountId {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        self.internal_burn(&sender_id, &token_id, None, None)
+    }
+
+    /// Burn `token_id`, checking that `sender_id` is allowed to burn it as in `internal_transfer`.
+    /// The token is removed from the metadata, enumeration and approval extensions being used,
+    /// the storage it freed is refunded to its owner, and the burn event is emitted.
+    ///
+    /// Returns the previous owner of the token.
+    pub fn internal_burn(
+        &mut self,
+        sender_id: &AccountId,
+        #[allow(clippy::ptr_arg)] token_id: &TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) -> AccountId {
+        let owner_id =
+            self.owner_by_id.get(token_id).unwrap_or_else(|| env::panic_str("Token not found"));
+        let initial_storage_usage = env::storage_usage();
+
+        let approved_account_ids =
+            self.approvals_by_id.as_mut().map(|by_id| by_id.remove(token_id).unwrap_or_default());
+        let sender_id = Self::check_authorized(
+            sender_id,
+            &owner_id,
+            approved_account_ids.as_ref(),
+            approval_id,
+        );
+        if let Some(next_approval_id_by_id) = &mut self.next_approval_id_by_id {
+            next_approval_id_by_id.remove(token_id);
+        }
+
+        if let Some(token_metadata_by_id) = &mut self.token_metadata_by_id {
+            token_metadata_by_id.remove(token_id);
+        }
+
+        if let Some(tokens_per_owner) = &mut self.tokens_per_owner {
+            let mut owner_tokens = tokens_per_owner.get(&owner_id).unwrap_or_else(|| {
+                env::panic_str("Unable to access tokens per owner in burn call.")
+            });
+            ReceivedIndex::new(tokens_per_owner).remove(&owner_id, &owner_tokens, token_id);
+            owner_tokens.remove(token_id);
+            if owner_tokens.is_empty() {
+                tokens_per_owner.remove(&owner_id);
+            } else {
+                tokens_per_owner.insert(&owner_id, &owner_tokens);
+            }
+        }
+
+        self.owner_by_id.remove(token_id);
+
+        // Refund the storage freed by the token, including its approvals, to its owner
+        let storage_released = initial_storage_usage.saturating_sub(env::storage_usage());
+        if storage_released > 0 {
+            Promise::new(owner_id.clone())
+                .transfer(env::storage_byte_cost().saturating_mul(storage_released.into()));
+        }
+
+        NftBurn {
+            owner_id: &owner_id,
+            token_ids: &[token_id],
+            authorized_id: sender_id.map(|f| f.deref()),
+            memo: memo.as_deref(),
+        }
+        .emit();
+        owner_id
+    }
 }
 
 impl NonFungibleTokenCore for NonFungibleToken {
@@ -380,6 +650,7 @@ impl NonFungibleTokenCore for NonFungibleToken {
         memo: Option<String>,
     ) {
         assert_one_yocto();
+        assert_transferable(self.is_non_transferable());
         let sender_id = env::predecessor_account_id();
         self.internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, memo);
     }
@@ -393,6 +664,7 @@ impl NonFungibleTokenCore for NonFungibleToken {
         msg: String,
     ) -> PromiseOrValue<bool> {
         assert_one_yocto();
+        assert_transferable(self.is_non_transferable());
         require!(env::prepaid_gas() > GAS_FOR_NFT_TRANSFER_CALL, "More gas is required");
         let sender_id = env::predecessor_account_id();
         let (old_owner, old_approvals) =
@@ -476,3 +748,265 @@ impl NonFungibleTokenResolver for NonFungibleToken {
         false
     }
 }
+
+/// Groups the `(key, token_id)` pairs of a batch by key, in the order of their first occurrence,
+/// and calls `emit` with the groups split in chunks of at most [`MAX_BATCH_EVENT_DATA_LEN`]
+/// bytes, `key_len` giving the length of the account IDs of an entry.
+fn emit_batch_events<'a, K, I, L, F>(tokens: I, key_len: L, mut emit: F)
+where
+    K: Copy + Eq + Hash,
+    I: IntoIterator<Item = (K, &'a str)>,
+    L: Fn(&K) -> usize,
+    F: FnMut(&[(K, Vec<&'a str>)]),
+{
+    let mut groups: Vec<(K, Vec<&'a str>)> = Vec::new();
+    let mut index_by_key: HashMap<K, usize> = HashMap::new();
+    for (key, token_id) in tokens {
+        let index = *index_by_key.entry(key).or_insert_with(|| {
+            groups.push((key, Vec::new()));
+            groups.len() - 1
+        });
+        groups[index].1.push(token_id);
+    }
+
+    let mut chunk: Vec<(K, Vec<&'a str>)> = Vec::new();
+    let mut chunk_len = 0;
+    for (key, token_ids) in groups {
+        let entry_len = key_len(&key) + BATCH_EVENT_ENTRY_OVERHEAD;
+        let mut in_chunk = false;
+        for token_id in token_ids {
+            // Quotes and comma around the token ID
+            let token_len = token_id.len() + 3;
+            let len = if in_chunk { token_len } else { token_len + entry_len };
+            if !chunk.is_empty() && chunk_len + len > MAX_BATCH_EVENT_DATA_LEN {
+                emit(&chunk);
+                chunk.clear();
+                chunk_len = 0;
+                in_chunk = false;
+            }
+            if !in_chunk {
+                chunk.push((key, Vec::new()));
+                chunk_len += entry_len;
+                in_chunk = true;
+            }
+            chunk_len += token_len;
+            if let Some((_, chunk_token_ids)) = chunk.last_mut() {
+                chunk_token_ids.push(token_id);
+            }
+        }
+    }
+    if !chunk.is_empty() {
+        emit(&chunk);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::non_fungible_token::enumeration::NonFungibleTokenEnumeration;
+    use crate::test_utils::{alice, bob, charlie, Persona};
+    use near_sdk::test_utils::{accounts, get_logs};
+    use near_sdk::{testing_env, NearToken};
+
+    /// Alice owns the tokens "0" and "1", with all the extensions.
+    fn setup() -> NonFungibleToken {
+        let alice = alice().with_nft_tokens(["0", "1"]);
+        alice.set_as_caller();
+        let mut token =
+            NonFungibleToken::new(b"n", accounts(0), Some(b"m"), Some(b"e"), Some(b"a"));
+        alice.setup_nft(&mut token);
+        token
+    }
+
+    fn set_caller_with_one_yocto(persona: &Persona) {
+        testing_env!(persona.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+    }
+
+    #[test]
+    fn burn_by_owner() {
+        let mut token = setup();
+        let alice = alice();
+        set_caller_with_one_yocto(&alice);
+        assert_eq!(&token.nft_burn("0".into()), alice.account_id());
+
+        assert!(token.nft_token("0".into()).is_none());
+        assert!(token.token_metadata_by_id.as_ref().unwrap().get(&"0".to_string()).is_none());
+        assert_eq!(token.nft_supply_for_owner(alice.account_id().clone()).0, 1);
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_burn","data":[{"owner_id":"alice","token_ids":["0"]}]}"#
+            ]
+        );
+
+        token.nft_burn("1".into());
+        assert!(token.tokens_per_owner.as_ref().unwrap().get(alice.account_id()).is_none());
+        assert_eq!(token.nft_total_supply().0, 0);
+    }
+
+    #[test]
+    fn burn_by_approved_account() {
+        let mut token = setup();
+        let bob = bob();
+        token
+            .approvals_by_id
+            .as_mut()
+            .unwrap()
+            .insert(&"0".to_string(), &HashMap::from([(bob.account_id().clone(), 1)]));
+        token.next_approval_id_by_id.as_mut().unwrap().insert(&"0".to_string(), &2);
+
+        set_caller_with_one_yocto(&bob);
+        token.internal_burn(bob.account_id(), &"0".into(), Some(1), Some("burnt".into()));
+
+        assert!(token.approvals_by_id.as_ref().unwrap().get(&"0".to_string()).is_none());
+        assert!(token.next_approval_id_by_id.as_ref().unwrap().get(&"0".to_string()).is_none());
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_burn","data":[{"owner_id":"alice","token_ids":["0"],"authorized_id":"bob","memo":"burnt"}]}"#
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Sender not approved")]
+    fn burn_by_unapproved_account() {
+        let mut token = setup();
+        set_caller_with_one_yocto(&bob());
+        token.nft_burn("0".into());
+    }
+
+    #[test]
+    fn non_transferable_kept_out_of_state() {
+        let mut token = setup();
+        let serialized = near_sdk::borsh::to_vec(&token).unwrap();
+        token.set_non_transferable(true);
+        assert_eq!(near_sdk::borsh::to_vec(&token).unwrap(), serialized);
+
+        let token: NonFungibleToken = near_sdk::borsh::from_slice(&serialized).unwrap();
+        assert!(token.is_non_transferable());
+    }
+
+    #[test]
+    #[should_panic(expected = "The token is non-transferable")]
+    fn non_transferable() {
+        let mut token = setup();
+        let (alice, bob) = (alice(), bob());
+        token.set_non_transferable(true);
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"non_transferable","version":"1.0.0","event":"transfers_disabled","data":{"standard":"nep171"}}"#
+            ]
+        );
+
+        // Minting and burning are not restricted.
+        set_caller_with_one_yocto(&alice);
+        token.internal_mint_with_refund(
+            "2".into(),
+            alice.account_id().clone(),
+            Some(TokenMetadata::default()),
+            None,
+        );
+        token.nft_burn("0".into());
+        assert_eq!(token.nft_supply_for_owner(alice.account_id().clone()).0, 2);
+
+        token.nft_transfer(bob.account_id().clone(), "1".into(), None, None);
+    }
+
+    #[test]
+    fn batch_transfer() {
+        let mut token = setup();
+        let (alice, bob, charlie) = (alice(), bob(), charlie());
+        token
+            .approvals_by_id
+            .as_mut()
+            .unwrap()
+            .insert(&"1".to_string(), &HashMap::from([(charlie.account_id().clone(), 1)]));
+
+        set_caller_with_one_yocto(&alice);
+        token.nft_batch_transfer(vec![
+            ("0".into(), bob.account_id().clone()),
+            ("1".into(), charlie.account_id().clone()),
+        ]);
+        assert_eq!(token.owner_by_id.get(&"0".to_string()).as_ref(), Some(bob.account_id()));
+        assert_eq!(token.owner_by_id.get(&"1".to_string()).as_ref(), Some(charlie.account_id()));
+        assert!(token.approvals_by_id.as_ref().unwrap().get(&"1".to_string()).is_none());
+        assert_eq!(token.nft_supply_for_owner(alice.account_id().clone()).0, 0);
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","token_ids":["0"]},{"old_owner_id":"alice","new_owner_id":"charlie","token_ids":["1"]}]}"#
+            ]
+        );
+
+        set_caller_with_one_yocto(&charlie);
+        token.nft_batch_transfer(vec![("1".into(), bob.account_id().clone())]);
+        assert_eq!(token.nft_supply_for_owner(bob.account_id().clone()).0, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Sender not approved")]
+    fn batch_transfer_of_unapproved_token() {
+        let mut token = setup();
+        let bob = bob();
+        set_caller_with_one_yocto(&bob);
+        token.nft_batch_transfer(vec![("0".into(), accounts(3))]);
+    }
+
+    #[test]
+    fn batch_mint() {
+        let mut token = setup();
+        let (bob, charlie) = (bob(), charlie());
+        testing_env!(alice().context().attached_deposit(NearToken::from_near(1)).build());
+        let tokens = token.nft_batch_mint(vec![
+            ("2".into(), bob.account_id().clone(), Some(TokenMetadata::default())),
+            ("3".into(), charlie.account_id().clone(), Some(TokenMetadata::default())),
+            ("4".into(), bob.account_id().clone(), Some(TokenMetadata::default())),
+        ]);
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(token.nft_supply_for_owner(bob.account_id().clone()).0, 2);
+        assert_eq!(token.nft_total_supply().0, 5);
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_mint","data":[{"owner_id":"bob","token_ids":["2","4"]},{"owner_id":"charlie","token_ids":["3"]}]}"#
+            ]
+        );
+    }
+
+    #[test]
+    fn batch_mint_events_are_chunked() {
+        let mut token = setup();
+        let bob = bob();
+        testing_env!(alice().context().attached_deposit(NearToken::from_near(10)).build());
+        let batch: Vec<_> =
+            (0..100).map(|i| (format!("{:064}", i), bob.account_id().clone(), None)).collect();
+        token.nft_batch_mint(batch);
+
+        let logs = get_logs();
+        assert!(logs.len() > 1);
+        let mut token_ids = Vec::new();
+        for log in &logs {
+            assert!(log.len() < 5 * 1024);
+            let event: near_sdk::serde_json::Value =
+                near_sdk::serde_json::from_str(log.strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+            assert_eq!(event["event"], "nft_mint");
+            for data in event["data"].as_array().unwrap() {
+                assert_eq!(data["owner_id"], "bob");
+                token_ids.extend(data["token_ids"].as_array().unwrap().iter().cloned());
+            }
+        }
+        let expected: Vec<_> = (0..100).map(|i| format!("{:064}", i)).collect();
+        assert_eq!(token_ids, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn batch_mint_by_other_account() {
+        let mut token = setup();
+        let bob = bob();
+        testing_env!(bob.context().attached_deposit(NearToken::from_near(1)).build());
+        token.nft_batch_mint(vec![("2".into(), bob.account_id().clone(), None)]);
+    }
+}
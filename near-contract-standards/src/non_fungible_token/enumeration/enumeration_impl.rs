@@ -1,11 +1,94 @@
-use super::NonFungibleTokenEnumeration;
+use super::{NonFungibleTokenEnumeration, NonFungibleTokenOrderedEnumeration};
 use crate::non_fungible_token::token::Token;
 use crate::non_fungible_token::NonFungibleToken;
+use crate::settings::{collection_prefix, setting_key};
+use near_sdk::collections::{LookupMap, UnorderedSet};
 use near_sdk::json_types::U128;
+use near_sdk::store;
 use near_sdk::{env, require, AccountId};
+use std::ops::Bound;
 
 type TokenId = String;
 
+/// Index of the tokens of each owner in the order they were received in, kept next to
+/// `tokens_per_owner` under keys derived from its prefix, see [`crate::settings`], so that the
+/// state of the contracts already deployed stays readable.
+///
+/// Each token of an owner is stored in an ordered map of the owner under a sequence number one
+/// past the last one of the map, and the sequence number of each token is stored by token ID, to
+/// page the tokens of an owner by range from a token. The tokens an owner had before the index
+/// was introduced are indexed in the order of their set the first time the owner receives or
+/// sends a token.
+pub(crate) struct ReceivedIndex {
+    trees: store::LookupMap<AccountId, store::TreeMap<u64, TokenId>>,
+    sequences: store::LookupMap<TokenId, u64>,
+    nodes_prefix: Vec<u8>,
+}
+
+impl ReceivedIndex {
+    pub(crate) fn new(tokens_per_owner: &LookupMap<AccountId, UnorderedSet<TokenId>>) -> Self {
+        let prefix = collection_prefix(tokens_per_owner);
+        Self {
+            trees: store::LookupMap::new(setting_key(&prefix, "received_trees")),
+            sequences: store::LookupMap::new(setting_key(&prefix, "received_sequences")),
+            nodes_prefix: setting_key(&prefix, "received_nodes"),
+        }
+    }
+
+    /// Returns the ordered map of the tokens of `owner_id`, indexing the tokens of `owner_tokens`,
+    /// the set of the tokens of the owner before the change, if the owner isn't indexed yet.
+    fn tree_mut(
+        &mut self,
+        owner_id: &AccountId,
+        owner_tokens: &UnorderedSet<TokenId>,
+    ) -> &mut store::TreeMap<u64, TokenId> {
+        if !self.trees.contains_key(owner_id) {
+            let mut tree = store::TreeMap::new(
+                [self.nodes_prefix.as_slice(), &env::sha256(owner_id.as_bytes())].concat(),
+            );
+            for (sequence, token_id) in (0..).zip(owner_tokens.iter()) {
+                self.sequences.insert(token_id.clone(), sequence);
+                tree.insert(sequence, token_id);
+            }
+            self.trees.insert(owner_id.clone(), tree);
+        }
+        self.trees.get_mut(owner_id).unwrap_or_else(|| env::abort())
+    }
+
+    /// Records that `owner_id`, owning the tokens of `owner_tokens`, received `token_id`.
+    pub(crate) fn add(
+        &mut self,
+        owner_id: &AccountId,
+        owner_tokens: &UnorderedSet<TokenId>,
+        token_id: &TokenId,
+    ) {
+        let tree = self.tree_mut(owner_id, owner_tokens);
+        let sequence = tree.keys().next_back().map_or(0, |last| last + 1);
+        tree.insert(sequence, token_id.clone());
+        self.sequences.insert(token_id.clone(), sequence);
+    }
+
+    /// Records that `owner_id`, owning the tokens of `owner_tokens`, sent or burned `token_id`.
+    pub(crate) fn remove(
+        &mut self,
+        owner_id: &AccountId,
+        owner_tokens: &UnorderedSet<TokenId>,
+        token_id: &TokenId,
+    ) {
+        self.tree_mut(owner_id, owner_tokens);
+        let sequence = self.sequences.remove(token_id).unwrap_or_else(|| {
+            env::panic_str("Unable to access the sequence number of the token in the index.")
+        });
+        let tree = self.trees.get_mut(owner_id).unwrap_or_else(|| env::abort());
+        tree.remove(&sequence);
+        if tree.is_empty() {
+            // Removes the free slots of the nodes from the storage as well.
+            tree.clear();
+            self.trees.remove(owner_id);
+        }
+    }
+}
+
 impl NonFungibleToken {
     /// Helper function used by a enumerations methods
     /// Note: this method is not exposed publicly to end users
@@ -18,6 +101,16 @@ impl NonFungibleToken {
 
         Token { token_id, owner_id, metadata, approved_account_ids }
     }
+
+    /// Helper function used by a enumerations methods of the tokens of an owner
+    fn enum_tokens_per_owner(&self) -> &LookupMap<AccountId, UnorderedSet<TokenId>> {
+        self.tokens_per_owner.as_ref().unwrap_or_else(|| {
+            env::panic_str(
+                "Could not find tokens_per_owner when calling a method on the \
+                enumeration standard.",
+            )
+        })
+    }
 }
 
 impl NonFungibleTokenEnumeration for NonFungibleToken {
@@ -46,12 +139,7 @@ impl NonFungibleTokenEnumeration for NonFungibleToken {
     }
 
     fn nft_supply_for_owner(&self, account_id: AccountId) -> U128 {
-        let tokens_per_owner = self.tokens_per_owner.as_ref().unwrap_or_else(|| {
-            env::panic_str(
-                "Could not find tokens_per_owner when calling a method on the \
-                enumeration standard.",
-            )
-        });
+        let tokens_per_owner = self.enum_tokens_per_owner();
         tokens_per_owner
             .get(&account_id)
             .map(|account_tokens| U128::from(account_tokens.len() as u128))
@@ -64,12 +152,7 @@ impl NonFungibleTokenEnumeration for NonFungibleToken {
         from_index: Option<U128>,
         limit: Option<u64>,
     ) -> Vec<Token> {
-        let tokens_per_owner = self.tokens_per_owner.as_ref().unwrap_or_else(|| {
-            env::panic_str(
-                "Could not find tokens_per_owner when calling a method on the \
-                enumeration standard.",
-            )
-        });
+        let tokens_per_owner = self.enum_tokens_per_owner();
         let token_set = if let Some(token_set) = tokens_per_owner.get(&account_id) {
             token_set
         } else {
@@ -95,3 +178,182 @@ impl NonFungibleTokenEnumeration for NonFungibleToken {
             .collect()
     }
 }
+
+impl NonFungibleTokenOrderedEnumeration for NonFungibleToken {
+    fn nft_tokens_from(
+        &self,
+        from_token_id: Option<TokenId>,
+        limit: Option<u64>,
+        descending: Option<bool>,
+    ) -> Vec<Token> {
+        let limit = limit.map(|v| v as usize).unwrap_or(usize::MAX);
+        require!(limit != 0, "Cannot provide limit of 0.");
+        // Range queries on the tree, starting right after `from_token_id`.
+        let tokens: Box<dyn Iterator<Item = (TokenId, AccountId)> + '_> =
+            match (from_token_id, descending.unwrap_or_default()) {
+                (None, false) => Box::new(self.owner_by_id.iter()),
+                (None, true) => Box::new(self.owner_by_id.iter_rev()),
+                (Some(token_id), false) => Box::new(self.owner_by_id.iter_from(token_id)),
+                (Some(token_id), true) => Box::new(self.owner_by_id.iter_rev_from(token_id)),
+            };
+        tokens
+            .take(limit)
+            .map(|(token_id, owner_id)| self.enum_get_token(owner_id, token_id))
+            .collect()
+    }
+
+    fn nft_tokens_by_owner(
+        &self,
+        account_id: AccountId,
+        from_token_id: Option<TokenId>,
+        limit: Option<u64>,
+        descending: Option<bool>,
+    ) -> Vec<Token> {
+        let limit = limit.map(|v| v as usize).unwrap_or(usize::MAX);
+        require!(limit != 0, "Cannot provide limit of 0.");
+        let descending = descending.unwrap_or_default();
+        let tokens_per_owner = self.enum_tokens_per_owner();
+        let index = ReceivedIndex::new(tokens_per_owner);
+        let token_ids: Vec<TokenId> = if let Some(tree) = index.trees.get(&account_id) {
+            // Range queries on the ordered map of the owner, starting right after the sequence
+            // number of `from_token_id`.
+            let from = match &from_token_id {
+                Some(token_id) => {
+                    let sequence = index.sequences.get(token_id).copied();
+                    match sequence.filter(|sequence| tree.get(sequence) == Some(token_id)) {
+                        Some(sequence) => Bound::Excluded(sequence),
+                        None => env::panic_str("from_token_id is not owned by account_id."),
+                    }
+                }
+                None => Bound::Unbounded,
+            };
+            if descending {
+                tree.range((Bound::Unbounded, from))
+                    .rev()
+                    .take(limit)
+                    .map(|(_, id)| id.clone())
+                    .collect()
+            } else {
+                tree.range((from, Bound::Unbounded)).take(limit).map(|(_, id)| id.clone()).collect()
+            }
+        } else if let Some(token_set) = tokens_per_owner.get(&account_id) {
+            // The owner didn't receive or send a token since the index was introduced, the tokens
+            // are in the order of the set.
+            let token_ids = token_set.as_vector().iter();
+            let mut token_ids: Box<dyn Iterator<Item = TokenId> + '_> =
+                if descending { Box::new(token_ids.rev()) } else { Box::new(token_ids) };
+            if let Some(from_token_id) = &from_token_id {
+                require!(
+                    token_set.contains(from_token_id),
+                    "from_token_id is not owned by account_id."
+                );
+                token_ids = Box::new(token_ids.skip_while(move |id| id != from_token_id).skip(1));
+            }
+            token_ids.take(limit).collect()
+        } else {
+            require!(from_token_id.is_none(), "from_token_id is not owned by account_id.");
+            vec![]
+        };
+        token_ids
+            .into_iter()
+            .map(|token_id| self.enum_get_token(account_id.clone(), token_id))
+            .collect()
+    }
+
+    fn nft_tokens_count_by_owner(&self, account_id: AccountId) -> u64 {
+        self.enum_tokens_per_owner().get(&account_id).map_or(0, |token_set| token_set.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{alice, bob};
+    use near_sdk::test_utils::accounts;
+
+    fn token_ids(tokens: Vec<Token>) -> Vec<TokenId> {
+        tokens.into_iter().map(|token| token.token_id).collect()
+    }
+
+    /// Alice owns the tokens "0" to "4", received in that order, and bob the token "5".
+    fn setup() -> NonFungibleToken {
+        let alice = alice().with_nft_tokens(["0", "1", "2", "3", "4"]);
+        let bob = bob().with_nft_tokens(["5"]);
+        alice.set_as_caller();
+        let mut token =
+            NonFungibleToken::new(b"n", accounts(0), Some(b"m"), Some(b"e"), None::<Vec<u8>>);
+        alice.setup_nft(&mut token);
+        bob.setup_nft(&mut token);
+        token
+    }
+
+    #[test]
+    fn tokens_in_both_orders() {
+        let token = setup();
+        assert_eq!(token_ids(token.nft_tokens_from(None, Some(2), None)), ["0", "1"]);
+        assert_eq!(token_ids(token.nft_tokens_from(Some("1".into()), Some(2), None)), ["2", "3"]);
+        assert_eq!(token_ids(token.nft_tokens_from(None, Some(2), Some(true))), ["5", "4"]);
+        assert_eq!(
+            token_ids(token.nft_tokens_from(Some("4".into()), None, Some(true))),
+            ["3", "2", "1", "0"]
+        );
+        assert!(token.nft_tokens_from(Some("5".into()), None, None).is_empty());
+    }
+
+    #[test]
+    fn tokens_by_owner_in_both_orders() {
+        let token = setup();
+        let alice = alice().account_id().clone();
+        assert_eq!(
+            token_ids(token.nft_tokens_by_owner(alice.clone(), Some("0".into()), Some(2), None)),
+            ["1", "2"]
+        );
+        assert_eq!(
+            token_ids(token.nft_tokens_by_owner(alice.clone(), None, Some(2), Some(true))),
+            ["4", "3"]
+        );
+        assert_eq!(
+            token_ids(token.nft_tokens_by_owner(alice.clone(), Some("2".into()), None, Some(true))),
+            ["1", "0"]
+        );
+        assert!(token.nft_tokens_by_owner(alice.clone(), Some("4".into()), None, None).is_empty());
+        assert!(token.nft_tokens_by_owner(accounts(3), None, None, Some(true)).is_empty());
+
+        assert_eq!(token.nft_tokens_count_by_owner(alice), 5);
+        assert_eq!(token.nft_tokens_count_by_owner(bob().account_id().clone()), 1);
+        assert_eq!(token.nft_tokens_count_by_owner(accounts(3)), 0);
+    }
+
+    #[test]
+    fn tokens_by_owner_in_order_received() {
+        let mut token = setup();
+        let alice = alice().account_id().clone();
+        let bob = bob().account_id().clone();
+        // Removing "1" from the set of alice moves "4" to its place in the set.
+        token.internal_transfer_unguarded(&"1".into(), &alice, &bob);
+        token.internal_transfer_unguarded(&"5".into(), &bob, &alice);
+        token.internal_transfer_unguarded(&"1".into(), &bob, &alice);
+        assert_eq!(
+            token_ids(token.nft_tokens_by_owner(alice.clone(), None, None, Some(true))),
+            ["1", "5", "4", "3", "2", "0"]
+        );
+        assert_eq!(
+            token_ids(token.nft_tokens_by_owner(alice.clone(), Some("4".into()), Some(2), None)),
+            ["5", "1"]
+        );
+
+        token.internal_burn(&alice, &"5".into(), None, None);
+        assert_eq!(
+            token_ids(token.nft_tokens_by_owner(alice, Some("1".into()), None, Some(true))),
+            ["4", "3", "2", "0"]
+        );
+        assert!(token.nft_tokens_by_owner(bob, None, None, None).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "from_token_id is not owned by account_id.")]
+    fn tokens_by_owner_from_token_of_other_owner() {
+        let token = setup();
+        token.nft_tokens_by_owner(bob().account_id().clone(), Some("0".into()), None, Some(true));
+    }
+}
@@ -1,6 +1,8 @@
 mod enumeration_impl;
 
-use crate::non_fungible_token::token::Token;
+pub(crate) use self::enumeration_impl::ReceivedIndex;
+
+use crate::non_fungible_token::token::{Token, TokenId};
 use near_sdk::json_types::U128;
 use near_sdk::{ext_contract, AccountId};
 
@@ -86,3 +88,101 @@ pub trait NonFungibleTokenEnumeration {
         limit: Option<u64>,       // default: unlimited (could fail due to gas limit)
     ) -> Vec<Token>;
 }
+
+/// Enumeration of the tokens in either order, so that e.g. a gallery can page the tokens of an
+/// owner newest first. It is not part of the [NFT enumeration standard], which
+/// [`NonFungibleTokenEnumeration`] implements.
+///
+/// All the tokens are listed in the order of their IDs, compared as strings, so `"10"` comes
+/// before `"9"`: token IDs padded with zeros to the same length list the tokens in the order they
+/// were minted in. The tokens of an owner are listed in the order the owner received them in,
+/// minted or transferred, from an index kept by [`NonFungibleToken`] with the enumeration
+/// extension. Both are paged with range queries from the last token of the previous page, so
+/// that a page costs the same gas wherever it starts.
+///
+/// [`NonFungibleToken`]: crate::non_fungible_token::NonFungibleToken
+///
+/// [NFT enumeration standard]: https://nomicon.io/Standards/NonFungibleToken/Enumeration.html
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{near, AccountId, PanicOnDefault};
+/// use near_contract_standards::non_fungible_token::enumeration::NonFungibleTokenOrderedEnumeration;
+/// use near_contract_standards::non_fungible_token::{NonFungibleToken, Token, TokenId};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     tokens: NonFungibleToken,
+/// }
+///
+/// #[near]
+/// impl NonFungibleTokenOrderedEnumeration for Contract {
+///     fn nft_tokens_from(
+///         &self,
+///         from_token_id: Option<TokenId>,
+///         limit: Option<u64>,
+///         descending: Option<bool>,
+///     ) -> Vec<Token> {
+///         self.tokens.nft_tokens_from(from_token_id, limit, descending)
+///     }
+///
+///     fn nft_tokens_by_owner(
+///         &self,
+///         account_id: AccountId,
+///         from_token_id: Option<TokenId>,
+///         limit: Option<u64>,
+///         descending: Option<bool>,
+///     ) -> Vec<Token> {
+///         self.tokens.nft_tokens_by_owner(account_id, from_token_id, limit, descending)
+///     }
+///
+///     fn nft_tokens_count_by_owner(&self, account_id: AccountId) -> u64 {
+///         self.tokens.nft_tokens_count_by_owner(account_id)
+///     }
+/// }
+/// ```
+#[ext_contract(ext_nft_ordered_enumeration)]
+pub trait NonFungibleTokenOrderedEnumeration {
+    /// Get a list of tokens in the order of their IDs, compared as strings
+    ///
+    /// Arguments:
+    /// * `from_token_id`: the token after which the list starts, exclusive, usually the last
+    ///    token of the previous page. The list starts from the first token if not given.
+    /// * `limit`: the maximum number of tokens to return
+    /// * `descending`: whether the list is in descending order of the token IDs, from the last
+    ///    token if `from_token_id` is not given
+    ///
+    /// Returns an array of Token objects, as described in Core standard
+    fn nft_tokens_from(
+        &self,
+        from_token_id: Option<TokenId>,
+        limit: Option<u64>, // default: unlimited (could fail due to gas limit)
+        descending: Option<bool>, // default: false
+    ) -> Vec<Token>;
+
+    /// Get a list of the tokens owned by a given account, in the order they were received in
+    ///
+    /// Arguments:
+    /// * `account_id`: a valid NEAR account
+    /// * `from_token_id`: the token after which the list starts, exclusive, usually the last
+    ///    token of the previous page, which must still be owned by the account. The list starts
+    ///    from the first token received if not given.
+    /// * `limit`: the maximum number of tokens to return
+    /// * `descending`: whether the list is newest first, from the last token received if
+    ///    `from_token_id` is not given
+    ///
+    /// Returns a paginated list of tokens owned by this account
+    fn nft_tokens_by_owner(
+        &self,
+        account_id: AccountId,
+        from_token_id: Option<TokenId>,
+        limit: Option<u64>, // default: unlimited (could fail due to gas limit)
+        descending: Option<bool>, // default: false
+    ) -> Vec<Token>;
+
+    /// Returns the number of tokens owned by a given account, as `nft_supply_for_owner` but as
+    /// a JSON number.
+    fn nft_tokens_count_by_owner(&self, account_id: AccountId) -> u64;
+}
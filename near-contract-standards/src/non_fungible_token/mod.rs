@@ -12,6 +12,9 @@ mod macros;
 /// Metadata traits and implementation according to the [NFT enumeration standard](https://nomicon.io/Standards/NonFungibleToken/Metadata.html).
 /// This covers both the contract metadata and the individual token metadata.
 pub mod metadata;
+/// The [payout standard](https://nomicon.io/Standards/Tokens/NonFungibleToken/Payout) for NFTs,
+/// with royalties per contract or per token.
+pub mod payout;
 /// The Token struct for the non-fungible token.
 mod token;
 pub use self::token::{Token, TokenId};
@@ -23,6 +26,7 @@ pub use utils::*;
 pub use self::approval::NonFungibleTokenApproval;
 pub use self::core::NonFungibleToken;
 pub use self::core::NonFungibleTokenResolver;
-pub use self::enumeration::NonFungibleTokenEnumeration;
+pub use self::enumeration::{NonFungibleTokenEnumeration, NonFungibleTokenOrderedEnumeration};
+pub use self::payout::NonFungibleTokenPayout;
 
 pub mod events;
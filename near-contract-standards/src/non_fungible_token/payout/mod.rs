@@ -0,0 +1,94 @@
+mod payout_impl;
+
+pub use payout_impl::{Royalties, MAX_BASIS_POINTS};
+
+use crate::non_fungible_token::token::TokenId;
+use near_sdk::json_types::U128;
+use near_sdk::{ext_contract, near, AccountId};
+use std::collections::HashMap;
+
+/// Split of a sale amount between the accounts to pay, as returned by the methods of
+/// [`NonFungibleTokenPayout`].
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Payout {
+    /// Account ID -> The amount to pay to it.
+    pub payout: HashMap<AccountId, U128>,
+}
+
+/// Trait of the [payout standard](https://nomicon.io/Standards/Tokens/NonFungibleToken/Payout)
+/// for NFTs, letting marketplaces pay the royalties of a token when selling it.
+///
+/// The royalties are kept by a [`Royalties`] component next to the
+/// [`NonFungibleToken`](crate::non_fungible_token::NonFungibleToken), as a table of basis points
+/// per account for the whole contract, which each token may override with its own table.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{near, AccountId, PanicOnDefault};
+/// use near_sdk::json_types::U128;
+/// use near_contract_standards::non_fungible_token::payout::{
+///     NonFungibleTokenPayout, Payout, Royalties,
+/// };
+/// use near_contract_standards::non_fungible_token::{NonFungibleToken, TokenId};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     tokens: NonFungibleToken,
+///     royalties: Royalties,
+/// }
+///
+/// #[near]
+/// impl NonFungibleTokenPayout for Contract {
+///     fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: Option<u32>) -> Payout {
+///         self.royalties.nft_payout(&self.tokens, token_id, balance, max_len_payout)
+///     }
+///
+///     #[payable]
+///     fn nft_transfer_payout(
+///         &mut self,
+///         receiver_id: AccountId,
+///         token_id: TokenId,
+///         approval_id: Option<u64>,
+///         memo: Option<String>,
+///         balance: U128,
+///         max_len_payout: Option<u32>,
+///     ) -> Payout {
+///         self.royalties.nft_transfer_payout(
+///             &mut self.tokens,
+///             receiver_id,
+///             token_id,
+///             approval_id,
+///             memo,
+///             balance,
+///             max_len_payout,
+///         )
+///     }
+/// }
+/// ```
+#[ext_contract(ext_nft_payout)]
+pub trait NonFungibleTokenPayout {
+    /// Returns how `balance`, the amount a token is sold for, is split between its royalty
+    /// accounts and its owner.
+    ///
+    /// Panics if the payout has more than `max_len_payout` accounts.
+    fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: Option<u32>) -> Payout;
+
+    /// Transfers the token as `nft_transfer` does, typically by a marketplace approved for it,
+    /// and returns the payout of `balance` to the previous owner and the royalty accounts.
+    /// Exactly 1 yoctoNEAR must be attached.
+    ///
+    /// Panics if the payout has more than `max_len_payout` accounts, in which case the token is
+    /// not transferred.
+    fn nft_transfer_payout(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        balance: U128,
+        max_len_payout: Option<u32>,
+    ) -> Payout;
+}
@@ -0,0 +1,273 @@
+use super::Payout;
+use crate::non_fungible_token::utils::refund_approved_account_ids;
+use crate::non_fungible_token::{NonFungibleToken, TokenId};
+use crate::non_transferable::assert_transferable;
+use near_sdk::json_types::U128;
+use near_sdk::store::LookupMap;
+use near_sdk::{assert_one_yocto, env, near, require, AccountId, IntoStorageKey};
+use std::collections::HashMap;
+
+/// Basis points of the whole sale amount.
+pub const MAX_BASIS_POINTS: u32 = 10_000;
+
+/// Implementation of the royalties of the tokens of a [`NonFungibleToken`], and of their payouts
+/// as defined by [`NonFungibleTokenPayout`](super::NonFungibleTokenPayout).
+///
+/// A royalty table gives the share of the sale amount of a token paid to each account, in basis
+/// points, the owner of the token being paid the rest. The total of a table is capped by
+/// `max_total_bps`. The methods setting the tables don't check the predecessor, e.g. to set them
+/// from a method of the contract restricted to its owner, or when minting the tokens.
+#[near]
+pub struct Royalties {
+    /// Royalty table of the tokens without their own one.
+    pub default_royalty: HashMap<AccountId, u32>,
+    /// Token ID -> Its own royalty table.
+    pub royalty_by_id: LookupMap<TokenId, HashMap<AccountId, u32>>,
+    /// Maximum total of a royalty table, in basis points.
+    pub max_total_bps: u32,
+}
+
+impl Royalties {
+    pub fn new<S>(prefix: S, max_total_bps: u32) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        require!(
+            max_total_bps <= MAX_BASIS_POINTS,
+            format!("The maximum total of the royalties exceeds {} basis points", MAX_BASIS_POINTS)
+        );
+        let prefix = prefix.into_storage_key();
+        Self {
+            default_royalty: HashMap::new(),
+            royalty_by_id: LookupMap::new([prefix.as_slice(), b"r"].concat()),
+            max_total_bps,
+        }
+    }
+
+    /// Returns the royalty table of `token_id`, its own one or the default one.
+    pub fn royalty_of(&self, token_id: &TokenId) -> &HashMap<AccountId, u32> {
+        self.royalty_by_id.get(token_id).unwrap_or(&self.default_royalty)
+    }
+
+    /// Sets the royalty table of the tokens without their own one.
+    pub fn set_default_royalty(&mut self, royalty: HashMap<AccountId, u32>) {
+        self.assert_valid_royalty(&royalty);
+        self.default_royalty = royalty;
+    }
+
+    /// Sets the own royalty table of `token_id`, overriding the default one.
+    pub fn set_token_royalty(&mut self, token_id: TokenId, royalty: HashMap<AccountId, u32>) {
+        self.assert_valid_royalty(&royalty);
+        self.royalty_by_id.insert(token_id, royalty);
+    }
+
+    /// Removes the own royalty table of `token_id`, which then has the default one.
+    pub fn remove_token_royalty(&mut self, token_id: &TokenId) {
+        self.royalty_by_id.remove(token_id);
+    }
+
+    fn assert_valid_royalty(&self, royalty: &HashMap<AccountId, u32>) {
+        let total = royalty.values().try_fold(0u32, |total, bps| total.checked_add(*bps));
+        require!(
+            matches!(total, Some(total) if total <= self.max_total_bps),
+            format!("The total of the royalty exceeds {} basis points", self.max_total_bps)
+        );
+    }
+
+    /// Returns the payout of `balance` for `token_id` owned by `owner_id`: each royalty account
+    /// is paid its share, rounded down, and the owner the rest.
+    ///
+    /// Panics if the payout has more than `max_len_payout` accounts.
+    pub fn payout(
+        &self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        balance: u128,
+        max_len_payout: Option<u32>,
+    ) -> Payout {
+        let royalty = self.royalty_of(token_id);
+        let mut payout = HashMap::with_capacity(royalty.len() + 1);
+        let mut paid = 0;
+        for (account_id, bps) in royalty {
+            let bps = u128::from(*bps);
+            let basis = u128::from(MAX_BASIS_POINTS);
+            // Split to avoid overflowing `balance * bps`.
+            let amount = balance / basis * bps + balance % basis * bps / basis;
+            paid += amount;
+            payout.insert(account_id.clone(), U128(amount));
+        }
+        payout.entry(owner_id.clone()).or_insert(U128(0)).0 += balance - paid;
+        if let Some(max_len_payout) = max_len_payout {
+            require!(
+                payout.len() <= max_len_payout as usize,
+                format!("The payout has more than {} accounts", max_len_payout)
+            );
+        }
+        Payout { payout }
+    }
+
+    /// Implementation of [`NonFungibleTokenPayout::nft_payout`](super::NonFungibleTokenPayout)
+    /// for the tokens of `token`.
+    pub fn nft_payout(
+        &self,
+        token: &NonFungibleToken,
+        token_id: TokenId,
+        balance: U128,
+        max_len_payout: Option<u32>,
+    ) -> Payout {
+        let owner_id =
+            token.owner_by_id.get(&token_id).unwrap_or_else(|| env::panic_str("Token not found"));
+        self.payout(&token_id, &owner_id, balance.0, max_len_payout)
+    }
+
+    /// Implementation of
+    /// [`NonFungibleTokenPayout::nft_transfer_payout`](super::NonFungibleTokenPayout) for the
+    /// tokens of `token`. The approvals of the token are cleared by the transfer, and their
+    /// storage refunded to the previous owner.
+    #[allow(clippy::too_many_arguments)]
+    pub fn nft_transfer_payout(
+        &self,
+        token: &mut NonFungibleToken,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        balance: U128,
+        max_len_payout: Option<u32>,
+    ) -> Payout {
+        assert_one_yocto();
+        assert_transferable(token.is_non_transferable());
+        let sender_id = env::predecessor_account_id();
+        let (previous_owner_id, approved_account_ids) =
+            token.internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, memo);
+        let payout = self.payout(&token_id, &previous_owner_id, balance.0, max_len_payout);
+        if let Some(approved_account_ids) =
+            approved_account_ids.filter(|approved_account_ids| !approved_account_ids.is_empty())
+        {
+            refund_approved_account_ids(previous_owner_id, &approved_account_ids);
+        }
+        payout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{alice, bob, charlie, danny};
+    use near_sdk::test_utils::accounts;
+    use near_sdk::testing_env;
+    use near_sdk::NearToken;
+
+    fn royalty(entries: &[(&AccountId, u32)]) -> HashMap<AccountId, u32> {
+        entries.iter().map(|(account_id, bps)| ((*account_id).clone(), *bps)).collect()
+    }
+
+    fn payout(entries: &[(&AccountId, u128)]) -> Payout {
+        Payout {
+            payout: entries
+                .iter()
+                .map(|(account_id, amount)| ((*account_id).clone(), U128(*amount)))
+                .collect(),
+        }
+    }
+
+    /// Alice owns the token "0", with a default royalty of 10% to charlie.
+    fn setup() -> (NonFungibleToken, Royalties) {
+        let alice = alice().with_nft_tokens(["0"]);
+        alice.set_as_caller();
+        let mut token =
+            NonFungibleToken::new(b"n", accounts(0), Some(b"m"), None::<Vec<u8>>, Some(b"a"));
+        alice.setup_nft(&mut token);
+        let mut royalties = Royalties::new(b"r", 5_000);
+        royalties.set_default_royalty(royalty(&[(charlie().account_id(), 1_000)]));
+        (token, royalties)
+    }
+
+    #[test]
+    fn default_and_token_royalties() {
+        let (token, mut royalties) = setup();
+        let (alice, charlie, danny) = (alice(), charlie(), danny());
+        assert_eq!(
+            royalties.nft_payout(&token, "0".into(), U128(1_000), None),
+            payout(&[(alice.account_id(), 900), (charlie.account_id(), 100)])
+        );
+
+        royalties.set_token_royalty(
+            "0".into(),
+            royalty(&[(danny.account_id(), 2_500), (alice.account_id(), 1_000)]),
+        );
+        assert_eq!(
+            royalties.nft_payout(&token, "0".into(), U128(1_000), Some(2)),
+            payout(&[(alice.account_id(), 750), (danny.account_id(), 250)])
+        );
+
+        royalties.remove_token_royalty(&"0".to_string());
+        assert_eq!(royalties.royalty_of(&"0".to_string()), &royalties.default_royalty);
+    }
+
+    #[test]
+    fn payout_without_overflow() {
+        let (_, royalties) = setup();
+        let payout = royalties.payout(&"0".into(), alice().account_id(), u128::MAX, None);
+        assert_eq!(payout.payout[charlie().account_id()], U128(u128::MAX / 10));
+        assert_eq!(payout.payout[alice().account_id()], U128(u128::MAX - u128::MAX / 10));
+    }
+
+    #[test]
+    #[should_panic(expected = "The payout has more than 1 accounts")]
+    fn payout_too_long() {
+        let (token, royalties) = setup();
+        royalties.nft_payout(&token, "0".into(), U128(1_000), Some(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "The total of the royalty exceeds 5000 basis points")]
+    fn royalty_over_max_total() {
+        let (_, mut royalties) = setup();
+        royalties.set_token_royalty(
+            "0".into(),
+            royalty(&[(charlie().account_id(), 3_000), (danny().account_id(), 2_001)]),
+        );
+    }
+
+    #[test]
+    fn transfer_payout_by_approved_account() {
+        let (mut token, royalties) = setup();
+        let (alice, bob, charlie) = (alice(), bob(), charlie());
+        token
+            .approvals_by_id
+            .as_mut()
+            .unwrap()
+            .insert(&"0".to_string(), &HashMap::from([(bob.account_id().clone(), 1)]));
+
+        testing_env!(bob.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        let result = royalties.nft_transfer_payout(
+            &mut token,
+            bob.account_id().clone(),
+            "0".into(),
+            Some(1),
+            None,
+            U128(1_000),
+            None,
+        );
+        assert_eq!(result, payout(&[(alice.account_id(), 900), (charlie.account_id(), 100)]));
+        assert_eq!(token.owner_by_id.get(&"0".to_string()).as_ref(), Some(bob.account_id()));
+        assert!(token.approvals_by_id.as_ref().unwrap().get(&"0".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Sender not approved")]
+    fn transfer_payout_by_unapproved_account() {
+        let (mut token, royalties) = setup();
+        testing_env!(bob().context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        royalties.nft_transfer_payout(
+            &mut token,
+            bob().account_id().clone(),
+            "0".into(),
+            None,
+            None,
+            U128(1_000),
+            None,
+        );
+    }
+}
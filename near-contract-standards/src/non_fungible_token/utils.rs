@@ -1,4 +1,4 @@
-use near_sdk::{env, require, AccountId, NearToken, Promise};
+use near_sdk::{env, require, AccountId, NearToken, Promise, TokenFormat};
 use std::collections::HashMap;
 use std::mem::size_of;
 
@@ -33,7 +33,7 @@ pub fn refund_deposit_to_account(storage_used: u64, account_id: AccountId) {
 
     require!(
         required_cost <= attached_deposit,
-        format!("Must attach {} to cover storage", required_cost.exact_amount_display())
+        format!("Must attach {} to cover storage", TokenFormat::new(required_cost).round_up())
     );
 
     let refund = attached_deposit.saturating_sub(required_cost);
@@ -0,0 +1,85 @@
+//! Non-transferable ("soulbound") tokens, for credentials or reputation which stay with the
+//! account they are minted to.
+//!
+//! Both [`FungibleToken`](crate::fungible_token::FungibleToken) and
+//! [`NonFungibleToken`](crate::non_fungible_token::NonFungibleToken) have a `non_transferable`
+//! option, set with their `set_non_transferable` method. While it is set, the transfer methods
+//! implementing the standards panic, including the batched, policy and payout variants, while
+//! minting, burning and the views are unchanged. The `internal_*` transfer methods are not
+//! restricted, so that the contract can still move tokens itself, e.g. to recover them.
+//!
+//! The option is stored under its own storage key rather than in the serialized state of the
+//! token, so that it can be set on the tokens of contracts already deployed. Every change of the
+//! option is logged as a [`NonTransferableEvent`], and the contract can return its metadata as a
+//! [`NonTransferableMetadata`] to reflect it in its metadata view.
+
+use near_sdk::{near, require};
+
+/// Changes of the `non_transferable` option of a token.
+#[near(event_json(standard = "non_transferable"))]
+pub enum NonTransferableEvent<'a> {
+    /// The transfers of the token implementing `standard`, e.g. `nep141`, were disabled.
+    #[event_version("1.0.0")]
+    TransfersDisabled { standard: &'a str },
+    /// The transfers of the token implementing `standard` were enabled again.
+    #[event_version("1.0.0")]
+    TransfersEnabled { standard: &'a str },
+}
+
+impl<'a> NonTransferableEvent<'a> {
+    /// Returns the event of setting the `non_transferable` option of `standard`.
+    pub(crate) fn new(standard: &'a str, non_transferable: bool) -> Self {
+        if non_transferable {
+            NonTransferableEvent::TransfersDisabled { standard }
+        } else {
+            NonTransferableEvent::TransfersEnabled { standard }
+        }
+    }
+}
+
+/// Metadata of a token along with whether it is non-transferable, e.g.
+/// `NonTransferableMetadata<FungibleTokenMetadata>` returned by the `ft_metadata` view of a
+/// contract instead of the metadata alone.
+///
+/// It is serialized to JSON as the fields of the metadata with an additional `non_transferable`
+/// field, which is omitted when the token is transferable.
+///
+/// # Examples
+///
+/// ```
+/// use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+/// use near_contract_standards::fungible_token::FungibleToken;
+/// use near_contract_standards::non_transferable::NonTransferableMetadata;
+/// use near_sdk::{near, PanicOnDefault};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     token: FungibleToken,
+///     metadata: FungibleTokenMetadata,
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     pub fn ft_metadata(&self) -> NonTransferableMetadata<FungibleTokenMetadata> {
+///         NonTransferableMetadata {
+///             metadata: self.metadata.clone(),
+///             non_transferable: self.token.is_non_transferable(),
+///         }
+///     }
+/// }
+/// ```
+#[near(serializers=[json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NonTransferableMetadata<M> {
+    /// Metadata of the token.
+    #[serde(flatten)]
+    pub metadata: M,
+    /// Whether the token is non-transferable, see the `is_non_transferable` method of the token.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub non_transferable: bool,
+}
+
+pub(crate) fn assert_transferable(non_transferable: bool) {
+    require!(!non_transferable, "The token is non-transferable");
+}
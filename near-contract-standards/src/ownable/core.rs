@@ -0,0 +1,58 @@
+use near_sdk::{ext_contract, AccountId};
+
+/// The contract methods of an ownable contract.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{near, AccountId, PanicOnDefault};
+/// use near_contract_standards::ownable::{Ownable, OwnableCore};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     ownable: Ownable,
+/// }
+///
+/// #[near]
+/// impl OwnableCore for Contract {
+///     fn own_get_owner(&self) -> Option<AccountId> {
+///         self.ownable.owner_id().cloned()
+///     }
+///
+///     fn own_get_proposed_owner(&self) -> Option<AccountId> {
+///         self.ownable.proposed_owner_id().cloned()
+///     }
+///
+///     fn own_propose_owner(&mut self, new_owner_id: Option<AccountId>) {
+///         self.ownable.propose_owner(new_owner_id);
+///     }
+///
+///     fn own_accept_owner(&mut self) {
+///         self.ownable.accept_owner();
+///     }
+///
+///     fn own_renounce_owner(&mut self) {
+///         self.ownable.renounce_owner();
+///     }
+/// }
+/// ```
+#[ext_contract(ext_ownable)]
+pub trait OwnableCore {
+    /// Returns the owner of the contract, if it wasn't renounced.
+    fn own_get_owner(&self) -> Option<AccountId>;
+
+    /// Returns the account proposed as the new owner, if any.
+    fn own_get_proposed_owner(&self) -> Option<AccountId>;
+
+    /// Proposes `new_owner_id` as the new owner, or withdraws the current proposal with `None`.
+    /// Can only be called by the owner.
+    fn own_propose_owner(&mut self, new_owner_id: Option<AccountId>);
+
+    /// Accepts the ownership of the contract. Can only be called by the proposed owner.
+    fn own_accept_owner(&mut self);
+
+    /// Leaves the contract without an owner, which can't be undone. Can only be called by the
+    /// owner.
+    fn own_renounce_owner(&mut self);
+}
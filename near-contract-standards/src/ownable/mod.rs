@@ -0,0 +1,47 @@
+//! Ownable component keeping the owner of a contract, transferred in two steps.
+//!
+//! An [`Ownable`] stores the account owning the contract. The ownership is transferred by the
+//! owner proposing a new owner with [`Ownable::propose_owner`], and the proposed owner accepting
+//! it with [`Ownable::accept_owner`], so that a typo in the account ID of the new owner can't
+//! leave the contract without a reachable owner. Every change is logged as an [`OwnableEvent`].
+//!
+//! Methods of the contract are restricted to the owner with `#[only_owner]`, which checks the
+//! predecessor through the [`HasRoles`](near_sdk::HasRoles) implementation of the contract,
+//! delegating to its [`Ownable`]:
+//!
+//! ```
+//! use near_sdk::{near, AccountId, HasRoles, PanicOnDefault};
+//! use near_contract_standards::ownable::Ownable;
+//!
+//! #[near(contract_state)]
+//! #[derive(PanicOnDefault)]
+//! pub struct Contract {
+//!     ownable: Ownable,
+//!     paused: bool,
+//! }
+//!
+//! impl HasRoles for Contract {
+//!     fn has_role(&self, role: &str, account_id: &AccountId) -> bool {
+//!         self.ownable.has_role(role, account_id)
+//!     }
+//! }
+//!
+//! #[near]
+//! impl Contract {
+//!     #[init]
+//!     pub fn new(owner_id: AccountId) -> Self {
+//!         Self { ownable: Ownable::new(owner_id), paused: false }
+//!     }
+//!
+//!     #[only_owner]
+//!     pub fn pause(&mut self) {
+//!         self.paused = true;
+//!     }
+//! }
+//! ```
+
+mod core;
+mod ownable_impl;
+
+pub use self::core::{ext_ownable, OwnableCore};
+pub use ownable_impl::{Ownable, OwnableEvent};
@@ -0,0 +1,156 @@
+use near_sdk::{env, near, require, AccountId, HasRoles, OWNER_ROLE};
+
+/// Changes of the owner of an [`Ownable`].
+#[near(event_json(standard = "ownable"))]
+pub enum OwnableEvent<'a> {
+    /// `owner_id` proposed `proposed_owner_id` as the new owner, or withdrew the proposal.
+    #[event_version("1.0.0")]
+    OwnerProposed { owner_id: &'a AccountId, proposed_owner_id: Option<&'a AccountId> },
+    /// The ownership was transferred from `previous_owner_id` to `owner_id`, or renounced.
+    #[event_version("1.0.0")]
+    OwnerChanged { previous_owner_id: &'a AccountId, owner_id: Option<&'a AccountId> },
+}
+
+/// Implementation of the ownership of a contract with a two-step transfer.
+#[near]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ownable {
+    /// Owner of the contract, `None` once renounced.
+    pub owner_id: Option<AccountId>,
+    /// Account proposed as the new owner, which has to accept the ownership.
+    pub proposed_owner_id: Option<AccountId>,
+}
+
+impl Ownable {
+    pub fn new(owner_id: AccountId) -> Self {
+        Self { owner_id: Some(owner_id), proposed_owner_id: None }
+    }
+
+    /// Returns the owner, unless the ownership was renounced.
+    pub fn owner_id(&self) -> Option<&AccountId> {
+        self.owner_id.as_ref()
+    }
+
+    /// Returns the account proposed as the new owner, if any.
+    pub fn proposed_owner_id(&self) -> Option<&AccountId> {
+        self.proposed_owner_id.as_ref()
+    }
+
+    /// Whether `account_id` is the owner.
+    pub fn is_owner(&self, account_id: &AccountId) -> bool {
+        self.owner_id.as_ref() == Some(account_id)
+    }
+
+    /// Panics unless the predecessor is the owner.
+    pub fn assert_owner(&self) {
+        require!(self.is_owner(&env::predecessor_account_id()), "Owner must be predecessor");
+    }
+
+    /// Proposes `new_owner_id` as the new owner if the predecessor is the owner, replacing the
+    /// current proposal, or withdraws it with `None`.
+    pub fn propose_owner(&mut self, new_owner_id: Option<AccountId>) {
+        self.assert_owner();
+        self.proposed_owner_id = new_owner_id;
+        OwnableEvent::OwnerProposed {
+            owner_id: &env::predecessor_account_id(),
+            proposed_owner_id: self.proposed_owner_id.as_ref(),
+        }
+        .emit();
+    }
+
+    /// Transfers the ownership to the predecessor if it is the proposed owner.
+    pub fn accept_owner(&mut self) {
+        let predecessor_id = env::predecessor_account_id();
+        require!(
+            self.proposed_owner_id.as_ref() == Some(&predecessor_id),
+            "Only the proposed owner can accept the ownership"
+        );
+        self.proposed_owner_id = None;
+        let previous_owner_id = self.owner_id.replace(predecessor_id);
+        // The owner can't be proposed once renounced.
+        let previous_owner_id = previous_owner_id.unwrap_or_else(|| env::abort());
+        OwnableEvent::OwnerChanged {
+            previous_owner_id: &previous_owner_id,
+            owner_id: self.owner_id.as_ref(),
+        }
+        .emit();
+    }
+
+    /// Leaves the contract without an owner if the predecessor is the owner, which can't be
+    /// undone.
+    pub fn renounce_owner(&mut self) {
+        self.assert_owner();
+        self.proposed_owner_id = None;
+        if let Some(previous_owner_id) = self.owner_id.take() {
+            OwnableEvent::OwnerChanged { previous_owner_id: &previous_owner_id, owner_id: None }
+                .emit();
+        }
+    }
+}
+
+impl HasRoles for Ownable {
+    fn has_role(&self, role: &str, account_id: &AccountId) -> bool {
+        role == OWNER_ROLE && self.is_owner(account_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn set_caller(account_id: AccountId) {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(account_id).build());
+    }
+
+    #[test]
+    fn two_step_transfer() {
+        let mut ownable = Ownable::new(accounts(0));
+        set_caller(accounts(0));
+        ownable.propose_owner(Some(accounts(1)));
+        assert!(ownable.is_owner(&accounts(0)));
+        assert_eq!(ownable.proposed_owner_id(), Some(&accounts(1)));
+
+        set_caller(accounts(1));
+        ownable.accept_owner();
+        assert!(ownable.is_owner(&accounts(1)));
+        assert!(ownable.has_role(OWNER_ROLE, &accounts(1)));
+        assert_eq!(ownable.proposed_owner_id(), None);
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"ownable","version":"1.0.0","event":"owner_proposed","data":{"owner_id":"alice","proposed_owner_id":"bob"}}"#,
+                r#"EVENT_JSON:{"standard":"ownable","version":"1.0.0","event":"owner_changed","data":{"previous_owner_id":"alice","owner_id":"bob"}}"#,
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the proposed owner can accept the ownership")]
+    fn accept_without_proposal() {
+        let mut ownable = Ownable::new(accounts(0));
+        set_caller(accounts(0));
+        ownable.propose_owner(Some(accounts(1)));
+        set_caller(accounts(2));
+        ownable.accept_owner();
+    }
+
+    #[test]
+    #[should_panic(expected = "Owner must be predecessor")]
+    fn propose_by_other_account() {
+        let mut ownable = Ownable::new(accounts(0));
+        set_caller(accounts(1));
+        ownable.propose_owner(Some(accounts(1)));
+    }
+
+    #[test]
+    fn renounce() {
+        let mut ownable = Ownable::new(accounts(0));
+        set_caller(accounts(0));
+        ownable.propose_owner(Some(accounts(1)));
+        ownable.renounce_owner();
+        assert_eq!(ownable.owner_id(), None);
+        assert_eq!(ownable.proposed_owner_id(), None);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::registry::{NameInfo, NameStatus};
+use near_sdk::json_types::Base58CryptoHash;
+use near_sdk::{ext_contract, AccountId};
+
+/// The contract methods of a name registry.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{env, near, AccountId, PanicOnDefault, Promise};
+/// use near_sdk::json_types::Base58CryptoHash;
+/// use near_contract_standards::registry::{NameInfo, NameStatus, Registry, RegistryCore};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     registry: Registry,
+/// }
+///
+/// #[near]
+/// impl RegistryCore for Contract {
+///     #[payable]
+///     fn registry_commit(&mut self, commitment: Base58CryptoHash) {
+///         let account_id = env::predecessor_account_id();
+///         let excess =
+///             self.registry.internal_commit(commitment.into(), &account_id, env::attached_deposit());
+///         if excess.as_yoctonear() > 0 {
+///             Promise::new(account_id).transfer(excess);
+///         }
+///     }
+///
+///     fn registry_remove_commitment(&mut self, commitment: Base58CryptoHash) {
+///         let removed = self.registry.internal_remove_expired_commitment(&commitment.into());
+///         if removed.deposit.as_yoctonear() > 0 {
+///             Promise::new(removed.account_id).transfer(removed.deposit);
+///         }
+///     }
+///
+///     #[payable]
+///     fn registry_register(&mut self, name: String, secret: String) {
+///         let owner_id = env::predecessor_account_id();
+///         let excess = self.registry.internal_register(
+///             &name,
+///             &owner_id,
+///             &secret,
+///             env::attached_deposit(),
+///         );
+///         if excess.as_yoctonear() > 0 {
+///             Promise::new(owner_id).transfer(excess);
+///         }
+///     }
+///
+///     #[payable]
+///     fn registry_renew(&mut self, name: String) {
+///         let excess = self.registry.internal_renew(&name, env::attached_deposit());
+///         if excess.as_yoctonear() > 0 {
+///             Promise::new(env::predecessor_account_id()).transfer(excess);
+///         }
+///     }
+///
+///     fn registry_transfer(&mut self, name: String, new_owner_id: AccountId) {
+///         self.registry.internal_transfer(&name, &env::predecessor_account_id(), new_owner_id);
+///     }
+///
+///     #[payable]
+///     fn registry_set_record(&mut self, name: String, key: String, value: Option<String>) {
+///         let owner_id = env::predecessor_account_id();
+///         let (_, refund) = self.registry.internal_set_record(
+///             &name,
+///             &owner_id,
+///             key,
+///             value,
+///             env::attached_deposit(),
+///         );
+///         if refund.as_yoctonear() > 0 {
+///             Promise::new(owner_id).transfer(refund);
+///         }
+///     }
+///
+///     fn registry_commitment(
+///         &self,
+///         name: String,
+///         owner_id: AccountId,
+///         secret: String,
+///     ) -> Base58CryptoHash {
+///         Registry::commitment(&name, &owner_id, &secret).into()
+///     }
+///
+///     fn registry_status(&self, name: String) -> NameStatus {
+///         self.registry.status(&name)
+///     }
+///
+///     fn registry_name(&self, name: String) -> Option<NameInfo> {
+///         self.registry.name_info(&name)
+///     }
+/// }
+/// ```
+#[ext_contract(ext_registry)]
+pub trait RegistryCore {
+    /// Commits to registering a name. The commitment is the hash returned by
+    /// [`registry_commitment`](Self::registry_commitment) and can be revealed through
+    /// [`registry_register`](Self::registry_register) once it is old enough. The attached
+    /// deposit must cover the storage of the commitment, which is refunded with the registration,
+    /// and any remaining deposit is refunded.
+    fn registry_commit(&mut self, commitment: Base58CryptoHash);
+
+    /// Removes an expired commitment and refunds the deposit paid for its storage to the account
+    /// which made it. Can be called by anyone.
+    fn registry_remove_commitment(&mut self, commitment: Base58CryptoHash);
+
+    /// Registers `name` for the predecessor by revealing the `secret` of an earlier commitment.
+    /// The attached deposit must cover the registration fee, any remaining deposit is refunded.
+    fn registry_register(&mut self, name: String, secret: String);
+
+    /// Extends the registration of `name` by one registration period. Available while the name
+    /// is active or in grace. Any remaining deposit is refunded.
+    fn registry_renew(&mut self, name: String);
+
+    /// Transfers `name` owned by the predecessor to `new_owner_id`.
+    fn registry_transfer(&mut self, name: String, new_owner_id: AccountId);
+
+    /// Sets the record `key` of `name` owned by the predecessor, or removes it if `value` is
+    /// `None`. The attached deposit must cover the storage added by the record, and the remaining
+    /// deposit is refunded along with the storage released.
+    fn registry_set_record(&mut self, name: String, key: String, value: Option<String>);
+
+    /// Returns the commitment to register `name` for `owner_id` with `secret`. The secret should
+    /// be random and is only revealed at registration.
+    fn registry_commitment(
+        &self,
+        name: String,
+        owner_id: AccountId,
+        secret: String,
+    ) -> Base58CryptoHash;
+
+    /// Returns the current state of `name`.
+    fn registry_status(&self, name: String) -> NameStatus;
+
+    /// Returns the registration of `name`, unless the name is available.
+    fn registry_name(&self, name: String) -> Option<NameInfo>;
+}
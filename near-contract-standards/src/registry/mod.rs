@@ -0,0 +1,23 @@
+//! Registry component for names that are owned by accounts for a limited time.
+//!
+//! A [`Registry`] maps names to owners and a set of text records. It can back naming services,
+//! handle registries or any app-level namespace. Names go through the following states (see
+//! [`NameStatus`]):
+//!
+//! - available, until someone registers the name by paying the registration fee,
+//! - active, until the registration period ends. The owner can transfer the name and set
+//!   records, and anyone can renew the name by paying the fee again,
+//! - in grace, for a configured period after expiry, where only renewals are accepted,
+//! - and available again once the grace period is over.
+//!
+//! To prevent front-running of registrations, names are registered through a commit-reveal
+//! scheme: the registrant first commits to a hash of the name, their account and a secret (see
+//! [`Registry::commitment`]), and reveals them in the registration once the commitment is old
+//! enough. The commitment is removed when it is revealed, or by anyone once it has expired, and
+//! the deposit paid for its storage is refunded.
+
+mod core;
+mod registry_impl;
+
+pub use self::core::{ext_registry, RegistryCore};
+pub use registry_impl::{Commitment, NameInfo, NameStatus, Registry, RegistryConfig};
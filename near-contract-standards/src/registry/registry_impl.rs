@@ -0,0 +1,523 @@
+use std::collections::BTreeMap;
+
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U64;
+use near_sdk::{env, near, require, AccountId, CryptoHash, IntoStorageKey, NearToken, TokenFormat};
+
+const ERR_NOT_OWNER: &str = "Only the owner of the name can do this";
+const ERR_NOT_ACTIVE: &str = "The name is not registered or has expired";
+
+/// Configuration of a [`Registry`].
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegistryConfig {
+    /// Deposit required to register or renew a name for one registration period.
+    pub fee: NearToken,
+    /// Duration of a registration or renewal in nanoseconds.
+    pub registration_period: U64,
+    /// Duration in nanoseconds after expiry during which the name can still be renewed, but not
+    /// registered by anyone else.
+    pub grace_period: U64,
+    /// Minimum age of a commitment in nanoseconds before it can be revealed.
+    pub min_commitment_age: U64,
+    /// Maximum age of a commitment in nanoseconds, after which it can no longer be revealed.
+    pub max_commitment_age: U64,
+    /// Minimum length of a name.
+    pub min_name_length: u32,
+    /// Maximum length of a name.
+    pub max_name_length: u32,
+}
+
+/// State of a name in a [`Registry`], derived from the block timestamp.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameStatus {
+    /// The name can be registered.
+    Available,
+    /// The name is owned and its records resolve.
+    Active,
+    /// The registration has expired, but the name can still be renewed.
+    Grace,
+}
+
+/// Commitment to register a name, see [`Registry::commitment`].
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commitment {
+    /// Account which made the commitment.
+    pub account_id: AccountId,
+    /// Block timestamp in nanoseconds at which the commitment was made.
+    pub committed_at: U64,
+    /// Deposit paid for the storage of the commitment, refunded when it is removed.
+    pub deposit: NearToken,
+}
+
+/// Registration of a name.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NameInfo {
+    pub owner_id: AccountId,
+    /// Block timestamp in nanoseconds at which the registration expires.
+    pub expires_at: U64,
+    /// Text records set by the owner.
+    pub records: BTreeMap<String, String>,
+}
+
+/// Implementation of a name registry with expiring registrations and commit-reveal
+/// registration.
+///
+/// The registry only keeps track of names and fees, transferring NEAR is left to the contract
+/// (see [`RegistryCore`](crate::registry::RegistryCore)). Registration fees are kept by the
+/// contract and should cover the storage of the name. The storage of the commitments and of the
+/// records is paid by the accounts setting them, per byte, and refunded when it is released.
+#[near]
+pub struct Registry {
+    pub config: RegistryConfig,
+    /// Name -> Registration of the name. Expired registrations are replaced on registration.
+    pub names: LookupMap<String, NameInfo>,
+    /// Commitment hash -> The commitment, until it is revealed or removed after expiring.
+    pub commitments: LookupMap<CryptoHash, Commitment>,
+}
+
+impl Registry {
+    pub fn new<S>(prefix: S, config: RegistryConfig) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        require!(
+            config.min_commitment_age.0 < config.max_commitment_age.0,
+            "Commitments must be revealable for a non-empty period"
+        );
+        require!(
+            0 < config.min_name_length && config.min_name_length <= config.max_name_length,
+            "Invalid name length bounds"
+        );
+        let prefix = prefix.into_storage_key();
+        Self {
+            config,
+            names: LookupMap::new([prefix.as_slice(), b"n"].concat()),
+            commitments: LookupMap::new([prefix.as_slice(), b"c"].concat()),
+        }
+    }
+
+    /// Returns the commitment to register `name` for `owner_id`, revealed with `secret`.
+    pub fn commitment(name: &str, owner_id: &AccountId, secret: &str) -> CryptoHash {
+        let data =
+            near_sdk::borsh::to_vec(&(name, owner_id, secret)).unwrap_or_else(|_| env::abort());
+        env::sha256_array(&data).into()
+    }
+
+    /// Returns the current state of `name`.
+    pub fn status(&self, name: &str) -> NameStatus {
+        match self.names.get(&name.to_string()) {
+            Some(info) => self.status_of(&info),
+            None => NameStatus::Available,
+        }
+    }
+
+    fn status_of(&self, info: &NameInfo) -> NameStatus {
+        let now = env::block_timestamp();
+        if now < info.expires_at.0 {
+            NameStatus::Active
+        } else if now - info.expires_at.0 < self.config.grace_period.0 {
+            NameStatus::Grace
+        } else {
+            NameStatus::Available
+        }
+    }
+
+    /// Returns the registration of `name`, unless the name is available.
+    pub fn name_info(&self, name: &str) -> Option<NameInfo> {
+        self.names
+            .get(&name.to_string())
+            .filter(|info| self.status_of(info) != NameStatus::Available)
+    }
+
+    /// Returns the owner of `name` while the registration is active.
+    pub fn owner_of(&self, name: &str) -> Option<AccountId> {
+        self.active_name(name).map(|info| info.owner_id)
+    }
+
+    /// Returns the record `key` of `name` while the registration is active.
+    pub fn record(&self, name: &str, key: &str) -> Option<String> {
+        self.active_name(name).and_then(|mut info| info.records.remove(key))
+    }
+
+    fn active_name(&self, name: &str) -> Option<NameInfo> {
+        self.names.get(&name.to_string()).filter(|info| self.status_of(info) == NameStatus::Active)
+    }
+
+    /// Panics if `name` is not a valid name: lowercase alphanumeric characters, `-` and `_`,
+    /// with a length within the configured bounds.
+    pub fn assert_valid_name(&self, name: &str) {
+        require!(
+            (self.config.min_name_length as usize..=self.config.max_name_length as usize)
+                .contains(&name.len()),
+            "Invalid name length"
+        );
+        require!(
+            name.bytes().all(|c| matches!(c, b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_')),
+            "Names can only contain lowercase alphanumeric characters, '-' and '_'"
+        );
+    }
+
+    /// Records a commitment made by `account_id` and charges its storage from `deposit`. Returns
+    /// the part of the deposit that was not needed and should be refunded.
+    ///
+    /// A commitment can't be made again until it is revealed or removed, so commitments to the
+    /// same name and owner should use different secrets.
+    pub fn internal_commit(
+        &mut self,
+        commitment: CryptoHash,
+        account_id: &AccountId,
+        deposit: NearToken,
+    ) -> NearToken {
+        require!(!self.commitments.contains_key(&commitment), "The commitment already exists");
+        let initial_storage_usage = env::storage_usage();
+        let mut entry = Commitment {
+            account_id: account_id.clone(),
+            committed_at: U64(env::block_timestamp()),
+            deposit: NearToken::from_yoctonear(0),
+        };
+        // The deposit has a fixed size, so the storage is measured with a zero one.
+        self.commitments.insert(&commitment, &entry);
+        entry.deposit = storage_cost(initial_storage_usage);
+        self.commitments.insert(&commitment, &entry);
+        charge(deposit, entry.deposit, "to cover the storage of the commitment")
+    }
+
+    /// Removes `commitment` once it has expired. Returns the commitment, whose deposit should be
+    /// refunded to the account which made it.
+    pub fn internal_remove_expired_commitment(&mut self, commitment: &CryptoHash) -> Commitment {
+        let entry = self
+            .commitments
+            .get(commitment)
+            .unwrap_or_else(|| env::panic_str("No commitment found"));
+        require!(
+            env::block_timestamp() - entry.committed_at.0 > self.config.max_commitment_age.0,
+            "The commitment has not expired"
+        );
+        self.commitments.remove(commitment);
+        entry
+    }
+
+    /// Registers `name` for `owner_id` by revealing the commitment made earlier, and charges the
+    /// registration fee from `deposit`. Returns the part of the deposit that was not needed and
+    /// should be refunded, which includes the deposit of the commitment.
+    pub fn internal_register(
+        &mut self,
+        name: &str,
+        owner_id: &AccountId,
+        secret: &str,
+        deposit: NearToken,
+    ) -> NearToken {
+        self.assert_valid_name(name);
+        let commitment = Self::commitment(name, owner_id, secret);
+        let entry = self
+            .commitments
+            .remove(&commitment)
+            .unwrap_or_else(|| env::panic_str("No commitment found for the registration"));
+        let age = env::block_timestamp() - entry.committed_at.0;
+        require!(age >= self.config.min_commitment_age.0, "The commitment is too recent");
+        require!(age <= self.config.max_commitment_age.0, "The commitment has expired");
+        require!(self.status(name) == NameStatus::Available, "The name is not available");
+        let excess = self.charge_fee(deposit);
+
+        let info = NameInfo {
+            owner_id: owner_id.clone(),
+            expires_at: self.extend(env::block_timestamp()),
+            records: BTreeMap::new(),
+        };
+        self.names.insert(&name.to_string(), &info);
+        excess.saturating_add(entry.deposit)
+    }
+
+    /// Extends the registration of `name` by one registration period and charges the fee from
+    /// `deposit`. Returns the part of the deposit that was not needed and should be refunded.
+    ///
+    /// Anyone can renew a name, as long as it is active or in grace.
+    pub fn internal_renew(&mut self, name: &str, deposit: NearToken) -> NearToken {
+        let mut info = self
+            .names
+            .get(&name.to_string())
+            .filter(|info| self.status_of(info) != NameStatus::Available)
+            .unwrap_or_else(|| env::panic_str("The name is not registered"));
+        let excess = self.charge_fee(deposit);
+        info.expires_at = self.extend(info.expires_at.0);
+        self.names.insert(&name.to_string(), &info);
+        excess
+    }
+
+    /// Transfers the active registration of `name` from `owner_id` to `new_owner_id`.
+    /// Records are kept.
+    pub fn internal_transfer(&mut self, name: &str, owner_id: &AccountId, new_owner_id: AccountId) {
+        let mut info = self.active_name(name).unwrap_or_else(|| env::panic_str(ERR_NOT_ACTIVE));
+        require!(&info.owner_id == owner_id, ERR_NOT_OWNER);
+        info.owner_id = new_owner_id;
+        self.names.insert(&name.to_string(), &info);
+    }
+
+    /// Sets the record `key` of the active registration of `name` to `value`, or removes it if
+    /// `value` is `None`, and charges the storage it uses from `deposit`. Returns the previous
+    /// value of the record, and the part of the deposit that was not needed along with the cost
+    /// of the storage released, which should be refunded.
+    ///
+    /// The records of a name are dropped without refund when the name is registered again after
+    /// expiring.
+    pub fn internal_set_record(
+        &mut self,
+        name: &str,
+        owner_id: &AccountId,
+        key: String,
+        value: Option<String>,
+        deposit: NearToken,
+    ) -> (Option<String>, NearToken) {
+        let mut info = self.active_name(name).unwrap_or_else(|| env::panic_str(ERR_NOT_ACTIVE));
+        require!(&info.owner_id == owner_id, ERR_NOT_OWNER);
+        let prev = match value {
+            Some(value) => info.records.insert(key, value),
+            None => info.records.remove(&key),
+        };
+        let initial_storage_usage = env::storage_usage();
+        self.names.insert(&name.to_string(), &info);
+        let refund = if env::storage_usage() >= initial_storage_usage {
+            let cost = storage_cost(initial_storage_usage);
+            charge(deposit, cost, "to cover the storage of the record")
+        } else {
+            let released = initial_storage_usage - env::storage_usage();
+            deposit.saturating_add(env::storage_byte_cost().saturating_mul(released.into()))
+        };
+        (prev, refund)
+    }
+
+    fn charge_fee(&self, deposit: NearToken) -> NearToken {
+        require!(
+            deposit >= self.config.fee,
+            format!(
+                "Must attach {} to register a name",
+                TokenFormat::new(self.config.fee).round_up()
+            )
+        );
+        deposit.saturating_sub(self.config.fee)
+    }
+
+    fn extend(&self, from: u64) -> U64 {
+        U64(from
+            .checked_add(self.config.registration_period.0)
+            .unwrap_or_else(|| env::panic_str("Expiry overflow")))
+    }
+}
+
+/// Returns the cost of the storage used since `initial_storage_usage`.
+fn storage_cost(initial_storage_usage: u64) -> NearToken {
+    let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+    env::storage_byte_cost().saturating_mul(storage_used.into())
+}
+
+/// Charges `cost` from `deposit`, returning the rest.
+fn charge(deposit: NearToken, cost: NearToken, purpose: &str) -> NearToken {
+    require!(
+        deposit >= cost,
+        format!("Must attach {} {}", TokenFormat::new(cost).round_up(), purpose)
+    );
+    deposit.saturating_sub(cost)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    const SECOND: u64 = 1_000_000_000;
+
+    fn config() -> RegistryConfig {
+        RegistryConfig {
+            fee: NearToken::from_near(1),
+            registration_period: U64(100 * SECOND),
+            grace_period: U64(10 * SECOND),
+            min_commitment_age: U64(SECOND),
+            max_commitment_age: U64(60 * SECOND),
+            min_name_length: 3,
+            max_name_length: 32,
+        }
+    }
+
+    fn set_time(seconds: u64) {
+        testing_env!(VMContextBuilder::new().block_timestamp(seconds * SECOND).build());
+    }
+
+    fn commit(registry: &mut Registry, name: &str, owner_id: &AccountId) -> CryptoHash {
+        let commitment = Registry::commitment(name, owner_id, "secret");
+        let excess = registry.internal_commit(commitment, owner_id, NearToken::from_near(1));
+        let deposit = registry.commitments.get(&commitment).unwrap().deposit;
+        assert!(deposit > NearToken::from_yoctonear(0));
+        assert_eq!(excess, NearToken::from_near(1).saturating_sub(deposit));
+        commitment
+    }
+
+    fn register(registry: &mut Registry, name: &str, owner_id: &AccountId, at: u64) {
+        set_time(at);
+        let commitment = commit(registry, name, owner_id);
+        let deposit = registry.commitments.get(&commitment).unwrap().deposit;
+        set_time(at + 1);
+        let excess = registry.internal_register(name, owner_id, "secret", NearToken::from_near(2));
+        assert_eq!(excess, NearToken::from_near(1).saturating_add(deposit));
+        assert!(registry.commitments.get(&commitment).is_none());
+    }
+
+    #[test]
+    fn test_lifecycle() {
+        let mut registry = Registry::new(b"r", config());
+        register(&mut registry, "alice", &accounts(0), 0);
+        assert_eq!(registry.owner_of("alice"), Some(accounts(0)));
+
+        registry.internal_set_record(
+            "alice",
+            &accounts(0),
+            "url".into(),
+            Some("x".into()),
+            NearToken::from_near(1),
+        );
+        registry.internal_transfer("alice", &accounts(0), accounts(1));
+        assert_eq!(registry.owner_of("alice"), Some(accounts(1)));
+        assert_eq!(registry.record("alice", "url"), Some("x".into()));
+
+        set_time(105);
+        assert_eq!(registry.status("alice"), NameStatus::Grace);
+        assert_eq!(registry.owner_of("alice"), None);
+        registry.internal_renew("alice", NearToken::from_near(1));
+        assert_eq!(registry.name_info("alice").unwrap().expires_at, U64(201 * SECOND));
+        assert_eq!(registry.status("alice"), NameStatus::Active);
+
+        set_time(211);
+        assert_eq!(registry.status("alice"), NameStatus::Available);
+        register(&mut registry, "alice", &accounts(2), 211);
+        let info = registry.name_info("alice").unwrap();
+        assert_eq!(info.owner_id, accounts(2));
+        assert!(info.records.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "The commitment is too recent")]
+    fn test_commitment_too_recent() {
+        let mut registry = Registry::new(b"r", config());
+        set_time(0);
+        commit(&mut registry, "alice", &accounts(0));
+        registry.internal_register("alice", &accounts(0), "secret", NearToken::from_near(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "No commitment found for the registration")]
+    fn test_commitment_for_other_owner() {
+        let mut registry = Registry::new(b"r", config());
+        set_time(0);
+        commit(&mut registry, "alice", &accounts(0));
+        set_time(2);
+        registry.internal_register("alice", &accounts(1), "secret", NearToken::from_near(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "The name is not available")]
+    fn test_register_taken_name() {
+        let mut registry = Registry::new(b"r", config());
+        register(&mut registry, "alice", &accounts(0), 0);
+        register(&mut registry, "alice", &accounts(1), 105);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner of the name can do this")]
+    fn test_set_record_not_owner() {
+        let mut registry = Registry::new(b"r", config());
+        register(&mut registry, "alice", &accounts(0), 0);
+        registry.internal_set_record(
+            "alice",
+            &accounts(1),
+            "url".into(),
+            None,
+            NearToken::from_yoctonear(0),
+        );
+    }
+
+    #[test]
+    fn test_records_storage() {
+        let mut registry = Registry::new(b"r", config());
+        register(&mut registry, "alice", &accounts(0), 0);
+        let deposit = NearToken::from_near(1);
+        let set = |registry: &mut Registry, value: Option<&str>| {
+            let value = value.map(str::to_string);
+            registry.internal_set_record("alice", &accounts(0), "url".into(), value, deposit)
+        };
+
+        let (prev, refund) = set(&mut registry, Some("https://example.com"));
+        assert_eq!(prev, None);
+        let cost = deposit.saturating_sub(refund);
+        assert!(cost > NearToken::from_yoctonear(0));
+
+        let (prev, refund) = set(&mut registry, Some("https://example.com/longer"));
+        assert_eq!(prev.as_deref(), Some("https://example.com"));
+        let longer_cost = deposit.saturating_sub(refund);
+        assert!(longer_cost > NearToken::from_yoctonear(0) && longer_cost < cost);
+
+        let (_, refund) = set(&mut registry, None);
+        assert_eq!(refund, deposit.saturating_add(cost).saturating_add(longer_cost));
+    }
+
+    #[test]
+    #[should_panic(expected = "to cover the storage of the record")]
+    fn test_record_without_deposit() {
+        let mut registry = Registry::new(b"r", config());
+        register(&mut registry, "alice", &accounts(0), 0);
+        registry.internal_set_record(
+            "alice",
+            &accounts(0),
+            "url".into(),
+            Some("x".repeat(100)),
+            NearToken::from_yoctonear(0),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "to cover the storage of the commitment")]
+    fn test_commit_without_deposit() {
+        let mut registry = Registry::new(b"r", config());
+        set_time(0);
+        let commitment = Registry::commitment("alice", &accounts(0), "secret");
+        registry.internal_commit(commitment, &accounts(0), NearToken::from_yoctonear(0));
+    }
+
+    #[test]
+    fn test_remove_expired_commitment() {
+        let mut registry = Registry::new(b"r", config());
+        set_time(0);
+        let commitment = commit(&mut registry, "alice", &accounts(1));
+        let entry = registry.commitments.get(&commitment).unwrap();
+
+        set_time(61);
+        assert_eq!(registry.internal_remove_expired_commitment(&commitment), entry);
+        assert_eq!(entry.account_id, accounts(1));
+        assert!(registry.commitments.get(&commitment).is_none());
+        commit(&mut registry, "alice", &accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "The commitment has not expired")]
+    fn test_remove_pending_commitment() {
+        let mut registry = Registry::new(b"r", config());
+        set_time(0);
+        let commitment = commit(&mut registry, "alice", &accounts(0));
+        set_time(60);
+        registry.internal_remove_expired_commitment(&commitment);
+    }
+
+    #[test]
+    #[should_panic(expected = "The commitment already exists")]
+    fn test_commit_twice() {
+        let mut registry = Registry::new(b"r", config());
+        set_time(0);
+        commit(&mut registry, "alice", &accounts(0));
+        set_time(61);
+        commit(&mut registry, "alice", &accounts(0));
+    }
+}
@@ -0,0 +1,75 @@
+use crate::sale::{Purchase, SaleInfo};
+use near_sdk::json_types::U128;
+use near_sdk::{ext_contract, AccountId, NearToken, Promise};
+
+/// The contract methods of a token sale.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{env, near, AccountId, NearToken, PanicOnDefault, Promise};
+/// use near_sdk::json_types::U128;
+/// use near_contract_standards::access_control::AccessControl;
+/// use near_contract_standards::fungible_token::FungibleToken;
+/// use near_contract_standards::sale::{Purchase, SaleInfo, TokenSale, TokenSaleCore};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     acl: AccessControl,
+///     token: FungibleToken,
+///     sale: TokenSale,
+/// }
+///
+/// #[near]
+/// impl TokenSaleCore for Contract {
+///     #[payable]
+///     fn sale_buy(&mut self, amount: U128) {
+///         let buyer = env::predecessor_account_id();
+///         let deposit = env::attached_deposit();
+///         let excess =
+///             self.sale.internal_buy(&self.acl, &mut self.token, &buyer, amount.0, deposit);
+///         if excess.as_yoctonear() > 0 {
+///             Promise::new(buyer).transfer(excess);
+///         }
+///     }
+///
+///     fn sale_refund(&mut self) -> Promise {
+///         let account_id = env::predecessor_account_id();
+///         let paid = self.sale.internal_refund(&mut self.token, &account_id);
+///         Promise::new(account_id).transfer(paid)
+///     }
+///
+///     fn sale_info(&self) -> SaleInfo {
+///         self.sale.info()
+///     }
+///
+///     fn sale_price(&self, amount: U128) -> NearToken {
+///         self.sale.price_of(amount.0)
+///     }
+///
+///     fn sale_purchase_of(&self, account_id: AccountId) -> Option<Purchase> {
+///         self.sale.purchases.get(&account_id)
+///     }
+/// }
+/// ```
+#[ext_contract(ext_token_sale)]
+pub trait TokenSaleCore {
+    /// Buys `amount` tokens for the predecessor at the current price of the sale.
+    /// The attached deposit must cover [`sale_price`](Self::sale_price) of `amount`, any
+    /// remaining deposit is refunded. The predecessor must be registered with the token.
+    fn sale_buy(&mut self, amount: U128);
+
+    /// Burns the tokens bought by the predecessor and returns the deposit they paid.
+    /// Only available once the sale has ended without reaching its soft cap.
+    fn sale_refund(&mut self) -> Promise;
+
+    /// Returns the configuration and the current progress of the sale.
+    fn sale_info(&self) -> SaleInfo;
+
+    /// Returns the deposit required to buy `amount` tokens at the current price of the sale.
+    fn sale_price(&self, amount: U128) -> NearToken;
+
+    /// Returns the tokens bought and the deposit paid by `account_id`, if any.
+    fn sale_purchase_of(&self, account_id: AccountId) -> Option<Purchase>;
+}
@@ -0,0 +1,66 @@
+use crate::fungible_token::Balance;
+use near_sdk::json_types::U128;
+use near_sdk::math::{mul_div, Rounding};
+use near_sdk::near;
+
+/// Price curve of a [`TokenSale`](crate::sale::TokenSale).
+///
+/// Prices are denominated in yoctoNEAR per whole token, that is per `token_unit` of the smallest
+/// token denomination (see [`SaleConfig::token_unit`](crate::sale::SaleConfig::token_unit)).
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PricingCurve {
+    /// Every token is sold for the same `price`.
+    Fixed { price: U128 },
+    /// The price starts at `base_price` and grows by `slope` with every whole token sold.
+    Linear { base_price: U128, slope: U128 },
+}
+
+impl PricingCurve {
+    /// Returns the price in yoctoNEAR of buying `amount` tokens after `sold` tokens have already
+    /// been sold, rounded up. Returns `None` if the price overflows.
+    pub fn cost(&self, token_unit: Balance, sold: Balance, amount: Balance) -> Option<Balance> {
+        match self {
+            Self::Fixed { price } => mul_div(amount, price.0, token_unit, Rounding::Up),
+            Self::Linear { base_price, slope } => {
+                // The area under the curve between `sold` and `sold + amount`, which is `amount`
+                // times the price in the middle of that interval.
+                let midpoint = sold.checked_mul(2)?.checked_add(amount)?;
+                let increase =
+                    mul_div(slope.0, midpoint, token_unit.checked_mul(2)?, Rounding::Down)?;
+                mul_div(amount, base_price.0.checked_add(increase)?, token_unit, Rounding::Up)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_TOKEN: Balance = 10u128.pow(24);
+    const ONE_NEAR: Balance = 10u128.pow(24);
+
+    #[test]
+    fn fixed_cost() {
+        let curve = PricingCurve::Fixed { price: U128(ONE_NEAR / 10) };
+        assert_eq!(curve.cost(ONE_TOKEN, 0, 5 * ONE_TOKEN), Some(ONE_NEAR / 2));
+        assert_eq!(curve.cost(ONE_TOKEN, 1_000 * ONE_TOKEN, 5 * ONE_TOKEN), Some(ONE_NEAR / 2));
+        // Fractions of a yoctoNEAR are rounded up.
+        assert_eq!(curve.cost(ONE_TOKEN, 0, 1), Some(1));
+    }
+
+    #[test]
+    fn linear_cost() {
+        let curve = PricingCurve::Linear { base_price: U128(10), slope: U128(2) };
+        assert_eq!(curve.cost(100, 0, 100), Some(11));
+        assert_eq!(curve.cost(100, 100, 100), Some(13));
+        assert_eq!(curve.cost(100, 0, 200), Some(24));
+
+        let curve = PricingCurve::Linear { base_price: U128(ONE_NEAR), slope: U128(ONE_NEAR) };
+        assert_eq!(
+            curve.cost(ONE_TOKEN, 1_000_000 * ONE_TOKEN, 2 * ONE_TOKEN),
+            Some(2_000_004 * ONE_NEAR)
+        );
+    }
+}
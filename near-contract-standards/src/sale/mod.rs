@@ -0,0 +1,26 @@
+//! Token sale component for launching a fungible token through a fair distribution.
+//!
+//! A [`TokenSale`] sells tokens of a [`FungibleToken`](crate::fungible_token::FungibleToken)
+//! at a fixed price or along a linear bonding curve (see [`PricingCurve`]). A sale goes through
+//! the following phases (see [`SalePhase`]):
+//!
+//! - a whitelist phase, where only the accounts with the whitelist role of the sale can buy, as
+//!   given by the [`HasRoles`](near_sdk::HasRoles) implementation of the contract, e.g. its
+//!   [`AccessControl`](crate::access_control::AccessControl),
+//! - a public phase, where any account can buy,
+//! - and the end of the sale, which either succeeds or fails depending on whether the soft cap
+//!   was raised. Buyers of a failed sale can reclaim their deposits.
+//!
+//! Purchased tokens are minted to the buyer right away and burned again on refund, so the whole
+//! sale can be followed by indexers through the standard [`FtMint`] and [`FtBurn`] events.
+//!
+//! [`FtMint`]: crate::fungible_token::events::FtMint
+//! [`FtBurn`]: crate::fungible_token::events::FtBurn
+
+mod core;
+mod curve;
+mod sale_impl;
+
+pub use self::core::{ext_token_sale, TokenSaleCore};
+pub use curve::PricingCurve;
+pub use sale_impl::{Purchase, SaleConfig, SaleInfo, SalePhase, TokenSale};
@@ -0,0 +1,438 @@
+use crate::fungible_token::events::{FtBurn, FtMint};
+use crate::fungible_token::{Balance, FungibleToken};
+use crate::sale::PricingCurve;
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, near, require, AccountId, HasRoles, IntoStorageKey, NearToken, TokenFormat};
+
+const ERR_PRICE_OVERFLOW: &str = "Price overflow";
+
+/// Configuration of a [`TokenSale`].
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SaleConfig {
+    /// Price curve the tokens are sold along.
+    pub curve: PricingCurve,
+    /// Amount of the smallest token denomination that makes up one whole token,
+    /// i.e. `10^decimals` of the token.
+    pub token_unit: U128,
+    /// Total amount of tokens offered by the sale.
+    pub total_for_sale: U128,
+    /// Deposit that has to be raised for the sale to succeed. If the sale ends below the soft cap,
+    /// buyers can reclaim their deposits.
+    pub soft_cap: NearToken,
+    /// Maximum amount of tokens a single account can buy over the whole sale.
+    pub per_account_cap: Option<U128>,
+    /// Block timestamp in nanoseconds at which the whitelisted accounts can start buying.
+    pub whitelist_start: U64,
+    /// Block timestamp in nanoseconds at which any account can start buying.
+    pub public_start: U64,
+    /// Block timestamp in nanoseconds at which the sale ends.
+    pub end: U64,
+}
+
+/// Phase of a [`TokenSale`], derived from the block timestamp and the progress of the sale.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SalePhase {
+    /// The sale has not started yet.
+    Pending,
+    /// Only the whitelisted accounts can buy.
+    Whitelist,
+    /// Any account can buy.
+    Public,
+    /// The sale has ended or sold out, and raised at least the soft cap.
+    Succeeded,
+    /// The sale has ended or sold out below the soft cap. Buyers can reclaim their deposits.
+    Failed,
+}
+
+/// Tokens bought and the deposit paid by a single account.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Purchase {
+    pub tokens: U128,
+    pub paid: NearToken,
+}
+
+/// Configuration and current progress of a [`TokenSale`], as returned by views.
+#[near(serializers=[json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SaleInfo {
+    pub config: SaleConfig,
+    pub phase: SalePhase,
+    pub tokens_sold: U128,
+    pub raised: NearToken,
+}
+
+/// Implementation of a token sale with whitelist and public phases, per-account caps and refunds
+/// when the soft cap is not reached.
+///
+/// Bought tokens are minted through [`FungibleToken::internal_deposit`], so buyers have to be
+/// registered with the token before buying. The sale only keeps track of tokens and deposits,
+/// transferring NEAR is left to the contract (see [`TokenSaleCore`](crate::sale::TokenSaleCore)).
+///
+/// The whitelisted accounts are the ones with the whitelist role in the [`HasRoles`]
+/// implementation passed to [`TokenSale::internal_buy`], e.g. the
+/// [`AccessControl`](crate::access_control::AccessControl) of the contract.
+#[near]
+pub struct TokenSale {
+    pub config: SaleConfig,
+    /// Role of the accounts allowed to buy during the whitelist phase.
+    pub whitelist_role: String,
+    /// AccountID -> Tokens bought and deposit paid.
+    pub purchases: LookupMap<AccountId, Purchase>,
+    /// Total amount of tokens sold.
+    pub tokens_sold: Balance,
+    /// Total deposit raised.
+    pub raised: NearToken,
+    /// Whether the raised deposit was already withdrawn after a successful sale.
+    pub proceeds_withdrawn: bool,
+}
+
+impl TokenSale {
+    pub fn new<S>(prefix: S, config: SaleConfig, whitelist_role: &str) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        require!(config.token_unit.0 > 0, "Token unit should be a positive number");
+        require!(
+            config.whitelist_start.0 <= config.public_start.0
+                && config.public_start.0 <= config.end.0,
+            "Sale phases must be in chronological order"
+        );
+        let prefix = prefix.into_storage_key();
+        Self {
+            config,
+            whitelist_role: whitelist_role.to_string(),
+            purchases: LookupMap::new([prefix.as_slice(), b"p"].concat()),
+            tokens_sold: 0,
+            raised: NearToken::from_yoctonear(0),
+            proceeds_withdrawn: false,
+        }
+    }
+
+    /// Returns the current phase of the sale.
+    pub fn phase(&self) -> SalePhase {
+        let now = env::block_timestamp();
+        if now < self.config.whitelist_start.0 {
+            SalePhase::Pending
+        } else if now < self.config.end.0 && self.tokens_sold < self.config.total_for_sale.0 {
+            if now < self.config.public_start.0 {
+                SalePhase::Whitelist
+            } else {
+                SalePhase::Public
+            }
+        } else if self.raised >= self.config.soft_cap {
+            SalePhase::Succeeded
+        } else {
+            SalePhase::Failed
+        }
+    }
+
+    /// Returns the configuration and the current progress of the sale.
+    pub fn info(&self) -> SaleInfo {
+        SaleInfo {
+            config: self.config.clone(),
+            phase: self.phase(),
+            tokens_sold: self.tokens_sold.into(),
+            raised: self.raised,
+        }
+    }
+
+    /// Returns the deposit required to buy `amount` tokens at the current price.
+    pub fn price_of(&self, amount: Balance) -> NearToken {
+        self.config
+            .curve
+            .cost(self.config.token_unit.0, self.tokens_sold, amount)
+            .map(NearToken::from_yoctonear)
+            .unwrap_or_else(|| env::panic_str(ERR_PRICE_OVERFLOW))
+    }
+
+    /// Sells `amount` tokens to `buyer` for the attached `deposit` and mints them to the buyer.
+    /// Returns the part of the deposit that was not needed and should be refunded.
+    ///
+    /// During the whitelist phase, `buyer` must have the whitelist role in `roles`.
+    pub fn internal_buy(
+        &mut self,
+        roles: &impl HasRoles,
+        token: &mut FungibleToken,
+        buyer: &AccountId,
+        amount: Balance,
+        deposit: NearToken,
+    ) -> NearToken {
+        match self.phase() {
+            SalePhase::Whitelist => {
+                require!(
+                    roles.has_role(&self.whitelist_role, buyer),
+                    "The account is not whitelisted"
+                )
+            }
+            SalePhase::Public => {}
+            _ => env::panic_str("The sale is not active"),
+        }
+        require!(amount > 0, "The amount should be a positive number");
+
+        let tokens_sold = self
+            .tokens_sold
+            .checked_add(amount)
+            .filter(|sold| *sold <= self.config.total_for_sale.0)
+            .unwrap_or_else(|| env::panic_str("Not enough tokens left for sale"));
+
+        let mut purchase = self
+            .purchases
+            .get(buyer)
+            .unwrap_or(Purchase { tokens: U128(0), paid: NearToken::from_yoctonear(0) });
+        purchase.tokens.0 = purchase
+            .tokens
+            .0
+            .checked_add(amount)
+            .unwrap_or_else(|| env::panic_str("Balance overflow"));
+        if let Some(cap) = self.config.per_account_cap {
+            require!(purchase.tokens.0 <= cap.0, "The purchase exceeds the per-account cap");
+        }
+
+        let cost = self.price_of(amount);
+        require!(
+            deposit >= cost,
+            format!("Must attach {} to buy {} tokens", TokenFormat::new(cost).round_up(), amount)
+        );
+        purchase.paid = purchase.paid.saturating_add(cost);
+        self.purchases.insert(buyer, &purchase);
+        self.tokens_sold = tokens_sold;
+        self.raised = self.raised.saturating_add(cost);
+
+        token.internal_deposit(buyer, amount);
+        FtMint { owner_id: buyer, amount: U128(amount), memo: Some("sale purchase") }.emit();
+
+        deposit.saturating_sub(cost)
+    }
+
+    /// Burns the tokens bought by `account_id` after a failed sale.
+    /// Returns the deposit paid for them, which should be transferred back to the account.
+    ///
+    /// The totals of the sale are kept as they were when the sale ended.
+    pub fn internal_refund(
+        &mut self,
+        token: &mut FungibleToken,
+        account_id: &AccountId,
+    ) -> NearToken {
+        require!(
+            self.phase() == SalePhase::Failed,
+            "Refunds are only available after a failed sale"
+        );
+        let purchase = self
+            .purchases
+            .remove(account_id)
+            .unwrap_or_else(|| env::panic_str("The account has nothing to refund"));
+
+        token.internal_withdraw(account_id, purchase.tokens.0);
+        FtBurn { owner_id: account_id, amount: purchase.tokens, memo: Some("sale refund") }.emit();
+
+        purchase.paid
+    }
+
+    /// Marks the raised deposit as withdrawn after a successful sale and returns it, so the
+    /// contract can transfer it to the beneficiary. Can only be called once.
+    pub fn internal_withdraw_proceeds(&mut self) -> NearToken {
+        require!(
+            self.phase() == SalePhase::Succeeded,
+            "Proceeds are only available after a successful sale"
+        );
+        require!(!self.proceeds_withdrawn, "Proceeds were already withdrawn");
+        self.proceeds_withdrawn = true;
+        self.raised
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::access_control::AccessControl;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    const SECOND: u64 = 1_000_000_000;
+    const WHITELIST_ROLE: &str = "whitelisted";
+
+    /// Sale of 100 tokens at 1 NEAR each, with a soft cap of 10 NEAR and a cap of 30 tokens per
+    /// account. The whitelist phase starts at 10 seconds, the public phase at 20 seconds and the
+    /// sale ends at 30 seconds. Alice is whitelisted, alice and bob are registered with the token.
+    fn setup() -> (TokenSale, FungibleToken, AccessControl) {
+        set_time(0);
+        let config = SaleConfig {
+            curve: PricingCurve::Fixed { price: U128(NearToken::from_near(1).as_yoctonear()) },
+            token_unit: U128(1),
+            total_for_sale: U128(100),
+            soft_cap: NearToken::from_near(10),
+            per_account_cap: Some(U128(30)),
+            whitelist_start: U64(10 * SECOND),
+            public_start: U64(20 * SECOND),
+            end: U64(30 * SECOND),
+        };
+        let sale = TokenSale::new(b"s", config, WHITELIST_ROLE);
+        let mut token = FungibleToken::new(b"t");
+        token.internal_register_account(&accounts(0));
+        token.internal_register_account(&accounts(1));
+        let mut acl = AccessControl::new(b"a");
+        acl.internal_grant_role(WHITELIST_ROLE, &accounts(0));
+        (sale, token, acl)
+    }
+
+    fn set_time(seconds: u64) {
+        testing_env!(VMContextBuilder::new().block_timestamp(seconds * SECOND).build());
+    }
+
+    fn buy(
+        (sale, token, acl): &mut (TokenSale, FungibleToken, AccessControl),
+        buyer: &AccountId,
+        amount: Balance,
+        near: u128,
+    ) -> NearToken {
+        sale.internal_buy(acl, token, buyer, amount, NearToken::from_near(near))
+    }
+
+    #[test]
+    fn test_whitelist_phase() {
+        let mut state = setup();
+        set_time(15);
+        assert_eq!(state.0.phase(), SalePhase::Whitelist);
+        assert_eq!(buy(&mut state, &accounts(0), 10, 11), NearToken::from_near(1));
+
+        let (sale, token, _) = &state;
+        assert_eq!(token.internal_unwrap_balance_of(&accounts(0)), 10);
+        assert_eq!(
+            sale.purchases.get(&accounts(0)),
+            Some(Purchase { tokens: U128(10), paid: NearToken::from_near(10) })
+        );
+        assert_eq!(sale.tokens_sold, 10);
+        assert_eq!(sale.raised, NearToken::from_near(10));
+    }
+
+    #[test]
+    #[should_panic(expected = "The account is not whitelisted")]
+    fn test_whitelist_phase_not_whitelisted() {
+        let mut state = setup();
+        set_time(15);
+        buy(&mut state, &accounts(1), 10, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "The sale is not active")]
+    fn test_buy_before_start() {
+        let mut state = setup();
+        buy(&mut state, &accounts(0), 10, 10);
+    }
+
+    #[test]
+    fn test_public_phase() {
+        let mut state = setup();
+        set_time(25);
+        assert_eq!(state.0.phase(), SalePhase::Public);
+        assert_eq!(buy(&mut state, &accounts(1), 5, 5), NearToken::from_near(0));
+        assert_eq!(state.1.internal_unwrap_balance_of(&accounts(1)), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "The sale is not active")]
+    fn test_buy_after_end() {
+        let mut state = setup();
+        set_time(30);
+        buy(&mut state, &accounts(1), 5, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Must attach 5 NEAR to buy 5 tokens")]
+    fn test_buy_without_enough_deposit() {
+        let mut state = setup();
+        set_time(25);
+        buy(&mut state, &accounts(1), 5, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "The purchase exceeds the per-account cap")]
+    fn test_per_account_cap() {
+        let mut state = setup();
+        set_time(15);
+        buy(&mut state, &accounts(0), 20, 20);
+        set_time(25);
+        buy(&mut state, &accounts(0), 11, 11);
+    }
+
+    #[test]
+    fn test_refund_after_failed_sale() {
+        let mut state = setup();
+        set_time(25);
+        buy(&mut state, &accounts(1), 5, 5);
+        set_time(30);
+
+        let (sale, token, _) = &mut state;
+        assert_eq!(sale.phase(), SalePhase::Failed);
+        assert_eq!(sale.internal_refund(token, &accounts(1)), NearToken::from_near(5));
+        assert_eq!(token.internal_unwrap_balance_of(&accounts(1)), 0);
+        assert!(sale.purchases.get(&accounts(1)).is_none());
+        assert_eq!(sale.info().raised, NearToken::from_near(5));
+    }
+
+    #[test]
+    #[should_panic(expected = "The account has nothing to refund")]
+    fn test_refund_twice() {
+        let mut state = setup();
+        set_time(25);
+        buy(&mut state, &accounts(1), 5, 5);
+        set_time(30);
+
+        let (sale, token, _) = &mut state;
+        sale.internal_refund(token, &accounts(1));
+        sale.internal_refund(token, &accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Refunds are only available after a failed sale")]
+    fn test_refund_after_successful_sale() {
+        let mut state = setup();
+        set_time(25);
+        buy(&mut state, &accounts(1), 10, 10);
+        set_time(30);
+
+        let (sale, token, _) = &mut state;
+        sale.internal_refund(token, &accounts(1));
+    }
+
+    #[test]
+    fn test_withdraw_proceeds() {
+        let mut state = setup();
+        set_time(15);
+        buy(&mut state, &accounts(0), 10, 10);
+        set_time(25);
+        buy(&mut state, &accounts(1), 5, 5);
+        assert_eq!(state.0.phase(), SalePhase::Public);
+        set_time(30);
+
+        let sale = &mut state.0;
+        assert_eq!(sale.phase(), SalePhase::Succeeded);
+        assert_eq!(sale.internal_withdraw_proceeds(), NearToken::from_near(15));
+        assert!(sale.proceeds_withdrawn);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proceeds were already withdrawn")]
+    fn test_withdraw_proceeds_twice() {
+        let mut state = setup();
+        set_time(25);
+        buy(&mut state, &accounts(1), 10, 10);
+        set_time(30);
+
+        state.0.internal_withdraw_proceeds();
+        state.0.internal_withdraw_proceeds();
+    }
+
+    #[test]
+    #[should_panic(expected = "Proceeds are only available after a successful sale")]
+    fn test_withdraw_proceeds_after_failed_sale() {
+        let mut state = setup();
+        set_time(30);
+        state.0.internal_withdraw_proceeds();
+    }
+}
@@ -0,0 +1,41 @@
+//! Settings of the token implementations, stored under their own storage keys rather than in the
+//! serialized state of the tokens, so that adding a setting keeps the state of the contracts
+//! already deployed readable.
+//!
+//! The key of a setting is the prefix of a collection of the token followed by `~` and the name
+//! of the setting. The keys of the collection are the prefix followed by the borsh serialization
+//! of a length, whose second byte is never a letter, so the settings can't collide with them.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::TreeMap;
+use near_sdk::env;
+
+/// Returns the storage key of the setting `name` of the token with a collection under `prefix`.
+pub(crate) fn setting_key(prefix: &[u8], name: &str) -> Vec<u8> {
+    [prefix, b"~", name.as_bytes()].concat()
+}
+
+/// Returns the prefix of a collection serialized as its prefix, e.g. a `LookupMap`.
+pub(crate) fn collection_prefix<C: BorshSerialize>(collection: &C) -> Vec<u8> {
+    let serialized = borsh::to_vec(collection).unwrap_or_else(|_| env::abort());
+    borsh::from_slice(&serialized).unwrap_or_else(|_| env::abort())
+}
+
+/// Returns the prefix of the values of `map`, serialized after the `u64` of its root.
+pub(crate) fn tree_map_values_prefix<K, V>(map: &TreeMap<K, V>) -> Vec<u8> {
+    let serialized = borsh::to_vec(map).unwrap_or_else(|_| env::abort());
+    Vec::<u8>::deserialize(&mut &serialized[8..]).unwrap_or_else(|_| env::abort())
+}
+
+/// Reads the setting stored under `key`, or `None` if it was never written.
+pub(crate) fn read_setting<T: BorshDeserialize>(key: &[u8]) -> Option<T> {
+    env::storage_read(key).map(|value| {
+        borsh::from_slice(&value)
+            .unwrap_or_else(|_| env::panic_str("Cannot deserialize the setting with Borsh"))
+    })
+}
+
+/// Writes the setting stored under `key`.
+pub(crate) fn write_setting<T: BorshSerialize>(key: &[u8], value: &T) {
+    env::storage_write(key, &borsh::to_vec(value).unwrap_or_else(|_| env::abort()));
+}
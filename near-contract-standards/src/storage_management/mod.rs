@@ -1,5 +1,9 @@
 use near_sdk::{ext_contract, near, AccountId, NearToken};
 
+mod tracker;
+
+pub use tracker::{AccountStorage, StorageTracker};
+
 #[near(serializers=[borsh, json])]
 pub struct StorageBalance {
     pub total: NearToken,
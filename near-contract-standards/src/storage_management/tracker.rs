@@ -0,0 +1,347 @@
+use crate::storage_management::{StorageBalance, StorageBalanceBounds, StorageManagement};
+use near_sdk::store::LookupMap;
+use near_sdk::{
+    assert_one_yocto, env, log, near, require, AccountId, IntoStorageKey, NearToken, Promise,
+    StorageUsage, TokenFormat,
+};
+
+/// Storage deposit of an account in a [`StorageTracker`].
+#[near(serializers=[borsh])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountStorage {
+    /// Deposit of the account.
+    pub deposit: NearToken,
+    /// Bytes of storage used by the account, including its registration.
+    pub used_bytes: StorageUsage,
+}
+
+/// Implementation of [`StorageManagement`] for any contract, e.g. a non-fungible token or a
+/// marketplace, keeping the storage deposit of each account and the bytes of storage it uses.
+///
+/// The contract charges the storage of its state mutations to an account by running them in
+/// [`StorageTracker::track`], which panics if the deposit of the account doesn't cover its
+/// storage, and credits it back when they free storage. The collections of [`near_sdk::store`]
+/// cache their changes until they are flushed, so they are flushed in the tracked closure.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{env, near, AccountId, NearToken, PanicOnDefault};
+/// use near_sdk::collections::LookupMap;
+/// use near_contract_standards::storage_management::{
+///     StorageBalance, StorageBalanceBounds, StorageManagement, StorageTracker,
+/// };
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     storage: StorageTracker,
+///     notes: LookupMap<(AccountId, String), String>,
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     pub fn add_note(&mut self, title: String, note: String) {
+///         let account_id = env::predecessor_account_id();
+///         let key = (account_id.clone(), title);
+///         self.storage.track(&account_id, || self.notes.insert(&key, &note));
+///     }
+/// }
+///
+/// #[near]
+/// impl StorageManagement for Contract {
+///     #[payable]
+///     fn storage_deposit(
+///         &mut self,
+///         account_id: Option<AccountId>,
+///         registration_only: Option<bool>,
+///     ) -> StorageBalance {
+///         self.storage.storage_deposit(account_id, registration_only)
+///     }
+///
+///     #[payable]
+///     fn storage_withdraw(&mut self, amount: Option<NearToken>) -> StorageBalance {
+///         self.storage.storage_withdraw(amount)
+///     }
+///
+///     #[payable]
+///     fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+///         self.storage.storage_unregister(force)
+///     }
+///
+///     fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+///         self.storage.storage_balance_bounds()
+///     }
+///
+///     fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+///         self.storage.storage_balance_of(account_id)
+///     }
+/// }
+/// ```
+#[near]
+pub struct StorageTracker {
+    /// AccountID -> Deposit and bytes of storage used.
+    pub accounts: LookupMap<AccountId, AccountStorage>,
+    /// The storage size in bytes of the registration of one account.
+    pub account_storage_usage: StorageUsage,
+}
+
+impl StorageTracker {
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let mut this =
+            Self { accounts: LookupMap::new(prefix.into_storage_key()), account_storage_usage: 0 };
+        this.measure_account_storage_usage();
+        this
+    }
+
+    fn measure_account_storage_usage(&mut self) {
+        let initial_storage_usage = env::storage_usage();
+        let tmp_account_id: AccountId = "a".repeat(64).parse().unwrap();
+        let tmp_storage = AccountStorage { deposit: NearToken::from_near(0), used_bytes: 0 };
+        self.accounts.insert(tmp_account_id.clone(), tmp_storage);
+        self.accounts.flush();
+        self.account_storage_usage = env::storage_usage() - initial_storage_usage;
+        self.accounts.remove(&tmp_account_id);
+        self.accounts.flush();
+    }
+
+    /// Returns the deposit and the storage used by `account_id`, if it is registered.
+    pub fn account_storage(&self, account_id: &AccountId) -> Option<&AccountStorage> {
+        self.accounts.get(account_id)
+    }
+
+    /// Returns the part of the deposit of `account_id` not used by its storage.
+    pub fn available_balance(&self, account_id: &AccountId) -> Option<NearToken> {
+        self.accounts.get(account_id).map(|storage| {
+            storage
+                .deposit
+                .saturating_sub(env::storage_byte_cost().saturating_mul(storage.used_bytes.into()))
+        })
+    }
+
+    /// Runs `f`, charging the storage it uses to `account_id`, or crediting the storage it
+    /// frees. Returns the result of `f`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `account_id` is not registered, or if its deposit doesn't cover its storage.
+    pub fn track<R>(&mut self, account_id: &AccountId, f: impl FnOnce() -> R) -> R {
+        let initial_storage_usage = env::storage_usage();
+        let result = f();
+        let storage_usage = env::storage_usage();
+        if storage_usage >= initial_storage_usage {
+            self.internal_use_storage(account_id, storage_usage - initial_storage_usage);
+        } else {
+            self.internal_free_storage(account_id, initial_storage_usage - storage_usage);
+        }
+        result
+    }
+
+    /// Charges `bytes` of storage to `account_id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `account_id` is not registered, or if its deposit doesn't cover its storage.
+    pub fn internal_use_storage(&mut self, account_id: &AccountId, bytes: StorageUsage) {
+        let storage = self.internal_unwrap_account_storage(account_id);
+        storage.used_bytes = storage.used_bytes.saturating_add(bytes);
+        let required = env::storage_byte_cost().saturating_mul(storage.used_bytes.into());
+        if storage.deposit < required {
+            env::panic_str(&format!(
+                "The storage deposit of {} is less than the {} required",
+                account_id,
+                TokenFormat::new(required).round_up()
+            ));
+        }
+    }
+
+    /// Credits `bytes` of freed storage back to `account_id`.
+    pub fn internal_free_storage(&mut self, account_id: &AccountId, bytes: StorageUsage) {
+        let account_storage_usage = self.account_storage_usage;
+        let storage = self.internal_unwrap_account_storage(account_id);
+        storage.used_bytes = storage.used_bytes.saturating_sub(bytes).max(account_storage_usage);
+    }
+
+    fn internal_unwrap_account_storage(&mut self, account_id: &AccountId) -> &mut AccountStorage {
+        self.accounts.get_mut(account_id).unwrap_or_else(|| {
+            env::panic_str(format!("The account {} is not registered", account_id).as_str())
+        })
+    }
+
+    fn internal_storage_balance_of(&self, account_id: &AccountId) -> Option<StorageBalance> {
+        self.account_storage(account_id).map(|storage| StorageBalance {
+            total: storage.deposit,
+            available: self.available_balance(account_id).unwrap(),
+        })
+    }
+}
+
+impl StorageManagement for StorageTracker {
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let amount = env::attached_deposit();
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let registration_only = registration_only.unwrap_or(false);
+        if let Some(storage) = self.accounts.get_mut(&account_id) {
+            if registration_only {
+                log!("The account is already registered, refunding the deposit");
+                if amount > NearToken::from_near(0) {
+                    Promise::new(env::predecessor_account_id()).transfer(amount);
+                }
+            } else {
+                storage.deposit = storage.deposit.saturating_add(amount);
+            }
+        } else {
+            let min_balance = self.storage_balance_bounds().min;
+            if amount < min_balance {
+                env::panic_str(&format!(
+                    "The attached deposit is less than the minimum storage balance of {}",
+                    TokenFormat::new(min_balance).round_up()
+                ));
+            }
+            let deposit = if registration_only { min_balance } else { amount };
+            self.accounts.insert(
+                account_id.clone(),
+                AccountStorage { deposit, used_bytes: self.account_storage_usage },
+            );
+            let refund = amount.saturating_sub(deposit);
+            if refund > NearToken::from_near(0) {
+                Promise::new(env::predecessor_account_id()).transfer(refund);
+            }
+        }
+        self.internal_storage_balance_of(&account_id).unwrap()
+    }
+
+    fn storage_withdraw(&mut self, amount: Option<NearToken>) -> StorageBalance {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let available = self.available_balance(&account_id).unwrap_or_else(|| {
+            env::panic_str(format!("The account {} is not registered", &account_id).as_str())
+        });
+        let amount = amount.unwrap_or(available);
+        require!(amount <= available, "The amount is greater than the available storage balance");
+        if amount > NearToken::from_near(0) {
+            let storage = self.internal_unwrap_account_storage(&account_id);
+            storage.deposit = storage.deposit.saturating_sub(amount);
+            Promise::new(account_id.clone()).transfer(amount);
+        }
+        self.internal_storage_balance_of(&account_id).unwrap()
+    }
+
+    /// Unregisters the predecessor once the contract freed all of its storage, as the tracker
+    /// can't remove the data of the account. `force` is not supported.
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_one_yocto();
+        require!(!force.unwrap_or(false), "Force unregistration is not supported");
+        let account_id = env::predecessor_account_id();
+        if let Some(storage) = self.accounts.get(&account_id) {
+            require!(
+                storage.used_bytes <= self.account_storage_usage,
+                "Can't unregister the account while it still uses storage"
+            );
+            let deposit = storage.deposit;
+            self.accounts.remove(&account_id);
+            Promise::new(account_id).transfer(deposit);
+            true
+        } else {
+            log!("The account {} is not registered", &account_id);
+            false
+        }
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        let required_storage_balance =
+            env::storage_byte_cost().saturating_mul(self.account_storage_usage.into());
+        StorageBalanceBounds { min: required_storage_balance, max: None }
+    }
+
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.internal_storage_balance_of(&account_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{alice, bob};
+    use near_sdk::collections::LookupMap as CollectionsLookupMap;
+    use near_sdk::testing_env;
+
+    fn setup() -> StorageTracker {
+        let mut tracker = StorageTracker::new(b"s");
+        testing_env!(alice().context().attached_deposit(NearToken::from_near(1)).build());
+        tracker.storage_deposit(None, None);
+        tracker
+    }
+
+    #[test]
+    fn deposit_and_track() {
+        let mut tracker = setup();
+        let alice = alice();
+        let registration = tracker.storage_balance_bounds().min;
+        let balance = tracker.storage_balance_of(alice.account_id().clone()).unwrap();
+        assert_eq!(balance.total, NearToken::from_near(1));
+        assert_eq!(balance.available, NearToken::from_near(1).saturating_sub(registration));
+
+        let mut notes = CollectionsLookupMap::new(b"n");
+        tracker.track(alice.account_id(), || notes.insert(&1u64, &"x".repeat(100)));
+        let used = tracker.account_storage(alice.account_id()).unwrap().used_bytes;
+        assert!(used > tracker.account_storage_usage + 100);
+
+        // Freeing the storage credits it back.
+        tracker.track(alice.account_id(), || notes.remove(&1u64));
+        assert_eq!(
+            tracker.account_storage(alice.account_id()).unwrap().used_bytes,
+            tracker.account_storage_usage
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The storage deposit of alice is less than")]
+    fn track_beyond_deposit() {
+        let mut tracker = setup();
+        let mut notes = CollectionsLookupMap::new(b"n");
+        // 1 NEAR covers 100kB of storage.
+        tracker.track(alice().account_id(), || notes.insert(&1u64, &"x".repeat(200_000)));
+    }
+
+    #[test]
+    #[should_panic(expected = "The account bob is not registered")]
+    fn track_unregistered_account() {
+        let mut tracker = setup();
+        let mut notes = CollectionsLookupMap::new(b"n");
+        tracker.track(bob().account_id(), || notes.insert(&1u64, &1u64));
+    }
+
+    #[test]
+    fn withdraw_and_unregister() {
+        let mut tracker = setup();
+        let alice = alice();
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        let balance = tracker.storage_withdraw(None);
+        assert_eq!(balance.available, NearToken::from_near(0));
+        assert_eq!(balance.total, tracker.storage_balance_bounds().min);
+
+        assert!(tracker.storage_unregister(None));
+        assert!(tracker.storage_balance_of(alice.account_id().clone()).is_none());
+        assert!(!tracker.storage_unregister(None));
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't unregister the account while it still uses storage")]
+    fn unregister_with_storage_in_use() {
+        let mut tracker = setup();
+        let alice = alice();
+        let mut notes = CollectionsLookupMap::new(b"n");
+        tracker.track(alice.account_id(), || notes.insert(&1u64, &1u64));
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        tracker.storage_unregister(None);
+    }
+}
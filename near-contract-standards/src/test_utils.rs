@@ -0,0 +1,161 @@
+//! Account fixtures for unit tests of contracts using the standards components.
+//!
+//! A [`Persona`] is a test account with the state it holds in the components, e.g. a registered
+//! fungible token holder with a balance or the owner of non-fungible tokens. The state is set up
+//! through the components themselves, so tests only describe who the actors are:
+//!
+//! ```
+//! use near_contract_standards::fungible_token::FungibleToken;
+//! use near_contract_standards::test_utils::{alice, bob};
+//!
+//! let alice = alice().with_ft_balance(100);
+//! let bob = bob().with_ft_balance(0);
+//!
+//! let mut token = FungibleToken::new(b"t");
+//! alice.setup_ft(&mut token);
+//! bob.setup_ft(&mut token);
+//!
+//! // Call as alice.
+//! alice.set_as_caller();
+//! token.internal_transfer(alice.account_id(), bob.account_id(), 40, None);
+//! assert_eq!(token.internal_unwrap_balance_of(bob.account_id()), 40);
+//! ```
+//!
+//! This module is only available with the `unit-testing` feature, outside of wasm32 targets.
+
+use crate::fungible_token::{Balance, FungibleToken};
+use crate::non_fungible_token::metadata::TokenMetadata;
+use crate::non_fungible_token::{NonFungibleToken, TokenId};
+use near_sdk::test_utils::{accounts, VMContextBuilder};
+use near_sdk::{testing_env, AccountId};
+
+/// Returns the [`Persona`] of `accounts(0)`, `alice`.
+pub fn alice() -> Persona {
+    Persona::new(accounts(0))
+}
+
+/// Returns the [`Persona`] of `accounts(1)`, `bob`.
+pub fn bob() -> Persona {
+    Persona::new(accounts(1))
+}
+
+/// Returns the [`Persona`] of `accounts(2)`, `charlie`.
+pub fn charlie() -> Persona {
+    Persona::new(accounts(2))
+}
+
+/// Returns the [`Persona`] of `accounts(3)`, `danny`.
+pub fn danny() -> Persona {
+    Persona::new(accounts(3))
+}
+
+/// Test account with the state it holds in the standards components, see the
+/// [module documentation](self).
+#[derive(Clone, Debug)]
+pub struct Persona {
+    account_id: AccountId,
+    ft_balance: Option<Balance>,
+    nft_tokens: Vec<TokenId>,
+}
+
+impl Persona {
+    /// Creates a persona of `account_id` holding nothing.
+    pub fn new(account_id: AccountId) -> Self {
+        Self { account_id, ft_balance: None, nft_tokens: Vec::new() }
+    }
+
+    /// Returns the account ID of the persona.
+    pub fn account_id(&self) -> &AccountId {
+        &self.account_id
+    }
+
+    /// Makes the persona a registered fungible token holder with `balance`.
+    pub fn with_ft_balance(mut self, balance: Balance) -> Self {
+        self.ft_balance = Some(balance);
+        self
+    }
+
+    /// Makes the persona the owner of the non-fungible tokens `token_ids`.
+    pub fn with_nft_tokens<I>(mut self, token_ids: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<TokenId>,
+    {
+        self.nft_tokens.extend(token_ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Returns a context of a call signed by the persona.
+    pub fn context(&self) -> VMContextBuilder {
+        let mut context = VMContextBuilder::new();
+        context
+            .predecessor_account_id(self.account_id.clone())
+            .signer_account_id(self.account_id.clone());
+        context
+    }
+
+    /// Sets up the testing environment for a call signed by the persona, keeping the storage.
+    pub fn set_as_caller(&self) {
+        testing_env!(self.context().build());
+    }
+
+    /// Registers the persona in `token` and deposits its balance, if it was given one with
+    /// [`with_ft_balance`](Self::with_ft_balance).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the persona is already registered.
+    pub fn setup_ft(&self, token: &mut FungibleToken) {
+        if let Some(balance) = self.ft_balance {
+            token.internal_register_account(&self.account_id);
+            if balance > 0 {
+                token.internal_deposit(&self.account_id, balance);
+            }
+        }
+    }
+
+    /// Mints the tokens of the persona in `token`, with default metadata. The storage of the
+    /// tokens is not charged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the tokens already exists.
+    pub fn setup_nft(&self, token: &mut NonFungibleToken) {
+        for token_id in &self.nft_tokens {
+            token.internal_mint_with_refund(
+                token_id.clone(),
+                self.account_id.clone(),
+                Some(TokenMetadata::default()),
+                None,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::non_fungible_token::core::NonFungibleTokenCore;
+
+    #[test]
+    fn setup_personas() {
+        let alice = alice().with_ft_balance(100).with_nft_tokens(["0", "1"]);
+        let bob = bob().with_ft_balance(0);
+        alice.set_as_caller();
+
+        let mut ft = FungibleToken::new(b"f");
+        alice.setup_ft(&mut ft);
+        bob.setup_ft(&mut ft);
+        charlie().setup_ft(&mut ft);
+        assert_eq!(ft.internal_unwrap_balance_of(alice.account_id()), 100);
+        assert_eq!(ft.internal_unwrap_balance_of(bob.account_id()), 0);
+        assert!(!ft.accounts.contains_key(charlie().account_id()));
+        assert_eq!(ft.total_supply, 100);
+
+        let mut nft =
+            NonFungibleToken::new(b"n", accounts(0), Some(b"m"), None::<Vec<u8>>, None::<Vec<u8>>);
+        alice.setup_nft(&mut nft);
+        let token = nft.nft_token("1".to_string()).unwrap();
+        assert_eq!(&token.owner_id, alice.account_id());
+    }
+}
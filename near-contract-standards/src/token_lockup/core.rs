@@ -0,0 +1,100 @@
+use crate::token_lockup::Lockup;
+use near_sdk::json_types::U128;
+use near_sdk::{ext_contract, AccountId};
+
+/// The contract methods of a token lockup.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{env, near, AccountId, HasRoles, PanicOnDefault, OWNER_ROLE};
+/// use near_sdk::json_types::U128;
+/// use near_contract_standards::fungible_token::FungibleToken;
+/// use near_contract_standards::ownable::Ownable;
+/// use near_contract_standards::token_lockup::{
+///     Lockup, TokenLockup, TokenLockupCore, VestingSchedule,
+/// };
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     ownable: Ownable,
+///     token: FungibleToken,
+///     lockup: TokenLockup,
+/// }
+///
+/// impl HasRoles for Contract {
+///     fn has_role(&self, role: &str, account_id: &AccountId) -> bool {
+///         self.ownable.has_role(role, account_id)
+///     }
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     #[init]
+///     pub fn new(owner_id: AccountId, total_supply: U128) -> Self {
+///         let mut token = FungibleToken::new(b"t");
+///         token.internal_register_account(&owner_id);
+///         token.internal_register_account(&env::current_account_id());
+///         token.internal_deposit(&owner_id, total_supply.0);
+///         Self {
+///             ownable: Ownable::new(owner_id),
+///             token,
+///             lockup: TokenLockup::new(b"l", OWNER_ROLE),
+///         }
+///     }
+///
+///     /// Locks `amount` tokens of the owner for `account_id`.
+///     #[only_owner]
+///     pub fn create_lockup(&mut self, account_id: AccountId, amount: U128, schedule: VestingSchedule) {
+///         let owner_id = env::predecessor_account_id();
+///         self.lockup.internal_create(&mut self.token, &owner_id, &account_id, amount.0, schedule);
+///     }
+/// }
+///
+/// #[near]
+/// impl TokenLockupCore for Contract {
+///     fn lockup_of(&self, account_id: AccountId) -> Option<Lockup> {
+///         self.lockup.lockup_of(&account_id).cloned()
+///     }
+///
+///     fn lockup_vested(&self, account_id: AccountId) -> U128 {
+///         self.lockup.vested_amount(&account_id).into()
+///     }
+///
+///     fn lockup_unvested(&self, account_id: AccountId) -> U128 {
+///         self.lockup.unvested_amount(&account_id).into()
+///     }
+///
+///     fn lockup_claim(&mut self) -> U128 {
+///         self.lockup.claim(&mut self.token, &env::predecessor_account_id()).into()
+///     }
+///
+///     fn lockup_terminate(&mut self, account_id: AccountId) -> U128 {
+///         let refund_id = env::predecessor_account_id();
+///         self.lockup.terminate(&self.ownable, &mut self.token, &account_id, &refund_id).into()
+///     }
+/// }
+/// ```
+#[ext_contract(ext_token_lockup)]
+pub trait TokenLockupCore {
+    /// Returns the lockup of `account_id`, if any.
+    fn lockup_of(&self, account_id: AccountId) -> Option<Lockup>;
+
+    /// Returns the amount of tokens of `account_id` vested so far, including the claimed ones.
+    fn lockup_vested(&self, account_id: AccountId) -> U128;
+
+    /// Returns the amount of tokens of `account_id` that have not vested yet.
+    fn lockup_unvested(&self, account_id: AccountId) -> U128;
+
+    /// Transfers the vested tokens of the predecessor that were not claimed yet to it, and
+    /// returns their amount.
+    fn lockup_claim(&mut self) -> U128;
+
+    /// Terminates the lockup of `account_id`, whose vested tokens stay claimable, and returns
+    /// the unvested tokens to an account chosen by the contract, e.g. the predecessor.
+    /// Can only be called by the accounts having the terminator role.
+    ///
+    /// Returns the amount of returned tokens.
+    fn lockup_terminate(&mut self, account_id: AccountId) -> U128;
+}
@@ -0,0 +1,21 @@
+//! Token lockup component vesting fungible tokens to accounts over time.
+//!
+//! A [`TokenLockup`] holds the tokens locked for each account on the balance of the contract
+//! account in its [`FungibleToken`](crate::fungible_token::FungibleToken), and releases them
+//! along a [`VestingSchedule`]: nothing vests before the cliff, then the tokens vest linearly
+//! from the start of the schedule to its end. A schedule whose cliff is its end vests all the
+//! tokens at once.
+//!
+//! The account claims its vested tokens with [`TokenLockup::claim`], which transfers them from
+//! the contract account through the token. A lockup is terminated by the accounts having the
+//! terminator role of the lockup, checked through any [`HasRoles`](near_sdk::HasRoles)
+//! implementation: the tokens vested so far stay claimable, and the unvested ones are returned.
+//! Every change is logged as a [`TokenLockupEvent`].
+//!
+//! The contract account has to be registered with the token before creating lockups.
+
+mod core;
+mod token_lockup_impl;
+
+pub use self::core::{ext_token_lockup, TokenLockupCore};
+pub use token_lockup_impl::{Lockup, TokenLockup, TokenLockupEvent, VestingSchedule};
@@ -0,0 +1,313 @@
+use crate::fungible_token::{Balance, FungibleToken};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::store::LookupMap;
+use near_sdk::{env, near, require, AccountId, HasRoles, IntoStorageKey};
+
+/// Changes of the lockups of a [`TokenLockup`].
+#[near(event_json(standard = "token_lockup"))]
+pub enum TokenLockupEvent<'a> {
+    /// `amount` tokens were locked for `account_id` along `schedule`.
+    #[event_version("1.0.0")]
+    LockupCreated { account_id: &'a AccountId, amount: U128, schedule: &'a VestingSchedule },
+    /// `account_id` claimed `amount` vested tokens.
+    #[event_version("1.0.0")]
+    LockupClaimed { account_id: &'a AccountId, amount: U128 },
+    /// The lockup of `account_id` was terminated by `sender_id`, returning `unvested` tokens.
+    #[event_version("1.0.0")]
+    LockupTerminated { account_id: &'a AccountId, unvested: U128, sender_id: &'a AccountId },
+}
+
+/// Vesting schedule of a [`Lockup`], as block timestamps in nanoseconds.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VestingSchedule {
+    /// Start of the linear vesting.
+    pub start: U64,
+    /// Timestamp before which no tokens are vested. The tokens vested linearly since the start
+    /// are released at once at the cliff.
+    pub cliff: U64,
+    /// Timestamp from which all the tokens are vested.
+    pub end: U64,
+}
+
+impl VestingSchedule {
+    /// Returns the amount of `total` vested at `timestamp`.
+    pub fn vested_at(&self, total: Balance, timestamp: u64) -> Balance {
+        if timestamp < self.cliff.0 {
+            0
+        } else if timestamp >= self.end.0 {
+            total
+        } else {
+            let elapsed = u128::from(timestamp.saturating_sub(self.start.0));
+            let duration = u128::from(self.end.0 - self.start.0);
+            // Split to avoid overflowing `total * elapsed`.
+            total / duration * elapsed + total % duration * elapsed / duration
+        }
+    }
+}
+
+/// Tokens locked for an account.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lockup {
+    pub schedule: VestingSchedule,
+    /// Amount of tokens vesting along the schedule. Reduced to the vested amount on termination.
+    pub total: U128,
+    /// Amount of vested tokens already claimed.
+    pub claimed: U128,
+}
+
+impl Lockup {
+    /// Returns the amount of tokens vested at `timestamp`, including the claimed ones.
+    pub fn vested_at(&self, timestamp: u64) -> Balance {
+        self.schedule.vested_at(self.total.0, timestamp)
+    }
+}
+
+/// Implementation of token lockups with linear and cliff vesting, see the
+/// [module documentation](crate::token_lockup).
+///
+/// The locked tokens are held by the contract account in the [`FungibleToken`] passed to the
+/// methods. The methods prefixed with `internal_` don't check the predecessor, e.g. to create
+/// lockups from a method of the contract restricted to its owner.
+#[near]
+pub struct TokenLockup {
+    /// Account ID -> Its lockup.
+    pub lockups: LookupMap<AccountId, Lockup>,
+    /// Role of the accounts that can terminate lockups.
+    pub terminator_role: String,
+}
+
+impl TokenLockup {
+    pub fn new<S>(prefix: S, terminator_role: &str) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let prefix = prefix.into_storage_key();
+        Self {
+            lockups: LookupMap::new([prefix.as_slice(), b"l"].concat()),
+            terminator_role: terminator_role.to_string(),
+        }
+    }
+
+    /// Returns the lockup of `account_id`, if any.
+    pub fn lockup_of(&self, account_id: &AccountId) -> Option<&Lockup> {
+        self.lockups.get(account_id)
+    }
+
+    /// Returns the amount of tokens of `account_id` vested so far, including the claimed ones.
+    pub fn vested_amount(&self, account_id: &AccountId) -> Balance {
+        self.lockup_of(account_id).map_or(0, |lockup| lockup.vested_at(env::block_timestamp()))
+    }
+
+    /// Returns the amount of tokens of `account_id` that have not vested yet.
+    pub fn unvested_amount(&self, account_id: &AccountId) -> Balance {
+        self.lockup_of(account_id)
+            .map_or(0, |lockup| lockup.total.0 - lockup.vested_at(env::block_timestamp()))
+    }
+
+    /// Locks `amount` tokens of `funder_id` for `account_id` along `schedule`, transferring them
+    /// to the contract account. An account has at most one lockup.
+    pub fn internal_create(
+        &mut self,
+        token: &mut FungibleToken,
+        funder_id: &AccountId,
+        account_id: &AccountId,
+        amount: Balance,
+        schedule: VestingSchedule,
+    ) {
+        require!(
+            schedule.start.0 <= schedule.cliff.0 && schedule.cliff.0 <= schedule.end.0,
+            "The vesting schedule must be in chronological order"
+        );
+        require!(amount > 0, "The amount should be a positive number");
+        require!(!self.lockups.contains_key(account_id), "The account already has a lockup");
+        token.internal_transfer(
+            funder_id,
+            &env::current_account_id(),
+            amount,
+            Some("lockup".into()),
+        );
+        TokenLockupEvent::LockupCreated { account_id, amount: U128(amount), schedule: &schedule }
+            .emit();
+        self.lockups
+            .insert(account_id.clone(), Lockup { schedule, total: U128(amount), claimed: U128(0) });
+    }
+
+    /// Transfers the vested tokens of `account_id` that were not claimed yet to it, removing its
+    /// lockup once all of its tokens are claimed. Returns the amount of claimed tokens.
+    pub fn claim(&mut self, token: &mut FungibleToken, account_id: &AccountId) -> Balance {
+        let lockup = self
+            .lockups
+            .get_mut(account_id)
+            .unwrap_or_else(|| env::panic_str("The account has no lockup"));
+        let amount = lockup.vested_at(env::block_timestamp()) - lockup.claimed.0;
+        require!(amount > 0, "The account has no vested tokens to claim");
+        lockup.claimed.0 += amount;
+        if lockup.claimed == lockup.total {
+            self.lockups.remove(account_id);
+        }
+        token.internal_transfer(&env::current_account_id(), account_id, amount, None);
+        TokenLockupEvent::LockupClaimed { account_id, amount: U128(amount) }.emit();
+        amount
+    }
+
+    /// Terminates the lockup of `account_id` if the predecessor has the terminator role in
+    /// `roles`. The tokens vested so far stay claimable, and the unvested ones are transferred to
+    /// `refund_id`. Returns the amount of unvested tokens.
+    pub fn terminate(
+        &mut self,
+        roles: &impl HasRoles,
+        token: &mut FungibleToken,
+        account_id: &AccountId,
+        refund_id: &AccountId,
+    ) -> Balance {
+        let sender_id = env::predecessor_account_id();
+        if !roles.has_role(&self.terminator_role, &sender_id) {
+            env::panic_str(&format!(
+                "Only the {} role can terminate lockups",
+                self.terminator_role
+            ));
+        }
+        let now = env::block_timestamp();
+        let lockup = self
+            .lockups
+            .get_mut(account_id)
+            .unwrap_or_else(|| env::panic_str("The account has no lockup"));
+        let vested = lockup.vested_at(now);
+        let unvested = lockup.total.0 - vested;
+        require!(unvested > 0, "The lockup is already fully vested");
+        // Stop the vesting, the tokens vested so far being all the tokens of the lockup.
+        lockup.total = U128(vested);
+        lockup.schedule.cliff = lockup.schedule.cliff.0.min(now).into();
+        lockup.schedule.end = now.into();
+        if lockup.claimed == lockup.total {
+            self.lockups.remove(account_id);
+        }
+        token.internal_transfer(&env::current_account_id(), refund_id, unvested, None);
+        TokenLockupEvent::LockupTerminated {
+            account_id,
+            unvested: U128(unvested),
+            sender_id: &sender_id,
+        }
+        .emit();
+        unvested
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ownable::Ownable;
+    use crate::test_utils::{alice, bob, Persona};
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, OWNER_ROLE};
+
+    const START: u64 = 1_000;
+    const CLIFF: u64 = 1_250;
+    const END: u64 = 2_000;
+
+    fn set_context(persona: &Persona, block_timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(lockup_account())
+            .predecessor_account_id(persona.account_id().clone())
+            .block_timestamp(block_timestamp)
+            .build());
+    }
+
+    fn lockup_account() -> AccountId {
+        "lockup".parse().unwrap()
+    }
+
+    /// Locks 1000 tokens of alice for bob.
+    fn setup() -> (Persona, Persona, FungibleToken, TokenLockup) {
+        let (alice, bob) = (alice().with_ft_balance(1_000), bob().with_ft_balance(0));
+        set_context(&alice, 0);
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+        bob.setup_ft(&mut token);
+        token.internal_register_account(&lockup_account());
+        let mut lockup = TokenLockup::new(b"l", OWNER_ROLE);
+        let schedule =
+            VestingSchedule { start: START.into(), cliff: CLIFF.into(), end: END.into() };
+        lockup.internal_create(&mut token, alice.account_id(), bob.account_id(), 1_000, schedule);
+        (alice, bob, token, lockup)
+    }
+
+    #[test]
+    fn vesting_schedule() {
+        let schedule =
+            VestingSchedule { start: START.into(), cliff: CLIFF.into(), end: END.into() };
+        assert_eq!(schedule.vested_at(1_000, CLIFF - 1), 0);
+        assert_eq!(schedule.vested_at(1_000, CLIFF), 250);
+        assert_eq!(schedule.vested_at(1_000, 1_500), 500);
+        assert_eq!(schedule.vested_at(1_000, END), 1_000);
+        assert_eq!(schedule.vested_at(u128::MAX, 1_500), u128::MAX / 2);
+
+        let cliff_only =
+            VestingSchedule { start: START.into(), cliff: END.into(), end: END.into() };
+        assert_eq!(cliff_only.vested_at(1_000, END - 1), 0);
+        assert_eq!(cliff_only.vested_at(1_000, END), 1_000);
+    }
+
+    #[test]
+    fn claim() {
+        let (alice, bob, mut token, mut lockup) = setup();
+        assert_eq!(token.internal_unwrap_balance_of(alice.account_id()), 0);
+        assert_eq!(token.internal_unwrap_balance_of(&lockup_account()), 1_000);
+
+        set_context(&bob, 1_500);
+        assert_eq!(lockup.vested_amount(bob.account_id()), 500);
+        assert_eq!(lockup.unvested_amount(bob.account_id()), 500);
+        assert_eq!(lockup.claim(&mut token, bob.account_id()), 500);
+        assert_eq!(token.internal_unwrap_balance_of(bob.account_id()), 500);
+        assert_eq!(
+            get_logs().last().unwrap(),
+            r#"EVENT_JSON:{"standard":"token_lockup","version":"1.0.0","event":"lockup_claimed","data":{"account_id":"bob","amount":"500"}}"#
+        );
+
+        set_context(&bob, END);
+        assert_eq!(lockup.claim(&mut token, bob.account_id()), 500);
+        assert_eq!(token.internal_unwrap_balance_of(bob.account_id()), 1_000);
+        assert!(lockup.lockup_of(bob.account_id()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "The account has no vested tokens to claim")]
+    fn claim_before_cliff() {
+        let (_, bob, mut token, mut lockup) = setup();
+        set_context(&bob, CLIFF - 1);
+        lockup.claim(&mut token, bob.account_id());
+    }
+
+    #[test]
+    fn terminate() {
+        let (alice, bob, mut token, mut lockup) = setup();
+        let ownable = Ownable::new(alice.account_id().clone());
+
+        set_context(&alice, 1_500);
+        assert_eq!(
+            lockup.terminate(&ownable, &mut token, bob.account_id(), alice.account_id()),
+            500
+        );
+        assert_eq!(token.internal_unwrap_balance_of(alice.account_id()), 500);
+        assert_eq!(lockup.vested_amount(bob.account_id()), 500);
+        assert_eq!(lockup.unvested_amount(bob.account_id()), 0);
+
+        // The tokens vested before the termination stay claimable, and no more vest.
+        set_context(&bob, END);
+        assert_eq!(lockup.vested_amount(bob.account_id()), 500);
+        assert_eq!(lockup.claim(&mut token, bob.account_id()), 500);
+        assert!(lockup.lockup_of(bob.account_id()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner role can terminate lockups")]
+    fn terminate_without_role() {
+        let (alice, bob, mut token, mut lockup) = setup();
+        let ownable = Ownable::new(alice.account_id().clone());
+
+        set_context(&bob, 1_500);
+        lockup.terminate(&ownable, &mut token, bob.account_id(), bob.account_id());
+    }
+}
@@ -0,0 +1,96 @@
+use crate::upgradable::{MigrationCall, StagedCode};
+use near_sdk::json_types::{Base58CryptoHash, Base64VecU8, U64};
+use near_sdk::{ext_contract, Promise};
+
+/// The contract methods of an upgradable contract.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::json_types::{Base58CryptoHash, Base64VecU8, U64};
+/// use near_sdk::{near, AccountId, PanicOnDefault, Promise, OWNER_ROLE};
+/// use near_contract_standards::ownable::Ownable;
+/// use near_contract_standards::upgradable::{
+///     MigrationCall, StagedCode, Upgradable, UpgradableCore,
+/// };
+///
+/// /// One day, in nanoseconds.
+/// const TIMELOCK: u64 = 24 * 60 * 60 * 1_000_000_000;
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     ownable: Ownable,
+///     upgradable: Upgradable,
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     #[init]
+///     pub fn new(owner_id: AccountId) -> Self {
+///         Self {
+///             ownable: Ownable::new(owner_id),
+///             upgradable: Upgradable::new(b"u", TIMELOCK, OWNER_ROLE),
+///         }
+///     }
+/// }
+///
+/// #[near]
+/// impl UpgradableCore for Contract {
+///     fn up_staged_code(&self) -> Option<StagedCode> {
+///         self.upgradable.staged_code().cloned()
+///     }
+///
+///     fn up_timelock(&self) -> U64 {
+///         self.upgradable.timelock().into()
+///     }
+///
+///     fn up_stage_code(&mut self, code: Base64VecU8) -> StagedCode {
+///         self.upgradable.stage_code(&self.ownable, code.0)
+///     }
+///
+///     fn up_unstage_code(&mut self) {
+///         self.upgradable.unstage_code(&self.ownable);
+///     }
+///
+///     fn up_deploy_code(
+///         &mut self,
+///         code_hash: Base58CryptoHash,
+///         migration: Option<MigrationCall>,
+///     ) -> Promise {
+///         self.upgradable.deploy_code(&self.ownable, code_hash.into(), migration)
+///     }
+///
+///     fn up_set_timelock(&mut self, timelock: U64) {
+///         self.upgradable.set_timelock(&self.ownable, timelock.into());
+///     }
+/// }
+/// ```
+#[ext_contract(ext_upgradable)]
+pub trait UpgradableCore {
+    /// Returns the staged code, if any.
+    fn up_staged_code(&self) -> Option<StagedCode>;
+
+    /// Returns the duration in nanoseconds between the staging of code and its deployment.
+    fn up_timelock(&self) -> U64;
+
+    /// Stages `code`, replacing the code staged before, and returns its hash and when it can be
+    /// deployed. Can only be called by the accounts having the manager role.
+    fn up_stage_code(&mut self, code: Base64VecU8) -> StagedCode;
+
+    /// Removes the staged code. Can only be called by the accounts having the manager role.
+    fn up_unstage_code(&mut self);
+
+    /// Deploys the staged code with the hash `code_hash` once the timelock has passed, followed
+    /// by the `migration` call in the same batch. Can only be called by the accounts having the
+    /// manager role.
+    fn up_deploy_code(
+        &mut self,
+        code_hash: Base58CryptoHash,
+        migration: Option<MigrationCall>,
+    ) -> Promise;
+
+    /// Sets the timelock of the code staged from now on. Can only be called by the accounts
+    /// having the manager role.
+    fn up_set_timelock(&mut self, timelock: U64);
+}
@@ -0,0 +1,22 @@
+//! Upgradable component staging new code of the contract behind a timelock.
+//!
+//! An [`Upgradable`] keeps the code of the next version of the contract, staged by the accounts
+//! having its manager role, e.g. [`OWNER_ROLE`](near_sdk::OWNER_ROLE) or the role of a DAO. The
+//! staged code can only be deployed once the timelock has passed since it was staged, so that the
+//! users of the contract can review it, identified by its sha256 hash, and leave before the
+//! upgrade. The deployment and the optional migration call run in the same batch of actions, so
+//! that the new code never runs on the old state. Every change is logged as an
+//! [`UpgradableEvent`].
+//!
+//! The roles are checked through any [`HasRoles`](near_sdk::HasRoles) implementation, usually
+//! the [`Ownable`](crate::ownable::Ownable) or
+//! [`AccessControl`](crate::access_control::AccessControl) of the contract, see
+//! [`UpgradableCore`] for an example.
+//!
+//! This component replaces the deprecated `upgrade` module.
+
+mod core;
+mod upgradable_impl;
+
+pub use self::core::{ext_upgradable, UpgradableCore};
+pub use upgradable_impl::{MigrationCall, StagedCode, Upgradable, UpgradableEvent};
@@ -0,0 +1,227 @@
+use near_sdk::json_types::{Base58CryptoHash, Base64VecU8, U64};
+use near_sdk::{
+    env, near, require, CryptoHash, Duration, Gas, HasRoles, IntoStorageKey, NearToken, Promise,
+};
+
+/// Changes of the code of an [`Upgradable`] contract.
+#[near(event_json(standard = "upgradable"))]
+pub enum UpgradableEvent<'a> {
+    /// Code with the hash `code_hash` was staged, and can be deployed from `deployable_at`.
+    #[event_version("1.0.0")]
+    CodeStaged { code_hash: Base58CryptoHash, deployable_at: U64 },
+    /// The staged code with the hash `code_hash` was removed.
+    #[event_version("1.0.0")]
+    CodeUnstaged { code_hash: Base58CryptoHash },
+    /// The staged code with the hash `code_hash` was deployed, followed by the `migration` method.
+    #[event_version("1.0.0")]
+    CodeDeployed { code_hash: Base58CryptoHash, migration: Option<&'a str> },
+    /// The timelock of the code staged from now on was set to `timelock` nanoseconds.
+    #[event_version("1.0.0")]
+    TimelockChanged { timelock: U64 },
+}
+
+/// Code staged in an [`Upgradable`].
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StagedCode {
+    /// Sha256 hash of the code.
+    pub code_hash: Base58CryptoHash,
+    /// Block timestamp in nanoseconds from which the code can be deployed.
+    pub deployable_at: U64,
+}
+
+/// Call of the migration method of the new code, in the batch deploying it.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MigrationCall {
+    pub method_name: String,
+    pub args: Base64VecU8,
+    pub gas: Gas,
+}
+
+/// Implementation of the staging and deployment of the code of the contract, see the
+/// [module documentation](crate::upgradable).
+///
+/// The methods take the [`HasRoles`] implementation checking that the predecessor has the
+/// manager role, e.g. the [`Ownable`](crate::ownable::Ownable) of the contract.
+#[near]
+pub struct Upgradable {
+    /// Storage key of the staged code, which is written directly into storage to avoid reading
+    /// it with the state of the contract.
+    pub code_key: Vec<u8>,
+    /// Role of the accounts managing the upgrades.
+    pub manager_role: String,
+    /// Duration in nanoseconds between the staging of code and its deployment.
+    pub timelock: Duration,
+    pub staged: Option<StagedCode>,
+}
+
+impl Upgradable {
+    pub fn new<S>(prefix: S, timelock: Duration, manager_role: &str) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self {
+            code_key: [prefix.into_storage_key().as_slice(), b"c"].concat(),
+            manager_role: manager_role.to_string(),
+            timelock,
+            staged: None,
+        }
+    }
+
+    /// Returns the duration in nanoseconds between the staging of code and its deployment.
+    pub fn timelock(&self) -> Duration {
+        self.timelock
+    }
+
+    /// Returns the staged code, if any.
+    pub fn staged_code(&self) -> Option<&StagedCode> {
+        self.staged.as_ref()
+    }
+
+    /// Panics unless the predecessor has the manager role in `roles`.
+    pub fn assert_manager(&self, roles: &impl HasRoles) {
+        if !roles.has_role(&self.manager_role, &env::predecessor_account_id()) {
+            env::panic_str(&format!("Only the {} role can manage upgrades", self.manager_role));
+        }
+    }
+
+    /// Stages `code` if the predecessor has the manager role, replacing the code staged before.
+    pub fn stage_code(&mut self, roles: &impl HasRoles, code: Vec<u8>) -> StagedCode {
+        self.assert_manager(roles);
+        require!(!code.is_empty(), "The code can't be empty");
+        let staged = StagedCode {
+            code_hash: env::sha256_array(&code).into(),
+            deployable_at: env::block_timestamp().saturating_add(self.timelock).into(),
+        };
+        env::storage_write(&self.code_key, &code);
+        self.staged = Some(staged.clone());
+        UpgradableEvent::CodeStaged {
+            code_hash: staged.code_hash,
+            deployable_at: staged.deployable_at,
+        }
+        .emit();
+        staged
+    }
+
+    /// Removes the staged code if the predecessor has the manager role.
+    pub fn unstage_code(&mut self, roles: &impl HasRoles) {
+        self.assert_manager(roles);
+        if let Some(staged) = self.staged.take() {
+            env::storage_remove(&self.code_key);
+            UpgradableEvent::CodeUnstaged { code_hash: staged.code_hash }.emit();
+        }
+    }
+
+    /// Deploys the staged code if the predecessor has the manager role, once the timelock has
+    /// passed, followed by the `migration` call in the same batch.
+    ///
+    /// The hash of the staged code must be `code_hash`, so that the code reviewed by the manager
+    /// is the one deployed.
+    pub fn deploy_code(
+        &mut self,
+        roles: &impl HasRoles,
+        code_hash: CryptoHash,
+        migration: Option<MigrationCall>,
+    ) -> Promise {
+        self.assert_manager(roles);
+        let staged = self.staged.take().unwrap_or_else(|| env::panic_str("No code is staged"));
+        require!(
+            CryptoHash::from(staged.code_hash) == code_hash,
+            "The hash of the staged code doesn't match"
+        );
+        if env::block_timestamp() < staged.deployable_at.0 {
+            env::panic_str(&format!(
+                "The staged code can't be deployed before {}",
+                staged.deployable_at.0
+            ));
+        }
+        let code = env::storage_read(&self.code_key)
+            .unwrap_or_else(|| env::panic_str("No code is staged"));
+        env::storage_remove(&self.code_key);
+
+        UpgradableEvent::CodeDeployed {
+            code_hash: staged.code_hash,
+            migration: migration.as_ref().map(|migration| migration.method_name.as_str()),
+        }
+        .emit();
+        let promise = Promise::new(env::current_account_id()).deploy_contract(code);
+        match migration {
+            Some(MigrationCall { method_name, args, gas }) => {
+                promise.function_call(method_name, args.0, NearToken::from_yoctonear(0), gas)
+            }
+            None => promise,
+        }
+    }
+
+    /// Sets the timelock of the code staged from now on if the predecessor has the manager role.
+    /// The code staged before keeps its deployment time.
+    pub fn set_timelock(&mut self, roles: &impl HasRoles, timelock: Duration) {
+        self.assert_manager(roles);
+        self.timelock = timelock;
+        UpgradableEvent::TimelockChanged { timelock: timelock.into() }.emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ownable::Ownable;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, AccountId};
+
+    const TIMELOCK: Duration = 1_000;
+
+    fn set_context(account_id: AccountId, block_timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account_id)
+            .block_timestamp(block_timestamp)
+            .build());
+    }
+
+    #[test]
+    fn stage_and_deploy() {
+        let owner = Ownable::new(accounts(0));
+        let mut upgradable = Upgradable::new(b"u", TIMELOCK, near_sdk::OWNER_ROLE);
+        set_context(accounts(0), 10);
+        let staged = upgradable.stage_code(&owner, b"code".to_vec());
+        assert_eq!(staged.deployable_at, U64(10 + TIMELOCK));
+        assert_eq!(upgradable.staged_code(), Some(&staged));
+
+        set_context(accounts(0), 10 + TIMELOCK);
+        upgradable.deploy_code(&owner, env::sha256_array(b"code").into(), None);
+        assert_eq!(upgradable.staged_code(), None);
+        assert!(!env::storage_has_key(&upgradable.code_key));
+    }
+
+    #[test]
+    #[should_panic(expected = "The staged code can't be deployed before 1010")]
+    fn deploy_before_timelock() {
+        let owner = Ownable::new(accounts(0));
+        let mut upgradable = Upgradable::new(b"u", TIMELOCK, near_sdk::OWNER_ROLE);
+        set_context(accounts(0), 10);
+        upgradable.stage_code(&owner, b"code".to_vec());
+        set_context(accounts(0), 1_009);
+        upgradable.deploy_code(&owner, env::sha256_array(b"code").into(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The hash of the staged code doesn't match")]
+    fn deploy_other_code() {
+        let owner = Ownable::new(accounts(0));
+        let mut upgradable = Upgradable::new(b"u", TIMELOCK, near_sdk::OWNER_ROLE);
+        set_context(accounts(0), 10);
+        upgradable.stage_code(&owner, b"code".to_vec());
+        set_context(accounts(0), 10 + TIMELOCK);
+        upgradable.deploy_code(&owner, env::sha256_array(b"other code").into(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner role can manage upgrades")]
+    fn stage_without_role() {
+        let owner = Ownable::new(accounts(0));
+        let mut upgradable = Upgradable::new(b"u", TIMELOCK, near_sdk::OWNER_ROLE);
+        set_context(accounts(1), 10);
+        upgradable.stage_code(&owner, b"code".to_vec());
+    }
+}
@@ -0,0 +1,44 @@
+use near_sdk::json_types::U128;
+use near_sdk::{ext_contract, Promise};
+
+/// The contract methods wrapping and unwrapping native NEAR.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{near, PanicOnDefault, Promise};
+/// use near_sdk::json_types::U128;
+/// use near_contract_standards::fungible_token::FungibleToken;
+/// use near_contract_standards::wrap_near::WrapNearCore;
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Contract {
+///     token: FungibleToken,
+/// }
+///
+/// #[near]
+/// impl WrapNearCore for Contract {
+///     #[payable]
+///     fn near_deposit(&mut self) {
+///         self.token.near_deposit()
+///     }
+///
+///     #[payable]
+///     fn near_withdraw(&mut self, amount: U128) -> Promise {
+///         self.token.near_withdraw(amount)
+///     }
+/// }
+/// ```
+#[ext_contract(ext_wrap_near)]
+pub trait WrapNearCore {
+    /// Wraps the attached deposit into tokens of the predecessor.
+    ///
+    /// If the predecessor is not registered with the token, the storage of its account is paid
+    /// out of the deposit first, and only the rest is wrapped.
+    fn near_deposit(&mut self);
+
+    /// Unwraps `amount` tokens of the predecessor and transfers as much NEAR to it.
+    /// Exactly 1 yoctoNEAR must be attached.
+    fn near_withdraw(&mut self, amount: U128) -> Promise;
+}
@@ -0,0 +1,21 @@
+//! Wrapped NEAR component, holding native NEAR as a NEP-141 balance of the contract (the wNEAR
+//! pattern).
+//!
+//! [`WrapNearCore`] is implemented by [`FungibleToken`](crate::fungible_token::FungibleToken):
+//! `near_deposit` mints the attached deposit to the predecessor, registering its account with
+//! the token first if needed, and `near_withdraw` burns tokens and transfers as much NEAR back.
+//! The wrapped balance is an ordinary fungible token balance, so the contract also implements
+//! [`FungibleTokenCore`](crate::fungible_token::FungibleTokenCore) and
+//! [`StorageManagement`](crate::storage_management::StorageManagement) with the same token, and
+//! [`FungibleTokenMetadata`](crate::fungible_token::metadata::FungibleTokenMetadata) with 24
+//! decimals.
+//!
+//! Deposits and withdrawals emit the standard [`FtMint`] and [`FtBurn`] events.
+//!
+//! [`FtMint`]: crate::fungible_token::events::FtMint
+//! [`FtBurn`]: crate::fungible_token::events::FtBurn
+
+mod core;
+mod wrap_near_impl;
+
+pub use self::core::{ext_wrap_near, WrapNearCore};
@@ -0,0 +1,125 @@
+use crate::fungible_token::events::{FtBurn, FtMint};
+use crate::fungible_token::{Balance, FungibleToken};
+use crate::storage_management::StorageManagement;
+use crate::wrap_near::WrapNearCore;
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, log, require, AccountId, NearToken, Promise};
+
+impl FungibleToken {
+    /// Wraps `deposit` into tokens of `account_id`, registering the account first if needed, in
+    /// which case the storage of the account is paid out of `deposit`.
+    /// Returns the amount of wrapped tokens.
+    pub fn internal_near_deposit(&mut self, account_id: &AccountId, deposit: NearToken) -> Balance {
+        let mut amount = deposit.as_yoctonear();
+        if !self.accounts.contains_key(account_id) {
+            let storage_cost = self.storage_balance_bounds().min.as_yoctonear();
+            require!(
+                amount > storage_cost,
+                format!("The deposit should be more than {} to register the account", storage_cost)
+            );
+            self.internal_register_account(account_id);
+            log!("Registered the account {} with the token", account_id);
+            amount -= storage_cost;
+        }
+        require!(amount > 0, "The deposit should be a positive number");
+        self.internal_deposit(account_id, amount);
+        FtMint { owner_id: account_id, amount: U128(amount), memo: Some("wrap") }.emit();
+        amount
+    }
+
+    /// Unwraps `amount` tokens of `account_id`. Returns the transfer of the NEAR to the account.
+    pub fn internal_near_withdraw(&mut self, account_id: &AccountId, amount: Balance) -> Promise {
+        require!(amount > 0, "The amount should be a positive number");
+        self.internal_withdraw(account_id, amount);
+        FtBurn { owner_id: account_id, amount: U128(amount), memo: Some("unwrap") }.emit();
+        Promise::new(account_id.clone()).transfer(NearToken::from_yoctonear(amount))
+    }
+}
+
+impl WrapNearCore for FungibleToken {
+    fn near_deposit(&mut self) {
+        self.internal_near_deposit(&env::predecessor_account_id(), env::attached_deposit());
+    }
+
+    fn near_withdraw(&mut self, amount: U128) -> Promise {
+        assert_one_yocto();
+        self.internal_near_withdraw(&env::predecessor_account_id(), amount.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fungible_token::FungibleTokenCore;
+    use crate::test_utils::{alice, bob};
+    use near_sdk::test_utils::get_logs;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn deposit_registers_the_account() {
+        let alice = alice();
+        let mut token = FungibleToken::new(b"t");
+        let storage_cost = token.storage_balance_bounds().min.as_yoctonear();
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_near(1)).build());
+        token.near_deposit();
+        let wrapped = NearToken::from_near(1).as_yoctonear() - storage_cost;
+        assert_eq!(token.ft_balance_of(alice.account_id().clone()), U128(wrapped));
+        assert_eq!(token.ft_total_supply(), U128(wrapped));
+
+        // The account is registered now, the whole deposit is wrapped.
+        testing_env!(alice.context().attached_deposit(NearToken::from_near(1)).build());
+        token.near_deposit();
+        assert_eq!(
+            token.ft_balance_of(alice.account_id().clone()),
+            U128(wrapped + NearToken::from_near(1).as_yoctonear())
+        );
+        assert_eq!(
+            get_logs(),
+            vec![format!(
+                r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"ft_mint","data":[{{"owner_id":"alice","amount":"{}","memo":"wrap"}}]}}"#,
+                NearToken::from_near(1).as_yoctonear()
+            )]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "to register the account")]
+    fn deposit_below_storage_cost() {
+        let alice = alice();
+        let mut token = FungibleToken::new(b"t");
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.near_deposit();
+    }
+
+    #[test]
+    fn withdraw() {
+        let (alice, bob) = (alice().with_ft_balance(100), bob().with_ft_balance(50));
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+        bob.setup_ft(&mut token);
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.near_withdraw(U128(40));
+        assert_eq!(token.ft_balance_of(alice.account_id().clone()), U128(60));
+        assert_eq!(token.ft_total_supply(), U128(110));
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_burn","data":[{"owner_id":"alice","amount":"40","memo":"unwrap"}]}"#
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough balance")]
+    fn withdraw_more_than_balance() {
+        let alice = alice().with_ft_balance(100);
+        let mut token = FungibleToken::new(b"t");
+        alice.setup_ft(&mut token);
+
+        testing_env!(alice.context().attached_deposit(NearToken::from_yoctonear(1)).build());
+        token.near_withdraw(U128(101));
+    }
+}
@@ -1,9 +1,10 @@
-use proc_macro2::{Span, TokenStream as TokenStream2};
+use proc_macro2::{Ident, Literal, Span, TokenStream as TokenStream2};
 use quote::{format_ident, quote};
 use syn::{parse_quote, Attribute, Expr, Lit::Str, Meta::NameValue, MetaNameValue, Type};
 
 use crate::core_impl::{
-    utils, BindgenArgType, ImplItemMethodInfo, ItemImplInfo, MethodKind, ReturnKind, SerializerType,
+    utils, AbiHints, ArgDefault, BindgenArgType, Diagnostic, ImplItemMethodInfo, ItemImplInfo,
+    MethodKind, ReturnKind, SerializerType,
 };
 
 pub fn generate(i: &ItemImplInfo) -> TokenStream2 {
@@ -13,23 +14,63 @@ pub fn generate(i: &ItemImplInfo) -> TokenStream2 {
     }
 
     let functions: Vec<TokenStream2> = i.methods.iter().map(|m| m.abi_struct()).collect();
+    let errors: Vec<TokenStream2> = i.methods.iter().filter_map(|m| m.abi_error_tokens()).collect();
+    let hints: Vec<TokenStream2> = i.methods.iter().filter_map(|m| m.abi_hints_tokens()).collect();
+    let serializers: Vec<TokenStream2> =
+        i.methods.iter().filter_map(|m| m.abi_serializers_tokens()).collect();
     let first_function_name = &i.methods[0].attr_signature_info.ident;
     let near_abi_symbol = format_ident!("__near_abi_{}", first_function_name);
+    // The schemas of error types and of types serialized with an `ArgsSerializer` which don't
+    // implement `JsonSchema` fall back to this trait.
+    let schema_fallback = (!errors.is_empty()
+        || i.methods.iter().any(|m| m.attr_signature_info.uses_custom_serializer()))
+    .then(|| quote! { use ::near_sdk::__private::AbiSchemaFallback as _; });
+    generate_abi_symbol(
+        &near_abi_symbol,
+        quote! {
+            #schema_fallback
+            let mut gen = ::near_sdk::schemars::gen::SchemaGenerator::default();
+            let functions = vec![#(#functions),*];
+            let errors = vec![#(#errors),*];
+            let hints = vec![#(#hints),*];
+            let serializers = vec![#(#serializers),*];
+            ::near_sdk::__private::ChunkedAbiEntry::new(
+                functions,
+                ::near_sdk::__private::methods_root_schema(gen, errors, hints, serializers),
+            )
+        },
+    )
+}
+
+/// Generates the ABI of the contract itself, without functions, whose root schema has the doc
+/// comment of the contract state and its NEP-330 source metadata, from the
+/// `CONTRACT_SOURCE_METADATA` constant.
+pub fn generate_contract(attrs: &[Attribute]) -> TokenStream2 {
+    let doc = match parse_rustdoc(attrs) {
+        Some(doc) => quote! { ::std::option::Option::Some(#doc) },
+        None => quote! { ::std::option::Option::None },
+    };
+    generate_abi_symbol(
+        &format_ident!("__near_abi___contract"),
+        quote! {
+            ::near_sdk::__private::ChunkedAbiEntry::new(
+                ::std::vec::Vec::new(),
+                ::near_sdk::__private::contract_root_schema(#doc, CONTRACT_SOURCE_METADATA),
+            )
+        },
+    )
+}
+
+/// Generates the symbol returning the serialized ABI chunk built by `entry`.
+fn generate_abi_symbol(symbol: &Ident, entry: TokenStream2) -> TokenStream2 {
     quote! {
         #[cfg(not(target_arch = "wasm32"))]
         const _: () = {
             #[no_mangle]
-            pub extern "C" fn #near_abi_symbol() -> (*const u8, usize) {
-                use ::std::string::String;
-
-                let mut gen = ::near_sdk::schemars::gen::SchemaGenerator::default();
-                let functions = vec![#(#functions),*];
+            pub extern "C" fn #symbol() -> (*const u8, usize) {
+                let entry = { #entry };
                 let mut data = ::std::mem::ManuallyDrop::new(
-                    ::near_sdk::serde_json::to_vec(&::near_sdk::__private::ChunkedAbiEntry::new(
-                        functions,
-                        gen.into_root_schema_for::<String>(),
-                    ))
-                    .unwrap(),
+                    ::near_sdk::serde_json::to_vec(&entry).unwrap(),
                 );
                 data.shrink_to_fit();
                 assert!(data.len() == data.capacity());
@@ -113,9 +154,20 @@ impl ImplItemMethodInfo {
             let arg_name = arg.ident.to_string();
             match arg.bindgen_ty {
                 BindgenArgType::Regular => {
-                    let schema = generate_schema(typ, &arg.serializer_ty);
+                    let mut schema = generate_schema(typ, &arg.serializer_ty);
+                    if let Some(default) = &arg.default {
+                        let value = match default {
+                            ArgDefault::Default => {
+                                quote! { <#typ as ::std::default::Default>::default() }
+                            }
+                            ArgDefault::Expr(expr) => quote! { { let value: #typ = #expr; value } },
+                        };
+                        schema = quote! {
+                            ::near_sdk::__private::schema_with_default(#schema, #value)
+                        };
+                    }
                     match arg.serializer_ty {
-                        SerializerType::JSON => params.push(quote! {
+                        SerializerType::JSON | SerializerType::Custom(_) => params.push(quote! {
                             ::near_sdk::__private::AbiJsonParameter {
                                 name: ::std::string::String::from(#arg_name),
                                 type_schema: #schema,
@@ -132,19 +184,38 @@ impl ImplItemMethodInfo {
                 BindgenArgType::CallbackArg => {
                     callbacks.push(generate_abi_type(typ, &arg.serializer_ty));
                 }
+                // Set from the attached deposit rather than passed by the caller.
+                BindgenArgType::Deposit => {}
                 BindgenArgType::CallbackResultArg => {
                     let typ = if let Some(ok_type) = utils::extract_ok_type(typ) {
                         ok_type
                     } else {
-                        return syn::Error::new_spanned(
-                            &arg.ty,
-                            "Function parameters marked with \
+                        return Diagnostic::CallbackResultType
+                            .error_spanned(
+                                &arg.ty,
+                                "Function parameters marked with \
                                 #[callback_result] should have type Result<T, PromiseError>",
-                        )
-                        .into_compile_error();
+                            )
+                            .into_compile_error();
                     };
                     callbacks.push(generate_abi_type(typ, &arg.serializer_ty));
                 }
+                BindgenArgType::CallbackJoinArg => {
+                    let types = if let Some(ok_types) = utils::extract_join_ok_types(typ) {
+                        ok_types
+                    } else {
+                        return Diagnostic::CallbackResultType
+                            .error_spanned(
+                                &arg.ty,
+                                "Function parameters marked with #[callback_join] should have \
+                                type (Result<T1, PromiseError>, Result<T2, PromiseError>, ...)",
+                            )
+                            .into_compile_error();
+                    };
+                    callbacks.extend(
+                        types.into_iter().map(|typ| generate_abi_type(typ, &arg.serializer_ty)),
+                    );
+                }
                 BindgenArgType::CallbackArgVec => {
                     if callback_vec.is_none() {
                         let typ = if let Some(vec_type) = utils::extract_vec_type(typ) {
@@ -157,7 +228,8 @@ impl ImplItemMethodInfo {
                             .into_compile_error();
                         };
 
-                        callback_vec = Some(self.abi_callback_vec_tokens(typ));
+                        let abi_type = generate_abi_type(typ, &arg.serializer_ty);
+                        callback_vec = Some(quote! { ::std::option::Option::Some(#abi_type) });
                     } else {
                         return syn::Error::new(
                             Span::call_site(),
@@ -169,7 +241,7 @@ impl ImplItemMethodInfo {
             };
         }
         let params = match attr_signature_info.input_serializer {
-            SerializerType::JSON => quote! {
+            SerializerType::JSON | SerializerType::Custom(_) => quote! {
                 ::near_sdk::__private::AbiParameters::Json {
                     args: ::std::vec![#(#params),*]
                 }
@@ -198,6 +270,96 @@ impl ImplItemMethodInfo {
         }
     }
 
+    /// Generates the entry of the error type of this function in the ABI, `(name, schema)`, if it
+    /// returns a `Result` with `#[handle_result]`.
+    fn abi_error_tokens(&self) -> Option<TokenStream2> {
+        match &self.attr_signature_info.returns.kind {
+            ReturnKind::HandlesResult(ty) => {
+                let function_name_str = self.attr_signature_info.ident.to_string();
+                Some(quote! {
+                    (
+                        #function_name_str,
+                        ::near_sdk::__private::AbiSchema::<
+                            <#ty as ::near_sdk::__private::ResultTypeExt>::Error
+                        >::new().schema(&mut gen),
+                    )
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Generates the entry of the hints of this function in the ABI, `(name, hints)`, if they are
+    /// set with `#[near(abi(...))]`.
+    fn abi_hints_tokens(&self) -> Option<TokenStream2> {
+        let AbiHints { min_deposit, recommended_gas } = &self.attr_signature_info.abi_hints;
+        if min_deposit.is_none() && recommended_gas.is_none() {
+            return None;
+        }
+        let function_name_str = self.attr_signature_info.ident.to_string();
+        let min_deposit = match min_deposit {
+            Some(amount) => {
+                let amount = Literal::u128_unsuffixed(*amount);
+                quote! {
+                    ::std::option::Option::Some(::near_sdk::NearToken::from_yoctonear(#amount))
+                }
+            }
+            None => quote! { ::std::option::Option::None },
+        };
+        let recommended_gas = match recommended_gas {
+            Some(gas) => {
+                let gas = Literal::u64_unsuffixed(*gas);
+                quote! { ::std::option::Option::Some(::near_sdk::Gas::from_gas(#gas)) }
+            }
+            None => quote! { ::std::option::Option::None },
+        };
+        Some(quote! {
+            (
+                #function_name_str,
+                ::near_sdk::__private::AbiMethodHints {
+                    min_deposit: #min_deposit,
+                    recommended_gas: #recommended_gas,
+                },
+            )
+        })
+    }
+
+    /// Generates the entry of the formats of the arguments and result of this function in the
+    /// ABI, `(name, serializers)`, if they are serialized with an `ArgsSerializer`.
+    fn abi_serializers_tokens(&self) -> Option<TokenStream2> {
+        let attr_signature_info = &self.attr_signature_info;
+        let format = |serializer: &SerializerType| match serializer {
+            SerializerType::Custom(serializer) => Some(quote! {
+                ::std::option::Option::Some(
+                    <#serializer as ::near_sdk::ArgsSerializer>::FORMAT
+                )
+            }),
+            SerializerType::JSON | SerializerType::Borsh => None,
+        };
+        let args = attr_signature_info
+            .has_input_args()
+            .then(|| format(&attr_signature_info.input_serializer))
+            .flatten();
+        let result = match (&attr_signature_info.method_kind, &attr_signature_info.returns.kind) {
+            (MethodKind::Init(_), _) | (_, ReturnKind::Default) => None,
+            (MethodKind::Call(call_method), _) => format(&call_method.result_serializer),
+            (MethodKind::View(view_method), _) => format(&view_method.result_serializer),
+        };
+        if args.is_none() && result.is_none() {
+            return None;
+        }
+        let function_name_str = attr_signature_info.ident.to_string();
+        let none = quote! { ::std::option::Option::None };
+        let args = args.unwrap_or_else(|| none.clone());
+        let result = result.unwrap_or(none);
+        Some(quote! {
+            (
+                #function_name_str,
+                ::near_sdk::__private::AbiMethodSerializers { args: #args, result: #result },
+            )
+        })
+    }
+
     fn abi_result_tokens(&self) -> TokenStream2 {
         use ReturnKind::*;
 
@@ -227,21 +389,6 @@ impl ImplItemMethodInfo {
             View(view_method) => some_abi_type(&view_method.result_serializer),
         }
     }
-
-    fn abi_callback_vec_tokens(&self, callback_vec_type: &Type) -> TokenStream2 {
-        let abi_type = |result_serializer: &SerializerType| {
-            let tokens = generate_abi_type(callback_vec_type, result_serializer);
-            quote! {
-                ::std::option::Option::Some(#tokens)
-            }
-        };
-
-        match &self.attr_signature_info.method_kind {
-            MethodKind::Call(call_method) => abi_type(&call_method.result_serializer),
-            MethodKind::Init(_) => quote! { ::std::option::Option::None },
-            MethodKind::View(view_method) => abi_type(&view_method.result_serializer),
-        }
-    }
 }
 
 fn generate_schema(ty: &Type, serializer_type: &SerializerType) -> TokenStream2 {
@@ -252,13 +399,16 @@ fn generate_schema(ty: &Type, serializer_type: &SerializerType) -> TokenStream2
         SerializerType::Borsh => quote! {
             ::near_sdk::borsh::schema_container_of::<#ty>()
         },
+        SerializerType::Custom(_) => quote! {
+            ::near_sdk::__private::AbiSchema::<#ty>::new().schema(&mut gen)
+        },
     }
 }
 
 fn generate_abi_type(ty: &Type, serializer_type: &SerializerType) -> TokenStream2 {
     let schema = generate_schema(ty, serializer_type);
     match serializer_type {
-        SerializerType::JSON => quote! {
+        SerializerType::JSON | SerializerType::Custom(_) => quote! {
             ::near_sdk::__private::AbiType::Json {
                 type_schema: #schema,
             }
@@ -345,6 +495,103 @@ mod tests {
         local_insta_assert_snapshot!(pretty_print_fn_body_syn_str(actual));
     }
     
+    #[test]
+    fn test_generate_abi_errors() {
+        let impl_type: Type = syn::parse_str("Test").unwrap();
+        let mut method = parse_quote! {
+            #[handle_result]
+            pub fn f3(&mut self, arg0: FancyStruct) -> Result<IsOk, Error> { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type.clone()).unwrap().unwrap();
+        let actual = method_info.abi_error_tokens().unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_fn_body_syn_str(quote! { let error = #actual; }));
+
+        let mut method = parse_quote! {
+            pub fn f4(&self) -> IsOk { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        assert!(method_info.abi_error_tokens().is_none());
+    }
+
+    #[test]
+    fn test_generate_abi_hints() {
+        let impl_type: Type = syn::parse_str("Test").unwrap();
+        let mut method = parse_quote! {
+            #[payable]
+            #[near(abi(min_deposit = "1 yocto", recommended_gas = "30 Tgas"))]
+            pub fn buy(&mut self) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type.clone()).unwrap().unwrap();
+        let actual = method_info.abi_hints_tokens().unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_fn_body_syn_str(quote! { let hints = #actual; }));
+
+        let mut method = parse_quote! {
+            #[near(abi(recommended_gas = "5 Tgas"))]
+            pub fn get(&self) -> u64 { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type.clone()).unwrap().unwrap();
+        assert!(method_info.abi_hints_tokens().unwrap().to_string().contains("from_gas (5000000000000)"));
+
+        let mut method = parse_quote! {
+            #[near(abi(min_deposit = "1 yocto"))]
+            pub fn set(&mut self) { }
+        };
+        let err = ImplItemMethodInfo::new(&mut method, None, impl_type).err().unwrap();
+        assert_eq!(err.to_string(), "`min_deposit` in `#[near(abi(...))]` requires the method to be #[payable].");
+    }
+
+    #[test]
+    fn test_generate_abi_custom_serializer() {
+        let impl_type: Type = syn::parse_str("Test").unwrap();
+        let mut method = parse_quote! {
+            #[result_serializer(with = Cbor)]
+            pub fn swap(&mut self, #[serializer(with = Cbor)] order: Order, #[serializer(with = Cbor)] min_out: u64) -> Receipt { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type.clone()).unwrap().unwrap();
+        let actual = method_info.abi_struct();
+        local_insta_assert_snapshot!(pretty_print_fn_body_syn_str(actual));
+        let serializers = method_info.abi_serializers_tokens().unwrap();
+        assert!(serializers.to_string().contains("args : :: std :: option :: Option :: Some (< Cbor as :: near_sdk :: ArgsSerializer > :: FORMAT)"));
+
+        let mut method = parse_quote! {
+            #[result_serializer(borsh)]
+            pub fn get(&self, #[serializer(borsh)] key: String) -> u64 { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type.clone()).unwrap().unwrap();
+        assert!(method_info.abi_serializers_tokens().is_none());
+
+        let mut method = parse_quote! {
+            pub fn set(&mut self, #[serializer(with = Cbor)] key: String, value: u64) { }
+        };
+        let err = ImplItemMethodInfo::new(&mut method, None, impl_type).err().unwrap();
+        assert_eq!(err.to_string(), "Input arguments should be all of the same serialization type.");
+    }
+
+    #[test]
+    fn test_generate_abi_contract() {
+        let item: syn::ItemStruct = parse_quote! {
+            /// I am a contract.
+            pub struct Contract { }
+        };
+        let actual = super::generate_contract(&item.attrs);
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn test_generate_abi_arg_default() {
+        let impl_type: Type = syn::parse_str("Test").unwrap();
+        let mut method = parse_quote! {
+            pub fn method(&self, #[arg(default)] from_index: u64, #[arg(default = "Some(10)")] limit: Option<u64>) -> Vec<u64> { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.abi_struct();
+
+        local_insta_assert_snapshot!(pretty_print_fn_body_syn_str(actual));
+    }
+
     #[test]
     fn test_generate_abi_private_callback_vec() {
         let impl_type: Type = syn::parse_str("Test").unwrap();
@@ -373,6 +620,19 @@ mod tests {
         local_insta_assert_snapshot!(pretty_print_fn_body_syn_str(actual));
     }
     
+    #[test]
+    fn test_generate_abi_borsh_callback_results() {
+        let impl_type: Type = syn::parse_str("Test").unwrap();
+        let mut method = parse_quote! {
+            #[private]
+            pub fn method(&self, #[callback_result] #[serializer(borsh)] x: Result<u64, PromiseError>, #[callback_vec] #[serializer(borsh)] y: Vec<String>) -> bool { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.abi_struct();
+
+        local_insta_assert_snapshot!(pretty_print_fn_body_syn_str(actual));
+    }
+
     #[test]
     fn test_generate_abi_init_ignore_state() {
         let impl_type: Type = syn::parse_str("Test").unwrap();
@@ -6,4 +6,4 @@ pub use abi_embed::embed;
 #[cfg(feature = "__abi-generate")]
 mod abi_generator;
 #[cfg(feature = "__abi-generate")]
-pub use abi_generator::generate;
+pub use abi_generator::{generate, generate_contract};
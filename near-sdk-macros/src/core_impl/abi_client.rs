@@ -0,0 +1,482 @@
+use crate::core_impl::ext::{generate_ext_function_wrappers, generate_ext_structs};
+use crate::core_impl::info_extractor::{ExtCallDefaults, ItemTraitInfo};
+use inflector::Inflector;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use serde_json::{Map, Value};
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_quote, Error, Ident, ItemTrait, LitStr, Token, Type};
+
+/// Types of the SDK generated for the definitions of the same name in the ABI.
+const SDK_TYPES: [(&str, &str); 10] = [
+    ("AccountId", "::near_sdk::AccountId"),
+    ("NearToken", "::near_sdk::NearToken"),
+    ("NearGas", "::near_sdk::Gas"),
+    ("PublicKey", "::near_sdk::PublicKey"),
+    ("U64", "::near_sdk::json_types::U64"),
+    ("U128", "::near_sdk::json_types::U128"),
+    ("I64", "::near_sdk::json_types::I64"),
+    ("I128", "::near_sdk::json_types::I128"),
+    ("Base64VecU8", "::near_sdk::json_types::Base64VecU8"),
+    ("Base58CryptoHash", "::near_sdk::json_types::Base58CryptoHash"),
+];
+
+/// Arguments of `abi_client!`: the name of the generated module, the name of the contract in the
+/// ABI by default, followed by the path of the ABI file relative to the manifest of the crate.
+pub(crate) struct AbiClientArgs {
+    mod_name: Option<Ident>,
+    path: LitStr,
+}
+
+impl Parse for AbiClientArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mod_name = if input.peek(Ident) {
+            let mod_name = input.parse()?;
+            input.parse::<Token![,]>()?;
+            Some(mod_name)
+        } else {
+            None
+        };
+        let path = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self { mod_name, path })
+    }
+}
+
+/// Generates the client of the contract described by the ABI file of `args`.
+pub(crate) fn abi_client(args: AbiClientArgs) -> syn::Result<TokenStream2> {
+    let AbiClientArgs { mod_name, path } = args;
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full_path = std::path::Path::new(&manifest_dir).join(path.value());
+    let abi = std::fs::read_to_string(&full_path)
+        .map_err(|e| Error::new(path.span(), format!("Failed to read the ABI file: {}", e)))?;
+    let abi: Value = serde_json::from_str(&abi)
+        .map_err(|e| Error::new(path.span(), format!("Failed to parse the ABI file: {}", e)))?;
+    let full_path = full_path.to_string_lossy();
+    generate_abi_client(mod_name, &abi, path.span(), quote! { include_str!(#full_path) })
+}
+
+/// Generates a module with the types defined by `abi`, a trait describing the interface of the
+/// contract, and the `ext` client generated for it by `#[ext_contract]`. `abi` is either a full
+/// ABI or only its body, with the `functions` and `root_schema` of the contract.
+///
+/// `source` is included in the module so that the crate is rebuilt when the ABI file changes.
+fn generate_abi_client(
+    mod_name: Option<Ident>,
+    abi: &Value,
+    span: Span,
+    source: TokenStream2,
+) -> syn::Result<TokenStream2> {
+    let error = |message: String| Error::new(span, message);
+    let mod_name = match mod_name {
+        Some(mod_name) => mod_name,
+        None => {
+            let name = abi["metadata"]["name"].as_str().ok_or_else(|| {
+                error("The ABI has no contract name, pass the name of the module.".into())
+            })?;
+            Ident::new(&name.to_snake_case(), span)
+        }
+    };
+    let body = abi.get("body").unwrap_or(abi);
+    let functions =
+        body["functions"].as_array().ok_or_else(|| error("The ABI has no functions.".into()))?;
+    let empty = Map::new();
+    let definitions = body["root_schema"]["definitions"].as_object().unwrap_or(&empty);
+
+    let mut types = TokenStream2::new();
+    for (name, schema) in definitions {
+        if SDK_TYPES.iter().all(|(sdk_name, _)| sdk_name != name) {
+            types.extend(definition_item(name, schema, span).map_err(error)?);
+        }
+    }
+
+    let trait_ident = Ident::new(&mod_name.to_string().to_pascal_case(), span);
+    let mut methods = TokenStream2::new();
+    for function in functions {
+        methods.extend(trait_method(function, span).map_err(error)?);
+    }
+    let mut item_trait: ItemTrait = parse_quote! {
+        /// Interface of the contract, as described by its ABI.
+        pub trait #trait_ident {
+            #methods
+        }
+    };
+    let info = ItemTraitInfo::new(&mut item_trait, None, ExtCallDefaults::default())?;
+    let ext_structs = generate_ext_structs(&trait_ident, None);
+    let ext_methods = generate_ext_function_wrappers(
+        &trait_ident,
+        info.methods.iter().map(|m| (&m.attr_sig_info, ExtCallDefaults::default())),
+    );
+
+    Ok(quote! {
+        pub mod #mod_name {
+            const _: &str = #source;
+            #types
+            #item_trait
+            #ext_structs
+            #ext_methods
+        }
+    })
+}
+
+/// Returns the method of the interface trait for the function of the ABI.
+fn trait_method(function: &Value, span: Span) -> Result<TokenStream2, String> {
+    let name = function["name"].as_str().ok_or("Function of the ABI without a name.")?;
+    let ident = to_ident(name, span)?;
+    let doc = doc_attr(&function["doc"]);
+    let receiver = match function["kind"].as_str() {
+        Some("view") => quote! { &self },
+        _ => quote! { &mut self },
+    };
+
+    let params = &function["params"];
+    let borsh = params["serialization_type"] == "borsh";
+    let mut args = vec![];
+    for arg in params["args"].as_array().into_iter().flatten() {
+        let arg_name = arg["name"]
+            .as_str()
+            .ok_or_else(|| format!("Argument of `{}` without a name.", name))?;
+        let arg_ident = to_ident(arg_name, span)?;
+        let ty = if borsh {
+            borsh_type(&arg["type_schema"]).map_err(|ty| {
+                format!(
+                    "Unsupported Borsh type `{}` of the argument `{}` of `{}`.",
+                    ty, arg_name, name
+                )
+            })?
+        } else {
+            json_type(&arg["type_schema"])
+        };
+        let serializer = borsh.then(|| quote! { #[serializer(borsh)] });
+        args.push(quote! { #serializer #arg_ident: #ty });
+    }
+
+    let result = &function["result"];
+    let (result_serializer, output) = match result["serialization_type"].as_str() {
+        Some("borsh") => {
+            let ty = borsh_type(&result["type_schema"]).map_err(|ty| {
+                format!("Unsupported Borsh type `{}` of the result of `{}`.", ty, name)
+            })?;
+            (Some(quote! { #[result_serializer(borsh)] }), Some(quote! { -> #ty }))
+        }
+        Some(_) => {
+            let ty = json_type(&result["type_schema"]);
+            (None, Some(quote! { -> #ty }))
+        }
+        None => (None, None),
+    };
+
+    Ok(quote! {
+        #doc
+        #result_serializer
+        fn #ident(#receiver, #(#args),*) #output;
+    })
+}
+
+/// Returns the item generated for the definition `name` of the root schema of the ABI: a struct
+/// for objects, an enum for enumerations of strings, and a type alias otherwise.
+fn definition_item(name: &str, schema: &Value, span: Span) -> Result<TokenStream2, String> {
+    let ident = to_type_ident(name, span)?;
+    let doc = doc_attr(&schema["description"]);
+    let derive = quote! {
+        #[derive(::near_sdk::serde::Serialize, ::near_sdk::serde::Deserialize, Clone, Debug, PartialEq)]
+        #[serde(crate = "::near_sdk::serde")]
+    };
+
+    if let Some(properties) = schema["properties"].as_object() {
+        let required: Vec<_> =
+            schema["required"].as_array().into_iter().flatten().filter_map(Value::as_str).collect();
+        let mut fields = vec![];
+        for (field_name, field_schema) in properties {
+            let field_ident = to_ident(&field_name.to_snake_case(), span)?;
+            let rename =
+                (field_ident != field_name).then(|| quote! { #[serde(rename = #field_name)] });
+            let field_doc = doc_attr(&field_schema["description"]);
+            let ty = json_type(field_schema);
+            let (ty, optional) = if required.contains(&field_name.as_str()) {
+                (ty, None)
+            } else {
+                let ty = if is_option(field_schema) {
+                    ty
+                } else {
+                    parse_quote! { ::std::option::Option<#ty> }
+                };
+                (
+                    ty,
+                    Some(
+                        quote! { #[serde(default, skip_serializing_if = "::std::option::Option::is_none")] },
+                    ),
+                )
+            };
+            fields.push(quote! { #field_doc #rename #optional pub #field_ident: #ty });
+        }
+        return Ok(quote! {
+            #doc
+            #derive
+            pub struct #ident {
+                #(#fields),*
+            }
+        });
+    }
+
+    let variants: Option<Vec<&str>> =
+        schema["enum"].as_array().and_then(|variants| variants.iter().map(Value::as_str).collect());
+    if let Some(variants) = variants {
+        let variants = variants
+            .into_iter()
+            .map(|variant| {
+                let variant_ident = to_type_ident(variant, span)?;
+                Ok(quote! { #[serde(rename = #variant)] #variant_ident })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        return Ok(quote! {
+            #doc
+            #derive
+            pub enum #ident {
+                #(#variants),*
+            }
+        });
+    }
+
+    let ty = json_type(schema);
+    Ok(quote! {
+        #doc
+        pub type #ident = #ty;
+    })
+}
+
+/// Returns the Rust type of a JSON schema, [`serde_json::Value`] for the schemas without one.
+fn json_type(schema: &Value) -> Type {
+    if let Some(reference) = schema["$ref"].as_str() {
+        let name = reference.trim_start_matches("#/definitions/");
+        if let Some((_, sdk_type)) = SDK_TYPES.iter().find(|(sdk_name, _)| *sdk_name == name) {
+            return syn::parse_str(sdk_type).unwrap();
+        }
+        if let Ok(ident) = to_type_ident(name, Span::call_site()) {
+            return parse_quote! { #ident };
+        }
+    }
+    if let Some(inner) = option_inner(schema) {
+        let ty = json_type(&inner);
+        return parse_quote! { ::std::option::Option<#ty> };
+    }
+    if let Some([single]) = schema["allOf"].as_array().map(Vec::as_slice) {
+        return json_type(single);
+    }
+    match schema["type"].as_str() {
+        Some("string") => parse_quote! { ::std::string::String },
+        Some("boolean") => parse_quote! { bool },
+        Some("number") => parse_quote! { f64 },
+        Some("null") => parse_quote! { () },
+        Some("integer") => match schema["format"].as_str() {
+            Some("uint8") => parse_quote! { u8 },
+            Some("uint16") => parse_quote! { u16 },
+            Some("uint32") => parse_quote! { u32 },
+            Some("uint64" | "uint") => parse_quote! { u64 },
+            Some("uint128") => parse_quote! { u128 },
+            Some("int8") => parse_quote! { i8 },
+            Some("int16") => parse_quote! { i16 },
+            Some("int32") => parse_quote! { i32 },
+            Some("int128") => parse_quote! { i128 },
+            _ => parse_quote! { i64 },
+        },
+        Some("array") => match &schema["items"] {
+            Value::Array(items) => {
+                let items = items.iter().map(json_type);
+                parse_quote! { (#(#items,)*) }
+            }
+            Value::Object(_) => {
+                let ty = json_type(&schema["items"]);
+                parse_quote! { ::std::vec::Vec<#ty> }
+            }
+            _ => parse_quote! { ::std::vec::Vec<::near_sdk::serde_json::Value> },
+        },
+        Some("object") if schema["additionalProperties"].is_object() => {
+            let ty = json_type(&schema["additionalProperties"]);
+            parse_quote! { ::std::collections::HashMap<::std::string::String, #ty> }
+        }
+        _ => parse_quote! { ::near_sdk::serde_json::Value },
+    }
+}
+
+/// Returns the schema of `T` if `schema` is the one of an `Option<T>`, i.e. `T` or `null`.
+fn option_inner(schema: &Value) -> Option<Value> {
+    if let Some([first, second]) = schema["type"].as_array().map(Vec::as_slice) {
+        let ty = if second == "null" {
+            first
+        } else if first == "null" {
+            second
+        } else {
+            return None;
+        };
+        let mut inner = schema.clone();
+        inner["type"] = ty.clone();
+        return Some(inner);
+    }
+    let variants = schema["anyOf"].as_array().or_else(|| schema["oneOf"].as_array())?;
+    match variants.as_slice() {
+        [inner, null] | [null, inner] if null["type"] == "null" && inner["type"] != "null" => {
+            Some(inner.clone())
+        }
+        _ => None,
+    }
+}
+
+fn is_option(schema: &Value) -> bool {
+    option_inner(schema).is_some()
+}
+
+/// Returns the Rust type of a Borsh schema with a primitive declaration, or the declaration of
+/// the schema if it is not one.
+fn borsh_type(schema: &Value) -> Result<Type, String> {
+    let declaration = schema["declaration"].as_str().unwrap_or_default();
+    match declaration {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128" | "bool"
+        | "f32" | "f64" => Ok(syn::parse_str(declaration).unwrap()),
+        "String" | "string" => Ok(parse_quote! { ::std::string::String }),
+        "()" => Ok(parse_quote! { () }),
+        _ => Err(declaration.to_string()),
+    }
+}
+
+/// Returns the identifier of a function or an argument, a raw identifier for keywords.
+fn to_ident(name: &str, span: Span) -> Result<Ident, String> {
+    let invalid = || format!("`{}` is not a valid identifier.", name);
+    let mut chars = name.chars();
+    if !matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_')
+        || !chars.all(|c| c.is_alphanumeric() || c == '_')
+    {
+        return Err(invalid());
+    }
+    match syn::parse_str::<Ident>(name) {
+        Ok(mut ident) => {
+            ident.set_span(span);
+            Ok(ident)
+        }
+        Err(_) => Ok(Ident::new_raw(name, span)),
+    }
+}
+
+/// Returns the identifier of a type or an enum variant.
+fn to_type_ident(name: &str, span: Span) -> Result<Ident, String> {
+    let is_pascal_case =
+        name.starts_with(|c: char| c.is_uppercase()) && name.chars().all(|c| c.is_alphanumeric());
+    if is_pascal_case {
+        to_ident(name, span)
+    } else {
+        to_ident(&name.to_pascal_case(), span)
+    }
+}
+
+fn doc_attr(doc: &Value) -> Option<TokenStream2> {
+    let doc = doc.as_str()?;
+    let lines = doc.lines().map(|line| {
+        if line.starts_with(' ') {
+            line.to_string()
+        } else {
+            format!(" {}", line)
+        }
+    });
+    Some(quote! { #(#[doc = #lines])* })
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use super::generate_abi_client;
+    use crate::core_impl::utils::test_helpers::{local_insta_assert_snapshot, pretty_print_syn_str};
+    use proc_macro2::Span;
+    use quote::quote;
+    use serde_json::json;
+
+    #[test]
+    fn abi_client_storage() {
+        let abi = json!({
+            "schema_version": "0.4.0",
+            "metadata": { "name": "storage-manager" },
+            "body": {
+                "functions": [
+                    {
+                        "name": "storage_deposit",
+                        "doc": " Deposits the attached NEAR for the storage of `account_id`.",
+                        "kind": "call",
+                        "modifiers": ["payable"],
+                        "params": {
+                            "serialization_type": "json",
+                            "args": [
+                                { "name": "account_id", "type_schema": { "anyOf": [{ "$ref": "#/definitions/AccountId" }, { "type": "null" }] } },
+                                { "name": "registration_only", "type_schema": { "type": ["boolean", "null"] } }
+                            ]
+                        },
+                        "result": { "serialization_type": "json", "type_schema": { "$ref": "#/definitions/StorageBalance" } }
+                    },
+                    {
+                        "name": "storage_balance_bounds",
+                        "kind": "view",
+                        "result": { "serialization_type": "json", "type_schema": { "$ref": "#/definitions/StorageBalanceBounds" } }
+                    },
+                    {
+                        "name": "set_count",
+                        "kind": "call",
+                        "params": {
+                            "serialization_type": "borsh",
+                            "args": [{ "name": "type", "type_schema": { "declaration": "u64", "definitions": {} } }]
+                        }
+                    }
+                ],
+                "root_schema": {
+                    "definitions": {
+                        "AccountId": { "type": "string" },
+                        "NearToken": { "type": "string" },
+                        "StorageBalance": {
+                            "type": "object",
+                            "required": ["available", "total"],
+                            "properties": {
+                                "available": { "$ref": "#/definitions/NearToken" },
+                                "total": { "$ref": "#/definitions/NearToken" }
+                            }
+                        },
+                        "StorageBalanceBounds": {
+                            "description": "Bounds of the storage balance of an account.",
+                            "type": "object",
+                            "required": ["min"],
+                            "properties": {
+                                "max": { "anyOf": [{ "$ref": "#/definitions/NearToken" }, { "type": "null" }] },
+                                "min": { "$ref": "#/definitions/NearToken" },
+                                "extraFields": { "type": "object", "additionalProperties": { "type": "integer", "format": "uint32" } }
+                            }
+                        },
+                        "Kind": { "enum": ["fungible", "non_fungible"] }
+                    }
+                }
+            }
+        });
+        let actual = generate_abi_client(None, &abi, Span::call_site(), quote! { "abi.json" }).unwrap();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn abi_client_unsupported_borsh() {
+        let abi = json!({
+            "functions": [{
+                "name": "set",
+                "kind": "call",
+                "params": {
+                    "serialization_type": "borsh",
+                    "args": [{ "name": "value", "type_schema": { "declaration": "Pair", "definitions": {} } }]
+                }
+            }],
+            "root_schema": {}
+        });
+        let actual = generate_abi_client(Some(syn::parse_quote!(ext_pair)), &abi, Span::call_site(), quote! { "abi.json" }).unwrap_err();
+        assert_eq!(actual.to_string(), "Unsupported Borsh type `Pair` of the argument `value` of `set`.");
+    }
+
+    #[test]
+    fn abi_client_without_name() {
+        let abi = json!({ "functions": [], "root_schema": {} });
+        let actual = generate_abi_client(None, &abi, Span::call_site(), quote! { "abi.json" }).unwrap_err();
+        assert_eq!(actual.to_string(), "The ABI has no contract name, pass the name of the module.");
+    }
+}
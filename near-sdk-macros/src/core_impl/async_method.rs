@@ -0,0 +1,514 @@
+use crate::core_impl::{utils, Diagnostic};
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use std::collections::HashSet;
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Attribute, Expr, FnArg, ImplItem, ImplItemFn, ItemImpl, Local, LocalInit, Meta,
+    Pat, PatIdent, Stmt, Token, Type,
+};
+
+/// A variable of an async method, which can be passed to the callbacks.
+#[derive(Clone)]
+struct Binding {
+    ident: Ident,
+    mutability: Option<Token![mut]>,
+    /// Declared type of the variable, which is needed to pass it to a callback.
+    ty: Option<Type>,
+}
+
+/// A `.await` statement at the top level of an async method.
+struct AwaitPoint {
+    /// The awaited promise.
+    promise: Expr,
+    /// The variable the result of the promise is bound to, if any.
+    result: Option<Binding>,
+    /// The `#[serializer(...)]` attribute of the statement, deserializing the result in the
+    /// callback.
+    serializer: Option<Attribute>,
+    /// Variables declared before the statement.
+    bindings: Vec<Binding>,
+}
+
+/// Statements of an async method up to a `.await` statement, or the end of the method.
+struct Segment {
+    stmts: Vec<Stmt>,
+    await_point: Option<AwaitPoint>,
+}
+
+/// Replaces each method marked with `#[near(async)]` with a method running the statements up to
+/// its first `.await` and a private callback running the statements up to the next one.
+///
+/// Each `.await` must be a statement at the top level of the method, `promise.await;` or
+/// `let name: Type = promise.await;`. The method schedules `promise` followed by the callback,
+/// which receives the result of the promise as `#[callback_result]` if `Type` is a `Result`,
+/// or `#[callback_unwrap]` otherwise, deserialized from JSON unless the statement has a
+/// `#[serializer(...)]` attribute. The arguments and variables used after the `.await` are
+/// passed to the callback as JSON arguments, so they need a type annotation.
+pub(crate) fn expand_async_methods(input: &mut ItemImpl) -> syn::Result<()> {
+    let mut items = Vec::with_capacity(input.items.len());
+    let mut errors = vec![];
+    for item in std::mem::take(&mut input.items) {
+        let mut method = match item {
+            ImplItem::Fn(method) => method,
+            item => {
+                items.push(item);
+                continue;
+            }
+        };
+        let attr = match take_async_attr(&mut method.attrs) {
+            Some(attr) => attr,
+            None => {
+                items.push(ImplItem::Fn(method));
+                continue;
+            }
+        };
+        if input.trait_.is_some() {
+            errors.push(
+                Diagnostic::AsyncMethod
+                    .error_spanned(attr, "#[near(async)] is not supported on trait methods."),
+            );
+            continue;
+        }
+        match expand_method(method, attr.span()) {
+            Ok(methods) => items.extend(methods.into_iter().map(ImplItem::Fn)),
+            Err(e) => errors.push(e),
+        }
+    }
+    input.items = items;
+
+    match errors.into_iter().reduce(|mut l, r| (l.combine(r), l).1) {
+        Some(combined_errors) => Err(combined_errors),
+        None => Ok(()),
+    }
+}
+
+/// Removes and returns the `#[near(async)]` attribute from `attrs`, if any.
+fn take_async_attr(attrs: &mut Vec<Attribute>) -> Option<Attribute> {
+    let position = attrs.iter().position(|attr| match &attr.meta {
+        Meta::List(list) => list.path.is_ident("near") && list.tokens.to_string() == "async",
+        _ => false,
+    })?;
+    Some(attrs.remove(position))
+}
+
+fn expand_method(method: ImplItemFn, attr_span: Span) -> syn::Result<Vec<ImplItemFn>> {
+    let ImplItemFn { attrs, vis, defaultness, mut sig, block } = method;
+    sig.asyncness = None;
+
+    if let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("init")) {
+        return Err(Diagnostic::AsyncMethod
+            .error_spanned(attr, "#[init] methods cannot be marked with #[near(async)]."));
+    }
+    let receiver = sig.inputs.iter().find_map(|arg| match arg {
+        FnArg::Receiver(receiver) => Some(receiver.clone()),
+        FnArg::Typed(_) => None,
+    });
+    match &receiver {
+        Some(receiver) if receiver.reference.is_some() && receiver.mutability.is_some() => {}
+        _ => {
+            return Err(Diagnostic::AsyncMethod.error(
+                sig.ident.span(),
+                "Methods with #[near(async)] must take `&mut self`, as views cannot schedule \
+                promises.",
+            ))
+        }
+    }
+
+    let mut bindings: Vec<_> = sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+                Pat::Ident(pat_ident) => Some(typed_binding(pat_ident, &pat_type.ty)),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+    let mut segments = vec![];
+    let mut stmts = vec![];
+    for stmt in block.stmts {
+        match await_stmt(&stmt)? {
+            Some((promise, result)) => {
+                check_no_await(promise.to_token_stream())?;
+                let serializer = match &stmt {
+                    Stmt::Local(local) => {
+                        local.attrs.iter().find(|attr| attr.path().is_ident("serializer")).cloned()
+                    }
+                    _ => None,
+                };
+                segments.push(Segment {
+                    stmts: std::mem::take(&mut stmts),
+                    await_point: Some(AwaitPoint {
+                        promise,
+                        result: result.clone(),
+                        serializer,
+                        bindings: bindings.clone(),
+                    }),
+                });
+                bindings.extend(result);
+            }
+            None => {
+                check_no_await(stmt.to_token_stream())?;
+                if let Stmt::Local(local) = &stmt {
+                    pat_bindings(&local.pat, &mut bindings);
+                }
+                stmts.push(stmt);
+            }
+        }
+    }
+    segments.push(Segment { stmts, await_point: None });
+    if segments.len() == 1 {
+        return Err(Diagnostic::AsyncMethod
+            .error(attr_span, "Methods with #[near(async)] must `.await` at least one promise."));
+    }
+
+    let names: Vec<_> = (0..segments.len())
+        .map(|i| match i {
+            0 => sig.ident.clone(),
+            i => format_ident!("__{}_resume_{}", sig.ident, i),
+        })
+        .collect();
+    // Variables passed to the callback of each `.await`.
+    let mut passed = vec![];
+    for (i, segment) in segments.iter().enumerate() {
+        if let Some(await_point) = &segment.await_point {
+            if let Some(token) = find_ident(segment_tokens(segment), "return") {
+                return Err(Diagnostic::AsyncMethod.error(
+                    token.span(),
+                    "`return` is only supported after the last `.await` of a #[near(async)] \
+                    method.",
+                ));
+            }
+            let used = used_idents(segments[i + 1..].iter().map(segment_tokens));
+            passed.push(passed_bindings(&await_point.bindings, &used)?);
+        }
+    }
+
+    let results: Vec<_> = segments
+        .iter()
+        .filter_map(|segment| segment.await_point.as_ref())
+        .map(|await_point| (await_point.result.clone(), await_point.serializer.clone()))
+        .collect();
+
+    let (result_attrs, entry_attrs): (Vec<_>, Vec<_>) = attrs.into_iter().partition(|attr| {
+        attr.path().is_ident("handle_result") || attr.path().is_ident("result_serializer")
+    });
+    let cfg_attrs: Vec<_> =
+        entry_attrs.iter().filter(|attr| attr.path().is_ident("cfg")).cloned().collect();
+
+    let mut methods = vec![];
+    for (i, segment) in segments.into_iter().enumerate() {
+        let mut sig = sig.clone();
+        sig.ident = names[i].clone();
+        let mut attrs = if i == 0 {
+            entry_attrs.clone()
+        } else {
+            let previous = &passed[i - 1];
+            let params = previous.iter().map(|binding| {
+                let Binding { ident, mutability, ty } = binding;
+                quote! { #mutability #ident: #ty }
+            });
+            let (result, serializer) = &results[i - 1];
+            let callback = result.as_ref().map(|binding| {
+                let Binding { ident, mutability, ty } = binding;
+                let attr = if matches!(ty, Some(ty) if utils::type_is_result(ty)) {
+                    quote! { callback_result }
+                } else {
+                    quote! { callback_unwrap }
+                };
+                quote! { #[#attr] #serializer #mutability #ident: #ty }
+            });
+            sig.inputs = parse_quote! { #receiver, #(#params,)* #callback };
+            let mut attrs = cfg_attrs.clone();
+            attrs.push(parse_quote! { #[doc(hidden)] });
+            attrs.push(parse_quote! { #[private] });
+            if segment.await_point.is_none() {
+                attrs.extend(result_attrs.iter().cloned());
+            }
+            attrs
+        };
+        // Variables are declared `mut` in every method if they are mutated in one of them.
+        attrs.push(parse_quote! { #[allow(unused_mut)] });
+
+        let stmts = segment.stmts;
+        let block = match segment.await_point {
+            Some(AwaitPoint { promise, .. }) => {
+                sig.output = parse_quote! { -> ::near_sdk::Promise };
+                let next = &names[i + 1];
+                let args = passed[i].iter().map(|binding| &binding.ident);
+                parse_quote! {{
+                    #(#stmts)*
+                    (#promise).then(
+                        Self::ext(::near_sdk::env::current_account_id()).#next(#(#args),*)
+                    )
+                }}
+            }
+            None => parse_quote! {{ #(#stmts)* }},
+        };
+        methods.push(ImplItemFn { attrs, vis: vis.clone(), defaultness, sig, block });
+    }
+    Ok(methods)
+}
+
+/// Returns the awaited promise and the variable its result is bound to, if `stmt` is a `.await`
+/// statement.
+fn await_stmt(stmt: &Stmt) -> syn::Result<Option<(Expr, Option<Binding>)>> {
+    match stmt {
+        Stmt::Local(Local { pat, init: Some(LocalInit { expr, diverge, .. }), .. }) => {
+            let promise = match expr.as_ref() {
+                Expr::Await(expr_await) => (*expr_await.base).clone(),
+                _ => return Ok(None),
+            };
+            let result = match pat {
+                Pat::Type(pat_type) => match pat_type.pat.as_ref() {
+                    Pat::Ident(pat_ident)
+                        if pat_ident.by_ref.is_none()
+                            && pat_ident.subpat.is_none()
+                            && diverge.is_none() =>
+                    {
+                        Some(typed_binding(pat_ident, &pat_type.ty))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            };
+            match result {
+                Some(result) => Ok(Some((promise, Some(result)))),
+                None => Err(Diagnostic::AsyncMethod.error_spanned(
+                    pat,
+                    "The result of `.await` must be bound to a variable with a type annotation, \
+                    e.g. `let result: Result<T, PromiseError> = promise.await;`.",
+                )),
+            }
+        }
+        Stmt::Expr(Expr::Await(expr_await), Some(_)) => {
+            Ok(Some(((*expr_await.base).clone(), None)))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn typed_binding(pat_ident: &PatIdent, ty: &Type) -> Binding {
+    Binding {
+        ident: pat_ident.ident.clone(),
+        mutability: pat_ident.mutability,
+        ty: Some(ty.clone()),
+    }
+}
+
+/// Adds the variables declared by `pat` to `bindings`.
+fn pat_bindings(pat: &Pat, bindings: &mut Vec<Binding>) {
+    match pat {
+        Pat::Type(pat_type) => match pat_type.pat.as_ref() {
+            Pat::Ident(pat_ident) if pat_ident.subpat.is_none() => {
+                bindings.push(typed_binding(pat_ident, &pat_type.ty))
+            }
+            pat => pat_bindings(pat, bindings),
+        },
+        // Paths and field names in patterns are mistaken for variables, which only matters if
+        // the same name is used after a `.await`.
+        pat => for_each_ident(pat.to_token_stream(), &mut |ident, after_dot| {
+            let name = ident.to_string();
+            if !after_dot
+                && name.starts_with(|c: char| c.is_lowercase())
+                && name != "mut"
+                && name != "ref"
+            {
+                bindings.push(Binding { ident: ident.clone(), mutability: None, ty: None });
+            }
+        }),
+    }
+}
+
+/// Returns the variables of `bindings` used in the rest of the method, in declaration order.
+fn passed_bindings(bindings: &[Binding], used: &HashSet<String>) -> syn::Result<Vec<Binding>> {
+    let mut seen = HashSet::new();
+    let mut passed = vec![];
+    // Later declarations shadow earlier ones.
+    for binding in bindings.iter().rev() {
+        let name = binding.ident.to_string();
+        if used.contains(&name) && seen.insert(name) {
+            if binding.ty.is_none() {
+                return Err(Diagnostic::AsyncMethod.error(
+                    binding.ident.span(),
+                    format!(
+                        "`{}` is used after `.await`, so it is passed to the callback and needs \
+                        a type annotation.",
+                        binding.ident
+                    ),
+                ));
+            }
+            passed.push(binding.clone());
+        }
+    }
+    passed.reverse();
+    Ok(passed)
+}
+
+fn segment_tokens(segment: &Segment) -> TokenStream2 {
+    let stmts = &segment.stmts;
+    let promise = segment.await_point.as_ref().map(|await_point| &await_point.promise);
+    quote! { #(#stmts)* #promise }
+}
+
+/// Returns the names of the variables possibly used in `tokens`.
+fn used_idents(tokens: impl IntoIterator<Item = TokenStream2>) -> HashSet<String> {
+    let mut used = HashSet::new();
+    for tokens in tokens {
+        for_each_ident(tokens, &mut |ident, after_dot| {
+            if !after_dot {
+                used.insert(ident.to_string());
+            }
+        });
+    }
+    used
+}
+
+fn check_no_await(tokens: TokenStream2) -> syn::Result<()> {
+    match find_ident(tokens, "await") {
+        Some(token) => Err(Diagnostic::AsyncMethod.error(
+            token.span(),
+            "`.await` is only supported as a statement at the top level of a #[near(async)] \
+            method, `promise.await;` or `let result: T = promise.await;`.",
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Returns the first identifier `name` of `tokens`, if any.
+pub(crate) fn find_ident(tokens: TokenStream2, name: &str) -> Option<Ident> {
+    let mut found = None;
+    for_each_ident(tokens, &mut |ident, _| {
+        if found.is_none() && ident == name {
+            found = Some(ident.clone());
+        }
+    });
+    found
+}
+
+/// Calls `f` with each identifier of `tokens`, and whether it follows a `.`, i.e. is a field or
+/// a method rather than a variable.
+fn for_each_ident(tokens: TokenStream2, f: &mut impl FnMut(&Ident, bool)) {
+    let mut after_dot = false;
+    for tree in tokens {
+        match tree {
+            TokenTree::Ident(ident) => {
+                f(&ident, after_dot);
+                after_dot = false;
+            }
+            TokenTree::Punct(punct) => after_dot = punct.as_char() == '.',
+            TokenTree::Group(group) => {
+                for_each_ident(group.stream(), f);
+                after_dot = false;
+            }
+            TokenTree::Literal(_) => after_dot = false,
+        }
+    }
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use super::expand_async_methods;
+    use crate::core_impl::utils::test_helpers::{local_insta_assert_snapshot, pretty_print_syn_str};
+    use quote::ToTokens;
+    use syn::{parse_quote, ItemImpl};
+
+    #[test]
+    fn async_method_two_awaits() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Hello {
+                /// Swaps `amount` of the token at the oracle price.
+                #[payable]
+                #[handle_result]
+                #[near(async)]
+                pub fn swap(&mut self, token: AccountId, mut amount: U128) -> Result<U128, &'static str> {
+                    let fee: u128 = self.fee;
+                    let ignored = fee * 2;
+                    let price: Result<U128, PromiseError> = oracle::ext(self.oracle.clone()).get_price(token.clone()).await;
+                    let price: U128 = price.unwrap_or(U128(0));
+                    amount.0 -= fee;
+                    ft::ext(token).ft_transfer(env::predecessor_account_id(), amount).await;
+                    if price.0 == 0 {
+                        return Err("no price");
+                    }
+                    Ok(U128(amount.0 * price.0))
+                }
+            }
+        };
+        expand_async_methods(&mut input).unwrap();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&input.to_token_stream()).unwrap());
+    }
+
+    #[test]
+    fn async_method_borsh_result() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Hello {
+                #[near(async)]
+                pub fn method(&mut self) -> u64 {
+                    #[serializer(borsh)]
+                    let value: Result<u64, PromiseError> = ext::ext(self.other.clone()).value().await;
+                    value.unwrap_or(0)
+                }
+            }
+        };
+        expand_async_methods(&mut input).unwrap();
+        let actual = input.to_token_stream().to_string();
+        assert!(actual.contains("# [callback_result] # [serializer (borsh)] value : Result < u64 , PromiseError >"));
+    }
+
+    #[test]
+    fn async_method_untyped_local() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Hello {
+                #[near(async)]
+                pub fn method(&mut self) -> u64 {
+                    let count = self.count;
+                    let value: u64 = ext::ext(self.other.clone()).value().await;
+                    value + count
+                }
+            }
+        };
+        let actual = expand_async_methods(&mut input).unwrap_err();
+        let expected = "[near-sdk E0016] `count` is used after `.await`, so it is passed to the callback and needs a type annotation.";
+        assert_eq!(expected, actual.to_string());
+    }
+
+    #[test]
+    fn async_method_return_before_await() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Hello {
+                #[near(async)]
+                pub fn method(&mut self) {
+                    if self.done {
+                        return;
+                    }
+                    ext::ext(self.other.clone()).run().await;
+                }
+            }
+        };
+        let actual = expand_async_methods(&mut input).unwrap_err();
+        let expected = "[near-sdk E0016] `return` is only supported after the last `.await` of a #[near(async)] method.";
+        assert_eq!(expected, actual.to_string());
+    }
+
+    #[test]
+    fn async_method_nested_await() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Hello {
+                #[near(async)]
+                pub fn method(&mut self) {
+                    if self.enabled {
+                        ext::ext(self.other.clone()).run().await;
+                    }
+                }
+            }
+        };
+        let actual = expand_async_methods(&mut input).unwrap_err();
+        let expected = "[near-sdk E0016] `.await` is only supported as a statement at the top level of a #[near(async)] method, `promise.await;` or `let result: T = promise.await;`.";
+        assert_eq!(expected, actual.to_string());
+    }
+}
@@ -1,8 +1,10 @@
 use proc_macro2::TokenStream as TokenStream2;
 
-use crate::core_impl::info_extractor::{ArgInfo, AttrSigInfo, BindgenArgType, SerializerType};
-use crate::core_impl::{utils, MethodKind};
-use quote::quote;
+use crate::core_impl::info_extractor::{
+    ArgDefault, ArgInfo, AttrSigInfo, BindgenArgType, NonReentrant, SerializerType,
+};
+use crate::core_impl::{utils, Diagnostic, MethodKind};
+use quote::{format_ident, quote};
 
 impl AttrSigInfo {
     /// Whether the signature has function arguments.
@@ -11,9 +13,6 @@ impl AttrSigInfo {
     }
 
     /// Whether the method has `payable` attribute.
-    /// Only available when `__abi-generate` feature is enabled as it's only in the abi generator
-    /// currently.
-    #[cfg(feature = "__abi-generate")]
     pub fn is_payable(&self) -> bool {
         use MethodKind::*;
 
@@ -35,6 +34,30 @@ impl AttrSigInfo {
         }
     }
 
+    /// Whether the arguments, callbacks or result of the method are serialized with an
+    /// `ArgsSerializer`.
+    #[cfg(feature = "__abi-generate")]
+    pub fn uses_custom_serializer(&self) -> bool {
+        let result_serializer = match &self.method_kind {
+            MethodKind::Call(call_method) => Some(&call_method.result_serializer),
+            MethodKind::View(view_method) => Some(&view_method.result_serializer),
+            MethodKind::Init(_) => None,
+        };
+        self.args
+            .iter()
+            .map(|arg| &arg.serializer_ty)
+            .chain(result_serializer)
+            .any(|serializer| matches!(serializer, SerializerType::Custom(_)))
+    }
+
+    /// The role of the method in the reentrancy guard, if it has the `non_reentrant` attribute.
+    pub fn non_reentrant(&self) -> Option<&NonReentrant> {
+        match &self.method_kind {
+            MethodKind::Call(call_method) => call_method.non_reentrant.as_ref(),
+            MethodKind::Init(_) | MethodKind::View(_) => None,
+        }
+    }
+
     pub fn input_struct_ser(&self) -> TokenStream2 {
         let args: Vec<_> = self.input_args().collect();
         assert!(
@@ -50,6 +73,10 @@ impl AttrSigInfo {
                 #[derive(::near_sdk::borsh::BorshSerialize)]
                 #[borsh(crate = "::near_sdk::borsh")]
             },
+            SerializerType::Custom(_) => quote! {
+                #[derive(::near_sdk::serde::Serialize)]
+                #[serde(crate = "::near_sdk::serde")]
+            },
         };
         let mut fields = TokenStream2::new();
         for arg in args {
@@ -89,6 +116,10 @@ impl AttrSigInfo {
             "Can only generate input struct for when input args are specified"
         );
         let attribute = match &self.input_serializer {
+            SerializerType::JSON if self.deny_unknown_arguments => quote! {
+                #[derive(::near_sdk::serde::Deserialize)]
+                #[serde(crate = "::near_sdk::serde", deny_unknown_fields)]
+            },
             SerializerType::JSON => quote! {
                 #[derive(::near_sdk::serde::Deserialize)]
                 #[serde(crate = "::near_sdk::serde")]
@@ -97,15 +128,36 @@ impl AttrSigInfo {
                 #[derive(::near_sdk::borsh::BorshDeserialize)]
                 #[borsh(crate = "::near_sdk::borsh")]
             },
+            SerializerType::Custom(_) => quote! {
+                #[derive(::near_sdk::serde::Deserialize)]
+                #[serde(crate = "::near_sdk::serde")]
+            },
         };
+        let mut default_fns = TokenStream2::new();
         let mut fields = TokenStream2::new();
         for arg in args {
-            let ArgInfo { ty, ident, .. } = &arg;
+            let ArgInfo { ty, ident, default, .. } = &arg;
+            let field_attr = match default {
+                None => quote! {},
+                Some(ArgDefault::Default) => quote! { #[serde(default)] },
+                Some(ArgDefault::Expr(expr)) => {
+                    let default_fn = format_ident!("__near_default_{}", ident);
+                    let default_fn_str = default_fn.to_string();
+                    default_fns.extend(quote! {
+                        fn #default_fn() -> #ty {
+                            #expr
+                        }
+                    });
+                    quote! { #[serde(default = #default_fn_str)] }
+                }
+            };
             fields.extend(quote! {
+                #field_attr
                 #ident: #ty,
             });
         }
         quote! {
+            #default_fns
             #attribute
             struct Input {
                 #fields
@@ -208,17 +260,21 @@ impl AttrSigInfo {
 
     /// Create code that deserializes arguments that were decorated with `#[callback*]`
     pub fn callback_deserialization(&self) -> TokenStream2 {
+        // Index of the promise result read by the next callback argument.
+        let mut next_idx = 0u64;
         self.args
             .iter()
             .filter(|arg| {
                 matches!(
                     arg.bindgen_ty,
-                    BindgenArgType::CallbackArg | BindgenArgType::CallbackResultArg
+                    BindgenArgType::CallbackArg
+                        | BindgenArgType::CallbackResultArg
+                        | BindgenArgType::CallbackJoinArg
                 )
             })
-            .enumerate()
-            .fold(TokenStream2::new(), |acc, (idx, arg)| {
-                let idx = idx as u64;
+            .fold(TokenStream2::new(), |acc, arg| {
+                let idx = next_idx;
+                next_idx += 1;
                 let ArgInfo { mutability, ident, ty, bindgen_ty, serializer_ty, .. } = arg;
                 match &bindgen_ty {
                     BindgenArgType::CallbackArg => {
@@ -240,43 +296,31 @@ impl AttrSigInfo {
                         let ok_type = if let Some(ok_type) = utils::extract_ok_type(ty) {
                             ok_type
                         } else {
-                            return syn::Error::new_spanned(ty, "Function parameters marked with \
+                            return Diagnostic::CallbackResultType.error_spanned(ty, "Function parameters marked with \
                                 #[callback_result] should have type Result<T, PromiseError>").into_compile_error()
                         };
-                        let deserialize = deserialize_data(serializer_ty);
-                        let deserialization_branch = match ok_type {
-                            // The unit type in this context is a bit special because functions
-                            // without an explicit return type do not serialize their response.
-                            // But when someone tries to refer to their callback result with
-                            // `#[callback_result]` they specify the callback type as
-                            // `Result<(), PromiseError>` which cannot be correctly deserialized from
-                            // an empty byte array.
-                            //
-                            // So instead of going through serde, we consider deserialization to be
-                            // successful if the byte array is empty or try the normal
-                            // deserialization otherwise.
-                            syn::Type::Tuple(type_tuple) if type_tuple.elems.is_empty() =>
-                                quote! {
-                                    ::near_sdk::PromiseResult::Successful(data) if data.is_empty() =>
-                                        ::std::result::Result::Ok(()),
-                                    ::near_sdk::PromiseResult::Successful(data) => ::std::result::Result::Ok(#deserialize)
-                                },
-                            _ =>
-                                quote! {
-                                    ::near_sdk::PromiseResult::Successful(data) => ::std::result::Result::Ok(#deserialize)
-                                }
-                        };
-                        let result = quote! {
-                            match ::near_sdk::env::promise_result(#idx) {
-                                #deserialization_branch,
-                                ::near_sdk::PromiseResult::Failed => ::std::result::Result::Err(::near_sdk::PromiseError::Failed),
-                            }
-                        };
+                        let result = callback_result_deserialization(idx, ok_type, serializer_ty);
                         quote! {
                             #acc
                             let #mutability #ident: #ty = #result;
                         }
                     }
+                    BindgenArgType::CallbackJoinArg => {
+                        let ok_types = if let Some(ok_types) = utils::extract_join_ok_types(ty) {
+                            ok_types
+                        } else {
+                            return Diagnostic::CallbackResultType.error_spanned(ty, "Function parameters marked with \
+                                #[callback_join] should have type (Result<T1, PromiseError>, Result<T2, PromiseError>, ...)").into_compile_error()
+                        };
+                        next_idx = idx + ok_types.len() as u64;
+                        let results = ok_types.into_iter().enumerate().map(|(i, ok_type)| {
+                            callback_result_deserialization(idx + i as u64, ok_type, serializer_ty)
+                        });
+                        quote! {
+                            #acc
+                            let #mutability #ident: #ty = (#(#results,)*);
+                        }
+                    }
                     _ => unreachable!()
                 }
             })
@@ -307,6 +351,41 @@ impl AttrSigInfo {
     }
 }
 
+/// Returns the `Result<T, PromiseError>` of the promise result `idx`, where `T` is `ok_type`.
+fn callback_result_deserialization(
+    idx: u64,
+    ok_type: &syn::Type,
+    serializer_ty: &SerializerType,
+) -> TokenStream2 {
+    let deserialize = deserialize_data(serializer_ty);
+    let deserialization_branch = match ok_type {
+        // The unit type in this context is a bit special because functions
+        // without an explicit return type do not serialize their response.
+        // But when someone tries to refer to their callback result with
+        // `#[callback_result]` they specify the callback type as
+        // `Result<(), PromiseError>` which cannot be correctly deserialized from
+        // an empty byte array.
+        //
+        // So instead of going through serde, we consider deserialization to be
+        // successful if the byte array is empty or try the normal
+        // deserialization otherwise.
+        syn::Type::Tuple(type_tuple) if type_tuple.elems.is_empty() => quote! {
+            ::near_sdk::PromiseResult::Successful(data) if data.is_empty() =>
+                ::std::result::Result::Ok(()),
+            ::near_sdk::PromiseResult::Successful(data) => ::std::result::Result::Ok(#deserialize)
+        },
+        _ => quote! {
+            ::near_sdk::PromiseResult::Successful(data) => ::std::result::Result::Ok(#deserialize)
+        },
+    };
+    quote! {
+        match ::near_sdk::env::promise_result(#idx) {
+            #deserialization_branch,
+            ::near_sdk::PromiseResult::Failed => ::std::result::Result::Err(::near_sdk::PromiseError::Failed),
+        }
+    }
+}
+
 fn deserialize_data(ty: &SerializerType) -> TokenStream2 {
     match ty {
         SerializerType::JSON => quote! {
@@ -321,5 +400,15 @@ fn deserialize_data(ty: &SerializerType) -> TokenStream2 {
                 Err(_) => ::near_sdk::env::panic_str("Failed to deserialize callback using Borsh"),
             }
         },
+        SerializerType::Custom(serializer) => quote! {
+            match <#serializer as ::near_sdk::ArgsSerializer>::deserialize(&data) {
+                Ok(deserialized) => deserialized,
+                Err(e) => ::near_sdk::env::panic_str(&::std::format!(
+                    "Failed to deserialize callback using {}: {}",
+                    <#serializer as ::near_sdk::ArgsSerializer>::FORMAT,
+                    e,
+                )),
+            }
+        },
     }
 }
@@ -1,5 +1,6 @@
+use crate::core_impl::info_extractor::ExtCallDefaults;
 use crate::core_impl::{serializer, AttrSigInfo};
-use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use proc_macro2::{Ident, Literal, Span, TokenStream as TokenStream2};
 use quote::{format_ident, quote, ToTokens};
 use syn::{parse_quote, Attribute, Generics, Path, Signature};
 
@@ -16,16 +17,17 @@ pub(crate) fn generate_ext_structs(
         pub fn ext(account_id: ::near_sdk::AccountId) -> #name {
             #name {
                 account_id,
-                deposit: ::near_sdk::NearToken::from_near(0),
-                static_gas: ::near_sdk::Gas::from_gas(0),
+                deposit: ::std::option::Option::None,
+                static_gas: ::std::option::Option::None,
                 gas_weight: ::near_sdk::GasWeight::default(),
             }
         }
     };
     if let Some(generics) = generic_details {
         // If ext generation is on struct, make ext function associated with struct not module
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
         ext_code = quote! {
-            impl #generics #ident #generics {
+            impl #impl_generics #ident #ty_generics #where_clause {
                 #ext_code
             }
         };
@@ -35,24 +37,38 @@ pub(crate) fn generate_ext_structs(
       #[must_use]
       pub struct #name {
           pub(crate) account_id: ::near_sdk::AccountId,
-          pub(crate) deposit: ::near_sdk::NearToken,
-          pub(crate) static_gas: ::near_sdk::Gas,
+          pub(crate) deposit: ::std::option::Option<::near_sdk::NearToken>,
+          pub(crate) static_gas: ::std::option::Option<::near_sdk::Gas>,
           pub(crate) gas_weight: ::near_sdk::GasWeight,
       }
 
       impl #name {
+          /// Attaches `amount` to the call instead of the default deposit of the method, none
+          /// unless set in `#[ext_contract]`.
           pub fn with_attached_deposit(mut self, amount: ::near_sdk::NearToken) -> Self {
-              self.deposit = amount;
+              self.deposit = ::std::option::Option::Some(amount);
               self
           }
+          /// Sets the gas the call receives in any case instead of the default static gas of the
+          /// method, none unless set in `#[ext_contract]`.
           pub fn with_static_gas(mut self, static_gas: ::near_sdk::Gas) -> Self {
-              self.static_gas = static_gas;
+              self.static_gas = ::std::option::Option::Some(static_gas);
               self
           }
+          /// Sets the weight of the share of the unused gas of the current call the call
+          /// receives on top of its static gas, 1 by default. The unused gas is split between the
+          /// calls scheduled by the current call in proportion to their weights.
           pub fn with_unused_gas_weight(mut self, gas_weight: u64) -> Self {
               self.gas_weight = ::near_sdk::GasWeight(gas_weight);
               self
           }
+          /// Sets both the static gas of the call, a floor it receives in any case, and the weight
+          /// of its share of the unused gas, see [`Self::with_unused_gas_weight`].
+          pub fn with_gas(mut self, static_gas: ::near_sdk::Gas, gas_weight: u64) -> Self {
+              self.static_gas = ::std::option::Option::Some(static_gas);
+              self.gas_weight = ::near_sdk::GasWeight(gas_weight);
+              self
+          }
       }
 
       #ext_code
@@ -95,14 +111,15 @@ fn is_fn_attribute_to_forward(attribute: &Attribute) -> bool {
 }
 
 /// Generate methods on <StructName>Ext to enable calling each method.
+/// The calls use the deposit and static gas of `defaults` unless set with the builder methods.
 pub(crate) fn generate_ext_function_wrappers<'a>(
     ident: &Ident,
-    methods: impl IntoIterator<Item = &'a AttrSigInfo>,
+    methods: impl IntoIterator<Item = (&'a AttrSigInfo, ExtCallDefaults)>,
 ) -> TokenStream2 {
     let ext_ident = format_ident!("{}Ext", ident);
     let mut res = TokenStream2::new();
-    for method in methods {
-        res.extend(generate_ext_function(method));
+    for (method, defaults) in methods {
+        res.extend(generate_ext_function(method, defaults));
     }
     quote! {
         impl #ext_ident {
@@ -111,7 +128,10 @@ pub(crate) fn generate_ext_function_wrappers<'a>(
     }
 }
 
-fn generate_ext_function(attr_signature_info: &AttrSigInfo) -> TokenStream2 {
+fn generate_ext_function(
+    attr_signature_info: &AttrSigInfo,
+    defaults: ExtCallDefaults,
+) -> TokenStream2 {
     let pat_type_list = attr_signature_info.pat_type_list();
     let serialize =
         serializer::generate_serializer(attr_signature_info, &attr_signature_info.input_serializer);
@@ -125,6 +145,8 @@ fn generate_ext_function(attr_signature_info: &AttrSigInfo) -> TokenStream2 {
         }
     }
     let Signature { generics, .. } = original_sig;
+    let deposit = Literal::u128_unsuffixed(defaults.deposit.unwrap_or(0));
+    let static_gas = Literal::u64_unsuffixed(defaults.static_gas.unwrap_or(0));
     quote! {
         #new_non_bindgen_attrs
         pub fn #ident #generics(self, #pat_type_list) -> ::near_sdk::Promise {
@@ -133,8 +155,8 @@ fn generate_ext_function(attr_signature_info: &AttrSigInfo) -> TokenStream2 {
             .function_call_weight(
                 ::std::string::String::from(#ident_str),
                 __args,
-                self.deposit,
-                self.static_gas,
+                self.deposit.unwrap_or(::near_sdk::NearToken::from_yoctonear(#deposit)),
+                self.static_gas.unwrap_or(::near_sdk::Gas::from_gas(#static_gas)),
                 self.gas_weight,
             )
         }
@@ -178,7 +200,7 @@ mod tests {
             pub fn method(&self) { }
         };
         let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
-        let actual = generate_ext_function(&method_info.attr_signature_info);
+        let actual = generate_ext_function(&method_info.attr_signature_info, ExtCallDefaults::default());
 
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
@@ -190,7 +212,7 @@ mod tests {
             pub fn method(&self, k: &String) { }
         };
         let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
-        let actual = generate_ext_function(&method_info.attr_signature_info);
+        let actual = generate_ext_function(&method_info.attr_signature_info, ExtCallDefaults::default());
      
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
@@ -202,7 +224,7 @@ mod tests {
           pub fn borsh_test(&mut self, #[serializer(borsh)] a: String) {}
         };
         let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
-        let actual = generate_ext_function(&method_info.attr_signature_info);
+        let actual = generate_ext_function(&method_info.attr_signature_info, ExtCallDefaults::default());
        
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
@@ -1,8 +1,11 @@
-use crate::core_impl::info_extractor::{ImplItemMethodInfo, SerializerType};
-use crate::core_impl::{MethodKind, ReturnKind};
+use crate::core_impl::info_extractor::{
+    ArgInfo, BindgenArgType, DepositBounds, ImplItemMethodInfo, NonReentrant, OnlyCallers,
+    PrivateAllow, SerializerType,
+};
+use crate::core_impl::{utils, MethodKind, ReturnKind};
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::Receiver;
+use syn::{Expr, Receiver};
 
 impl ImplItemMethodInfo {
     /// Generate wrapper method for the given method of the contract.
@@ -22,6 +25,7 @@ impl ImplItemMethodInfo {
         let deposit_check = self.deposit_check_tokens();
         let is_private_check = self.private_check_tokens();
         let state_check = self.state_check_tokens();
+        let non_reentrant_enter = self.non_reentrant_enter_tokens();
 
         let body = match self.attr_signature_info.returns.kind {
             // Extractor errors if Init method doesn't return anything, so we don't need extra check
@@ -44,6 +48,7 @@ impl ImplItemMethodInfo {
                 #callback_deser
                 #callback_vec_deser
                 #state_check
+                #non_reentrant_enter
                 #body
             }
         }
@@ -53,10 +58,12 @@ impl ImplItemMethodInfo {
         let contract_init = self.contract_init_tokens();
         let method_invocation = self.method_invocation_tokens();
         let contract_ser = self.contract_ser_tokens();
+        let non_reentrant_exit = self.non_reentrant_exit_tokens(None);
 
         quote! {
             #contract_init
             #method_invocation;
+            #non_reentrant_exit
             #contract_ser
         }
     }
@@ -67,10 +74,15 @@ impl ImplItemMethodInfo {
         let contract_ser = self.contract_ser_tokens();
         let value_ser = self.value_ser_tokens();
         let value_return = self.value_return_tokens();
+        let non_reentrant_exit = match &self.attr_signature_info.returns.kind {
+            ReturnKind::General(ty) => self.non_reentrant_exit_tokens(Some(ty)),
+            _ => quote! {},
+        };
 
         quote! {
             #contract_init
             #method_invocation_with_return
+            #non_reentrant_exit
             #value_ser
             #value_return
             #contract_ser
@@ -84,12 +96,19 @@ impl ImplItemMethodInfo {
         let value_ser = self.value_ser_tokens();
         let value_return = self.value_return_tokens();
         let result_identifier = self.result_identifier();
+        let non_reentrant_exit = match &self.attr_signature_info.returns.kind {
+            ReturnKind::HandlesResult(ty) => {
+                self.non_reentrant_exit_tokens(Some(utils::extract_ok_type(ty).unwrap_or(ty)))
+            }
+            _ => quote! {},
+        };
 
         quote! {
             #contract_init
             #method_invocation_with_return
             match #result_identifier {
                 ::std::result::Result::Ok(#result_identifier) => {
+                    #non_reentrant_exit
                     #value_ser
                     #value_return
                     #contract_ser
@@ -108,37 +127,77 @@ impl ImplItemMethodInfo {
     fn arg_struct_tokens(&self) -> TokenStream2 {
         if self.attr_signature_info.has_input_args() {
             self.attr_signature_info.input_struct_deser()
+        } else if self.attr_signature_info.deny_unknown_arguments {
+            quote! {
+                #[derive(::near_sdk::serde::Deserialize)]
+                #[serde(crate = "::near_sdk::serde", deny_unknown_fields)]
+                struct Input {}
+            }
         } else {
             quote! {}
         }
     }
 
     fn arg_parsing_tokens(&self) -> TokenStream2 {
+        let deny_unknown_arguments = self.attr_signature_info.deny_unknown_arguments;
         if self.attr_signature_info.has_input_args() {
             let decomposition = self.attr_signature_info.decomposition_pattern();
-            let serializer_invocation = match self.attr_signature_info.input_serializer {
+            let serializer_invocation = match &self.attr_signature_info.input_serializer {
+                // The error names the unknown argument.
+                SerializerType::JSON if deny_unknown_arguments => quote! {
+                    ::near_sdk::env::input_ref(|input| match input {
+                        Some(input) => match ::near_sdk::serde_json::from_slice(input) {
+                            Ok(deserialized) => deserialized,
+                            Err(e) => ::near_sdk::env::panic_str(&::std::format!("Failed to deserialize input from JSON: {}", e))
+                        },
+                        None => ::near_sdk::env::panic_str("Expected input since method has arguments.")
+                    })
+                },
                 SerializerType::JSON => quote! {
-                    match ::near_sdk::env::input() {
-                        Some(input) => match ::near_sdk::serde_json::from_slice(&input) {
+                    ::near_sdk::env::input_ref(|input| match input {
+                        Some(input) => match ::near_sdk::serde_json::from_slice(input) {
                             Ok(deserialized) => deserialized,
                             Err(_) => ::near_sdk::env::panic_str("Failed to deserialize input from JSON.")
                         },
                         None => ::near_sdk::env::panic_str("Expected input since method has arguments.")
-                    };
+                    })
                 },
                 SerializerType::Borsh => quote! {
-                    match ::near_sdk::env::input() {
-                        Some(input) => match ::near_sdk::borsh::BorshDeserialize::try_from_slice(&input) {
+                    ::near_sdk::env::input_ref(|input| match input {
+                        Some(input) => match ::near_sdk::borsh::BorshDeserialize::try_from_slice(input) {
                             Ok(deserialized) => deserialized,
                             Err(_) => ::near_sdk::env::panic_str("Failed to deserialize input from Borsh.")
                         },
                         None => ::near_sdk::env::panic_str("Expected input since method has arguments.")
-                    };
+                    })
+                },
+                SerializerType::Custom(serializer) => quote! {
+                    ::near_sdk::env::input_ref(|input| match input {
+                        Some(input) => match <#serializer as ::near_sdk::ArgsSerializer>::deserialize(input) {
+                            Ok(deserialized) => deserialized,
+                            Err(e) => ::near_sdk::env::panic_str(&::std::format!(
+                                "Failed to deserialize input from {}: {}",
+                                <#serializer as ::near_sdk::ArgsSerializer>::FORMAT,
+                                e,
+                            ))
+                        },
+                        None => ::near_sdk::env::panic_str("Expected input since method has arguments.")
+                    })
                 },
             };
             quote! {
                 let #decomposition : Input = #serializer_invocation ;
             }
+        } else if deny_unknown_arguments {
+            quote! {
+                ::near_sdk::env::input_ref(|input| {
+                    if let Some(input) = input.filter(|input| !input.is_empty()) {
+                        if let Err(e) = ::near_sdk::serde_json::from_slice::<Input>(input) {
+                            ::near_sdk::env::panic_str(&::std::format!("Failed to deserialize input from JSON: {}", e));
+                        }
+                    }
+                });
+            }
         } else {
             quote! {}
         }
@@ -162,7 +221,7 @@ impl ImplItemMethodInfo {
                 if !call_method.is_payable {
                     reject_deposit_code()
                 } else {
-                    quote! {}
+                    self.deposit_bounds_check_tokens()
                 }
             }
 
@@ -170,7 +229,7 @@ impl ImplItemMethodInfo {
                 if !init_method.is_payable {
                     reject_deposit_code()
                 } else {
-                    quote! {}
+                    self.deposit_bounds_check_tokens()
                 }
             }
 
@@ -178,16 +237,215 @@ impl ImplItemMethodInfo {
         }
     }
 
+    /// Checks the deposit of a `#[payable(...)]` method against its bounds, refunds the excess
+    /// with `refund_excess`, and sets the `#[deposit]` argument to the deposit kept.
+    fn deposit_bounds_check_tokens(&self) -> TokenStream2 {
+        let DepositBounds { min, max, exact, refund_excess } =
+            &self.attr_signature_info.deposit_bounds;
+        let method_name = self.attr_signature_info.ident.to_string();
+        let mut checks = TokenStream2::new();
+        let mut check = |bound: &Expr, name: TokenStream2, rejects: TokenStream2, error: &str| {
+            let error = format!("Method {} {} {{}}", method_name, error);
+            checks.extend(quote! {
+                let #name: ::near_sdk::NearToken = #bound;
+                if #rejects {
+                    ::near_sdk::env::panic_str(&::std::format!(#error, #name));
+                }
+            });
+        };
+        if let Some(exact) = exact {
+            let rejects = if *refund_excess {
+                quote! { deposit < exact_deposit }
+            } else {
+                quote! { deposit != exact_deposit }
+            };
+            check(exact, quote! { exact_deposit }, rejects, "requires a deposit of exactly");
+        }
+        if let Some(min) = min {
+            let rejects = quote! { deposit < min_deposit };
+            check(min, quote! { min_deposit }, rejects, "requires a deposit of at least");
+        }
+        if let Some(max) = max {
+            if *refund_excess {
+                checks.extend(quote! { let max_deposit: ::near_sdk::NearToken = #max; });
+            } else {
+                let rejects = quote! { deposit > max_deposit };
+                check(max, quote! { max_deposit }, rejects, "accepts a deposit of at most");
+            }
+        }
+        if checks.is_empty() {
+            return self.deposit_arg_tokens(quote! { ::near_sdk::env::attached_deposit() });
+        }
+
+        let (refund, deposit_arg) = if *refund_excess {
+            let kept = if exact.is_some() {
+                quote! { exact_deposit }
+            } else if max.is_some() {
+                quote! { max_deposit }
+            } else {
+                quote! { min_deposit }
+            };
+            let refund = quote! {
+                if deposit > #kept {
+                    ::near_sdk::Promise::new(::near_sdk::env::predecessor_account_id())
+                        .transfer(deposit.saturating_sub(#kept));
+                }
+            };
+            (refund, self.deposit_arg_tokens(quote! { ::std::cmp::min(deposit, #kept) }))
+        } else {
+            (quote! {}, self.deposit_arg_tokens(quote! { deposit }))
+        };
+        quote! {
+            let deposit = ::near_sdk::env::attached_deposit();
+            #checks
+            #refund
+            #deposit_arg
+        }
+    }
+
+    /// Sets the `#[deposit]` argument of the method, if any, to `deposit`.
+    fn deposit_arg_tokens(&self, deposit: TokenStream2) -> TokenStream2 {
+        let arg = self
+            .attr_signature_info
+            .args
+            .iter()
+            .find(|arg| matches!(arg.bindgen_ty, BindgenArgType::Deposit));
+        match arg {
+            Some(ArgInfo { mutability, ident, ty, .. }) => quote! {
+                let #mutability #ident: #ty = #deposit;
+            },
+            None => quote! {},
+        }
+    }
+
     fn private_check_tokens(&self) -> TokenStream2 {
-        if self.attr_signature_info.is_private() {
-            let error = format!("Method {} is private", self.attr_signature_info.ident);
+        let PrivateAllow { accounts, allow_fn } = &self.attr_signature_info.private_allow;
+        if !self.attr_signature_info.is_private() || allow_fn.is_some() {
+            // Methods with an allow function are checked once the contract is deserialized.
+            return quote! {};
+        }
+        let error = format!("Method {} is private", self.attr_signature_info.ident);
+        if accounts.is_empty() {
             quote! {
                 if ::near_sdk::env::current_account_id() != ::near_sdk::env::predecessor_account_id() {
                     ::near_sdk::env::panic_str(#error);
                 }
             }
         } else {
-            quote! {}
+            quote! {
+                let predecessor_account_id = ::near_sdk::env::predecessor_account_id();
+                if ::near_sdk::env::current_account_id() != predecessor_account_id
+                    && ![#(#accounts),*].contains(&predecessor_account_id.as_str())
+                {
+                    ::near_sdk::env::panic_str(#error);
+                }
+            }
+        }
+    }
+
+    /// Check of a `#[private(allow_fn = "name")]` method, which calls `name` on the contract.
+    fn private_allow_fn_check_tokens(&self) -> TokenStream2 {
+        let PrivateAllow { accounts, allow_fn } = &self.attr_signature_info.private_allow;
+        let allow_fn = match allow_fn {
+            Some(allow_fn) if self.attr_signature_info.is_private() => allow_fn,
+            _ => return quote! {},
+        };
+        let struct_type = &self.struct_type;
+        let error = format!("Method {} is private", self.attr_signature_info.ident);
+        let allowed_accounts = (!accounts.is_empty()).then(|| {
+            quote! { && ![#(#accounts),*].contains(&predecessor_account_id.as_str()) }
+        });
+        quote! {
+            let predecessor_account_id = ::near_sdk::env::predecessor_account_id();
+            if ::near_sdk::env::current_account_id() != predecessor_account_id
+                #allowed_accounts
+                && !#struct_type::#allow_fn(&contract, &predecessor_account_id)
+            {
+                ::near_sdk::env::panic_str(#error);
+            }
+        }
+    }
+
+    /// Check of a `#[only(...)]` or `#[only_owner]` method, which asks the contract whether the
+    /// predecessor is allowed.
+    fn only_check_tokens(&self) -> TokenStream2 {
+        let ident = &self.attr_signature_info.ident;
+        let (is_allowed, error) = match &self.attr_signature_info.only {
+            Some(OnlyCallers::Role(role)) => (
+                quote! { ::near_sdk::HasRoles::has_role(&contract, #role, &::near_sdk::env::predecessor_account_id()) },
+                format!("Method {} requires the role {}", ident, role),
+            ),
+            Some(OnlyCallers::Owner) => (
+                quote! { ::near_sdk::HasRoles::is_owner(&contract, &::near_sdk::env::predecessor_account_id()) },
+                format!("Method {} can only be called by the owner", ident),
+            ),
+            None => return quote! {},
+        };
+        quote! {
+            if !#is_allowed {
+                ::near_sdk::env::panic_str(#error);
+            }
+        }
+    }
+
+    /// Sets the reentrancy guard of the key of a guarded method, the caller by default. The
+    /// callback of a guarded method checks that the contract called it and computes the key of the
+    /// guard it clears.
+    fn non_reentrant_enter_tokens(&self) -> TokenStream2 {
+        let key_tokens = |key: &syn::Ident| {
+            quote! {
+                let __non_reentrant_key = ::near_sdk::utils::non_reentrant_key(&#key);
+            }
+        };
+        match self.attr_signature_info.non_reentrant() {
+            Some(NonReentrant::Guard { key, .. }) => {
+                let ident = self.attr_signature_info.ident.to_string();
+                let key = match key {
+                    Some(key) => key_tokens(key),
+                    None => quote! {
+                        let __non_reentrant_key = ::near_sdk::utils::non_reentrant_key(
+                            &::near_sdk::env::predecessor_account_id(),
+                        );
+                    },
+                };
+                quote! {
+                    #key
+                    ::near_sdk::__private::enter_non_reentrant(#ident, &__non_reentrant_key);
+                }
+            }
+            Some(NonReentrant::Callback { key }) => {
+                let ident = self.attr_signature_info.ident.to_string();
+                let key = key_tokens(key);
+                quote! {
+                    ::near_sdk::__private::check_non_reentrant_callback(#ident);
+                    #key
+                }
+            }
+            None => quote! {},
+        }
+    }
+
+    /// Clears the reentrancy guard after the method returned `result` of type `ty`. A guarded
+    /// method returning a promise keeps the guard set for its callback to clear it.
+    fn non_reentrant_exit_tokens(&self, ty: Option<&syn::Type>) -> TokenStream2 {
+        let exit = quote! {
+            ::near_sdk::__private::exit_non_reentrant(&__non_reentrant_key);
+        };
+        match self.attr_signature_info.non_reentrant() {
+            Some(NonReentrant::Guard { .. }) => match ty {
+                Some(ty) if utils::type_is_named(ty, "Promise") => quote! {},
+                Some(ty) if utils::type_is_named(ty, "PromiseOrValue") => {
+                    let result_identifier = self.result_identifier();
+                    quote! {
+                        if let ::near_sdk::PromiseOrValue::Value(_) = &#result_identifier {
+                            #exit
+                        }
+                    }
+                }
+                _ => exit,
+            },
+            Some(NonReentrant::Callback { .. }) => exit,
+            None => quote! {},
         }
     }
 
@@ -203,7 +461,7 @@ impl ImplItemMethodInfo {
                 if !init_method.ignores_state {
                     quote! {
                         if ::near_sdk::env::state_exists() {
-                            ::near_sdk::env::panic_str("The contract has already been initialized");
+                            ::near_sdk::env::panic_error(&::near_sdk::AlreadyInitialized);
                         }
                     }
                 } else {
@@ -224,9 +482,16 @@ impl ImplItemMethodInfo {
 
         let contract_deser = |receiver: &Receiver| {
             let mutability = receiver.mutability;
+            let private_allow_fn_check = self.private_allow_fn_check_tokens();
+            let only_check = self.only_check_tokens();
 
             quote! {
-                let #mutability contract: #struct_type = ::near_sdk::env::state_read().unwrap_or_default();
+                let #mutability contract: #struct_type = {
+                    use ::near_sdk::__private::StateLoaderFallback as _;
+                    ::near_sdk::__private::StateLoader::<#struct_type>::new().load()
+                };
+                #private_allow_fn_check
+                #only_check
             }
         };
 
@@ -283,7 +548,7 @@ impl ImplItemMethodInfo {
     fn method_invocation_tokens(&self) -> TokenStream2 {
         use MethodKind::*;
 
-        let ident = &self.attr_signature_info.ident;
+        let ident = self.preview_of.as_ref().unwrap_or(&self.attr_signature_info.ident);
         let arg_list = self.attr_signature_info.arg_list();
         let struct_type = &self.struct_type;
 
@@ -373,6 +638,16 @@ impl ImplItemMethodInfo {
                     Err(_) => ::near_sdk::env::panic_str("Failed to serialize the return value using Borsh."),
                 };
             },
+            SerializerType::Custom(serializer) => quote! {
+                let result = match <#serializer as ::near_sdk::ArgsSerializer>::serialize(&result) {
+                    Ok(v) => v,
+                    Err(e) => ::near_sdk::env::panic_str(&::std::format!(
+                        "Failed to serialize the return value using {}: {}",
+                        <#serializer as ::near_sdk::ArgsSerializer>::FORMAT,
+                        e,
+                    )),
+                };
+            },
         };
 
         match &self.attr_signature_info.method_kind {
@@ -1,7 +1,12 @@
-use crate::core_impl::ext::generate_ext_function_wrappers;
+use crate::core_impl::ext::{generate_ext_function_wrappers, generate_ext_structs};
+use crate::core_impl::info_extractor::{
+    ArgInfo, AttrSigInfo, ExtCallDefaults, MethodKind, NonReentrant, ReturnKind,
+};
+use crate::core_impl::serializer;
 use crate::ItemImplInfo;
-use proc_macro2::TokenStream as TokenStream2;
-use quote::ToTokens;
+use inflector::Inflector;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{spanned::Spanned, Ident};
 
 impl ItemImplInfo {
@@ -14,16 +19,282 @@ impl ItemImplInfo {
         res
     }
 
+    /// Generate a hidden macro named after each exported method. Macros exported with
+    /// `#[macro_export]` share the namespace of the crate root, so a method exported by two impl
+    /// blocks, possibly in different modules, fails to compile with an error at both methods
+    /// instead of a duplicate symbol when linking the contract.
+    pub fn exported_names_check(&self) -> TokenStream2 {
+        let mut res = TokenStream2::new();
+        for method in &self.methods {
+            let AttrSigInfo { ident, non_bindgen_attrs, .. } = &method.attr_signature_info;
+            let cfg_attrs = non_bindgen_attrs.iter().filter(|attr| attr.path().is_ident("cfg"));
+            let name = format_ident!("__near_contract_method_{}", ident, span = ident.span());
+            res.extend(quote_spanned! {ident.span()=>
+                #(#cfg_attrs)*
+                #[doc(hidden)]
+                #[allow(non_local_definitions)]
+                #[macro_export]
+                macro_rules! #name { () => {}; }
+            });
+        }
+        res
+    }
+
+    /// Generate a hidden constant of the contract type for each `#[non_reentrant(callback)]`
+    /// method, and a use of it for each guarded method naming its callback with `then`, so that
+    /// a guarded method returning a promise fails to compile unless its callback, possibly in
+    /// another impl block, clears the guard.
+    pub fn non_reentrant_callbacks_check(&self) -> TokenStream2 {
+        let ty = &self.ty;
+        let mut res = TokenStream2::new();
+        for method in &self.methods {
+            let AttrSigInfo { ident, non_bindgen_attrs, .. } = &method.attr_signature_info;
+            let cfg_attrs = non_bindgen_attrs.iter().filter(|attr| attr.path().is_ident("cfg"));
+            match method.attr_signature_info.non_reentrant() {
+                Some(NonReentrant::Callback { .. }) => {
+                    let name = format_ident!("__near_non_reentrant_callback_{}", ident);
+                    res.extend(quote! {
+                        #(#cfg_attrs)*
+                        impl #ty {
+                            #[doc(hidden)]
+                            #[allow(non_upper_case_globals)]
+                            pub const #name: () = ();
+                        }
+                    });
+                }
+                Some(NonReentrant::Guard { then: Some(then), .. }) => {
+                    let name =
+                        format_ident!("__near_non_reentrant_callback_{}", then, span = then.span());
+                    res.extend(quote_spanned! {then.span()=>
+                        #(#cfg_attrs)*
+                        const _: () = <#ty>::#name;
+                    });
+                }
+                _ => {}
+            }
+        }
+        res
+    }
+
+    /// Returns the name of the contract type, without the generic arguments of an instantiated
+    /// impl block.
+    fn ty_ident(&self) -> syn::Result<Ident> {
+        match &self.ty {
+            syn::Type::Path(path) if path.qself.is_none() && path.path.segments.len() == 1 => {
+                Ok(path.path.segments[0].ident.clone())
+            }
+            ty => syn::parse2::<Ident>(ty.to_token_stream()),
+        }
+    }
+
     pub fn generate_ext_wrapper_code(&self) -> TokenStream2 {
-        match syn::parse::<Ident>(self.ty.to_token_stream().into()) {
+        match self.ty_ident() {
             Ok(n) => generate_ext_function_wrappers(
                 &n,
-                self.methods.iter().map(|m| &m.attr_signature_info),
+                self.methods.iter().map(|m| (&m.attr_signature_info, ExtCallDefaults::default())),
             ),
             Err(e) => syn::Error::new(self.ty.span(), e).to_compile_error(),
         }
     }
+
+    /// Generate the items exported by `#[init(schema)]` methods for factory contracts: the name of
+    /// the method, a builder serializing its arguments and, with the `abi` feature, the JSON
+    /// schema of its arguments.
+    pub fn init_schema_code(&self) -> TokenStream2 {
+        let ty = &self.ty;
+        let mut res = TokenStream2::new();
+        let mut exported = false;
+        for method in &self.methods {
+            let attr_sig_info = &method.attr_signature_info;
+            if !matches!(&attr_sig_info.method_kind, MethodKind::Init(init) if init.exports_schema)
+            {
+                continue;
+            }
+            if std::mem::replace(&mut exported, true) {
+                return syn::Error::new(
+                    attr_sig_info.ident.span(),
+                    "Only one init method per contract can be marked with #[init(schema)].",
+                )
+                .to_compile_error();
+            }
+            let ident_str = attr_sig_info.ident.to_string();
+            let pat_type_list = attr_sig_info.pat_type_list();
+            let serialize =
+                serializer::generate_serializer(attr_sig_info, &attr_sig_info.input_serializer);
+            let schema = init_args_schema(attr_sig_info);
+            res.extend(quote! {
+                impl #ty {
+                    /// Name of the init method exported with `#[init(schema)]`.
+                    pub const INIT_METHOD_NAME: &'static str = #ident_str;
+
+                    /// Serializes the arguments of the init method exported with
+                    /// `#[init(schema)]`, e.g. to deploy this contract from a factory.
+                    pub fn init_args(#pat_type_list) -> ::std::vec::Vec<u8> {
+                        #serialize
+                    }
+
+                    #schema
+                }
+            });
+        }
+        res
+    }
+
+    /// Generate the implementation of `InitOnDeploy` by the `#[init(on_deploy)]` method, which
+    /// initializes the contract when one of its methods is called before its state is written.
+    pub fn init_on_deploy_code(&self) -> TokenStream2 {
+        let ty = &self.ty;
+        let mut res = TokenStream2::new();
+        let mut implemented = false;
+        for method in &self.methods {
+            let attr_sig_info = &method.attr_signature_info;
+            if !matches!(&attr_sig_info.method_kind, MethodKind::Init(init) if init.on_deploy) {
+                continue;
+            }
+            if std::mem::replace(&mut implemented, true) {
+                return syn::Error::new(
+                    attr_sig_info.ident.span(),
+                    "Only one init method per contract can be marked with #[init(on_deploy)].",
+                )
+                .to_compile_error();
+            }
+            let AttrSigInfo { ident, non_bindgen_attrs, returns, .. } = attr_sig_info;
+            let cfg_attrs = non_bindgen_attrs.iter().filter(|attr| attr.path().is_ident("cfg"));
+            let init = match &returns.kind {
+                ReturnKind::HandlesResult(_) => quote! {
+                    match Self::#ident() {
+                        ::std::result::Result::Ok(contract) => contract,
+                        ::std::result::Result::Err(err) => ::near_sdk::FunctionError::panic(&err),
+                    }
+                },
+                _ => quote! { Self::#ident() },
+            };
+            res.extend(quote! {
+                #(#cfg_attrs)*
+                impl ::near_sdk::__private::InitOnDeploy for #ty {
+                    fn init_on_deploy() -> Self {
+                        #init
+                    }
+                }
+            });
+        }
+        res
+    }
+
+    /// Generate the module of `#[near(generate_interface)]`, named `mod_name` or after the type
+    /// of the contract, with a trait declaring the methods of the impl block which other contracts
+    /// can call, i.e. all but the private ones, and the `ext` client of these methods.
+    pub fn interface_code(&self, mod_name: Option<&str>) -> TokenStream2 {
+        let ident = match self.ty_ident() {
+            Ok(ident) => ident,
+            Err(e) => return syn::Error::new(self.ty.span(), e).to_compile_error(),
+        };
+        let mod_name = match mod_name {
+            Some(mod_name) => Ident::new(mod_name, Span::call_site()),
+            None => format_ident!("{}_interface", ident.to_string().to_snake_case()),
+        };
+        let methods: Vec<_> = self
+            .methods
+            .iter()
+            .map(|method| &method.attr_signature_info)
+            .filter(|attr_sig_info| match &attr_sig_info.method_kind {
+                MethodKind::Call(call) => !call.is_private,
+                MethodKind::View(view) => !view.is_private,
+                MethodKind::Init(_) => true,
+            })
+            .collect();
+
+        let declarations = methods.iter().map(|attr_sig_info| {
+            let AttrSigInfo { ident, non_bindgen_attrs, method_kind, returns, .. } = attr_sig_info;
+            let attrs = non_bindgen_attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("doc") || attr.path().is_ident("cfg"));
+            let receiver = match method_kind {
+                MethodKind::Call(_) => quote! { &mut self, },
+                MethodKind::View(_) => quote! { &self, },
+                MethodKind::Init(_) => quote! {},
+            };
+            let args = attr_sig_info.input_args().map(|arg| {
+                let ArgInfo { ident, original, .. } = arg;
+                let ty = &original.ty;
+                quote! { #ident: #ty }
+            });
+            let output = match (method_kind, &returns.kind) {
+                (MethodKind::Init(_), _) | (_, ReturnKind::Default) => quote! {},
+                (_, ReturnKind::General(ty)) => quote! { -> #ty },
+                (_, ReturnKind::HandlesResult(ty)) => {
+                    quote! { -> <#ty as ::near_sdk::__private::ResultTypeExt>::Okay }
+                }
+            };
+            quote! {
+                #(#attrs)*
+                fn #ident(#receiver #(#args),*) #output;
+            }
+        });
+        let ext_structs = generate_ext_structs(&ident, None);
+        let ext_methods = generate_ext_function_wrappers(
+            &ident,
+            methods.iter().map(|attr_sig_info| (*attr_sig_info, ExtCallDefaults::default())),
+        );
+
+        quote! {
+            pub mod #mod_name {
+                use super::*;
+
+                /// Interface of the contract, with the methods other contracts can call.
+                pub trait #ident {
+                    #(#declarations)*
+                }
+                #ext_structs
+                #ext_methods
+            }
+        }
+    }
 }
+
+#[cfg(feature = "abi")]
+fn init_args_schema(attr_sig_info: &AttrSigInfo) -> TokenStream2 {
+    let ident_str = attr_sig_info.ident.to_string();
+    let properties = attr_sig_info.input_args().map(|arg| {
+        let name = arg.ident.to_string();
+        let ty = &arg.ty;
+        quote! {
+            object.properties.insert(#name.to_string(), gen.subschema_for::<#ty>());
+            object.required.insert(#name.to_string());
+        }
+    });
+    quote! {
+        /// Returns the JSON schema of the arguments of the init method exported with
+        /// `#[init(schema)]`.
+        pub fn get_init_schema() -> ::near_sdk::schemars::schema::RootSchema {
+            use ::near_sdk::schemars::schema::{InstanceType, Metadata, ObjectValidation, SchemaObject};
+
+            let mut gen = ::near_sdk::schemars::gen::SchemaSettings::draft07().into_generator();
+            let mut object = ObjectValidation::default();
+            #(#properties)*
+            let schema = SchemaObject {
+                metadata: ::std::option::Option::Some(::std::boxed::Box::new(Metadata {
+                    title: ::std::option::Option::Some(#ident_str.to_string()),
+                    ..::std::default::Default::default()
+                })),
+                instance_type: ::std::option::Option::Some(InstanceType::Object.into()),
+                object: ::std::option::Option::Some(::std::boxed::Box::new(object)),
+                ..::std::default::Default::default()
+            };
+            ::near_sdk::schemars::schema::RootSchema {
+                meta_schema: gen.settings().meta_schema.clone(),
+                definitions: gen.take_definitions(),
+                schema,
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "abi"))]
+fn init_args_schema(_attr_sig_info: &AttrSigInfo) -> TokenStream2 {
+    TokenStream2::new()
+}
+
 // Rustfmt removes comas.
 #[rustfmt::skip]
 #[cfg(test)]
@@ -33,6 +304,93 @@ mod tests {
     use crate::core_impl::utils::test_helpers::{local_insta_assert_snapshot, pretty_print_syn_str};
 
 
+    #[test]
+    fn exported_names_check() {
+        let mut input: syn::ItemImpl = parse_quote! {
+            impl Hello {
+                pub fn get(&self) -> u64 { }
+                #[cfg(feature = "legacy")]
+                pub fn r#set(&mut self, value: u64) { }
+                fn helper(&self) { }
+            }
+        };
+        let info = crate::ItemImplInfo::new(&mut input).unwrap();
+        let actual = info.exported_names_check();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn non_reentrant_callbacks_check() {
+        let mut input: syn::ItemImpl = parse_quote! {
+            impl Hello {
+                #[non_reentrant(then = "on_withdraw")]
+                pub fn withdraw(&mut self, amount: U128) -> Promise { }
+                #[non_reentrant(key = "account_id")]
+                pub fn deposit(&mut self, account_id: AccountId, amount: U128) { }
+                #[private]
+                #[non_reentrant(callback, key = "account_id")]
+                pub fn on_withdraw(&mut self, account_id: AccountId) { }
+            }
+        };
+        let info = crate::ItemImplInfo::new(&mut input).unwrap();
+        let actual = info.non_reentrant_callbacks_check();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn generate_interface() {
+        let mut input: syn::ItemImpl = parse_quote! {
+            impl Hello {
+                /// Returns the greeting of `account_id`.
+                pub fn get_greeting(&self, account_id: AccountId) -> String { }
+                #[payable]
+                pub fn set_greeting(&mut self, #[serializer(borsh)] greeting: String) { }
+                #[handle_result]
+                pub fn try_reset(&mut self) -> Result<u64, &'static str> { }
+                #[init]
+                pub fn new(mut greeting: String) -> Self { }
+                #[private]
+                pub fn on_reset(&mut self, #[callback_unwrap] count: u64) { }
+                fn helper(&self) { }
+            }
+        };
+        let info = crate::ItemImplInfo::new(&mut input).unwrap();
+        let actual = info.interface_code(None);
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn instantiated_generic_impl() {
+        let mut input: syn::ItemImpl = parse_quote! {
+            impl<'a, T: 'a + std::fmt::Display, const N: usize> Incrementer<T, N> {
+                pub fn inc(&mut self, by: T) -> [T; N] { }
+                pub fn first(&self, values: Vec<T>) -> Option<&'a T> { }
+                #[payable]
+                pub fn reset(&mut self) -> T { T::default() }
+            }
+        };
+        let instance: Type = parse_quote! { Incrementer<Vec<u8>, 4> };
+        let info = crate::ItemImplInfo::instantiate(&mut input, &instance).unwrap();
+        let actual = info.wrapper_code();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+
+        // The methods are still generic, without the bindgen attributes.
+        let input = quote::quote! { #input }.to_string();
+        assert!(input.contains("pub fn inc (& mut self , by : T) -> [T ; N]"));
+        assert!(!input.contains("payable"));
+
+        let mut input: syn::ItemImpl = parse_quote! {
+            impl<T, U> Pair<T> {
+                pub fn get(&self) -> U { }
+            }
+        };
+        let instance: Type = parse_quote! { Pair<u32> };
+        let err = crate::ItemImplInfo::instantiate(&mut input, &instance).err().unwrap();
+        assert_eq!(err.to_string(), "`U` has to be a generic argument of the type of the impl block to be instantiated.");
+        let instance: Type = parse_quote! { Other<u32> };
+        assert!(crate::ItemImplInfo::instantiate(&mut input, &instance).is_err());
+    }
+
     #[test]
     fn trait_implt() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
@@ -148,6 +506,28 @@ mod tests {
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
 
+    #[test]
+    fn non_reentrant_promise_or_value() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[non_reentrant(then = "on_withdraw")] pub fn withdraw(&mut self, amount: U128) -> PromiseOrValue<U128> { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn non_reentrant_callback() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[private] #[non_reentrant(callback, key = "account_id")] pub fn on_withdraw(&mut self, account_id: AccountId, #[callback_result] result: Result<(), PromiseError>) -> bool { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
     #[test]
     fn callback_args_only() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
@@ -170,6 +550,17 @@ mod tests {
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
 
+    #[test]
+    fn callback_args_join() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[private] pub fn method(&self, #[callback_unwrap] x: u64, #[callback_join] y: (Result<String, PromiseError>, Result<(), PromiseError>), #[callback_result] z: Result<u8, PromiseError>) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
     #[test]
     fn callback_args_vec() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
@@ -201,7 +592,7 @@ mod tests {
             pub fn method(k: &mut u64) { }
         };
         let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
-        let expected = "Init function must return the contract state.";
+        let expected = "[near-sdk E0009] Init function must return the contract state.";
         assert_eq!(expected, actual.to_string());
     }
 
@@ -230,6 +621,56 @@ mod tests {
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
 
+    #[test]
+    fn init_payable_shorthand() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut shorthand: ImplItemFn = parse_quote! {
+            #[init(payable)]
+            pub fn method(k: &mut u64) -> Self { }
+        };
+        let mut method: ImplItemFn = parse_quote! {
+            #[init]
+            #[payable]
+            pub fn method(k: &mut u64) -> Self { }
+        };
+        let shorthand_info =
+            ImplItemMethodInfo::new(&mut shorthand, None, impl_type.clone()).unwrap().unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        assert_eq!(
+            shorthand_info.method_wrapper().to_string(),
+            method_info.method_wrapper().to_string()
+        );
+    }
+
+    #[test]
+    fn init_on_deploy() {
+        let mut input: syn::ItemImpl = parse_quote! {
+            impl Hello {
+                #[init(on_deploy)]
+                #[handle_result]
+                pub fn new() -> Result<Self, &'static str> { }
+                pub fn get(&self) -> u64 { }
+            }
+        };
+        let info = crate::ItemImplInfo::new(&mut input).unwrap();
+        let actual = info.init_on_deploy_code();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn init_on_deploy_with_args() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[init(on_deploy)]
+            pub fn new(value: u64) -> Self { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        assert_eq!(
+            actual.to_string(),
+            "#[init(on_deploy)] is only supported for init methods without arguments."
+        );
+    }
+
     #[test]
     fn args_return_mut_borsh() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
@@ -242,6 +683,24 @@ mod tests {
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
 
+    #[test]
+    fn args_return_custom_serializer() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[result_serializer(with = cbor::Cbor)]
+            pub fn method(&mut self, #[serializer(with = cbor::Cbor)] k: u64, #[serializer(with = cbor::Cbor)] m: Bar, #[callback_result] #[serializer(with = cbor::Cbor)] r: Result<u64, PromiseError>) -> Option<u64> { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type.clone()).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+
+        let mut method: ImplItemFn = parse_quote! {
+            pub fn method(&self, #[serializer(borsh, with = cbor::Cbor)] k: u64) { }
+        };
+        let err = ImplItemMethodInfo::new(&mut method, None, impl_type).err().unwrap();
+        assert_eq!(err.to_string(), "Only one of `borsh`, `json` or `with` can be specified.");
+    }
+
     #[test]
     fn callback_args_mixed_serialization() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
@@ -271,6 +730,136 @@ mod tests {
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
 
+    #[test]
+    fn private_method_allow() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[private(allow = ["dao.near", "factory.near"])]
+            pub fn on_update(&mut self, value: u64) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn private_method_allow_fn() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[private(allow = ["dao.near"], allow_fn = "is_controller")]
+            pub fn get_secret(&self) -> String { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn private_method_invalid_account() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[private(allow = ["DAO.near"])]
+            pub fn on_update(&mut self) { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        assert_eq!("Invalid account ID.", actual.to_string());
+    }
+
+    #[test]
+    fn payable_deposit_bounds() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[payable(min = "0.1 NEAR", max = "Hello::MAX_DEPOSIT", refund_excess)]
+            pub fn deposit(&mut self) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn payable_exact_deposit() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[payable(exact = 1)]
+            pub fn transfer(&mut self, receiver_id: AccountId) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn payable_deposit_arg() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[payable(max = "1 NEAR", refund_excess)]
+            pub fn deposit(&mut self, account_id: AccountId, #[deposit] deposit: NearToken) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn deposit_arg_not_payable() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            pub fn deposit(&mut self, #[deposit] deposit: NearToken) { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        assert_eq!("Only #[payable] methods can take a #[deposit] argument.", actual.to_string());
+    }
+
+    #[test]
+    fn payable_refund_excess_without_bound() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[payable(refund_excess)]
+            pub fn deposit(&mut self) { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        assert_eq!("`refund_excess` requires `exact`, `max` or `min`.", actual.to_string());
+    }
+
+    #[test]
+    fn only_role() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[only(role = "minter")]
+            pub fn mint(&mut self, amount: u64) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn only_role_view() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[only(role = "minter")]
+            pub fn minted(&self) -> u64 { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        assert_eq!(
+            "Methods with `#[only(...)]` or `#[only_owner]` must be call methods taking `&mut self`, which is used to check the predecessor.",
+            actual.to_string()
+        );
+    }
+
+    #[test]
+    fn only_owner() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[only_owner]
+            pub fn pause(&mut self) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
     #[test]
     fn handle_result_json() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
@@ -342,4 +931,50 @@ mod tests {
         let actual = method_info.method_wrapper();
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
+
+    #[test]
+    fn deny_unknown_arguments() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[near(deny_unknown_arguments)]
+            pub fn method(&mut self, k: u64, m: String) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn deny_unknown_arguments_no_args() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            #[near(deny_unknown_arguments)]
+            pub fn method(&self) -> u64 { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn arg_default() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            pub fn method(&mut self, k: u64, #[arg(default)] m: String, #[arg(default = "10")] limit: u32) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        let actual = method_info.method_wrapper();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn arg_default_borsh() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemFn = parse_quote! {
+            pub fn method(&mut self, #[serializer(borsh)] #[arg(default)] k: u64) { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        let expected = "Default values are only supported for arguments deserialized from JSON.";
+        assert_eq!(expected, actual.to_string());
+    }
 }
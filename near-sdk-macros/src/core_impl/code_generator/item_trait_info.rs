@@ -11,7 +11,7 @@ impl ItemTraitInfo {
 
         let ext_methods = generate_ext_function_wrappers(
             &self.original.ident,
-            self.methods.iter().map(|m| &m.attr_sig_info),
+            self.methods.iter().map(|m| (&m.attr_sig_info, m.defaults.or(self.defaults))),
         );
 
         quote! {
@@ -30,7 +30,7 @@ impl ItemTraitInfo {
 mod tests {
     use syn::ItemTrait;
     use quote::quote;
-    use crate::core_impl::info_extractor::ItemTraitInfo;
+    use crate::core_impl::info_extractor::{ExtCallDefaults, ItemTraitInfo};
     use crate::core_impl::utils::test_helpers::{local_insta_assert_snapshot, pretty_print_syn_str};
 
     #[test]
@@ -51,11 +51,31 @@ mod tests {
                 }
             }
         ).unwrap();
-        let info = ItemTraitInfo::new(&mut t, None).unwrap();
+        let info = ItemTraitInfo::new(&mut t, None, ExtCallDefaults::default()).unwrap();
         let actual = info.wrap_trait_ext();
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
 
+    #[test]
+    fn ext_call_defaults() {
+        let mut t: ItemTrait = syn::parse2(
+            quote!{
+                pub trait Bob {
+                    fn ping(&self);
+                    #[gas("50 Tgas")]
+                    #[deposit("1 NEAR")]
+                    fn store(&mut self, value: String);
+                }
+            }
+        ).unwrap();
+        let defaults = ExtCallDefaults { deposit: Some(0), static_gas: Some(10_000_000_000_000) };
+        let info = ItemTraitInfo::new(&mut t, None, defaults).unwrap();
+        let actual = info.wrap_trait_ext();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+        assert!(t.items.iter().all(|item| matches!(item, syn::TraitItem::Fn(f) if f.attrs.is_empty())));
+    }
+
     #[test]
     fn serialize_with_borsh() {
         let mut t: ItemTrait = syn::parse2(
@@ -66,7 +86,7 @@ mod tests {
               }
             }
         ).unwrap();
-        let info = ItemTraitInfo::new(&mut t, None).unwrap();
+        let info = ItemTraitInfo::new(&mut t, None, ExtCallDefaults::default()).unwrap();
         let actual = info.wrap_trait_ext();
 
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
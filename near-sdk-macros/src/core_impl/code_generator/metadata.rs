@@ -2,15 +2,20 @@ use proc_macro2::Ident;
 use quote::quote;
 use syn::Generics;
 
-/// Generates a view method to retrieve the source metadata.
+/// Generates a view method to retrieve the source metadata, parsed from the
+/// `CONTRACT_SOURCE_METADATA` const so that its schema is part of the ABI of the contract.
 pub(crate) fn generate_contract_metadata_method(
     ident: &Ident,
     generics: &Generics,
 ) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     quote! {
-        impl #generics #ident #generics {
-            pub fn contract_source_metadata() {
-                near_sdk::env::value_return(CONTRACT_SOURCE_METADATA.as_bytes())
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Returns the NEP-330 source metadata of the contract.
+            pub fn contract_source_metadata() -> ::near_sdk::contract_metadata::ContractSourceMetadata {
+                ::near_sdk::serde_json::from_str(CONTRACT_SOURCE_METADATA).unwrap_or_else(|_| {
+                    ::near_sdk::env::panic_str("Cannot parse the contract source metadata.")
+                })
             }
         }
     }
@@ -26,6 +26,16 @@ pub fn generate_serializer(
                 Err(_) => ::near_sdk::env::panic_str("Failed to serialize the cross contract args using Borsh."),
             }
         },
+        SerializerType::Custom(serializer) => quote! {
+            match <#serializer as ::near_sdk::ArgsSerializer>::serialize(&__args) {
+                Ok(serialized) => serialized,
+                Err(e) => ::near_sdk::env::panic_str(&::std::format!(
+                    "Failed to serialize the cross contract args using {}: {}",
+                    <#serializer as ::near_sdk::ArgsSerializer>::FORMAT,
+                    e,
+                )),
+            }
+        },
     };
 
     quote! {
@@ -4,6 +4,8 @@ pub(crate) struct BuildInfo {
     build_command: Vec<String>,
     contract_path: String,
     source_code_snapshot: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_wasm_path: Option<String>,
 }
 
 const ERR_EMPTY_BUILD_ENVIRONMENT: &str = "`NEP330_BUILD_INFO_BUILD_ENVIRONMENT` is set, \
@@ -16,12 +18,21 @@ const ERR_PARSE_BUILD_COMMAND: &str = "problem parsing `NEP330_BUILD_INFO_BUILD_
 const ERR_UNSET_CONTRACT_PATH: &str = "`NEP330_BUILD_INFO_CONTRACT_PATH` was provided, \
                                         but it's not set!";
 
-const ERR_UNSET_OR_EMPTY_SOURCE_SNAPSHOT: &str = "`NEP330_BUILD_INFO_SOURCE_CODE_SNAPSHOT` is \
-                                                    required, when `NEP330_BUILD_INFO_BUILD_ENVIRONMENT` \
+const ERR_UNSET_OR_EMPTY_SOURCE_SNAPSHOT: &str = "`NEP330_BUILD_INFO_SOURCE_CODE_SNAPSHOT` or \
+                                                    `NEP330_BUILD_INFO_GIT_COMMIT` is required, \
+                                                    when `NEP330_BUILD_INFO_BUILD_ENVIRONMENT` \
                                                     is set, but it's either not set or empty!";
 
+const ERR_GIT_COMMIT_WITHOUT_LINK: &str =
+    "`NEP330_BUILD_INFO_GIT_COMMIT` requires the link of the \
+                                            contract metadata, set by `link`, `NEP330_LINK` or the \
+                                            `repository` of the package, to be the git repository!";
+
 impl BuildInfo {
-    pub(super) fn from_env() -> Result<Self, String> {
+    /// Reads the build info from the `NEP330_BUILD_INFO_*` environment variables. Without
+    /// `NEP330_BUILD_INFO_SOURCE_CODE_SNAPSHOT`, the snapshot is the `NEP330_BUILD_INFO_GIT_COMMIT`
+    /// of the git repository at `link`.
+    pub(super) fn from_env(link: Option<&str>) -> Result<Self, String> {
         let build_environment = std::env::var("NEP330_BUILD_INFO_BUILD_ENVIRONMENT")
             .ok()
             .filter(|build_environment| !build_environment.is_empty())
@@ -34,13 +45,53 @@ impl BuildInfo {
         let build_command: Vec<String> = serde_json::from_str(&build_command)
             .map_err(|err| format!("{}: {}", ERR_PARSE_BUILD_COMMAND, err))?;
 
-        let source_code_snapshot = std::env::var("NEP330_BUILD_INFO_SOURCE_CODE_SNAPSHOT")
-            .ok()
-            .filter(|source_code_snapshot| !source_code_snapshot.is_empty())
-            .ok_or(ERR_UNSET_OR_EMPTY_SOURCE_SNAPSHOT.to_string())?;
+        let source_code_snapshot = match non_empty_var("NEP330_BUILD_INFO_SOURCE_CODE_SNAPSHOT") {
+            Some(source_code_snapshot) => source_code_snapshot,
+            None => {
+                let git_commit = non_empty_var("NEP330_BUILD_INFO_GIT_COMMIT")
+                    .ok_or(ERR_UNSET_OR_EMPTY_SOURCE_SNAPSHOT.to_string())?;
+                let link = link.ok_or(ERR_GIT_COMMIT_WITHOUT_LINK.to_string())?;
+                git_source_code_snapshot(link, &git_commit)
+            }
+        };
         let contract_path = std::env::var("NEP330_BUILD_INFO_CONTRACT_PATH")
             .map_err(|_| ERR_UNSET_CONTRACT_PATH.to_string())?;
+        let output_wasm_path = non_empty_var("NEP330_BUILD_INFO_OUTPUT_WASM_PATH");
+
+        Ok(Self {
+            build_environment,
+            build_command,
+            contract_path,
+            source_code_snapshot,
+            output_wasm_path,
+        })
+    }
+}
+
+fn non_empty_var(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|value| !value.is_empty())
+}
+
+/// Returns the NEP-330 snapshot of the `git_commit` of the git repository at `link`, e.g.
+/// `git+https://github.com/org/repo?rev=8d8a8a0fe86a1d8eb3bce45f04ab1a65fecf5a1b`.
+fn git_source_code_snapshot(link: &str, git_commit: &str) -> String {
+    let repository = link.strip_prefix("git+").unwrap_or(link).trim_end_matches('/');
+    format!("git+{}?rev={}", repository, git_commit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok(Self { build_environment, build_command, contract_path, source_code_snapshot })
+    #[test]
+    fn git_source_code_snapshot_of_link() {
+        assert_eq!(
+            git_source_code_snapshot("https://github.com/org/repo/", "8d8a8a0"),
+            "git+https://github.com/org/repo?rev=8d8a8a0"
+        );
+        assert_eq!(
+            git_source_code_snapshot("git+https://github.com/org/repo", "8d8a8a0"),
+            "git+https://github.com/org/repo?rev=8d8a8a0"
+        );
     }
 }
@@ -5,6 +5,7 @@ use proc_macro2::TokenStream;
 use quote::quote;
 
 mod build_info;
+mod standards;
 
 #[derive(FromMeta)]
 struct MacroConfig {
@@ -19,14 +20,20 @@ pub(crate) struct ContractMetadata {
     #[darling(multiple, rename = "standard")]
     standards: Vec<Standard>,
 
+    /// Whether the standards implemented by the `near-contract-standards` components of the
+    /// contract state are added to `standards`.
+    #[darling(default)]
+    #[serde(skip)]
+    auto_standards: bool,
+
     #[darling(skip)]
     build_info: Option<build_info::BuildInfo>,
 }
 
 impl quote::ToTokens for ContractMetadata {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        let version = &self.version;
-        let link = &self.link;
+        let version = self.version.as_ref().map(|version| quote! { version = #version, });
+        let link = self.link.as_ref().map(|link| quote! { link = #link, });
         let mut standards = quote! {};
         let standards_vec = &self.standards;
         for standard in standards_vec {
@@ -37,11 +44,13 @@ impl quote::ToTokens for ContractMetadata {
                 standard(standard = #standard_name, version = #standard_version),
             };
         }
+        let auto_standards = self.auto_standards.then(|| quote! { auto_standards, });
         tokens.extend(quote! {
             contract_metadata(
-                version = #version,
-                link = #link,
+                #version
+                #link
                 #standards
+                #auto_standards
             )
         })
     }
@@ -54,7 +63,7 @@ struct Standard {
 }
 
 impl ContractMetadata {
-    fn populate(mut self) -> Self {
+    fn populate(mut self, field_types: &[&syn::Type]) -> Self {
         if self.link.is_none() {
             let field_val = std::env::var("NEP330_LINK")
                 .or(std::env::var("CARGO_PKG_REPOSITORY"))
@@ -72,6 +81,18 @@ impl ContractMetadata {
             }
         }
 
+        if self.auto_standards {
+            for standard in standards::component_standards(field_types.iter().copied()) {
+                if self
+                    .standards
+                    .iter()
+                    .all(|s| !s.standard.eq_ignore_ascii_case(&standard.standard))
+                {
+                    self.standards.push(standard);
+                }
+            }
+        }
+
         // adding nep330 if it is not present
         if self.standards.is_empty()
             || self.standards.iter().all(|s| !s.standard.eq_ignore_ascii_case("nep330"))
@@ -82,7 +103,7 @@ impl ContractMetadata {
 
         if std::env::var("NEP330_BUILD_INFO_BUILD_ENVIRONMENT").is_ok() {
             self.build_info = Some(
-                build_info::BuildInfo::from_env()
+                build_info::BuildInfo::from_env(self.link.as_deref())
                     .expect("Build Details Extension field not provided or malformed"),
             );
         }
@@ -92,10 +113,14 @@ impl ContractMetadata {
 }
 
 /// Allows for the injection of the contract source metadata information into the contract code as
-/// a constant.
-pub(crate) fn contract_source_metadata_const(attr: proc_macro::TokenStream) -> TokenStream {
+/// a constant. `field_types` are the types of the fields of the contract state, in which the
+/// standards of `auto_standards` are looked up.
+pub(crate) fn contract_source_metadata_const(
+    attr: proc_macro::TokenStream,
+    field_types: &[&syn::Type],
+) -> TokenStream {
     if attr.to_string().is_empty() {
-        let metadata = serde_json::to_string(&ContractMetadata::default().populate())
+        let metadata = serde_json::to_string(&ContractMetadata::default().populate(field_types))
             .expect("ContractMetadata implements Serialize");
 
         return quote! {
@@ -121,7 +146,7 @@ pub(crate) fn contract_source_metadata_const(attr: proc_macro::TokenStream) -> T
         &args
             .contract_metadata
             .expect("Attribute input must be present given standard was followed")
-            .populate(),
+            .populate(field_types),
     )
     .expect("ContractMetadata implements Serialize");
 
@@ -0,0 +1,86 @@
+use super::Standard;
+
+/// Standards implemented by the components of `near-contract-standards`, by the name of the type
+/// embedded in the contract state.
+const COMPONENT_STANDARDS: &[(&str, &[(&str, &str)])] = &[
+    ("FungibleToken", &[("nep141", "1.0.0"), ("nep145", "1.0.0")]),
+    ("FungibleTokenMetadata", &[("nep148", "1.0.0")]),
+    ("NonFungibleToken", &[("nep171", "1.0.0")]),
+    ("NFTContractMetadata", &[("nep177", "2.0.0")]),
+];
+
+/// Returns the standards implemented by the components of `near-contract-standards` found in the
+/// types of the fields of the contract state, including in their generic arguments, e.g. the
+/// `FungibleTokenMetadata` of a `LazyOption<FungibleTokenMetadata>`.
+pub(super) fn component_standards<'a>(
+    types: impl IntoIterator<Item = &'a syn::Type>,
+) -> Vec<Standard> {
+    let mut standards: Vec<Standard> = vec![];
+    for ty in types {
+        for name in type_names(ty) {
+            let component_standards = COMPONENT_STANDARDS
+                .iter()
+                .filter(|(component, _)| name == *component)
+                .flat_map(|(_, standards)| standards.iter());
+            for (standard, version) in component_standards {
+                if !standards.iter().any(|s| s.standard == *standard) {
+                    standards.push(Standard {
+                        standard: standard.to_string(),
+                        version: version.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    standards
+}
+
+/// Returns the names of the last segments of the paths in `ty`.
+fn type_names(ty: &syn::Type) -> Vec<String> {
+    let mut names = vec![];
+    match ty {
+        syn::Type::Path(type_path) => {
+            if let Some(segment) = type_path.path.segments.last() {
+                names.push(segment.ident.to_string());
+                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                    for arg in &args.args {
+                        if let syn::GenericArgument::Type(ty) = arg {
+                            names.extend(type_names(ty));
+                        }
+                    }
+                }
+            }
+        }
+        syn::Type::Group(group) => names.extend(type_names(&group.elem)),
+        syn::Type::Paren(paren) => names.extend(type_names(&paren.elem)),
+        syn::Type::Tuple(tuple) => names.extend(tuple.elems.iter().flat_map(type_names)),
+        _ => {}
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn component_standards_of_fields() {
+        let types: Vec<syn::Type> = vec![
+            parse_quote! { near_contract_standards::fungible_token::FungibleToken },
+            parse_quote! { LazyOption<FungibleTokenMetadata> },
+            parse_quote! { FungibleToken },
+            parse_quote! { u64 },
+        ];
+        let standards: Vec<_> =
+            component_standards(&types).into_iter().map(|s| (s.standard, s.version)).collect();
+        assert_eq!(
+            standards,
+            vec![
+                ("nep141".to_string(), "1.0.0".to_string()),
+                ("nep145".to_string(), "1.0.0".to_string()),
+                ("nep148".to_string(), "1.0.0".to_string()),
+            ]
+        );
+    }
+}
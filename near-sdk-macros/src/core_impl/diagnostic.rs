@@ -0,0 +1,96 @@
+use proc_macro2::Span;
+use quote::ToTokens;
+use std::fmt;
+
+/// Errors reported by the contract macros, each with a stable code printed at the start of the
+/// error message as `[near-sdk E0000]`, so that tooling can map errors to the documentation in
+/// `near_sdk::diagnostics`. Codes are never reused: a removed diagnostic keeps its code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Diagnostic {
+    /// Function argument with a pattern other than an identifier.
+    UnsupportedArgPattern,
+    /// Argument or return type which cannot be (de)serialized by the contract API.
+    UnsupportedType,
+    /// Contract method with type or const generics.
+    GenericMethod,
+    /// `#[near]` impl block with generics.
+    GenericImpl,
+    /// View method marked `#[payable]`.
+    PayableView,
+    /// `#[init]` method which is also a view or call method.
+    InitWithReceiver,
+    /// `#[init]` method with a `#[result_serializer]`.
+    InitResultSerializer,
+    /// `#[init]` method taking `self`.
+    InitSelf,
+    /// `#[init]` method which does not return the contract state.
+    InitReturn,
+    /// `#[handle_result]` method which does not return a `Result`.
+    HandleResultType,
+    /// Method returning a `Result` without `#[handle_result]`.
+    ResultWithoutHandleResult,
+    /// `Self` used in the signature of a non-init method.
+    SelfInNonInit,
+    /// Method using a name reserved for the methods generated by the SDK.
+    ReservedMethod,
+    /// `#[callback_result]` argument which is not a `Result<T, PromiseError>`.
+    CallbackResultType,
+    /// Two contract methods of the same impl block with the same name.
+    DuplicateMethod,
+    /// `#[near(async)]` method which cannot be split into promises and callbacks.
+    AsyncMethod,
+    /// Invalid `#[non_reentrant]` method, e.g. a view or a guarded method returning a promise
+    /// without its callback.
+    NonReentrant,
+    /// `#[then]` method which cannot be chained with its callback.
+    ThenMethod,
+    /// View method writing to the storage.
+    StateWriteInView,
+    /// `#[payable(refund_excess)]` method reading the attached deposit, which includes the refund.
+    RefundedDepositRead,
+}
+
+impl Diagnostic {
+    pub(crate) fn code(self) -> &'static str {
+        match self {
+            Diagnostic::UnsupportedArgPattern => "E0001",
+            Diagnostic::UnsupportedType => "E0002",
+            Diagnostic::GenericMethod => "E0003",
+            Diagnostic::GenericImpl => "E0004",
+            Diagnostic::PayableView => "E0005",
+            Diagnostic::InitWithReceiver => "E0006",
+            Diagnostic::InitResultSerializer => "E0007",
+            Diagnostic::InitSelf => "E0008",
+            Diagnostic::InitReturn => "E0009",
+            Diagnostic::HandleResultType => "E0010",
+            Diagnostic::ResultWithoutHandleResult => "E0011",
+            Diagnostic::SelfInNonInit => "E0012",
+            Diagnostic::ReservedMethod => "E0013",
+            Diagnostic::CallbackResultType => "E0014",
+            Diagnostic::DuplicateMethod => "E0015",
+            Diagnostic::AsyncMethod => "E0016",
+            Diagnostic::NonReentrant => "E0017",
+            Diagnostic::ThenMethod => "E0018",
+            Diagnostic::StateWriteInView => "E0019",
+            Diagnostic::RefundedDepositRead => "E0020",
+        }
+    }
+
+    fn message<M: fmt::Display>(self, message: M) -> String {
+        format!("[near-sdk {}] {}", self.code(), message)
+    }
+
+    /// Returns the error of the diagnostic at `span`.
+    pub(crate) fn error<M: fmt::Display>(self, span: Span, message: M) -> syn::Error {
+        syn::Error::new(span, self.message(message))
+    }
+
+    /// Returns the error of the diagnostic spanning `tokens`.
+    pub(crate) fn error_spanned<T: ToTokens, M: fmt::Display>(
+        self,
+        tokens: T,
+        message: M,
+    ) -> syn::Error {
+        syn::Error::new_spanned(tokens, self.message(message))
+    }
+}
@@ -1,9 +1,10 @@
-use crate::core_impl::info_extractor::SerializerType;
-use crate::core_impl::utils;
+use crate::core_impl::info_extractor::{SerializerAttr, SerializerType};
+use crate::core_impl::{utils, Diagnostic};
 use proc_macro2::{Span, TokenStream};
 use quote::ToTokens;
-use syn::{Attribute, Error, Ident, Pat, PatType, Token, Type};
+use syn::{Attribute, Error, Expr, Ident, LitStr, Pat, PatType, Token, Type};
 
+#[derive(Clone)]
 pub enum BindgenArgType {
     /// Argument that we read from `env::input()`.
     Regular,
@@ -13,9 +14,25 @@ pub enum BindgenArgType {
     CallbackResultArg,
     /// An argument that we read from all `env::promise_result()`.
     CallbackArgVec,
+    /// A tuple of arguments that we read from consecutive `env::promise_result()`, each of which
+    /// handles the error.
+    CallbackJoinArg,
+    /// An argument set to the deposit kept by a payable method, marked with `#[deposit]`.
+    Deposit,
+}
+
+/// Value of a JSON argument missing from the input, set with `#[arg(default)]` or
+/// `#[arg(default = "expr")]`.
+#[derive(Clone)]
+pub enum ArgDefault {
+    /// `Default::default()` of the type of the argument.
+    Default,
+    /// The value of the expression.
+    Expr(Expr),
 }
 
 /// A single argument of a function after it was processed by the bindgen.
+#[derive(Clone)]
 pub struct ArgInfo {
     /// Attributes not related to bindgen.
     #[allow(unused)]
@@ -38,6 +55,8 @@ pub struct ArgInfo {
     pub bindgen_ty: BindgenArgType,
     /// Type of serializer that we use for this argument.
     pub serializer_ty: SerializerType,
+    /// Value of the argument if it is missing from the JSON input, if any.
+    pub default: Option<ArgDefault>,
     /// Spans of all occurrences of the `Self` token, if any.
     pub self_occurrences: Vec<Span>,
     /// The original `PatType` of the argument.
@@ -52,6 +71,7 @@ use darling::FromAttributes;
 struct AttributeConfig {
     borsh: Option<bool>,
     json: Option<bool>,
+    with: Option<syn::Path>,
 }
 
 impl ArgInfo {
@@ -62,7 +82,7 @@ impl ArgInfo {
             Pat::Ident(pat_ident) => {
                 Ok((pat_ident.by_ref, pat_ident.mutability, pat_ident.ident.clone()))
             }
-            _ => Err(Error::new_spanned(
+            _ => Err(Diagnostic::UnsupportedArgPattern.error_spanned(
                 &original.pat,
                 "Only identity patterns are supported in function arguments.",
             )),
@@ -79,6 +99,7 @@ impl ArgInfo {
         let mut bindgen_ty = BindgenArgType::Regular;
         // In the absence of serialization attributes this is a JSON serialization.
         let mut serializer_ty = SerializerType::JSON;
+        let mut default = None;
         let mut more_errors: Vec<Error> = Vec::new();
         for attr in &original.attrs {
             let attr_str = attr.path().to_token_stream().to_string();
@@ -92,6 +113,16 @@ impl ArgInfo {
                 "callback_vec" => {
                     bindgen_ty = BindgenArgType::CallbackArgVec;
                 }
+                "callback_join" => {
+                    bindgen_ty = BindgenArgType::CallbackJoinArg;
+                }
+                "deposit" => {
+                    bindgen_ty = BindgenArgType::Deposit;
+                }
+                "arg" => match parse_arg_attr(attr) {
+                    Ok(value) => default = Some((value, attr)),
+                    Err(e) => more_errors.push(e),
+                },
                 "serializer" => {
                     let args = match AttributeConfig::from_attributes(&original.attrs) {
                         Ok(args) => args,
@@ -100,23 +131,12 @@ impl ArgInfo {
                             continue;
                         }
                     };
-                    if args.borsh.is_some() && args.json.is_some() {
-                        let spanned_error = syn::Error::new_spanned(
+                    match SerializerAttr::from_args(args.borsh, args.json, args.with) {
+                        Some(serializer) => serializer_ty = serializer.serializer_type,
+                        None => more_errors.push(syn::Error::new_spanned(
                             attr,
-                            "Only one of `borsh` or `json` can be specified.",
-                        );
-                        more_errors.push(spanned_error);
-                    };
-
-                    if let Some(borsh) = args.borsh {
-                        if borsh {
-                            serializer_ty = SerializerType::Borsh;
-                        }
-                    }
-                    if let Some(json) = args.json {
-                        if json {
-                            serializer_ty = SerializerType::JSON;
-                        }
+                            "Only one of `borsh`, `json` or `with` can be specified.",
+                        )),
                     }
                 }
                 _ => {
@@ -125,6 +145,42 @@ impl ArgInfo {
             }
         }
 
+        if let Some((_, attr)) = &default {
+            if !matches!(bindgen_ty, BindgenArgType::Regular)
+                || serializer_ty != SerializerType::JSON
+            {
+                more_errors.push(Error::new_spanned(
+                    attr,
+                    "Default values are only supported for arguments deserialized from JSON.",
+                ));
+            }
+        }
+        let default = default.map(|(value, _)| value);
+
+        // Checked here rather than when generating the wrapper, which is only compiled for wasm.
+        if let (BindgenArgType::CallbackResultArg, Ok((_, (_, _, ty)))) =
+            (&bindgen_ty, &result_sanitize_and_ty)
+        {
+            if utils::extract_ok_type(ty).is_none() {
+                more_errors.push(Diagnostic::CallbackResultType.error_spanned(
+                    ty,
+                    "Function parameters marked with #[callback_result] should have type \
+                    Result<T, PromiseError>",
+                ));
+            }
+        }
+        if let (BindgenArgType::CallbackJoinArg, Ok((_, (_, _, ty)))) =
+            (&bindgen_ty, &result_sanitize_and_ty)
+        {
+            if utils::extract_join_ok_types(ty).is_none() {
+                more_errors.push(Diagnostic::CallbackResultType.error_spanned(
+                    ty,
+                    "Function parameters marked with #[callback_join] should have type \
+                    (Result<T1, PromiseError>, Result<T2, PromiseError>, ...)",
+                ));
+            }
+        }
+
         original.attrs.retain(|attr| {
             let attr_str = attr.path().to_token_stream().to_string();
             attr_str != "callback"
@@ -132,6 +188,9 @@ impl ArgInfo {
                 && attr_str != "serializer"
                 && attr_str != "callback_result"
                 && attr_str != "callback_unwrap"
+                && attr_str != "callback_join"
+                && attr_str != "deposit"
+                && attr_str != "arg"
         });
 
         match (&pat_info, &result_sanitize_and_ty, more_errors.is_empty()) {
@@ -149,6 +208,7 @@ impl ArgInfo {
                 ty: ty.clone(),
                 bindgen_ty,
                 serializer_ty,
+                default,
                 self_occurrences: sanitize_self.self_occurrences.clone(),
                 original: original.clone(),
             }),
@@ -168,3 +228,21 @@ impl ArgInfo {
         })
     }
 }
+
+/// Parses `#[arg(default)]` or `#[arg(default = "expr")]`.
+fn parse_arg_attr(attr: &Attribute) -> syn::Result<ArgDefault> {
+    let mut default = None;
+    attr.parse_nested_meta(|meta| {
+        if !meta.path.is_ident("default") {
+            return Err(meta.error("Expected `default` or `default = \"expr\"`."));
+        }
+        default = Some(if meta.input.peek(Token![=]) {
+            let expr: LitStr = meta.value()?.parse()?;
+            ArgDefault::Expr(expr.parse()?)
+        } else {
+            ArgDefault::Default
+        });
+        Ok(())
+    })?;
+    default.ok_or_else(|| Error::new_spanned(attr, "Expected `default` or `default = \"expr\"`."))
+}
@@ -1,14 +1,17 @@
 use super::visitor::Visitor;
 use super::{
-    ArgInfo, BindgenArgType, HandleResultAttr, InitAttr, MethodKind, SerializerAttr, SerializerType,
+    parse_deposit, parse_gas, AbiHints, ArgInfo, BindgenArgType, CallMethod, DepositBounds,
+    HandleResultAttr, InitAttr, MethodKind, NonReentrant, OnlyCallers, PrivateAllow,
+    SerializerAttr, SerializerType,
 };
-use crate::core_impl::{utils, Returns};
+use crate::core_impl::{utils, Diagnostic, ReturnKind, Returns};
 use proc_macro2::{Span, TokenStream as TokenStream2};
-use quote::ToTokens;
+use quote::{format_ident, ToTokens};
 use syn::spanned::Spanned;
 use syn::{Attribute, Error, FnArg, GenericParam, Ident, ReturnType, Signature, Type};
 
 /// Information extracted from method attributes and signature.
+#[derive(Clone)]
 pub struct AttrSigInfo {
     /// The name of the method.
     pub ident: Ident,
@@ -24,19 +27,68 @@ pub struct AttrSigInfo {
     pub input_serializer: SerializerType,
     /// The original method signature.
     pub original_sig: Signature,
+    /// The name of the view generated by `#[near(view_callback_preview)]`, if any.
+    pub view_callback_preview: Option<Ident>,
+    /// Whether JSON arguments other than the ones of the method are rejected, with
+    /// `#[near(deny_unknown_arguments)]` on the method or its impl block.
+    pub deny_unknown_arguments: bool,
+    /// Callers other than the contract itself allowed by `#[private(...)]`.
+    pub private_allow: PrivateAllow,
+    /// Bounds of the deposit set by `#[payable(...)]`.
+    pub deposit_bounds: DepositBounds,
+    /// Callers the method is restricted to by `#[only(...)]` or `#[only_owner]`.
+    pub only: Option<OnlyCallers>,
+    /// Hints for the callers of the method in the ABI, set by `#[near(abi(...))]`.
+    pub abi_hints: AbiHints,
 }
 
+/// Arguments of `abi(...)` in `#[near(abi(...))]`.
+#[derive(darling::FromMeta, Clone, Debug)]
+struct AbiHintsConfig {
+    min_deposit: Option<syn::Lit>,
+    recommended_gas: Option<syn::Lit>,
+}
+
+use darling::util::Override;
 use darling::FromAttributes;
 #[derive(darling::FromAttributes, Clone, Debug)]
 #[darling(
-    attributes(init, payable, private, result_serializer, serializer, handle_result),
+    attributes(
+        init,
+        payable,
+        private,
+        non_reentrant,
+        result_serializer,
+        serializer,
+        handle_result,
+        only,
+        only_owner,
+        near
+    ),
     forward_attrs(serializer)
 )]
 struct AttributeConfig {
     borsh: Option<bool>,
     json: Option<bool>,
+    with: Option<syn::Path>,
     ignore_state: Option<bool>,
+    schema: Option<bool>,
+    on_deploy: Option<bool>,
+    payable: Option<bool>,
     aliased: Option<bool>,
+    view_callback_preview: Option<Override<String>>,
+    deny_unknown_arguments: Option<bool>,
+    callback: Option<bool>,
+    key: Option<syn::LitStr>,
+    then: Option<syn::LitStr>,
+    allow: Option<Vec<syn::LitStr>>,
+    allow_fn: Option<syn::LitStr>,
+    min: Option<syn::Lit>,
+    max: Option<syn::Lit>,
+    exact: Option<syn::Lit>,
+    refund_excess: Option<bool>,
+    role: Option<syn::LitStr>,
+    abi: Option<AbiHintsConfig>,
 }
 
 impl AttrSigInfo {
@@ -47,19 +99,22 @@ impl AttrSigInfo {
     ) -> syn::Result<Vec<Span>> {
         match original_sig.output {
             ReturnType::Default => Ok(vec![]),
-            ReturnType::Type(_, ref mut ty) => match ty.as_mut() {
-                x @ (Type::Array(_) | Type::Path(_) | Type::Tuple(_) | Type::Group(_)) => {
-                    let res = utils::sanitize_self(x, source_type)?;
-                    *ty = res.ty.into();
-                    Ok(res.self_occurrences)
-                }
-                Type::Reference(ref mut r) => {
-                    let res = utils::sanitize_self(&r.elem, source_type)?;
-                    r.elem = res.ty.into();
-                    Ok(res.self_occurrences)
+            ReturnType::Type(_, ref mut ty) => {
+                match ty.as_mut() {
+                    x @ (Type::Array(_) | Type::Path(_) | Type::Tuple(_) | Type::Group(_)) => {
+                        let res = utils::sanitize_self(x, source_type)?;
+                        *ty = res.ty.into();
+                        Ok(res.self_occurrences)
+                    }
+                    Type::Reference(ref mut r) => {
+                        let res = utils::sanitize_self(&r.elem, source_type)?;
+                        r.elem = res.ty.into();
+                        Ok(res.self_occurrences)
+                    }
+                    _ => Err(Diagnostic::UnsupportedType
+                        .error(ty.span(), "Unsupported contract API type.")),
                 }
-                _ => Err(Error::new(ty.span(), "Unsupported contract API type.")),
-            },
+            }
         }
     }
 
@@ -73,14 +128,14 @@ impl AttrSigInfo {
         for generic in &original_sig.generics.params {
             match generic {
                 GenericParam::Type(type_generic) => {
-                    errors.push(Error::new(
+                    errors.push(Diagnostic::GenericMethod.error(
                         type_generic.span(),
                         "Contract API is not allowed to have generics.",
                     ));
                 }
                 GenericParam::Const(const_generic) => {
                     // `generic.span()` points to the `const` part of const generics, so we use `ident` explicitly.
-                    errors.push(Error::new(
+                    errors.push(Diagnostic::GenericMethod.error(
                         const_generic.ident.span(),
                         "Contract API is not allowed to have generics.",
                     ));
@@ -96,6 +151,12 @@ impl AttrSigInfo {
 
         let ident = original_sig.ident.clone();
         let mut non_bindgen_attrs = vec![];
+        let mut view_callback_preview = None;
+        let mut deny_unknown_arguments = false;
+        let mut private_allow = PrivateAllow::default();
+        let mut deposit_bounds = DepositBounds::default();
+        let mut only = None;
+        let mut abi_hints = AbiHints::default();
 
         let args = AttributeConfig::from_attributes(original_attrs)?;
         // Visit attributes
@@ -103,36 +164,111 @@ impl AttrSigInfo {
             let attr_str = attr.path().to_token_stream().to_string();
             match attr_str.as_str() {
                 "init" => {
-                    let mut init_attr = InitAttr { ignore_state: false };
-                    if let Some(state) = args.ignore_state {
-                        init_attr.ignore_state = state;
-                    }
+                    let init_attr = InitAttr {
+                        ignore_state: args.ignore_state.unwrap_or_default(),
+                        schema: args.schema.unwrap_or_default(),
+                        on_deploy: args.on_deploy.unwrap_or_default(),
+                    };
                     visitor.visit_init_attr(attr, &init_attr)?;
+                    // `#[init(payable)]` is a shorthand for `#[init] #[payable]`.
+                    if args.payable.unwrap_or_default() {
+                        visitor.visit_payable_attr(attr)?;
+                    }
                 }
                 "payable" => {
                     visitor.visit_payable_attr(attr)?;
+                    deposit_bounds = DepositBounds {
+                        min: args.min.as_ref().map(parse_deposit_bound).transpose()?,
+                        max: args.max.as_ref().map(parse_deposit_bound).transpose()?,
+                        exact: args.exact.as_ref().map(parse_deposit_bound).transpose()?,
+                        refund_excess: args.refund_excess.unwrap_or_default(),
+                    };
+                    if deposit_bounds.exact.is_some()
+                        && (deposit_bounds.min.is_some() || deposit_bounds.max.is_some())
+                    {
+                        return Err(Error::new(
+                            attr.span(),
+                            "`exact` can't be combined with `min` or `max`.",
+                        ));
+                    }
+                    if deposit_bounds.refund_excess && deposit_bounds.is_empty() {
+                        return Err(Error::new(
+                            attr.span(),
+                            "`refund_excess` requires `exact`, `max` or `min`.",
+                        ));
+                    }
                 }
                 "private" => {
                     visitor.visit_private_attr(attr)?;
+                    for account in args.allow.iter().flatten() {
+                        if !is_valid_account_id(&account.value()) {
+                            return Err(Error::new(account.span(), "Invalid account ID."));
+                        }
+                        private_allow.accounts.push(account.value());
+                    }
+                    if let Some(allow_fn) = &args.allow_fn {
+                        private_allow.allow_fn = Some(allow_fn.parse().map_err(|_| {
+                            Error::new(allow_fn.span(), "`allow_fn` must be a method name.")
+                        })?);
+                    }
                 }
-                "result_serializer" => {
-                    if args.borsh.is_some() && args.json.is_some() {
+                "only" | "only_owner" => {
+                    if only.is_some() {
                         return Err(Error::new(
                             attr.span(),
-                            "Only one of `borsh` or `json` can be specified.",
+                            "Only one of `#[only(...)]` or `#[only_owner]` can be specified.",
                         ));
-                    };
-                    let mut serializer = SerializerAttr { serializer_type: SerializerType::JSON };
-                    if let Some(borsh) = args.borsh {
-                        if borsh {
-                            serializer.serializer_type = SerializerType::Borsh;
-                        }
                     }
-                    if let Some(json) = args.json {
-                        if json {
-                            serializer.serializer_type = SerializerType::JSON;
+                    let callers = if attr_str == "only_owner" {
+                        OnlyCallers::Owner
+                    } else {
+                        let role = args.role.as_ref().ok_or_else(|| {
+                            Error::new(
+                                attr.span(),
+                                "Expected the role, e.g. `#[only(role = \"admin\")]`.",
+                            )
+                        })?;
+                        OnlyCallers::Role(role.value())
+                    };
+                    only = Some((callers, attr.span()));
+                }
+                "non_reentrant" => {
+                    let key = args.key.as_ref().map(parse_method_name).transpose()?;
+                    let then = args.then.as_ref().map(parse_method_name).transpose()?;
+                    let non_reentrant = if args.callback.unwrap_or_default() {
+                        if then.is_some() {
+                            return Err(Diagnostic::NonReentrant.error(
+                                attr.span(),
+                                "`then` names the callback of a guarded method, it can't be set \
+                                on the callback.",
+                            ));
                         }
-                    }
+                        let key = key.ok_or_else(|| {
+                            Diagnostic::NonReentrant.error(
+                                attr.span(),
+                                "A `#[non_reentrant(callback)]` method must name the argument \
+                                holding the key of the guard to clear, e.g. the account which \
+                                called the guarded method: \
+                                `#[non_reentrant(callback, key = \"account_id\")]`.",
+                            )
+                        })?;
+                        NonReentrant::Callback { key }
+                    } else {
+                        NonReentrant::Guard { key, then }
+                    };
+                    visitor.visit_non_reentrant_attr(attr, non_reentrant)?;
+                }
+                "result_serializer" => {
+                    let serializer =
+                        match SerializerAttr::from_args(args.borsh, args.json, args.with.clone()) {
+                            Some(serializer) => serializer,
+                            None => {
+                                return Err(Error::new(
+                                    attr.span(),
+                                    "Only one of `borsh`, `json` or `with` can be specified.",
+                                ))
+                            }
+                        };
                     visitor.visit_result_serializer_attr(attr, &serializer)?;
                 }
                 "handle_result" => {
@@ -144,6 +280,40 @@ impl AttrSigInfo {
                         visitor.visit_handle_result_attr(&handle_result);
                     }
                 }
+                "near" => {
+                    let name = match &args.view_callback_preview {
+                        Some(Override::Explicit(name)) => {
+                            Some(syn::parse_str::<Ident>(name).map_err(|_| {
+                                Error::new(attr.span(), "The preview name must be an identifier.")
+                            })?)
+                        }
+                        Some(Override::Inherit) => Some(format_ident!("{}_preview", ident)),
+                        None => None,
+                    };
+                    if name.is_none() && args.deny_unknown_arguments.is_none() && args.abi.is_none()
+                    {
+                        return Err(Error::new(
+                            attr.span(),
+                            "Only `#[near(view_callback_preview)]`, \
+                            `#[near(deny_unknown_arguments)]` and `#[near(abi(...))]` are \
+                            supported on methods.",
+                        ));
+                    }
+                    if let Some(abi) = &args.abi {
+                        abi_hints = AbiHints {
+                            min_deposit: abi.min_deposit.as_ref().map(parse_deposit).transpose()?,
+                            recommended_gas: abi
+                                .recommended_gas
+                                .as_ref()
+                                .map(parse_gas)
+                                .transpose()?,
+                        };
+                    }
+                    if let Some(name) = name {
+                        view_callback_preview = Some((name, attr.span()));
+                    }
+                    deny_unknown_arguments = args.deny_unknown_arguments.unwrap_or_default();
+                }
                 _ => {
                     non_bindgen_attrs.push((*attr).clone());
                 }
@@ -163,6 +333,49 @@ impl AttrSigInfo {
 
         let (method_kind, returns) = visitor.build()?;
 
+        if let Some(allow_fn) = &private_allow.allow_fn {
+            let receiver = match &method_kind {
+                MethodKind::Call(call_method) => &call_method.receiver,
+                MethodKind::View(view_method) => &view_method.receiver,
+                MethodKind::Init(_) => &None,
+            };
+            if receiver.is_none() {
+                return Err(Error::new(
+                    allow_fn.span(),
+                    "Methods with `#[private(allow_fn = \"...\")]` must take `self`, which is \
+                    passed to the allow function.",
+                ));
+            }
+        }
+
+        if let Some((_, span)) = &only {
+            if !matches!(&method_kind, MethodKind::Call(call_method) if call_method.receiver.is_some())
+            {
+                return Err(Error::new(
+                    *span,
+                    "Methods with `#[only(...)]` or `#[only_owner]` must be call methods taking \
+                    `&mut self`, which is used to check the predecessor.",
+                ));
+            }
+        }
+
+        if let MethodKind::Call(CallMethod { non_reentrant: Some(non_reentrant), .. }) =
+            &method_kind
+        {
+            check_non_reentrant(
+                non_reentrant,
+                &method_kind,
+                &private_allow,
+                &args,
+                &returns,
+                &ident,
+            )?;
+        }
+
+        if let Some((_, span)) = &view_callback_preview {
+            check_view_callback_preview(&method_kind, &args, *span)?;
+        }
+
         self_occurrences.extend(args.iter().flat_map(|arg| arg.self_occurrences.clone()));
 
         original_attrs.clone_from(&non_bindgen_attrs);
@@ -170,6 +383,7 @@ impl AttrSigInfo {
         if matches!(method_kind, MethodKind::Call(_) | MethodKind::View(_)) {
             report_spans(
                 &self_occurrences,
+                Diagnostic::SelfInNonInit,
                 "references to `Self` in non-init methods are forbidden since `near-sdk` 5.0",
             )?;
         }
@@ -182,19 +396,62 @@ impl AttrSigInfo {
             returns,
             input_serializer: SerializerType::JSON,
             original_sig: original_sig.clone(),
+            view_callback_preview: view_callback_preview.map(|(name, _)| name),
+            deny_unknown_arguments,
+            private_allow,
+            deposit_bounds,
+            only: only.map(|(callers, _)| callers),
+            abi_hints,
         };
-
-        let input_serializer =
-            if result.input_args().all(|arg: &ArgInfo| arg.serializer_ty == SerializerType::JSON) {
-                SerializerType::JSON
-            } else if result.input_args().all(|arg| arg.serializer_ty == SerializerType::Borsh) {
-                SerializerType::Borsh
-            } else {
+        if result.abi_hints.min_deposit.is_some() && !result.is_payable() {
+            return Err(Error::new(
+                result.ident.span(),
+                "`min_deposit` in `#[near(abi(...))]` requires the method to be #[payable].",
+            ));
+        }
+        let mut deposit_args =
+            result.args.iter().filter(|arg| matches!(arg.bindgen_ty, BindgenArgType::Deposit));
+        if let Some(arg) = deposit_args.next() {
+            if !result.is_payable() {
                 return Err(Error::new(
-                    Span::call_site(),
-                    "Input arguments should be all of the same serialization type.",
+                    arg.original.span(),
+                    "Only #[payable] methods can take a #[deposit] argument.",
                 ));
-            };
+            }
+            if let Some(arg) = deposit_args.next() {
+                return Err(Error::new(
+                    arg.original.span(),
+                    "A method can only have one #[deposit] argument.",
+                ));
+            }
+        }
+
+        let input_serializer = result
+            .input_args()
+            .next()
+            .map_or(SerializerType::JSON, |arg: &ArgInfo| arg.serializer_ty.clone());
+        if result.input_args().any(|arg| arg.serializer_ty != input_serializer) {
+            return Err(Error::new(
+                Span::call_site(),
+                "Input arguments should be all of the same serialization type.",
+            ));
+        }
+        if matches!(&result.method_kind, MethodKind::Init(init) if init.exports_schema)
+            && input_serializer != SerializerType::JSON
+        {
+            return Err(Error::new(
+                result.ident.span(),
+                "#[init(schema)] is only supported for init methods with JSON arguments.",
+            ));
+        }
+        if matches!(&result.method_kind, MethodKind::Init(init) if init.on_deploy)
+            && result.input_args().next().is_some()
+        {
+            return Err(Error::new(
+                result.ident.span(),
+                "#[init(on_deploy)] is only supported for init methods without arguments.",
+            ));
+        }
         result.input_serializer = input_serializer;
         Ok(result)
     }
@@ -205,14 +462,145 @@ impl AttrSigInfo {
     }
 }
 
+/// Parses the name of an argument or a method given as a string literal.
+fn parse_method_name(lit: &syn::LitStr) -> syn::Result<Ident> {
+    lit.parse().map_err(|_| Error::new(lit.span(), "Expected an identifier."))
+}
+
+/// Checks that the `key` of a `#[non_reentrant]` method is one of its arguments, that a guarded
+/// method returning a promise names the callback clearing its guard, and that only the contract
+/// can call the callback.
+fn check_non_reentrant(
+    non_reentrant: &NonReentrant,
+    method_kind: &MethodKind,
+    private_allow: &PrivateAllow,
+    args: &[ArgInfo],
+    returns: &Returns,
+    ident: &Ident,
+) -> syn::Result<()> {
+    let (key, then) = match non_reentrant {
+        NonReentrant::Guard { key, then } => (key.as_ref(), then.as_ref()),
+        NonReentrant::Callback { key } => (Some(key), None),
+    };
+    if let NonReentrant::Callback { .. } = non_reentrant {
+        let is_private =
+            matches!(method_kind, MethodKind::Call(call_method) if call_method.is_private);
+        if !is_private || !private_allow.accounts.is_empty() || private_allow.allow_fn.is_some() {
+            return Err(Diagnostic::NonReentrant.error(
+                ident.span(),
+                "A `#[non_reentrant(callback)]` method clears the guard of the key it is called \
+                with, so it must be `#[private]`, without `allow` or `allow_fn`, for other \
+                accounts not to clear the guard while the promise is in flight.",
+            ));
+        }
+    }
+    if let Some(key) = key {
+        if !args.iter().any(|arg| arg.ident == *key) {
+            return Err(Diagnostic::NonReentrant
+                .error(key.span(), format!("The method has no argument `{}`.", key)));
+        }
+    }
+    let returned = match &returns.kind {
+        ReturnKind::Default => None,
+        ReturnKind::General(ty) => Some(ty),
+        ReturnKind::HandlesResult(ty) => Some(utils::extract_ok_type(ty).unwrap_or(ty)),
+    };
+    let returns_promise = returned.is_some_and(|ty| {
+        utils::type_is_named(ty, "Promise") || utils::type_is_named(ty, "PromiseOrValue")
+    });
+    if matches!(non_reentrant, NonReentrant::Guard { .. }) && returns_promise && then.is_none() {
+        return Err(Diagnostic::NonReentrant.error(
+            ident.span(),
+            "A `#[non_reentrant]` method returning a promise keeps its guard set until the \
+            promise calls back, so it must name its `#[non_reentrant(callback)]` callback: \
+            `#[non_reentrant(then = \"callback\")]`.",
+        ));
+    }
+    Ok(())
+}
+
+/// Parses a deposit bound of `#[payable(...)]`: an amount such as `"0.1 NEAR"` or a number of
+/// yoctoNEAR, or else a string with a `NearToken` expression.
+fn parse_deposit_bound(lit: &syn::Lit) -> syn::Result<syn::Expr> {
+    match parse_deposit(lit) {
+        Ok(amount) => {
+            let amount = proc_macro2::Literal::u128_unsuffixed(amount);
+            Ok(syn::parse_quote! { ::near_sdk::NearToken::from_yoctonear(#amount) })
+        }
+        Err(err) => match lit {
+            syn::Lit::Str(s) => s.parse().map_err(|_| err),
+            _ => Err(err),
+        },
+    }
+}
+
+/// Whether `account_id` is a valid NEAR account ID, so that an account listed in
+/// `#[private(allow = [...])]` with a typo is reported at compile time.
+fn is_valid_account_id(account_id: &str) -> bool {
+    (2..=64).contains(&account_id.len())
+        && account_id.split(['.', '-', '_']).all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        })
+}
+
+/// Checks that the preview generated by `#[near(view_callback_preview)]` can run the same code as
+/// the callback: the method cannot modify the state, and the callback results it takes have to be
+/// passed as regular arguments of the preview.
+fn check_view_callback_preview(
+    method_kind: &MethodKind,
+    args: &[ArgInfo],
+    span: Span,
+) -> syn::Result<()> {
+    if !matches!(method_kind, MethodKind::View(_)) {
+        return Err(Error::new(
+            span,
+            "Methods with #[near(view_callback_preview)] must take `&self` or no `self`, \
+            so that the callback and its preview cannot modify the state. Apply the state \
+            changes in a separate method.",
+        ));
+    }
+    let mut has_callback_arg = false;
+    for arg in args {
+        match arg.bindgen_ty {
+            BindgenArgType::Regular => {}
+            BindgenArgType::CallbackArg => has_callback_arg = true,
+            BindgenArgType::CallbackResultArg
+            | BindgenArgType::CallbackArgVec
+            | BindgenArgType::CallbackJoinArg
+            | BindgenArgType::Deposit => {
+                return Err(Error::new(
+                    arg.original.span(),
+                    "Methods with #[near(view_callback_preview)] only support \
+                    #[callback_unwrap] callback arguments.",
+                ));
+            }
+        }
+    }
+    if !has_callback_arg {
+        return Err(Error::new(
+            span,
+            "Methods with #[near(view_callback_preview)] must take at least one \
+            #[callback_unwrap] argument.",
+        ));
+    }
+    if args.iter().any(|arg| arg.serializer_ty != args[0].serializer_ty) {
+        return Err(Error::new(
+            span,
+            "Arguments of methods with #[near(view_callback_preview)] should be all of the same \
+            serialization type, including the callback arguments.",
+        ));
+    }
+    Ok(())
+}
+
 // Generate errors for a given collection of spans. Returns `Ok` if no spans are provided.
-fn report_spans(spans: &[Span], msg: &str) -> Result<(), syn::Error> {
+fn report_spans(spans: &[Span], diagnostic: Diagnostic, msg: &str) -> Result<(), syn::Error> {
     if spans.is_empty() {
         Ok(())
     } else {
         let combined_errors = spans
             .iter()
-            .map(|span| syn::Error::new(*span, msg))
+            .map(|span| diagnostic.error(*span, msg))
             .reduce(|mut acc, e| {
                 acc.combine(e);
                 acc
@@ -0,0 +1,171 @@
+use syn::parse::{Parse, ParseStream};
+use syn::{Attribute, Error, Ident, Lit, Token};
+
+/// Deposit and static gas of the calls of an `_Ext` method which are used unless set with
+/// `with_attached_deposit` or `with_static_gas`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExtCallDefaults {
+    /// Attached deposit, in yoctoNEAR.
+    pub deposit: Option<u128>,
+    /// Static gas, in gas units.
+    pub static_gas: Option<u64>,
+}
+
+impl ExtCallDefaults {
+    /// Returns the defaults of `self`, falling back to the ones of `other` for those not set.
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            deposit: self.deposit.or(other.deposit),
+            static_gas: self.static_gas.or(other.static_gas),
+        }
+    }
+
+    /// Removes the `#[gas(...)]` and `#[deposit(...)]` attributes of a trait method from `attrs`
+    /// and returns the defaults they set.
+    pub fn take_from_attrs(attrs: &mut Vec<Attribute>) -> syn::Result<Self> {
+        let mut defaults = Self::default();
+        let mut errors = vec![];
+        attrs.retain(|attr| {
+            let parsed = if attr.path().is_ident("gas") {
+                attr.parse_args::<Lit>()
+                    .and_then(|lit| parse_gas(&lit))
+                    .map(|gas| defaults.static_gas = Some(gas))
+            } else if attr.path().is_ident("deposit") {
+                attr.parse_args::<Lit>()
+                    .and_then(|lit| parse_deposit(&lit))
+                    .map(|deposit| defaults.deposit = Some(deposit))
+            } else {
+                return true;
+            };
+            if let Err(e) = parsed {
+                errors.push(e);
+            }
+            false
+        });
+        match errors.into_iter().reduce(|mut l, r| (l.combine(r), l).1) {
+            Some(combined_errors) => Err(combined_errors),
+            None => Ok(defaults),
+        }
+    }
+}
+
+/// Arguments of `#[ext_contract]`: the name of the generated module, followed by the defaults of
+/// the calls of all the methods, e.g. `#[ext_contract(ext_bob, gas = "10 Tgas", deposit = 0)]`.
+#[derive(Default)]
+pub struct ExtContractAttr {
+    pub mod_name: Option<Ident>,
+    pub defaults: ExtCallDefaults,
+}
+
+impl Parse for ExtContractAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attr = Self::default();
+        if input.peek(Ident) && !input.peek2(Token![=]) {
+            attr.mod_name = Some(input.parse()?);
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        while !input.is_empty() {
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value: Lit = input.parse()?;
+            match name.to_string().as_str() {
+                "gas" => attr.defaults.static_gas = Some(parse_gas(&value)?),
+                "deposit" => attr.defaults.deposit = Some(parse_deposit(&value)?),
+                _ => return Err(Error::new(name.span(), "Expected `gas` or `deposit`.")),
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(attr)
+    }
+}
+
+/// Parses an amount of gas, either a number of gas units or a string such as `"10 Tgas"`.
+pub(crate) fn parse_gas(lit: &Lit) -> syn::Result<u64> {
+    let amount = parse_amount(lit, &[("gas", 0), ("Ggas", 9), ("Tgas", 12), ("Pgas", 15)])?;
+    u64::try_from(amount).map_err(|_| Error::new(lit.span(), "Gas amount is too large."))
+}
+
+/// Parses a deposit, either a number of yoctoNEAR or a string such as `"0.1 NEAR"`.
+pub(crate) fn parse_deposit(lit: &Lit) -> syn::Result<u128> {
+    parse_amount(lit, &[("yoctoNEAR", 0), ("yocto", 0), ("milliNEAR", 21), ("NEAR", 24)])
+}
+
+/// Parses an integer literal, or a string literal with a decimal number followed by one of
+/// `units`, given with the power of ten of the smallest unit it stands for.
+fn parse_amount(lit: &Lit, units: &[(&str, u32)]) -> syn::Result<u128> {
+    let value = match lit {
+        Lit::Int(int) => return int.base10_parse(),
+        Lit::Str(s) => s.value(),
+        _ => return Err(Error::new(lit.span(), "Expected an integer or a string.")),
+    };
+    let expected = || {
+        let units: Vec<_> = units.iter().map(|(unit, _)| *unit).collect();
+        Error::new(
+            lit.span(),
+            format!(
+                "Expected an amount in {}, e.g. \"1 {}\".",
+                units.join(", "),
+                units[units.len() - 1]
+            ),
+        )
+    };
+    let (number, unit) = value.trim().split_once(' ').ok_or_else(expected)?;
+    let exponent = units
+        .iter()
+        .find(|(name, _)| *name == unit.trim())
+        .map(|(_, exponent)| *exponent)
+        .ok_or_else(expected)?;
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let digits = u32::try_from(fraction.len()).map_err(|_| expected())?;
+    if digits > exponent
+        || integer.is_empty()
+        || !(integer.chars().chain(fraction.chars())).all(|c| c.is_ascii_digit())
+    {
+        return Err(expected());
+    }
+    format!("{integer}{fraction}")
+        .parse::<u128>()
+        .ok()
+        .and_then(|amount| amount.checked_mul(10u128.checked_pow(exponent - digits)?))
+        .ok_or_else(|| Error::new(lit.span(), "Amount is too large."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn parse_amounts() {
+        assert_eq!(parse_gas(&parse_quote!("10 Tgas")).unwrap(), 10_000_000_000_000);
+        assert_eq!(parse_gas(&parse_quote!("2.5 Tgas")).unwrap(), 2_500_000_000_000);
+        assert_eq!(parse_gas(&parse_quote!(1_000)).unwrap(), 1_000);
+        assert_eq!(parse_deposit(&parse_quote!("0.1 NEAR")).unwrap(), 10u128.pow(23));
+        assert_eq!(parse_deposit(&parse_quote!(0)).unwrap(), 0);
+        assert_eq!(parse_deposit(&parse_quote!("1 yocto")).unwrap(), 1);
+        assert!(parse_gas(&parse_quote!("10 TGas")).is_err());
+        assert!(parse_gas(&parse_quote!("0.5 gas")).is_err());
+        assert!(parse_gas(&parse_quote!("20000000 Tgas")).is_err());
+        assert!(parse_deposit(&parse_quote!("1NEAR")).is_err());
+    }
+
+    #[test]
+    fn parse_ext_contract_attr() {
+        let attr: ExtContractAttr = syn::parse_quote!(ext_bob, gas = "10 Tgas", deposit = 0);
+        assert_eq!(attr.mod_name.unwrap().to_string(), "ext_bob");
+        assert_eq!(
+            attr.defaults,
+            ExtCallDefaults { deposit: Some(0), static_gas: Some(10_000_000_000_000) }
+        );
+
+        let attr: ExtContractAttr = syn::parse_quote!(gas = "5 Tgas");
+        assert!(attr.mod_name.is_none());
+        assert_eq!(attr.defaults.static_gas, Some(5_000_000_000_000));
+
+        assert!(syn::parse_str::<ExtContractAttr>("ext_bob, weight = 1").is_err());
+    }
+}
@@ -1,7 +1,11 @@
-use crate::core_impl::info_extractor::AttrSigInfo;
-use crate::core_impl::utils;
-use quote::ToTokens;
-use syn::{ImplItemFn as ImplItemMethod, Path, Type, Visibility};
+use crate::core_impl::info_extractor::{AttrSigInfo, BindgenArgType, MethodKind, SerializerType};
+use crate::core_impl::{utils, Diagnostic};
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{parse_quote, Ident, ImplItemFn as ImplItemMethod, Path, Type, Visibility};
+
+/// Functions of `env` which write to the storage, and cannot be called by view methods.
+const STORAGE_WRITES: &[&str] = &["storage_write", "storage_remove", "state_write"];
 
 /// Information extracted from `ImplItemMethod`.
 pub struct ImplItemMethodInfo {
@@ -11,6 +15,9 @@ pub struct ImplItemMethodInfo {
     pub struct_type: Type,
     /// The trait that this method is implemented for.
     pub impl_trait: Option<Path>,
+    /// The method invoked by this view, if it is a preview generated by
+    /// `#[near(view_callback_preview)]`.
+    pub preview_of: Option<Ident>,
 }
 
 impl ImplItemMethodInfo {
@@ -25,11 +32,112 @@ impl ImplItemMethodInfo {
         if impl_trait.is_some() || matches!(original.vis, Visibility::Public(_)) {
             let source_type = &struct_type.to_token_stream();
             let attr_signature_info = AttrSigInfo::new(attrs, sig, source_type)?;
-            Ok(Some(Self { attr_signature_info, struct_type, impl_trait }))
+            if matches!(attr_signature_info.method_kind, MethodKind::View(_)) {
+                guard_view_method(original)?;
+            }
+            if attr_signature_info.deposit_bounds.refund_excess {
+                check_refund_excess_method(original)?;
+            }
+            Ok(Some(Self { attr_signature_info, struct_type, impl_trait, preview_of: None }))
         } else {
             Ok(None)
         }
     }
+
+    /// Returns the view generated for a method with `#[near(view_callback_preview)]`.
+    ///
+    /// The preview invokes the same method, but reads the callback arguments from its input
+    /// instead of the promise results, so that it can be called with the value the callback
+    /// would receive.
+    pub fn view_callback_preview(&self) -> Option<Self> {
+        let preview_ident = self.attr_signature_info.view_callback_preview.clone()?;
+        let mut attr_signature_info = self.attr_signature_info.clone();
+        for arg in &mut attr_signature_info.args {
+            arg.bindgen_ty = BindgenArgType::Regular;
+        }
+        if let MethodKind::View(view_method) = &mut attr_signature_info.method_kind {
+            view_method.is_private = false;
+        }
+        attr_signature_info.input_serializer = attr_signature_info
+            .args
+            .first()
+            .map_or(SerializerType::JSON, |arg| arg.serializer_ty.clone());
+        attr_signature_info.ident = preview_ident;
+        attr_signature_info.view_callback_preview = None;
+
+        Some(Self {
+            attr_signature_info,
+            struct_type: self.struct_type.clone(),
+            impl_trait: self.impl_trait.clone(),
+            preview_of: Some(self.attr_signature_info.ident.clone()),
+        })
+    }
+}
+
+/// Rejects a view method calling one of the [`STORAGE_WRITES`] functions of `env`, and makes the
+/// method panic on writes to the storage in non-wasm builds, i.e. in unit tests, to catch the
+/// writes of the collections and helpers it calls.
+fn guard_view_method(method: &mut ImplItemMethod) -> syn::Result<()> {
+    if let Some(write) = find_env_call(method.block.to_token_stream(), STORAGE_WRITES) {
+        return Err(Diagnostic::StateWriteInView.error_spanned(
+            &write,
+            format!(
+                "View function can't write to the storage with `{}`, take `&mut self` to make it a call function.",
+                write.to_string().replace(' ', "")
+            ),
+        ));
+    }
+    let method_name = method.sig.ident.to_string();
+    method.block.stmts.insert(
+        0,
+        parse_quote! {
+            #[cfg(not(target_arch = "wasm32"))]
+            let __near_view_guard = ::near_sdk::__private::ViewGuard::enter(#method_name);
+        },
+    );
+    Ok(())
+}
+
+/// Rejects a method with `#[payable(refund_excess)]` reading the attached deposit, which includes
+/// the excess refunded by the method, instead of taking the deposit it keeps as an argument.
+fn check_refund_excess_method(method: &ImplItemMethod) -> syn::Result<()> {
+    if let Some(read) = find_env_call(method.block.to_token_stream(), &["attached_deposit"]) {
+        return Err(Diagnostic::RefundedDepositRead.error_spanned(
+            &read,
+            "`env::attached_deposit` includes the deposit refunded by `refund_excess`, take the \
+            deposit kept by the method with a `#[deposit]` argument instead, e.g. \
+            `#[deposit] deposit: NearToken`.",
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the first path `env::name` in `tokens` of one of the functions `names`.
+fn find_env_call(tokens: TokenStream2, names: &[&str]) -> Option<TokenStream2> {
+    let trees: Vec<TokenTree> = tokens.into_iter().collect();
+    for (index, tree) in trees.iter().enumerate() {
+        match tree {
+            TokenTree::Group(group) => {
+                if let Some(call) = find_env_call(group.stream(), names) {
+                    return Some(call);
+                }
+            }
+            TokenTree::Ident(env) if env == "env" => {
+                if let [TokenTree::Punct(first), TokenTree::Punct(second), TokenTree::Ident(name), ..] =
+                    &trees[index + 1..]
+                {
+                    if first.as_char() == ':'
+                        && second.as_char() == ':'
+                        && names.iter().any(|call| name == call)
+                    {
+                        return Some(quote! { #env #first #second #name });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    None
 }
 
 // Rustfmt removes comas.
@@ -47,7 +155,7 @@ mod tests {
             pub fn method(k: &mut u64) { }
         };
         let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
-        let expected = "Init function must return the contract state.";
+        let expected = "[near-sdk E0009] Init function must return the contract state.";
         assert_eq!(expected, actual.to_string());
     }
 
@@ -73,7 +181,7 @@ mod tests {
             pub fn method(&self) -> &'static str { }
         };
         let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
-        let expected = "Function marked with #[handle_result] should return Result<T, E> (where E implements FunctionError). If you're trying to use a type alias for `Result`, try `#[handle_result(aliased)]`.";
+        let expected = "[near-sdk E0010] Function marked with #[handle_result] should return Result<T, E> (where E implements FunctionError). If you're trying to use a type alias for `Result`, try `#[handle_result(aliased)]`.";
         assert_eq!(expected, actual.to_string());
     }
 
@@ -84,7 +192,7 @@ mod tests {
             pub fn method(&self) -> Result<u64, &'static str> { }
         };
         let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
-        let expected = "Serializing Result<T, E> has been deprecated. Consider marking your method with #[handle_result] if the second generic represents a panicable error or replacing Result with another two type sum enum otherwise. If you really want to keep the legacy behavior, mark the method with #[handle_result] and make it return Result<Result<T, E>, near_sdk::Abort>.";
+        let expected = "[near-sdk E0011] Serializing Result<T, E> has been deprecated. Consider marking your method with #[handle_result] if the second generic represents a panicable error or replacing Result with another two type sum enum otherwise. If you really want to keep the legacy behavior, mark the method with #[handle_result] and make it return Result<Result<T, E>, near_sdk::Abort>.";
         assert_eq!(expected, actual.to_string());
     }
 
@@ -96,7 +204,7 @@ mod tests {
             pub fn new() -> Result<Self, &'static str> { }
         };
         let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
-        let expected = "Serializing Result<T, E> has been deprecated. Consider marking your method with #[handle_result] if the second generic represents a panicable error or replacing Result with another two type sum enum otherwise. If you really want to keep the legacy behavior, mark the method with #[handle_result] and make it return Result<Result<T, E>, near_sdk::Abort>.";
+        let expected = "[near-sdk E0011] Serializing Result<T, E> has been deprecated. Consider marking your method with #[handle_result] if the second generic represents a panicable error or replacing Result with another two type sum enum otherwise. If you really want to keep the legacy behavior, mark the method with #[handle_result] and make it return Result<Result<T, E>, near_sdk::Abort>.";
         assert_eq!(expected, actual.to_string());
     }
 
@@ -109,7 +217,183 @@ mod tests {
             pub fn method(self) -> Self { }
         };
         let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
-        let expected = "View function can't be payable.";
+        let expected = "[near-sdk E0005] View function can't be payable.";
         assert_eq!(expected.to_string(), actual.to_string());
     }
+
+    #[test]
+    fn view_callback_preview() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[private]
+            #[near(view_callback_preview)]
+            pub fn on_price(&self, #[callback_unwrap] price: u64, amount: u64) -> u64 { }
+        };
+        let method = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        assert!(method.attr_signature_info.is_private());
+        assert!(method.attr_signature_info.non_bindgen_attrs.is_empty());
+
+        let preview = method.view_callback_preview().unwrap();
+        assert_eq!(preview.attr_signature_info.ident, "on_price_preview");
+        assert_eq!(preview.preview_of.as_ref().unwrap(), "on_price");
+        assert!(!preview.attr_signature_info.is_private());
+        assert_eq!(preview.attr_signature_info.input_args().count(), 2);
+        assert!(preview.view_callback_preview().is_none());
+
+        let wrapper = preview.method_wrapper().to_string();
+        assert!(wrapper.contains("pub extern \"C\" fn on_price_preview"));
+        assert!(wrapper.contains("Hello :: on_price"));
+        assert!(!wrapper.contains("promise_result"));
+    }
+
+    #[test]
+    fn view_callback_preview_explicit_name() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[near(view_callback_preview = "quote")]
+            pub fn on_price(#[callback_unwrap] price: u64) -> u64 { }
+        };
+        let method = ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        assert_eq!(method.view_callback_preview().unwrap().attr_signature_info.ident, "quote");
+    }
+
+    #[test]
+    fn view_callback_preview_mut_self_fails() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[near(view_callback_preview)]
+            pub fn on_price(&mut self, #[callback_unwrap] price: u64) -> u64 { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        let expected = "Methods with #[near(view_callback_preview)] must take `&self` or no `self`, so that the callback and its preview cannot modify the state. Apply the state changes in a separate method.";
+        assert_eq!(expected, actual.to_string());
+    }
+
+    #[test]
+    fn view_callback_preview_without_callback_fails() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[near(view_callback_preview)]
+            pub fn on_price(&self, price: u64) -> u64 { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        let expected = "Methods with #[near(view_callback_preview)] must take at least one #[callback_unwrap] argument.";
+        assert_eq!(expected, actual.to_string());
+    }
+
+    #[test]
+    fn storage_write_in_view_fails() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            pub fn method(&self) -> u64 {
+                if self.count > 0 {
+                    near_sdk::env::storage_write(b"count", &[1]);
+                }
+                self.count
+            }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        let expected = "[near-sdk E0019] View function can't write to the storage with `env::storage_write`, take `&mut self` to make it a call function.";
+        assert_eq!(expected, actual.to_string());
+    }
+
+    #[test]
+    fn attached_deposit_with_refund_excess_fails() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[payable(min = "1 NEAR", refund_excess)]
+            pub fn deposit(&mut self) {
+                self.total = self.total.saturating_add(env::attached_deposit());
+            }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        let expected = "[near-sdk E0020] `env::attached_deposit` includes the deposit refunded by `refund_excess`, take the deposit kept by the method with a `#[deposit]` argument instead, e.g. `#[deposit] deposit: NearToken`.";
+        assert_eq!(expected, actual.to_string());
+    }
+
+    #[test]
+    fn view_guard() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            pub fn method(&self) -> u64 { self.count }
+        };
+        ImplItemMethodInfo::new(&mut method, None, impl_type.clone()).unwrap().unwrap();
+        let expected: ImplItemMethod = parse_quote! {
+            pub fn method(&self) -> u64 {
+                #[cfg(not(target_arch = "wasm32"))]
+                let __near_view_guard = ::near_sdk::__private::ViewGuard::enter("method");
+                self.count
+            }
+        };
+        assert_eq!(expected, method);
+
+        let mut method: ImplItemMethod = parse_quote! {
+            pub fn method(&mut self) { env::storage_write(b"count", &[1]); }
+        };
+        let expected = method.clone();
+        ImplItemMethodInfo::new(&mut method, None, impl_type).unwrap().unwrap();
+        assert_eq!(expected, method);
+    }
+
+    #[test]
+    fn non_reentrant_view_fails() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[non_reentrant]
+            pub fn method(&self) { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        let expected = "[near-sdk E0017] View function can't be non-reentrant, only call methods can be guarded.";
+        assert_eq!(expected, actual.to_string());
+    }
+
+    #[test]
+    fn non_reentrant_promise_without_callback_fails() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[non_reentrant]
+            pub fn withdraw(&mut self, amount: U128) -> Promise { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        let expected = "[near-sdk E0017] A `#[non_reentrant]` method returning a promise keeps its guard set until the promise calls back, so it must name its `#[non_reentrant(callback)]` callback: `#[non_reentrant(then = \"callback\")]`.";
+        assert_eq!(expected, actual.to_string());
+    }
+
+    #[test]
+    fn non_reentrant_callback_without_key_fails() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[private]
+            #[non_reentrant(callback)]
+            pub fn on_withdraw(&mut self, account_id: AccountId) { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        assert!(actual.to_string().starts_with(
+            "[near-sdk E0017] A `#[non_reentrant(callback)]` method must name the argument"
+        ));
+    }
+
+    #[test]
+    fn non_reentrant_callback_not_private_fails() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[non_reentrant(callback, key = "account_id")]
+            pub fn on_withdraw(&mut self, account_id: AccountId) { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        let expected = "[near-sdk E0017] A `#[non_reentrant(callback)]` method clears the guard of the key it is called with, so it must be `#[private]`, without `allow` or `allow_fn`, for other accounts not to clear the guard while the promise is in flight.";
+        assert_eq!(expected, actual.to_string());
+    }
+
+    #[test]
+    fn non_reentrant_unknown_key_fails() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[non_reentrant(key = "account")]
+            pub fn deposit(&mut self, account_id: AccountId) { }
+        };
+        let actual = ImplItemMethodInfo::new(&mut method, None, impl_type).map(|_| ()).unwrap_err();
+        let expected = "[near-sdk E0017] The method has no argument `account`.";
+        assert_eq!(expected, actual.to_string());
+    }
 }
@@ -1,3 +1,5 @@
 pub struct InitAttr {
     pub ignore_state: bool,
+    pub schema: bool,
+    pub on_deploy: bool,
 }
@@ -1,6 +1,10 @@
+use crate::core_impl::Diagnostic;
 use crate::ImplItemMethodInfo;
+use proc_macro2::{Group, Ident, TokenStream as TokenStream2, TokenTree};
+use quote::ToTokens;
+use std::collections::HashSet;
 use syn::spanned::Spanned;
-use syn::{Error, ImplItem, ItemImpl, Type};
+use syn::{GenericArgument, GenericParam, Generics, ImplItem, ItemImpl, PathArguments, Type};
 
 /// Information extracted from `impl` section.
 pub struct ItemImplInfo {
@@ -13,20 +17,75 @@ pub struct ItemImplInfo {
 impl ItemImplInfo {
     pub fn new(original: &mut ItemImpl) -> syn::Result<Self> {
         if !original.generics.params.is_empty() {
-            return Err(Error::new(
+            return Err(Diagnostic::GenericImpl.error(
                 original.generics.params.span(),
-                "Impl type parameters are not supported for smart contracts.",
+                "Impl type parameters are not supported for smart contracts without \
+                `#[near(instantiate = \"...\")]`.",
             ));
         }
+        Self::extract(original)
+    }
+
+    /// Extracts the information of a generic impl block for its instantiation `instance`, e.g.
+    /// `Incrementer<u32>` for `impl<T> Incrementer<T>`, declared with
+    /// `#[near(instantiate = "...")]`.
+    ///
+    /// The methods of `original` are processed as in other impl blocks, but the exported functions
+    /// and the ABI are generated from a copy of the impl block whose generic parameters are
+    /// replaced by the arguments of `instance`.
+    pub fn instantiate(original: &mut ItemImpl, instance: &Type) -> syn::Result<Self> {
+        if original.generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                instance,
+                "`instantiate` is only supported on impl blocks with type parameters.",
+            ));
+        }
+        let substitutions = generic_substitutions(original, instance)?;
+        let mut instance_impl = original.clone();
+        instance_impl.generics = Generics::default();
+        *instance_impl.self_ty = turbofish(instance.clone());
+        if let Some((_, path, _)) = &mut instance_impl.trait_ {
+            *path = syn::parse2(substitute_generics(path.to_token_stream(), &substitutions))?;
+        }
+        for item in &mut instance_impl.items {
+            *item = syn::parse2(substitute_generics(item.to_token_stream(), &substitutions))?;
+        }
+
+        Self::extract(original)?;
+        Self::extract(&mut instance_impl)
+    }
+
+    fn extract(original: &mut ItemImpl) -> syn::Result<Self> {
         let ty = (*original.self_ty.as_ref()).clone();
         let trait_ = original.trait_.as_ref().map(|(_not, path, _for)| path);
 
         let mut methods = vec![];
         let mut errors = vec![];
+        let mut names = HashSet::new();
         for subitem in &mut original.items {
             if let ImplItem::Fn(m) = subitem {
+                // Reported before generating the exported functions, whose duplicate symbols
+                // would otherwise fail with confusing errors. Methods with different `#[cfg]`
+                // attributes may be mutually exclusive.
+                let cfg_attrs: Vec<_> = m
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("cfg"))
+                    .map(|attr| attr.to_token_stream().to_string())
+                    .collect();
+                if !names.insert((m.sig.ident.to_string(), cfg_attrs)) {
+                    errors.push(Diagnostic::DuplicateMethod.error_spanned(
+                        &m.sig.ident,
+                        format!("Duplicate contract method `{}`.", m.sig.ident),
+                    ));
+                    continue;
+                }
                 match ImplItemMethodInfo::new(m, trait_.cloned(), ty.clone()) {
-                    Ok(Some(method_info)) => methods.push(method_info),
+                    Ok(Some(method_info)) => {
+                        let preview = method_info.view_callback_preview();
+                        methods.push(method_info);
+                        methods.extend(preview);
+                    }
                     Ok(None) => {} // do nothing
                     Err(e) => errors.push(e),
                 }
@@ -45,3 +104,150 @@ impl ItemImplInfo {
         Ok(Self { ty, methods })
     }
 }
+
+/// Replacement of a generic parameter of an instantiated impl block.
+struct Substitution {
+    /// The name of the parameter, without the `'` of lifetimes.
+    param: Ident,
+    is_lifetime: bool,
+    /// The generic argument replacing the parameter, also without the `'` of lifetimes.
+    value: TokenStream2,
+}
+
+/// Matches the generic parameters of the impl block `original` with the generic arguments of its
+/// type in `instance`. Lifetimes which aren't arguments of the type are replaced by `'static`.
+fn generic_substitutions(original: &ItemImpl, instance: &Type) -> syn::Result<Vec<Substitution>> {
+    let mismatch = || {
+        syn::Error::new_spanned(
+            instance,
+            "`instantiate` must be the type of the impl block with concrete generic arguments.",
+        )
+    };
+    let segments = match (original.self_ty.as_ref(), instance) {
+        (Type::Path(generic), Type::Path(instance))
+            if generic.qself.is_none() && instance.qself.is_none() =>
+        {
+            (generic.path.segments.last().unwrap(), instance.path.segments.last().unwrap())
+        }
+        _ => return Err(mismatch()),
+    };
+    let (generic_args, instance_args) = match segments {
+        (generic, instance) if generic.ident != instance.ident => return Err(mismatch()),
+        (
+            syn::PathSegment { arguments: PathArguments::AngleBracketed(generic), .. },
+            syn::PathSegment { arguments: PathArguments::AngleBracketed(instance), .. },
+        ) if generic.args.len() == instance.args.len() => (&generic.args, &instance.args),
+        _ => return Err(mismatch()),
+    };
+
+    let mut substitutions = vec![];
+    for (generic_arg, instance_arg) in generic_args.iter().zip(instance_args) {
+        let substitution = match (generic_arg, instance_arg) {
+            (GenericArgument::Lifetime(param), GenericArgument::Lifetime(value)) => Substitution {
+                param: param.ident.clone(),
+                is_lifetime: true,
+                value: value.ident.to_token_stream(),
+            },
+            (GenericArgument::Type(Type::Path(param)), value) if param.qself.is_none() => {
+                let param = match param.path.get_ident() {
+                    Some(param) => param,
+                    None => continue,
+                };
+                let value = match value {
+                    GenericArgument::Type(ty) => turbofish(ty.clone()).to_token_stream(),
+                    GenericArgument::Const(expr) => expr.to_token_stream(),
+                    _ => return Err(mismatch()),
+                };
+                Substitution { param: param.clone(), is_lifetime: false, value }
+            }
+            _ => continue,
+        };
+        substitutions.push(substitution);
+    }
+
+    for param in &original.generics.params {
+        let (ident, is_lifetime) = match param {
+            GenericParam::Lifetime(param) => (&param.lifetime.ident, true),
+            GenericParam::Type(param) => (&param.ident, false),
+            GenericParam::Const(param) => (&param.ident, false),
+        };
+        if substitutions.iter().any(|s| &s.param == ident && s.is_lifetime == is_lifetime) {
+            continue;
+        }
+        if !is_lifetime {
+            return Err(syn::Error::new_spanned(
+                ident,
+                format!(
+                    "`{}` has to be a generic argument of the type of the impl block to be \
+                    instantiated.",
+                    ident
+                ),
+            ));
+        }
+        substitutions.push(Substitution {
+            param: ident.clone(),
+            is_lifetime,
+            value: quote::quote! { static },
+        });
+    }
+    Ok(substitutions)
+}
+
+/// Writes the generic arguments of the path of `ty` with `::`, e.g. `Incrementer::<u32>`, so
+/// that the type can also be used in expressions.
+fn turbofish(mut ty: Type) -> Type {
+    if let Type::Path(path) = &mut ty {
+        for segment in &mut path.path.segments {
+            if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                args.colon2_token.get_or_insert_with(Default::default);
+            }
+        }
+    }
+    ty
+}
+
+/// Replaces the generic parameters in `tokens`, except the identifiers following `.` or `::`,
+/// which are fields, methods or items of other paths.
+fn substitute_generics(tokens: TokenStream2, substitutions: &[Substitution]) -> TokenStream2 {
+    let mut result = TokenStream2::new();
+    let mut previous: [Option<TokenTree>; 2] = [None, None];
+    for tree in tokens {
+        let after_lifetime_quote = is_punct(&previous[1], '\'');
+        let after_path_or_field = is_punct(&previous[1], '.')
+            || (is_punct(&previous[1], ':') && is_punct(&previous[0], ':'));
+        let replaced = match &tree {
+            TokenTree::Ident(ident) if !after_path_or_field => substitutions
+                .iter()
+                .find(|s| &s.param == ident && s.is_lifetime == after_lifetime_quote)
+                .map(|s| {
+                    s.value
+                        .clone()
+                        .into_iter()
+                        .map(|mut t| {
+                            t.set_span(ident.span());
+                            t
+                        })
+                        .collect::<TokenStream2>()
+                }),
+            TokenTree::Group(group) => {
+                let mut replaced = Group::new(
+                    group.delimiter(),
+                    substitute_generics(group.stream(), substitutions),
+                );
+                replaced.set_span(group.span());
+                Some(TokenTree::Group(replaced).into())
+            }
+            _ => None,
+        };
+        match replaced {
+            Some(replaced) => result.extend(replaced),
+            None => result.extend([tree.clone()]),
+        }
+        previous = [previous[1].take(), Some(tree)];
+    }
+    result
+}
+
+fn is_punct(tree: &Option<TokenTree>, c: char) -> bool {
+    matches!(tree, Some(TokenTree::Punct(punct)) if punct.as_char() == c)
+}
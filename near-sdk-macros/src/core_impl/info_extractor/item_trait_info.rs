@@ -1,4 +1,4 @@
-use super::TraitItemMethodInfo;
+use super::{ExtCallDefaults, TraitItemMethodInfo};
 use inflector::Inflector;
 use quote::ToTokens;
 use syn::spanned::Spanned;
@@ -10,12 +10,18 @@ pub struct ItemTraitInfo {
     pub mod_name: Ident,
     /// Information extracted from the methods.
     pub methods: Vec<TraitItemMethodInfo>,
+    /// Deposit and gas of the calls of the methods without their own `#[deposit]` or `#[gas]`.
+    pub defaults: ExtCallDefaults,
     /// The original AST.
     pub original: ItemTrait,
 }
 
 impl ItemTraitInfo {
-    pub fn new(original: &mut ItemTrait, mod_name_override: Option<Ident>) -> syn::Result<Self> {
+    pub fn new(
+        original: &mut ItemTrait,
+        mod_name_override: Option<Ident>,
+        defaults: ExtCallDefaults,
+    ) -> syn::Result<Self> {
         let mod_name = mod_name_override.unwrap_or({
             let res = original.ident.to_string().to_snake_case();
             Ident::new(&res, original.span())
@@ -55,6 +61,6 @@ impl ItemTraitInfo {
             });
             return Err(combined_error.unwrap());
         }
-        Ok(Self { original: original.clone(), mod_name, methods })
+        Ok(Self { original: original.clone(), mod_name, methods, defaults })
     }
 }
@@ -1,10 +1,10 @@
-use syn::{Receiver, ReturnType, Type};
+use syn::{Expr, Ident, Receiver, ReturnType, Type};
 
 mod serializer_attr;
 pub use serializer_attr::SerializerAttr;
 
 mod arg_info;
-pub use arg_info::{ArgInfo, BindgenArgType};
+pub use arg_info::{ArgDefault, ArgInfo, BindgenArgType};
 
 mod handle_result_attr;
 pub use handle_result_attr::HandleResultAttr;
@@ -23,6 +23,10 @@ pub use item_trait_info::ItemTraitInfo;
 
 mod item_impl_info;
 
+mod ext_contract_attr;
+pub(crate) use ext_contract_attr::{parse_deposit, parse_gas};
+pub use ext_contract_attr::{ExtCallDefaults, ExtContractAttr};
+
 mod init_attr;
 pub use init_attr::InitAttr;
 
@@ -36,6 +40,8 @@ pub use item_impl_info::ItemImplInfo;
 pub enum SerializerType {
     JSON,
     Borsh,
+    /// Type implementing `near_sdk::ArgsSerializer`, named with `with = Type`.
+    Custom(syn::Path),
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -55,6 +61,19 @@ pub struct CallMethod {
     pub result_serializer: SerializerType,
     /// The receiver, like `mut self`, `self`, `&mut self`, `&self`, or `None`.
     pub receiver: Option<Receiver>,
+    /// Whether the method is guarded against reentrancy with `#[non_reentrant]`.
+    pub non_reentrant: Option<NonReentrant>,
+}
+
+/// Role of a method in the reentrancy guard of `#[non_reentrant]` methods.
+#[derive(Clone, PartialEq, Eq)]
+pub enum NonReentrant {
+    /// `#[non_reentrant]`: the method sets the guard of its caller, or of its argument `key`,
+    /// which stays set until the callback `then` of the promise the method returns, if any.
+    Guard { key: Option<Ident>, then: Option<Ident> },
+    /// `#[non_reentrant(callback)]`: the method is the callback of a guarded method and clears
+    /// the guard of its argument `key`.
+    Callback { key: Ident },
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -67,12 +86,67 @@ pub struct ViewMethod {
     pub receiver: Option<Receiver>,
 }
 
+/// Bounds of the attached deposit of a `#[payable(...)]` method, as `NearToken` expressions.
+#[derive(Clone, Default)]
+pub struct DepositBounds {
+    /// Minimum deposit, set with `min = "..."`.
+    pub min: Option<Expr>,
+    /// Maximum deposit, set with `max = "..."`.
+    pub max: Option<Expr>,
+    /// Required deposit, set with `exact = "..."`.
+    pub exact: Option<Expr>,
+    /// Whether the deposit above `exact`, or else `max` or `min`, is refunded to the predecessor
+    /// rather than rejected, with `refund_excess`.
+    pub refund_excess: bool,
+}
+
+impl DepositBounds {
+    pub fn is_empty(&self) -> bool {
+        self.min.is_none() && self.max.is_none() && self.exact.is_none()
+    }
+}
+
+/// Hints for the callers of a method in the ABI, set with `#[near(abi(...))]`.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct AbiHints {
+    /// Deposit to attach at least, in yoctoNEAR, set with `min_deposit = "..."`.
+    pub min_deposit: Option<u128>,
+    /// Gas to attach, set with `recommended_gas = "..."`.
+    pub recommended_gas: Option<u64>,
+}
+
+/// Callers other than the contract itself allowed to call a `#[private(...)]` method.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct PrivateAllow {
+    /// Account IDs listed with `allow = [...]`.
+    pub accounts: Vec<String>,
+    /// Method of the contract which returns whether it allows the predecessor, named with
+    /// `allow_fn = "name"`.
+    pub allow_fn: Option<Ident>,
+}
+
+/// Callers a method is restricted to with `#[only(...)]` or `#[only_owner]`, checked through the
+/// `HasRoles` implementation of the contract.
+#[derive(Clone, PartialEq, Eq)]
+pub enum OnlyCallers {
+    /// Accounts having the role, with `#[only(role = "...")]`.
+    Role(String),
+    /// The owner of the contract, with `#[only_owner]`.
+    Owner,
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct InitMethod {
     /// Whether method accepting $NEAR.
     pub is_payable: bool,
     /// Whether init method ignores state
     pub ignores_state: bool,
+    /// Whether the schema and a builder of the arguments of the init method are exported for
+    /// factory contracts, with `#[init(schema)]`.
+    pub exports_schema: bool,
+    /// Whether the contract is initialized with the init method by the first call to one of its
+    /// methods if its state is missing, with `#[init(on_deploy)]`.
+    pub on_deploy: bool,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -3,3 +3,24 @@ use super::SerializerType;
 pub struct SerializerAttr {
     pub serializer_type: SerializerType,
 }
+
+impl SerializerAttr {
+    /// Returns the serializer named by the `borsh`, `json` or `with = Type` arguments of a
+    /// `#[serializer(...)]` or `#[result_serializer(...)]` attribute, JSON by default, or `None`
+    /// if more than one of them is specified.
+    pub fn from_args(
+        borsh: Option<bool>,
+        json: Option<bool>,
+        with: Option<syn::Path>,
+    ) -> Option<Self> {
+        if [borsh.is_some(), json.is_some(), with.is_some()].iter().filter(|s| **s).count() > 1 {
+            return None;
+        }
+        let serializer_type = match with {
+            Some(path) => SerializerType::Custom(path),
+            None if borsh == Some(true) => SerializerType::Borsh,
+            None => SerializerType::JSON,
+        };
+        Some(Self { serializer_type })
+    }
+}
@@ -1,4 +1,4 @@
-use super::AttrSigInfo;
+use super::{AttrSigInfo, ExtCallDefaults};
 use crate::core_impl::utils;
 use proc_macro2::TokenStream as TokenStream2;
 use syn::spanned::Spanned;
@@ -8,6 +8,8 @@ use syn::{Error, LitStr, TraitItemFn};
 pub struct TraitItemMethodInfo {
     /// Attributes and signature information.
     pub attr_sig_info: AttrSigInfo,
+    /// Deposit and gas of the calls set with `#[deposit(...)]` and `#[gas(...)]`.
+    pub defaults: ExtCallDefaults,
     /// The original AST of the trait item method.
     #[allow(unused)]
     pub original: TraitItemFn,
@@ -30,11 +32,12 @@ impl TraitItemMethodInfo {
         let TraitItemFn { attrs, sig, .. } = original;
 
         utils::sig_is_supported(sig)?;
+        let defaults = ExtCallDefaults::take_from_attrs(attrs)?;
         let attr_sig_info = AttrSigInfo::new(attrs, sig, trait_name)?;
 
         let ident_byte_str =
             LitStr::new(&attr_sig_info.ident.to_string(), attr_sig_info.ident.span());
 
-        Ok(Self { attr_sig_info, original: original.clone(), ident_byte_str })
+        Ok(Self { attr_sig_info, defaults, original: original.clone(), ident_byte_str })
     }
 }
@@ -1,7 +1,9 @@
-use super::{HandleResultAttr, InitAttr, MethodKind, ReturnKind, SerializerAttr};
-use crate::core_impl::{utils, CallMethod, InitMethod, Returns, SerializerType, ViewMethod};
+use super::{HandleResultAttr, InitAttr, MethodKind, NonReentrant, ReturnKind, SerializerAttr};
+use crate::core_impl::{
+    utils, CallMethod, Diagnostic, InitMethod, Returns, SerializerType, ViewMethod,
+};
 use quote::ToTokens;
-use syn::{spanned::Spanned, Attribute, Error, FnArg, Receiver, ReturnType, Signature, Type};
+use syn::{spanned::Spanned, Attribute, FnArg, Receiver, ReturnType, Signature, Type};
 
 /// Traversal abstraction to walk a method declaration and build it's respective [MethodKind].
 pub struct Visitor {
@@ -14,14 +16,18 @@ struct ParsedData {
     handles_result: ResultHandling,
     is_payable: bool,
     is_private: bool,
+    non_reentrant: Option<NonReentrant>,
     ignores_state: bool,
+    exports_schema: bool,
+    on_deploy: bool,
     result_serializer: SerializerType,
     receiver: Option<Receiver>,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
 enum ResultHandling {
     // No result handling.
+    #[default]
     None,
     // Attempt to handle the `Result` without performing a heuristic type check.
     NoCheck,
@@ -29,19 +35,16 @@ enum ResultHandling {
     Check,
 }
 
-impl Default for ResultHandling {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 impl Default for ParsedData {
     fn default() -> Self {
         Self {
             handles_result: Default::default(),
             is_payable: Default::default(),
             is_private: Default::default(),
+            non_reentrant: Default::default(),
             ignores_state: Default::default(),
+            exports_schema: Default::default(),
+            on_deploy: Default::default(),
             result_serializer: SerializerType::JSON,
             receiver: Default::default(),
         }
@@ -77,12 +80,14 @@ impl Visitor {
         match self.kind {
             Init => {
                 self.parsed_data.ignores_state = init_attr.ignore_state;
+                self.parsed_data.exports_schema = init_attr.schema;
+                self.parsed_data.on_deploy = init_attr.on_deploy;
                 Ok(())
             }
             Call | View => {
                 let message =
                     format!("{} function can't be an init function at the same time.", self.kind);
-                Err(Error::new(attr.span(), message))
+                Err(Diagnostic::InitWithReceiver.error(attr.span(), message))
             }
         }
     }
@@ -97,7 +102,7 @@ impl Visitor {
             }
             View => {
                 let message = format!("{} function can't be payable.", self.kind);
-                Err(Error::new(attr.span(), message))
+                Err(Diagnostic::PayableView.error(attr.span(), message))
             }
         }
     }
@@ -107,6 +112,28 @@ impl Visitor {
         Ok(())
     }
 
+    pub fn visit_non_reentrant_attr(
+        &mut self,
+        attr: &Attribute,
+        non_reentrant: NonReentrant,
+    ) -> syn::Result<()> {
+        use VisitorKind::*;
+
+        match self.kind {
+            Call => {
+                self.parsed_data.non_reentrant = Some(non_reentrant);
+                Ok(())
+            }
+            View | Init => {
+                let message = format!(
+                    "{} function can't be non-reentrant, only call methods can be guarded.",
+                    self.kind
+                );
+                Err(Diagnostic::NonReentrant.error(attr.span(), message))
+            }
+        }
+    }
+
     pub fn visit_result_serializer_attr(
         &mut self,
         attr: &Attribute,
@@ -121,7 +148,7 @@ impl Visitor {
             }
             Init => {
                 let message = format!("{} function can't serialize return type.", self.kind);
-                Err(Error::new(attr.span(), message))
+                Err(Diagnostic::InitResultSerializer.error(attr.span(), message))
             }
         }
     }
@@ -141,7 +168,7 @@ impl Visitor {
             }
             Init => {
                 let message = format!("{} function can't have `self` parameter.", self.kind);
-                Err(Error::new(receiver.span(), message))
+                Err(Diagnostic::InitSelf.error(receiver.span(), message))
             }
         }
     }
@@ -158,7 +185,7 @@ impl Visitor {
                 }
                 Init => {
                     let message = format!("{} function must return the contract state.", self.kind);
-                    Err(Error::new(self.return_type.span(), message))
+                    Err(Diagnostic::InitReturn.error(self.return_type.span(), message))
                 }
             },
             ReturnType::Type(_, typ) => Ok(Returns {
@@ -178,14 +205,31 @@ impl Visitor {
         let Visitor { kind, parsed_data, .. } = self;
 
         let ParsedData {
-            is_payable, is_private, ignores_state, result_serializer, receiver, ..
+            is_payable,
+            is_private,
+            non_reentrant,
+            ignores_state,
+            exports_schema,
+            on_deploy,
+            result_serializer,
+            receiver,
+            ..
         } = parsed_data;
 
         let method_kind = match kind {
-            Call => {
-                MethodKind::Call(CallMethod { is_payable, is_private, result_serializer, receiver })
-            }
-            Init => MethodKind::Init(InitMethod { is_payable, ignores_state }),
+            Call => MethodKind::Call(CallMethod {
+                is_payable,
+                is_private,
+                result_serializer,
+                receiver,
+                non_reentrant,
+            }),
+            Init => MethodKind::Init(InitMethod {
+                is_payable,
+                ignores_state,
+                exports_schema,
+                on_deploy,
+            }),
             View => MethodKind::View(ViewMethod { is_private, result_serializer, receiver }),
         };
 
@@ -214,14 +258,14 @@ fn parse_return_kind(typ: &Type, handles_result: ResultHandling) -> syn::Result<
         ResultHandling::NoCheck => Ok(ReturnKind::HandlesResult(typ.clone())),
         ResultHandling::Check => {
             if !utils::type_is_result(typ) {
-                Err(Error::new(typ.span(), "Function marked with #[handle_result] should return Result<T, E> (where E implements FunctionError). If you're trying to use a type alias for `Result`, try `#[handle_result(aliased)]`."))
+                Err(Diagnostic::HandleResultType.error(typ.span(), "Function marked with #[handle_result] should return Result<T, E> (where E implements FunctionError). If you're trying to use a type alias for `Result`, try `#[handle_result(aliased)]`."))
             } else {
                 Ok(ReturnKind::HandlesResult(typ.clone()))
             }
         }
         ResultHandling::None => {
             if utils::type_is_result(typ) {
-                Err(Error::new(
+                Err(Diagnostic::ResultWithoutHandleResult.error(
                     typ.span(),
                     "Serializing Result<T, E> has been deprecated. Consider marking your method \
                     with #[handle_result] if the second generic represents a panicable error or \
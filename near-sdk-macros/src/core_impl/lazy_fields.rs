@@ -0,0 +1,118 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Attribute, Error, Fields, ItemStruct};
+
+/// Replaces the type `T` of each field of `input` marked with `#[near(lazy)]` by
+/// `store::Lazy<T>`, stored under the key `"{Struct}::{field}"`, and generates the accessors of
+/// the values of these fields.
+pub(crate) fn expand_lazy_fields(
+    input: &mut ItemStruct,
+    near_sdk: &TokenStream2,
+) -> syn::Result<TokenStream2> {
+    let ident = input.ident.clone();
+    let is_named = matches!(input.fields, Fields::Named(_));
+    let mut errors = vec![];
+    let mut accessors = TokenStream2::new();
+    for field in input.fields.iter_mut() {
+        let position = match field.attrs.iter().position(is_lazy_attr) {
+            Some(position) => position,
+            None => continue,
+        };
+        field.attrs.remove(position);
+        let field_ident = match &field.ident {
+            Some(field_ident) if is_named => field_ident.clone(),
+            _ => {
+                errors.push(Error::new_spanned(&field.ty, "Lazy fields must be named."));
+                continue;
+            }
+        };
+
+        let ty = field.ty.clone();
+        let vis = &field.vis;
+        let key = format!("{}::{}", ident, field_ident);
+        let constructor = format_ident!("lazy_{}", field_ident);
+        let getter_mut = format_ident!("{}_mut", field_ident);
+        let setter = format_ident!("set_{}", field_ident);
+        let constructor_doc =
+            format!(" Value of the lazy field `{}`, stored under `{:?}`.", field_ident, key);
+        let getter_doc = format!(
+            " Returns the value of `{}`, read from storage on the first access.",
+            field_ident
+        );
+        let setter_doc =
+            format!(" Sets the value of `{}`, without reading the current one.", field_ident);
+        accessors.extend(quote! {
+            #[doc = #constructor_doc]
+            #vis fn #constructor(value: #ty) -> #near_sdk::store::Lazy<#ty> {
+                #near_sdk::store::Lazy::new(#key.as_bytes(), value)
+            }
+
+            #[doc = #getter_doc]
+            #vis fn #field_ident(&self) -> &#ty {
+                self.#field_ident.get()
+            }
+
+            #[doc = #getter_doc]
+            #vis fn #getter_mut(&mut self) -> &mut #ty {
+                self.#field_ident.get_mut()
+            }
+
+            #[doc = #setter_doc]
+            #vis fn #setter(&mut self, value: #ty) {
+                self.#field_ident.set(value)
+            }
+        });
+        field.ty = syn::parse_quote! { #near_sdk::store::Lazy<#ty> };
+    }
+
+    if let Some(combined_errors) = errors.into_iter().reduce(|mut l, r| (l.combine(r), l).1) {
+        return Err(combined_errors);
+    }
+    if accessors.is_empty() {
+        return Ok(accessors);
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #accessors
+        }
+    })
+}
+
+/// Whether the attribute is `#[near(lazy)]`.
+fn is_lazy_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("near")
+        && matches!(attr.parse_args::<syn::Ident>(), Ok(arg) if arg == "lazy")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand_lazy_fields;
+    use crate::core_impl::utils::test_helpers::{
+        local_insta_assert_snapshot, pretty_print_syn_str,
+    };
+    use quote::quote;
+    use syn::{parse_quote, ItemStruct};
+
+    #[test]
+    fn lazy_fields() {
+        let mut input: ItemStruct = parse_quote! {
+            pub struct Contract {
+                owner: AccountId,
+                #[near(lazy)]
+                pub metadata: Metadata,
+            }
+        };
+        let actual = expand_lazy_fields(&mut input, &quote!(::near_sdk)).unwrap();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&quote!(#input #actual)).unwrap());
+    }
+
+    #[test]
+    fn lazy_fields_unnamed() {
+        let mut input: ItemStruct = parse_quote! {
+            pub struct Contract(#[near(lazy)] Metadata);
+        };
+        let actual = expand_lazy_fields(&mut input, &quote!(::near_sdk)).map(|_| ()).unwrap_err();
+        assert_eq!(actual.to_string(), "Lazy fields must be named.");
+    }
+}
@@ -1,12 +1,26 @@
 #[cfg(feature = "abi")]
 pub(crate) mod abi;
+mod abi_client;
+mod async_method;
 mod code_generator;
 mod contract_metadata;
+mod diagnostic;
 mod event;
 mod info_extractor;
+mod lazy_fields;
+mod storage_key;
+mod then_method;
 mod utils;
+mod versioned_state;
+pub(crate) use abi_client::{abi_client, AbiClientArgs};
+pub(crate) use async_method::{expand_async_methods, find_ident};
 pub(crate) use code_generator::*;
 pub(crate) use contract_metadata::contract_source_metadata_const;
 pub(crate) use contract_metadata::ContractMetadata;
+pub(crate) use diagnostic::Diagnostic;
 pub(crate) use event::{get_event_version, near_events};
 pub(crate) use info_extractor::*;
+pub(crate) use lazy_fields::expand_lazy_fields;
+pub(crate) use storage_key::borsh_storage_key;
+pub(crate) use then_method::expand_then_methods;
+pub(crate) use versioned_state::versioned_state;
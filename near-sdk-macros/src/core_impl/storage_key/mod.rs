@@ -0,0 +1,289 @@
+use inflector::Inflector;
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_quote, Expr, ExprLit, Fields, Generics, Ident, ItemEnum, ItemStruct, Lit, LitByteStr,
+    Variant, WhereClause,
+};
+
+/// Generates the storage key implementations of the `BorshStorageKey` derive.
+///
+/// Structs and enums without explicit prefixes use their Borsh serialization as the storage key.
+/// Enums where every variant has a `#[storage_key(prefix = b"...")]` attribute use the prefix of
+/// the variant followed by the Borsh serialization of its fields instead, so that the keys do
+/// not depend on the order of the variants. Unit variants with a `#[storage_key(nested)]`
+/// attribute get a constructor of the prefixes of collections nested in entries of another
+/// collection, named after the variant in snake case. Enums also get an implementation of
+/// `StorageKeyPrefixes`, which is used to check at compile time that the prefixes of multiple
+/// enums do not collide.
+pub(crate) fn borsh_storage_key(item: TokenStream) -> TokenStream {
+    let result = if let Ok(input) = syn::parse::<ItemEnum>(item.clone()) {
+        enum_storage_key(&input)
+    } else if let Ok(input) = syn::parse::<ItemStruct>(item) {
+        Ok(borsh_into_storage_key(&input.ident, &input.generics))
+    } else {
+        Err(syn::Error::new(
+            Span::call_site(),
+            "BorshStorageKey can only be used as a derive on enums or structs.",
+        ))
+    };
+    match result {
+        Ok(output) => TokenStream::from(output),
+        Err(err) => TokenStream::from(err.to_compile_error()),
+    }
+}
+
+fn borsh_into_storage_key(name: &Ident, generics: &Generics) -> TokenStream2 {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let predicate = parse_quote!(#name #ty_generics: ::near_sdk::borsh::BorshSerialize);
+    let where_clause: WhereClause = if let Some(mut w) = where_clause.cloned() {
+        w.predicates.push(predicate);
+        w
+    } else {
+        parse_quote!(where #predicate)
+    };
+    quote! {
+        impl #impl_generics ::near_sdk::__private::BorshIntoStorageKey for #name #ty_generics #where_clause {}
+    }
+}
+
+fn enum_storage_key(input: &ItemEnum) -> syn::Result<TokenStream2> {
+    let variant_attrs =
+        input.variants.iter().map(variant_attrs).collect::<syn::Result<Vec<_>>>()?;
+    let nested_impl = nested_constructors(input, &variant_attrs);
+    let explicit_prefixes: Vec<_> = variant_attrs.into_iter().map(|attrs| attrs.prefix).collect();
+
+    let (key_impl, prefixes) = if explicit_prefixes.iter().all(Option::is_none) {
+        let prefixes = borsh_tags(input)?.into_iter().map(|tag| vec![tag]).collect();
+        (borsh_into_storage_key(&input.ident, &input.generics), prefixes)
+    } else {
+        let prefixes = explicit_prefixes
+            .into_iter()
+            .zip(&input.variants)
+            .map(|(prefix, variant)| {
+                prefix.ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        &variant.ident,
+                        "all variants need a `#[storage_key(prefix = ...)]` attribute if one of them has one",
+                    )
+                })
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        check_unique_prefixes(input, &prefixes)?;
+        (prefixed_into_storage_key(input, &prefixes), prefixes)
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let prefixes = prefixes.iter().map(|prefix| LitByteStr::new(prefix, Span::call_site()));
+    Ok(quote! {
+        #key_impl
+
+        #nested_impl
+
+        impl #impl_generics ::near_sdk::__private::StorageKeyPrefixes for #name #ty_generics #where_clause {
+            const PREFIXES: &'static [&'static [u8]] = &[#(#prefixes),*];
+        }
+    })
+}
+
+/// Options of a variant set with the `#[storage_key(...)]` attribute.
+#[derive(Default)]
+struct VariantAttrs {
+    /// Explicit prefix of the variant, set with `prefix = b"..."`.
+    prefix: Option<Vec<u8>>,
+    /// Whether the variant is the parent prefix of nested collections, set with `nested`.
+    nested: bool,
+}
+
+/// Parses the `#[storage_key(prefix = b"...", nested)]` attribute of a variant.
+fn variant_attrs(variant: &Variant) -> syn::Result<VariantAttrs> {
+    let mut attrs = VariantAttrs::default();
+    let mut seen = false;
+    for attr in variant.attrs.iter().filter(|attr| attr.path().is_ident("storage_key")) {
+        if seen {
+            return Err(syn::Error::new_spanned(attr, "duplicate `storage_key` attribute"));
+        }
+        seen = true;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("nested") {
+                attrs.nested = true;
+                return Ok(());
+            }
+            if !meta.path.is_ident("prefix") {
+                return Err(meta.error("expected `prefix = b\"...\"` or `nested`"));
+            }
+            let bytes = match meta.value()?.parse::<Lit>()? {
+                Lit::ByteStr(lit) => lit.value(),
+                Lit::Str(lit) => lit.value().into_bytes(),
+                lit => {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        "expected a byte string or string literal",
+                    ))
+                }
+            };
+            if bytes.is_empty() {
+                return Err(meta.error("storage key prefix cannot be empty"));
+            }
+            attrs.prefix = Some(bytes);
+            Ok(())
+        })?;
+    }
+    if attrs.nested && !matches!(variant.fields, Fields::Unit) {
+        return Err(syn::Error::new_spanned(
+            &variant.ident,
+            "`#[storage_key(nested)]` can only be used on unit variants",
+        ));
+    }
+    Ok(attrs)
+}
+
+/// Returns the first byte of the Borsh serialization of each variant.
+fn borsh_tags(input: &ItemEnum) -> syn::Result<Vec<u8>> {
+    let use_discriminant = input.attrs.iter().any(|attr| {
+        attr.path().is_ident("borsh")
+            && attr
+                .meta
+                .to_token_stream()
+                .to_string()
+                .replace(' ', "")
+                .contains("use_discriminant=true")
+    });
+
+    let mut next_tag = 0u64;
+    input
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(index, variant)| {
+            let tag = match &variant.discriminant {
+                Some((_, Expr::Lit(ExprLit { lit: Lit::Int(lit), .. }))) if use_discriminant => {
+                    lit.base10_parse::<u64>()?
+                }
+                Some((_, expr)) if use_discriminant => {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        "BorshStorageKey requires integer literal discriminants",
+                    ))
+                }
+                _ if use_discriminant => next_tag,
+                _ => index as u64,
+            };
+            next_tag = tag + 1;
+            u8::try_from(tag).map_err(|_| {
+                syn::Error::new_spanned(
+                    &variant.ident,
+                    "the Borsh tag of a storage key variant has to fit in a byte",
+                )
+            })
+        })
+        .collect()
+}
+
+/// Checks that no explicit prefix is a prefix of another, as the keys of the variants could then
+/// overlap.
+fn check_unique_prefixes(input: &ItemEnum, prefixes: &[Vec<u8>]) -> syn::Result<()> {
+    for (i, variant) in input.variants.iter().enumerate() {
+        for (other, other_prefix) in input.variants.iter().zip(prefixes).take(i) {
+            if prefixes[i].starts_with(other_prefix) || other_prefix.starts_with(&prefixes[i]) {
+                return Err(syn::Error::new_spanned(
+                    &variant.ident,
+                    format!(
+                        "the storage key prefix of `{}` collides with the prefix of `{}`",
+                        variant.ident, other.ident
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn prefixed_into_storage_key(input: &ItemEnum, prefixes: &[Vec<u8>]) -> TokenStream2 {
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for field in input.variants.iter().flat_map(|variant| variant.fields.iter()) {
+        let ty = &field.ty;
+        where_clause.predicates.push(parse_quote!(#ty: ::near_sdk::borsh::BorshSerialize));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let arms = input.variants.iter().zip(prefixes).map(|(variant, prefix)| {
+        let ident = &variant.ident;
+        let prefix = LitByteStr::new(prefix, Span::call_site());
+        let bindings: Vec<Ident> =
+            (0..variant.fields.len()).map(|i| format_ident!("field_{}", i)).collect();
+        let pattern = match &variant.fields {
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|field| &field.ident);
+                quote!({ #(#names: #bindings),* })
+            }
+            Fields::Unnamed(_) => quote!(( #(#bindings),* )),
+            Fields::Unit => quote!(),
+        };
+        quote! {
+            Self::#ident #pattern => {
+                let mut key = #prefix.to_vec();
+                #(
+                    ::near_sdk::borsh::BorshSerialize::serialize(&#bindings, &mut key)
+                        .unwrap_or_else(|_| ::near_sdk::env::abort());
+                )*
+                key
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_generics ::near_sdk::IntoStorageKey for #name #ty_generics #where_clause {
+            fn into_storage_key(self) -> ::std::vec::Vec<u8> {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    }
+}
+
+/// Generates a `fn variant_name(key: &K) -> ::near_sdk::StorageKey` constructor of the nested
+/// prefixes of each `#[storage_key(nested)]` variant.
+fn nested_constructors(input: &ItemEnum, variant_attrs: &[VariantAttrs]) -> Option<TokenStream2> {
+    let constructors: Vec<_> = input
+        .variants
+        .iter()
+        .zip(variant_attrs)
+        .filter(|(_, attrs)| attrs.nested)
+        .map(|(variant, _)| {
+            let ident = &variant.ident;
+            let fn_name = format_ident!("{}", ident.to_string().to_snake_case());
+            let doc = format!(
+                " Returns the prefix of the collection nested in the entry `key`, made of the key \
+                of [`{}`](Self::{}) followed by the Borsh serialization of `key`.",
+                ident, ident
+            );
+            quote! {
+                #[doc = #doc]
+                pub fn #fn_name<K>(key: &K) -> ::near_sdk::StorageKey
+                where
+                    Self: ::near_sdk::IntoStorageKey,
+                    K: ::near_sdk::borsh::BorshSerialize + ?Sized,
+                {
+                    ::near_sdk::StorageKey::nested(Self::#ident, key)
+                }
+            }
+        })
+        .collect();
+    if constructors.is_empty() {
+        return None;
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Some(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#constructors)*
+        }
+    })
+}
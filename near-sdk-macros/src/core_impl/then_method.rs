@@ -0,0 +1,153 @@
+use crate::core_impl::{find_ident, Diagnostic};
+use quote::ToTokens;
+use syn::spanned::Spanned;
+use syn::{parse_quote, Attribute, Expr, Ident, ImplItem, ImplItemFn, ItemImpl, LitStr, Stmt};
+
+/// Callback of a method marked with `#[then(...)]`.
+struct ThenAttr {
+    /// The method of the contract called with the result of the promise.
+    callback: Ident,
+    /// Variables passed to the callback as arguments.
+    capture: Vec<Ident>,
+}
+
+/// Chains the promise returned by each method marked with `#[then(callback = "name")]` with a
+/// call of the method `name` of the current account, passing it the variables listed in
+/// `capture(...)`, which are in scope at the end of the method, as arguments.
+pub(crate) fn expand_then_methods(input: &mut ItemImpl) -> syn::Result<()> {
+    let mut errors = vec![];
+    for item in &mut input.items {
+        let method = match item {
+            ImplItem::Fn(method) => method,
+            _ => continue,
+        };
+        let position = match method.attrs.iter().position(|attr| attr.path().is_ident("then")) {
+            Some(position) => position,
+            None => continue,
+        };
+        let attr = method.attrs.remove(position);
+        if let Err(e) = parse_then_attr(&attr).and_then(|then| expand_method(method, then)) {
+            errors.push(e);
+        }
+    }
+
+    match errors.into_iter().reduce(|mut l, r| (l.combine(r), l).1) {
+        Some(combined_errors) => Err(combined_errors),
+        None => Ok(()),
+    }
+}
+
+fn parse_then_attr(attr: &Attribute) -> syn::Result<ThenAttr> {
+    let mut callback = None;
+    let mut capture = vec![];
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("callback") {
+            let name: LitStr = meta.value()?.parse()?;
+            callback = Some(name.parse::<Ident>().map_err(|_| {
+                Diagnostic::ThenMethod.error(name.span(), "The callback must be a method name.")
+            })?);
+            Ok(())
+        } else if meta.path.is_ident("capture") {
+            meta.parse_nested_meta(|meta| {
+                capture.push(meta.path.require_ident()?.clone());
+                Ok(())
+            })
+        } else {
+            Err(meta.error("Expected `callback = \"name\"` or `capture(...)`."))
+        }
+    })?;
+    let callback = callback.ok_or_else(|| {
+        Diagnostic::ThenMethod.error_spanned(attr, "#[then] requires `callback = \"name\"`.")
+    })?;
+    Ok(ThenAttr { callback, capture })
+}
+
+fn expand_method(method: &mut ImplItemFn, then: ThenAttr) -> syn::Result<()> {
+    if let Some(token) = find_ident(method.block.to_token_stream(), "return") {
+        return Err(Diagnostic::ThenMethod.error(
+            token.span(),
+            "`return` is not supported in methods with #[then], as the returned promise would \
+            not be followed by the callback.",
+        ));
+    }
+    let span = method.block.stmts.last().map_or(method.sig.ident.span(), |stmt| stmt.span());
+    let promise = match method.block.stmts.last_mut() {
+        Some(Stmt::Expr(expr, None)) => expr,
+        _ => {
+            return Err(Diagnostic::ThenMethod.error(
+                span,
+                "Methods with #[then] must end with the expression of the promise they return.",
+            ))
+        }
+    };
+    let ThenAttr { callback, capture } = then;
+    let chained: Expr = parse_quote! {
+        ::near_sdk::Promise::then(
+            #promise,
+            Self::ext(::near_sdk::env::current_account_id()).#callback(#(#capture),*),
+        )
+    };
+    *promise = chained;
+    Ok(())
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use super::expand_then_methods;
+    use crate::core_impl::utils::test_helpers::{local_insta_assert_snapshot, pretty_print_syn_str};
+    use quote::ToTokens;
+    use syn::{parse_quote, ItemImpl};
+
+    #[test]
+    fn then_method_capture() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Hello {
+                #[payable]
+                #[then(callback = "on_signature", capture(request_id, payload))]
+                pub fn sign(&mut self, payload: String) -> Promise {
+                    let request_id: u64 = self.next_request_id;
+                    self.next_request_id += 1;
+                    signer::ext(self.signer.clone()).sign(payload.clone())
+                }
+            }
+        };
+        expand_then_methods(&mut input).unwrap();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&input.to_token_stream()).unwrap());
+    }
+
+    #[test]
+    fn then_method_without_tail_expression() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Hello {
+                #[then(callback = "on_value")]
+                pub fn method(&mut self) -> Promise {
+                    let promise = other::ext(self.other.clone()).value();
+                    promise;
+                }
+            }
+        };
+        let actual = expand_then_methods(&mut input).unwrap_err();
+        let expected = "[near-sdk E0018] Methods with #[then] must end with the expression of the promise they return.";
+        assert_eq!(expected, actual.to_string());
+    }
+
+    #[test]
+    fn then_method_return() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Hello {
+                #[then(callback = "on_value")]
+                pub fn method(&mut self, cached: bool) -> Promise {
+                    if cached {
+                        return Promise::new(env::current_account_id());
+                    }
+                    other::ext(self.other.clone()).value()
+                }
+            }
+        };
+        let actual = expand_then_methods(&mut input).unwrap_err();
+        let expected = "[near-sdk E0018] `return` is not supported in methods with #[then], as the returned promise would not be followed by the callback.";
+        assert_eq!(expected, actual.to_string());
+    }
+}
@@ -4,6 +4,8 @@ use syn::spanned::Spanned;
 use syn::token::{And, Mut};
 use syn::{GenericArgument, Path, PathArguments, Signature, Type};
 
+use crate::core_impl::Diagnostic;
+
 #[cfg(test)]
 pub mod test_helpers;
 
@@ -24,6 +26,17 @@ pub(crate) fn type_is_result(ty: &Type) -> bool {
     }
 }
 
+/// Checks whether the last segment of the path of the type is `name`, e.g. `Promise` for
+/// `near_sdk::Promise`.
+pub(crate) fn type_is_named(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => {
+            matches!(type_path.path.segments.last(), Some(segment) if segment.ident == name)
+        }
+        _ => false,
+    }
+}
+
 /// Extracts the Ok type from a `Result` type.
 ///
 /// For example, given `Result<String, u8>` type it will return `String` type.
@@ -47,6 +60,19 @@ pub(crate) fn extract_ok_type(ty: &Type) -> Option<&Type> {
     }
 }
 
+/// Extracts the Ok types from a tuple of `Result` types.
+///
+/// For example, given `(Result<String, E>, Result<u8, E>)` type it will return `String` and `u8`
+/// types. Returns `None` if the type is not a non-empty tuple of `Result` types.
+pub(crate) fn extract_join_ok_types(ty: &Type) -> Option<Vec<&Type>> {
+    match ty {
+        Type::Tuple(type_tuple) if !type_tuple.elems.is_empty() => {
+            type_tuple.elems.iter().map(extract_ok_type).collect()
+        }
+        _ => None,
+    }
+}
+
 /// Checks whether the given path is literally "Vec".
 /// Note that it won't match a fully qualified name `std::vec::Vec` or a type alias like
 /// `type MyVec = Vec<String>`.
@@ -88,7 +114,7 @@ pub(crate) fn extract_ref_mut(ty: &Type) -> syn::Result<(Option<And>, Option<Mut
             Ok((None, None, (*x).clone()))
         }
         Type::Reference(r) => Ok((Some(r.and_token), r.mutability, (*r.elem.as_ref()).clone())),
-        _ => Err(syn::Error::new_spanned(ty, "Unsupported contract API type.")),
+        _ => Err(Diagnostic::UnsupportedType.error_spanned(ty, "Unsupported contract API type.")),
     }
 }
 
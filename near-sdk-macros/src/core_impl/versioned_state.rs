@@ -0,0 +1,201 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{Error, Fields, ItemStruct, Member, Path};
+
+/// Generates the `{Contract}VersionedState` enum of a struct marked with
+/// `#[near(contract_state(versioned(V1, V2, ...)))]`, with a variant for each of the previous
+/// versions of the state, in order, and a last one for the struct itself.
+///
+/// The state is serialized as the last variant of the enum and deserialized from any of them,
+/// converting the previous versions to the current one with the `From` implementations of each
+/// version into the next one. The struct itself must not derive the Borsh traits, and the
+/// `#[borsh(...)]` attributes of its fields are moved to the generated implementations.
+pub(crate) fn versioned_state(
+    input: &mut ItemStruct,
+    versions: &[Path],
+    near_sdk: &TokenStream2,
+    borsh_crate: &str,
+) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "Versioned contract state can't have generic parameters.",
+        ));
+    }
+    if matches!(input.fields, Fields::Unit) {
+        return Err(Error::new(input.span(), "Versioned contract state must have fields."));
+    }
+    if versions.len() >= u8::MAX as usize {
+        return Err(Error::new(input.span(), "Too many versions of the contract state."));
+    }
+
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let versioned_ident = format_ident!("{}VersionedState", ident);
+    let borsh = quote! { #near_sdk::borsh };
+
+    // Structs with the fields of the current version, and references to them, which are
+    // serialized as the struct would be with the Borsh derives.
+    let mut fields = input.fields.clone();
+    let mut field_refs = input.fields.clone();
+    for (field, field_ref) in fields.iter_mut().zip(field_refs.iter_mut()) {
+        field.attrs.retain(|attr| attr.path().is_ident("borsh"));
+        field.vis = syn::Visibility::Inherited;
+        field_ref.attrs = field.attrs.clone();
+        field_ref.vis = syn::Visibility::Inherited;
+        let ty = &field.ty;
+        field_ref.ty = syn::parse_quote! { &'a #ty };
+    }
+    for field in input.fields.iter_mut() {
+        field.attrs.retain(|attr| !attr.path().is_ident("borsh"));
+    }
+    let members: Vec<Member> = match &input.fields {
+        Fields::Named(named) => {
+            named.named.iter().map(|field| Member::Named(field.ident.clone().unwrap())).collect()
+        }
+        _ => (0..input.fields.len()).map(Member::from).collect(),
+    };
+    let semi = match &input.fields {
+        Fields::Named(_) => quote! {},
+        _ => quote! { ; },
+    };
+
+    let current_tag = versions.len() as u8;
+    let variants: Vec<_> = (1..=versions.len() + 1).map(|n| format_ident!("V{}", n)).collect();
+    let current_variant = &variants[versions.len()];
+    let previous_variants = &variants[..versions.len()];
+    let tags: Vec<u8> = (0..current_tag).collect();
+    let conversions = (0..versions.len()).map(|index| {
+        let state = quote! { state };
+        versions[index + 1..].iter().fold(
+            state,
+            |state, version| quote! { <#version as ::std::convert::From<_>>::from(#state) },
+        )
+    });
+    let variant_docs = versions.iter().map(|version| {
+        let doc = format!(" State of [`{}`].", quote! { #version }.to_string().replace(' ', ""));
+        quote! { #[doc = #doc] }
+    });
+
+    let versioned_doc = format!(" Versions of the state of [`{}`].", ident);
+    let current_doc = format!(" State of [`{}`], the current version.", ident);
+    Ok(quote! {
+        #[doc = #versioned_doc]
+        #vis enum #versioned_ident {
+            #(
+                #variant_docs
+                #previous_variants(#versions),
+            )*
+            #[doc = #current_doc]
+            #current_variant(#ident),
+        }
+
+        impl ::std::convert::From<#versioned_ident> for #ident {
+            fn from(state: #versioned_ident) -> Self {
+                match state {
+                    #(#versioned_ident::#previous_variants(state) => {
+                        <#ident as ::std::convert::From<_>>::from(#conversions)
+                    })*
+                    #versioned_ident::#current_variant(state) => state,
+                }
+            }
+        }
+
+        const _: () = {
+            #[derive(#borsh::BorshDeserialize)]
+            #[borsh(crate = #borsh_crate)]
+            struct Fields #fields #semi
+
+            // Fields skipped by `#[borsh(skip)]` are never read.
+            #[allow(dead_code)]
+            #[derive(#borsh::BorshSerialize)]
+            #[borsh(crate = #borsh_crate)]
+            struct FieldRefs<'a> #field_refs #semi
+
+            impl #borsh::BorshSerialize for #versioned_ident {
+                fn serialize<W: #borsh::io::Write>(&self, writer: &mut W) -> #borsh::io::Result<()> {
+                    match self {
+                        #(Self::#previous_variants(state) => {
+                            #borsh::BorshSerialize::serialize(&#tags, writer)?;
+                            #borsh::BorshSerialize::serialize(state, writer)
+                        })*
+                        Self::#current_variant(state) => #borsh::BorshSerialize::serialize(state, writer),
+                    }
+                }
+            }
+
+            impl #borsh::BorshDeserialize for #versioned_ident {
+                fn deserialize_reader<R: #borsh::io::Read>(reader: &mut R) -> #borsh::io::Result<Self> {
+                    let tag: u8 = #borsh::BorshDeserialize::deserialize_reader(reader)?;
+                    #(
+                        if tag == #tags {
+                            return Ok(Self::#previous_variants(#borsh::BorshDeserialize::deserialize_reader(reader)?));
+                        }
+                    )*
+                    if tag == #current_tag {
+                        let fields: Fields = #borsh::BorshDeserialize::deserialize_reader(reader)?;
+                        return Ok(Self::#current_variant(#ident { #(#members: fields.#members),* }));
+                    }
+                    Err(#borsh::io::Error::new(
+                        #borsh::io::ErrorKind::InvalidData,
+                        ::std::format!("Unexpected version {} of the contract state", tag),
+                    ))
+                }
+            }
+
+            impl #borsh::BorshSerialize for #ident {
+                fn serialize<W: #borsh::io::Write>(&self, writer: &mut W) -> #borsh::io::Result<()> {
+                    #borsh::BorshSerialize::serialize(&#current_tag, writer)?;
+                    #borsh::BorshSerialize::serialize(&FieldRefs { #(#members: &self.#members),* }, writer)
+                }
+            }
+
+            impl #borsh::BorshDeserialize for #ident {
+                fn deserialize_reader<R: #borsh::io::Read>(reader: &mut R) -> #borsh::io::Result<Self> {
+                    let state: #versioned_ident = #borsh::BorshDeserialize::deserialize_reader(reader)?;
+                    Ok(state.into())
+                }
+            }
+        };
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::versioned_state;
+    use crate::core_impl::utils::test_helpers::{
+        local_insta_assert_snapshot, pretty_print_syn_str,
+    };
+    use quote::quote;
+    use syn::{parse_quote, ItemStruct};
+
+    #[test]
+    fn versioned_state_two_versions() {
+        let mut input: ItemStruct = parse_quote! {
+            pub struct Contract {
+                pub greeting: String,
+                #[borsh(skip)]
+                cache: Option<u64>,
+            }
+        };
+        let versions = [parse_quote!(ContractV1), parse_quote!(legacy::ContractV2)];
+        let actual =
+            versioned_state(&mut input, &versions, &quote!(::near_sdk), "::near_sdk::borsh")
+                .unwrap();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&quote!(#input #actual)).unwrap());
+    }
+
+    #[test]
+    fn versioned_state_generic() {
+        let mut input: ItemStruct = parse_quote! {
+            pub struct Contract<T> {
+                value: T,
+            }
+        };
+        let actual = versioned_state(&mut input, &[], &quote!(::near_sdk), "::near_sdk::borsh")
+            .map(|_| ())
+            .unwrap_err();
+        assert_eq!(actual.to_string(), "Versioned contract state can't have generic parameters.");
+    }
+}
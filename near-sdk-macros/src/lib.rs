@@ -9,10 +9,11 @@ use proc_macro::TokenStream;
 
 use self::core_impl::*;
 use darling::ast::NestedMeta;
+use darling::util::{Override, PathList};
 use darling::{Error, FromMeta};
 use proc_macro2::{Ident, Span};
 use quote::{quote, ToTokens};
-use syn::{parse_quote, Expr, ImplItem, ItemEnum, ItemImpl, ItemStruct, ItemTrait, WhereClause};
+use syn::{parse_quote, Expr, ImplItem, ItemEnum, ItemImpl, ItemStruct, ItemTrait};
 
 #[derive(Debug, Clone)]
 struct Serializers {
@@ -35,12 +36,38 @@ impl FromMeta for Serializers {
     }
 }
 
+/// Arguments of `contract_state(...)` in `#[near(contract_state(...))]`.
+#[derive(FromMeta, Default)]
+struct ContractStateArgs {
+    versioned: Option<Override<PathList>>,
+}
+
 #[derive(FromMeta)]
 struct NearMacroArgs {
     serializers: Option<Serializers>,
-    contract_state: Option<bool>,
+    contract_state: Option<Override<ContractStateArgs>>,
     contract_metadata: Option<core_impl::ContractMetadata>,
     inside_nearsdk: Option<bool>,
+    generate_interface: Option<Override<String>>,
+    deny_unknown_arguments: Option<bool>,
+    instantiate: Option<syn::Type>,
+}
+
+/// Arguments of `#[near_bindgen]` on impl blocks.
+#[derive(FromMeta, Default)]
+struct NearBindgenImplArgs {
+    generate_interface: Option<Override<String>>,
+    deny_unknown_arguments: Option<bool>,
+    /// The instantiation of a generic impl block, from which its methods are exported.
+    instantiate: Option<syn::Type>,
+}
+
+impl NearBindgenImplArgs {
+    fn parse(attr: TokenStream) -> Result<Self, proc_macro2::TokenStream> {
+        let meta_list =
+            NestedMeta::parse_meta_list(attr.into()).map_err(|e| Error::from(e).write_errors())?;
+        Self::from_list(&meta_list).map_err(|e| e.write_errors())
+    }
 }
 
 fn has_nested_near_macros(item: TokenStream) -> bool {
@@ -103,11 +130,29 @@ pub fn near(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     let mut expanded: proc_macro2::TokenStream = quote! {};
 
-    if near_macro_args.contract_state.unwrap_or(false) {
-        if let Some(metadata) = near_macro_args.contract_metadata {
-            expanded = quote! {#[#near_sdk_crate::near_bindgen(#metadata)]}
-        } else {
+    let contract_state = near_macro_args.contract_state.map(Override::unwrap_or_default);
+    let is_contract_state = contract_state.is_some();
+    let versions = contract_state.and_then(|args| args.versioned).map(|versions| match versions {
+        Override::Explicit(versions) => versions.to_vec(),
+        Override::Inherit => vec![],
+    });
+
+    let instantiate = near_macro_args.instantiate.as_ref().map(|instance| {
+        let instance = instance.to_token_stream().to_string();
+        quote! { instantiate = #instance }
+    });
+
+    if is_contract_state {
+        let bindgen_args: Vec<_> = near_macro_args
+            .contract_metadata
+            .map(|metadata| metadata.to_token_stream())
+            .into_iter()
+            .chain(instantiate.clone())
+            .collect();
+        if bindgen_args.is_empty() {
             expanded = quote! {#[#near_sdk_crate::near_bindgen]}
+        } else {
+            expanded = quote! {#[#near_sdk_crate::near_bindgen(#(#bindgen_args),*)]}
         }
     };
 
@@ -160,6 +205,11 @@ pub fn near(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     }
 
+    // The Borsh traits of versioned state are implemented along with its versions.
+    if versions.is_some() {
+        has_borsh = false;
+    }
+
     #[cfg(feature = "abi")]
     {
         let schema_derive: proc_macro2::TokenStream =
@@ -186,19 +236,59 @@ pub fn near(attr: TokenStream, item: TokenStream) -> TokenStream {
         };
     }
 
-    if let Ok(input) = syn::parse::<ItemStruct>(item.clone()) {
+    if let Ok(mut input) = syn::parse::<ItemStruct>(item.clone()) {
+        let lazy_accessors = match core_impl::expand_lazy_fields(&mut input, &near_sdk_crate) {
+            Ok(lazy_accessors) => lazy_accessors,
+            Err(err) => return err.to_compile_error().into(),
+        };
+        let versioned_state = match versions {
+            Some(versions) => match core_impl::versioned_state(
+                &mut input,
+                &versions,
+                &near_sdk_crate,
+                &string_borsh_crate,
+            ) {
+                Ok(versioned_state) => versioned_state,
+                Err(err) => return err.to_compile_error().into(),
+            },
+            None => quote! {},
+        };
         expanded = quote! {
             #expanded
             #input
+            #lazy_accessors
+            #versioned_state
         };
     } else if let Ok(input) = syn::parse::<ItemEnum>(item.clone()) {
+        if versions.is_some() {
+            return TokenStream::from(
+                syn::Error::new(
+                    Span::call_site(),
+                    "Only structs can be marked with `contract_state(versioned)`.",
+                )
+                .to_compile_error(),
+            );
+        }
         expanded = quote! {
             #expanded
             #input
         };
     } else if let Ok(input) = syn::parse::<ItemImpl>(item) {
+        let mut bindgen_args = vec![];
+        match near_macro_args.generate_interface {
+            Some(Override::Explicit(mod_name)) => {
+                bindgen_args.push(quote! { generate_interface = #mod_name })
+            }
+            Some(Override::Inherit) => bindgen_args.push(quote! { generate_interface }),
+            None => {}
+        }
+        if near_macro_args.deny_unknown_arguments.unwrap_or_default() {
+            bindgen_args.push(quote! { deny_unknown_arguments });
+        }
+        bindgen_args.extend(instantiate);
+        let bindgen_args = (!bindgen_args.is_empty()).then(|| quote! { (#(#bindgen_args),*) });
         expanded = quote! {
-            #[#near_sdk_crate::near_bindgen]
+            #[#near_sdk_crate::near_bindgen #bindgen_args]
             #input
         };
     } else {
@@ -221,19 +311,28 @@ pub fn near_bindgen(attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 
     let generate_metadata = |ident: &Ident,
-                             generics: &syn::Generics|
+                             generics: &syn::Generics,
+                             instantiate: Option<syn::Type>|
      -> Result<proc_macro2::TokenStream, proc_macro2::TokenStream> {
         let metadata_impl_gen = generate_contract_metadata_method(ident, generics).into();
 
         let metadata_impl_gen = syn::parse::<ItemImpl>(metadata_impl_gen)
             .expect("failed to generate contract metadata");
-        process_impl_block(metadata_impl_gen)
+        process_impl_block(
+            metadata_impl_gen,
+            &NearBindgenImplArgs { instantiate, ..Default::default() },
+        )
     };
 
     if let Ok(input) = syn::parse::<ItemStruct>(item.clone()) {
-        let metadata = core_impl::contract_source_metadata_const(attr);
+        let (instantiate, attr) = match split_instantiate(attr) {
+            Ok(args) => args,
+            Err(err) => return err.into(),
+        };
+        let field_types: Vec<_> = input.fields.iter().map(|field| &field.ty).collect();
+        let metadata = core_impl::contract_source_metadata_const(attr, &field_types);
 
-        let metadata_impl_gen = generate_metadata(&input.ident, &input.generics);
+        let metadata_impl_gen = generate_metadata(&input.ident, &input.generics, instantiate);
 
         let metadata_impl_gen = match metadata_impl_gen {
             Ok(metadata) => metadata,
@@ -245,16 +344,30 @@ pub fn near_bindgen(attr: TokenStream, item: TokenStream) -> TokenStream {
         let abi_embedded = abi::embed();
         #[cfg(not(feature = "__abi-embed-checked"))]
         let abi_embedded = quote! {};
+        #[cfg(feature = "__abi-generate")]
+        let abi_generated = abi::generate_contract(&input.attrs);
+        #[cfg(not(feature = "__abi-generate"))]
+        let abi_generated = quote! {};
         TokenStream::from(quote! {
             #input
             #ext_gen
             #abi_embedded
+            #abi_generated
             #metadata
             #metadata_impl_gen
         })
     } else if let Ok(input) = syn::parse::<ItemEnum>(item.clone()) {
-        let metadata = core_impl::contract_source_metadata_const(attr);
-        let metadata_impl_gen = generate_metadata(&input.ident, &input.generics);
+        let (instantiate, attr) = match split_instantiate(attr) {
+            Ok(args) => args,
+            Err(err) => return err.into(),
+        };
+        let field_types: Vec<_> = input
+            .variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter().map(|field| &field.ty))
+            .collect();
+        let metadata = core_impl::contract_source_metadata_const(attr, &field_types);
+        let metadata_impl_gen = generate_metadata(&input.ident, &input.generics, instantiate);
 
         let metadata_impl_gen = match metadata_impl_gen {
             Ok(metadata) => metadata,
@@ -266,10 +379,15 @@ pub fn near_bindgen(attr: TokenStream, item: TokenStream) -> TokenStream {
         let abi_embedded = abi::embed();
         #[cfg(not(feature = "__abi-embed-checked"))]
         let abi_embedded = quote! {};
+        #[cfg(feature = "__abi-generate")]
+        let abi_generated = abi::generate_contract(&input.attrs);
+        #[cfg(not(feature = "__abi-generate"))]
+        let abi_generated = quote! {};
         TokenStream::from(quote! {
             #input
             #ext_gen
             #abi_embedded
+            #abi_generated
             #metadata
             #metadata_impl_gen
         })
@@ -279,16 +397,21 @@ pub fn near_bindgen(attr: TokenStream, item: TokenStream) -> TokenStream {
                 let ident = &m.sig.ident;
                 if ident.eq("__contract_abi") || ident.eq("contract_source_metadata") {
                     return TokenStream::from(
-                        syn::Error::new_spanned(
-                            ident.to_token_stream(),
-                            "use of reserved contract method",
-                        )
-                        .to_compile_error(),
+                        core_impl::Diagnostic::ReservedMethod
+                            .error_spanned(
+                                ident.to_token_stream(),
+                                "use of reserved contract method",
+                            )
+                            .to_compile_error(),
                     );
                 }
             }
         }
-        match process_impl_block(input) {
+        let args = match NearBindgenImplArgs::parse(attr) {
+            Ok(args) => args,
+            Err(err) => return err.into(),
+        };
+        match process_impl_block(input, &args) {
             Ok(output) => output,
             Err(output) => output,
         }
@@ -304,19 +427,57 @@ pub fn near_bindgen(attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
+/// Splits `instantiate = "..."` off the arguments of `#[near_bindgen]` on the contract state,
+/// whose other arguments are its `contract_metadata(...)`.
+fn split_instantiate(
+    attr: TokenStream,
+) -> Result<(Option<syn::Type>, TokenStream), proc_macro2::TokenStream> {
+    let meta_list =
+        NestedMeta::parse_meta_list(attr.into()).map_err(|e| Error::from(e).write_errors())?;
+    let mut instantiate = None;
+    let mut rest = vec![];
+    for meta in meta_list {
+        match &meta {
+            NestedMeta::Meta(meta) if meta.path().is_ident("instantiate") => {
+                instantiate =
+                    Some(syn::Type::from_meta(meta).map_err(|e| e.with_span(meta).write_errors())?);
+            }
+            _ => rest.push(meta),
+        }
+    }
+    Ok((instantiate, quote! { #(#rest),* }.into()))
+}
+
 // This function deals with impl block processing, generating wrappers and ABI.
 //
 // # Arguments
 // * input - impl block to process.
+// * args - arguments of `#[near_bindgen]` on the impl block.
 //
 // The Result has a TokenStream error type, because those need to be propagated to the compiler.
 fn process_impl_block(
     mut input: ItemImpl,
+    args: &NearBindgenImplArgs,
 ) -> Result<proc_macro2::TokenStream, proc_macro2::TokenStream> {
-    let item_impl_info = match ItemImplInfo::new(&mut input) {
+    if let Err(err) = expand_async_methods(&mut input) {
+        return Err(err.to_compile_error());
+    }
+    if let Err(err) = expand_then_methods(&mut input) {
+        return Err(err.to_compile_error());
+    }
+    let item_impl_info = match &args.instantiate {
+        Some(instance) => ItemImplInfo::instantiate(&mut input, instance),
+        None => ItemImplInfo::new(&mut input),
+    };
+    let mut item_impl_info = match item_impl_info {
         Ok(x) => x,
         Err(err) => return Err(err.to_compile_error()),
     };
+    if args.deny_unknown_arguments.unwrap_or_default() {
+        for method in &mut item_impl_info.methods {
+            method.attr_signature_info.deny_unknown_arguments = true;
+        }
+    }
 
     #[cfg(not(feature = "__abi-generate"))]
     let abi_generated = quote! {};
@@ -324,15 +485,29 @@ fn process_impl_block(
     let abi_generated = abi::generate(&item_impl_info);
 
     let generated_code = item_impl_info.wrapper_code();
+    let exported_names_check = item_impl_info.exported_names_check();
+    let non_reentrant_callbacks_check = item_impl_info.non_reentrant_callbacks_check();
+    let init_schema_code = item_impl_info.init_schema_code();
+    let init_on_deploy_code = item_impl_info.init_on_deploy_code();
 
     // Add wrapper methods for ext call API
     let ext_generated_code = item_impl_info.generate_ext_wrapper_code();
+    let interface_code = match &args.generate_interface {
+        Some(Override::Explicit(mod_name)) => item_impl_info.interface_code(Some(mod_name)),
+        Some(Override::Inherit) => item_impl_info.interface_code(None),
+        None => quote! {},
+    };
 
     Ok(TokenStream::from(quote! {
         #ext_generated_code
         #input
         #generated_code
+        #exported_names_check
+        #non_reentrant_callbacks_check
+        #init_schema_code
+        #init_on_deploy_code
         #abi_generated
+        #interface_code
     })
     .into())
 }
@@ -340,23 +515,19 @@ fn process_impl_block(
 #[proc_macro_attribute]
 pub fn ext_contract(attr: TokenStream, item: TokenStream) -> TokenStream {
     if let Ok(mut input) = syn::parse::<ItemTrait>(item) {
-        let mod_name: Option<proc_macro2::Ident> = if attr.is_empty() {
-            None
-        } else {
-            match syn::parse(attr) {
-                Ok(x) => x,
-                Err(err) => {
-                    return TokenStream::from(
-                        syn::Error::new(
-                            Span::call_site(),
-                            format!("Failed to parse mod name for ext_contract: {}", err),
-                        )
-                        .to_compile_error(),
+        let ExtContractAttr { mod_name, defaults } = match syn::parse(attr) {
+            Ok(x) => x,
+            Err(err) => {
+                return TokenStream::from(
+                    syn::Error::new(
+                        err.span(),
+                        format!("Failed to parse ext_contract arguments: {}", err),
                     )
-                }
+                    .to_compile_error(),
+                )
             }
         };
-        let item_trait_info = match ItemTraitInfo::new(&mut input, mod_name) {
+        let item_trait_info = match ItemTraitInfo::new(&mut input, mod_name, defaults) {
             Ok(x) => x,
             Err(err) => return TokenStream::from(err.to_compile_error()),
         };
@@ -374,6 +545,16 @@ pub fn ext_contract(attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
+/// Generates the client of the contract described by an ABI file, see `near_sdk::abi_client`.
+#[proc_macro]
+pub fn abi_client(item: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(item as AbiClientArgs);
+    match core_impl::abi_client(args) {
+        Ok(client) => TokenStream::from(client),
+        Err(err) => TokenStream::from(err.to_compile_error()),
+    }
+}
+
 // The below attributes a marker-attributes and therefore they are no-op.
 
 /// `callback` is a marker attribute it does not generate code by itself.
@@ -611,12 +792,12 @@ fn get_where_clause(
     generics: &syn::Generics,
     input_ident: &syn::Ident,
     trait_name: proc_macro2::TokenStream,
-) -> WhereClause {
+) -> syn::WhereClause {
     let (_, ty_generics, where_clause) = generics.split_for_impl();
 
     let predicate = parse_quote!(#input_ident #ty_generics: #trait_name);
 
-    let where_clause: WhereClause = if let Some(mut w) = where_clause.cloned() {
+    let where_clause: syn::WhereClause = if let Some(mut w) = where_clause.cloned() {
         w.predicates.push(predicate);
         w
     } else {
@@ -647,54 +828,148 @@ pub fn derive_no_default(item: TokenStream) -> TokenStream {
     }
 }
 
-#[proc_macro_derive(BorshStorageKey)]
+#[proc_macro_derive(BorshStorageKey, attributes(storage_key))]
 pub fn borsh_storage_key(item: TokenStream) -> TokenStream {
-    let (name, generics) = if let Ok(input) = syn::parse::<ItemEnum>(item.clone()) {
-        (input.ident, input.generics)
-    } else if let Ok(input) = syn::parse::<ItemStruct>(item) {
-        (input.ident, input.generics)
-    } else {
+    core_impl::borsh_storage_key(item)
+}
+
+#[derive(darling::FromDeriveInput)]
+#[darling(attributes(function_error))]
+struct DeriveFunctionError {
+    ident: syn::Ident,
+    data: darling::ast::Data<FunctionErrorVariant, darling::util::Ignored>,
+    code: Option<syn::LitStr>,
+}
+
+#[derive(darling::FromVariant)]
+#[darling(attributes(function_error))]
+struct FunctionErrorVariant {
+    ident: syn::Ident,
+    code: Option<syn::LitStr>,
+}
+
+#[proc_macro_derive(FunctionError, attributes(function_error))]
+pub fn function_error(item: TokenStream) -> TokenStream {
+    use darling::FromDeriveInput;
+
+    let derive_input = syn::parse_macro_input!(item as syn::DeriveInput);
+    if let syn::Data::Union(_) = derive_input.data {
         return TokenStream::from(
             syn::Error::new(
                 Span::call_site(),
-                "BorshStorageKey can only be used as a derive on enums or structs.",
+                "FunctionError can only be used as a derive on enums or structs.",
             )
             .to_compile_error(),
         );
+    }
+    let args = match DeriveFunctionError::from_derive_input(&derive_input) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(e.write_errors());
+        }
     };
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    let predicate = parse_quote!(#name #ty_generics: ::near_sdk::borsh::BorshSerialize);
-    let where_clause: WhereClause = if let Some(mut w) = where_clause.cloned() {
-        w.predicates.push(predicate);
-        w
-    } else {
-        parse_quote!(where #predicate)
+    let name = &args.ident;
+
+    let error_code = match &args.data {
+        darling::ast::Data::Struct(_) => args.code.as_ref().map(|code| quote! { #code }),
+        darling::ast::Data::Enum(variants) => {
+            if let Some(code) = &args.code {
+                return TokenStream::from(
+                    syn::Error::new_spanned(
+                        code,
+                        "The codes of an enum are set on its variants with `#[function_error(code = \"...\")]`.",
+                    )
+                    .to_compile_error(),
+                );
+            }
+            if variants.iter().all(|variant| variant.code.is_none()) {
+                None
+            } else {
+                let mut arms = Vec::new();
+                for FunctionErrorVariant { ident, code } in variants {
+                    match code {
+                        Some(code) => arms.push(quote! { Self::#ident { .. } => #code, }),
+                        None => {
+                            return TokenStream::from(
+                                syn::Error::new_spanned(
+                                    ident,
+                                    "Every variant needs a code once a variant has one, set with `#[function_error(code = \"...\")]`.",
+                                )
+                                .to_compile_error(),
+                            )
+                        }
+                    }
+                }
+                Some(quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                })
+            }
+        }
     };
-    TokenStream::from(quote! {
-        impl #impl_generics ::near_sdk::__private::BorshIntoStorageKey for #name #ty_generics #where_clause {}
+
+    TokenStream::from(match error_code {
+        Some(error_code) => quote! {
+            impl ::near_sdk::ErrorCode for #name {
+                fn error_code(&self) -> &'static str {
+                    #error_code
+                }
+            }
+
+            impl ::near_sdk::FunctionError for #name {
+                fn panic(&self) -> ! {
+                    ::near_sdk::env::panic_error(self)
+                }
+            }
+        },
+        None => quote! {
+            impl ::near_sdk::FunctionError for #name {
+                fn panic(&self) -> ! {
+                    ::near_sdk::env::panic_str(&::std::string::ToString::to_string(&self))
+                }
+            }
+        },
     })
 }
 
-#[proc_macro_derive(FunctionError)]
-pub fn function_error(item: TokenStream) -> TokenStream {
-    let name = if let Ok(input) = syn::parse::<ItemEnum>(item.clone()) {
-        input.ident
-    } else if let Ok(input) = syn::parse::<ItemStruct>(item) {
-        input.ident
-    } else {
-        return TokenStream::from(
-            syn::Error::new(
-                Span::call_site(),
-                "FunctionError can only be used as a derive on enums or structs.",
-            )
-            .to_compile_error(),
-        );
+#[derive(darling::FromDeriveInput)]
+#[darling(attributes(transfer_call_msg))]
+struct DeriveTransferCallMsg {
+    ident: syn::Ident,
+    generics: syn::Generics,
+    max_len: Option<usize>,
+    version: Option<u64>,
+}
+
+#[proc_macro_derive(TransferCallMsg, attributes(transfer_call_msg))]
+pub fn derive_transfer_call_msg(input: TokenStream) -> TokenStream {
+    use darling::FromDeriveInput;
+
+    let derive_input = syn::parse_macro_input!(input as syn::DeriveInput);
+    let args = match DeriveTransferCallMsg::from_derive_input(&derive_input) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(e.write_errors());
+        }
     };
+
+    let name = &args.ident;
+    let (impl_generics, type_generics, where_clause) = args.generics.split_for_impl();
+    let max_len = args.max_len.map(|max_len| {
+        quote! {
+            const MAX_LEN: usize = #max_len;
+        }
+    });
+    let version = args.version.map(|version| {
+        quote! {
+            const VERSION: ::std::option::Option<u64> = ::std::option::Option::Some(#version);
+        }
+    });
     TokenStream::from(quote! {
-        impl ::near_sdk::FunctionError for #name {
-            fn panic(&self) -> ! {
-                ::near_sdk::env::panic_str(&::std::string::ToString::to_string(&self))
-            }
+        impl #impl_generics ::near_sdk::TransferCallMsg for #name #type_generics #where_clause {
+            #max_len
+            #version
         }
     })
 }
@@ -5,24 +5,47 @@ fn compilation_tests() {
     t.compile_fail("compilation_tests/invalid_arg_pat.rs");
     t.pass("compilation_tests/regular.rs");
     t.pass("compilation_tests/private.rs");
+    t.pass("compilation_tests/view_callback_preview.rs");
+    t.pass("compilation_tests/async_method.rs");
+    t.pass("compilation_tests/non_reentrant.rs");
+    t.compile_fail("compilation_tests/non_reentrant_no_callback.rs");
+    t.compile_fail("compilation_tests/non_reentrant_public_callback.rs");
+    t.pass("compilation_tests/then_method.rs");
+    t.pass("compilation_tests/ext_contract_defaults.rs");
+    t.pass("compilation_tests/generate_interface.rs");
+    t.pass("compilation_tests/deny_unknown_arguments.rs");
+    t.pass("compilation_tests/arg_default.rs");
+    t.pass("compilation_tests/versioned_state.rs");
+    t.pass("compilation_tests/lazy_fields.rs");
+    t.pass("compilation_tests/private_allow.rs");
+    t.pass("compilation_tests/payable_bounds.rs");
+    t.pass("compilation_tests/only_role.rs");
+    t.pass("compilation_tests/only_owner.rs");
     t.pass("compilation_tests/trait_impl.rs");
     t.compile_fail("compilation_tests/bad_argument.rs");
     t.pass("compilation_tests/complex.rs");
     t.compile_fail("compilation_tests/impl_generic.rs");
+    t.pass("compilation_tests/impl_generic_instantiate.rs");
     t.pass("compilation_tests/references.rs");
     t.pass("compilation_tests/init_function.rs");
     t.pass("compilation_tests/init_ignore_state.rs");
+    t.pass("compilation_tests/init_on_deploy.rs");
     t.pass("compilation_tests/no_default.rs");
     // TODO: unignore upon resolution of https://github.com/near/near-sdk-rs/issues/1211
     // t.pass("compilation_tests/lifetime_method_result.rs");
     t.pass("compilation_tests/lifetime_method.rs");
     t.pass("compilation_tests/cond_compilation.rs");
     t.compile_fail("compilation_tests/payable_view.rs");
+    t.compile_fail("compilation_tests/callback_result_type.rs");
+    t.compile_fail("compilation_tests/duplicate_method.rs");
+    t.compile_fail("compilation_tests/duplicate_method_modules.rs");
     t.pass("compilation_tests/borsh_storage_key.rs");
     t.pass("compilation_tests/borsh_storage_key_generics.rs");
     t.pass("compilation_tests/function_error.rs");
+    t.pass("compilation_tests/function_error_code.rs");
     t.pass("compilation_tests/enum_near_bindgen.rs");
     t.pass("compilation_tests/schema_derive.rs");
+    t.pass("compilation_tests/types_schema.rs");
 
     if rustversion::cfg!(since(1.80)) && std::env::consts::OS == "linux" {
         // The compilation error output has slightly changed in 1.7x and 1.8x and between platforms,
@@ -33,10 +56,15 @@ fn compilation_tests() {
     t.compile_fail("compilation_tests/generic_const_function.rs");
     t.pass("compilation_tests/self_support.rs");
     t.pass("compilation_tests/private_init_method.rs");
+    t.pass("compilation_tests/init_schema.rs");
+    t.pass("compilation_tests/transfer_call_msg.rs");
+    t.pass("compilation_tests/custom_serializer.rs");
+    t.pass("compilation_tests/callback_result_borsh.rs");
     t.compile_fail("compilation_tests/self_forbidden_in_non_init_fn_return.rs");
     t.compile_fail("compilation_tests/self_forbidden_in_non_init_fn_arg.rs");
     t.pass("compilation_tests/handle_result_alias.rs");
     t.pass("compilation_tests/contract_metadata.rs");
+    t.pass("compilation_tests/contract_metadata_auto_standards.rs");
     t.compile_fail("compilation_tests/contract_metadata_fn_name.rs");
     t.pass("compilation_tests/contract_metadata_bindgen.rs");
     t.pass("compilation_tests/types.rs");
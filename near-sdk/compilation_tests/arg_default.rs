@@ -0,0 +1,30 @@
+//! Optional JSON arguments with default values.
+
+use near_sdk::near;
+
+const DEFAULT_LIMIT: u64 = 10;
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Contract {
+    items: Vec<u64>,
+}
+
+#[near]
+impl Contract {
+    pub fn items(
+        &self,
+        #[arg(default)] from_index: u64,
+        #[arg(default = "DEFAULT_LIMIT")] limit: u64,
+    ) -> Vec<u64> {
+        self.items.iter().skip(from_index as usize).take(limit as usize).copied().collect()
+    }
+
+    pub fn add(&mut self, item: u64, #[arg(default = "Some(1)")] times: Option<u64>) {
+        for _ in 0..times.unwrap_or(1) {
+            self.items.push(item);
+        }
+    }
+}
+
+fn main() {}
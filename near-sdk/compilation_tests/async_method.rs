@@ -0,0 +1,55 @@
+//! Cross-contract calls awaited in `#[near(async)]` methods.
+
+use near_sdk::json_types::U128;
+use near_sdk::{env, ext_contract, near, AccountId, PromiseError};
+
+#[ext_contract(ext_oracle)]
+trait Oracle {
+    fn get_price(&self, token_id: AccountId) -> U128;
+}
+
+#[ext_contract(ext_ledger)]
+trait Ledger {
+    fn record(&mut self, account_id: AccountId, amount: U128);
+}
+
+#[near(contract_state)]
+struct Exchange {
+    oracle: AccountId,
+    ledger: AccountId,
+    fee: u128,
+}
+
+impl Default for Exchange {
+    fn default() -> Self {
+        Self {
+            oracle: "oracle.near".parse().unwrap(),
+            ledger: "ledger.near".parse().unwrap(),
+            fee: 1,
+        }
+    }
+}
+
+#[near]
+impl Exchange {
+    #[near(async)]
+    #[handle_result]
+    pub fn quote(&mut self, token_id: AccountId, amount: U128) -> Result<U128, &'static str> {
+        let fee: u128 = self.fee;
+        let price: Result<U128, PromiseError> =
+            ext_oracle::ext(self.oracle.clone()).get_price(token_id).await;
+        // Passed to the next callback, so it has to be serializable.
+        let price: Option<U128> = price.ok();
+        ext_ledger::ext(self.ledger.clone()).record(env::predecessor_account_id(), amount).await;
+        let price = price.ok_or("oracle failed")?;
+        Ok(U128(amount.0 * price.0 - fee))
+    }
+
+    #[near(async)]
+    pub fn refresh(&mut self, token_id: AccountId) {
+        let price: U128 = ext_oracle::ext(self.oracle.clone()).get_price(token_id).await;
+        self.fee = price.0 / 100;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,60 @@
+//! Callback results serialized with Borsh.
+
+use near_sdk::{ext_contract, near, AccountId, PromiseError};
+
+#[ext_contract(ext_vault)]
+pub trait Vault {
+    #[result_serializer(borsh)]
+    fn balance_of(&self, account_id: AccountId) -> u128;
+
+    #[result_serializer(borsh)]
+    fn withdraw(&mut self, amount: u128);
+}
+
+#[near(contract_state)]
+#[derive(Default)]
+struct Contract {
+    balances: Vec<u128>,
+}
+
+#[near]
+impl Contract {
+    #[private]
+    pub fn on_balance(
+        &mut self,
+        #[callback_result]
+        #[serializer(borsh)]
+        balance: Result<u128, PromiseError>,
+        #[callback_result]
+        #[serializer(borsh)]
+        withdrawn: Result<(), PromiseError>,
+        account_id: AccountId,
+    ) -> bool {
+        let _ = account_id;
+        if let Ok(balance) = balance {
+            self.balances.push(balance);
+        }
+        withdrawn.is_ok()
+    }
+
+    #[private]
+    pub fn on_balances(
+        &mut self,
+        #[callback_join]
+        #[serializer(borsh)]
+        balances: (Result<u128, PromiseError>, Result<u128, PromiseError>),
+    ) {
+        self.balances.extend(balances.0.ok());
+        self.balances.extend(balances.1.ok());
+    }
+
+    #[near(async)]
+    pub fn refresh(&mut self, vault_id: AccountId, account_id: AccountId) -> u128 {
+        #[serializer(borsh)]
+        let balance: Result<u128, PromiseError> =
+            ext_vault::ext(vault_id).balance_of(account_id).await;
+        balance.unwrap_or(0)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+//! `#[callback_result]` arguments have to be a `Result<T, PromiseError>`.
+
+use near_sdk::near;
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Callbacks {}
+
+#[near]
+impl Callbacks {
+    #[private]
+    pub fn on_call(&mut self, #[callback_result] value: u64) {}
+}
+
+fn main() {}
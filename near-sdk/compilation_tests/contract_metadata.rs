@@ -12,4 +12,9 @@ struct Contract {}
 #[near]
 impl Contract {}
 
-fn main() {}
+fn main() {
+    let metadata = Contract::contract_source_metadata();
+    assert_eq!(metadata.version.as_deref(), Some("39f2d2646f2f60e18ab53337501370dc02a5661c"));
+    assert_eq!(metadata.standards.len(), 3);
+    assert_eq!(metadata.standards[1].standard, "nep171");
+}
@@ -0,0 +1,13 @@
+//! Contract metadata without version nor link, with the standards of the components of the state.
+
+use near_sdk::near;
+
+#[near(contract_state, contract_metadata(auto_standards))]
+struct Contract {
+    value: u64,
+}
+
+#[near]
+impl Contract {}
+
+fn main() {}
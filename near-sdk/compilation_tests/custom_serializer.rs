@@ -0,0 +1,61 @@
+//! Arguments and results serialized with an `ArgsSerializer`.
+
+use near_sdk::serde::{de::DeserializeOwned, Serialize};
+use near_sdk::{ext_contract, near, ArgsSerializer, PromiseError};
+
+/// JSON with the value in an envelope, standing in for a binary format.
+pub struct Enveloped;
+
+impl ArgsSerializer for Enveloped {
+    const FORMAT: &'static str = "enveloped-json";
+    type Error = near_sdk::serde_json::Error;
+
+    fn serialize<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Self::Error> {
+        near_sdk::serde_json::to_vec(&near_sdk::serde_json::json!({ "value": value }))
+    }
+
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Self::Error> {
+        let mut envelope: near_sdk::serde_json::Value = near_sdk::serde_json::from_slice(bytes)?;
+        near_sdk::serde_json::from_value(envelope["value"].take())
+    }
+}
+
+#[ext_contract(ext_oracle)]
+pub trait Oracle {
+    #[result_serializer(with = Enveloped)]
+    fn price(&self, #[serializer(with = Enveloped)] pair: String) -> u64;
+}
+
+#[near(contract_state)]
+#[derive(Default)]
+struct Contract {
+    prices: Vec<u64>,
+}
+
+#[near]
+impl Contract {
+    #[result_serializer(with = Enveloped)]
+    pub fn add(
+        &mut self,
+        #[serializer(with = Enveloped)] price: u64,
+        #[serializer(with = Enveloped)] pair: String,
+    ) -> usize {
+        let _ = pair;
+        self.prices.push(price);
+        self.prices.len()
+    }
+
+    #[private]
+    pub fn on_price(
+        &mut self,
+        #[callback_result]
+        #[serializer(with = Enveloped)]
+        price: Result<u64, PromiseError>,
+    ) {
+        if let Ok(price) = price {
+            self.prices.push(price);
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+//! Methods rejecting unknown JSON arguments.
+
+use near_sdk::near;
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Contract {
+    greeting: String,
+}
+
+#[near(deny_unknown_arguments)]
+impl Contract {
+    pub fn set_greeting(&mut self, greeting: Option<String>) {
+        self.greeting = greeting.unwrap_or_default();
+    }
+
+    pub fn clear(&mut self) {
+        self.greeting.clear();
+    }
+}
+
+#[near]
+impl Contract {
+    #[near(deny_unknown_arguments)]
+    pub fn get_greeting(&self) -> String {
+        self.greeting.clone()
+    }
+
+    #[near(deny_unknown_arguments)]
+    pub fn set_borsh(&mut self, #[serializer(borsh)] greeting: String) {
+        self.greeting = greeting;
+    }
+}
+
+fn main() {}
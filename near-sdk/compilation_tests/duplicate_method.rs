@@ -0,0 +1,20 @@
+//! Contract methods have to have unique names.
+
+use near_sdk::near;
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Counter {}
+
+#[near]
+impl Counter {
+    pub fn get(&self) -> u64 {
+        0
+    }
+
+    pub fn get(&self) -> u64 {
+        1
+    }
+}
+
+fn main() {}
@@ -0,0 +1,40 @@
+//! Contract methods exported by different impl blocks, possibly in different modules, have to
+//! have unique names.
+
+use near_sdk::near;
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Counter {}
+
+mod getter {
+    use super::*;
+
+    pub trait Getter {
+        fn get(&self) -> u64;
+    }
+
+    #[near]
+    impl Getter for Counter {
+        fn get(&self) -> u64 {
+            0
+        }
+    }
+}
+
+mod legacy {
+    use super::*;
+
+    pub trait LegacyGetter {
+        fn get(&self) -> u64;
+    }
+
+    #[near]
+    impl LegacyGetter for Counter {
+        fn get(&self) -> u64 {
+            1
+        }
+    }
+}
+
+fn main() {}
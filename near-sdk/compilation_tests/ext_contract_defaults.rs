@@ -0,0 +1,33 @@
+//! External contract clients with default deposits and gas.
+
+use near_sdk::{env, ext_contract, near, Gas, Promise};
+
+#[ext_contract(ext_bob, gas = "10 Tgas", deposit = 0)]
+trait Bob {
+    fn ping(&self);
+    #[gas("50 Tgas")]
+    #[deposit("0.01 NEAR")]
+    fn store(&mut self, value: String);
+}
+
+#[ext_contract]
+trait Alice {
+    #[gas(5_000_000_000_000)]
+    fn ping(&self);
+}
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Contract {}
+
+#[near]
+impl Contract {
+    pub fn store(&mut self, value: String) -> Promise {
+        ext_bob::ext(env::current_account_id())
+            .store(value)
+            .then(ext_bob::ext(env::current_account_id()).with_static_gas(Gas::from_tgas(1)).ping())
+            .then(alice::ext(env::current_account_id()).ping())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,60 @@
+//! Testing FunctionError macro with error codes.
+
+use near_sdk::near;
+use near_sdk::{ErrorCode, FunctionError};
+use std::fmt;
+
+#[derive(FunctionError)]
+#[function_error(code = "INVALID_VALUE")]
+struct ErrorStruct {
+    message: String,
+}
+
+impl fmt::Display for ErrorStruct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid value: {}", self.message)
+    }
+}
+
+#[derive(FunctionError)]
+enum ErrorEnum {
+    #[function_error(code = "NOT_FOUND")]
+    NotFound,
+    #[function_error(code = "BANNED")]
+    Banned { account_id: String },
+    #[function_error(code = "LIMIT_EXCEEDED")]
+    LimitExceeded(u32),
+}
+
+impl fmt::Display for ErrorEnum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorEnum::NotFound => write!(f, "not found"),
+            ErrorEnum::Banned { account_id } => write!(f, "account {} is banned", account_id),
+            ErrorEnum::LimitExceeded(limit) => write!(f, "limit of {} exceeded", limit),
+        }
+    }
+}
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Contract {}
+
+#[near]
+impl Contract {
+    #[handle_result]
+    pub fn set(&self, value: String) -> Result<String, ErrorStruct> {
+        Err(ErrorStruct { message: value })
+    }
+
+    #[handle_result]
+    pub fn get(&self) -> Result<String, ErrorEnum> {
+        Err(ErrorEnum::NotFound)
+    }
+}
+
+fn main() {
+    assert_eq!(ErrorStruct { message: String::new() }.error_code(), "INVALID_VALUE");
+    assert_eq!(ErrorEnum::Banned { account_id: "bob.near".to_string() }.error_code(), "BANNED");
+    assert_eq!(ErrorEnum::LimitExceeded(3).error_code(), "LIMIT_EXCEEDED");
+}
@@ -0,0 +1,69 @@
+//! Interface modules generated for the impl blocks of a contract.
+
+use near_sdk::{env, near, AccountId, Promise, PromiseError};
+
+#[near(serializers = [json])]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+#[derive(Default)]
+#[near(contract_state)]
+pub struct Counter {
+    value: u64,
+}
+
+#[near(generate_interface)]
+impl Counter {
+    #[init]
+    pub fn new(value: u64) -> Self {
+        Self { value }
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value
+    }
+
+    #[payable]
+    pub fn step(&mut self, direction: Direction) {
+        match direction {
+            Direction::Up => self.value += 1,
+            Direction::Down => self.value -= 1,
+        }
+    }
+
+    #[handle_result]
+    pub fn try_reset(&mut self) -> Result<u64, &'static str> {
+        Ok(std::mem::take(&mut self.value))
+    }
+
+    #[private]
+    pub fn on_get(&mut self, #[callback_result] value: Result<u64, PromiseError>) {
+        self.value = value.unwrap_or_default();
+    }
+}
+
+pub trait Named {
+    fn name(&self) -> String;
+}
+
+#[near(generate_interface = "named_interface")]
+impl Named for Counter {
+    fn name(&self) -> String {
+        "counter".to_string()
+    }
+}
+
+#[near]
+impl Counter {
+    pub fn step_other(&mut self, other: AccountId) -> Promise {
+        counter_interface::ext(other.clone())
+            .step(Direction::Up)
+            .then(counter_interface::ext(other.clone()).get())
+            .then(named_interface::ext(other).name())
+            .then(Self::ext(env::current_account_id()).on_get())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+//! Generic impl blocks exported for an instantiation of the contract.
+
+use near_sdk::near;
+use std::marker::PhantomData;
+
+#[near(contract_state, instantiate = "Incrementer<u32>")]
+#[derive(Default)]
+struct Incrementer<T> {
+    value: u32,
+    data: PhantomData<T>,
+}
+
+#[near(instantiate = "Incrementer<u32>")]
+impl<'a, T: 'a + std::fmt::Display> Incrementer<T> {
+    pub fn inc(&mut self, by: u32) {
+        self.value += by;
+    }
+
+    pub fn describe(&self, item: T) -> String {
+        format!("{}: {}", item, self.value)
+    }
+}
+
+fn main() {}
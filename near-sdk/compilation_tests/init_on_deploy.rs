@@ -0,0 +1,33 @@
+//! Smart contract initialized by the first call to one of its methods.
+
+use near_sdk::{env, near, AccountId, PanicOnDefault};
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+struct Incrementer {
+    owner_id: AccountId,
+    value: u32,
+}
+
+#[near]
+impl Incrementer {
+    #[init(payable, on_deploy)]
+    #[handle_result]
+    pub fn new() -> Result<Self, &'static str> {
+        if env::current_account_id().as_str().ends_with(".near") {
+            Ok(Self { owner_id: env::predecessor_account_id(), value: 0 })
+        } else {
+            Err("The contract can only be deployed to a .near account")
+        }
+    }
+
+    pub fn inc(&mut self, by: u32) {
+        self.value += by;
+    }
+
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+//! Init methods marked with `#[init(schema)]` export their name and an arguments builder.
+
+use near_sdk::json_types::U128;
+use near_sdk::{near, AccountId};
+
+#[near(contract_state)]
+struct Token {
+    owner_id: AccountId,
+    total_supply: U128,
+}
+
+#[near]
+impl Token {
+    #[init(schema)]
+    pub fn new(owner_id: AccountId, total_supply: U128) -> Self {
+        Self { owner_id, total_supply }
+    }
+}
+
+fn main() {
+    let owner_id: AccountId = "alice.near".parse().unwrap();
+    let args = Token::init_args(owner_id, U128(100));
+    assert_eq!(Token::INIT_METHOD_NAME, "new");
+    assert_eq!(args, br#"{"owner_id":"alice.near","total_supply":"100"}"#);
+
+    #[cfg(feature = "abi")]
+    {
+        let schema = Token::get_init_schema();
+        let object = schema.schema.object.unwrap();
+        assert!(object.required.contains("owner_id"));
+        assert!(object.properties.contains_key("total_supply"));
+    }
+}
@@ -0,0 +1,46 @@
+//! Contract state with lazily loaded fields.
+
+use near_sdk::{near, AccountId};
+
+#[near(serializers=[borsh])]
+pub struct Metadata {
+    description: String,
+    icon: Vec<u8>,
+}
+
+#[near(contract_state)]
+pub struct Contract {
+    owner: AccountId,
+    #[near(lazy)]
+    metadata: Metadata,
+    #[near(lazy)]
+    pub history: Vec<String>,
+}
+
+#[near]
+impl Contract {
+    #[init]
+    pub fn new(owner: AccountId, description: String) -> Self {
+        let metadata = Metadata { description, icon: vec![] };
+        Self { owner, metadata: Self::lazy_metadata(metadata), history: Self::lazy_history(vec![]) }
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    pub fn get_icon(&self) -> Vec<u8> {
+        self.metadata().icon.clone()
+    }
+
+    pub fn set_description(&mut self, description: String) {
+        self.history_mut().push(description.clone());
+        self.metadata_mut().description = description;
+    }
+
+    pub fn clear_history(&mut self) {
+        self.set_history(vec![]);
+    }
+}
+
+fn main() {}
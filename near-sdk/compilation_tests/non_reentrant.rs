@@ -0,0 +1,54 @@
+//! Methods guarded against reentrancy.
+
+use near_sdk::json_types::U128;
+use near_sdk::{env, near, AccountId, NearToken, Promise, PromiseError, PromiseOrValue};
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Vault {
+    balance: u128,
+}
+
+#[near]
+impl Vault {
+    #[non_reentrant(then = "on_withdraw")]
+    pub fn withdraw(&mut self, amount: U128) -> Promise {
+        self.balance -= amount.0;
+        Promise::new(env::predecessor_account_id())
+            .transfer(NearToken::from_yoctonear(amount.0))
+            .then(
+                Self::ext(env::current_account_id())
+                    .on_withdraw(env::predecessor_account_id(), amount),
+            )
+    }
+
+    #[non_reentrant(then = "on_withdraw")]
+    pub fn withdraw_some(&mut self, amount: U128) -> PromiseOrValue<U128> {
+        if amount.0 == 0 {
+            return PromiseOrValue::Value(amount);
+        }
+        self.withdraw(amount).into()
+    }
+
+    #[non_reentrant(key = "account_id")]
+    #[handle_result]
+    pub fn deposit(&mut self, account_id: AccountId, amount: U128) -> Result<(), &'static str> {
+        self.balance = self.balance.checked_add(amount.0).ok_or("Overflow")?;
+        Ok(())
+    }
+
+    #[private]
+    #[non_reentrant(callback, key = "account_id")]
+    pub fn on_withdraw(
+        &mut self,
+        account_id: AccountId,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            self.balance += amount.0;
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+//! Guarded methods returning a promise must name the callback clearing their guard.
+
+use near_sdk::{env, near, NearToken, Promise};
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Vault {}
+
+#[near]
+impl Vault {
+    #[non_reentrant]
+    pub fn withdraw(&mut self) -> Promise {
+        Promise::new(env::predecessor_account_id()).transfer(NearToken::from_yoctonear(1))
+    }
+}
+
+fn main() {}
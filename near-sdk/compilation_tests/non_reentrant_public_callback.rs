@@ -0,0 +1,22 @@
+//! The callbacks clearing the guards of non-reentrant methods must be private.
+
+use near_sdk::{env, near, AccountId, NearToken, Promise};
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Vault {}
+
+#[near]
+impl Vault {
+    #[non_reentrant(then = "on_withdraw")]
+    pub fn withdraw(&mut self) -> Promise {
+        Promise::new(env::predecessor_account_id()).transfer(NearToken::from_yoctonear(1))
+    }
+
+    #[non_reentrant(callback, key = "account_id")]
+    pub fn on_withdraw(&mut self, account_id: AccountId) {
+        let _ = account_id;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+//! Methods restricted to the owner of the contract.
+
+use near_sdk::{near, AccountId, HasRoles, PanicOnDefault, OWNER_ROLE};
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Contract {
+    value: u64,
+    owner_id: AccountId,
+}
+
+impl HasRoles for Contract {
+    fn has_role(&self, role: &str, account_id: &AccountId) -> bool {
+        role == OWNER_ROLE && account_id == &self.owner_id
+    }
+}
+
+#[near]
+impl Contract {
+    #[only_owner]
+    pub fn set(&mut self, value: u64) {
+        self.value = value;
+    }
+
+    #[only_owner]
+    #[payable]
+    pub fn set_owner(&mut self, owner_id: AccountId) {
+        self.owner_id = owner_id;
+    }
+}
+
+fn main() {}
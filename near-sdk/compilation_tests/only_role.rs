@@ -0,0 +1,33 @@
+//! Methods restricted to the accounts having a role.
+
+use near_sdk::store::LookupSet;
+use near_sdk::{near, AccountId, HasRoles, PanicOnDefault};
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Contract {
+    value: u64,
+    admins: LookupSet<AccountId>,
+}
+
+impl HasRoles for Contract {
+    fn has_role(&self, role: &str, account_id: &AccountId) -> bool {
+        role == "admin" && self.admins.contains(account_id)
+    }
+}
+
+#[near]
+impl Contract {
+    #[only(role = "admin")]
+    pub fn set(&mut self, value: u64) {
+        self.value = value;
+    }
+
+    #[only(role = "admin")]
+    #[payable]
+    pub fn reset(&mut self) -> u64 {
+        std::mem::take(&mut self.value)
+    }
+}
+
+fn main() {}
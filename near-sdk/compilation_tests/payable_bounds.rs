@@ -0,0 +1,45 @@
+//! Payable methods with bounds on their deposit.
+
+use near_sdk::{near, AccountId, NearToken};
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Contract {
+    deposits: u128,
+}
+
+impl Contract {
+    const MAX_DEPOSIT: NearToken = NearToken::from_near(10);
+}
+
+#[near]
+impl Contract {
+    #[init]
+    #[payable(min = "1 NEAR")]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[payable(exact = "1 yoctoNEAR")]
+    pub fn transfer(&mut self, receiver_id: AccountId) {
+        let _ = receiver_id;
+    }
+
+    #[payable(min = "0.1 NEAR", max = "Contract::MAX_DEPOSIT")]
+    pub fn deposit(&mut self) {
+        self.deposits += near_sdk::env::attached_deposit().as_yoctonear();
+    }
+
+    #[payable(exact = 1_000, refund_excess)]
+    pub fn register(&mut self, #[deposit] deposit: NearToken) {
+        self.deposits += deposit.as_yoctonear();
+    }
+
+    #[payable]
+    pub fn donate(&mut self, message: String, #[deposit] deposit: NearToken) {
+        let _ = message;
+        self.deposits += deposit.as_yoctonear();
+    }
+}
+
+fn main() {}
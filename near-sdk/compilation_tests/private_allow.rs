@@ -0,0 +1,37 @@
+//! Private methods callable by other accounts.
+
+use near_sdk::store::LookupSet;
+use near_sdk::{near, AccountId, PanicOnDefault};
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Contract {
+    value: u64,
+    controllers: LookupSet<AccountId>,
+}
+
+#[near]
+impl Contract {
+    #[private(allow = ["dao.near", "factory.near"])]
+    pub fn reset(&mut self) {
+        self.value = 0;
+    }
+
+    #[private(allow_fn = "is_controller")]
+    pub fn set(&mut self, value: u64) {
+        self.value = value;
+    }
+
+    #[private(allow = ["dao.near"], allow_fn = "is_controller")]
+    pub fn get(&self) -> u64 {
+        self.value
+    }
+}
+
+impl Contract {
+    fn is_controller(&self, account_id: &AccountId) -> bool {
+        self.controllers.contains(account_id)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,49 @@
+//! Methods chained with their callbacks by `#[then]`.
+
+use near_sdk::{ext_contract, near, AccountId, Promise, PromiseError};
+
+#[ext_contract(signer)]
+trait Signer {
+    fn sign(&self, payload: String) -> String;
+}
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Contract {
+    signer: Option<AccountId>,
+    next_request_id: u64,
+    signatures: Vec<(u64, String)>,
+}
+
+#[near]
+impl Contract {
+    #[payable]
+    #[then(callback = "on_signature", capture(request_id, payload))]
+    pub fn sign(&mut self, payload: String) -> Promise {
+        let request_id: u64 = self.next_request_id;
+        self.next_request_id += 1;
+        signer::ext(self.signer.clone().unwrap()).sign(payload.clone())
+    }
+
+    #[then(callback = "on_ping")]
+    pub fn ping(&mut self) -> Promise {
+        signer::ext(self.signer.clone().unwrap()).sign(String::new())
+    }
+
+    #[private]
+    pub fn on_signature(
+        &mut self,
+        request_id: u64,
+        payload: String,
+        #[callback_result] signature: Result<String, PromiseError>,
+    ) -> Option<String> {
+        let signature = signature.ok()?;
+        self.signatures.push((request_id, format!("{payload}:{signature}")));
+        Some(signature)
+    }
+
+    #[private]
+    pub fn on_ping(&mut self, #[callback_unwrap] _signature: String) {}
+}
+
+fn main() {}
@@ -0,0 +1,47 @@
+//! Testing TransferCallMsg macro.
+
+use near_sdk::json_types::U128;
+use near_sdk::serde::Deserialize;
+use near_sdk::{near, AccountId, PromiseOrValue, TransferCallMsg};
+
+#[derive(Deserialize, TransferCallMsg)]
+#[serde(crate = "near_sdk::serde")]
+struct DefaultMsg {
+    memo: String,
+}
+
+#[derive(Deserialize, TransferCallMsg)]
+#[serde(crate = "near_sdk::serde", tag = "action", rename_all = "snake_case")]
+#[transfer_call_msg(max_len = 256, version = 2)]
+enum DepositMsg {
+    Stake { pool_id: u64 },
+    Swap { min_amount_out: U128 },
+}
+
+#[near(contract_state)]
+#[derive(Default)]
+struct Contract {}
+
+#[near]
+impl Contract {
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let _ = sender_id;
+        match DepositMsg::parse_msg_or_refund(&msg) {
+            Some(DepositMsg::Stake { .. }) | Some(DepositMsg::Swap { .. }) => {
+                PromiseOrValue::Value(U128(0))
+            }
+            None => PromiseOrValue::Value(amount),
+        }
+    }
+}
+
+fn main() {
+    assert_eq!(<DefaultMsg as TransferCallMsg>::MAX_LEN, near_sdk::DEFAULT_MAX_MSG_LEN);
+    assert_eq!(<DepositMsg as TransferCallMsg>::MAX_LEN, 256);
+    assert_eq!(<DepositMsg as TransferCallMsg>::VERSION, Some(2));
+}
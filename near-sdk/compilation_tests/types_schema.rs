@@ -0,0 +1,59 @@
+//! Checks that the public SDK types can be used in exported method signatures, i.e. that they
+//! implement both `JsonSchema` and `BorshSchema`.
+
+use near_sdk::json_types::{Base58CryptoHash, Base64VecU8, I128, I64, U128, U64};
+use near_sdk::{AccountId, CryptoHash, Gas, GasWeight, NearToken, PromiseOrValue, PublicKey};
+
+// https://stackoverflow.com/a/71721454/9806233
+macro_rules! impls {
+    ($ty:ty: $trait:path) => {{
+        trait DoesNotImpl {
+            const IMPLS: bool = false;
+        }
+        impl<T: ?Sized> DoesNotImpl for T {}
+
+        struct Wrapper<T: ?Sized>(std::marker::PhantomData<T>);
+
+        #[allow(dead_code)]
+        impl<T: ?Sized + $trait> Wrapper<T> {
+            const IMPLS: bool = true;
+        }
+
+        <Wrapper<$ty>>::IMPLS
+    }};
+}
+
+macro_rules! const_assert_schemas {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            const _: () = {
+                assert!(
+                    impls!($ty: near_sdk::schemars::JsonSchema),
+                    concat!("`", stringify!($ty), "` does not implement `JsonSchema`")
+                );
+                assert!(
+                    impls!($ty: near_sdk::borsh::BorshSchema),
+                    concat!("`", stringify!($ty), "` does not implement `BorshSchema`")
+                );
+            };
+        )*
+    };
+}
+
+const_assert_schemas!(
+    AccountId,
+    NearToken,
+    Gas,
+    GasWeight,
+    PublicKey,
+    CryptoHash,
+    Base58CryptoHash,
+    Base64VecU8,
+    U64,
+    U128,
+    I64,
+    I128,
+    PromiseOrValue<U128>,
+);
+
+fn main() {}
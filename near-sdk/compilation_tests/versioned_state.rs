@@ -0,0 +1,35 @@
+//! Versioned contract state upgraded from its previous versions.
+
+use near_sdk::near;
+
+#[near(serializers=[borsh])]
+pub struct ContractV1 {
+    greeting: String,
+}
+
+#[near(contract_state(versioned(ContractV1)))]
+#[derive(Default)]
+pub struct Contract {
+    greeting: String,
+    count: u64,
+}
+
+impl From<ContractV1> for Contract {
+    fn from(state: ContractV1) -> Self {
+        Self { greeting: state.greeting, count: 0 }
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn set_greeting(&mut self, greeting: String) {
+        self.greeting = greeting;
+        self.count += 1;
+    }
+
+    pub fn get_greeting(&self) -> String {
+        self.greeting.clone()
+    }
+}
+
+fn main() {}
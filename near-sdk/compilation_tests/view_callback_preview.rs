@@ -0,0 +1,27 @@
+//! Callbacks with views that preview their result.
+
+use near_sdk::json_types::U128;
+use near_sdk::near;
+
+#[derive(Default)]
+#[near(contract_state)]
+struct Exchange {
+    fee_bps: u16,
+}
+
+#[near]
+impl Exchange {
+    #[private]
+    #[near(view_callback_preview)]
+    pub fn on_price(&self, #[callback_unwrap] price: U128, amount: U128) -> U128 {
+        let gross = price.0 * amount.0;
+        U128(gross - gross * u128::from(self.fee_bps) / 10_000)
+    }
+
+    #[near(view_callback_preview = "quote")]
+    pub fn on_rate(#[callback_unwrap] rate: u64, amount: u64) -> u64 {
+        rate * amount
+    }
+}
+
+fn main() {}
@@ -0,0 +1,189 @@
+//! Arithmetic of [`Gas`] and [`NearToken`] amounts in `const` items, and sums of amounts.
+//!
+//! Both types have `const` checked and saturating methods, e.g. [`Gas::checked_add`] and
+//! [`NearToken::saturating_sub`], whose `Option`s are unwieldy to compose in `const` items. The
+//! functions of this module panic on overflow instead, which fails the compilation when they are
+//! evaluated in a `const` item, so that gas budgets can be composed at compile time. Outside of
+//! `const` items, an overflow panics like the integer operations in contracts.
+//!
+//! `Gas` and `NearToken` are defined in the `near-gas` and `near-token` crates, so the SDK can't
+//! implement [`Sum`](std::iter::Sum) for them: [`checked_sum`] and [`saturating_sum`] sum them
+//! instead. They implement [`Ord`], so [`Ord::max`] and [`Ord::min`] compare them outside of
+//! `const` items, and [`gas_max`] or [`token_max`] in `const` items.
+//!
+//! # Examples
+//!
+//! ```
+//! use near_sdk::amounts::{self, gas_add, gas_mul, gas_sub};
+//! use near_sdk::{Gas, NearToken};
+//!
+//! const GAS_FOR_TRANSFER: Gas = Gas::from_tgas(10);
+//! const GAS_FOR_RESOLVE: Gas = Gas::from_tgas(5);
+//! const GAS_FOR_BATCH: Gas = gas_add(gas_mul(GAS_FOR_TRANSFER, 4), GAS_FOR_RESOLVE);
+//! const GAS_LEFT: Gas = gas_sub(Gas::from_tgas(300), GAS_FOR_BATCH);
+//! assert_eq!(GAS_LEFT, Gas::from_tgas(255));
+//!
+//! let deposits = [NearToken::from_near(1), NearToken::from_millinear(500)];
+//! assert_eq!(amounts::checked_sum(deposits), Some(NearToken::from_millinear(1_500)));
+//! ```
+//!
+//! ```compile_fail
+//! use near_sdk::{amounts::gas_sub, Gas};
+//!
+//! const GAS_LEFT: Gas = gas_sub(Gas::from_tgas(5), Gas::from_tgas(10));
+//! ```
+
+use crate::{Gas, NearToken};
+
+macro_rules! impl_const_ops {
+    (
+        $ty: ident, $scalar: ty, $to_scalar: ident, $from_scalar: ident, $name: literal,
+        $add: ident, $sub: ident, $mul: ident, $div: ident, $max: ident, $min: ident
+    ) => {
+        #[doc = concat!("Returns `a + b`, panicking if the sum overflows.")]
+        pub const fn $add(a: $ty, b: $ty) -> $ty {
+            match a.checked_add(b) {
+                Some(sum) => sum,
+                None => panic!(concat!($name, " overflow")),
+            }
+        }
+
+        #[doc = concat!("Returns `a - b`, panicking if `b` is greater than `a`.")]
+        pub const fn $sub(a: $ty, b: $ty) -> $ty {
+            match a.checked_sub(b) {
+                Some(difference) => difference,
+                None => panic!(concat!($name, " underflow")),
+            }
+        }
+
+        #[doc = concat!("Returns `a * n`, panicking if the product overflows.")]
+        pub const fn $mul(a: $ty, n: $scalar) -> $ty {
+            match a.checked_mul(n) {
+                Some(product) => product,
+                None => panic!(concat!($name, " overflow")),
+            }
+        }
+
+        #[doc = concat!("Returns `a / n` rounded down, panicking if `n` is zero.")]
+        pub const fn $div(a: $ty, n: $scalar) -> $ty {
+            match a.checked_div(n) {
+                Some(quotient) => quotient,
+                None => panic!(concat!($name, " division by zero")),
+            }
+        }
+
+        #[doc = concat!("Returns the greater of `a` and `b`, like [`Ord::max`].")]
+        pub const fn $max(a: $ty, b: $ty) -> $ty {
+            if a.$to_scalar() >= b.$to_scalar() {
+                a
+            } else {
+                b
+            }
+        }
+
+        #[doc = concat!("Returns the lesser of `a` and `b`, like [`Ord::min`].")]
+        pub const fn $min(a: $ty, b: $ty) -> $ty {
+            if a.$to_scalar() <= b.$to_scalar() {
+                a
+            } else {
+                b
+            }
+        }
+
+        impl Amount for $ty {
+            const ZERO: Self = <$ty>::$from_scalar(0);
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$ty>::checked_add(self, rhs)
+            }
+
+            fn saturating_add(self, rhs: Self) -> Self {
+                <$ty>::saturating_add(self, rhs)
+            }
+        }
+    };
+}
+
+impl_const_ops!(
+    Gas, u64, as_gas, from_gas, "gas", gas_add, gas_sub, gas_mul, gas_div, gas_max, gas_min
+);
+impl_const_ops!(
+    NearToken,
+    u128,
+    as_yoctonear,
+    from_yoctonear,
+    "token amount",
+    token_add,
+    token_sub,
+    token_mul,
+    token_div,
+    token_max,
+    token_min
+);
+
+/// Amount which can be summed by [`checked_sum`] and [`saturating_sum`], implemented for [`Gas`]
+/// and [`NearToken`].
+pub trait Amount: Copy + Ord {
+    const ZERO: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    fn saturating_add(self, rhs: Self) -> Self;
+}
+
+/// Returns the sum of `amounts`, or `None` if it overflows.
+pub fn checked_sum<A: Amount>(amounts: impl IntoIterator<Item = A>) -> Option<A> {
+    amounts.into_iter().try_fold(A::ZERO, A::checked_add)
+}
+
+/// Returns the sum of `amounts`, or the maximum amount if it overflows.
+pub fn saturating_sum<A: Amount>(amounts: impl IntoIterator<Item = A>) -> A {
+    amounts.into_iter().fold(A::ZERO, A::saturating_add)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn const_ops() {
+        const BUDGET: Gas = gas_sub(gas_mul(Gas::from_tgas(10), 3), gas_div(Gas::from_tgas(1), 2));
+        assert_eq!(BUDGET, Gas::from_ggas(29_500));
+        assert_eq!(gas_max(Gas::from_tgas(1), Gas::from_tgas(2)), Gas::from_tgas(2));
+        assert_eq!(gas_min(Gas::from_tgas(1), Gas::from_tgas(2)), Gas::from_tgas(1));
+
+        const DEPOSIT: NearToken = token_add(NearToken::from_near(1), NearToken::from_millinear(1));
+        assert_eq!(DEPOSIT, NearToken::from_millinear(1_001));
+        assert_eq!(token_mul(DEPOSIT, 2), NearToken::from_millinear(2_002));
+        assert_eq!(token_div(DEPOSIT, 7), NearToken::from_millinear(143));
+        assert_eq!(token_max(DEPOSIT, NearToken::from_near(2)), NearToken::from_near(2));
+        assert_eq!(token_min(DEPOSIT, NearToken::from_near(2)), DEPOSIT);
+    }
+
+    #[test]
+    #[should_panic(expected = "gas overflow")]
+    fn gas_overflow() {
+        gas_add(Gas::from_gas(u64::MAX), Gas::from_gas(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "token amount underflow")]
+    fn token_underflow() {
+        token_sub(NearToken::from_yoctonear(1), NearToken::from_yoctonear(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "gas division by zero")]
+    fn gas_division_by_zero() {
+        gas_div(Gas::from_tgas(1), 0);
+    }
+
+    #[test]
+    fn sums() {
+        assert_eq!(checked_sum([Gas::from_tgas(1), Gas::from_tgas(2)]), Some(Gas::from_tgas(3)));
+        assert_eq!(checked_sum(Vec::<NearToken>::new()), Some(NearToken::from_yoctonear(0)));
+        let max = NearToken::from_yoctonear(u128::MAX);
+        assert_eq!(checked_sum([max, NearToken::from_yoctonear(1)]), None);
+        assert_eq!(saturating_sum([max, NearToken::from_yoctonear(1)]), max);
+    }
+}
@@ -91,6 +91,19 @@ where
         this
     }
 
+    /// Helper utility to be able to easily migrate to the new [`LazyOption`] implementation.
+    ///
+    /// The new [`LazyOption`] stores the value under the same key with the same encoding, so the
+    /// existing value is reused in place and is only loaded when it is first accessed.
+    ///
+    /// [`LazyOption`]: crate::store::LazyOption
+    pub fn to_v2(&self) -> crate::store::LazyOption<T> {
+        crate::store::LazyOption {
+            prefix: self.storage_key.clone().into_boxed_slice(),
+            cache: Default::default(),
+        }
+    }
+
     fn serialize_value(value: &T) -> Vec<u8> {
         match to_vec(value) {
             Ok(x) => x,
@@ -37,6 +37,21 @@ impl<K, V> LookupMap<K, V> {
         Self { key_prefix: key_prefix.into_storage_key(), el: PhantomData }
     }
 
+    /// Helper utility to be able to easily migrate to the new [`LookupMap`] implementation.
+    ///
+    /// The new [`LookupMap`] with its default [`Identity`] hasher uses the same storage layout,
+    /// so the existing entries are reused in place and nothing is rewritten in storage.
+    ///
+    /// [`LookupMap`]: crate::store::LookupMap
+    /// [`Identity`]: crate::store::key::Identity
+    pub fn to_v2(&self) -> crate::store::LookupMap<K, V>
+    where
+        K: BorshSerialize + Ord,
+        V: BorshSerialize,
+    {
+        crate::store::LookupMap::new(self.key_prefix.as_slice())
+    }
+
     fn raw_key_to_storage_key(&self, raw_key: &[u8]) -> Vec<u8> {
         append_slice(&self.key_prefix, raw_key)
     }
@@ -366,6 +381,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_to_v2() {
+        let mut map = LookupMap::new(b"m");
+        map.insert(&1u64, &"a".to_string());
+        map.insert(&2u64, &"b".to_string());
+
+        let mut map_v2 = map.to_v2();
+        assert_eq!(map_v2.get(&1), Some(&"a".to_string()));
+        assert_eq!(map_v2.remove(&2), Some("b".to_string()));
+        map_v2.flush();
+
+        assert!(!map.contains_key(&2));
+    }
+
     #[test]
     fn test_debug() {
         let map: LookupMap<u64, u64> = LookupMap::new(b"m");
@@ -38,6 +38,20 @@ impl<T> LookupSet<T> {
         Self { element_prefix: element_prefix.into_storage_key(), el: PhantomData }
     }
 
+    /// Helper utility to be able to easily migrate to the new [`LookupSet`] implementation.
+    ///
+    /// The new [`LookupSet`] with its default [`Identity`] hasher uses the same storage layout,
+    /// so the existing elements are reused in place and nothing is rewritten in storage.
+    ///
+    /// [`LookupSet`]: crate::store::LookupSet
+    /// [`Identity`]: crate::store::key::Identity
+    pub fn to_v2(&self) -> crate::store::LookupSet<T>
+    where
+        T: BorshSerialize,
+    {
+        crate::store::LookupSet::new(self.element_prefix.as_slice())
+    }
+
     fn raw_element_to_storage_key(&self, element_raw: &[u8]) -> Vec<u8> {
         append_slice(&self.element_prefix, element_raw)
     }
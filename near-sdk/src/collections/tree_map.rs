@@ -386,6 +386,52 @@ where
         self.iter().collect()
     }
 
+    /// Moves up to `batch_size` entries from this tree into `target`, removing them from this
+    /// tree. Returns `true` once this tree is empty and the migration is complete.
+    ///
+    /// The storage layout of the new [`TreeMap`] is not compatible with this tree, so `target`
+    /// must use a different prefix. Trees too large to be moved within the gas limit of a single
+    /// call can be migrated by calling this method repeatedly until it returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::collections::TreeMap;
+    ///
+    /// let mut old: TreeMap<u32, u32> = TreeMap::new(b"t");
+    /// for i in 0..10 {
+    ///     old.insert(&i, &(i * 10));
+    /// }
+    ///
+    /// let mut new = near_sdk::store::TreeMap::new(b"n");
+    /// while !old.migrate_in_batches(&mut new, 3) {}
+    /// assert_eq!(new.len(), 10);
+    /// assert_eq!(new.get(&3), Some(&30));
+    /// ```
+    ///
+    /// [`TreeMap`]: crate::store::TreeMap
+    #[cfg(feature = "unstable")]
+    pub fn migrate_in_batches<H>(
+        &mut self,
+        target: &mut crate::store::TreeMap<K, V, H>,
+        batch_size: u64,
+    ) -> bool
+    where
+        H: crate::store::key::ToKey,
+    {
+        for _ in 0..batch_size {
+            let key = match self.max() {
+                Some(key) => key,
+                None => break,
+            };
+            let value = self
+                .remove(&key)
+                .unwrap_or_else(|| env::panic_str(crate::collections::ERR_INCONSISTENT_STATE));
+            target.insert(key, value);
+        }
+        self.is_empty()
+    }
+
     //
     // Internal utilities
     //
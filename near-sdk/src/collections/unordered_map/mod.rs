@@ -5,6 +5,7 @@ mod iter;
 pub use iter::Iter;
 
 use crate::collections::{append, append_slice, Vector};
+use crate::store::key::ToKey;
 use crate::{env, IntoStorageKey};
 use borsh::{to_vec, BorshDeserialize, BorshSerialize};
 use near_sdk_macros::near;
@@ -310,6 +311,52 @@ where
         }
     }
 
+    /// Moves up to `batch_size` entries from this map into `target`, removing them from this map.
+    /// Returns `true` once this map is empty and the migration is complete.
+    ///
+    /// The storage layout of [`IterableMap`] is not compatible with this map, so `target` must use
+    /// a different prefix. Maps too large to be moved within the gas limit of a single call can be
+    /// migrated by calling this method repeatedly until it returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::collections::UnorderedMap;
+    /// use near_sdk::store::IterableMap;
+    ///
+    /// let mut old: UnorderedMap<u8, u8> = UnorderedMap::new(b"m");
+    /// old.extend((0..10).map(|i| (i, i * 10)));
+    ///
+    /// let mut new: IterableMap<u8, u8> = IterableMap::new(b"n");
+    /// assert!(!old.migrate_in_batches(&mut new, 4));
+    /// assert!(!old.migrate_in_batches(&mut new, 4));
+    /// assert!(old.migrate_in_batches(&mut new, 4));
+    /// assert_eq!(new.len(), 10);
+    /// assert_eq!(new.get(&3), Some(&30));
+    /// ```
+    ///
+    /// [`IterableMap`]: crate::store::IterableMap
+    pub fn migrate_in_batches<H>(
+        &mut self,
+        target: &mut crate::store::IterableMap<K, V, H>,
+        batch_size: u64,
+    ) -> bool
+    where
+        K: Ord + Clone,
+        H: ToKey,
+    {
+        for _ in 0..batch_size {
+            // Migrating from the back only pops from the underlying vectors.
+            let key = match self.len().checked_sub(1).and_then(|last| self.keys.get(last)) {
+                Some(key) => key,
+                None => break,
+            };
+            let value = self.remove(&key).unwrap_or_else(|| env::panic_str(ERR_INCONSISTENT_STATE));
+            target.insert(key, value);
+        }
+        self.is_empty()
+    }
+
     /// Returns a view of keys as a vector.
     /// It's sometimes useful to have random access to the keys.
     pub fn keys_as_vector(&self) -> &Vector<K> {
@@ -574,6 +621,30 @@ mod tests {
         assert_eq!(actual, key_to_value);
     }
 
+    #[test]
+    pub fn test_migrate_in_batches() {
+        let mut map = UnorderedMap::new(b"m");
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(5);
+        let mut key_to_value = HashMap::new();
+        for _ in 0..100 {
+            let key = rng.gen::<u64>();
+            let value = rng.gen::<u64>();
+            key_to_value.insert(key, value);
+            map.insert(&key, &value);
+        }
+
+        let mut map_v2 = crate::store::IterableMap::new(b"n");
+        let mut batches = 0;
+        while !map.migrate_in_batches(&mut map_v2, 30) {
+            batches += 1;
+        }
+        assert_eq!(batches, 3);
+        assert!(map.is_empty());
+
+        let actual: HashMap<u64, u64> = map_v2.iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(actual, key_to_value);
+    }
+
     #[test]
     fn test_debug() {
         let mut map = UnorderedMap::new(b"m");
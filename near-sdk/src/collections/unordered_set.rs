@@ -1,6 +1,7 @@
 //! A set implemented on a trie. Unlike `std::collections::HashSet` the elements in this set are not
 //! hashed but are instead serialized.
 use crate::collections::{append, append_slice, Vector};
+use crate::store::key::ToKey;
 use crate::{env, IntoStorageKey};
 use borsh::{to_vec, BorshDeserialize, BorshSerialize};
 use near_sdk_macros::near;
@@ -236,6 +237,52 @@ where
         }
     }
 
+    /// Moves up to `batch_size` elements from this set into `target`, removing them from this set.
+    /// Returns `true` once this set is empty and the migration is complete.
+    ///
+    /// The storage layout of [`IterableSet`] is not compatible with this set, so `target` must use
+    /// a different prefix. Sets too large to be moved within the gas limit of a single call can be
+    /// migrated by calling this method repeatedly until it returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::collections::UnorderedSet;
+    /// use near_sdk::store::IterableSet;
+    ///
+    /// let mut old: UnorderedSet<u8> = UnorderedSet::new(b"s");
+    /// old.extend(0..10);
+    ///
+    /// let mut new: IterableSet<u8> = IterableSet::new(b"n");
+    /// while !old.migrate_in_batches(&mut new, 3) {}
+    /// assert_eq!(new.len(), 10);
+    /// assert!(new.contains(&7));
+    /// ```
+    ///
+    /// [`IterableSet`]: crate::store::IterableSet
+    pub fn migrate_in_batches<H>(
+        &mut self,
+        target: &mut crate::store::IterableSet<T, H>,
+        batch_size: u64,
+    ) -> bool
+    where
+        T: Ord + Clone,
+        H: ToKey,
+    {
+        for _ in 0..batch_size {
+            // Migrating from the back only pops from the underlying vector.
+            let element = match self.len().checked_sub(1).and_then(|last| self.elements.get(last)) {
+                Some(element) => element,
+                None => break,
+            };
+            if !self.remove(&element) {
+                env::panic_str(ERR_INCONSISTENT_STATE)
+            }
+            target.insert(element);
+        }
+        self.is_empty()
+    }
+
     /// Returns a view of elements as a vector.
     /// It's sometimes useful to have random access to the elements.
     pub fn as_vector(&self) -> &Vector<T> {
@@ -0,0 +1,124 @@
+//! Errors reported by the [`near`](crate::near) macro.
+//!
+//! Every error of the macro starts with a stable code, such as `[near-sdk E0005]`, which tools
+//! can use to link the error to its section below. Codes are never reused for another error.
+//!
+//! ## E0001
+//!
+//! A contract method argument is a pattern, e.g. `(a, b): (u8, u32)`. Arguments are deserialized
+//! by name, so they have to be identifiers. Destructure the argument in the method body instead.
+//!
+//! ## E0002
+//!
+//! A contract method argument or return type cannot be (de)serialized, e.g. `impl Trait` or a raw
+//! pointer. Use a concrete type implementing the traits of the serializer of the method.
+//!
+//! ## E0003
+//!
+//! A contract method has type or const generics. Exported methods have to be monomorphic, so move
+//! the generic code to a private helper called with concrete types from the contract method.
+//!
+//! ## E0004
+//!
+//! A `#[near]` impl block has generics. The contract type has to be concrete, e.g. with a type
+//! alias for a generic contract, or the instantiation of the generic contract whose methods are
+//! exported has to be declared with `#[near(instantiate = "Contract<u32>")]` on the contract state
+//! and on its impl blocks.
+//!
+//! ## E0005
+//!
+//! A view method, i.e. one taking `&self` or no `self`, is marked `#[payable]`. View calls cannot
+//! attach a deposit, so take `&mut self` if the method accepts a deposit.
+//!
+//! ## E0006
+//!
+//! A method is marked as an init method while being a view or call method. Init methods create
+//! the contract state, so they take no `self` and return `Self`.
+//!
+//! ## E0007
+//!
+//! An `#[init]` method has a `#[result_serializer]`. Init methods return the contract state,
+//! which is written to storage rather than returned.
+//!
+//! ## E0008
+//!
+//! An `#[init]` method takes a `self` parameter. Remove it and return `Self`.
+//!
+//! ## E0009
+//!
+//! An `#[init]` method does not return the contract state. Return `Self`.
+//!
+//! ## E0010
+//!
+//! A `#[handle_result]` method does not return a `Result`. Use `#[handle_result(aliased)]` if the
+//! return type is an alias of `Result`.
+//!
+//! ## E0011
+//!
+//! A method returns a `Result` without `#[handle_result]`. Mark the method with
+//! `#[handle_result]` to panic with the error, whose type has to implement
+//! [`FunctionError`](crate::FunctionError).
+//!
+//! ## E0012
+//!
+//! The signature of a method other than `#[init]` refers to `Self`. Name the type explicitly.
+//!
+//! ## E0013
+//!
+//! A method uses a name reserved for a method generated by the SDK, `__contract_abi` or
+//! `contract_source_metadata`. Rename the method.
+//!
+//! ## E0014
+//!
+//! A `#[callback_result]` argument is not a `Result`, or a `#[callback_join]` argument is not a
+//! tuple of `Result`s. Callback results are `Result<T, PromiseError>`, use `#[callback_unwrap]`
+//! to panic if the promise failed instead.
+//!
+//! ## E0015
+//!
+//! Two methods of a `#[near]` impl block have the same name, which would be exported twice.
+//! Rename one of them, or make them mutually exclusive with `#[cfg]` attributes.
+//!
+//! Methods of different `#[near]` impl blocks, e.g. implementing traits in different modules,
+//! are checked when the crate is compiled: the error is that the name
+//! `__near_contract_method_{name}` is defined multiple times, at both methods.
+//!
+//! ## E0016
+//!
+//! A `#[near(async)]` method cannot be split into promises and callbacks at its `.await`s. The
+//! method has to take `&mut self`, and each `.await` has to be a statement at the top level of
+//! the method, `promise.await;` or `let result: T = promise.await;`. Variables used after an
+//! `.await` are passed to the callback, so they need a type annotation, and `return` can only be
+//! used after the last `.await`.
+//!
+//! ## E0017
+//!
+//! A `#[non_reentrant]` method is invalid:
+//!
+//! * a view or `#[init]` method is marked `#[non_reentrant]`. The reentrancy guard is a flag in
+//!   the storage, which only call methods, taking `&mut self`, can set;
+//! * a guarded method returning a promise doesn't name the callback clearing its guard with
+//!   `#[non_reentrant(then = "callback")]`;
+//! * a `#[non_reentrant(callback)]` method doesn't name the argument holding the key of the guard
+//!   with `key = "name"`, or `key` is not an argument of the method;
+//! * a `#[non_reentrant(callback)]` method is not `#[private]`, or allows other callers with
+//!   `allow` or `allow_fn`, so that accounts other than the contract could clear the guard.
+//!
+//! ## E0018
+//!
+//! A `#[then]` method cannot be chained with its callback. The method has to end with the
+//! expression of the promise it returns and cannot use `return`, and the attribute has to name the
+//! callback, `#[then(callback = "name")]`.
+//!
+//! ## E0019
+//!
+//! A view method, taking `&self` or no `self`, writes to the storage with `env::storage_write`,
+//! `env::storage_remove` or `env::state_write`. The state is only saved by call methods, taking
+//! `&mut self`, and writes of views are either rejected by the runtime or discarded. In unit tests,
+//! view methods also panic on writes of the collections or other helpers they call.
+//!
+//! ## E0020
+//!
+//! A `#[payable(..., refund_excess)]` method reads its deposit with `env::attached_deposit`, which
+//! still includes the excess transferred back to the predecessor. The method takes the deposit it
+//! keeps with a `#[deposit]` argument instead, e.g. `#[deposit] deposit: NearToken`.
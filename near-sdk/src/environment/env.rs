@@ -12,7 +12,8 @@ use std::{convert::TryFrom, mem::MaybeUninit};
 use crate::mock::MockedBlockchain;
 use crate::promise::Allowance;
 use crate::types::{
-    AccountId, BlockHeight, Gas, NearToken, PromiseIndex, PromiseResult, PublicKey, StorageUsage,
+    AccountId, BlockHeight, CurveType, EcrecoverError, Ed25519VerifyError, EpochInfo, ErrorCode,
+    Gas, NearToken, PromiseIndex, PromiseResult, PublicKey, StorageUsage,
 };
 use crate::{CryptoHash, GasWeight, PromiseError};
 use near_sys as sys;
@@ -26,10 +27,21 @@ const REGISTER_EXPECTED_ERR: &str =
 const ATOMIC_OP_REGISTER: u64 = u64::MAX - 2;
 /// Register used to record evicted values from the storage.
 const EVICTED_REGISTER: u64 = u64::MAX - 1;
+/// Register used to read the values of [`StorageScan`], whose keys are read from
+/// `ATOMIC_OP_REGISTER`.
+#[cfg(all(
+    feature = "legacy-storage-iterators",
+    not(all(not(target_arch = "wasm32"), feature = "unit-testing"))
+))]
+const STORAGE_SCAN_REGISTER: u64 = u64::MAX - 3;
 
 /// Key used to store the state of the contract.
 const STATE_KEY: &[u8] = b"STATE";
 
+/// Storage used by an account without its contract, keys and data, `num_bytes_account` in the
+/// runtime config.
+const ACCOUNT_STORAGE_USAGE: StorageUsage = 100;
+
 /// The minimum length of a valid account ID.
 const MIN_ACCOUNT_ID_LEN: u64 = 2;
 /// The maximum length of a valid account ID.
@@ -230,6 +242,46 @@ pub fn input() -> Option<Vec<u8>> {
     try_method_into_register!(input)
 }
 
+/// Calls `f` with the input to the contract call, like [`input`], but borrowed from a buffer
+/// reused across calls rather than returned as an owned copy. The input is `None` if it is not
+/// provided.
+///
+/// The code generated by `#[near]` deserializes the arguments of the methods from it. The input
+/// is read again on each call, so that it follows the context set by `testing_env!` in unit
+/// tests, but the buffer only grows, so reading inputs no larger than the ones read before doesn't
+/// allocate.
+///
+/// # Examples
+/// ```
+/// use near_sdk::env::input_ref;
+///
+/// assert_eq!(input_ref(|input| input.map(<[u8]>::len)), Some(0));
+/// ```
+pub fn input_ref<R>(f: impl FnOnce(Option<&[u8]>) -> R) -> R {
+    std::thread_local! {
+        static INPUT_BUFFER: std::cell::Cell<Vec<u8>> = const { std::cell::Cell::new(Vec::new()) };
+    }
+
+    unsafe { sys::input(ATOMIC_OP_REGISTER) };
+    let len: usize = match register_len(ATOMIC_OP_REGISTER) {
+        Some(len) => len.try_into().unwrap_or_else(|_| abort()),
+        None => return f(None),
+    };
+    // Taken out of the thread local for the duration of `f`, so that `f` can call `input_ref`
+    // again, which then reads into a new buffer.
+    let mut buffer = INPUT_BUFFER.with(|buffer| buffer.take());
+    buffer.clear();
+    buffer.reserve(len);
+    //* SAFETY: The buffer has a capacity of at least the length of the register read into it.
+    unsafe {
+        sys::read_register(ATOMIC_OP_REGISTER, buffer.as_mut_ptr() as u64);
+        buffer.set_len(len);
+    }
+    let result = f(Some(&buffer));
+    INPUT_BUFFER.with(|cell| cell.set(buffer));
+    result
+}
+
 /// Current block index.
 ///
 /// # Examples
@@ -379,6 +431,70 @@ pub fn used_gas() -> Gas {
     Gas::from_gas(unsafe { sys::used_gas() })
 }
 
+/// Whether the checkpoints of [`gas_checkpoint`] are recorded.
+const GAS_PROFILING: bool = cfg!(any(
+    feature = "gas-profiling",
+    all(feature = "unit-testing", not(target_arch = "wasm32"))
+));
+
+std::thread_local! {
+    /// Labels and used gas of the checkpoints recorded by [`gas_checkpoint`].
+    static GAS_CHECKPOINTS: std::cell::RefCell<Vec<(String, Gas)>> = const {
+        std::cell::RefCell::new(Vec::new())
+    };
+}
+
+/// Records the gas used so far under `label`, to be reported by [`gas_report`].
+///
+/// This is a no-op on-chain unless the `gas-profiling` feature is enabled, so the checkpoints can
+/// be left in the code of hot methods. They are always recorded in unit tests. The bookkeeping
+/// burns a little gas, which is included in the reported gas.
+pub fn gas_checkpoint(label: &str) {
+    if !GAS_PROFILING {
+        return;
+    }
+    let used_gas = used_gas();
+    GAS_CHECKPOINTS.with(|checkpoints| {
+        checkpoints.borrow_mut().push((label.to_string(), used_gas));
+    });
+}
+
+/// Logs and returns the gas used between the checkpoints recorded by [`gas_checkpoint`], i.e.
+/// for each checkpoint, the gas used since the previous one, or since the start of the call for
+/// the first one, and clears the checkpoints.
+///
+/// This is a no-op returning no checkpoints on-chain unless the `gas-profiling` feature is
+/// enabled.
+///
+/// # Examples
+/// ```
+/// use near_sdk::env;
+///
+/// env::gas_checkpoint("start");
+/// env::sha256_array(b"data");
+/// env::gas_checkpoint("hash");
+///
+/// let report = env::gas_report();
+/// assert_eq!(report[1].0, "hash");
+/// assert!(report[1].1.as_gas() > 0);
+/// ```
+pub fn gas_report() -> Vec<(String, Gas)> {
+    if !GAS_PROFILING {
+        return Vec::new();
+    }
+    let checkpoints = GAS_CHECKPOINTS.with(|checkpoints| checkpoints.take());
+    let mut previous = Gas::from_gas(0);
+    checkpoints
+        .into_iter()
+        .map(|(label, used_gas)| {
+            let delta = used_gas.saturating_sub(previous);
+            previous = used_gas;
+            log_str(&format!("gas_report: {}: {} gas", label, delta.as_gas()));
+            (label, delta)
+        })
+        .collect()
+}
+
 // ############
 // # Math API #
 // ############
@@ -688,6 +804,124 @@ pub fn ed25519_verify(signature: &[u8; 64], message: &[u8], public_key: &[u8; 32
     }
 }
 
+/// Recovers the secp256k1 public key which signed the 32-byte message `hash` with `signature`
+/// and the recovery byte `v`, which must be in `0..=3`.
+///
+/// With `malleability_flag`, signatures whose `s` value is in the upper half of the curve order
+/// are rejected, so that the same message can't have two valid signatures, as required for
+/// transactions.
+///
+/// Unlike [`ecrecover`], this doesn't require the `unstable` feature. In unit tests, with the
+/// `unit-testing` feature, the signature is recovered by the software implementation of the
+/// protocol, so that the signature logic of a contract can be tested off-chain.
+///
+/// # Examples
+/// ```
+/// use near_sdk::{env, CurveType, EcrecoverError};
+///
+/// let hash = [1; 32];
+/// let signature = [0; 64];
+/// assert_eq!(
+///     env::secp256k1_recover(&hash, &signature, 4, false),
+///     Err(EcrecoverError::InvalidRecoveryId(4))
+/// );
+/// assert_eq!(
+///     env::secp256k1_recover(&hash, &signature, 0, false),
+///     Err(EcrecoverError::RecoveryFailed)
+/// );
+/// ```
+pub fn secp256k1_recover(
+    hash: &[u8; 32],
+    signature: &[u8; 64],
+    v: u8,
+    malleability_flag: bool,
+) -> Result<PublicKey, EcrecoverError> {
+    if v > 3 {
+        return Err(EcrecoverError::InvalidRecoveryId(v));
+    }
+    //* SAFETY: ecrecover writes the 64 bytes of the public key into the atomic op register when
+    //*         it returns 1, so the read has a sufficient buffer of 64.
+    let key = unsafe {
+        let return_code = sys::ecrecover(
+            hash.len() as _,
+            hash.as_ptr() as _,
+            signature.len() as _,
+            signature.as_ptr() as _,
+            v as u64,
+            malleability_flag as u64,
+            ATOMIC_OP_REGISTER,
+        );
+        if return_code == 0 {
+            return Err(EcrecoverError::RecoveryFailed);
+        }
+        read_register_fixed_64(ATOMIC_OP_REGISTER)
+    };
+    PublicKey::from_parts(CurveType::SECP256K1, key.to_vec()).map_err(|_| abort())
+}
+
+/// Recovers the Ethereum address which signed the 32-byte message `hash` with a 65-byte
+/// `signature`, as produced by Ethereum wallets: the `r` and `s` values followed by the recovery
+/// byte `v`, which can be `0`, `1`, `27` or `28`.
+///
+/// The address is the last 20 bytes of the Keccak-256 hash of the recovered public key. Malleable
+/// signatures are rejected, see [`secp256k1_recover`].
+pub fn eth_recover_address(
+    hash: &[u8; 32],
+    signature: &[u8; 65],
+) -> Result<[u8; 20], EcrecoverError> {
+    let v = match signature[64] {
+        v @ (0 | 1) => v,
+        v @ (27 | 28) => v - 27,
+        v => return Err(EcrecoverError::InvalidRecoveryId(v)),
+    };
+    let (rs, _) = signature.split_at(64);
+    let rs: &[u8; 64] = rs.try_into().unwrap_or_else(|_| abort());
+    let public_key = secp256k1_recover(hash, rs, v, true)?;
+    let hash = keccak256_array(&public_key.as_bytes()[1..]);
+    let mut address = [0; 20];
+    address.copy_from_slice(&hash[12..]);
+    Ok(address)
+}
+
+/// Verifies that `signature` is the ed25519 signature of `message` by `public_key`.
+///
+/// Unlike [`ed25519_verify`], this takes a [`PublicKey`], e.g. [`signer_account_pk`], and
+/// returns why the verification failed. In unit tests, with the `unit-testing` feature, the
+/// signature is verified by the software implementation of the protocol.
+///
+/// # Examples
+/// ```
+/// use near_sdk::{env, Ed25519VerifyError, PublicKey};
+///
+/// let public_key: PublicKey = "ed25519:BWJZuKWeVvgtkiAk5Vwjoit55YfoFRya4GmX4ZzGj1SS".parse().unwrap();
+/// let signature: [u8; 64] = hex::decode("41C44494DAB13009BE73D2CCBD3A49677DDC1F26AD2823CE72833CE4B9603F77CA70A9E179272D92D28E8B2AE7006747C87AB1890362A50347EFF553F5EC4008")
+///     .unwrap()
+///     .try_into()
+///     .unwrap();
+///
+/// assert_eq!(env::verify_ed25519(&signature, b"Hello world!", &public_key), Ok(()));
+/// assert_eq!(
+///     env::verify_ed25519(&signature, b"Modified message!", &public_key),
+///     Err(Ed25519VerifyError::InvalidSignature)
+/// );
+/// ```
+pub fn verify_ed25519(
+    signature: &[u8; 64],
+    message: &[u8],
+    public_key: &PublicKey,
+) -> Result<(), Ed25519VerifyError> {
+    let curve_type = public_key.curve_type();
+    if curve_type != CurveType::ED25519 {
+        return Err(Ed25519VerifyError::UnsupportedKey(curve_type));
+    }
+    let key: &[u8; 32] = public_key.as_bytes()[1..].try_into().unwrap_or_else(|_| abort());
+    if ed25519_verify(signature, message, key) {
+        Ok(())
+    } else {
+        Err(Ed25519VerifyError::InvalidSignature)
+    }
+}
+
 /// Compute alt_bn128 g1 multiexp.
 ///
 /// `alt_bn128` is a specific curve from the Barreto-Naehrig(BN) family. It is particularly
@@ -1383,6 +1617,101 @@ pub fn promise_batch_action_delete_account(
     }
 }
 
+/// Attach promise action that deploys the global contract `code`, identified by its hash, to the
+/// NEAR promise index with the provided promise index. Accounts can then use the contract with
+/// [`promise_batch_action_use_global_contract`] without storing the code themselves.
+///
+/// More info about batching [here](crate::env::promise_batch_create)
+/// # Examples
+/// ```no_run
+/// use near_sdk::env::{promise_batch_action_deploy_global_contract, promise_batch_create};
+/// use near_sdk::AccountId;
+/// use std::str::FromStr;
+///
+/// let promise = promise_batch_create(
+///     &AccountId::from_str("contract.near").unwrap()
+/// );
+///
+/// let code = [0; 1487];
+/// promise_batch_action_deploy_global_contract(promise, &code);
+/// ```
+pub fn promise_batch_action_deploy_global_contract(promise_index: PromiseIndex, code: &[u8]) {
+    unsafe {
+        sys::promise_batch_action_deploy_global_contract(
+            promise_index.0,
+            code.len() as _,
+            code.as_ptr() as _,
+        )
+    }
+}
+
+/// Attach promise action that deploys the global contract `code`, identified by the account the
+/// promise acts on, to the NEAR promise index with the provided promise index. Unlike a contract
+/// identified by its hash, the account can upgrade the contract of the accounts using it by
+/// deploying a new version.
+///
+/// More info about batching [here](crate::env::promise_batch_create)
+pub fn promise_batch_action_deploy_global_contract_by_account_id(
+    promise_index: PromiseIndex,
+    code: &[u8],
+) {
+    unsafe {
+        sys::promise_batch_action_deploy_global_contract_by_account_id(
+            promise_index.0,
+            code.len() as _,
+            code.as_ptr() as _,
+        )
+    }
+}
+
+/// Attach promise action that sets the contract of the account to the global contract with the
+/// hash `code_hash` to the NEAR promise index with the provided promise index.
+///
+/// More info about batching [here](crate::env::promise_batch_create)
+/// # Examples
+/// ```no_run
+/// use near_sdk::env::{promise_batch_action_use_global_contract, promise_batch_create};
+/// use near_sdk::{AccountId, CryptoHash};
+/// use std::str::FromStr;
+///
+/// let promise = promise_batch_create(
+///     &AccountId::from_str("instance.contract.near").unwrap()
+/// );
+///
+/// let code_hash = CryptoHash([0; 32]);
+/// promise_batch_action_use_global_contract(promise, &code_hash);
+/// ```
+pub fn promise_batch_action_use_global_contract(
+    promise_index: PromiseIndex,
+    code_hash: &CryptoHash,
+) {
+    unsafe {
+        sys::promise_batch_action_use_global_contract(
+            promise_index.0,
+            code_hash.0.len() as _,
+            code_hash.0.as_ptr() as _,
+        )
+    }
+}
+
+/// Attach promise action that sets the contract of the account to the global contract deployed
+/// by `account_id` to the NEAR promise index with the provided promise index.
+///
+/// More info about batching [here](crate::env::promise_batch_create)
+pub fn promise_batch_action_use_global_contract_by_account_id(
+    promise_index: PromiseIndex,
+    account_id: &AccountId,
+) {
+    let account_id: &str = account_id.as_ref();
+    unsafe {
+        sys::promise_batch_action_use_global_contract_by_account_id(
+            promise_index.0,
+            account_id.len() as _,
+            account_id.as_ptr() as _,
+        )
+    }
+}
+
 /// If the current function is invoked by a callback we can access the execution results of the
 /// promises that caused the callback. This function returns the number of complete and
 /// incomplete callbacks.
@@ -1435,6 +1764,66 @@ pub fn promise_result(result_idx: u64) -> PromiseResult {
     }
 }
 
+/// Returns the results of the promises that caused the callback, see [`promise_result`].
+///
+/// # Examples
+/// ```no_run
+/// use near_sdk::env::{log_str, promise_results};
+/// use near_sdk::PromiseResult;
+///
+/// let failed = promise_results().filter(|result| matches!(result, PromiseResult::Failed)).count();
+/// log_str(&format!("{failed} promises failed"));
+/// ```
+pub fn promise_results() -> impl Iterator<Item = PromiseResult> {
+    (0..promise_results_count()).map(promise_result)
+}
+
+/// Returns the result of the promise `result_idx` that caused the callback, deserialized from
+/// JSON, or [`PromiseError::Failed`] if the promise failed. An empty result, returned by functions
+/// without a return value, is deserialized as `null`, e.g. as `()`.
+///
+/// This is the result of a `#[callback_result]` argument, use [`promise_result_as_borsh`] for
+/// results serialized with Borsh, i.e. of a `#[callback_result] #[serializer(borsh)]` argument.
+///
+/// # Panics
+///
+/// Panics if the result cannot be deserialized as `T`.
+///
+/// # Examples
+/// ```no_run
+/// use near_sdk::env::{promise_result_as, promise_results_count};
+/// use near_sdk::json_types::U128;
+///
+/// let total: u128 = (0..promise_results_count())
+///     .filter_map(|i| promise_result_as::<U128>(i).ok())
+///     .map(|balance| balance.0)
+///     .sum();
+/// ```
+pub fn promise_result_as<T: serde::de::DeserializeOwned>(
+    result_idx: u64,
+) -> Result<T, PromiseError> {
+    promise_result_internal(result_idx)?;
+    let data = expect_register(read_register(ATOMIC_OP_REGISTER));
+    let data = if data.is_empty() { &b"null"[..] } else { &data };
+    Ok(serde_json::from_slice(data)
+        .unwrap_or_else(|_| panic_str("Failed to deserialize callback using JSON")))
+}
+
+/// Returns the result of the promise `result_idx` that caused the callback, deserialized with
+/// Borsh, or [`PromiseError::Failed`] if the promise failed, see [`promise_result_as`].
+///
+/// # Panics
+///
+/// Panics if the result cannot be deserialized as `T`.
+pub fn promise_result_as_borsh<T: borsh::BorshDeserialize>(
+    result_idx: u64,
+) -> Result<T, PromiseError> {
+    promise_result_internal(result_idx)?;
+    let data = expect_register(read_register(ATOMIC_OP_REGISTER));
+    Ok(T::try_from_slice(&data)
+        .unwrap_or_else(|_| panic_str("Failed to deserialize callback using Borsh")))
+}
+
 pub(crate) fn promise_result_internal(result_idx: u64) -> Result<(), PromiseError> {
     match unsafe { sys::promise_result(result_idx, ATOMIC_OP_REGISTER) } {
         1 => Ok(()),
@@ -1588,8 +1977,8 @@ pub fn promise_yield_create(
 pub fn promise_yield_resume(data_id: &CryptoHash, data: &[u8]) -> bool {
     unsafe {
         sys::promise_yield_resume(
-            data_id.len() as _,
-            data_id.as_ptr() as _,
+            data_id.0.len() as _,
+            data_id.0.as_ptr() as _,
             data.len() as _,
             data.as_ptr() as _,
         ) != 0
@@ -1640,6 +2029,35 @@ pub fn validator_total_stake() -> NearToken {
     NearToken::from_yoctonear(u128::from_le_bytes(data))
 }
 
+/// Whether `account_id` is a validator of the current epoch, i.e. has a stake in it.
+///
+/// # Examples
+/// ```
+/// use near_sdk::env::is_current_validator;
+/// use near_sdk::AccountId;
+///
+/// assert!(!is_current_validator(&"bob.near".parse::<AccountId>().unwrap()));
+/// ```
+pub fn is_current_validator(account_id: &AccountId) -> bool {
+    validator_stake(account_id).as_yoctonear() != 0
+}
+
+/// Returns the height and the total stake of the current epoch.
+///
+/// # Examples
+/// ```
+/// use near_sdk::env::epoch_info;
+/// use near_sdk::{EpochInfo, NearToken};
+///
+/// assert_eq!(
+///     epoch_info(),
+///     EpochInfo { epoch_height: 0, total_stake: NearToken::from_yoctonear(0) }
+/// );
+/// ```
+pub fn epoch_info() -> EpochInfo {
+    EpochInfo { epoch_height: epoch_height(), total_stake: validator_total_stake() }
+}
+
 // #####################
 // # Miscellaneous API #
 // #####################
@@ -1700,6 +2118,42 @@ pub fn panic_str(message: &str) -> ! {
     unsafe { sys::panic_utf8(message.len() as _, message.as_ptr() as _) }
 }
 
+/// Terminates the execution of the program with the code and the message of `error` as a JSON
+/// payload, `{"code":"<error_code>","message":"<message>"}`, so that front-ends can match on the
+/// code, see [`ErrorCode`].
+///
+/// # Examples
+/// ```should_panic
+/// use near_sdk::{env, ErrorCode};
+///
+/// struct Unauthorized;
+///
+/// impl std::fmt::Display for Unauthorized {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "Only the owner can call this method")
+///     }
+/// }
+///
+/// impl ErrorCode for Unauthorized {
+///     fn error_code(&self) -> &'static str {
+///         "UNAUTHORIZED"
+///     }
+/// }
+///
+/// env::panic_error(&Unauthorized);
+/// ```
+pub fn panic_error<E: ErrorCode + ?Sized>(error: &E) -> ! {
+    #[derive(serde::Serialize)]
+    struct ErrorPayload<'a> {
+        code: &'a str,
+        message: &'a str,
+    }
+
+    let message = error.to_string();
+    let payload = ErrorPayload { code: error.error_code(), message: &message };
+    panic_str(&serde_json::to_string(&payload).unwrap_or_else(|_| abort()))
+}
+
 /// Aborts the current contract execution without a custom message.
 /// To include a message, use [`panic_str`].
 ///
@@ -1781,6 +2235,8 @@ pub fn log(message: &[u8]) {
 /// ```
 /// Example of usage [here](https://github.com/near/near-sdk-rs/blob/189897180649bce47aefa4e5af03664ee525508d/near-contract-standards/src/upgrade/mod.rs#L63)
 pub fn storage_write(key: &[u8], value: &[u8]) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    crate::__private::assert_not_in_view();
     match unsafe {
         sys::storage_write(
             key.len() as _,
@@ -1833,6 +2289,8 @@ pub fn storage_read(key: &[u8]) -> Option<Vec<u8>> {
 /// ```
 /// Example of usage [here](https://github.com/near/near-sdk-rs/blob/189897180649bce47aefa4e5af03664ee525508d/near-contract-standards/src/upgrade/mod.rs#L79)
 pub fn storage_remove(key: &[u8]) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    crate::__private::assert_not_in_view();
     match unsafe { sys::storage_remove(key.len() as _, key.as_ptr() as _, EVICTED_REGISTER) } {
         0 => false,
         1 => true,
@@ -1876,6 +2334,233 @@ pub fn storage_has_key(key: &[u8]) -> bool {
     }
 }
 
+/// Returns an iterator over the keys of `keys` which start with `prefix` and exist in the
+/// storage, with their values, e.g. to migrate or clean up state which no longer matches a
+/// collection.
+///
+/// The runtime doesn't let contracts iterate over their storage, so the keys are listed
+/// off-chain, with the `view_state` RPC query and its `prefix_base64`, and passed to the method
+/// migrating them. Keys which were removed since they were listed are skipped.
+///
+/// # Examples
+/// ```
+/// use near_sdk::env;
+///
+/// env::storage_write(b"user:alice", b"1");
+///
+/// // e.g. listed with the `view_state` RPC query and passed to the migration method.
+/// let keys = vec![b"user:alice".to_vec(), b"user:bob".to_vec(), b"config".to_vec()];
+/// let entries: Vec<_> = env::storage_scan_keys(b"user:", keys).collect();
+/// assert_eq!(entries, [(b"user:alice".to_vec(), b"1".to_vec())]);
+/// ```
+pub fn storage_scan_keys<'a, I>(
+    prefix: &'a [u8],
+    keys: I,
+) -> impl Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a
+where
+    I: IntoIterator<Item = Vec<u8>>,
+    I::IntoIter: 'a,
+{
+    keys.into_iter()
+        .filter(move |key| key.starts_with(prefix))
+        .filter_map(|key| storage_read(&key).map(|value| (key, value)))
+}
+
+/// Returns an iterator over the keys and values of the storage starting with `prefix`, in the
+/// order of the keys, with the storage iterators of the runtime.
+///
+/// The storage iterators were removed from the runtime, and contracts importing them fail to
+/// deploy on current protocol versions, so this is only available with the
+/// `legacy-storage-iterators` feature, for networks still running an older protocol. Use
+/// [`storage_scan_keys`] otherwise. In unit tests, the keys of the mocked storage at the time of
+/// the call are iterated.
+///
+/// Writing or removing keys while iterating invalidates the iterator on-chain, so the keys to
+/// remove should be collected first.
+///
+/// # Examples
+/// ```
+/// use near_sdk::env;
+///
+/// env::storage_write(b"user:alice", b"1");
+/// env::storage_write(b"user:bob", b"2");
+/// env::storage_write(b"config", b"3");
+///
+/// let keys: Vec<Vec<u8>> = env::storage_scan(b"user:").map(|(key, _)| key).collect();
+/// assert_eq!(keys, [b"user:alice".to_vec(), b"user:bob".to_vec()]);
+/// for key in keys {
+///     env::storage_remove(&key);
+/// }
+/// ```
+#[cfg(feature = "legacy-storage-iterators")]
+pub fn storage_scan(prefix: &[u8]) -> StorageScan {
+    #[cfg(all(not(target_arch = "wasm32"), feature = "unit-testing"))]
+    {
+        let mut entries: Vec<(Vec<u8>, Vec<u8>)> = crate::mock::with_mocked_blockchain(|b| {
+            b.storage()
+                .iter()
+                .filter(|(key, _)| key.starts_with(prefix))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        });
+        entries.sort();
+        StorageScan { entries: entries.into_iter() }
+    }
+    #[cfg(not(all(not(target_arch = "wasm32"), feature = "unit-testing")))]
+    StorageScan {
+        iterator_id: unsafe { sys::storage_iter_prefix(prefix.len() as _, prefix.as_ptr() as _) },
+    }
+}
+
+/// Iterator over the keys and values of the storage starting with a prefix, returned by
+/// [`storage_scan`].
+#[cfg(feature = "legacy-storage-iterators")]
+#[derive(Debug)]
+pub struct StorageScan {
+    #[cfg(all(not(target_arch = "wasm32"), feature = "unit-testing"))]
+    entries: std::vec::IntoIter<(Vec<u8>, Vec<u8>)>,
+    #[cfg(not(all(not(target_arch = "wasm32"), feature = "unit-testing")))]
+    iterator_id: u64,
+}
+
+#[cfg(feature = "legacy-storage-iterators")]
+impl Iterator for StorageScan {
+    type Item = (Vec<u8>, Vec<u8>);
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "unit-testing"))]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next()
+    }
+
+    #[cfg(not(all(not(target_arch = "wasm32"), feature = "unit-testing")))]
+    fn next(&mut self) -> Option<Self::Item> {
+        match unsafe {
+            sys::storage_iter_next(self.iterator_id, ATOMIC_OP_REGISTER, STORAGE_SCAN_REGISTER)
+        } {
+            0 => None,
+            1 => Some((
+                expect_register(read_register(ATOMIC_OP_REGISTER)),
+                expect_register(read_register(STORAGE_SCAN_REGISTER)),
+            )),
+            _ => abort(),
+        }
+    }
+}
+
+/// Returns an empty [`StorageBatch`], to stage writes and removals of keys and commit them at
+/// once.
+///
+/// # Examples
+/// ```
+/// use near_sdk::env;
+///
+/// env::storage_write(b"old", b"value");
+/// env::storage_batch().write(b"a", b"1").remove(b"old").commit();
+/// assert_eq!(env::storage_read(b"a").unwrap(), b"1");
+/// assert!(!env::storage_has_key(b"old"));
+/// ```
+pub fn storage_batch() -> StorageBatch {
+    StorageBatch::default()
+}
+
+/// Writes and removals of keys staged in memory, committed to the storage by
+/// [`commit`](StorageBatch::commit), created with [`storage_batch`].
+///
+/// The staged keys and values are appended to a single buffer rather than allocated one by one,
+/// and values can be serialized directly into it with [`write_with`](StorageBatch::write_with).
+/// Only the last operation staged for a key is committed, so a key written or removed several
+/// times costs a single host call, and the values evicted by the commit all go to the same
+/// register without being read back. The buffer is kept by the batch, so a batch reused across
+/// commits doesn't allocate again. Collections of [`store`](crate::store) flush their modified
+/// entries through a batch.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[must_use = "the staged operations are only applied by `commit`"]
+pub struct StorageBatch {
+    /// Keys and values of the staged operations, one after the other.
+    data: Vec<u8>,
+    /// Staged operations: the range of the key in `data`, and the range of the value to write or
+    /// `None` to remove the key.
+    operations: Vec<(std::ops::Range<usize>, Option<std::ops::Range<usize>>)>,
+}
+
+impl StorageBatch {
+    /// Stages the write of `value` under `key`, replacing the operation staged for `key`.
+    pub fn write(&mut self, key: &[u8], value: &[u8]) -> &mut Self {
+        self.write_with(key, |buffer| buffer.extend_from_slice(value))
+    }
+
+    /// Stages the write under `key` of the value appended by `serialize` to the buffer it is
+    /// given, replacing the operation staged for `key`. `serialize` must only append to the
+    /// buffer.
+    pub fn write_with(&mut self, key: &[u8], serialize: impl FnOnce(&mut Vec<u8>)) -> &mut Self {
+        let key = self.push_key(key);
+        serialize(&mut self.data);
+        self.operations.push((key.clone(), Some(key.end..self.data.len())));
+        self
+    }
+
+    /// Stages the removal of `key`, replacing the operation staged for `key`.
+    pub fn remove(&mut self, key: &[u8]) -> &mut Self {
+        let key = self.push_key(key);
+        self.operations.push((key, None));
+        self
+    }
+
+    /// Returns `true` if no operation is staged.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    fn push_key(&mut self, key: &[u8]) -> std::ops::Range<usize> {
+        let start = self.data.len();
+        self.data.extend_from_slice(key);
+        start..self.data.len()
+    }
+
+    /// Applies the staged operations to the storage, in the order of their keys, leaving the
+    /// batch empty.
+    pub fn commit(&mut self) {
+        if self.operations.is_empty() {
+            return;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        crate::__private::assert_not_in_view();
+
+        let data = &self.data;
+        // The sort is stable, so the last operation staged for a key is the last of its run.
+        self.operations.sort_by(|(a, _), (b, _)| data[a.clone()].cmp(&data[b.clone()]));
+        for (index, (key, value)) in self.operations.iter().enumerate() {
+            let key = &data[key.clone()];
+            if matches!(self.operations.get(index + 1), Some((next, _)) if &data[next.clone()] == key)
+            {
+                continue;
+            }
+            let result = match value {
+                Some(value) => {
+                    let value = &data[value.clone()];
+                    unsafe {
+                        sys::storage_write(
+                            key.len() as _,
+                            key.as_ptr() as _,
+                            value.len() as _,
+                            value.as_ptr() as _,
+                            EVICTED_REGISTER,
+                        )
+                    }
+                }
+                None => unsafe {
+                    sys::storage_remove(key.len() as _, key.as_ptr() as _, EVICTED_REGISTER)
+                },
+            };
+            if result > 1 {
+                abort();
+            }
+        }
+        self.operations.clear();
+        self.data.clear();
+    }
+}
+
 // ############################################
 // # Saving and loading of the contract state #
 // ############################################
@@ -1919,6 +2604,34 @@ pub fn storage_byte_cost() -> NearToken {
     NearToken::from_yoctonear(10_000_000_000_000_000_000u128)
 }
 
+/// Returns the balance an account needs to stake for `storage_usage` bytes of storage, at the
+/// price of [`storage_byte_cost`].
+///
+/// # Examples
+/// ```
+/// use near_sdk::env::storage_cost;
+/// use near_sdk::NearToken;
+///
+/// assert_eq!(storage_cost(100), NearToken::from_millinear(1));
+/// ```
+pub fn storage_cost(storage_usage: StorageUsage) -> NearToken {
+    storage_byte_cost().saturating_mul(storage_usage.into())
+}
+
+/// Returns the minimum balance of a new account with a contract of `code_len` bytes and
+/// `state_usage` bytes of data, i.e. the cost of the storage of the account, its contract and its
+/// data. The storage of access keys is not included.
+///
+/// # Examples
+/// ```
+/// use near_sdk::env::{account_min_balance, storage_cost};
+///
+/// assert_eq!(account_min_balance(1000, 0), storage_cost(1100));
+/// ```
+pub fn account_min_balance(code_len: u64, state_usage: StorageUsage) -> NearToken {
+    storage_cost(ACCOUNT_STORAGE_USAGE.saturating_add(code_len).saturating_add(state_usage))
+}
+
 // ##################
 // # Helper methods #
 // ##################
@@ -2090,46 +2803,209 @@ mod tests {
         assert_eq!(super::random_seed(), [8; 32]);
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    #[should_panic(expected = r#"{"code":"NOT_FOUND","message":"\"key\" not found"}"#)]
+    fn panic_error() {
+        struct NotFound;
+
+        impl std::fmt::Display for NotFound {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "\"key\" not found")
+            }
+        }
+
+        impl ErrorCode for NotFound {
+            fn error_code(&self) -> &'static str {
+                "NOT_FOUND"
+            }
+        }
+
+        super::panic_error(&NotFound);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn validators() {
+        use crate::test_utils::{accounts, VMContextBuilder};
+        use crate::{test_vm_config, testing_env, RuntimeFeesConfig};
+
+        testing_env!(
+            VMContextBuilder::new().epoch_height(7).build(),
+            test_vm_config(),
+            RuntimeFeesConfig::test(),
+            [
+                (accounts(0).to_string(), NearToken::from_near(10)),
+                (accounts(1).to_string(), NearToken::from_near(30)),
+            ]
+            .into(),
+        );
+        assert!(super::is_current_validator(&accounts(0)));
+        assert!(!super::is_current_validator(&accounts(2)));
+        assert_eq!(
+            super::epoch_info(),
+            EpochInfo { epoch_height: 7, total_stake: NearToken::from_near(40) }
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn storage_batch() {
+        use crate::test_utils::test_env;
+
+        test_env::setup_free();
+        super::storage_write(b"b", b"old");
+        super::storage_write(b"c", b"old");
+        let mut batch = super::storage_batch();
+        batch
+            .write(b"a", b"1")
+            .remove(b"a")
+            .write(b"b", b"2")
+            .write_with(b"b", |buffer| buffer.extend_from_slice(b"3"))
+            .remove(b"c")
+            .write(b"c", b"4");
+        assert_eq!(super::storage_read(b"b").unwrap(), b"old");
+
+        batch.commit();
+        assert!(batch.is_empty());
+        assert!(!super::storage_has_key(b"a"));
+        assert_eq!(super::storage_read(b"b").unwrap(), b"3");
+        assert_eq!(super::storage_read(b"c").unwrap(), b"4");
+
+        batch.write(b"d", b"5").commit();
+        assert_eq!(super::storage_read(b"d").unwrap(), b"5");
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn gas_report() {
+        use crate::test_utils::{get_logs, VMContextBuilder};
+        use crate::testing_env;
+
+        testing_env!(VMContextBuilder::new().build());
+        super::gas_checkpoint("start");
+        super::sha256_array(b"data");
+        super::gas_checkpoint("hash");
+
+        let report = super::gas_report();
+        assert_eq!(
+            report.iter().map(|(label, _)| label.as_str()).collect::<Vec<_>>(),
+            ["start", "hash"]
+        );
+        assert!(report[1].1 > Gas::from_gas(0));
+        assert_eq!(
+            get_logs(),
+            report
+                .iter()
+                .map(|(label, gas)| format!("gas_report: {}: {} gas", label, gas.as_gas()))
+                .collect::<Vec<_>>()
+        );
+        assert!(super::gas_report().is_empty());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[derive(serde::Deserialize)]
+    struct EcrecoverTest {
+        #[serde(with = "hex::serde")]
+        m: [u8; 32],
+        v: u8,
+        #[serde(with = "hex::serde")]
+        sig: [u8; 64],
+        mc: bool,
+        #[serde(deserialize_with = "deserialize_option_hex")]
+        res: Option<[u8; 64]>,
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn deserialize_option_hex<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: hex::FromHex,
+        <T as hex::FromHex>::Error: std::fmt::Display,
+    {
+        use serde::de::Error;
+        serde::Deserialize::deserialize(deserializer)
+            .map(|v: Option<&str>| v.map(hex::FromHex::from_hex).transpose().map_err(Error::custom))
+            .and_then(|v| v)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn ecrecover_tests() -> Vec<EcrecoverTest> {
+        serde_json::from_slice(include_bytes!("../../tests/ecrecover-tests.json")).unwrap()
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     #[cfg(feature = "unstable")]
     #[test]
     fn test_ecrecover() {
         use crate::test_utils::test_env;
-        use hex::FromHex;
-        use serde::de::Error;
-        use serde::{Deserialize, Deserializer};
-        use serde_json::from_slice;
-        use std::fmt::Display;
-
-        #[derive(Deserialize)]
-        struct EcrecoverTest {
-            #[serde(with = "hex::serde")]
-            m: [u8; 32],
-            v: u8,
-            #[serde(with = "hex::serde")]
-            sig: [u8; 64],
-            mc: bool,
-            #[serde(deserialize_with = "deserialize_option_hex")]
-            res: Option<[u8; 64]>,
+
+        test_env::setup_free();
+        for EcrecoverTest { m, v, sig, mc, res } in ecrecover_tests() {
+            assert_eq!(super::ecrecover(&m, &sig, v, mc), res);
         }
+    }
 
-        fn deserialize_option_hex<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
-        where
-            D: Deserializer<'de>,
-            T: FromHex,
-            <T as FromHex>::Error: Display,
-        {
-            Deserialize::deserialize(deserializer)
-                .map(|v: Option<&str>| v.map(FromHex::from_hex).transpose().map_err(Error::custom))
-                .and_then(|v| v)
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_secp256k1_recover() {
+        use crate::test_utils::test_env;
+
+        test_env::setup_free();
+        for EcrecoverTest { m, v, sig, mc, res } in ecrecover_tests() {
+            let expected = res
+                .map(|key| PublicKey::from_parts(CurveType::SECP256K1, key.to_vec()).unwrap())
+                .ok_or(EcrecoverError::RecoveryFailed);
+            assert_eq!(super::secp256k1_recover(&m, &sig, v, mc), expected);
         }
+        assert_eq!(
+            super::secp256k1_recover(&[0; 32], &[0; 64], 27, false),
+            Err(EcrecoverError::InvalidRecoveryId(27))
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_eth_recover_address() {
+        use crate::test_utils::test_env;
 
         test_env::setup_free();
-        for EcrecoverTest { m, v, sig, mc, res } in
-            from_slice::<'_, Vec<_>>(include_bytes!("../../tests/ecrecover-tests.json")).unwrap()
-        {
-            assert_eq!(super::ecrecover(&m, &sig, v, mc), res);
+        let EcrecoverTest { m, v, sig, .. } = ecrecover_tests().remove(0);
+        let address: [u8; 20] =
+            hex::decode("a19d069d48d2e9392ec2bb41ecab0a72119d633b").unwrap().try_into().unwrap();
+        let mut signature = [0; 65];
+        signature[..64].copy_from_slice(&sig);
+        for v in [v, v + 27] {
+            signature[64] = v;
+            assert_eq!(super::eth_recover_address(&m, &signature), Ok(address));
         }
+        signature[64] = 2;
+        assert_eq!(
+            super::eth_recover_address(&m, &signature),
+            Err(EcrecoverError::InvalidRecoveryId(2))
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_verify_ed25519() {
+        let public_key: PublicKey =
+            "ed25519:FAe4sisG95oZ42w7buUn5qEE4TAnfTTFPiguZUHmhiF".parse().unwrap();
+        let signature: [u8; 64] = hex::decode("7bc0ea578290c8dcf6fc8a6e134a7f3e794ddd7e8922108bccd6202f95de532b92c2298dc8e161ac2b5e3653f92c5b0e12adf26b3d46e7bd2057715f25d3e205")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(super::verify_ed25519(&signature, b"message", &public_key), Ok(()));
+        assert_eq!(
+            super::verify_ed25519(&signature, b"other message", &public_key),
+            Err(Ed25519VerifyError::InvalidSignature)
+        );
+
+        let secp_key = PublicKey::from_parts(CurveType::SECP256K1, vec![1; 64]).unwrap();
+        assert_eq!(
+            super::verify_ed25519(&signature, b"message", &secp_key),
+            Err(Ed25519VerifyError::UnsupportedKey(CurveType::SECP256K1))
+        );
     }
 
     #[cfg(not(target_arch = "wasm32"))]
@@ -2144,6 +3020,39 @@ mod tests {
         assert_eq!(super::signer_account_pk(), key);
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn promise_results_as() {
+        use crate::json_types::U128;
+
+        crate::testing_env!(
+            crate::test_utils::VMContextBuilder::new().build(),
+            crate::test_vm_config(),
+            near_parameters::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![
+                PromiseResult::Successful(b"\"5\"".to_vec()),
+                PromiseResult::Failed,
+                PromiseResult::Successful(vec![]),
+                PromiseResult::Successful(borsh::to_vec(&7u32).unwrap()),
+            ],
+        );
+        assert_eq!(
+            super::promise_results().collect::<Vec<_>>(),
+            [
+                PromiseResult::Successful(b"\"5\"".to_vec()),
+                PromiseResult::Failed,
+                PromiseResult::Successful(vec![]),
+                PromiseResult::Successful(borsh::to_vec(&7u32).unwrap()),
+            ]
+        );
+        assert_eq!(super::promise_result_as::<U128>(0), Ok(U128(5)));
+        assert_eq!(super::promise_result_as::<U128>(1), Err(PromiseError::Failed));
+        assert_eq!(super::promise_result_as::<()>(2), Ok(()));
+        assert_eq!(super::promise_result_as::<Option<U128>>(2), Ok(None));
+        assert_eq!(super::promise_result_as_borsh::<u32>(3), Ok(7));
+    }
+
     #[test]
     fn ed25519_verify() {
         const SIGNATURE: [u8; 64] = [
@@ -111,6 +111,14 @@ where
         std::mem::take(&mut self.logic_fixture.ext.fake_trie)
     }
 
+    pub(crate) fn storage(&self) -> &HashMap<Vec<u8>, Vec<u8>> {
+        &self.logic_fixture.ext.fake_trie
+    }
+
+    pub(crate) fn set_storage(&mut self, storage: HashMap<Vec<u8>, Vec<u8>>) {
+        self.logic_fixture.ext.fake_trie = storage;
+    }
+
     /// Returns metadata about the receipts created
     pub fn created_receipts(&self) -> Vec<Receipt> {
         let action_log = &self.logic_fixture.ext.action_log;
@@ -547,6 +555,40 @@ mod mock_chain {
             )
         })
     }
+    // Global contracts are not supported by the VM logic of the mocked blockchain, promises using
+    // them can be inspected with `Promise::describe` instead.
+    #[no_mangle]
+    extern "C-unwind" fn promise_batch_action_deploy_global_contract(
+        _promise_index: u64,
+        _code_len: u64,
+        _code_ptr: u64,
+    ) {
+        panic!("Global contracts are not supported by the mocked blockchain")
+    }
+    #[no_mangle]
+    extern "C-unwind" fn promise_batch_action_deploy_global_contract_by_account_id(
+        _promise_index: u64,
+        _code_len: u64,
+        _code_ptr: u64,
+    ) {
+        panic!("Global contracts are not supported by the mocked blockchain")
+    }
+    #[no_mangle]
+    extern "C-unwind" fn promise_batch_action_use_global_contract(
+        _promise_index: u64,
+        _code_hash_len: u64,
+        _code_hash_ptr: u64,
+    ) {
+        panic!("Global contracts are not supported by the mocked blockchain")
+    }
+    #[no_mangle]
+    extern "C-unwind" fn promise_batch_action_use_global_contract_by_account_id(
+        _promise_index: u64,
+        _account_id_len: u64,
+        _account_id_ptr: u64,
+    ) {
+        panic!("Global contracts are not supported by the mocked blockchain")
+    }
     #[no_mangle]
     extern "C-unwind" fn promise_results_count() -> u64 {
         with_mock_interface(|b| b.promise_results_count())
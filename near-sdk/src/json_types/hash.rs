@@ -20,6 +20,18 @@ impl From<CryptoHash> for Base58CryptoHash {
     }
 }
 
+impl From<[u8; 32]> for Base58CryptoHash {
+    fn from(bytes: [u8; 32]) -> Base58CryptoHash {
+        Base58CryptoHash(CryptoHash(bytes))
+    }
+}
+
+impl From<Base58CryptoHash> for [u8; 32] {
+    fn from(v: Base58CryptoHash) -> [u8; 32] {
+        v.0 .0
+    }
+}
+
 impl ser::Serialize for Base58CryptoHash {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -56,7 +68,7 @@ impl schemars::JsonSchema for Base58CryptoHash {
 
 impl From<&Base58CryptoHash> for String {
     fn from(hash: &Base58CryptoHash) -> Self {
-        bs58::encode(&hash.0).into_string()
+        hash.0.to_string()
     }
 }
 
@@ -80,8 +92,8 @@ impl std::str::FromStr for Base58CryptoHash {
     type Err = ParseCryptoHashError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        let mut crypto_hash: CryptoHash = CryptoHash::default();
-        let size = bs58::decode(value).onto(&mut crypto_hash)?;
+        let mut crypto_hash = CryptoHash::default();
+        let size = bs58::decode(value).onto(&mut crypto_hash.0)?;
         if size != std::mem::size_of::<CryptoHash>() {
             return Err(ParseCryptoHashError {
                 kind: ParseCryptoHashErrorKind::InvalidLength(size),
@@ -91,6 +103,7 @@ impl std::str::FromStr for Base58CryptoHash {
     }
 }
 
+/// Error returned when parsing a [`Base58CryptoHash`] or a [`CryptoHash`] from base58.
 #[derive(Debug)]
 pub struct ParseCryptoHashError {
     kind: ParseCryptoHashErrorKind,
@@ -6,7 +6,7 @@ mod vector;
 
 use crate::types::{AccountId, PublicKey};
 
-pub use hash::Base58CryptoHash;
+pub use hash::{Base58CryptoHash, ParseCryptoHashError};
 pub use integers::{I128, I64, U128, U64};
 pub use vector::Base64VecU8;
 
@@ -24,6 +24,18 @@
 //! near-sdk = "5.6.0"
 //! ```
 //!
+//! The optional parts of the SDK are enabled with the following features:
+//!
+//! - `abi`: generation of the contract ABI, used by `cargo near`.
+//! - `unit-testing`: unit tests on a mocked blockchain, with `testing_env!` and `test_utils`.
+//! - `legacy-collections`: `near_sdk::collections`, superseded by [`store`]. Formerly `legacy`,
+//!   which is kept as an alias.
+//! - `legacy-storage-iterators`: `env::storage_scan`, with the storage iterators removed from the
+//!   runtime, for networks still running an older protocol.
+//! - `standards-interop`: everything the components of `near-contract-standards` build on.
+//!
+//! The items most contracts need can be imported at once from the [`prelude`].
+//!
 //! ### Example: Counter Smart Contract. For more information, see the [near] documentation.
 //!
 //! Below is an example of a simple counter contract that increments and retrieves a value:
@@ -123,6 +135,94 @@ extern crate quickcheck;
 /// }
 /// ```
 ///
+/// ## `#[near(contract_state(versioned(...)))]` (annotates structs)
+///
+/// Stores the state with its version, so that a new version of the contract upgrades the state
+/// written by the previous ones when it reads it. The previous versions of the state are listed
+/// from the oldest, and a `{Contract}VersionedState` enum is generated with a variant for each of
+/// them, `V1`, `V2`, ..., and a last one for the current version. Each version has to implement
+/// `From` of the one before it, and the current version `From` of the last one listed; the
+/// compiler reports the missing implementations.
+///
+/// The state is read as any of the versions and converted to the current one, which is written
+/// back at the end of the first call changing it. New versions must only be appended to the list.
+/// The previous versions are serialized with Borsh, as with [`#[near(serializers=[borsh])]`](near#nearserializers-annotates-structsenums).
+/// `#[near(contract_state(versioned))]` starts versioning a new contract; the state of a
+/// deployed contract which was not versioned still has to be migrated with an `#[init(ignore_state)]`
+/// method.
+///
+/// ### Basic example
+/// ```rust
+/// use near_sdk::near;
+///
+/// #[near(serializers=[borsh])]
+/// pub struct ContractV1 {
+///     greeting: String,
+/// }
+///
+/// #[near(contract_state(versioned(ContractV1)))]
+/// pub struct Contract {
+///     greeting: String,
+///     count: u64,
+/// }
+///
+/// impl From<ContractV1> for Contract {
+///     fn from(state: ContractV1) -> Self {
+///         Self { greeting: state.greeting, count: 0 }
+///     }
+/// }
+/// ```
+///
+/// ## `#[near(lazy)]` (annotates fields of structs)
+///
+/// Stores the value of the field under its own key, as [`store::Lazy`](crate::store::Lazy), so that it is only read
+/// and deserialized from storage when it is accessed, rather than with the rest of the state of
+/// the contract, e.g. for large values which most methods don't need. The type `T` of the field
+/// is replaced with `store::Lazy<T>`, with the value under the key `"{Struct}::{field}"`, and the
+/// struct gets accessors for the value of each lazy field, e.g. for `metadata`:
+/// * `lazy_metadata(value)` which creates the field with its key, to construct the struct;
+/// * `metadata()` and `metadata_mut()` which read the value;
+/// * `set_metadata(value)` which replaces the value without reading it.
+///
+/// Changes of the value are written to storage when the struct is dropped, at the end of the
+/// method.
+///
+/// ### Basic example
+/// ```rust
+/// use near_sdk::{near, AccountId};
+///
+/// #[near(serializers=[borsh])]
+/// pub struct Metadata {
+///     description: String,
+///     icon: Vec<u8>,
+/// }
+///
+/// #[near(contract_state)]
+/// pub struct Contract {
+///     owner: AccountId,
+///     #[near(lazy)]
+///     metadata: Metadata,
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     #[init]
+///     pub fn new(owner: AccountId, description: String) -> Self {
+///         let metadata = Metadata { description, icon: vec![] };
+///         Self { owner, metadata: Self::lazy_metadata(metadata) }
+///     }
+///
+///     // Doesn't read the metadata.
+///     pub fn get_owner(&self) -> AccountId {
+///         self.owner.clone()
+///     }
+///
+///     pub fn get_description(&self) -> String {
+///         self.metadata().description.clone()
+///     }
+/// }
+/// ```
+///
 /// ## `#[near(serializers=[...])` (annotates structs/enums)
 ///
 /// The attribute makes the struct or enum serializable with either json or borsh. By default, borsh is used.
@@ -192,6 +292,59 @@ extern crate quickcheck;
 /// }
 /// ```
 ///
+/// Other formats, e.g. CBOR, are named with `#[serializer(with = Type)]`, where `Type` implements
+/// [`ArgsSerializer`](crate::ArgsSerializer).
+///
+/// On the `#[callback_unwrap]`, `#[callback_result]`, `#[callback_join]` and `#[callback_vec]`
+/// arguments of a callback, the attribute names the format of the promise results, i.e. the
+/// `#[result_serializer(...)]` of the called methods, independently of the other arguments:
+///
+/// ```rust
+/// use near_sdk::{near, PromiseError};
+///# #[near(contract_state)]
+///# pub struct Contract {}
+///
+/// #[near]
+/// impl Contract {
+///     #[private]
+///     pub fn on_balance(
+///         &mut self,
+///         #[callback_result]
+///         #[serializer(borsh)]
+///         balance: Result<u128, PromiseError>,
+///         account: String,
+///     ) -> u128 {
+///         let balance = balance.unwrap_or(0);
+///         near_sdk::log!("Balance of {}: {}", account, balance);
+///         balance
+///     }
+/// }
+/// ```
+///
+/// ## `#[arg(default)]` (annotates function arguments)
+///
+/// Makes a JSON argument optional: when it is missing from the input, `Default::default()` of its
+/// type is used, or the value of the expression given with `#[arg(default = "expr")]`. The
+/// expression is evaluated in the scope of the module of the contract. The default value is
+/// included in the schema of the argument in the ABI. Not supported for Borsh or callback
+/// arguments.
+///
+/// ### Basic example
+///
+/// ```rust
+/// use near_sdk::near;
+///# #[near(contract_state)]
+///# pub struct Contract {}
+///
+/// #[near]
+/// impl Contract {
+///     // Can be called with `{}`, which returns the first 10 items.
+///     pub fn items(&self, #[arg(default)] from_index: u64, #[arg(default = "10")] limit: u64) -> Vec<u64> {
+///         (from_index..from_index + limit).collect()
+///     }
+/// }
+/// ```
+///
 /// ## `#[init]` (annotates methods of a type in its `impl` block)
 ///
 /// Contract initialization method annotation. More details can be found [here](https://docs.near.org/build/smart-contracts/anatomy/storage#initializing-the-state)
@@ -222,6 +375,81 @@ extern crate quickcheck;
 /// }
 /// ```
 ///
+/// An init method fails with the [`AlreadyInitialized`] error, of code `ALREADY_INITIALIZED`, when
+/// the state of the contract already exists, unless it is annotated with `#[init(ignore_state)]`.
+/// `#[init(payable)]` is a shorthand for `#[init]` with `#[payable]`, and init methods
+/// returning a `Result<Self, E>` can be annotated with `#[handle_result]` to fail with the error
+/// instead of writing the state.
+///
+/// ### Initializing the contract on its first call
+///
+/// One init method without arguments can be annotated with `#[init(on_deploy)]`, so that the
+/// contract is initialized with it, instead of with `Default::default()`, when one of its methods
+/// is called while its state is missing. The state is then written by the first method taking
+/// `&mut self`. The method can still be called as any other init method.
+///
+/// ```rust
+/// use near_sdk::{env, near, AccountId, PanicOnDefault};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Counter {
+///     owner_id: AccountId,
+///     value: u64,
+/// }
+///
+/// #[near]
+/// impl Counter {
+///     #[init(on_deploy)]
+///     pub fn new() -> Self {
+///         Self { owner_id: env::current_account_id(), value: 0 }
+///     }
+///
+///     pub fn increment(&mut self) {
+///         self.value += 1;
+///     }
+/// }
+/// ```
+///
+/// ### Exporting the init arguments for factories
+///
+/// One init method with JSON arguments can be annotated with `#[init(schema)]`. The contract type
+/// then gets an `INIT_METHOD_NAME` const and an `init_args` function serializing the arguments of
+/// the method, so that a factory depending on the contract crate can deploy it with typed
+/// arguments. With the `abi` feature, `get_init_schema()` also returns the JSON schema of the
+/// arguments.
+///
+/// ```rust
+/// use near_sdk::{near, AccountId, NearToken, PanicOnDefault, Promise};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Counter {
+///     value: u64,
+/// }
+///
+/// #[near]
+/// impl Counter {
+///     #[init(schema)]
+///     pub fn new(value: u64) -> Self {
+///         Self { value }
+///     }
+/// }
+///
+/// fn deploy_counter(account_id: AccountId, code: Vec<u8>) -> Promise {
+///     Promise::new(account_id)
+///         .create_account()
+///         .transfer(NearToken::from_near(1))
+///         .deploy_contract(code)
+///         .function_call(
+///             Counter::INIT_METHOD_NAME.to_string(),
+///             Counter::init_args(5),
+///             NearToken::from_near(0),
+///             near_sdk::Gas::from_tgas(10),
+///         )
+/// }
+/// ```
+///
 /// ## `#[payable]` (annotates methods of a type in its `impl` block)
 ///
 /// Specifies that the method can accept NEAR tokens. More details can be found [here](https://docs.near.org/build/smart-contracts/anatomy/functions#payable-functions)
@@ -250,6 +478,47 @@ extern crate quickcheck;
 /// }
 /// ```
 ///
+/// ### Deposit bounds
+///
+/// `#[payable(min = "...")]`, `#[payable(max = "...")]` and `#[payable(exact = "...")]` reject
+/// calls with a deposit out of bounds. A bound is an amount such as `"0.1 NEAR"`, `"1 milliNEAR"`
+/// or a number of yoctoNEAR, or a `NearToken` expression such as `"Counter::MIN_DEPOSIT"`,
+/// evaluated in the scope of the module of the contract. With `refund_excess`, the deposit above
+/// `exact`, or else `max` or `min`, is transferred back to the predecessor rather than rejected.
+///
+/// A payable method takes the deposit it keeps with a `#[deposit]` argument of type `NearToken`,
+/// which is not part of the input of the method. It is the attached deposit less the excess
+/// refunded with `refund_excess`, so methods with `refund_excess` take their deposit this way and
+/// fail to compile if they call [`env::attached_deposit`].
+///
+/// ```rust
+/// use near_sdk::{near, AccountId, NearToken};
+///
+/// #[near(contract_state)]
+/// #[derive(Default)]
+/// pub struct Counter {
+///     val: i8,
+/// }
+///
+/// impl Counter {
+///     const STORAGE_DEPOSIT: NearToken = NearToken::from_millinear(10);
+/// }
+///
+/// #[near]
+/// impl Counter {
+///     #[payable(exact = 1)]
+///     pub fn transfer(&mut self, receiver_id: AccountId) {
+///        //...
+///     }
+///
+///     #[payable(min = "Counter::STORAGE_DEPOSIT", refund_excess)]
+///     pub fn register(&mut self, #[deposit] deposit: NearToken) {
+///        assert_eq!(deposit, Counter::STORAGE_DEPOSIT);
+///        //...
+///     }
+/// }
+/// ```
+///
 /// ## `#[private]` (annotates methods of a type in its `impl` block)]
 ///
 /// The attribute forbids to call the method except from within the contract.
@@ -277,10 +546,228 @@ extern crate quickcheck;
 /// }
 /// ```
 ///
+/// ### Allowing other callers
+///
+/// `#[private(allow = ["dao.near", "factory.near"])]` also allows the listed accounts to call the
+/// method, e.g. a controller contract, and `#[private(allow_fn = "name")]` the accounts for which
+/// the method `name` of the contract, taking `&self` and the `&AccountId` of the predecessor,
+/// returns `true`, e.g. to look up an allowlist kept in the state. The contract itself is always
+/// allowed.
+///
+/// ```rust
+/// use near_sdk::store::LookupSet;
+/// use near_sdk::{near, AccountId, PanicOnDefault};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Counter {
+///     val: u64,
+///     controllers: LookupSet<AccountId>,
+/// }
+///
+/// #[near]
+/// impl Counter {
+///     #[private(allow = ["dao.near"])]
+///     pub fn reset(&mut self) {
+///         self.val = 0;
+///     }
+///
+///     #[private(allow_fn = "is_controller")]
+///     pub fn set(&mut self, val: u64) {
+///         self.val = val;
+///     }
+/// }
+///
+/// impl Counter {
+///     fn is_controller(&self, account_id: &AccountId) -> bool {
+///         self.controllers.contains(account_id)
+///     }
+/// }
+/// ```
+///
+/// ## `#[only(...)]` (annotates methods of a type in its `impl` block)
+///
+/// `#[only(role = "admin")]` restricts a call method to the accounts having the role. The role
+/// of the predecessor is checked once the state is read, through the [`HasRoles`] implementation
+/// of the contract, and the call fails otherwise. Roles are usually kept by the `AccessControl`
+/// component of `near_contract_standards::access_control`.
+///
+/// ### Basic example
+///
+/// ```rust
+/// use near_sdk::store::LookupSet;
+/// use near_sdk::{near, AccountId, HasRoles, PanicOnDefault};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Counter {
+///     val: u64,
+///     admins: LookupSet<AccountId>,
+/// }
+///
+/// impl HasRoles for Counter {
+///     fn has_role(&self, role: &str, account_id: &AccountId) -> bool {
+///         role == "admin" && self.admins.contains(account_id)
+///     }
+/// }
+///
+/// #[near]
+/// impl Counter {
+///     #[only(role = "admin")]
+///     pub fn reset(&mut self) {
+///         self.val = 0;
+///     }
+/// }
+/// ```
+///
+/// ## `#[only_owner]` (annotates methods of a type in its `impl` block)
+///
+/// `#[only_owner]` restricts a call method to the owner of the contract, i.e. to the accounts
+/// having [`OWNER_ROLE`] through the [`HasRoles`] implementation of the contract. The owner is
+/// usually kept by the `Ownable` component of `near_contract_standards::ownable`, which transfers
+/// the ownership in two steps.
+///
+/// ### Basic example
+///
+/// ```rust
+/// use near_sdk::{near, AccountId, HasRoles, PanicOnDefault, OWNER_ROLE};
+///
+/// #[near(contract_state)]
+/// #[derive(PanicOnDefault)]
+/// pub struct Counter {
+///     val: u64,
+///     owner_id: AccountId,
+/// }
+///
+/// impl HasRoles for Counter {
+///     fn has_role(&self, role: &str, account_id: &AccountId) -> bool {
+///         role == OWNER_ROLE && account_id == &self.owner_id
+///     }
+/// }
+///
+/// #[near]
+/// impl Counter {
+///     #[only_owner]
+///     pub fn reset(&mut self) {
+///         self.val = 0;
+///     }
+/// }
+/// ```
+///
+/// ## `#[non_reentrant]` (annotates methods of a type in its `impl` block)
+///
+/// Guards a call method against reentrancy: a flag is set in the storage before the method runs,
+/// and the call fails if it is already set, i.e. if a guarded method is called while another one,
+/// or the promise it returned, is still executing for the same key. The key is the account
+/// calling the method by default, or the argument of the method named by `key = "name"`, so that
+/// a caller can't block the guarded methods for the others.
+///
+/// The flag is cleared once the method returns, unless it returns a [`Promise`], or a
+/// [`PromiseOrValue::Promise`], in which case it stays set until the callback of the promise,
+/// marked `#[non_reentrant(callback, key = "name")]`, returns. The callback is called by the
+/// contract itself, so it gets the key of the guard to clear in its argument `name`. A guarded
+/// method returning a promise must name its callback with `then = "callback"`, which fails to
+/// compile unless the callback is marked `#[non_reentrant(callback)]`. The callback must also be
+/// `#[private]`, without `allow` or `allow_fn`, and panics unless the contract itself calls it, so
+/// that other accounts can't clear the guard while the promise is in flight.
+///
+/// A callback which panics or runs out of gas doesn't clear the flag, so the callbacks of guarded
+/// methods should handle the failures of the promises with `#[callback_result]` rather than
+/// panic, and should have enough gas. A flag left set can be cleared with
+/// [`utils::clear_non_reentrant`](crate::utils::clear_non_reentrant), e.g. from a method restricted to the owner of the contract.
+///
+/// ### Basic example
+///
+/// ```rust
+/// use near_sdk::json_types::U128;
+/// use near_sdk::{env, near, AccountId, NearToken, Promise, PromiseError};
+///
+/// #[near(contract_state)]
+/// #[derive(Default)]
+/// pub struct Vault {
+///     balance: u128,
+/// }
+///
+/// #[near]
+/// impl Vault {
+///     #[non_reentrant(then = "on_withdraw")]
+///     pub fn withdraw(&mut self, amount: U128) -> Promise {
+///         self.balance -= amount.0;
+///         let account_id = env::predecessor_account_id();
+///         Promise::new(account_id.clone())
+///             .transfer(NearToken::from_yoctonear(amount.0))
+///             .then(Self::ext(env::current_account_id()).on_withdraw(account_id, amount))
+///     }
+///
+///     #[private]
+///     #[non_reentrant(callback, key = "account_id")]
+///     pub fn on_withdraw(
+///         &mut self,
+///         account_id: AccountId,
+///         amount: U128,
+///         #[callback_result] result: Result<(), PromiseError>,
+///     ) {
+///         if result.is_err() {
+///             self.balance += amount.0;
+///         }
+///     }
+/// }
+/// ```
+///
+/// ## `#[then(...)]` (annotates methods of a type in its `impl` block)
+///
+/// Chains the promise returned by a call method with a callback on the current account, passed
+/// as `callback = "name"`, instead of building the `.then(Self::ext(...).name(...))` call by
+/// hand. The variables listed in `capture(...)` are passed to the callback as its first
+/// arguments, in order, followed by its `#[callback_*]` arguments.
+///
+/// The method has to end with the expression of the promise it returns, without `return`.
+/// The captured variables are moved after the promise is built, so the promise expression has to
+/// clone the ones it uses.
+///
+/// ### Basic example
+///
+/// ```rust
+/// use near_sdk::{ext_contract, near, AccountId, Promise, PromiseError};
+///
+/// #[ext_contract(signer)]
+/// trait Signer {
+///     fn sign(&self, payload: String) -> String;
+/// }
+///
+/// #[near(contract_state)]
+/// #[derive(Default)]
+/// pub struct Contract {
+///     signer: Option<AccountId>,
+///     next_request_id: u64,
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     #[then(callback = "on_signature", capture(request_id, payload))]
+///     pub fn sign(&mut self, payload: String) -> Promise {
+///         let request_id: u64 = self.next_request_id;
+///         self.next_request_id += 1;
+///         signer::ext(self.signer.clone().unwrap()).sign(payload.clone())
+///     }
+///
+///     #[private]
+///     pub fn on_signature(
+///         &mut self,
+///         request_id: u64,
+///         payload: String,
+///         #[callback_result] signature: Result<String, PromiseError>,
+///     ) -> Option<String> {
+///         signature.ok()
+///     }
+/// }
+/// ```
+///
 /// ## `#[result_serializer(...)]` (annotates methods of a type in its `impl` block)
 ///
 /// The attribute defines the serializer for function return serialization.
-/// Only one of `borsh` or `json` can be specified.
+/// Only one of `borsh`, `json` or `with = Type` can be specified, where `Type` implements
+/// [`ArgsSerializer`](crate::ArgsSerializer).
 ///
 /// ```rust
 /// use near_sdk::near;
@@ -365,6 +852,232 @@ extern crate quickcheck;
 /// }
 /// ```
 ///
+/// ## `#[near(view_callback_preview)]` (annotates methods of a type in its `impl` block)
+///
+/// Generates a view next to a callback, which takes the `#[callback_unwrap]` arguments of the
+/// callback as regular arguments and runs the same method. This lets users preview the result of
+/// a call that depends on a cross-contract view, e.g. how many tokens a swap at the current price
+/// would return, by calling the remote view themselves and passing its value to the preview.
+///
+/// The preview is named after the callback with a `_preview` suffix, or as given with
+/// `#[near(view_callback_preview = "name")]`. As both run the same method, the method has to take
+/// `&self` or no `self`, so that the preview does not change the state. Apply the state changes
+/// of the callback in a separate method. `#[private]` only applies to the callback.
+///
+/// ### Basic example
+///
+/// ```rust
+/// use near_sdk::json_types::U128;
+/// use near_sdk::near;
+///
+/// #[near(contract_state)]
+/// #[derive(Default)]
+/// pub struct Exchange {
+///     fee_bps: u16,
+/// }
+///
+/// #[near]
+/// impl Exchange {
+///     /// Called with the price returned by the oracle. Also exported as the
+///     /// `on_price_preview(price, amount)` view.
+///     #[private]
+///     #[near(view_callback_preview)]
+///     pub fn on_price(&self, #[callback_unwrap] price: U128, amount: U128) -> U128 {
+///         let gross = price.0 * amount.0;
+///         U128(gross - gross * u128::from(self.fee_bps) / 10_000)
+///     }
+/// }
+/// ```
+///
+/// ## `#[near(async)]` (annotates methods of a type in its `impl` block)
+///
+/// Lets a method `.await` the promises of cross-contract calls instead of scheduling a callback
+/// by hand. The method is split at each `.await`: the statements up to the first one run in the
+/// method, which returns the awaited promise followed by a call to a generated `#[private]`
+/// callback, which runs the statements up to the next `.await`, and so on. The value of the
+/// method is returned by the last callback.
+///
+/// Each `.await` has to be a statement at the top level of the method, either `promise.await;`
+/// or `let result: T = promise.await;`. If `T` is a `Result<_, PromiseError>`, the callback
+/// receives it as a `#[callback_result]`, otherwise as a `#[callback_unwrap]`, failing if the
+/// promise failed. The result is deserialized from JSON, or as given by a `#[serializer(...)]`
+/// attribute on the `let` statement, e.g. `#[serializer(borsh)]`. The state of the contract is read again in each callback, and the arguments
+/// and variables used after an `.await` are passed to the callback as JSON arguments, so they
+/// need a type annotation and have to be serializable. As the method and the callbacks before the
+/// last one return a [`Promise`], `return` and `?` can only be used after the last `.await`.
+///
+/// The callbacks are named `__<method>_resume_<n>`, and use the default gas of cross-contract
+/// calls, a share of the unused gas.
+///
+/// ### Basic example
+///
+/// ```rust
+/// use near_sdk::json_types::U128;
+/// use near_sdk::{ext_contract, near, AccountId, PromiseError};
+///
+/// #[ext_contract(ext_oracle)]
+/// trait Oracle {
+///     fn get_price(&self, token_id: AccountId) -> U128;
+/// }
+///
+/// #[near(contract_state)]
+/// #[derive(Default)]
+/// pub struct Exchange {
+///     prices: std::collections::HashMap<AccountId, u128>,
+/// }
+///
+/// #[near]
+/// impl Exchange {
+///     /// Fetches the price of `token_id` from the oracle and returns whether it changed.
+///     #[near(async)]
+///     pub fn update_price(&mut self, oracle_id: AccountId, token_id: AccountId) -> bool {
+///         let price: Result<U128, PromiseError> =
+///             ext_oracle::ext(oracle_id).get_price(token_id.clone()).await;
+///         match price {
+///             Ok(price) => self.prices.insert(token_id, price.0) != Some(price.0),
+///             Err(_) => false,
+///         }
+///     }
+/// }
+/// ```
+///
+/// ## `#[near(deny_unknown_arguments)]` (annotates methods of a type in its `impl` block, or `impl` blocks)
+///
+/// Makes the method fail when its JSON arguments have fields other than its arguments, e.g. a
+/// misspelled optional argument, which are ignored by default. The error names the unknown field.
+/// On an impl block, applies to all its methods. Methods taking their arguments in Borsh already
+/// fail on unexpected input.
+///
+/// ### Basic example
+///
+/// ```rust
+/// use near_sdk::near;
+///
+/// #[near(contract_state)]
+/// #[derive(Default)]
+/// pub struct Contract {
+///     greeting: String,
+/// }
+///
+/// #[near(deny_unknown_arguments)]
+/// impl Contract {
+///     // Fails with `{"greting": "hello"}` rather than setting the default greeting.
+///     pub fn set_greeting(&mut self, greeting: Option<String>) {
+///         self.greeting = greeting.unwrap_or_else(|| "Hello".to_string());
+///     }
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     #[near(deny_unknown_arguments)]
+///     pub fn get_greeting(&self) -> String {
+///         self.greeting.clone()
+///     }
+/// }
+/// ```
+///
+/// ## `#[near(abi(...))]` (annotates methods of a type in its `impl` block)
+///
+/// Adds hints for the callers of the method to the ABI, so that wallets and other tools can
+/// prefill the call without maintaining their own configuration: the deposit to attach at least
+/// with `min_deposit`, in `yoctoNEAR`, `yocto`, `milliNEAR` or `NEAR`, which requires the method
+/// to be `#[payable]`, and the gas to attach with `recommended_gas`, in `gas`, `Ggas`, `Tgas` or
+/// `Pgas`. The hints are listed by method name in the `method_hints` extension of the root schema
+/// of the ABI, and don't change the behavior of the method.
+///
+/// ### Basic example
+///
+/// ```rust
+/// use near_sdk::near;
+///
+/// #[near(contract_state)]
+/// #[derive(Default)]
+/// pub struct Contract {
+///     tickets: u32,
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     #[payable]
+///     #[near(abi(min_deposit = "1 yocto", recommended_gas = "30 Tgas"))]
+///     pub fn buy_ticket(&mut self) {
+///         self.tickets += 1;
+///     }
+/// }
+/// ```
+///
+/// ## `#[near(generate_interface)]` (annotates `impl` blocks)
+///
+/// Generates the interface of the methods of the impl block which other contracts can call, i.e.
+/// all but the `#[private]` ones, in a module named after the contract, e.g. `counter_interface`
+/// for `Counter`, or after the name given with `generate_interface = "name"`. The module declares
+/// a trait with the methods, as it would be written for [`ext_contract`], and its `ext` client.
+///
+/// The types of the signatures are those of the module of the contract, so the interface can be
+/// used by the other contracts of a crate, or moved to its own crate with these types. An
+/// interface crate can also be generated from the ABI of the contract with [`abi_client`]. Each
+/// module has to have its own name, so impl blocks of the same type need explicit names.
+///
+/// ### Basic example
+///
+/// ```rust
+/// use near_sdk::{near, AccountId, Promise};
+///
+/// #[near(contract_state)]
+/// #[derive(Default)]
+/// pub struct Counter {
+///     value: u64,
+/// }
+///
+/// #[near(generate_interface)]
+/// impl Counter {
+///     pub fn increment(&mut self) {
+///         self.value += 1;
+///     }
+///
+///     pub fn get(&self) -> u64 {
+///         self.value
+///     }
+/// }
+///
+/// // In another contract.
+/// fn increment(counter: AccountId) -> Promise {
+///     counter_interface::ext(counter.clone())
+///         .increment()
+///         .then(counter_interface::ext(counter).get())
+/// }
+/// ```
+///
+/// ## `#[near(instantiate = "...")]` (annotates generic structs/enums and `impl` blocks)
+///
+/// Exports the methods of a generic contract for one instantiation of it, e.g.
+/// `Incrementer<u32>` for `Incrementer<T>`. Contract methods and their ABI need concrete types,
+/// so the generic parameters of the impl block are replaced by the arguments of the instantiation
+/// in the exported functions and the ABI, while the methods themselves stay generic. The
+/// instantiation is declared on the contract state and on each of its impl blocks, whose type
+/// must take the generic parameters as arguments, e.g. `impl<T> Incrementer<T>`.
+///
+/// ### Basic example
+///
+/// ```rust
+/// use near_sdk::near;
+/// use std::marker::PhantomData;
+///
+/// #[near(contract_state, instantiate = "Incrementer<u32>")]
+/// #[derive(Default)]
+/// pub struct Incrementer<T> {
+///     value: u64,
+///     step: PhantomData<T>,
+/// }
+///
+/// #[near(instantiate = "Incrementer<u32>")]
+/// impl<T: Into<u64>> Incrementer<T> {
+///     pub fn increment(&mut self, by: T) {
+///         self.value += by.into();
+///     }
+/// }
+/// ```
+///
 /// ## `#[near(event_json(...))]` (annotates enums)
 ///
 /// By passing `event_json` as an argument `near` will generate the relevant code to format events
@@ -419,9 +1132,22 @@ extern crate quickcheck;
 /// The `standard` will be populated with `nep330` by default.
 ///
 /// Any additional standards can be added and should be specified using the `standard` attribute.
-///
-/// The `contract_source_metadata()` view function will be added and can be used to retrieve the source metadata.
+/// With `auto_standards`, the standards implemented by the `near-contract-standards` components
+/// embedded in the contract state are added as well, e.g. `nep141` and `nep145` for a
+/// `FungibleToken` field and `nep148` for a `LazyOption<FungibleTokenMetadata>` field.
+///
+/// The `build_info` of NEP-330 1.2.0 is embedded when the `NEP330_BUILD_INFO_BUILD_ENVIRONMENT`
+/// environment variable is set, as by `cargo near build`, from the `NEP330_BUILD_INFO_*` variables:
+/// `BUILD_ENVIRONMENT`, `BUILD_COMMAND` as a JSON array, `CONTRACT_PATH`, `SOURCE_CODE_SNAPSHOT`
+/// and the optional `OUTPUT_WASM_PATH`. Instead of `SOURCE_CODE_SNAPSHOT`, `GIT_COMMIT` sets the
+/// snapshot to this commit of the git repository of the `link`.
+///
+/// The `contract_source_metadata()` view function will be added and can be used to retrieve the source metadata,
+/// as a [`ContractSourceMetadata`](crate::contract_metadata::ContractSourceMetadata) whose schema is part of the ABI.
 /// Also, the source metadata will be stored as a constant, `CONTRACT_SOURCE_METADATA`, in the contract code.
+/// The ABI of the contract includes it in the `contract_metadata` extension of its root schema,
+/// next to the doc comment of the contract state as its description, and to the schemas of the
+/// error types of `#[handle_result]` methods in the `errors` extension, by method name.
 ///
 /// **Please note that the `contract_metadata` will be ignored if [`#[near(contract_state)]`](near#nearcontract_state-annotates-structsenums) is not used**.
 ///
@@ -494,9 +1220,87 @@ pub use near_sdk_macros::near_bindgen;
 ///
 /// ```
 ///
+/// The gas of the call is its static gas, set with `with_static_gas`, plus a share of the gas
+/// left unused by the current call, split between the calls it schedules in proportion to their
+/// weights, set with `with_unused_gas_weight`, 1 by default. `with_gas` sets both, e.g. a floor
+/// of 5 TGas with twice the share of the unused gas of the other calls:
+///
+/// ```rust
+/// # use near_sdk::{ext_contract, AccountId, Gas, Promise};
+/// # #[ext_contract(ext_calculator)]
+/// # trait Calculator {
+/// #     fn mult(&self, a: u64, b: u64) -> u128;
+/// # }
+/// # fn multiply(calculator_account: AccountId) -> Promise {
+/// ext_calculator::ext(calculator_account).with_gas(Gas::from_tgas(5), 2).mult(2, 5)
+/// # }
+/// ```
+///
+/// Default deposits and static gas of the calls, used unless set with `with_attached_deposit`
+/// and `with_static_gas`, can be declared for all the methods after the module name, and for a
+/// single method with `#[deposit(...)]` and `#[gas(...)]`. Gas is given in `gas`, `Ggas`,
+/// `Tgas` or `Pgas`, and deposits in `yoctoNEAR`, `milliNEAR` or `NEAR`, with integers standing
+/// for gas units and yoctoNEAR:
+///
+/// ```rust
+/// # use near_sdk::{ext_contract, AccountId, NearToken, Promise};
+/// #[ext_contract(ext_bob, gas = "10 Tgas", deposit = 0)]
+/// trait Bob {
+///     fn ping(&self);
+///     #[gas("50 Tgas")]
+///     #[deposit("0.01 NEAR")]
+///     fn store(&mut self, value: String);
+/// }
+///
+/// # fn store(bob: AccountId) -> Promise {
+/// // 10 TGas and no deposit.
+/// ext_bob::ext(bob.clone()).ping()
+///     // 50 TGas and 0.1 NEAR.
+///     .and(ext_bob::ext(bob).with_attached_deposit(NearToken::from_millinear(100)).store("v".into()))
+/// # }
+/// ```
+///
 /// See more information about role of ext_contract in [NEAR documentation](https://docs.near.org/build/smart-contracts/anatomy/crosscontract)
 pub use near_sdk_macros::ext_contract;
 
+/// `abi_client!` generates the client of an external contract from its ABI file, as generated by
+/// `cargo near abi`, instead of transcribing its interface into an [`ext_contract`] trait.
+///
+/// The path of the file is relative to the manifest of the crate. The client is generated in a
+/// module named after the contract in the ABI, or after the name given before the path, with:
+/// * the types defined in the schema of the ABI, structs for objects and enums for enumerations
+///   of strings, while the other schemas are mapped to Rust types, with
+///   [`serde_json::Value`] for those without one;
+/// * a trait with the functions of the contract, as it would be written for `ext_contract`;
+/// * the `ext` function and the client generated by `ext_contract` for the trait.
+///
+/// Only primitive arguments are supported for the functions taking their arguments in Borsh.
+///
+/// ## Examples
+///
+/// ```ignore
+/// use near_sdk::{abi_client, near, AccountId, Promise};
+///
+/// // Generates the `status_message` module from the ABI of the `status-message` contract.
+/// abi_client!("abi/status_message.json");
+/// // Generates the `ext_ft` module from the ABI of a fungible token.
+/// abi_client!(ext_ft, "abi/ft.json");
+///
+/// #[near(contract_state)]
+/// pub struct Contract {
+///     status_account: AccountId,
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     pub fn set_status(&mut self, message: String) -> Promise {
+///         status_message::ext(self.status_account.clone())
+///             .set_status(message, status_message::Mood::Happy)
+///     }
+/// }
+/// ```
+pub use near_sdk_macros::abi_client;
+
 /// `BorshStorageKey` generates implementation for [BorshIntoStorageKey](crate::__private::BorshIntoStorageKey) trait.
 /// It allows the type to be passed as a unique prefix for persistent collections.
 /// The type should also implement or derive [BorshSerialize](borsh::BorshSerialize) trait.
@@ -527,6 +1331,70 @@ pub use near_sdk_macros::ext_contract;
 ///     }
 /// }
 /// ```
+///
+/// By default the storage key of an enum variant starts with the index of the variant, so
+/// reordering, inserting or removing variants changes the keys of existing collections. To keep
+/// the keys stable across refactorings, every variant can be given an explicit byte prefix, which
+/// is followed by the Borsh serialization of the fields of the variant. The derive fails to compile
+/// if the prefix of a variant is a prefix of the prefix of another variant. Use
+/// [`assert_unique_storage_keys`] to check that the keys of multiple enums do not collide.
+///
+/// ```rust
+/// use near_sdk::{BorshStorageKey, IntoStorageKey, near};
+///
+/// #[near(serializers=[borsh])]
+/// #[derive(BorshStorageKey)]
+/// pub enum StorageKey {
+///     #[storage_key(prefix = b"m")]
+///     Messages,
+///     #[storage_key(prefix = "author")]
+///     MessagesByAuthor { author_hash: [u8; 32] },
+/// }
+///
+/// assert_eq!(StorageKey::Messages.into_storage_key(), b"m");
+/// assert_eq!(
+///     StorageKey::MessagesByAuthor { author_hash: [1; 32] }.into_storage_key(),
+///     [b"author".as_slice(), &[1; 32]].concat()
+/// );
+/// ```
+///
+/// A unit variant with the `#[storage_key(nested)]` attribute is the parent prefix of collections
+/// nested in the entries of another collection. The derive generates a constructor named after
+/// the variant in snake case, which returns the [`StorageKey`] of the collection nested in an
+/// entry, see [`StorageKey::nested`].
+///
+/// ```rust
+/// use near_sdk::store::{LookupMap, Vector};
+/// use near_sdk::{near, AccountId, BorshStorageKey};
+///
+/// #[near(serializers=[borsh])]
+/// #[derive(BorshStorageKey)]
+/// pub enum StorageKey {
+///     Orders,
+///     #[storage_key(nested)]
+///     OrdersOf,
+/// }
+///
+/// #[near(contract_state)]
+/// pub struct Contract {
+///     orders: LookupMap<AccountId, Vector<u64>>,
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     pub fn add_order(&mut self, order_id: u64) {
+///         self.orders
+///             .entry(near_sdk::env::predecessor_account_id())
+///             .or_insert_with_key(|account_id| Vector::new(StorageKey::orders_of(account_id)))
+///             .push(order_id);
+///     }
+/// }
+/// # impl Default for Contract {
+/// #     fn default() -> Self {
+/// #         Self { orders: LookupMap::new(StorageKey::Orders) }
+/// #     }
+/// # }
+/// ```
 pub use near_sdk_macros::BorshStorageKey;
 
 /// `PanicOnDefault` generates implementation for `Default` trait that panics with the following
@@ -574,6 +1442,10 @@ pub use near_sdk_macros::NearSchema;
 /// `FunctionError` generates implementation for `near_sdk::FunctionError` trait.
 /// It allows contract runtime to panic with the type using its [ToString] implementation
 /// as the message.
+///
+/// With `#[function_error(code = "...")]` on the struct or on every variant of the enum, it also
+/// implements [`ErrorCode`], and the contract panics with the code and the message as a JSON
+/// payload, see [`env::panic_error`].
 /// ## Example
 /// ```rust
 /// use near_sdk::{FunctionError, near};
@@ -604,9 +1476,33 @@ pub use near_sdk_macros::NearSchema;
 /// ```
 pub use near_sdk_macros::FunctionError;
 
+/// `TransferCallMsg` generates an implementation of the [`TransferCallMsg`](trait@TransferCallMsg)
+/// trait, which parses the `msg` of `ft_on_transfer` and `nft_on_transfer` into the type. The type
+/// should also derive [`Deserialize`](serde::Deserialize).
+///
+/// The `#[transfer_call_msg(max_len = ..., version = ...)]` attribute sets the maximum length of
+/// the `msg` in bytes, and the version its `"version"` field has to match.
+///
+/// ## Example
+/// ```rust
+/// use near_sdk::serde::Deserialize;
+/// use near_sdk::TransferCallMsg;
+///
+/// #[derive(Deserialize, TransferCallMsg)]
+/// #[serde(crate = "near_sdk::serde")]
+/// #[transfer_call_msg(max_len = 128)]
+/// struct LockMsg {
+///     unlock_timestamp: u64,
+/// }
+///
+/// let msg = LockMsg::parse_msg(r#"{"unlock_timestamp":1700000000}"#).unwrap();
+/// assert_eq!(msg.unlock_timestamp, 1700000000);
+/// ```
+pub use near_sdk_macros::TransferCallMsg;
+
 pub mod store;
 
-#[cfg(feature = "legacy")]
+#[cfg(feature = "legacy-collections")]
 pub mod collections;
 mod environment;
 pub use environment::env;
@@ -615,7 +1511,12 @@ pub use environment::env;
 pub use near_sys as sys;
 
 mod promise;
-pub use promise::{Allowance, Promise, PromiseOrValue};
+#[cfg(not(target_arch = "wasm32"))]
+pub use promise::PromiseDescription;
+pub use promise::{
+    Allowance, NewAccountPromise, Promise, PromiseAction, PromiseOrValue, PromiseRetry,
+    PromiseTuple, RetryOutcome, YieldOutcome, YieldedPromise,
+};
 
 // Private types just used within macro generation, not stable to be used.
 #[doc(hidden)]
@@ -624,6 +1525,20 @@ pub mod __private;
 
 pub mod json_types;
 
+pub mod pagination;
+
+pub mod math;
+
+pub mod amounts;
+
+pub mod contract_metadata;
+
+pub mod rand;
+
+pub mod prelude;
+
+pub mod diagnostics;
+
 mod types;
 pub use crate::types::*;
 
@@ -638,7 +1553,7 @@ pub use environment::mock::MockedBlockchain;
 pub use test_utils::context::VMContext;
 
 pub mod utils;
-pub use crate::utils::storage_key_impl::IntoStorageKey;
+pub use crate::utils::storage_key_impl::{IntoStorageKey, StorageKey};
 pub use crate::utils::*;
 
 #[cfg(feature = "__macro-docs")]
@@ -0,0 +1,425 @@
+//! Overflow-free integer math for token amounts and ratios, e.g. the prices of an AMM, staking
+//! rewards or fees, with [`mul_div`] and the [`FixedPoint`] numbers.
+//!
+//! The intermediate products are computed on 256 bits, so `amount * numerator / denominator`
+//! only fails when the result doesn't fit in a `u128`. Every operation which loses precision
+//! takes the direction of its [`Rounding`], which should be in favor of the contract: round down
+//! the amounts paid out by the contract, and round up the amounts paid in.
+//!
+//! # Examples
+//!
+//! ```
+//! use near_sdk::math::{self, FixedPoint, Rounding};
+//!
+//! // A 0.3% fee, rounded up as it is paid to the contract.
+//! let amount = 1_000_001;
+//! assert_eq!(math::basis_points_of(amount, 30, Rounding::Up), Some(3_001));
+//!
+//! // The output of a constant product swap, rounded down as it is paid out.
+//! let (reserve_in, reserve_out) = (5_000u128 * 10u128.pow(24), 2_000u128 * 10u128.pow(24));
+//! let amount_in = 10u128.pow(24);
+//! let amount_out =
+//!     math::mul_div(amount_in, reserve_out, reserve_in + amount_in, Rounding::Down).unwrap();
+//! assert_eq!(amount_out, 399_920_015_996_800_639_872_025);
+//!
+//! let price: FixedPoint = "0.4".parse().unwrap();
+//! assert_eq!(price.mul_int(amount_in, Rounding::Down), Some(4 * 10u128.pow(23)));
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Direction in which a result which can't be represented exactly is rounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Towards zero, i.e. the floor of the result.
+    Down,
+    /// Away from zero, i.e. the ceiling of the result.
+    Up,
+}
+
+/// Denominator of the basis points, hundredths of a percent.
+pub const BASIS_POINTS: u128 = 10_000;
+
+/// Returns `a * b / denominator` rounded in the direction of `rounding`, or `None` if
+/// `denominator` is zero or the result doesn't fit in a `u128`. The product `a * b` itself may
+/// exceed `u128::MAX`.
+pub const fn mul_div(a: u128, b: u128, denominator: u128, rounding: Rounding) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+    let (high, low) = mul_wide(a, b);
+    // The quotient fits in 128 bits only if the high half of the product is less than the
+    // denominator.
+    if high >= denominator {
+        return None;
+    }
+    let (quotient, remainder) = if high == 0 {
+        (low / denominator, low % denominator)
+    } else {
+        // Binary long division of the 256-bit product, with the remainder always less than the
+        // denominator, so that only the bits of the low half are left to divide.
+        let mut quotient = 0u128;
+        let mut remainder = high;
+        let mut i = 128;
+        while i > 0 {
+            i -= 1;
+            let carry = remainder >> 127;
+            remainder = (remainder << 1) | ((low >> i) & 1);
+            quotient <<= 1;
+            if carry == 1 || remainder >= denominator {
+                remainder = remainder.wrapping_sub(denominator);
+                quotient |= 1;
+            }
+        }
+        (quotient, remainder)
+    };
+    match rounding {
+        Rounding::Up if remainder != 0 => quotient.checked_add(1),
+        _ => Some(quotient),
+    }
+}
+
+/// Returns `a * b / denominator` rounded down, see [`mul_div`].
+pub const fn mul_div_floor(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    mul_div(a, b, denominator, Rounding::Down)
+}
+
+/// Returns `a * b / denominator` rounded up, see [`mul_div`].
+pub const fn mul_div_ceil(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    mul_div(a, b, denominator, Rounding::Up)
+}
+
+/// Returns `bps` basis points of `amount`, e.g. `30` for 0.3%, rounded in the direction of
+/// `rounding`. Returns `None` on overflow, which can only happen for more than 100%.
+pub const fn basis_points_of(amount: u128, bps: u128, rounding: Rounding) -> Option<u128> {
+    mul_div(amount, bps, BASIS_POINTS, rounding)
+}
+
+/// Returns `percent`% of `amount`, rounded in the direction of `rounding`. Returns `None` on
+/// overflow, which can only happen for more than 100%.
+pub const fn percent_of(amount: u128, percent: u128, rounding: Rounding) -> Option<u128> {
+    mul_div(amount, percent, 100, rounding)
+}
+
+/// Returns the 256-bit product of `a` and `b`, as its high and low halves.
+const fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & MASK);
+    let (b_high, b_low) = (b >> 64, b & MASK);
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let high_high = a_high * b_high;
+    // At most 3 * (2^64 - 1), which doesn't overflow.
+    let middle = (low_low >> 64) + (high_low & MASK) + (low_high & MASK);
+    let low = (middle << 64) | (low_low & MASK);
+    let high = high_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+    (high, low)
+}
+
+/// Non-negative fixed-point number with `DECIMALS` decimal places, 24 by default like NEAR
+/// amounts, stored as a `u128` multiple of `10^-DECIMALS`.
+///
+/// The number is serialized in Borsh as its `u128` representation, and in JSON as a base-10
+/// string, e.g. `"1.5"`. The operations return `None` on overflow, and take the [`Rounding`] of
+/// the results which lose precision.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::math::{FixedPoint, Rounding};
+///
+/// let third = FixedPoint::<6>::from_ratio(1, 3, Rounding::Down).unwrap();
+/// assert_eq!(third.to_string(), "0.333333");
+/// assert_eq!(FixedPoint::<6>::from_ratio(1, 3, Rounding::Up).unwrap().to_string(), "0.333334");
+///
+/// let rate = FixedPoint::<6>::from_integer(2).unwrap();
+/// assert_eq!(third.checked_mul(rate, Rounding::Down).unwrap().to_string(), "0.666666");
+/// assert_eq!(rate.mul_int(7, Rounding::Down), Some(14));
+/// ```
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, BorshSerialize, BorshDeserialize,
+)]
+pub struct FixedPoint<const DECIMALS: u32 = 24>(u128);
+
+impl<const DECIMALS: u32> FixedPoint<DECIMALS> {
+    /// The representation of one, `10^DECIMALS`.
+    pub const SCALE: u128 = 10u128.pow(DECIMALS);
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(Self::SCALE);
+    pub const MAX: Self = Self(u128::MAX);
+
+    /// Returns the number represented by `raw` multiples of `10^-DECIMALS`.
+    pub const fn from_raw(raw: u128) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the representation of the number, in multiples of `10^-DECIMALS`.
+    pub const fn raw(&self) -> u128 {
+        self.0
+    }
+
+    pub const fn from_integer(value: u128) -> Option<Self> {
+        match value.checked_mul(Self::SCALE) {
+            Some(raw) => Some(Self(raw)),
+            None => None,
+        }
+    }
+
+    /// Returns `numerator / denominator`, or `None` if `denominator` is zero or on overflow.
+    pub const fn from_ratio(
+        numerator: u128,
+        denominator: u128,
+        rounding: Rounding,
+    ) -> Option<Self> {
+        match mul_div(numerator, Self::SCALE, denominator, rounding) {
+            Some(raw) => Some(Self(raw)),
+            None => None,
+        }
+    }
+
+    /// Returns the integer part of the number, rounded in the direction of `rounding`.
+    pub const fn to_integer(&self, rounding: Rounding) -> u128 {
+        let (integer, fraction) = (self.0 / Self::SCALE, self.0 % Self::SCALE);
+        match rounding {
+            Rounding::Up if fraction != 0 => integer + 1,
+            _ => integer,
+        }
+    }
+
+    pub const fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_add(rhs.0) {
+            Some(raw) => Some(Self(raw)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_sub(rhs.0) {
+            Some(raw) => Some(Self(raw)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_mul(self, rhs: Self, rounding: Rounding) -> Option<Self> {
+        match mul_div(self.0, rhs.0, Self::SCALE, rounding) {
+            Some(raw) => Some(Self(raw)),
+            None => None,
+        }
+    }
+
+    /// Returns `self / rhs`, or `None` if `rhs` is zero or on overflow.
+    pub const fn checked_div(self, rhs: Self, rounding: Rounding) -> Option<Self> {
+        match mul_div(self.0, Self::SCALE, rhs.0, rounding) {
+            Some(raw) => Some(Self(raw)),
+            None => None,
+        }
+    }
+
+    /// Returns the integer `amount` multiplied by the number, e.g. an amount of tokens
+    /// converted at a price, rounded in the direction of `rounding`.
+    pub const fn mul_int(self, amount: u128, rounding: Rounding) -> Option<u128> {
+        mul_div(amount, self.0, Self::SCALE, rounding)
+    }
+
+    /// Returns the integer `amount` divided by the number, rounded in the direction of
+    /// `rounding`, or `None` if the number is zero or on overflow.
+    pub const fn div_int(self, amount: u128, rounding: Rounding) -> Option<u128> {
+        mul_div(amount, Self::SCALE, self.0, rounding)
+    }
+}
+
+impl<const DECIMALS: u32> fmt::Display for FixedPoint<DECIMALS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0 / Self::SCALE)?;
+        let fraction = self.0 % Self::SCALE;
+        if fraction != 0 {
+            let fraction = format!("{:0width$}", fraction, width = DECIMALS as usize);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
+
+impl<const DECIMALS: u32> FromStr for FixedPoint<DECIMALS> {
+    type Err = ParseFixedPointError;
+
+    /// Parses a base-10 number with at most `DECIMALS` decimal places, e.g. `1.5`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let is_digits = |digits: &str| digits.bytes().all(|digit| digit.is_ascii_digit());
+        if integer.is_empty() || !is_digits(integer) || !is_digits(fraction) {
+            return Err(ParseFixedPointError { kind: ParseFixedPointErrorKind::Invalid });
+        }
+        if fraction.len() > DECIMALS as usize {
+            return Err(ParseFixedPointError {
+                kind: ParseFixedPointErrorKind::TooManyDecimals(DECIMALS),
+            });
+        }
+        let overflow = || ParseFixedPointError { kind: ParseFixedPointErrorKind::Overflow };
+        let integer: u128 = integer.parse().map_err(|_| overflow())?;
+        let fraction = match fraction {
+            "" => 0,
+            fraction => {
+                fraction.parse::<u128>().map_err(|_| overflow())?
+                    * 10u128.pow(DECIMALS - fraction.len() as u32)
+            }
+        };
+        Self::from_integer(integer)
+            .and_then(|integer| integer.checked_add(Self(fraction)))
+            .ok_or_else(overflow)
+    }
+}
+
+impl<const DECIMALS: u32> Serialize for FixedPoint<DECIMALS> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de, const DECIMALS: u32> Deserialize<'de> for FixedPoint<DECIMALS> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        s.parse().map_err(|err: ParseFixedPointError| serde::de::Error::custom(err.to_string()))
+    }
+}
+
+#[cfg(feature = "abi")]
+impl<const DECIMALS: u32> schemars::JsonSchema for FixedPoint<DECIMALS> {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        String::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
+#[cfg(feature = "abi")]
+impl<const DECIMALS: u32> borsh::BorshSchema for FixedPoint<DECIMALS> {
+    fn add_definitions_recursively(
+        definitions: &mut std::collections::BTreeMap<
+            borsh::schema::Declaration,
+            borsh::schema::Definition,
+        >,
+    ) {
+        u128::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> borsh::schema::Declaration {
+        u128::declaration()
+    }
+}
+
+/// Error returned when parsing a [`FixedPoint`] from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFixedPointError {
+    kind: ParseFixedPointErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParseFixedPointErrorKind {
+    Invalid,
+    TooManyDecimals(u32),
+    Overflow,
+}
+
+impl fmt::Display for ParseFixedPointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseFixedPointErrorKind::Invalid => write!(f, "invalid fixed-point number"),
+            ParseFixedPointErrorKind::TooManyDecimals(decimals) => {
+                write!(f, "too many decimal places, at most {} are allowed", decimals)
+            }
+            ParseFixedPointErrorKind::Overflow => write!(f, "fixed-point number is too large"),
+        }
+    }
+}
+
+impl std::error::Error for ParseFixedPointError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_div_wide() {
+        assert_eq!(mul_div(6, 7, 4, Rounding::Down), Some(10));
+        assert_eq!(mul_div(6, 7, 4, Rounding::Up), Some(11));
+        assert_eq!(mul_div(6, 8, 4, Rounding::Up), Some(12));
+        assert_eq!(mul_div(1, 1, 0, Rounding::Down), None);
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX, Rounding::Down), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX - 1, Rounding::Down), None);
+        assert_eq!(mul_div(u128::MAX, 3, 4, Rounding::Down), Some(u128::MAX / 4 * 3 + 2));
+        assert_eq!(mul_div(u128::MAX, u128::MAX - 1, u128::MAX, Rounding::Up), Some(u128::MAX - 1));
+        assert_eq!(mul_div(u128::MAX, 2, 7, Rounding::Up), Some((u128::MAX / 7) * 2 + 1));
+        assert_eq!(mul_div_floor(u128::MAX, 1 << 100, 1 << 101), Some(u128::MAX / 2));
+        assert_eq!(mul_div_ceil(u128::MAX, 1 << 100, 1 << 101), Some(u128::MAX / 2 + 1));
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX, Rounding::Up), Some(u128::MAX));
+    }
+
+    #[test]
+    fn percentages() {
+        assert_eq!(basis_points_of(10_000, 30, Rounding::Down), Some(30));
+        assert_eq!(basis_points_of(10_001, 30, Rounding::Down), Some(30));
+        assert_eq!(basis_points_of(10_001, 30, Rounding::Up), Some(31));
+        assert_eq!(percent_of(u128::MAX, 100, Rounding::Down), Some(u128::MAX));
+        assert_eq!(percent_of(u128::MAX, 101, Rounding::Down), None);
+    }
+
+    #[test]
+    fn fixed_point() {
+        type Fixed = FixedPoint<2>;
+        let half = Fixed::from_ratio(1, 2, Rounding::Down).unwrap();
+        assert_eq!(half.raw(), 50);
+        assert_eq!(Fixed::ONE.checked_sub(half), Some(half));
+        assert_eq!(half.checked_mul(half, Rounding::Down), Some(Fixed::from_raw(25)));
+        assert_eq!(Fixed::ONE.checked_div(Fixed::from_raw(3), Rounding::Up).unwrap().raw(), 3334);
+        assert_eq!(Fixed::ONE.checked_div(Fixed::ZERO, Rounding::Down), None);
+        assert_eq!(half.div_int(3, Rounding::Down), Some(6));
+        assert_eq!(Fixed::from_raw(150).to_integer(Rounding::Down), 1);
+        assert_eq!(Fixed::from_raw(150).to_integer(Rounding::Up), 2);
+        assert_eq!(Fixed::from_integer(u128::MAX), None);
+        assert_eq!(Fixed::MAX.checked_add(Fixed::from_raw(1)), None);
+        assert_eq!(FixedPoint::<24>::ONE.raw(), 10u128.pow(24));
+    }
+
+    #[test]
+    fn fixed_point_format() {
+        type Fixed = FixedPoint<4>;
+        for s in ["0", "1", "1.5", "0.0001", "34028236692093846346337460743176821.1455"] {
+            assert_eq!(s.parse::<Fixed>().unwrap().to_string(), s);
+        }
+        assert_eq!("2.50".parse::<Fixed>().unwrap().to_string(), "2.5");
+        let error = |s: &str| s.parse::<Fixed>().unwrap_err().to_string();
+        assert_eq!(error("1.23456"), "too many decimal places, at most 4 are allowed");
+        assert_eq!(error(".5"), "invalid fixed-point number");
+        assert_eq!(error("-1"), "invalid fixed-point number");
+        assert_eq!(
+            error("34028236692093846346337460743176821.1456"),
+            "fixed-point number is too large"
+        );
+
+        let half: Fixed = serde_json::from_str("\"0.5\"").unwrap();
+        assert_eq!(serde_json::to_string(&half).unwrap(), "\"0.5\"");
+        assert_eq!(borsh::to_vec(&half).unwrap(), 5_000u128.to_le_bytes());
+    }
+}
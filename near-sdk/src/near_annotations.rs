@@ -7,14 +7,23 @@
 //! * init
 //! * payable
 //! * private
+//! * only
+//! * only_owner
+//! * non_reentrant
+//! * then
 //! * handle_result
 //! * event_json
 //! * contract_metadata
+//! * deny_unknown_arguments
+//! * generate_interface
 //! * serializer
+//! * arg
 //!
 //! These attributes are only part of the `near` macro.
 //! * serializers
 //! * contract_state
+//! * versioned
+//! * lazy
 
 /// See [`near_sdk::near #[init]`](crate::near#init-annotates-methods-of-a-type-in-its-impl-block)
 pub fn init() {}
@@ -25,6 +34,18 @@ pub fn payable() {}
 /// See [`near_sdk::near #[private]`](crate::near#private-annotates-methods-of-a-type-in-its-impl-block)
 pub fn private() {}
 
+/// See [`near_sdk::near #[only(...)]`](crate::near#only-annotates-methods-of-a-type-in-its-impl-block)
+pub fn only() {}
+
+/// See [`near_sdk::near #[only_owner]`](crate::near#only_owner-annotates-methods-of-a-type-in-its-impl-block)
+pub fn only_owner() {}
+
+/// See [`near_sdk::near #[non_reentrant]`](crate::near#non_reentrant-annotates-methods-of-a-type-in-its-impl-block)
+pub fn non_reentrant() {}
+
+/// See [`near_sdk::near #[then(...)]`](crate::near#then-annotates-methods-of-a-type-in-its-impl-block)
+pub fn then() {}
+
 /// See [`near_sdk::near #[result_serializer]`](crate::near#result_serializer-annotates-methods-of-a-type-in-its-impl-block)
 pub fn result_serializer() {}
 
@@ -37,11 +58,26 @@ pub fn event_json() {}
 /// See [`near_sdk::near #[near(contract_metadata(...))]`](crate::near#nearcontract_metadata-annotates-structsenums)
 pub fn contract_metadata() {}
 
+/// See [`near_sdk::near #[near(deny_unknown_arguments)]`](crate::near#neardeny_unknown_arguments-annotates-methods-of-a-type-in-its-impl-block-or-impl-blocks)
+pub fn deny_unknown_arguments() {}
+
+/// See [`near_sdk::near #[near(generate_interface)]`](crate::near#neargenerate_interface-annotates-impl-blocks)
+pub fn generate_interface() {}
+
 /// See [`near_sdk::near #[serializer(...)]`](crate::near#serializer-annotates-function-arguments)
 pub fn serializer() {}
 
+/// See [`near_sdk::near #[arg(default)]`](crate::near#argdefault-annotates-function-arguments)
+pub fn arg() {}
+
+/// See [`near_sdk::near #[near(lazy)]`](crate::near#nearlazy-annotates-fields-of-structs)
+pub fn lazy() {}
+
 /// See [`near_sdk::near #[near(serializers=[...])]`](crate::near#nearserializers-annotates-structsenums)
 pub fn serializers() {}
 
 /// See [`near_sdk::near #[near(contract_state)]`](crate::near#nearcontract_state-annotates-structsenums)
 pub fn contract_state() {}
+
+/// See [`near_sdk::near #[near(contract_state(versioned(...)))]`](crate::near#nearcontract_stateversioned-annotates-structs)
+pub fn versioned() {}
@@ -0,0 +1,143 @@
+//! Pagination of the results of view methods.
+//!
+//! View methods returning the entries of a collection have to return them in pages, as the gas
+//! of view calls is limited. [`paginate`] takes a page of any iterator, such as the iterators of
+//! the [`store`](crate::store) collections, and returns it as a [`Page`] with an opaque cursor
+//! to pass back to get the next page, instead of every contract defining its own
+//! `from_index: U128, limit: u64` arguments.
+//!
+//! # Examples
+//!
+//! ```
+//! use near_sdk::pagination::{paginate, Page};
+//! use near_sdk::store::IterableMap;
+//! use near_sdk::{near, AccountId};
+//!
+//! #[near(contract_state)]
+//! pub struct Contract {
+//!     balances: IterableMap<AccountId, u64>,
+//! }
+//!
+//! #[near]
+//! impl Contract {
+//!     pub fn get_balances(&self, cursor: Option<String>, limit: u32) -> Page<(AccountId, u64)> {
+//!         paginate(self.balances.iter(), cursor.as_deref(), limit)
+//!             .map(|(account_id, balance)| (account_id.clone(), *balance))
+//!     }
+//! }
+//! # impl Default for Contract {
+//! #     fn default() -> Self {
+//! #         Self { balances: IterableMap::new(b"b") }
+//! #     }
+//! # }
+//! ```
+
+use base64::Engine;
+use near_sdk_macros::near;
+
+use crate::{env, require};
+
+const ERR_INVALID_CURSOR: &str = "Invalid pagination cursor";
+
+/// A page of the items of a collection, returned by [`paginate`].
+#[near(inside_nearsdk, serializers=[json])]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    /// Items of the page.
+    pub items: Vec<T>,
+    /// Cursor of the next page, `None` if this is the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Maps the items of the page with `f`, keeping the cursor of the next page.
+    ///
+    /// Mapping the page rather than the iterator passed to [`paginate`] avoids mapping the items
+    /// skipped to get to the page.
+    pub fn map<U, F>(self, f: F) -> Page<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Page { items: self.items.into_iter().map(f).collect(), next_cursor: self.next_cursor }
+    }
+}
+
+/// Returns the page of at most `limit` items of `iter` starting at `cursor`, the cursor of the
+/// next page returned by a previous call, or at the first item if `cursor` is `None`.
+///
+/// The cursor is the opaque encoding of the position of the page in `iter`. Items before it are
+/// skipped with [`Iterator::nth`], which most iterators of the [`store`](crate::store) collections
+/// implement without loading the skipped values. If items are inserted or removed between the
+/// calls, the next page starts at the same position, so items may be returned twice or skipped.
+///
+/// # Panics
+///
+/// Panics if `limit` is zero or if `cursor` was not returned by `paginate`.
+pub fn paginate<I>(iter: I, cursor: Option<&str>, limit: u32) -> Page<I::Item>
+where
+    I: IntoIterator,
+{
+    require!(limit != 0, "Cannot provide limit of 0.");
+    let start = cursor.map_or(0, decode_cursor);
+    let skip = usize::try_from(start).unwrap_or_else(|_| env::panic_str(ERR_INVALID_CURSOR));
+
+    let mut iter = iter.into_iter();
+    if skip > 0 && iter.nth(skip - 1).is_none() {
+        return Page { items: Vec::new(), next_cursor: None };
+    }
+    let items: Vec<_> = iter.by_ref().take(limit as usize).collect();
+    let next_cursor = if items.len() == limit as usize && iter.next().is_some() {
+        Some(encode_cursor(start + u64::from(limit)))
+    } else {
+        None
+    };
+    Page { items, next_cursor }
+}
+
+fn encode_cursor(position: u64) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(position.to_le_bytes())
+}
+
+fn decode_cursor(cursor: &str) -> u64 {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .unwrap_or_else(|| env::panic_str(ERR_INVALID_CURSOR))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paginate_all_pages() {
+        let mut pages = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = paginate(0..10, cursor.as_deref(), 4);
+            pages.push(page.items);
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(pages, [vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]);
+
+        // No empty last page when the items end at a page boundary.
+        let page = paginate(0..8, None, 4);
+        let page = paginate(0..8, page.next_cursor.as_deref(), 4);
+        assert_eq!(page, Page { items: vec![4, 5, 6, 7], next_cursor: None });
+
+        // The collection shrank below the cursor.
+        let page = paginate(0..10, None, 4);
+        assert_eq!(paginate(0..2, page.next_cursor.as_deref(), 4).items, Vec::<i32>::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid pagination cursor")]
+    fn invalid_cursor() {
+        paginate(0..10, Some("0"), 4);
+    }
+}
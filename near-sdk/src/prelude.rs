@@ -0,0 +1,42 @@
+//! The items most contracts need, to import at once with:
+//!
+//! ```
+//! use near_sdk::prelude::*;
+//!
+//! #[near(contract_state)]
+//! #[derive(PanicOnDefault)]
+//! pub struct Contract {
+//!     owner_id: AccountId,
+//!     balances: LookupMap<AccountId, NearToken>,
+//! }
+//!
+//! #[near]
+//! impl Contract {
+//!     #[init]
+//!     pub fn new(owner_id: AccountId) -> Self {
+//!         Self { owner_id, balances: LookupMap::new(b"b") }
+//!     }
+//!
+//!     #[payable]
+//!     pub fn deposit(&mut self) {
+//!         let account_id = env::predecessor_account_id();
+//!         let balance =
+//!             self.balances.get(&account_id).copied().unwrap_or(NearToken::from_near(0));
+//!         require!(account_id != self.owner_id, "The owner cannot deposit");
+//!         self.balances.insert(account_id, balance.saturating_add(env::attached_deposit()));
+//!     }
+//! }
+//! ```
+//!
+//! The items stay available at their own paths, so importing the prelude is optional.
+
+pub use crate::json_types::{Base64VecU8, U128, U64};
+pub use crate::store::{IterableMap, IterableSet, LazyOption, LookupMap, LookupSet, Vector};
+pub use crate::{env, ext_contract, log, near, require};
+pub use crate::{
+    AccountId, AccountIdRef, BlockDuration, BlockHeight, BlockTimestamp, BorshStorageKey,
+    CryptoHash, FunctionError, Gas, GasWeight, IntoStorageKey, NearToken, PanicOnDefault, Promise,
+    PromiseError, PromiseOrValue, PromiseResult, PublicKey, Timestamp,
+};
+pub use borsh::{BorshDeserialize, BorshSerialize};
+pub use serde::{Deserialize, Serialize};
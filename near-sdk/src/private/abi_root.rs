@@ -0,0 +1,202 @@
+use std::marker::PhantomData;
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{RootSchema, Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde_json::Value;
+
+use crate::{Gas, NearToken};
+
+/// Extension of the root schema of the contract ABI with its NEP-330 source metadata, as returned
+/// by `contract_source_metadata`.
+pub const CONTRACT_METADATA_EXTENSION: &str = "contract_metadata";
+
+/// Extension of the root schema of the contract ABI with the schema of the error type of each
+/// method returning a `Result`, by method name.
+pub const ERRORS_EXTENSION: &str = "errors";
+
+/// Extension of the root schema of the contract ABI with the hints of the methods set with
+/// `#[near(abi(...))]`, by method name.
+pub const METHOD_HINTS_EXTENSION: &str = "method_hints";
+
+/// Extension of the root schema of the contract ABI with the formats of the arguments and results
+/// of the methods serialized with an [`ArgsSerializer`](crate::ArgsSerializer), by method name.
+pub const SERIALIZERS_EXTENSION: &str = "serializers";
+
+/// Hints for the callers of a method, e.g. to prefill the deposit and gas of the call in a wallet.
+#[derive(serde::Serialize)]
+pub struct AbiMethodHints {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_deposit: Option<NearToken>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommended_gas: Option<Gas>,
+}
+
+/// Formats of the arguments and result of a method serialized with an
+/// [`ArgsSerializer`](crate::ArgsSerializer), as their schemas in the ABI are JSON schemas.
+#[derive(serde::Serialize)]
+pub struct AbiMethodSerializers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<&'static str>,
+}
+
+/// Returns the root schema of the ABI of the contract itself, with the doc comment of its state
+/// as description and its NEP-330 source metadata.
+pub fn contract_root_schema(doc: Option<&str>, source_metadata: &str) -> RootSchema {
+    let mut root_schema = SchemaGenerator::default().into_root_schema_for::<String>();
+    root_schema.schema.metadata().description = doc.map(str::to_owned);
+    if let Ok(metadata) = serde_json::from_str::<Value>(source_metadata) {
+        root_schema.schema.extensions.insert(CONTRACT_METADATA_EXTENSION.to_owned(), metadata);
+    }
+    root_schema
+}
+
+/// Returns the root schema of the ABI of the methods of an `impl` block, with the schemas of
+/// their `errors`, which refer to the definitions of `gen`, their `hints` and the formats of
+/// their custom `serializers`.
+pub fn methods_root_schema(
+    gen: SchemaGenerator,
+    errors: Vec<(&str, Schema)>,
+    hints: Vec<(&str, AbiMethodHints)>,
+    serializers: Vec<(&str, AbiMethodSerializers)>,
+) -> RootSchema {
+    let mut root_schema = gen.into_root_schema_for::<String>();
+    insert_by_method(&mut root_schema, ERRORS_EXTENSION, errors);
+    insert_by_method(&mut root_schema, METHOD_HINTS_EXTENSION, hints);
+    insert_by_method(&mut root_schema, SERIALIZERS_EXTENSION, serializers);
+    root_schema
+}
+
+/// Inserts the `values` of the methods as the `extension` of the root schema, unless there are
+/// none.
+fn insert_by_method<T: serde::Serialize>(
+    root_schema: &mut RootSchema,
+    extension: &str,
+    values: Vec<(&str, T)>,
+) {
+    if values.is_empty() {
+        return;
+    }
+    let values = values
+        .into_iter()
+        .map(|(method, value)| {
+            (method.to_owned(), serde_json::to_value(value).expect("ABI values are valid JSON"))
+        })
+        .collect();
+    root_schema.schema.extensions.insert(extension.to_owned(), Value::Object(values));
+}
+
+/// Schema of a type `E` in the ABI which may not implement [`JsonSchema`], such as the error type
+/// of a method, which only needs to implement [`FunctionError`](crate::FunctionError), or the
+/// arguments and results serialized with an [`ArgsSerializer`](crate::ArgsSerializer).
+///
+/// The schema of types which don't implement `JsonSchema` is only titled with the name of the
+/// type, by [`AbiSchemaFallback`]. The generated code calls `AbiSchema::<E>::new().schema(gen)`,
+/// which resolves to the inherent method when `E` implements `JsonSchema`.
+pub struct AbiSchema<E>(PhantomData<E>);
+
+impl<E> AbiSchema<E> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<E: JsonSchema> AbiSchema<E> {
+    pub fn schema(&self, gen: &mut SchemaGenerator) -> Schema {
+        gen.subschema_for::<E>()
+    }
+}
+
+pub trait AbiSchemaFallback {
+    fn schema(&self, gen: &mut SchemaGenerator) -> Schema;
+}
+
+impl<E> AbiSchemaFallback for AbiSchema<E> {
+    fn schema(&self, _gen: &mut SchemaGenerator) -> Schema {
+        let mut schema = SchemaObject::default();
+        schema.metadata().title = Some(std::any::type_name::<E>().to_owned());
+        Schema::Object(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Opaque;
+
+    #[test]
+    fn contract() {
+        let root_schema =
+            contract_root_schema(Some(" A counter."), r#"{"version":"1.0.0","standards":[]}"#);
+        assert_eq!(root_schema.schema.metadata.unwrap().description.unwrap(), " A counter.");
+        assert_eq!(
+            root_schema.schema.extensions[CONTRACT_METADATA_EXTENSION],
+            serde_json::json!({ "version": "1.0.0", "standards": [] })
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut gen = SchemaGenerator::default();
+        let errors = vec![
+            ("get", AbiSchema::<String>::new().schema(&mut gen)),
+            ("set", AbiSchema::<Opaque>::new().schema(&mut gen)),
+        ];
+        let root_schema = methods_root_schema(gen, errors, vec![], vec![]);
+        assert_eq!(
+            root_schema.schema.extensions[ERRORS_EXTENSION],
+            serde_json::json!({
+                "get": { "type": "string" },
+                "set": { "title": "near_sdk::private::abi_root::tests::Opaque" },
+            })
+        );
+
+        let root_schema = methods_root_schema(SchemaGenerator::default(), vec![], vec![], vec![]);
+        assert!(!root_schema.schema.extensions.contains_key(ERRORS_EXTENSION));
+        assert!(!root_schema.schema.extensions.contains_key(METHOD_HINTS_EXTENSION));
+        assert!(!root_schema.schema.extensions.contains_key(SERIALIZERS_EXTENSION));
+    }
+
+    #[test]
+    fn method_hints() {
+        let hints = vec![
+            (
+                "deposit",
+                AbiMethodHints {
+                    min_deposit: Some(NearToken::from_yoctonear(1)),
+                    recommended_gas: Some(Gas::from_tgas(30)),
+                },
+            ),
+            ("view", AbiMethodHints { min_deposit: None, recommended_gas: None }),
+        ];
+        let root_schema = methods_root_schema(SchemaGenerator::default(), vec![], hints, vec![]);
+        assert_eq!(
+            root_schema.schema.extensions[METHOD_HINTS_EXTENSION],
+            serde_json::json!({
+                "deposit": { "min_deposit": "1", "recommended_gas": "30000000000000" },
+                "view": {},
+            })
+        );
+    }
+
+    #[test]
+    fn serializers() {
+        let serializers = vec![
+            ("get", AbiMethodSerializers { args: None, result: Some("cbor") }),
+            ("set", AbiMethodSerializers { args: Some("cbor"), result: None }),
+        ];
+        let root_schema =
+            methods_root_schema(SchemaGenerator::default(), vec![], vec![], serializers);
+        assert_eq!(
+            root_schema.schema.extensions[SERIALIZERS_EXTENSION],
+            serde_json::json!({
+                "get": { "result": "cbor" },
+                "set": { "args": "cbor" },
+            })
+        );
+    }
+}
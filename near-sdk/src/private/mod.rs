@@ -6,13 +6,121 @@ pub use near_abi::{
     AbiParameters, AbiType,
 };
 #[cfg(feature = "abi")]
+mod abi_root;
+#[cfg(feature = "abi")]
 mod result_type_ext;
 
+#[cfg(feature = "abi")]
+pub use abi_root::{
+    contract_root_schema, methods_root_schema, AbiMethodHints, AbiMethodSerializers, AbiSchema,
+    AbiSchemaFallback, CONTRACT_METADATA_EXTENSION, ERRORS_EXTENSION, METHOD_HINTS_EXTENSION,
+    SERIALIZERS_EXTENSION,
+};
+
 #[cfg(feature = "abi")]
 pub use result_type_ext::ResultTypeExt;
 
-use crate::IntoStorageKey;
-use borsh::{to_vec, BorshSerialize};
+#[cfg(not(target_arch = "wasm32"))]
+mod view_guard;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use view_guard::assert_not_in_view;
+#[cfg(not(target_arch = "wasm32"))]
+pub use view_guard::ViewGuard;
+
+use std::marker::PhantomData;
+
+use crate::{env, IntoStorageKey};
+
+/// Sets `default` as the default value of the parameter described by `schema`, for the ABI of
+/// `#[arg(default)]` arguments.
+#[cfg(feature = "abi")]
+pub fn schema_with_default<T: serde::Serialize>(
+    schema: schemars::schema::Schema,
+    default: T,
+) -> schemars::schema::Schema {
+    use schemars::schema::{Schema, SchemaObject};
+
+    let mut schema_object = match schema {
+        // Keywords next to `$ref` are ignored, so the reference is wrapped in `allOf`.
+        Schema::Object(object) if object.is_ref() => {
+            let mut wrapper = SchemaObject::default();
+            wrapper.subschemas().all_of = Some(vec![Schema::Object(object)]);
+            wrapper
+        }
+        Schema::Object(object) => object,
+        Schema::Bool(true) => SchemaObject::default(),
+        schema => return schema,
+    };
+    schema_object.metadata().default = serde_json::to_value(default).ok();
+    Schema::Object(schema_object)
+}
+use borsh::{to_vec, BorshDeserialize, BorshSerialize};
+
+/// Sets the reentrancy guard of `#[non_reentrant]` methods stored under `key`, returned by
+/// [`non_reentrant_key`](crate::utils::non_reentrant_key), before calling `method_name`.
+///
+/// # Panics
+///
+/// Panics if the guard is already set.
+pub fn enter_non_reentrant(method_name: &str, key: &[u8]) {
+    if env::storage_write(key, &[]) {
+        env::panic_str(&format!("Method {} cannot be reentered", method_name));
+    }
+}
+
+/// Clears the reentrancy guard of `#[non_reentrant]` methods stored under `key`.
+pub fn exit_non_reentrant(key: &[u8]) {
+    env::storage_remove(key);
+}
+
+/// Checks that the contract itself called `method_name`, a `#[non_reentrant(callback)]` method,
+/// before it clears the guard of the key it is called with.
+///
+/// # Panics
+///
+/// Panics if the predecessor is another account.
+pub fn check_non_reentrant_callback(method_name: &str) {
+    if env::predecessor_account_id() != env::current_account_id() {
+        env::panic_str(&format!("Method {} is private", method_name));
+    }
+}
+
+/// Initializer of a contract whose state is missing, implemented by `#[init(on_deploy)]` for the
+/// contract to be initialized by the first call to one of its methods instead of by `Default`.
+pub trait InitOnDeploy: Sized {
+    fn init_on_deploy() -> Self;
+}
+
+/// Reads the state of a contract `T` in the generated code of its methods.
+///
+/// A missing state is initialized by [`InitOnDeploy`] if `T` implements it, and by
+/// [`StateLoaderFallback`] with `Default` otherwise. The generated code calls
+/// `StateLoader::<T>::new().load()`, which resolves to the inherent method when `T` implements
+/// `InitOnDeploy`.
+pub struct StateLoader<T>(PhantomData<T>);
+
+impl<T> StateLoader<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: BorshDeserialize + InitOnDeploy> StateLoader<T> {
+    pub fn load(&self) -> T {
+        env::state_read().unwrap_or_else(T::init_on_deploy)
+    }
+}
+
+pub trait StateLoaderFallback<T> {
+    fn load(&self) -> T;
+}
+
+impl<T: BorshDeserialize + Default> StateLoaderFallback<T> for StateLoader<T> {
+    fn load(&self) -> T {
+        env::state_read().unwrap_or_default()
+    }
+}
 
 /// Converts a Borsh serializable object into a `Vec<u8>` that is used for a storage key.
 ///
@@ -43,3 +151,47 @@ where
         to_vec(&self).unwrap()
     }
 }
+
+/// Byte prefixes of the storage keys of each variant of an enum deriving
+/// [`BorshStorageKey`](crate::BorshStorageKey), used by
+/// [`assert_unique_storage_keys`](crate::assert_unique_storage_keys) to check at compile time
+/// that the keys of multiple enums do not collide.
+pub trait StorageKeyPrefixes {
+    const PREFIXES: &'static [&'static [u8]];
+}
+
+/// Returns `true` if a prefix of one group is a prefix of, or equal to, a prefix of another group.
+pub const fn storage_key_prefixes_collide(groups: &[&[&[u8]]]) -> bool {
+    let mut i = 0;
+    while i < groups.len() {
+        let mut j = i + 1;
+        while j < groups.len() {
+            let mut a = 0;
+            while a < groups[i].len() {
+                let mut b = 0;
+                while b < groups[j].len() {
+                    if starts_with_either(groups[i][a], groups[j][b]) {
+                        return true;
+                    }
+                    b += 1;
+                }
+                a += 1;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    false
+}
+
+const fn starts_with_either(a: &[u8], b: &[u8]) -> bool {
+    let len = if a.len() < b.len() { a.len() } else { b.len() };
+    let mut i = 0;
+    while i < len {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
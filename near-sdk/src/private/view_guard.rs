@@ -0,0 +1,33 @@
+use std::cell::Cell;
+
+thread_local! {
+    /// Name of the view method being executed, if any.
+    static VIEW_METHOD: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+/// Marks a view method as executing until dropped, so that writes to the storage panic.
+///
+/// Only used in non-wasm builds, where the writes of view methods would otherwise succeed on the
+/// mocked blockchain.
+pub struct ViewGuard {
+    previous: Option<&'static str>,
+}
+
+impl ViewGuard {
+    pub fn enter(method_name: &'static str) -> Self {
+        Self { previous: VIEW_METHOD.with(|method| method.replace(Some(method_name))) }
+    }
+}
+
+impl Drop for ViewGuard {
+    fn drop(&mut self) {
+        VIEW_METHOD.with(|method| method.set(self.previous));
+    }
+}
+
+/// Panics if a view method is executing.
+pub(crate) fn assert_not_in_view() {
+    if let Some(method_name) = VIEW_METHOD.with(Cell::get) {
+        crate::env::panic_str(&format!("View method {} cannot write to the storage", method_name));
+    }
+}
@@ -7,12 +7,18 @@ use std::io::{Error, Write};
 use std::num::NonZeroU128;
 use std::rc::Rc;
 
+use near_sdk_macros::near;
+
 use crate::env::migrate_to_allowance;
-use crate::{AccountId, Gas, GasWeight, NearToken, PromiseIndex, PublicKey};
+use crate::json_types::{Base58CryptoHash, Base64VecU8};
+use crate::{
+    require, AccountId, CryptoHash, Gas, GasWeight, NearToken, PromiseError, PromiseIndex,
+    PublicKey, StorageUsage,
+};
 
 /// Allow an access key to spend either an unlimited or limited amount of gas
 // This wrapper prevents incorrect construction
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Allowance {
     Unlimited,
     Limited(NonZeroU128),
@@ -29,7 +35,12 @@ impl Allowance {
     }
 }
 
-enum PromiseAction {
+/// An action of a [`Promise`], as added by the promise builder methods of the same name.
+///
+/// Actions are exposed through [`Promise::describe`] to inspect the promises built by a method.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PromiseAction {
     CreateAccount,
     DeployContract {
         code: Vec<u8>,
@@ -62,6 +73,7 @@ enum PromiseAction {
         public_key: PublicKey,
         allowance: Allowance,
         receiver_id: AccountId,
+        /// Comma-separated names of the methods the key can call.
         function_names: String,
         nonce: u64,
     },
@@ -71,10 +83,22 @@ enum PromiseAction {
     DeleteAccount {
         beneficiary_id: AccountId,
     },
+    DeployGlobalContract {
+        code: Vec<u8>,
+    },
+    DeployGlobalContractByAccountId {
+        code: Vec<u8>,
+    },
+    UseGlobalContract {
+        code_hash: CryptoHash,
+    },
+    UseGlobalContractByAccountId {
+        account_id: AccountId,
+    },
 }
 
 impl PromiseAction {
-    pub fn add(&self, promise_index: PromiseIndex) {
+    fn add(&self, promise_index: PromiseIndex) {
         use PromiseAction::*;
         match self {
             CreateAccount => crate::env::promise_batch_action_create_account(promise_index),
@@ -129,6 +153,24 @@ impl PromiseAction {
             DeleteAccount { beneficiary_id } => {
                 crate::env::promise_batch_action_delete_account(promise_index, beneficiary_id)
             }
+            DeployGlobalContract { code } => {
+                crate::env::promise_batch_action_deploy_global_contract(promise_index, code)
+            }
+            DeployGlobalContractByAccountId { code } => {
+                crate::env::promise_batch_action_deploy_global_contract_by_account_id(
+                    promise_index,
+                    code,
+                )
+            }
+            UseGlobalContract { code_hash } => {
+                crate::env::promise_batch_action_use_global_contract(promise_index, code_hash)
+            }
+            UseGlobalContractByAccountId { account_id } => {
+                crate::env::promise_batch_action_use_global_contract_by_account_id(
+                    promise_index,
+                    account_id,
+                )
+            }
         }
     }
 }
@@ -223,6 +265,21 @@ impl PromiseJoint {
 ///   .add_full_access_key(env::signer_account_pk());
 /// ```
 ///
+/// # Execution order
+///
+/// The protocol only guarantees the following about the order in which receipts are executed:
+/// * A promise scheduled with [`then`](Promise::then) is executed after all the promises it
+///   depends on have finished, whether they succeeded or failed.
+/// * The actions of a single promise are executed in the order they were added, atomically.
+///
+/// Sibling promises, e.g. promises joined with [`and`](Promise::and) or created independently
+/// by the same method, have no ordering guarantee between each other. They can be executed in any
+/// order, in the same or in different blocks, and receipts created by other transactions can be
+/// executed in between. Callbacks should therefore not rely on the state written by a sibling
+/// promise being there, or on the order in which sibling callbacks update the contract state.
+/// `ReceiptScenario` in `near_sdk::test_utils` executes a scenario of receipts in every order
+/// the protocol allows to surface such assumptions in unit tests.
+///
 /// More information about promises in [NEAR documentation](https://docs.near.org/build/smart-contracts/anatomy/crosscontract#promises)
 pub struct Promise {
     subtype: PromiseSubtype,
@@ -280,7 +337,65 @@ impl Promise {
         self.add_action(PromiseAction::CreateAccount)
     }
 
+    /// Creates the account `account_id`, returning a batch of actions on the new account in which
+    /// the account is created before it is used, see [`NewAccountPromise`].
+    /// Uses low-level [`crate::env::promise_batch_action_create_account`]
+    pub fn new_account(account_id: AccountId) -> NewAccountPromise {
+        NewAccountPromise { promise: Self::new(account_id).create_account() }
+    }
+
+    /// Creates the subaccount `name` of the current account with the contract `code`, initialized
+    /// with a call of `init_function` with `init_arguments`, returning the ID of the account and
+    /// the promise creating it.
+    ///
+    /// The account is funded with [`env::account_min_balance`](crate::env::account_min_balance)
+    /// of the code and `state_usage`, the bytes of data the init method writes, which is deducted
+    /// from the balance of the current account.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid subaccount name.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use near_sdk::{near, AccountId, Promise};
+    ///
+    /// #[near(contract_state)]
+    /// #[derive(Default)]
+    /// pub struct Factory {}
+    ///
+    /// #[near]
+    /// impl Factory {
+    ///     pub fn create_token(&mut self, name: String) -> AccountId {
+    ///         # let code = vec![];
+    ///         let (account_id, _) =
+    ///             Promise::create_subaccount_funded(&name, code, "new_default_meta", vec![], 1_000);
+    ///         account_id
+    ///     }
+    /// }
+    /// ```
+    pub fn create_subaccount_funded(
+        name: &str,
+        code: Vec<u8>,
+        init_function: &str,
+        init_arguments: Vec<u8>,
+        state_usage: StorageUsage,
+    ) -> (AccountId, Promise) {
+        let account_id: AccountId = format!("{}.{}", name, crate::env::current_account_id())
+            .parse()
+            .unwrap_or_else(|_| crate::env::panic_str("Invalid subaccount name"));
+        require!(!name.contains('.'), "Invalid subaccount name");
+        let balance = crate::env::account_min_balance(code.len() as u64, state_usage);
+        let promise = Self::new_account(account_id.clone()).transfer(balance).deploy_and_init(
+            code,
+            init_function,
+            init_arguments,
+        );
+        (account_id, promise)
+    }
+
     /// Deploy a smart contract to the account on which this promise acts.
+    /// Use [`Promise::new_account`] to deploy a contract to a new account.
     /// Uses low-level [`crate::env::promise_batch_action_deploy_contract`]
     pub fn deploy_contract(self, code: Vec<u8>) -> Self {
         self.add_action(PromiseAction::DeployContract { code })
@@ -319,6 +434,26 @@ impl Promise {
         })
     }
 
+    /// Makes a function call to the account that this promise acts on, with no static gas and a
+    /// weight of 1 on the unused gas of the current call. The unused gas is split between the
+    /// function calls scheduled by the current call in proportion to their weights, so a chain of
+    /// [`Promise::then`] calls gets all the gas left without hardcoding amounts of gas.
+    /// Use [`Promise::function_call_weight`] to reserve a static floor of gas or change the weight.
+    pub fn function_call_unused_gas(
+        self,
+        function_name: String,
+        arguments: Vec<u8>,
+        amount: NearToken,
+    ) -> Self {
+        self.function_call_weight(
+            function_name,
+            arguments,
+            amount,
+            Gas::from_gas(0),
+            GasWeight::default(),
+        )
+    }
+
     /// Transfer tokens to the account that this promise acts on.
     /// Uses low-level [`crate::env::promise_batch_action_transfer`]
     pub fn transfer(self, amount: NearToken) -> Self {
@@ -425,6 +560,35 @@ impl Promise {
         self.add_action(PromiseAction::DeleteAccount { beneficiary_id })
     }
 
+    /// Deploy a global contract identified by the hash of `code`, which accounts can use with
+    /// [`Promise::use_global_contract`] without storing the code themselves.
+    /// Uses low-level [`crate::env::promise_batch_action_deploy_global_contract`]
+    pub fn deploy_global_contract(self, code: Vec<u8>) -> Self {
+        self.add_action(PromiseAction::DeployGlobalContract { code })
+    }
+
+    /// Deploy a global contract identified by the account on which this promise acts, which
+    /// accounts can use with [`Promise::use_global_contract_by_account_id`]. Deploying a new
+    /// version upgrades the contract of all the accounts using it.
+    /// Uses low-level [`crate::env::promise_batch_action_deploy_global_contract_by_account_id`]
+    pub fn deploy_global_contract_by_account_id(self, code: Vec<u8>) -> Self {
+        self.add_action(PromiseAction::DeployGlobalContractByAccountId { code })
+    }
+
+    /// Use the global contract with the hash `code_hash` as the contract of the account on which
+    /// this promise acts.
+    /// Uses low-level [`crate::env::promise_batch_action_use_global_contract`]
+    pub fn use_global_contract(self, code_hash: impl Into<CryptoHash>) -> Self {
+        self.add_action(PromiseAction::UseGlobalContract { code_hash: code_hash.into() })
+    }
+
+    /// Use the global contract deployed by `account_id` as the contract of the account on which
+    /// this promise acts.
+    /// Uses low-level [`crate::env::promise_batch_action_use_global_contract_by_account_id`]
+    pub fn use_global_contract_by_account_id(self, account_id: AccountId) -> Self {
+        self.add_action(PromiseAction::UseGlobalContractByAccountId { account_id })
+    }
+
     /// Merge this promise with another promise, so that we can schedule execution of another
     /// smart contract right after all merged promises finish.
     ///
@@ -450,6 +614,56 @@ impl Promise {
         }
     }
 
+    /// Joins a tuple of promises, so that a promise scheduled after the joined promise with
+    /// [`then`](Self::then) runs once all of them finish, and receives their results in the same
+    /// order. This is equivalent to chaining them with [`and`](Self::and).
+    ///
+    /// A callback can read the results of the joined promises as a tuple, each deserialized to
+    /// its own type, with a `#[callback_join]` argument:
+    ///
+    /// ```
+    /// # use near_sdk::json_types::U128;
+    /// # use near_sdk::{env, ext_contract, near, AccountId, Promise, PromiseError};
+    /// #[ext_contract(ext_ft)]
+    /// pub trait FungibleToken {
+    ///     fn ft_balance_of(&self, account_id: AccountId) -> U128;
+    ///     fn ft_metadata(&self) -> String;
+    /// }
+    ///
+    /// #[near(contract_state)]
+    /// #[derive(Default)]
+    /// pub struct Aggregator {}
+    ///
+    /// #[near]
+    /// impl Aggregator {
+    ///     pub fn balances(&self, token_a: AccountId, token_b: AccountId) -> Promise {
+    ///         let account_id = env::predecessor_account_id();
+    ///         Promise::join((
+    ///             ext_ft::ext(token_a.clone()).ft_balance_of(account_id.clone()),
+    ///             ext_ft::ext(token_b).ft_balance_of(account_id),
+    ///             ext_ft::ext(token_a).ft_metadata(),
+    ///         ))
+    ///         .then(Self::ext(env::current_account_id()).on_balances())
+    ///     }
+    ///
+    ///     #[private]
+    ///     pub fn on_balances(
+    ///         &self,
+    ///         #[callback_join] results: (
+    ///             Result<U128, PromiseError>,
+    ///             Result<U128, PromiseError>,
+    ///             Result<String, PromiseError>,
+    ///         ),
+    ///     ) -> U128 {
+    ///         let (a, b, _metadata) = results;
+    ///         U128(a.map_or(0, |a| a.0) + b.map_or(0, |b| b.0))
+    ///     }
+    /// }
+    /// ```
+    pub fn join<P: PromiseTuple>(promises: P) -> Promise {
+        promises.join()
+    }
+
     /// Schedules execution of another promise right after the current promise finish executing.
     ///
     /// In the following code `bob_near` and `dave_near` will be created concurrently. `carol_near`
@@ -513,6 +727,56 @@ impl Promise {
         self
     }
 
+    /// Returns the description of the promises scheduled when this promise is dropped: their
+    /// receivers, actions and dependencies. Describing a promise doesn't schedule it.
+    ///
+    /// This allows unit tests to check the promises built by a method, or by a helper composing
+    /// promises, without going through the receipts recorded by the mocked blockchain.
+    ///
+    /// ```
+    /// use near_sdk::{Gas, NearToken, Promise, PromiseAction, PromiseDescription};
+    ///
+    /// let promise = Promise::new("bob.near".parse().unwrap())
+    ///     .transfer(NearToken::from_near(1))
+    ///     .then(Promise::new("alice.near".parse().unwrap()).function_call(
+    ///         "on_transfer".to_string(),
+    ///         vec![],
+    ///         NearToken::from_near(0),
+    ///         Gas::from_tgas(5),
+    ///     ));
+    ///
+    /// match promise.describe() {
+    ///     PromiseDescription::Single { receiver_id, actions, after } => {
+    ///         assert_eq!(receiver_id.as_str(), "alice.near");
+    ///         assert!(matches!(&actions[..], [PromiseAction::FunctionCall { .. }]));
+    ///         let after = after.unwrap();
+    ///         assert_eq!(
+    ///             *after,
+    ///             PromiseDescription::Single {
+    ///                 receiver_id: "bob.near".parse().unwrap(),
+    ///                 actions: vec![PromiseAction::Transfer { amount: NearToken::from_near(1) }],
+    ///                 after: None,
+    ///             }
+    ///         );
+    ///     }
+    ///     PromiseDescription::Joint(..) => unreachable!(),
+    /// }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn describe(&self) -> PromiseDescription {
+        match &self.subtype {
+            PromiseSubtype::Single(x) => PromiseDescription::Single {
+                receiver_id: x.account_id.clone(),
+                actions: x.actions.borrow().clone(),
+                after: x.after.borrow().as_ref().map(|after| Box::new(after.describe())),
+            },
+            PromiseSubtype::Joint(x) => PromiseDescription::Joint(
+                Box::new(x.promise_a.describe()),
+                Box::new(x.promise_b.describe()),
+            ),
+        }
+    }
+
     fn construct_recursively(&self) -> PromiseIndex {
         let res = match &self.subtype {
             PromiseSubtype::Single(x) => x.construct_recursively(),
@@ -525,6 +789,152 @@ impl Promise {
     }
 }
 
+/// Batch of actions creating a new account, returned by [`Promise::new_account`].
+///
+/// The batch starts with the creation of the account, so actions which need the account to exist,
+/// such as deploying a contract, are checked at compile time to come after it. Deploying the
+/// contract ends the batch, which can then be continued as a [`Promise`].
+///
+/// # Examples
+/// ```no_run
+/// use near_sdk::{env, AccountId, NearToken, Promise};
+///
+/// # let code = vec![];
+/// let account_id: AccountId = format!("app.{}", env::current_account_id()).parse().unwrap();
+/// Promise::new_account(account_id)
+///     .transfer(NearToken::from_near(5))
+///     .add_full_access_key(env::signer_account_pk())
+///     .deploy_and_init(code, "new", b"{}".to_vec());
+/// ```
+#[must_use]
+pub struct NewAccountPromise {
+    promise: Promise,
+}
+
+impl NewAccountPromise {
+    /// Transfers `amount` to the new account, see [`Promise::transfer`].
+    pub fn transfer(self, amount: NearToken) -> Self {
+        Self { promise: self.promise.transfer(amount) }
+    }
+
+    /// Adds a full access key to the new account, see [`Promise::add_full_access_key`].
+    pub fn add_full_access_key(self, public_key: PublicKey) -> Self {
+        Self { promise: self.promise.add_full_access_key(public_key) }
+    }
+
+    /// Adds a function call access key to the new account, see
+    /// [`Promise::add_access_key_allowance`].
+    pub fn add_access_key_allowance(
+        self,
+        public_key: PublicKey,
+        allowance: Allowance,
+        receiver_id: AccountId,
+        function_names: String,
+    ) -> Self {
+        Self {
+            promise: self.promise.add_access_key_allowance(
+                public_key,
+                allowance,
+                receiver_id,
+                function_names,
+            ),
+        }
+    }
+
+    /// Deploys `code` to the new account, see [`Promise::deploy_contract`].
+    pub fn deploy_contract(self, code: Vec<u8>) -> Promise {
+        self.promise.deploy_contract(code)
+    }
+
+    /// Uses the global contract with the hash `code_hash` as the contract of the new account, see
+    /// [`Promise::use_global_contract`].
+    pub fn use_global_contract(self, code_hash: impl Into<CryptoHash>) -> Promise {
+        self.promise.use_global_contract(code_hash)
+    }
+
+    /// Uses the global contract deployed by `account_id` as the contract of the new account, see
+    /// [`Promise::use_global_contract_by_account_id`].
+    pub fn use_global_contract_by_account_id(self, account_id: AccountId) -> Promise {
+        self.promise.use_global_contract_by_account_id(account_id)
+    }
+
+    /// Deploys `code` to the new account and calls its init method `function_name` with
+    /// `arguments`, with gas as in [`Promise::function_call_unused_gas`]. A deposit of the init
+    /// method can be transferred beforehand with [`NewAccountPromise::transfer`].
+    pub fn deploy_and_init(
+        self,
+        code: Vec<u8>,
+        function_name: impl Into<String>,
+        arguments: Vec<u8>,
+    ) -> Promise {
+        self.deploy_contract(code).function_call_unused_gas(
+            function_name.into(),
+            arguments,
+            NearToken::from_yoctonear(0),
+        )
+    }
+
+    /// Returns the batch as a [`Promise`], to add actions which do not need to be checked.
+    pub fn into_promise(self) -> Promise {
+        self.promise
+    }
+}
+
+impl From<NewAccountPromise> for Promise {
+    fn from(promise: NewAccountPromise) -> Self {
+        promise.promise
+    }
+}
+
+/// Tuples of 2 to 8 promises, which can be joined with [`Promise::join`].
+pub trait PromiseTuple: sealed::Sealed {
+    /// Joins the promises of the tuple in order.
+    fn join(self) -> Promise;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_promise_tuple {
+    ($($promise:ident),+) => {
+        impl sealed::Sealed for ($(impl_promise_tuple!(@promise $promise),)+) {}
+
+        impl PromiseTuple for ($(impl_promise_tuple!(@promise $promise),)+) {
+            fn join(self) -> Promise {
+                let ($($promise,)+) = self;
+                impl_promise_tuple!(@and $($promise),+)
+            }
+        }
+    };
+    (@promise $promise:ident) => { Promise };
+    (@and $first:ident $(, $rest:ident)*) => { $first$(.and($rest))* };
+}
+
+impl_promise_tuple!(p0, p1);
+impl_promise_tuple!(p0, p1, p2);
+impl_promise_tuple!(p0, p1, p2, p3);
+impl_promise_tuple!(p0, p1, p2, p3, p4);
+impl_promise_tuple!(p0, p1, p2, p3, p4, p5);
+impl_promise_tuple!(p0, p1, p2, p3, p4, p5, p6);
+impl_promise_tuple!(p0, p1, p2, p3, p4, p5, p6, p7);
+
+/// Description of a [`Promise`] and the promises it depends on, returned by
+/// [`Promise::describe`].
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PromiseDescription {
+    /// Batch of `actions` on `receiver_id`, executed after the promise `after` if any, see
+    /// [`Promise::then`].
+    Single {
+        receiver_id: AccountId,
+        actions: Vec<PromiseAction>,
+        after: Option<Box<PromiseDescription>>,
+    },
+    /// Promises executed concurrently, see [`Promise::and`].
+    Joint(Box<PromiseDescription>, Box<PromiseDescription>),
+}
+
 impl Drop for Promise {
     fn drop(&mut self) {
         self.construct_recursively();
@@ -632,6 +1042,303 @@ impl<T: schemars::JsonSchema> schemars::JsonSchema for PromiseOrValue<T> {
     }
 }
 
+/// Function call re-issued when it fails, up to a maximum number of attempts.
+///
+/// The call is scheduled with [`PromiseRetry::schedule`], followed by a callback taking the
+/// `PromiseRetry` as an argument along with the result of the call. The `PromiseRetry` counts the
+/// attempts made so far, so the callback passes the result to [`PromiseRetry::resolve`], which
+/// re-issues the call with the same callback if it failed and attempts are left, or returns the
+/// final result otherwise.
+///
+/// # Examples
+/// ```no_run
+/// use near_sdk::json_types::U128;
+/// use near_sdk::{env, near, AccountId, Promise, PromiseError, PromiseOrValue};
+/// use near_sdk::{PromiseRetry, RetryOutcome};
+///
+/// #[near(contract_state)]
+/// #[derive(Default)]
+/// pub struct Contract {
+///     price: Option<U128>,
+/// }
+///
+/// fn on_price(retry: PromiseRetry) -> Promise {
+///     Contract::ext(env::current_account_id()).on_price(retry)
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     pub fn update_price(&mut self, oracle: AccountId) -> Promise {
+///         PromiseRetry::new(oracle, "get_price", vec![], 3).schedule(on_price)
+///     }
+///
+///     #[private]
+///     pub fn on_price(
+///         &mut self,
+///         retry: PromiseRetry,
+///         #[callback_result] price: Result<U128, PromiseError>,
+///     ) -> PromiseOrValue<bool> {
+///         match retry.resolve(price, on_price) {
+///             RetryOutcome::Retrying(promise) => promise.into(),
+///             RetryOutcome::Done(price) => {
+///                 self.price = price.ok();
+///                 PromiseOrValue::Value(self.price.is_some())
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[near(inside_nearsdk, serializers=[json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PromiseRetry {
+    receiver_id: AccountId,
+    function_name: String,
+    arguments: Base64VecU8,
+    deposit: NearToken,
+    static_gas: Gas,
+    gas_weight: GasWeight,
+    max_attempts: u32,
+    attempts: u32,
+}
+
+impl PromiseRetry {
+    /// Creates a call of `function_name` of `receiver_id` with `arguments`, attempted at most
+    /// `max_attempts` times, with no deposit and gas as in [`Promise::function_call_unused_gas`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_attempts` is zero.
+    pub fn new(
+        receiver_id: AccountId,
+        function_name: impl Into<String>,
+        arguments: Vec<u8>,
+        max_attempts: u32,
+    ) -> Self {
+        require!(max_attempts > 0, "The call has to be attempted at least once");
+        Self {
+            receiver_id,
+            function_name: function_name.into(),
+            arguments: arguments.into(),
+            deposit: NearToken::from_yoctonear(0),
+            static_gas: Gas::from_gas(0),
+            gas_weight: GasWeight::default(),
+            max_attempts,
+            attempts: 0,
+        }
+    }
+
+    /// Attaches `amount` to each attempt of the call.
+    pub fn with_attached_deposit(mut self, amount: NearToken) -> Self {
+        self.deposit = amount;
+        self
+    }
+
+    /// Sets the static gas and the weight of the unused gas of each attempt of the call, see
+    /// [`Promise::function_call_weight`].
+    pub fn with_gas(mut self, static_gas: Gas, gas_weight: GasWeight) -> Self {
+        self.static_gas = static_gas;
+        self.gas_weight = gas_weight;
+        self
+    }
+
+    /// Returns the number of attempts of the call made so far.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Returns the maximum number of attempts of the call.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Schedules the next attempt of the call followed by the promise returned by `callback`,
+    /// which is passed the `PromiseRetry` of the attempt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if all the attempts were made.
+    pub fn schedule<F>(mut self, callback: F) -> Promise
+    where
+        F: FnOnce(PromiseRetry) -> Promise,
+    {
+        require!(self.attempts < self.max_attempts, "No attempts of the call are left");
+        self.attempts += 1;
+        let call = Promise::new(self.receiver_id.clone()).function_call_weight(
+            self.function_name.clone(),
+            self.arguments.0.clone(),
+            self.deposit,
+            self.static_gas,
+            self.gas_weight,
+        );
+        call.then(callback(self))
+    }
+
+    /// Handles the `result` of the last attempt of the call: if it failed and attempts are left,
+    /// schedules the next attempt followed by `callback` as in [`PromiseRetry::schedule`],
+    /// otherwise returns the result.
+    pub fn resolve<T, F>(self, result: Result<T, PromiseError>, callback: F) -> RetryOutcome<T>
+    where
+        F: FnOnce(PromiseRetry) -> Promise,
+    {
+        match result {
+            Err(PromiseError::Failed) if self.attempts < self.max_attempts => {
+                RetryOutcome::Retrying(self.schedule(callback))
+            }
+            result => RetryOutcome::Done(result),
+        }
+    }
+}
+
+/// Outcome of an attempt of a [`PromiseRetry`] call, returned by [`PromiseRetry::resolve`].
+#[must_use]
+pub enum RetryOutcome<T> {
+    /// The attempt failed and the call was re-issued, followed by the callback.
+    Retrying(Promise),
+    /// The attempt succeeded, or it failed and no attempts are left.
+    Done(Result<T, PromiseError>),
+}
+
+/// Handle of a yielded promise, which calls a method of the current account once it is resumed
+/// with [`YieldedPromise::resume`], cancelled with [`YieldedPromise::cancel`], or once it times out
+/// after `yield_timeout_length_in_blocks` blocks, see [`env::promise_yield_create`].
+///
+/// The handle is stored in the contract state to resume or cancel the promise from another call.
+/// The callback method takes the outcome as a `#[callback_result]` argument of type
+/// `Result<YieldOutcome<T>, PromiseError>`, where `T` is the type of the data of
+/// [`YieldedPromise::resume`], and converts it to a [`YieldOutcome`], in which a timeout is
+/// [`YieldOutcome::TimedOut`]. Since the callback is called in every case, it is the place to
+/// clean up the state of the request.
+///
+/// [`env::promise_yield_create`]: crate::env::promise_yield_create
+///
+/// # Examples
+/// ```no_run
+/// use near_sdk::store::LookupMap;
+/// use near_sdk::{env, near, serde_json, Gas, GasWeight, PromiseError};
+/// use near_sdk::{YieldOutcome, YieldedPromise};
+///
+/// #[near(contract_state)]
+/// pub struct Contract {
+///     requests: LookupMap<String, YieldedPromise>,
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     pub fn sign(&mut self, payload: String) {
+///         let arguments = serde_json::json!({ "payload": payload }).to_string().into_bytes();
+///         let (request, promise) =
+///             YieldedPromise::new("on_signature", &arguments, Gas::from_tgas(10), GasWeight(0));
+///         self.requests.insert(payload, request);
+///         env::promise_return(promise);
+///     }
+///
+///     pub fn respond(&mut self, payload: String, signature: String) {
+///         if let Some(request) = self.requests.get(&payload) {
+///             request.resume(&signature);
+///         }
+///     }
+///
+///     pub fn abort(&mut self, payload: String) {
+///         if let Some(request) = self.requests.get(&payload) {
+///             request.cancel("aborted by the owner");
+///         }
+///     }
+///
+///     #[private]
+///     pub fn on_signature(
+///         &mut self,
+///         payload: String,
+///         #[callback_result] outcome: Result<YieldOutcome<String>, PromiseError>,
+///     ) -> Option<String> {
+///         self.requests.remove(&payload);
+///         match YieldOutcome::from(outcome) {
+///             YieldOutcome::Resumed(signature) => Some(signature),
+///             YieldOutcome::Cancelled(_) | YieldOutcome::TimedOut => None,
+///         }
+///     }
+/// }
+/// # impl Default for Contract {
+/// #     fn default() -> Self {
+/// #         Self { requests: LookupMap::new(b"r") }
+/// #     }
+/// # }
+/// ```
+#[near(inside_nearsdk, serializers=[borsh, json])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct YieldedPromise {
+    data_id: Base58CryptoHash,
+}
+
+impl YieldedPromise {
+    /// Creates a yielded promise calling `function_name` of the current account with `arguments`
+    /// and the outcome of the promise, returning its handle and the index of the promise, which
+    /// can be returned with [`env::promise_return`](crate::env::promise_return).
+    /// Uses low-level [`crate::env::promise_yield_create`]
+    pub fn new(
+        function_name: &str,
+        arguments: &[u8],
+        gas: Gas,
+        weight: GasWeight,
+    ) -> (Self, PromiseIndex) {
+        const DATA_ID_REGISTER: u64 = 0;
+        let promise_index = crate::env::promise_yield_create(
+            function_name,
+            arguments,
+            gas,
+            weight,
+            DATA_ID_REGISTER,
+        );
+        let data_id: CryptoHash = crate::env::read_register(DATA_ID_REGISTER)
+            .and_then(|data_id| data_id.try_into().ok())
+            .unwrap_or_else(|| crate::env::abort());
+        (Self { data_id: data_id.into() }, promise_index)
+    }
+
+    /// Returns the resumption token of the promise.
+    pub fn data_id(&self) -> CryptoHash {
+        self.data_id.into()
+    }
+
+    /// Resumes the promise, calling its callback with [`YieldOutcome::Resumed`] of `data`.
+    /// Returns `false` if the promise was not found, e.g. because it timed out.
+    /// Uses low-level [`crate::env::promise_yield_resume`]
+    pub fn resume<T: serde::Serialize>(&self, data: &T) -> bool {
+        self.resume_with(&YieldOutcome::Resumed(data))
+    }
+
+    /// Cancels the promise, calling its callback with [`YieldOutcome::Cancelled`] of `reason`
+    /// instead of waiting for it to time out. Returns `false` if the promise was not found.
+    /// Uses low-level [`crate::env::promise_yield_resume`]
+    pub fn cancel(&self, reason: &str) -> bool {
+        self.resume_with(&YieldOutcome::<()>::Cancelled(reason.to_string()))
+    }
+
+    fn resume_with<T: serde::Serialize>(&self, outcome: &YieldOutcome<T>) -> bool {
+        let data = serde_json::to_vec(outcome)
+            .unwrap_or_else(|_| crate::env::panic_str("Failed to serialize the yield outcome"));
+        crate::env::promise_yield_resume(&self.data_id(), &data)
+    }
+}
+
+/// Outcome of a [`YieldedPromise`], passed to its callback.
+#[near(inside_nearsdk, serializers=[json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum YieldOutcome<T> {
+    /// The promise was resumed with the data.
+    Resumed(T),
+    /// The promise was cancelled for the reason.
+    Cancelled(String),
+    /// The promise timed out before being resumed or cancelled.
+    TimedOut,
+}
+
+impl<T> From<Result<YieldOutcome<T>, PromiseError>> for YieldOutcome<T> {
+    /// Converts the callback result of a [`YieldedPromise`], which fails if the promise timed out.
+    fn from(result: Result<YieldOutcome<T>, PromiseError>) -> Self {
+        result.unwrap_or(YieldOutcome::TimedOut)
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
@@ -639,8 +1346,8 @@ mod tests {
     use crate::test_utils::get_created_receipts;
     use crate::test_utils::test_env::{alice, bob};
     use crate::{
-        test_utils::VMContextBuilder, testing_env, AccountId, Allowance, NearToken, Promise,
-        PublicKey,
+        test_utils::VMContextBuilder, testing_env, AccountId, Allowance, CryptoHash, Gas,
+        GasWeight, NearToken, Promise, PublicKey,
     };
 
     fn pk() -> PublicKey {
@@ -860,4 +1567,231 @@ mod tests {
         });
         assert!(has_action);
     }
+
+    #[test]
+    fn test_describe() {
+        use crate::{PromiseAction, PromiseDescription};
+
+        testing_env!(VMContextBuilder::new().signer_account_id(alice()).build());
+
+        let create = Promise::new(alice()).create_account();
+        let transfer = Promise::new(bob()).transfer(NearToken::from_yoctonear(1));
+        let promise = create.and(transfer).then(Promise::new(bob()).delete_key(pk()));
+
+        let expected = PromiseDescription::Single {
+            receiver_id: bob(),
+            actions: vec![PromiseAction::DeleteKey { public_key: pk() }],
+            after: Some(Box::new(PromiseDescription::Joint(
+                Box::new(PromiseDescription::Single {
+                    receiver_id: alice(),
+                    actions: vec![PromiseAction::CreateAccount],
+                    after: None,
+                }),
+                Box::new(PromiseDescription::Single {
+                    receiver_id: bob(),
+                    actions: vec![PromiseAction::Transfer { amount: NearToken::from_yoctonear(1) }],
+                    after: None,
+                }),
+            ))),
+        };
+        assert_eq!(promise.describe(), expected);
+        // Nothing is scheduled until the promise is dropped.
+        assert!(crate::test_utils::get_created_receipts().is_empty());
+    }
+
+    #[test]
+    fn test_function_call_unused_gas() {
+        use crate::{PromiseAction, PromiseDescription};
+
+        testing_env!(VMContextBuilder::new().signer_account_id(alice()).build());
+
+        let promise = Promise::new(bob()).function_call_unused_gas(
+            "get".to_string(),
+            vec![],
+            NearToken::from_yoctonear(0),
+        );
+        let expected = PromiseDescription::Single {
+            receiver_id: bob(),
+            actions: vec![PromiseAction::FunctionCallWeight {
+                function_name: "get".to_string(),
+                arguments: vec![],
+                amount: NearToken::from_yoctonear(0),
+                gas: Gas::from_gas(0),
+                weight: GasWeight(1),
+            }],
+            after: None,
+        };
+        assert_eq!(promise.describe(), expected);
+    }
+
+    #[test]
+    fn test_retry() {
+        use crate::{PromiseDescription, PromiseError, PromiseRetry, RetryOutcome};
+
+        testing_env!(VMContextBuilder::new().current_account_id(alice()).build());
+
+        let callback = |retry: PromiseRetry| {
+            Promise::new(alice()).function_call_unused_gas(
+                "on_get".to_string(),
+                serde_json::to_vec(&serde_json::json!({ "retry": retry })).unwrap(),
+                NearToken::from_yoctonear(0),
+            )
+        };
+        let retry = PromiseRetry::new(bob(), "get", vec![], 2);
+        let PromiseDescription::Single { after: Some(call), .. } =
+            retry.clone().schedule(callback).describe()
+        else {
+            panic!("the call is not followed by the callback");
+        };
+        assert!(
+            matches!(*call, PromiseDescription::Single { receiver_id, .. } if receiver_id == bob())
+        );
+
+        // The callback of the first attempt gets the retry with one attempt made.
+        let retry: PromiseRetry = serde_json::from_str(
+            &serde_json::to_string(&PromiseRetry { attempts: 1, ..retry }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(retry.attempts(), 1);
+        assert!(matches!(retry.clone().resolve(Ok(5), callback), RetryOutcome::Done(Ok(5))));
+        let RetryOutcome::Retrying(_) =
+            retry.resolve(Err::<u8, _>(PromiseError::Failed), |retry| {
+                assert_eq!(retry.attempts(), 2);
+                callback(retry)
+            })
+        else {
+            panic!("the failed call is not retried");
+        };
+
+        let retry = PromiseRetry::new(bob(), "get", vec![], 1);
+        let retry = PromiseRetry { attempts: 1, ..retry };
+        assert!(matches!(
+            retry.resolve(Err::<u8, _>(PromiseError::Failed), callback),
+            RetryOutcome::Done(Err(PromiseError::Failed))
+        ));
+    }
+
+    #[test]
+    fn test_new_account() {
+        use crate::{PromiseAction, PromiseDescription};
+
+        testing_env!(VMContextBuilder::new().signer_account_id(alice()).build());
+
+        let promise = Promise::new_account(bob())
+            .transfer(NearToken::from_near(1))
+            .add_full_access_key(pk())
+            .deploy_and_init(vec![1, 2], "new", b"{}".to_vec());
+        let expected = PromiseDescription::Single {
+            receiver_id: bob(),
+            actions: vec![
+                PromiseAction::CreateAccount,
+                PromiseAction::Transfer { amount: NearToken::from_near(1) },
+                PromiseAction::AddFullAccessKey { public_key: pk(), nonce: 0 },
+                PromiseAction::DeployContract { code: vec![1, 2] },
+                PromiseAction::FunctionCallWeight {
+                    function_name: "new".to_string(),
+                    arguments: b"{}".to_vec(),
+                    amount: NearToken::from_yoctonear(0),
+                    gas: Gas::from_gas(0),
+                    weight: GasWeight(1),
+                },
+            ],
+            after: None,
+        };
+        assert_eq!(promise.describe(), expected);
+    }
+
+    #[test]
+    fn test_create_subaccount_funded() {
+        use crate::{PromiseAction, PromiseDescription};
+
+        testing_env!(VMContextBuilder::new().current_account_id(alice()).build());
+
+        let (account_id, promise) =
+            Promise::create_subaccount_funded("app", vec![0; 900], "new", b"{}".to_vec(), 100);
+        assert_eq!(account_id, format!("app.{}", alice()).parse::<AccountId>().unwrap());
+        let PromiseDescription::Single { receiver_id, actions, .. } = promise.describe() else {
+            panic!("the promise is not a single promise");
+        };
+        assert_eq!(receiver_id, account_id);
+        assert_eq!(
+            actions[..2],
+            [
+                PromiseAction::CreateAccount,
+                PromiseAction::Transfer { amount: NearToken::from_millinear(11) },
+            ]
+        );
+        assert_eq!(actions.len(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid subaccount name")]
+    fn test_create_subaccount_funded_invalid_name() {
+        testing_env!(VMContextBuilder::new().current_account_id(alice()).build());
+
+        let _ = Promise::create_subaccount_funded("a.b", vec![], "new", vec![], 0);
+    }
+
+    #[test]
+    fn test_global_contracts() {
+        use crate::{PromiseAction, PromiseDescription};
+
+        testing_env!(VMContextBuilder::new().signer_account_id(alice()).build());
+
+        let deploy = Promise::new(alice()).deploy_global_contract(vec![1, 2]);
+        let instance = Promise::new_account(bob()).use_global_contract([3; 32]);
+        let expected = PromiseDescription::Single {
+            receiver_id: bob(),
+            actions: vec![
+                PromiseAction::CreateAccount,
+                PromiseAction::UseGlobalContract { code_hash: CryptoHash([3; 32]) },
+            ],
+            after: Some(Box::new(PromiseDescription::Single {
+                receiver_id: alice(),
+                actions: vec![PromiseAction::DeployGlobalContract { code: vec![1, 2] }],
+                after: None,
+            })),
+        };
+        let promise = deploy.then(instance);
+        assert_eq!(promise.describe(), expected);
+        // The mocked blockchain cannot schedule global contract actions.
+        std::mem::forget(promise);
+    }
+
+    #[test]
+    fn test_yield_outcome() {
+        use crate::{PromiseError, YieldOutcome};
+
+        let resumed = serde_json::to_string(&YieldOutcome::Resumed("signature")).unwrap();
+        let cancelled =
+            serde_json::to_string(&YieldOutcome::<()>::Cancelled("aborted".into())).unwrap();
+        let outcome =
+            |json: &str| YieldOutcome::<String>::from(Ok(serde_json::from_str(json).unwrap()));
+        assert_eq!(outcome(&resumed), YieldOutcome::Resumed("signature".to_string()));
+        assert_eq!(outcome(&cancelled), YieldOutcome::Cancelled("aborted".to_string()));
+        assert_eq!(YieldOutcome::<String>::from(Err(PromiseError::Failed)), YieldOutcome::TimedOut);
+    }
+
+    #[test]
+    fn test_join() {
+        use crate::{PromiseAction, PromiseDescription};
+
+        testing_env!(VMContextBuilder::new().signer_account_id(alice()).build());
+
+        let single = |account_id: AccountId| PromiseDescription::Single {
+            receiver_id: account_id,
+            actions: vec![PromiseAction::CreateAccount],
+            after: None,
+        };
+        let joined = Promise::join((
+            Promise::new(alice()).create_account(),
+            Promise::new(bob()).create_account(),
+            Promise::new("carol.near".parse().unwrap()).create_account(),
+        ));
+        let expected = PromiseDescription::Joint(
+            Box::new(PromiseDescription::Joint(Box::new(single(alice())), Box::new(single(bob())))),
+            Box::new(single("carol.near".parse().unwrap())),
+        );
+        assert_eq!(joined.describe(), expected);
+    }
 }
@@ -0,0 +1,239 @@
+//! Random numbers derived from [`env::random_seed_array`], with [`ContractRng`].
+//!
+//! # Security model
+//!
+//! The random seed is the same for all the receipts executed in a block, and is derived from the
+//! VRF output of the block producer, so:
+//! - any contract executed in the same block can read the same seed and predict the numbers
+//!   generated from it, given the nonce. A contract making a call that depends on the numbers,
+//!   e.g. entering a lottery, should not let the caller know them before the call is committed;
+//! - the block producer knows the seed before others and could choose to produce the block or not
+//!   depending on the outcome, so the seed is not suited for outcomes worth more than a block
+//!   reward;
+//! - the seed doesn't change within a method call, so the numbers of a call only differ from the
+//!   ones of another call in the same block through the nonce.
+//!
+//! For high-value outcomes, prefer a commit-reveal scheme, where the participants commit to their
+//! secrets before the seed of a later block is combined with them.
+
+use std::ops::{Bound, RangeBounds};
+
+pub use rand_core::RngCore;
+use rand_core::{impls, Error};
+
+use crate::env;
+
+/// Deterministic generator of random numbers, seeded from the random seed of the block and a
+/// nonce provided by the contract, see the [module documentation](self) for its security model.
+///
+/// The nonce should differ for each generator created in a block, e.g. the ID of the game or a
+/// counter stored in the contract, as generators with the same nonce generate the same numbers.
+/// The generator implements [`RngCore`], so it can be used with the `rand` crate.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::rand::ContractRng;
+///
+/// let game_id: u64 = 7;
+/// let mut rng = ContractRng::new(&game_id.to_le_bytes());
+/// let dice = rng.gen_range(1..=6);
+/// assert!((1..=6).contains(&dice));
+///
+/// let prizes = [("car", 1), ("bike", 10), ("nothing", 89)];
+/// let (prize, _) = rng.choose_weighted(&prizes, |(_, weight)| *weight).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContractRng {
+    seed: [u8; 32],
+    counter: u64,
+    block: [u8; 32],
+    index: usize,
+}
+
+impl ContractRng {
+    /// Creates a generator from the random seed of the block and `nonce`.
+    pub fn new(nonce: &[u8]) -> Self {
+        Self::from_seed(env::sha256_array(&[&env::random_seed_array()[..], nonce].concat()))
+    }
+
+    /// Creates a generator from `seed`, e.g. from the secrets of a commit-reveal scheme.
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self { seed, counter: 0, block: [0; 32], index: 32 }
+    }
+
+    /// Returns a number in `range`, uniformly distributed, e.g. `rng.gen_range(1..=6)`.
+    ///
+    /// Unlike the remainder of the division of a random number by the size of the range, the
+    /// numbers are not biased towards the start of the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn gen_range(&mut self, range: impl RangeBounds<u64>) -> u64 {
+        let low = match range.start_bound() {
+            Bound::Included(low) => *low,
+            Bound::Excluded(low) => low.checked_add(1).unwrap_or_else(|| empty_range()),
+            Bound::Unbounded => 0,
+        };
+        let high = match range.end_bound() {
+            Bound::Included(high) => *high,
+            Bound::Excluded(high) => high.checked_sub(1).unwrap_or_else(|| empty_range()),
+            Bound::Unbounded => u64::MAX,
+        };
+        if low > high {
+            empty_range();
+        }
+        let span = match (high - low).checked_add(1) {
+            Some(span) => span,
+            None => return self.next_u64(),
+        };
+        // Largest number after which the numbers would be biased, as `span` doesn't divide 2^64.
+        let zone = u64::MAX - (u64::MAX % span + 1) % span;
+        loop {
+            let value = self.next_u64();
+            if value <= zone {
+                return low + value % span;
+            }
+        }
+    }
+
+    /// Returns an item of `items` chosen with a probability proportional to its `weight`, or
+    /// `None` if the weights sum to zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the weights sum to more than `u64::MAX`.
+    pub fn choose_weighted<'a, T>(
+        &mut self,
+        items: &'a [T],
+        weight: impl Fn(&T) -> u64,
+    ) -> Option<&'a T> {
+        let total = items.iter().try_fold(0u64, |total, item| total.checked_add(weight(item)));
+        let total = total.unwrap_or_else(|| env::panic_str("The weights overflow"));
+        if total == 0 {
+            return None;
+        }
+        let mut target = self.gen_range(0..total);
+        items.iter().find(|item| {
+            let weight = weight(item);
+            if target < weight {
+                true
+            } else {
+                target -= weight;
+                false
+            }
+        })
+    }
+
+    /// Shuffles `items` uniformly, with the Fisher-Yates algorithm.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.gen_range(0..=i as u64) as usize);
+        }
+    }
+
+    /// Generates the next 32 bytes, the sha256 hash of the seed and the counter.
+    fn next_block(&mut self) {
+        self.block = env::sha256_array(&[&self.seed[..], &self.counter.to_le_bytes()].concat());
+        self.counter += 1;
+        self.index = 0;
+    }
+}
+
+impl RngCore for ContractRng {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for byte in dest {
+            if self.index == self.block.len() {
+                self.next_block();
+            }
+            *byte = self.block[self.index];
+            self.index += 1;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+fn empty_range() -> ! {
+    env::panic_str("The range is empty")
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::test_utils::VMContextBuilder;
+    use crate::testing_env;
+
+    #[test]
+    fn deterministic() {
+        testing_env!(VMContextBuilder::new().random_seed([1; 32]).build());
+        let mut a = ContractRng::new(b"game");
+        let mut b = ContractRng::new(b"game");
+        let mut c = ContractRng::new(b"other game");
+        let numbers: Vec<u64> = (0..4).map(|_| a.next_u64()).collect();
+        assert_eq!(numbers, (0..4).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(numbers, (0..4).map(|_| c.next_u64()).collect::<Vec<_>>());
+
+        testing_env!(VMContextBuilder::new().random_seed([2; 32]).build());
+        let mut d = ContractRng::new(b"game");
+        assert_ne!(numbers, (0..4).map(|_| d.next_u64()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn gen_range() {
+        let mut rng = ContractRng::from_seed([0; 32]);
+        let mut counts = [0; 6];
+        for _ in 0..6000 {
+            counts[rng.gen_range(1..=6) as usize - 1] += 1;
+        }
+        assert!(counts.iter().all(|count| (800..1200).contains(count)), "{:?}", counts);
+
+        assert_eq!(rng.gen_range(5..6), 5);
+        assert_eq!(rng.gen_range(u64::MAX..), u64::MAX);
+        rng.gen_range(..);
+    }
+
+    #[test]
+    #[should_panic(expected = "The range is empty")]
+    fn gen_empty_range() {
+        ContractRng::from_seed([0; 32]).gen_range(3..3);
+    }
+
+    #[test]
+    fn choose_weighted() {
+        let mut rng = ContractRng::from_seed([0; 32]);
+        let items = [("never", 0), ("rare", 1), ("common", 9)];
+        let mut rare = 0;
+        for _ in 0..1000 {
+            match rng.choose_weighted(&items, |(_, weight)| *weight).unwrap().0 {
+                "rare" => rare += 1,
+                "common" => {}
+                item => panic!("{} was chosen", item),
+            }
+        }
+        assert!((50..150).contains(&rare), "{}", rare);
+        assert_eq!(rng.choose_weighted(&items[..1], |(_, weight)| *weight), None);
+    }
+
+    #[test]
+    fn shuffle() {
+        let mut rng = ContractRng::from_seed([0; 32]);
+        let mut items: Vec<u32> = (0..10).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..10).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,281 @@
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk_macros::near;
+
+use super::{LookupMap, ERR_INCONSISTENT_STATE};
+use crate::{env, IntoStorageKey};
+
+const ERR_INDEX_OVERFLOW: &str = "Archive index overflow";
+const ERR_ZERO_BATCH_SIZE: &str = "Archive batch size must be positive";
+
+/// An append-only list of records which moves old records from individual storage keys into
+/// batches of records stored under a single key.
+///
+/// Every record takes a storage key of its own when it is [`push`](Self::push)ed, so that recent
+/// records are cheap to read. Records are stamped with the block timestamp they are pushed at,
+/// and [`archive`](Self::archive) moves the batches of `batch_size` consecutive records that are
+/// all older than a threshold into the archive, where each batch is stored as a single borsh
+/// blob. This removes the storage overhead of the key and of the trie record of every archived
+/// record, at the cost of loading the whole batch when reading one of its records. It suits
+/// contracts which have to retain their history indefinitely but mostly read recent records.
+///
+/// Records keep their indices when they are archived, and [`get`](Self::get) reads both recent
+/// and archived records.
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::Archive;
+/// use near_sdk::{test_utils::VMContextBuilder, testing_env};
+///
+/// let mut history = Archive::new(b"h", 2);
+/// testing_env!(VMContextBuilder::new().block_timestamp(100).build());
+/// history.push("created".to_string());
+/// history.push("updated".to_string());
+/// testing_env!(VMContextBuilder::new().block_timestamp(200).build());
+/// history.push("deleted".to_string());
+///
+/// // Only the first batch is older than the threshold.
+/// assert_eq!(history.archive(200, 10), 1);
+/// assert_eq!(history.archived_len(), 2);
+/// assert_eq!(history.get(1), Some(&"updated".to_string()));
+/// assert_eq!(history.timestamp(2), Some(200));
+/// ```
+#[near(inside_nearsdk)]
+pub struct Archive<T>
+where
+    T: BorshSerialize,
+{
+    batch_size: u32,
+    len: u64,
+    /// Number of batches moved to the archive, all records below
+    /// `archived_batches * batch_size` are archived.
+    archived_batches: u64,
+    /// Index -> `(block_timestamp, record)` of the records that are not archived.
+    // ser/de is independent of `T` ser/de, `BorshSerialize`/`BorshDeserialize`/`BorshSchema` bounds removed
+    #[cfg_attr(not(feature = "abi"), borsh(bound(serialize = "", deserialize = "")))]
+    #[cfg_attr(
+        feature = "abi",
+        borsh(bound(serialize = "", deserialize = ""), schema(params = ""))
+    )]
+    recent: LookupMap<u64, (u64, T)>,
+    /// Batch index -> `(block_timestamp, record)` of the records of the batch.
+    // ser/de is independent of `T` ser/de, `BorshSerialize`/`BorshDeserialize`/`BorshSchema` bounds removed
+    #[cfg_attr(not(feature = "abi"), borsh(bound(serialize = "", deserialize = "")))]
+    #[cfg_attr(
+        feature = "abi",
+        borsh(bound(serialize = "", deserialize = ""), schema(params = ""))
+    )]
+    batches: LookupMap<u64, Vec<(u64, T)>>,
+}
+
+impl<T> fmt::Debug for Archive<T>
+where
+    T: BorshSerialize,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Archive")
+            .field("batch_size", &self.batch_size)
+            .field("len", &self.len)
+            .field("archived_batches", &self.archived_batches)
+            .finish()
+    }
+}
+
+impl<T> Archive<T>
+where
+    T: BorshSerialize,
+{
+    /// Create a new empty archive, which archives records in batches of `batch_size`. Prefixes
+    /// storage access with the prefix provided.
+    ///
+    /// This prefix can be anything that implements [`IntoStorageKey`]. The prefix is used when
+    /// storing and looking up values in storage to ensure no collisions with other collections.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::Archive;
+    ///
+    /// let history: Archive<String> = Archive::new(b"h", 64);
+    /// ```
+    pub fn new<S>(prefix: S, batch_size: u32) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        if batch_size == 0 {
+            env::panic_str(ERR_ZERO_BATCH_SIZE);
+        }
+        let mut recent_key = prefix.into_storage_key();
+        let batches_key = [recent_key.as_slice(), b"b"].concat();
+        recent_key.push(b'r');
+        Self {
+            batch_size,
+            len: 0,
+            archived_batches: 0,
+            recent: LookupMap::new(recent_key),
+            batches: LookupMap::new(batches_key),
+        }
+    }
+
+    /// Returns the number of records, archived or not.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no record was pushed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of archived records, which are the records with an index below it.
+    pub fn archived_len(&self) -> u64 {
+        self.archived_batches * u64::from(self.batch_size)
+    }
+
+    /// Returns the number of records in a batch of the archive.
+    pub fn batch_size(&self) -> u32 {
+        self.batch_size
+    }
+
+    /// Appends a record stamped with the current block timestamp, and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index overflows `u64::MAX`.
+    pub fn push(&mut self, value: T) -> u64 {
+        let index = self.len;
+        self.len = index.checked_add(1).unwrap_or_else(|| env::panic_str(ERR_INDEX_OVERFLOW));
+        self.recent.set(index, Some((env::block_timestamp(), value)));
+        index
+    }
+
+    /// Flushes the intermediate values of the archive before this is called when the structure
+    /// is [`Drop`]ed. This will write all modified values to storage but keep all cached values
+    /// in memory.
+    pub fn flush(&mut self) {
+        self.recent.flush();
+        self.batches.flush();
+    }
+}
+
+impl<T> Archive<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    fn record(&self, index: u64) -> Option<&(u64, T)> {
+        if index >= self.len {
+            return None;
+        }
+        if index >= self.archived_len() {
+            return self.recent.get(&index);
+        }
+        let batch_size = u64::from(self.batch_size);
+        let batch = self.batches.get(&(index / batch_size))?;
+        batch.get((index % batch_size) as usize)
+    }
+
+    /// Returns the record at `index`, or `None` if it is out of bounds. Reading an archived
+    /// record loads its whole batch.
+    pub fn get(&self, index: u64) -> Option<&T> {
+        self.record(index).map(|(_, value)| value)
+    }
+
+    /// Returns the block timestamp the record at `index` was pushed at, or `None` if it is out of
+    /// bounds.
+    pub fn timestamp(&self, index: u64) -> Option<u64> {
+        self.record(index).map(|(timestamp, _)| *timestamp)
+    }
+
+    /// Moves the batches of records pushed before the block timestamp `older_than` into the
+    /// archive, at most `max_batches` per call so that large histories can be archived across
+    /// multiple transactions. Only full batches are archived.
+    ///
+    /// Returns the number of archived batches.
+    pub fn archive(&mut self, older_than: u64, max_batches: u32) -> u32 {
+        let batch_size = u64::from(self.batch_size);
+        let mut archived = 0;
+        while archived < max_batches {
+            let start = self.archived_len();
+            let end = start + batch_size;
+            // Records are pushed in block timestamp order, so the last record of the batch is
+            // the most recent one.
+            match self.timestamp(end - 1) {
+                Some(timestamp) if timestamp < older_than => {}
+                _ => break,
+            }
+            let batch: Vec<_> = (start..end)
+                .map(|index| {
+                    self.recent
+                        .remove(&index)
+                        .unwrap_or_else(|| env::panic_str(ERR_INCONSISTENT_STATE))
+                })
+                .collect();
+            self.batches.set(self.archived_batches, Some(batch));
+            self.archived_batches += 1;
+            archived += 1;
+        }
+        archived
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use borsh::{to_vec, BorshDeserialize};
+
+    use super::Archive;
+    use crate::test_utils::VMContextBuilder;
+    use crate::{env, testing_env};
+
+    fn set_block_timestamp(timestamp: u64) {
+        testing_env!(VMContextBuilder::new().block_timestamp(timestamp).build());
+    }
+
+    #[test]
+    fn archive_in_batches() {
+        set_block_timestamp(0);
+        let mut archive = Archive::new(b"a", 3);
+        for i in 0..10u32 {
+            set_block_timestamp(u64::from(i) * 10);
+            assert_eq!(archive.push(i), u64::from(i));
+        }
+
+        assert_eq!(archive.archive(50, 10), 1);
+        assert_eq!(archive.archived_len(), 3);
+        assert_eq!(archive.archive(100, 1), 1);
+        assert_eq!(archive.archive(100, 10), 1);
+        // The last record is not part of a full batch.
+        assert_eq!(archive.archive(u64::MAX, 10), 0);
+        assert_eq!(archive.archived_len(), 9);
+
+        for i in 0..10u32 {
+            assert_eq!(archive.get(u64::from(i)), Some(&i));
+            assert_eq!(archive.timestamp(u64::from(i)), Some(u64::from(i) * 10));
+        }
+        assert_eq!(archive.get(10), None);
+    }
+
+    #[test]
+    fn archived_records_share_storage_keys() {
+        set_block_timestamp(0);
+        let mut archive = Archive::new(b"a", 4);
+        for i in 0..8u8 {
+            archive.push(i);
+        }
+        archive.flush();
+        let usage = env::storage_usage();
+
+        set_block_timestamp(1);
+        assert_eq!(archive.archive(1, 10), 2);
+        archive.flush();
+        assert!(env::storage_usage() < usage);
+
+        let archive = Archive::<u8>::try_from_slice(&to_vec(&archive).unwrap()).unwrap();
+        assert!((0..8).map(|i| archive.get(i).copied()).eq((0..8).map(Some)));
+    }
+}
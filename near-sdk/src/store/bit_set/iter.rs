@@ -0,0 +1,54 @@
+use std::iter::FusedIterator;
+
+use super::{BitSet, WORD_BITS};
+
+/// An iterator over the indices of the bits set in a range of a [`BitSet`], in ascending order.
+///
+/// This `struct` is created by the `iter_ones` method on [`BitSet`].
+#[derive(Clone)]
+pub struct IterOnes<'a> {
+    set: &'a BitSet,
+    /// Index of the word `bits` were read from.
+    word_index: u64,
+    /// Bits of the current word that are left to yield.
+    bits: u64,
+    /// End of the range, exclusive.
+    end: u64,
+}
+
+impl<'a> IterOnes<'a> {
+    pub(super) fn new(set: &'a BitSet, start: u64, end: u64) -> Self {
+        let word_index = start / WORD_BITS;
+        let bits = if start < end { set.word(word_index) & (!0 << (start % WORD_BITS)) } else { 0 };
+        Self { set, word_index, bits, end }
+    }
+}
+
+impl<'a> Iterator for IterOnes<'a> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while self.bits == 0 {
+            let word_start = (self.word_index + 1).checked_mul(WORD_BITS)?;
+            if word_start >= self.end {
+                // Keep returning `None` without loading further words.
+                self.end = 0;
+                return None;
+            }
+            self.word_index += 1;
+            self.bits = self.set.word(self.word_index);
+        }
+
+        let index = self.word_index * WORD_BITS + u64::from(self.bits.trailing_zeros());
+        if index >= self.end {
+            self.bits = 0;
+            self.end = 0;
+            return None;
+        }
+        // Clear the lowest bit set.
+        self.bits &= self.bits - 1;
+        Some(index)
+    }
+}
+
+impl<'a> FusedIterator for IterOnes<'a> {}
@@ -0,0 +1,222 @@
+mod iter;
+
+pub use self::iter::IterOnes;
+
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+
+use near_sdk_macros::near;
+
+use super::LookupMap;
+use crate::{env, IntoStorageKey};
+
+const ERR_INDEX_OVERFLOW: &str = "BitSet index overflow";
+
+/// Number of bits in a word of a [`BitSet`].
+const WORD_BITS: u64 = u64::BITS as u64;
+
+/// A set of `u64` indices stored as bits packed in `u64` words, each word stored under its own
+/// storage key.
+///
+/// This suits dense sets of indices, such as the claimed bitmap of an airdrop distributed by
+/// index, where a [`LookupSet<u64>`](super::LookupSet) would take a storage key per index. Words
+/// with no bits set are removed from storage, so setting few bits of a large index range only
+/// takes storage for the words these bits are in.
+///
+/// The number of bits set is cached with the metadata of the set, so
+/// [`count_ones`](Self::count_ones) does not read storage. [`iter_ones`](Self::iter_ones) reads
+/// every word of the range it iterates over, up to the highest index ever set.
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::BitSet;
+///
+/// let mut claimed = BitSet::new(b"c");
+/// assert!(!claimed.set(3, true));
+/// assert!(!claimed.set(70, true));
+/// assert!(claimed.get(3));
+/// assert!(!claimed.get(4));
+/// assert_eq!(claimed.count_ones(), 2);
+/// assert_eq!(claimed.iter_ones(..).collect::<Vec<_>>(), [3, 70]);
+/// assert_eq!(claimed.iter_ones(4..).collect::<Vec<_>>(), [70]);
+///
+/// // Returns the previous value of the bit.
+/// assert!(claimed.set(3, false));
+/// assert_eq!(claimed.count_ones(), 1);
+/// ```
+#[near(inside_nearsdk)]
+pub struct BitSet {
+    /// One past the highest index ever set, the end of the range iterated by `iter_ones`.
+    len: u64,
+    ones: u64,
+    words: LookupMap<u64, u64>,
+}
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BitSet").field("len", &self.len).field("ones", &self.ones).finish()
+    }
+}
+
+impl BitSet {
+    /// Create a new empty set. Prefixes storage access with the prefix provided.
+    ///
+    /// This prefix can be anything that implements [`IntoStorageKey`]. The prefix is used when
+    /// storing and looking up values in storage to ensure no collisions with other collections.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::BitSet;
+    ///
+    /// let set = BitSet::new(b"b");
+    /// ```
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self { len: 0, ones: 0, words: LookupMap::new(prefix) }
+    }
+
+    /// Returns one past the highest index that was ever set to `true`, even if it was unset
+    /// since.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no bit is set.
+    pub fn is_empty(&self) -> bool {
+        self.ones == 0
+    }
+
+    /// Returns the number of bits set.
+    pub fn count_ones(&self) -> u64 {
+        self.ones
+    }
+
+    fn word(&self, word_index: u64) -> u64 {
+        self.words.get(&word_index).copied().unwrap_or(0)
+    }
+
+    /// Returns the bit at `index`.
+    pub fn get(&self, index: u64) -> bool {
+        self.word(index / WORD_BITS) & (1 << (index % WORD_BITS)) != 0
+    }
+
+    /// Sets the bit at `index` to `value`, and returns its previous value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is `u64::MAX`.
+    pub fn set(&mut self, index: u64, value: bool) -> bool {
+        let word_index = index / WORD_BITS;
+        let mask = 1 << (index % WORD_BITS);
+        let word = self.word(word_index);
+        let previous = word & mask != 0;
+        if previous == value {
+            return previous;
+        }
+
+        let word = if value {
+            let end = index.checked_add(1).unwrap_or_else(|| env::panic_str(ERR_INDEX_OVERFLOW));
+            self.len = self.len.max(end);
+            self.ones += 1;
+            word | mask
+        } else {
+            self.ones -= 1;
+            word & !mask
+        };
+        // Words without bits set are removed to free their storage.
+        self.words.set(word_index, Some(word).filter(|word| *word != 0));
+        previous
+    }
+
+    /// Returns an iterator over the indices of the bits set in `range`, in ascending order.
+    ///
+    /// Every word in the range, up to [`len`](Self::len), is read from storage, so large ranges
+    /// should be iterated over in pages across multiple calls.
+    pub fn iter_ones<R>(&self, range: R) -> IterOnes<'_>
+    where
+        R: RangeBounds<u64>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len,
+        };
+        IterOnes::new(self, start, end.min(self.len))
+    }
+
+    /// Flushes the intermediate values of the set before this is called when the structure is
+    /// [`Drop`]ed. This will write all modified values to storage but keep all cached values in
+    /// memory.
+    pub fn flush(&mut self) {
+        self.words.flush();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use borsh::{to_vec, BorshDeserialize};
+
+    use super::BitSet;
+    use crate::env;
+
+    #[test]
+    fn set_and_iterate() {
+        let mut set = BitSet::new(b"b");
+        for index in [0, 5, 63, 64, 200, 1000] {
+            assert!(!set.set(index, true));
+        }
+        assert!(set.set(64, true));
+        assert_eq!(set.count_ones(), 6);
+        assert_eq!(set.len(), 1001);
+
+        assert_eq!(set.iter_ones(..).collect::<Vec<_>>(), [0, 5, 63, 64, 200, 1000]);
+        assert_eq!(set.iter_ones(5..64).collect::<Vec<_>>(), [5, 63]);
+        assert_eq!(set.iter_ones(6..=200).collect::<Vec<_>>(), [63, 64, 200]);
+        assert_eq!(set.iter_ones(1001..).count(), 0);
+        assert_eq!(set.iter_ones(64..64).count(), 0);
+
+        assert!(set.set(63, false));
+        assert!(!set.set(63, false));
+        assert_eq!(set.count_ones(), 5);
+        assert_eq!(set.iter_ones(..100).collect::<Vec<_>>(), [0, 5, 64]);
+
+        set.flush();
+        let set = BitSet::try_from_slice(&to_vec(&set).unwrap()).unwrap();
+        assert!(set.get(1000));
+        assert!(!set.get(999));
+        assert_eq!(set.count_ones(), 5);
+    }
+
+    #[test]
+    fn empty_words_are_removed() {
+        let mut set = BitSet::new(b"b");
+        set.flush();
+        let usage = env::storage_usage();
+
+        set.set(130, true);
+        set.set(131, true);
+        set.flush();
+        assert!(env::storage_usage() > usage);
+
+        set.set(130, false);
+        set.set(131, false);
+        set.flush();
+        assert_eq!(env::storage_usage(), usage);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "BitSet index overflow")]
+    fn max_index() {
+        BitSet::new(b"b").set(u64::MAX, true);
+    }
+}
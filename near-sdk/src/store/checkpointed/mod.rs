@@ -0,0 +1,186 @@
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk_macros::near;
+
+use super::Vector;
+use crate::{env, BlockHeight, IntoStorageKey};
+
+/// A value which keeps the history of its changes as checkpoints, so that the value at any past
+/// block height can be looked up.
+///
+/// Every [`set`](Self::set) records a `(block_height, value)` checkpoint at the current block
+/// height, replacing the checkpoint of the same block if the value was already set in it.
+/// Checkpoints are stored in a [`Vector`] in increasing block height order, so
+/// [`value_at`](Self::value_at) only loads the `O(log n)` checkpoints probed by a binary search.
+/// This is the usual building block of governance and voting contracts, which count the votes of
+/// an account with its balance at the block the proposal was created in.
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::Checkpointed;
+/// use near_sdk::{test_utils::VMContextBuilder, testing_env};
+///
+/// let mut balance = Checkpointed::new(b"b");
+/// testing_env!(VMContextBuilder::new().block_height(10).build());
+/// balance.set(100u128);
+/// testing_env!(VMContextBuilder::new().block_height(20).build());
+/// balance.set(40);
+///
+/// assert_eq!(balance.latest(), Some(&40));
+/// assert_eq!(balance.value_at(9), None);
+/// assert_eq!(balance.value_at(10), Some(&100));
+/// assert_eq!(balance.value_at(19), Some(&100));
+/// assert_eq!(balance.value_at(25), Some(&40));
+/// ```
+#[near(inside_nearsdk)]
+pub struct Checkpointed<T>
+where
+    T: BorshSerialize,
+{
+    // ser/de is independent of `T` ser/de, `BorshSerialize`/`BorshDeserialize`/`BorshSchema` bounds removed
+    #[cfg_attr(not(feature = "abi"), borsh(bound(serialize = "", deserialize = "")))]
+    #[cfg_attr(
+        feature = "abi",
+        borsh(bound(serialize = "", deserialize = ""), schema(params = ""))
+    )]
+    checkpoints: Vector<(BlockHeight, T)>,
+}
+
+impl<T> fmt::Debug for Checkpointed<T>
+where
+    T: BorshSerialize,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Checkpointed").field("checkpoints", &self.checkpoints.len()).finish()
+    }
+}
+
+impl<T> Checkpointed<T>
+where
+    T: BorshSerialize,
+{
+    /// Create a new value without checkpoints. Prefixes storage access with the prefix provided.
+    ///
+    /// This prefix can be anything that implements [`IntoStorageKey`]. The prefix is used when
+    /// storing and looking up values in storage to ensure no collisions with other collections.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::Checkpointed;
+    ///
+    /// let balance: Checkpointed<u128> = Checkpointed::new(b"b");
+    /// ```
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self { checkpoints: Vector::new(prefix) }
+    }
+
+    /// Returns the number of checkpoints.
+    pub fn len(&self) -> u32 {
+        self.checkpoints.len()
+    }
+
+    /// Returns `true` if the value was never set.
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty()
+    }
+
+    /// Flushes the intermediate checkpoints before this is called when the structure is
+    /// [`Drop`]ed. This will write all modified checkpoints to storage but keep all cached
+    /// checkpoints in memory.
+    pub fn flush(&mut self) {
+        self.checkpoints.flush()
+    }
+}
+
+impl<T> Checkpointed<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    /// Sets the value at the current block height. If the value was already set in the current
+    /// block, its checkpoint is replaced.
+    pub fn set(&mut self, value: T) {
+        let block_height = env::block_height();
+        match self.checkpoints.len().checked_sub(1) {
+            Some(last) if self.checkpoints[last].0 == block_height => {
+                self.checkpoints.set(last, (block_height, value))
+            }
+            _ => self.checkpoints.push((block_height, value)),
+        }
+    }
+
+    /// Returns the current value, i.e. the value of the last checkpoint, or `None` if the value
+    /// was never set.
+    pub fn latest(&self) -> Option<&T> {
+        let last = self.checkpoints.len().checked_sub(1)?;
+        self.checkpoints.get(last).map(|(_, value)| value)
+    }
+
+    /// Returns the value at the end of the block at `block_height`, i.e. the value of the last
+    /// checkpoint at or before `block_height`, or `None` if the value was not set yet.
+    pub fn value_at(&self, block_height: BlockHeight) -> Option<&T> {
+        let index = self.checkpoints.partition_point(|(height, _)| *height <= block_height);
+        self.checkpoints.get(index.checked_sub(1)?).map(|(_, value)| value)
+    }
+
+    /// Returns the block height and value of the checkpoint at `index`, in increasing block
+    /// height order.
+    pub fn checkpoint(&self, index: u32) -> Option<(BlockHeight, &T)> {
+        self.checkpoints.get(index).map(|(height, value)| (*height, value))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use borsh::{to_vec, BorshDeserialize};
+
+    use super::Checkpointed;
+    use crate::test_utils::VMContextBuilder;
+    use crate::testing_env;
+
+    fn at_height(block_height: u64) {
+        testing_env!(VMContextBuilder::new().block_height(block_height).build());
+    }
+
+    #[test]
+    fn value_at_heights() {
+        at_height(5);
+        let mut value = Checkpointed::new(b"c");
+        assert_eq!(value.latest(), None);
+        assert_eq!(value.value_at(100), None);
+
+        for height in [5, 8, 8, 20, 21] {
+            at_height(height);
+            value.set(height * 10);
+        }
+        assert_eq!(value.len(), 4);
+        assert_eq!(value.checkpoint(1), Some((8, &80)));
+        assert_eq!(value.latest(), Some(&210));
+
+        let expected = [(4, None), (5, Some(50)), (7, Some(50)), (8, Some(80)), (19, Some(80))];
+        for (height, expected) in expected {
+            assert_eq!(value.value_at(height).copied(), expected);
+        }
+        assert_eq!(value.value_at(20), Some(&200));
+        assert_eq!(value.value_at(u64::MAX), Some(&210));
+    }
+
+    #[test]
+    fn persists_between_loads() {
+        at_height(1);
+        let mut value = Checkpointed::new(b"c");
+        value.set("a".to_string());
+        at_height(3);
+        value.set("b".to_string());
+        value.flush();
+
+        let value = Checkpointed::<String>::try_from_slice(&to_vec(&value).unwrap()).unwrap();
+        assert_eq!(value.value_at(2).map(String::as_str), Some("a"));
+        assert_eq!(value.latest().map(String::as_str), Some("b"));
+    }
+}
@@ -0,0 +1,264 @@
+mod iter;
+
+pub use self::iter::Iter;
+
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk_macros::near;
+
+use super::LookupMap;
+use crate::{env, IntoStorageKey};
+
+const ERR_INDEX_OVERFLOW: &str = "Event log index overflow";
+
+/// An append-only log that stores its entries directly on the storage trie.
+///
+/// Every appended entry gets the next index of the log, starting from `0`. Indices are never
+/// reused, so they can be used as stable cursors by indexers and clients paging through the
+/// history, for example with [`iter_from`](Self::iter_from). Old entries can be removed from
+/// storage with [`prune`](Self::prune), which moves the first retained index of the log (the
+/// watermark) forward without affecting the indices of the remaining entries.
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::EventLog;
+///
+/// let mut log = EventLog::new(b"l");
+/// assert_eq!(log.append("created".to_string()), 0);
+/// assert_eq!(log.append("updated".to_string()), 1);
+/// assert_eq!(log.append("deleted".to_string()), 2);
+///
+/// let page: Vec<_> = log.iter_from(1).collect();
+/// assert_eq!(page, [(1, &"updated".to_string()), (2, &"deleted".to_string())]);
+///
+/// // Remove the entries below index 2 from storage.
+/// assert!(log.prune(2, 10));
+/// assert_eq!(log.get(0), None);
+/// assert_eq!(log.first_index(), 2);
+/// assert_eq!(log.append("created".to_string()), 3);
+/// ```
+#[near(inside_nearsdk)]
+pub struct EventLog<T>
+where
+    T: BorshSerialize,
+{
+    /// Index of the first entry that has not been pruned.
+    start: u64,
+    /// Index of the next appended entry.
+    end: u64,
+    // ser/de is independent of `T` ser/de, `BorshSerialize`/`BorshDeserialize`/`BorshSchema` bounds removed
+    #[cfg_attr(not(feature = "abi"), borsh(bound(serialize = "", deserialize = "")))]
+    #[cfg_attr(
+        feature = "abi",
+        borsh(bound(serialize = "", deserialize = ""), schema(params = ""))
+    )]
+    entries: LookupMap<u64, T>,
+}
+
+impl<T> fmt::Debug for EventLog<T>
+where
+    T: BorshSerialize,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventLog").field("start", &self.start).field("end", &self.end).finish()
+    }
+}
+
+impl<T> EventLog<T>
+where
+    T: BorshSerialize,
+{
+    /// Create a new empty log. Prefixes storage access with the prefix provided.
+    ///
+    /// This prefix can be anything that implements [`IntoStorageKey`]. The prefix is used when
+    /// storing and looking up values in storage to ensure no collisions with other collections.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::EventLog;
+    ///
+    /// let log: EventLog<String> = EventLog::new(b"l");
+    /// ```
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self { start: 0, end: 0, entries: LookupMap::new(prefix) }
+    }
+
+    /// Returns the number of entries that have not been pruned.
+    pub fn len(&self) -> u64 {
+        self.end - self.start
+    }
+
+    /// Returns `true` if the log has no entries that have not been pruned.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns the index of the first entry that has not been pruned. Equal to
+    /// [`next_index`](Self::next_index) if the log is empty.
+    pub fn first_index(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the index the next appended entry will get.
+    pub fn next_index(&self) -> u64 {
+        self.end
+    }
+
+    /// Appends an entry to the log and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index overflows `u64::MAX`.
+    pub fn append(&mut self, value: T) -> u64 {
+        let index = self.end;
+        self.end = index.checked_add(1).unwrap_or_else(|| env::panic_str(ERR_INDEX_OVERFLOW));
+        self.entries.set(index, Some(value));
+        index
+    }
+
+    /// Removes entries with an index below `watermark` from storage, at most `max_entries` per
+    /// call so that large histories can be pruned across multiple transactions. Entries appended
+    /// after the watermark was passed are never removed.
+    ///
+    /// Returns `true` once all entries below the watermark have been removed.
+    pub fn prune(&mut self, watermark: u64, max_entries: u32) -> bool {
+        let target = watermark.min(self.end);
+        let stop = target.min(self.start.saturating_add(max_entries.into()));
+        for index in self.start..stop {
+            self.entries.set(index, None);
+        }
+        self.start = self.start.max(stop);
+        self.start >= target
+    }
+
+    /// Flushes the intermediate values of the log before this is called when the structure is
+    /// [`Drop`]ed. This will write all modified values to storage but keep all cached values
+    /// in memory.
+    pub fn flush(&mut self) {
+        self.entries.flush()
+    }
+}
+
+impl<T> EventLog<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    /// Returns the entry at `index`, or `None` if it was pruned or not appended yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::EventLog;
+    ///
+    /// let mut log = EventLog::new(b"l");
+    /// log.append(7u8);
+    /// assert_eq!(log.get(0), Some(&7));
+    /// assert_eq!(log.get(1), None);
+    /// ```
+    pub fn get(&self, index: u64) -> Option<&T> {
+        if index < self.start || index >= self.end {
+            return None;
+        }
+        self.entries.get(&index)
+    }
+
+    /// Returns an iterator over the entries that have not been pruned, along with their indices.
+    pub fn iter(&self) -> Iter<T> {
+        Iter::new(self, self.start)
+    }
+
+    /// Returns an iterator over the entries starting at `index`, along with their indices. If
+    /// `index` was pruned, the iterator starts at the first entry that has not been pruned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::EventLog;
+    ///
+    /// let mut log = EventLog::new(b"l");
+    /// log.extend(0u8..10);
+    ///
+    /// let cursor = 8;
+    /// let page: Vec<_> = log.iter_from(cursor).map(|(_, entry)| *entry).collect();
+    /// assert_eq!(page, [8, 9]);
+    /// ```
+    pub fn iter_from(&self, index: u64) -> Iter<T> {
+        Iter::new(self, index)
+    }
+}
+
+impl<T> Extend<T> for EventLog<T>
+where
+    T: BorshSerialize,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for value in iter {
+            self.append(value);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use borsh::{to_vec, BorshDeserialize};
+
+    use super::EventLog;
+
+    #[test]
+    fn append_and_iterate() {
+        let mut log = EventLog::new(b"l");
+        for i in 0..10u32 {
+            assert_eq!(log.append(i), u64::from(i));
+        }
+        assert_eq!(log.len(), 10);
+        assert_eq!(log.get(9), Some(&9));
+        assert_eq!(log.get(10), None);
+
+        assert!(log.iter().map(|(i, v)| (i, *v)).eq((0..10).map(|i| (i, i as u32))));
+        assert!(log.iter_from(7).rev().map(|(i, _)| i).eq([9, 8, 7]));
+        assert_eq!(log.iter_from(10).count(), 0);
+        assert_eq!(log.iter_from(100).count(), 0);
+    }
+
+    #[test]
+    fn prune_in_batches() {
+        let mut log = EventLog::new(b"l");
+        log.extend(0u8..10);
+
+        assert!(!log.prune(6, 4));
+        assert_eq!(log.first_index(), 4);
+        assert!(log.prune(6, 4));
+        assert_eq!(log.first_index(), 6);
+        assert!(log.prune(3, 4));
+        assert_eq!(log.first_index(), 6);
+
+        assert_eq!(log.get(5), None);
+        assert!(log.iter_from(0).map(|(i, _)| i).eq(6..10));
+        assert!(log.prune(u64::MAX, 100));
+        assert!(log.is_empty());
+        assert_eq!(log.append(10), 10);
+    }
+
+    #[test]
+    fn persists_between_loads() {
+        let mut log = EventLog::new(b"l");
+        log.extend(["a".to_string(), "b".to_string(), "c".to_string()]);
+        log.prune(1, 1);
+        log.flush();
+
+        let log = EventLog::<String>::try_from_slice(&to_vec(&log).unwrap()).unwrap();
+        assert_eq!(log.first_index(), 1);
+        assert_eq!(log.next_index(), 3);
+        assert_eq!(log.get(0), None);
+        assert_eq!(log.get(2).map(String::as_str), Some("c"));
+    }
+}
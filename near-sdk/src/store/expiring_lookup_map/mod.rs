@@ -0,0 +1,332 @@
+use std::borrow::Borrow;
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk_macros::near;
+
+use super::{EventLog, LookupMap};
+use crate::{env, IntoStorageKey};
+
+/// Clock the expiration of the entries of an [`ExpiringLookupMap`] is compared against.
+#[near(inside_nearsdk)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Clock {
+    /// [`env::block_timestamp`], in nanoseconds.
+    BlockTimestamp,
+    /// [`env::block_height`].
+    BlockHeight,
+}
+
+impl Clock {
+    fn now(self) -> u64 {
+        match self {
+            Clock::BlockTimestamp => env::block_timestamp(),
+            Clock::BlockHeight => env::block_height(),
+        }
+    }
+}
+
+/// A non-iterable map whose entries expire at a given block timestamp or block height.
+///
+/// Every [`insert`](Self::insert) takes the time at which the entry expires. Expiry is lazy:
+/// expired entries are treated as absent by reads, but stay in storage until they are
+/// overwritten, [`remove`](Self::remove)d, or reclaimed by [`purge`](Self::purge). Purging walks a
+/// queue of the inserted keys a bounded number of entries at a time, so storage can be reclaimed
+/// incrementally, for example by a permissionless method or at the end of other calls. This is
+/// the usual pattern for session keys, price quotes and oracle data.
+///
+/// Maps created with [`new`](Self::new) compare expiration against the block timestamp in
+/// nanoseconds, and maps created with [`with_block_height`](Self::with_block_height) against the
+/// block height.
+///
+/// Keys are stored twice, once in the map and once in the purge queue, so prefer short keys.
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::ExpiringLookupMap;
+/// use near_sdk::{test_utils::VMContextBuilder, testing_env};
+///
+/// let mut quotes = ExpiringLookupMap::with_block_height(b"q");
+/// testing_env!(VMContextBuilder::new().block_height(10).build());
+/// quotes.insert("near-usd".to_string(), 5u64, 20);
+/// assert_eq!(quotes.get("near-usd"), Some(&5));
+///
+/// testing_env!(VMContextBuilder::new().block_height(20).build());
+/// assert_eq!(quotes.get("near-usd"), None);
+/// assert_eq!(quotes.purge(10), 1);
+/// ```
+#[near(inside_nearsdk)]
+pub struct ExpiringLookupMap<K, V>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+{
+    clock: Clock,
+    // ser/de is independent of `K` ser/de, `BorshSerialize`/`BorshDeserialize`/`BorshSchema` bounds removed
+    #[cfg_attr(not(feature = "abi"), borsh(bound(serialize = "", deserialize = "")))]
+    #[cfg_attr(
+        feature = "abi",
+        borsh(bound(serialize = "", deserialize = ""), schema(params = ""))
+    )]
+    entries: LookupMap<K, (u64, V)>,
+    /// `(expires_at, key)` records of the inserted entries, in the order they are purged.
+    // ser/de is independent of `K` ser/de, `BorshSerialize`/`BorshDeserialize`/`BorshSchema` bounds removed
+    #[cfg_attr(not(feature = "abi"), borsh(bound(serialize = "", deserialize = "")))]
+    #[cfg_attr(
+        feature = "abi",
+        borsh(bound(serialize = "", deserialize = ""), schema(params = ""))
+    )]
+    queue: EventLog<(u64, K)>,
+}
+
+impl<K, V> fmt::Debug for ExpiringLookupMap<K, V>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExpiringLookupMap")
+            .field("clock", &self.clock)
+            .field("queue", &self.queue.len())
+            .finish()
+    }
+}
+
+impl<K, V> ExpiringLookupMap<K, V>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+{
+    /// Create a new map whose entries expire at a block timestamp, in nanoseconds. Prefixes
+    /// storage access with the prefix provided.
+    ///
+    /// This prefix can be anything that implements [`IntoStorageKey`]. The prefix is used when
+    /// storing and looking up values in storage to ensure no collisions with other collections.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::ExpiringLookupMap;
+    ///
+    /// let sessions: ExpiringLookupMap<String, u64> = ExpiringLookupMap::new(b"s");
+    /// ```
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self::with_clock(prefix, Clock::BlockTimestamp)
+    }
+
+    /// Create a new map whose entries expire at a block height. Prefixes storage access with the
+    /// prefix provided.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::ExpiringLookupMap;
+    ///
+    /// let quotes: ExpiringLookupMap<String, u64> = ExpiringLookupMap::with_block_height(b"q");
+    /// ```
+    pub fn with_block_height<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self::with_clock(prefix, Clock::BlockHeight)
+    }
+
+    fn with_clock<S>(prefix: S, clock: Clock) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let mut queue_key = prefix.into_storage_key();
+        let map_key = [queue_key.as_slice(), b"m"].concat();
+        queue_key.push(b'q');
+        Self { clock, entries: LookupMap::new(map_key), queue: EventLog::new(queue_key) }
+    }
+
+    /// Returns the number of records in the purge queue, which is an upper bound of the number
+    /// of entries in storage, expired or not.
+    pub fn queue_len(&self) -> u64 {
+        self.queue.len()
+    }
+
+    /// Flushes the intermediate values of the map before this is called when the structure is
+    /// [`Drop`]ed. This will write all modified values to storage but keep all cached values
+    /// in memory.
+    pub fn flush(&mut self) {
+        self.entries.flush();
+        self.queue.flush();
+    }
+}
+
+impl<K, V> ExpiringLookupMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    /// Inserts a key-value pair into the map, expiring at `expires_at`: the entry is absent from
+    /// the block with a timestamp or height of `expires_at` onwards.
+    ///
+    /// Returns the previous value of the key if it had not expired.
+    pub fn insert(&mut self, key: K, value: V, expires_at: u64) -> Option<V> {
+        let now = self.clock.now();
+        let previous = self.entries.insert(key.clone(), (expires_at, value));
+        // The queue record of the previous entry is reused if it expires at the same time.
+        if previous.as_ref().map(|(previous_expiry, _)| *previous_expiry) != Some(expires_at) {
+            self.queue.append((expires_at, key));
+        }
+        previous.filter(|(previous_expiry, _)| *previous_expiry > now).map(|(_, value)| value)
+    }
+
+    /// Returns a reference to the value corresponding to the key, or `None` if the key is not in
+    /// the map or its entry expired.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match those for
+    /// the key type.
+    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        let now = self.clock.now();
+        self.entries.get(k).filter(|(expires_at, _)| *expires_at > now).map(|(_, value)| value)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key, or `None` if the key is
+    /// not in the map or its entry expired. Updating the value keeps its expiration.
+    pub fn get_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        let now = self.clock.now();
+        self.entries.get_mut(k).filter(|(expires_at, _)| *expires_at > now).map(|(_, value)| value)
+    }
+
+    /// Returns the time at which the entry of the key expires, or `None` if the key is not in the
+    /// map or its entry expired.
+    pub fn expires_at<Q: ?Sized>(&self, k: &Q) -> Option<u64>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        let now = self.clock.now();
+        self.entries
+            .get(k)
+            .map(|(expires_at, _)| *expires_at)
+            .filter(|expires_at| *expires_at > now)
+    }
+
+    /// Returns `true` if the map contains a value for the specified key that has not expired.
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.get(k).is_some()
+    }
+
+    /// Removes a key from the map, returning the value at the key if it had not expired. The
+    /// entry is removed from storage even if it expired.
+    pub fn remove<Q: ?Sized>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        let now = self.clock.now();
+        self.entries.remove(k).filter(|(expires_at, _)| *expires_at > now).map(|(_, value)| value)
+    }
+
+    /// Removes expired entries from storage, visiting at most `max_entries` records of the purge
+    /// queue so that the cost of a call is bounded. Records of entries that have not expired yet
+    /// are moved to the back of the queue, and records of entries that were overwritten or
+    /// removed since are dropped.
+    ///
+    /// Returns the number of expired entries removed from storage.
+    pub fn purge(&mut self, max_entries: u32) -> u32 {
+        let now = self.clock.now();
+        let mut removed = 0;
+        for _ in 0..self.queue.len().min(max_entries.into()) {
+            let index = self.queue.first_index();
+            let (expires_at, key) = match self.queue.get(index) {
+                Some((expires_at, key)) => (*expires_at, key.clone()),
+                None => break,
+            };
+            self.queue.prune(index + 1, 1);
+            // Records of entries overwritten with another expiration or removed are dropped.
+            if self.entries.get(&key).map(|(current_expiry, _)| *current_expiry) != Some(expires_at)
+            {
+                continue;
+            }
+            if expires_at <= now {
+                self.entries.remove(&key);
+                removed += 1;
+            } else {
+                self.queue.append((expires_at, key));
+            }
+        }
+        removed
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::ExpiringLookupMap;
+    use crate::test_utils::VMContextBuilder;
+    use crate::testing_env;
+
+    fn set_block_height(height: u64) {
+        testing_env!(VMContextBuilder::new().block_height(height).build());
+    }
+
+    #[test]
+    fn entries_expire_lazily() {
+        set_block_height(10);
+        let mut map = ExpiringLookupMap::with_block_height(b"m");
+        assert_eq!(map.insert(1u8, "a".to_string(), 15), None);
+        assert_eq!(map.insert(2, "b".to_string(), 20), None);
+        assert_eq!(map.insert(1, "c".to_string(), 15), Some("a".to_string()));
+        assert_eq!(map.queue_len(), 2);
+
+        set_block_height(15);
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.expires_at(&2), Some(20));
+        *map.get_mut(&2).unwrap() += "!";
+        assert_eq!(map.insert(1, "d".to_string(), 30), None);
+        assert_eq!(map.queue_len(), 3);
+
+        set_block_height(25);
+        assert!(map.contains_key(&1));
+        assert!(!map.contains_key(&2));
+        assert_eq!(map.remove(&2), None);
+        assert_eq!(map.remove(&1), Some("d".to_string()));
+    }
+
+    #[test]
+    fn purge_in_batches() {
+        set_block_height(0);
+        let mut map = ExpiringLookupMap::with_block_height(b"m");
+        for i in 0..10u32 {
+            map.insert(i, i, u64::from(i % 2 + 1) * 10);
+        }
+        // Overwritten entries leave a stale record in the queue.
+        map.insert(0, 0, 100);
+        assert_eq!(map.queue_len(), 11);
+
+        set_block_height(10);
+        assert_eq!(map.purge(4), 1);
+        assert_eq!(map.queue_len(), 9);
+        assert_eq!(map.purge(100), 3);
+        assert_eq!(map.queue_len(), 6);
+
+        set_block_height(20);
+        assert_eq!(map.purge(100), 5);
+        assert_eq!(map.queue_len(), 1);
+        assert_eq!(map.get(&0), Some(&0));
+        for i in 1..10 {
+            assert!(map.entries.get(&i).is_none());
+        }
+    }
+}
@@ -9,16 +9,59 @@ use borsh::{BorshDeserialize, BorshSerialize};
 
 use std::{fmt, mem};
 
-/// Index for value within a bucket.
-#[near(inside_nearsdk)]
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+/// Index of a value within a [`FreeList`].
+///
+/// The index of a value stays the same until the value is removed, after which it can be reused
+/// for a new value. It converts to and from a [`u32`], e.g. to be used as an external identifier,
+/// and is serialized as a plain number in JSON.
+#[near(inside_nearsdk, serializers=[borsh, json])]
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub struct FreeListIndex(pub(crate) u32);
 
+impl From<u32> for FreeListIndex {
+    fn from(index: u32) -> Self {
+        Self(index)
+    }
+}
+
+impl From<FreeListIndex> for u32 {
+    fn from(index: FreeListIndex) -> Self {
+        index.0
+    }
+}
+
 /// Unordered container of values. This is similar to [`Vector`] except that values are not
 /// re-arranged on removal, keeping the indices consistent. When an element is removed, it will
 /// be replaced with an empty cell which will be populated on the next insertion.
+///
+/// This makes it a good fit for allocating identifiers which are reused once released, such as
+/// ticket or order IDs: [`insert`](Self::insert) returns the [`FreeListIndex`] of the value,
+/// which stays valid until the value is [`remove`](Self::remove)d.
+///
+/// Values are cached and persisted like in [`Vector`]. The number of values and the chain of
+/// empty cells are stored in the [`FreeList`] itself, so it has to be persisted as part of the
+/// contract state.
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::FreeList;
+///
+/// let mut tickets = FreeList::new(b"t");
+/// let alice = tickets.insert("alice".to_string());
+/// let bob = tickets.insert("bob".to_string());
+/// assert_eq!(tickets.len(), 2);
+///
+/// // The index of a removed value is reused by the next insertion.
+/// assert_eq!(tickets.remove(alice), Some("alice".to_string()));
+/// let carol = tickets.insert("carol".to_string());
+/// assert_eq!(carol, alice);
+///
+/// assert_eq!(tickets.get(bob), Some(&"bob".to_string()));
+/// assert_eq!(u32::from(carol), 0);
+/// assert_eq!(tickets.iter().collect::<Vec<_>>(), [&"carol".to_string(), &"bob".to_string()]);
+/// ```
 #[near(inside_nearsdk)]
-pub(crate) struct FreeList<T>
+pub struct FreeList<T>
 where
     T: BorshSerialize,
 {
@@ -58,7 +101,7 @@ where
     T: BorshSerialize + BorshDeserialize + fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Bucket")
+        f.debug_struct("FreeList")
             .field("next_vacant", &self.first_free)
             .field("occupied_count", &self.occupied_count)
             .field("elements", &self.elements)
@@ -84,6 +127,10 @@ impl<T> FreeList<T>
 where
     T: BorshSerialize,
 {
+    /// Create new free list with zero elements. Prefixes storage access with the prefix provided.
+    ///
+    /// This prefix can be anything that implements [`IntoStorageKey`]. The prefix is used when
+    /// storing and looking up values in storage to ensure no collisions with other collections.
     pub fn new<S: IntoStorageKey>(prefix: S) -> Self {
         Self { first_free: None, occupied_count: 0, elements: Vector::new(prefix) }
     }
@@ -102,8 +149,7 @@ where
     }
 
     /// Clears the bucket, removing all values (including removed entries).
-    #[cfg(test)]
-    fn clear(&mut self) {
+    pub fn clear(&mut self) {
         self.elements.clear();
         self.first_free = None;
         self.occupied_count = 0;
@@ -116,7 +162,6 @@ where
 {
     /// Returns a reference to filled cell, if the value at the given index is valid. If the index
     /// is out of range or has been removed, returns `None`.
-    #[allow(dead_code)]
     pub fn get(&self, index: FreeListIndex) -> Option<&T> {
         if let Slot::Occupied(value) = self.elements.get(index.0)? {
             Some(value)
@@ -126,7 +171,6 @@ where
     }
     /// Returns a mutable reference to filled cell, if the value at the given index is valid. If
     /// the index is out of range or has been removed, returns `None`.
-    #[allow(dead_code)]
     pub fn get_mut(&mut self, index: FreeListIndex) -> Option<&mut T> {
         if let Slot::Occupied(value) = self.elements.get_mut(index.0)? {
             Some(value)
@@ -183,6 +227,30 @@ where
         prev.into_value()
     }
 
+    /// Retains only the values for which `f` returns `true`. The slots of the removed values are
+    /// added to the free slots, to be reused by subsequent insertions.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut remaining = self.occupied_count;
+        let mut index = 0;
+        while remaining > 0 {
+            let keep = match self.elements.get(index) {
+                Some(Slot::Occupied(value)) => {
+                    remaining -= 1;
+                    f(value)
+                }
+                Some(Slot::Empty { .. }) => true,
+                None => env::panic_str(ERR_INCONSISTENT_STATE),
+            };
+            if !keep {
+                self.remove(FreeListIndex(index));
+            }
+            index += 1;
+        }
+    }
+
     /// Generates iterator for shared references to each value in the bucket.
     pub fn iter(&self) -> Iter<T> {
         Iter::new(self)
@@ -214,6 +282,74 @@ where
         Defrag::new(self).defrag(callback);
         self.first_free = None;
     }
+
+    /// Incremental version of [`defrag`](Self::defrag), which reads at most `max_steps` slots
+    /// and keeps the free slot chain intact between calls.
+    ///
+    /// Each step releases the last slot of the list: an occupied last slot is moved into the
+    /// first free slot, an empty one is unlinked from the free slot chain. Unlinking an empty
+    /// slot walks the chain to find the slot pointing to it, one step per slot read. If the steps
+    /// run out before, the walked part of the chain is moved behind the last slot, so that the
+    /// next call resumes closer to it. Returns `true` if there are no empty slots left, in which
+    /// case further calls are no-ops.
+    pub(crate) fn defrag_partial<F>(&mut self, max_steps: u32, mut callback: F) -> bool
+    where
+        F: FnMut(&T, u32),
+    {
+        let mut steps = max_steps;
+        while steps > 0 {
+            let first_free = match self.first_free {
+                Some(first_free) => first_free,
+                None => return true,
+            };
+            steps -= 1;
+            let last_index = self.elements.len().saturating_sub(1);
+            match self.elements.pop() {
+                Some(Slot::Occupied(value)) => {
+                    callback(&value, first_free.0);
+                    match self.elements.replace(first_free.0, Slot::Occupied(value)) {
+                        Slot::Empty { next_free } => self.first_free = next_free,
+                        Slot::Occupied(_) => env::panic_str(ERR_INCONSISTENT_STATE),
+                    }
+                }
+                Some(Slot::Empty { next_free }) if first_free.0 == last_index => {
+                    self.first_free = next_free;
+                }
+                Some(Slot::Empty { next_free }) => {
+                    // The last slot is somewhere further in the chain, find the slot pointing to
+                    // it and link it to the slot that follows the removed one.
+                    let mut curr = first_free.0;
+                    loop {
+                        let next = match self.elements.get_mut(curr) {
+                            Some(Slot::Empty { next_free: next }) => next,
+                            _ => env::panic_str(ERR_INCONSISTENT_STATE),
+                        };
+                        match *next {
+                            Some(FreeListIndex(i)) if i == last_index => {
+                                *next = next_free;
+                                break;
+                            }
+                            Some(FreeListIndex(i)) if steps > 0 => {
+                                steps -= 1;
+                                curr = i;
+                            }
+                            Some(rest) => {
+                                // Out of steps: the chain becomes `rest, .., last, first_free,
+                                // .., curr, next_free, ..`, and the last slot is kept.
+                                *next = next_free;
+                                self.first_free = Some(rest);
+                                self.elements.push(Slot::Empty { next_free: Some(first_free) });
+                                return false;
+                            }
+                            None => env::panic_str(ERR_INCONSISTENT_STATE),
+                        }
+                    }
+                }
+                None => env::panic_str(ERR_INCONSISTENT_STATE),
+            }
+        }
+        self.first_free.is_none()
+    }
 }
 
 /// Defrag struct has helper functions to perform defragmentation of `FreeList`. See the
@@ -337,6 +473,27 @@ mod tests {
         assert_eq!(bucket.get(i3), Some(&4));
     }
 
+    #[test]
+    fn index_handles() {
+        let mut bucket = FreeList::new(b"b");
+        let i0 = bucket.insert(0u8);
+        let i1 = bucket.insert(1u8);
+        assert_eq!(u32::from(i1), 1);
+        assert_eq!(FreeListIndex::from(1), i1);
+        assert_eq!(serde_json::to_string(&i1).unwrap(), "1");
+        assert_eq!(serde_json::from_str::<FreeListIndex>("1").unwrap(), i1);
+
+        // Indices not handed out by the list do not refer to any value.
+        assert_eq!(bucket.get(FreeListIndex::from(2)), None);
+        assert_eq!(bucket.remove(FreeListIndex::from(2)), None);
+        assert_eq!(bucket.len(), 2);
+
+        assert_eq!(bucket.remove(i0), Some(0));
+        assert_eq!(bucket.remove(i0), None);
+        assert_eq!(bucket.get(i1), Some(&1));
+        assert_eq!(bucket.insert(2), i0);
+    }
+
     #[test]
     fn defrag() {
         let mut bucket = FreeList::new(b"b");
@@ -363,6 +520,87 @@ mod tests {
         }
     }
 
+    #[test]
+    fn defrag_partial() {
+        let mut bucket = FreeList::new(b"b");
+        let indices: Vec<_> = (0..8).map(|i| bucket.insert(i)).collect();
+
+        //Empty, Empty, Empty, Empty, Occupied, Empty, Occupied, Empty
+        bucket.remove(indices[1]);
+        bucket.remove(indices[3]);
+        bucket.remove(indices[0]);
+        bucket.remove(indices[5]);
+        bucket.remove(indices[2]);
+        bucket.remove(indices[7]);
+
+        let mut moved = Vec::new();
+        assert!(!bucket.defrag_partial(2, |v, i| moved.push((*v, i))));
+        assert_eq!(bucket.elements.len(), 6);
+        assert_eq!(moved, [(6, indices[2].0)]);
+        assert_eq!(bucket.iter().copied().collect::<Vec<_>>(), [6, 4]);
+
+        // Free slot chain is still usable between partial defrags.
+        let inserted = bucket.insert(9);
+        assert_eq!(*bucket.get(inserted).unwrap(), 9);
+        assert_eq!(bucket.remove(inserted), Some(9));
+
+        while !bucket.defrag_partial(1, |v, i| moved.push((*v, i))) {}
+        assert_eq!(bucket.occupied_count, bucket.len());
+        assert_eq!(bucket.elements.len(), 2);
+        assert_eq!(bucket.first_free, None);
+        assert_eq!(bucket.iter().copied().collect::<Vec<_>>(), [4, 6]);
+        assert!(bucket.defrag_partial(1, |_, _| {}));
+    }
+
+    #[test]
+    fn defrag_partial_bounded_walk() {
+        let mut bucket = FreeList::new(b"b");
+        let indices: Vec<_> = (0..8).map(|i| bucket.insert(i)).collect();
+
+        //Free slot chain: 3 -> 2 -> 1 -> 0 -> 7
+        for i in [7, 0, 1, 2, 3] {
+            bucket.remove(indices[i]);
+        }
+
+        // A single step can't reach the slot pointing to the last one, but moves the chain.
+        assert!(!bucket.defrag_partial(1, |_, _| panic!("nothing to move yet")));
+        assert_eq!(bucket.elements.len(), 8);
+        assert_eq!(bucket.first_free, Some(indices[2]));
+        let inserted = bucket.insert(9);
+        assert_eq!(bucket.remove(inserted), Some(9));
+
+        let mut calls = 1;
+        let mut moved = Vec::new();
+        while !bucket.defrag_partial(1, |v, i| moved.push((*v, i))) {
+            calls += 1;
+        }
+        assert!(calls < 16);
+        assert_eq!(bucket.elements.len(), 3);
+        assert_eq!(bucket.first_free, None);
+        let mut values: Vec<_> = bucket.iter().copied().collect();
+        values.sort();
+        assert_eq!(values, [4, 5, 6]);
+        assert_eq!(moved.len(), 3);
+    }
+
+    #[test]
+    fn retain() {
+        let mut bucket = FreeList::new(b"b");
+        let indices: Vec<_> = (0..8u8).map(|i| bucket.insert(i)).collect();
+        bucket.remove(indices[1]);
+
+        bucket.retain(|v| v % 3 != 0);
+        assert_eq!(bucket.len(), 4);
+        assert!(bucket.iter().copied().eq([2, 4, 5, 7]));
+
+        // Removed slots are reused, starting with the last one removed.
+        assert_eq!(bucket.insert(9), indices[6]);
+        assert_eq!(bucket.insert(10), indices[3]);
+        assert_eq!(bucket.insert(11), indices[0]);
+        assert_eq!(bucket.insert(12), indices[1]);
+        assert_eq!(bucket.insert(13), FreeListIndex(8));
+    }
+
     #[test]
     fn bucket_iterator() {
         let mut bucket = FreeList::new(b"b");
@@ -48,7 +48,7 @@ where
 
     /// Flushes the cache and writes all modified values to storage.
     pub fn flush(&mut self) {
-        let mut buf = Vec::new();
+        let mut batch = env::storage_batch();
         // Capacity is prefix length plus bytes needed for u32 bytes (4*u8)
         let mut key_buf = Vec::with_capacity(self.prefix.len() + 4);
         for (k, v) in self.cache.inner().iter_mut() {
@@ -58,14 +58,14 @@ where
                     Self::index_to_lookup_key(&self.prefix, *k, &mut key_buf);
                     match v.value().as_ref() {
                         Some(modified) => {
-                            buf.clear();
-                            BorshSerialize::serialize(modified, &mut buf)
-                                .unwrap_or_else(|_| env::panic_str(ERR_ELEMENT_SERIALIZATION));
-                            env::storage_write(&key_buf, &buf);
+                            batch.write_with(&key_buf, |buf| {
+                                BorshSerialize::serialize(modified, buf)
+                                    .unwrap_or_else(|_| env::panic_str(ERR_ELEMENT_SERIALIZATION))
+                            });
                         }
                         None => {
                             // Element was removed, clear the storage for the value
-                            env::storage_remove(&key_buf);
+                            batch.remove(&key_buf);
                         }
                     }
 
@@ -75,6 +75,24 @@ where
                 }
             }
         }
+        batch.commit();
+    }
+
+    /// Writes `values` to storage at the indices starting from `start`, bypassing the cache. Cached
+    /// entries for these indices are discarded, so that a pending change does not overwrite the
+    /// values on flush.
+    pub(crate) fn write_from(&mut self, start: u32, values: &[T]) {
+        let mut buf = Vec::new();
+        let mut key_buf = Vec::with_capacity(self.prefix.len() + 4);
+        for (index, value) in (start..).zip(values) {
+            self.cache.inner().remove(&index);
+            key_buf.clear();
+            Self::index_to_lookup_key(&self.prefix, index, &mut key_buf);
+            buf.clear();
+            BorshSerialize::serialize(value, &mut buf)
+                .unwrap_or_else(|_| env::panic_str(ERR_ELEMENT_SERIALIZATION));
+            env::storage_write(&key_buf, &buf);
+        }
     }
 
     /// Sets a value at a given index to the value provided. If none is provided, this index will
@@ -0,0 +1,188 @@
+use std::iter::FusedIterator;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::{FreeListIndex, InsertionOrderedMap, ToKey};
+
+impl<'a, K, V, H> IntoIterator for &'a InsertionOrderedMap<K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, H>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over elements of a [`InsertionOrderedMap`], in insertion order.
+///
+/// This `struct` is created by the `iter` and `iter_after` methods on [`InsertionOrderedMap`].
+pub struct Iter<'a, K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    map: &'a InsertionOrderedMap<K, V, H>,
+    /// Slot of the next key, `None` once the iterator is exhausted.
+    next: Option<FreeListIndex>,
+    /// Upper bound of the number of remaining elements.
+    remaining: u32,
+}
+
+impl<'a, K, V, H> Iter<'a, K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    pub(super) fn new(map: &'a InsertionOrderedMap<K, V, H>, next: Option<FreeListIndex>) -> Self {
+        Self { map, next, remaining: map.len() }
+    }
+}
+
+impl<'a, K, V, H> Clone for Iter<'a, K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    fn clone(&self) -> Self {
+        Self { map: self.map, next: self.next, remaining: self.remaining }
+    }
+}
+
+impl<'a, K, V, H> Iterator for Iter<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.map.node(self.next?);
+        self.next = node.next;
+        self.remaining = self.remaining.saturating_sub(1);
+        Some((&node.key, &self.map.entry(&node.key).value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.next {
+            Some(_) => (1, Some(self.remaining as usize)),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<'a, K, V, H> FusedIterator for Iter<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+}
+
+/// An iterator over the keys of a [`InsertionOrderedMap`], in insertion order.
+///
+/// This `struct` is created by the `keys` method on [`InsertionOrderedMap`].
+pub struct Keys<'a, K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    inner: Iter<'a, K, V, H>,
+}
+
+impl<'a, K, V, H> Keys<'a, K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    pub(super) fn new(map: &'a InsertionOrderedMap<K, V, H>) -> Self {
+        Self { inner: Iter::new(map, map.head) }
+    }
+}
+
+impl<'a, K, V, H> Iterator for Keys<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        // The value of the key is not needed, so only the linked list is followed.
+        let node = self.inner.map.node(self.inner.next?);
+        self.inner.next = node.next;
+        self.inner.remaining = self.inner.remaining.saturating_sub(1);
+        Some(&node.key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V, H> FusedIterator for Keys<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+}
+
+/// An iterator over the values of a [`InsertionOrderedMap`], in insertion order.
+///
+/// This `struct` is created by the `values` method on [`InsertionOrderedMap`].
+pub struct Values<'a, K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    inner: Iter<'a, K, V, H>,
+}
+
+impl<'a, K, V, H> Values<'a, K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    pub(super) fn new(map: &'a InsertionOrderedMap<K, V, H>) -> Self {
+        Self { inner: Iter::new(map, map.head) }
+    }
+}
+
+impl<'a, K, V, H> Iterator for Values<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<&'a V> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V, H> FusedIterator for Values<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+}
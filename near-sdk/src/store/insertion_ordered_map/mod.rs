@@ -0,0 +1,381 @@
+mod iter;
+
+pub use self::iter::{Iter, Keys, Values};
+
+use std::borrow::Borrow;
+use std::{fmt, mem};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk_macros::near;
+
+use crate::store::key::{Sha256, ToKey};
+use crate::{env, IntoStorageKey};
+
+use super::{FreeList, FreeListIndex, LookupMap, ERR_INCONSISTENT_STATE};
+
+/// A lazily loaded storage map which iterates over its entries in insertion order.
+///
+/// The keys are kept in a doubly linked list of [`FreeList`] slots, so the order of the remaining
+/// entries is preserved when an entry is removed, unlike [`IterableMap`](crate::store::IterableMap)
+/// which moves its last entry into the slot of the removed one. Overwriting the value of a key
+/// keeps the position of the key, and a key that is removed and inserted again is moved to the
+/// end. This gives stable pages to views paginating over the map, across removals and contract
+/// upgrades, for example with [`iter_after`](Self::iter_after) and the last key of the previous
+/// page as a cursor.
+///
+/// Each entry takes an additional storage read compared to
+/// [`IterableMap`](crate::store::IterableMap) when iterating, and removals update the neighbours
+/// of the removed entry.
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::InsertionOrderedMap;
+///
+/// let mut map = InsertionOrderedMap::new(b"m");
+/// map.insert("c".to_string(), 3u8);
+/// map.insert("a".to_string(), 1);
+/// map.insert("b".to_string(), 2);
+/// map.remove("a");
+/// map.insert("a".to_string(), 4);
+///
+/// let keys: Vec<_> = map.keys().map(String::as_str).collect();
+/// assert_eq!(keys, ["c", "b", "a"]);
+///
+/// let page: Vec<_> = map.iter_after("c").take(1).map(|(_, value)| *value).collect();
+/// assert_eq!(page, [2]);
+/// ```
+#[near(inside_nearsdk)]
+pub struct InsertionOrderedMap<K, V, H = Sha256>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    /// Slot of the first inserted key.
+    head: Option<FreeListIndex>,
+    /// Slot of the last inserted key.
+    tail: Option<FreeListIndex>,
+    // ser/de is independent of `K` ser/de, `BorshSerialize`/`BorshDeserialize`/`BorshSchema` bounds removed
+    #[cfg_attr(not(feature = "abi"), borsh(bound(serialize = "", deserialize = "")))]
+    #[cfg_attr(
+        feature = "abi",
+        borsh(bound(serialize = "", deserialize = ""), schema(params = ""))
+    )]
+    nodes: FreeList<Node<K>>,
+    // ser/de is independent of `K`, `V`, `H` ser/de, `BorshSerialize`/`BorshDeserialize`/`BorshSchema` bounds removed
+    #[cfg_attr(not(feature = "abi"), borsh(bound(serialize = "", deserialize = "")))]
+    #[cfg_attr(
+        feature = "abi",
+        borsh(bound(serialize = "", deserialize = ""), schema(params = ""))
+    )]
+    values: LookupMap<K, ValueAndNode<V>, H>,
+}
+
+#[near(inside_nearsdk)]
+struct Node<K> {
+    key: K,
+    prev: Option<FreeListIndex>,
+    next: Option<FreeListIndex>,
+}
+
+#[near(inside_nearsdk)]
+struct ValueAndNode<V> {
+    value: V,
+    node: FreeListIndex,
+}
+
+impl<K, V, H> fmt::Debug for InsertionOrderedMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InsertionOrderedMap")
+            .field("head", &self.head)
+            .field("tail", &self.tail)
+            .field("len", &self.nodes.len())
+            .finish()
+    }
+}
+
+impl<K, V> InsertionOrderedMap<K, V, Sha256>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+{
+    /// Create a new map. Prefixes storage access with the prefix provided.
+    ///
+    /// This prefix can be anything that implements [`IntoStorageKey`]. The prefix is used when
+    /// storing and looking up values in storage to ensure no collisions with other collections.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::InsertionOrderedMap;
+    ///
+    /// let mut map: InsertionOrderedMap<String, u8> = InsertionOrderedMap::new(b"b");
+    /// ```
+    #[inline]
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self::with_hasher(prefix)
+    }
+}
+
+impl<K, V, H> InsertionOrderedMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    /// Initialize a [`InsertionOrderedMap`] with a custom hash function.
+    ///
+    /// # Example
+    /// ```
+    /// use near_sdk::store::{InsertionOrderedMap, key::Keccak256};
+    ///
+    /// let map = InsertionOrderedMap::<String, String, Keccak256>::with_hasher(b"m");
+    /// ```
+    pub fn with_hasher<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let mut nodes_key = prefix.into_storage_key();
+        let map_key = [nodes_key.as_slice(), b"m"].concat();
+        nodes_key.push(b'n');
+        Self {
+            head: None,
+            tail: None,
+            nodes: FreeList::new(nodes_key),
+            values: LookupMap::with_hasher(map_key),
+        }
+    }
+
+    /// Return the amount of elements inside of the map.
+    pub fn len(&self) -> u32 {
+        self.nodes.len()
+    }
+
+    /// Returns true if there are no elements inside of the map.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Flushes the intermediate values of the map before this is called when the structure is
+    /// [`Drop`]ed. This will write all modified values to storage but keep all cached values
+    /// in memory.
+    pub fn flush(&mut self) {
+        self.nodes.flush();
+        self.values.flush();
+    }
+}
+
+impl<K, V, H> InsertionOrderedMap<K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    /// An iterator visiting all key-value pairs in insertion order.
+    /// The iterator element type is `(&'a K, &'a V)`.
+    pub fn iter(&self) -> Iter<K, V, H> {
+        Iter::new(self, self.head)
+    }
+
+    /// An iterator visiting the key-value pairs inserted after `k`, in insertion order. The
+    /// iterator is empty if `k` is not in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn iter_after<Q: ?Sized>(&self, k: &Q) -> Iter<K, V, H>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        let next = self.values.get(k).and_then(|entry| self.node(entry.node).next);
+        Iter::new(self, next)
+    }
+
+    /// An iterator visiting all keys in insertion order.
+    /// The iterator element type is `&'a K`.
+    pub fn keys(&self) -> Keys<K, V, H> {
+        Keys::new(self)
+    }
+
+    /// An iterator visiting all values in insertion order.
+    /// The iterator element type is `&'a V`.
+    pub fn values(&self) -> Values<K, V, H> {
+        Values::new(self)
+    }
+
+    /// Returns the first inserted key-value pair.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    /// Returns the last inserted key-value pair.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        let key = &self.node(self.tail?).key;
+        Some((key, &self.entry(key).value))
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.values.get(k).map(|entry| &entry.value)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn get_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.values.get_mut(k).map(|entry| &mut entry.value)
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K> + Ord,
+    {
+        self.values.contains_key(k)
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, [`None`] is returned and the key is added at the
+    /// end of the insertion order. Otherwise the value is updated, the key keeps its position, and
+    /// the old value is returned.
+    pub fn insert(&mut self, k: K, value: V) -> Option<V> {
+        if let Some(existing) = self.values.get_mut(&k) {
+            return Some(mem::replace(&mut existing.value, value));
+        }
+
+        let node = self.nodes.insert(Node { key: k.clone(), prev: self.tail, next: None });
+        match self.tail {
+            Some(tail) => self.node_mut(tail).next = Some(node),
+            None => self.head = Some(node),
+        }
+        self.tail = Some(node);
+        self.values.set(k, Some(ValueAndNode { value, node }));
+        None
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key was previously in
+    /// the map. The order of the other keys is preserved.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn remove<Q: ?Sized>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        let entry = self.values.remove(k)?;
+        let node =
+            self.nodes.remove(entry.node).unwrap_or_else(|| env::panic_str(ERR_INCONSISTENT_STATE));
+        match node.prev {
+            Some(prev) => self.node_mut(prev).next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(next) => self.node_mut(next).prev = node.prev,
+            None => self.tail = node.prev,
+        }
+        Some(entry.value)
+    }
+
+    /// Clears the map, removing all key-value pairs.
+    pub fn clear(&mut self) {
+        let mut next = self.head.take();
+        while let Some(index) = next {
+            let node =
+                self.nodes.remove(index).unwrap_or_else(|| env::panic_str(ERR_INCONSISTENT_STATE));
+            // Set instead of remove to avoid loading the value from storage.
+            self.values.set(node.key, None);
+            next = node.next;
+        }
+        self.tail = None;
+    }
+
+    fn node(&self, index: FreeListIndex) -> &Node<K> {
+        self.nodes.get(index).unwrap_or_else(|| env::panic_str(ERR_INCONSISTENT_STATE))
+    }
+
+    fn node_mut(&mut self, index: FreeListIndex) -> &mut Node<K> {
+        self.nodes.get_mut(index).unwrap_or_else(|| env::panic_str(ERR_INCONSISTENT_STATE))
+    }
+
+    fn entry(&self, key: &K) -> &ValueAndNode<V> {
+        self.values.get(key).unwrap_or_else(|| env::panic_str(ERR_INCONSISTENT_STATE))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use borsh::{to_vec, BorshDeserialize};
+
+    use super::InsertionOrderedMap;
+    use crate::store::key::Identity;
+
+    #[test]
+    fn iterates_in_insertion_order() {
+        let mut map = InsertionOrderedMap::<u32, u32, Identity>::with_hasher(b"m");
+        for i in 0..10 {
+            assert_eq!(map.insert(i, i * 10), None);
+        }
+        assert_eq!(map.insert(4, 44), Some(40));
+        assert_eq!(map.remove(&0), Some(0));
+        assert_eq!(map.remove(&5), Some(50));
+        assert_eq!(map.remove(&9), Some(90));
+        assert_eq!(map.remove(&9), None);
+        map.insert(5, 55);
+        map.insert(10, 100);
+
+        assert_eq!(map.len(), 9);
+        assert!(map.keys().copied().eq([1, 2, 3, 4, 6, 7, 8, 5, 10]));
+        assert_eq!(map.get(&4), Some(&44));
+        assert_eq!(map.first(), Some((&1, &10)));
+        assert_eq!(map.last(), Some((&10, &100)));
+        assert!(map.iter_after(&8).map(|(k, _)| *k).eq([5, 10]));
+        assert_eq!(map.iter_after(&10).count(), 0);
+        assert_eq!(map.iter_after(&0).count(), 0);
+
+        // Order is kept when the map is loaded again.
+        map.flush();
+        let mut map =
+            InsertionOrderedMap::<u32, u32, Identity>::try_from_slice(&to_vec(&map).unwrap())
+                .unwrap();
+        assert!(map.values().copied().eq([10, 20, 30, 44, 60, 70, 80, 55, 100]));
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.iter().count(), 0);
+        assert_eq!(map.get(&1), None);
+        map.insert(1, 1);
+        assert_eq!(map.first(), map.last());
+    }
+}
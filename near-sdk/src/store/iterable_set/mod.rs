@@ -474,6 +474,27 @@ where
         self.index.contains_key(value)
     }
 
+    /// Returns whether the set contains each of the specified values, in the same order.
+    ///
+    /// Lookups are cached, so values that appear multiple times in `values`, or that were already
+    /// looked up, are only read from storage once.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::store::IterableSet;
+    ///
+    /// let mut set = IterableSet::new(b"s");
+    /// set.insert(1u32);
+    /// set.insert(4u32);
+    /// assert_eq!(set.contains_many(&[4, 2, 1]), [true, false, true]);
+    /// ```
+    pub fn contains_many(&self, values: &[T]) -> Vec<bool>
+    where
+        T: Clone,
+    {
+        values.iter().map(|value| self.index.contains_key(value)).collect()
+    }
+
     /// Adds a value to the set.
     ///
     /// If the set did not have this value present, true is returned.
@@ -806,6 +827,17 @@ mod tests {
         assert!(b.is_superset(&a));
     }
 
+    #[test]
+    fn test_contains_many() {
+        let mut set = IterableSet::new(b"m");
+        set.extend([1, 3, 5]);
+        set.flush();
+
+        let set = IterableSet::<i32>::new(b"m");
+        assert_eq!(set.contains_many(&[5, 4, 3, 5]), [true, false, true, true]);
+        assert!(set.contains_many(&[]).is_empty());
+    }
+
     #[test]
     fn test_disjoint() {
         let mut xs = IterableSet::new(b"m");
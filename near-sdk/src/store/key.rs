@@ -1,3 +1,5 @@
+use std::io::{self, Write};
+
 use borsh::BorshSerialize;
 
 use crate::env;
@@ -76,3 +78,48 @@ impl ToKey for Identity {
         buffer.clone()
     }
 }
+
+/// Key wrapper which uses the wrapped bytes as they are instead of their Borsh serialization,
+/// which would be prefixed with their length for slices and vectors.
+///
+/// This is useful for keys that are already serialized or hashed, e.g. a
+/// [`CryptoHash`](crate::CryptoHash). Combined with the default [`Identity`] hasher of
+/// [`LookupMap`](crate::store::LookupMap), the key bytes are used after the map's prefix without
+/// any serialization or hashing.
+///
+/// The bytes of different keys must not be prefixes of each other for the keys to map to different
+/// storage entries, which is the case when all keys have the same length. Only the serialization
+/// of [`RawKey`] is overridden, so it must not be used where the key has to be deserialized again,
+/// like in the iterable maps.
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::key::RawKey;
+/// use near_sdk::store::LookupMap;
+/// use near_sdk::{env, CryptoHash};
+///
+/// let mut map: LookupMap<RawKey<CryptoHash>, u32> = LookupMap::new(b"m");
+/// let hash = CryptoHash::from(env::sha256_array(b"data"));
+/// map.insert(RawKey(hash), 1);
+/// assert_eq!(map.get(&RawKey(hash)), Some(&1));
+///
+/// map.flush();
+/// assert!(env::storage_has_key(&[b"m".as_slice(), hash.as_ref()].concat()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct RawKey<T>(pub T);
+
+impl<T> BorshSerialize for RawKey<T>
+where
+    T: AsRef<[u8]>,
+{
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.0.as_ref())
+    }
+}
+
+impl<T> From<T> for RawKey<T> {
+    fn from(bytes: T) -> Self {
+        Self(bytes)
+    }
+}
@@ -38,6 +38,16 @@ where
     CacheEntry::new_cached(Some(val))
 }
 
+pub(crate) fn load_and_deserialize_optional<T>(key: &[u8]) -> CacheEntry<T>
+where
+    T: BorshDeserialize,
+{
+    let val = env::storage_read(key).map(|bytes| {
+        T::try_from_slice(&bytes).unwrap_or_else(|_| env::panic_str(ERR_VALUE_DESERIALIZATION))
+    });
+    CacheEntry::new_cached(val)
+}
+
 pub(crate) fn serialize_and_store<T>(key: &[u8], value: &T)
 where
     T: BorshSerialize,
@@ -109,9 +119,14 @@ where
     pub fn flush(&mut self) {
         if let Some(v) = self.cache.get_mut() {
             if v.is_modified() {
-                // Value was modified, serialize and put the serialized bytes in storage.
-                let value = expect_consistent_state(v.value().as_ref());
-                serialize_and_store(&self.storage_key, value);
+                // Value was modified, serialize and put the serialized bytes in storage, or
+                // remove them if the value was taken.
+                match v.value().as_ref() {
+                    Some(value) => serialize_and_store(&self.storage_key, value),
+                    None => {
+                        env::storage_remove(&self.storage_key);
+                    }
+                }
 
                 // Replaces cache entry state to cached because the value in memory matches the
                 // stored value. This avoids writing the same value twice.
@@ -134,24 +149,96 @@ where
     /// The load from storage only happens once, and if the value is already cached, it will not
     /// be reloaded.
     ///
-    /// This function will panic if the cache is not loaded and the value at the key does not exist.
+    /// This function will panic if the cache is not loaded and the value at the key does not exist,
+    /// or if the value was [`take`](Self::take)n.
     pub fn get(&self) -> &T {
         let entry = self.cache.get_or_init(|| load_and_deserialize(&self.storage_key));
 
-        expect_consistent_state(entry.value().as_ref())
+        expect_key_exists(entry.value().as_ref())
     }
 
     /// Returns a reference to the lazily loaded storage value.
     /// The load from storage only happens once, and if the value is already cached, it will not
     /// be reloaded.
     ///
-    /// This function will panic if the cache is not loaded and the value at the key does not exist.
+    /// This function will panic if the cache is not loaded and the value at the key does not exist,
+    /// or if the value was [`take`](Self::take)n.
     pub fn get_mut(&mut self) -> &mut T {
         self.cache.get_or_init(|| load_and_deserialize(&self.storage_key));
         let entry = self.cache.get_mut().unwrap_or_else(|| env::abort());
 
+        expect_key_exists(entry.value_mut().as_mut())
+    }
+
+    /// Returns a mutable reference to the value, inserting the value returned by `f` if there is
+    /// no value in storage or the value was [`take`](Self::take)n.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::Lazy;
+    ///
+    /// let mut a: Lazy<Vec<u8>> = Lazy::new(b"a", vec![1]);
+    /// a.take();
+    /// a.get_or_insert_with(Vec::new).push(2);
+    /// assert_eq!(a.get(), &[2]);
+    /// ```
+    pub fn get_or_insert_with<F>(&mut self, f: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        self.cache.get_or_init(|| load_and_deserialize_optional(&self.storage_key));
+        let entry = self.cache.get_mut().unwrap_or_else(|| env::abort());
+        if entry.value().is_none() {
+            entry.replace(Some(f()));
+        }
+
         expect_consistent_state(entry.value_mut().as_mut())
     }
+
+    /// Applies `f` to the value in place and returns its result. The value is written to storage
+    /// when it is flushed, without being cloned.
+    ///
+    /// This function will panic if the cache is not loaded and the value at the key does not exist,
+    /// or if the value was [`take`](Self::take)n.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::Lazy;
+    ///
+    /// let mut a = Lazy::new(b"a", vec![1u8, 2]);
+    /// let len = a.map_mut(|v| {
+    ///     v.push(3);
+    ///     v.len()
+    /// });
+    /// assert_eq!(len, 3);
+    /// ```
+    pub fn map_mut<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        f(self.get_mut())
+    }
+
+    /// Takes the value out of the [`Lazy`], leaving it empty. The value is removed from storage
+    /// when the [`Lazy`] is flushed. Returns [`None`] if there is no value in storage or the value
+    /// was already taken.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::Lazy;
+    ///
+    /// let mut a = Lazy::new(b"a", "metadata".to_string());
+    /// assert_eq!(a.take(), Some("metadata".to_string()));
+    /// assert_eq!(a.take(), None);
+    /// ```
+    pub fn take(&mut self) -> Option<T> {
+        self.cache.get_or_init(|| load_and_deserialize_optional(&self.storage_key));
+        let entry = self.cache.get_mut().unwrap_or_else(|| env::abort());
+        entry.replace(None)
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -190,6 +277,38 @@ mod tests {
         assert_eq!(lazy_loaded, b);
     }
 
+    #[test]
+    pub fn test_take_and_insert() {
+        let mut a = Lazy::new(b"t", 8u32);
+        a.flush();
+        let serialized = to_vec(&a).unwrap();
+        drop(a);
+
+        let mut a = Lazy::<u32>::try_from_slice(&serialized).unwrap();
+        assert_eq!(a.take(), Some(8));
+        // Value is only removed from storage once flushed.
+        assert!(env::storage_has_key(b"t"));
+        a.flush();
+        assert!(!env::storage_has_key(b"t"));
+        drop(a);
+
+        let mut a = Lazy::<u32>::try_from_slice(&serialized).unwrap();
+        assert_eq!(a.take(), None);
+        assert_eq!(*a.get_or_insert_with(|| 3), 3);
+        assert_eq!(*a.get_or_insert_with(|| 5), 3);
+        assert_eq!(a.map_mut(|v| std::mem::replace(v, 4)), 3);
+        drop(a);
+        assert_eq!(u32::try_from_slice(&env::storage_read(b"t").unwrap()).unwrap(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "No value found for the given key")]
+    pub fn test_get_after_take() {
+        let mut a = Lazy::new(b"g", 8u32);
+        a.take();
+        a.get();
+    }
+
     #[test]
     pub fn test_remove() {
         let mut lazy = Lazy::new(b"m", 8u8);
@@ -5,7 +5,7 @@ use near_sdk_macros::near;
 use once_cell::unsync::OnceCell;
 
 use crate::env;
-use crate::store::lazy::{load_and_deserialize, serialize_and_store};
+use crate::store::lazy::{load_and_deserialize_optional, serialize_and_store};
 use crate::utils::{CacheEntry, EntryState};
 use crate::IntoStorageKey;
 
@@ -38,11 +38,11 @@ where
     T: BorshSerialize,
 {
     /// Key bytes to index the contract's storage.
-    prefix: Box<[u8]>,
+    pub(crate) prefix: Box<[u8]>,
 
     /// Cached value which is lazily loaded and deserialized from storage.
     #[borsh(skip, bound(deserialize = ""))] // removes `core::default::Default` bound from T
-    cache: OnceCell<CacheEntry<T>>,
+    pub(crate) cache: OnceCell<CacheEntry<T>>,
 }
 
 impl<T> LazyOption<T>
@@ -108,7 +108,7 @@ where
     /// The load from storage only happens once, and if the value is already cached, it will not
     /// be reloaded.
     pub fn get(&self) -> &Option<T> {
-        let entry = self.cache.get_or_init(|| load_and_deserialize(&self.prefix));
+        let entry = self.cache.get_or_init(|| load_and_deserialize_optional(&self.prefix));
         entry.value()
     }
 
@@ -116,9 +116,72 @@ where
     /// The load from storage only happens once, and if the value is already cached, it will not
     /// be reloaded.
     pub fn get_mut(&mut self) -> &mut Option<T> {
-        self.cache.get_or_init(|| load_and_deserialize(&self.prefix));
-        let entry = self.cache.get_mut().unwrap_or_else(|| env::abort());
-        entry.value_mut()
+        self.entry_mut().value_mut()
+    }
+
+    /// Returns a mutable reference to the value, inserting the value returned by `f` if there is
+    /// none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::LazyOption;
+    ///
+    /// let mut a: LazyOption<Vec<u8>> = LazyOption::new(b"a", None);
+    /// a.get_or_insert_with(Vec::new).push(1);
+    /// a.get_or_insert_with(Vec::new).push(2);
+    /// assert_eq!(a.get(), &Some(vec![1, 2]));
+    /// ```
+    pub fn get_or_insert_with<F>(&mut self, f: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        self.entry_mut().value_mut().get_or_insert_with(f)
+    }
+
+    /// Applies `f` to the value in place if there is one, and returns its result. The value is
+    /// written to storage when it is flushed, without being cloned. Nothing is written if there
+    /// is no value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::LazyOption;
+    ///
+    /// let mut a = LazyOption::new(b"a", Some(vec![1u8, 2]));
+    /// assert_eq!(a.map_mut(|v| v.pop()), Some(Some(2)));
+    /// assert_eq!(a.get(), &Some(vec![1]));
+    /// ```
+    pub fn map_mut<F, R>(&mut self, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let entry = self.entry_mut();
+        if entry.value().is_none() {
+            return None;
+        }
+        entry.value_mut().as_mut().map(f)
+    }
+
+    /// Takes the value out of the option, leaving [`None`] in its place. The value is removed
+    /// from storage when the option is flushed. Nothing is written if there is no value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::LazyOption;
+    ///
+    /// let mut a = LazyOption::new(b"a", Some(1u8));
+    /// assert_eq!(a.take(), Some(1));
+    /// assert!(a.is_none());
+    /// ```
+    pub fn take(&mut self) -> Option<T> {
+        self.entry_mut().replace(None)
+    }
+
+    fn entry_mut(&mut self) -> &mut CacheEntry<T> {
+        self.cache.get_or_init(|| load_and_deserialize_optional(&self.prefix));
+        self.cache.get_mut().unwrap_or_else(|| env::abort())
     }
 }
 
@@ -163,6 +226,31 @@ mod tests {
         assert!(!env::storage_has_key(b"a"));
     }
 
+    #[test]
+    pub fn test_combinators() {
+        let mut a = LazyOption::new(b"c", Some(1u32));
+        assert_eq!(a.map_mut(|v| std::mem::replace(v, 2)), Some(1));
+        a.flush();
+        let serialized = borsh::to_vec(&a).unwrap();
+        drop(a);
+
+        let mut a = LazyOption::<u32>::try_from_slice(&serialized).unwrap();
+        assert_eq!(*a.get_or_insert_with(|| 5), 2);
+        assert_eq!(a.take(), Some(2));
+        assert_eq!(a.map_mut(|v| *v), None);
+        drop(a);
+        assert!(!env::storage_has_key(b"c"));
+
+        // Loading a value that was removed from storage.
+        let mut a = LazyOption::<u32>::try_from_slice(&serialized).unwrap();
+        assert!(a.is_none());
+        assert_eq!(a.take(), None);
+        assert!(!a.cache.get().unwrap().is_modified());
+        *a.get_or_insert_with(|| 5) += 1;
+        drop(a);
+        assert_eq!(u32::try_from_slice(&env::storage_read(b"c").unwrap()).unwrap(), 6);
+    }
+
     #[test]
     pub fn test_debug() {
         let mut lazy_option = LazyOption::new(b"m", None);
@@ -430,6 +430,7 @@ where
     /// in memory.
     pub fn flush(&mut self) {
         let mut buf = Vec::new();
+        let mut batch = env::storage_batch();
         for (k, v) in self.cache.inner().iter_mut() {
             if let Some(val) = v.value.get_mut() {
                 if val.is_modified() {
@@ -440,14 +441,14 @@ where
                     });
                     match val.value().as_ref() {
                         Some(modified) => {
-                            buf.clear();
-                            BorshSerialize::serialize(modified, &mut buf)
-                                .unwrap_or_else(|_| env::panic_str(ERR_ELEMENT_SERIALIZATION));
-                            env::storage_write(key.as_ref(), &buf);
+                            batch.write_with(key.as_ref(), |buf| {
+                                BorshSerialize::serialize(modified, buf)
+                                    .unwrap_or_else(|_| env::panic_str(ERR_ELEMENT_SERIALIZATION))
+                            });
                         }
                         None => {
                             // Element was removed, clear the storage for the value
-                            env::storage_remove(key.as_ref());
+                            batch.remove(key.as_ref());
                         }
                     }
 
@@ -457,6 +458,7 @@ where
                 }
             }
         }
+        batch.commit();
     }
 }
 
@@ -465,7 +467,7 @@ where
 mod tests {
     use super::LookupMap;
     use crate::env;
-    use crate::store::key::{Keccak256, ToKey};
+    use crate::store::key::{Identity, Keccak256, RawKey, Sha256, ToKey};
     use crate::test_utils::test_env::setup_free;
     use arbitrary::{Arbitrary, Unstructured};
     use rand::seq::SliceRandom;
@@ -660,6 +662,25 @@ mod tests {
         assert_eq!(dup_map[&5], 8);
     }
 
+    #[test]
+    fn raw_keys() {
+        let mut map = LookupMap::new(b"r");
+        let hash = env::sha256_array(b"key");
+        map.insert(RawKey(hash), 1u8);
+        map.insert(RawKey([0; 32]), 2u8);
+        map.flush();
+
+        let storage_key = Identity::to_key(b"r", &RawKey(hash), &mut Vec::new());
+        assert_eq!(storage_key, [b"r".as_slice(), &hash].concat());
+        assert_eq!(env::storage_read(&storage_key), Some(vec![1]));
+
+        let mut hashed = LookupMap::<_, _, Sha256>::with_hasher(b"r");
+        hashed.insert(RawKey(hash.to_vec()), 3u8);
+        hashed.flush();
+        let storage_key = env::sha256_array(&[b"r".as_slice(), &hash].concat());
+        assert_eq!(env::storage_read(&storage_key), Some(vec![3]));
+    }
+
     #[derive(Arbitrary, Debug)]
     enum Op {
         Insert(u8, u8),
@@ -4,6 +4,7 @@ use crate::store::key::{Identity, ToKey};
 use crate::{env, IntoStorageKey};
 use borsh::BorshSerialize;
 use std::borrow::Borrow;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::marker::PhantomData;
 
@@ -118,6 +119,36 @@ where
         env::storage_has_key(lookup_key.as_ref())
     }
 
+    /// Returns whether the set contains each of the specified values, in the same order.
+    ///
+    /// Values that appear multiple times in `values` are only looked up in storage once.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::store::LookupSet;
+    ///
+    /// let mut allowlist = LookupSet::new(b"a");
+    /// allowlist.insert("alice.near".to_string());
+    /// allowlist.insert("bob.near".to_string());
+    ///
+    /// let accounts = ["alice.near".to_string(), "eve.near".to_string(), "alice.near".to_string()];
+    /// assert_eq!(allowlist.contains_many(&accounts), [true, false, true]);
+    /// ```
+    pub fn contains_many(&self, values: &[T]) -> Vec<bool> {
+        let mut found = BTreeMap::new();
+        let mut buffer = Vec::new();
+        values
+            .iter()
+            .map(|value| {
+                buffer.clear();
+                let lookup_key = H::to_key(&self.prefix, value, &mut buffer);
+                *found
+                    .entry(lookup_key.as_ref().to_vec())
+                    .or_insert_with(|| env::storage_has_key(lookup_key.as_ref()))
+            })
+            .collect()
+    }
+
     /// Adds a value to the set.
     ///
     /// If the set did not have this value present, true is returned.
@@ -175,6 +206,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_contains_many() {
+        let mut set = LookupSet::new(b"m");
+        set.insert(1u8);
+        set.insert(3u8);
+        assert_eq!(set.contains_many(&[3, 2, 1, 3]), [true, false, true, true]);
+        assert!(set.contains_many(&[]).is_empty());
+    }
+
     #[test]
     fn test_insert_remove() {
         let mut set = LookupSet::new(b"m");
@@ -43,18 +43,39 @@
 //!
 //! - [`Vector`]: Analogous to [`Vec`] but not contiguous and persisted to storage.
 //!
+//! - [`FreeList`]: Vector-like container which keeps the indices of the values stable on removal,
+//!   reusing the freed indices for new values.
+//!
+//! - [`EventLog`]: Append-only log with stable, monotonically increasing indices that can be
+//!   paged through and pruned below a watermark.
+//!
+//! - [`Archive`]: Append-only list of records which moves old records into batches stored under a
+//!   single key, reducing the storage overhead of retaining history indefinitely.
+//!
 //! Maps:
 //!
 //! - [`LookupMap`]: Wrapper around key-value storage interactions, similar to
 //!   [`UnorderedMap`]/[`std::collections::HashMap`] except that keys are not persisted and cannot be
 //!   iterated over.
 //!
+//! - [`ExpiringLookupMap`]: [`LookupMap`] whose entries expire at a block timestamp or height,
+//!   and can be purged from storage incrementally.
+//!
 //! - [`UnorderedMap`]: Storage version of [`std::collections::HashMap`]. No ordering
 //!   guarantees.
 //!
 //! - [`TreeMap`] (`unstable`): Storage version of [`std::collections::BTreeMap`]. Ordered by key,
 //!   which comes at the cost of more expensive lookups and iteration.
 //!
+//! - [`InsertionOrderedMap`]: Iterable map which iterates in insertion order, even after
+//!   removals, giving stable pages to paginated views.
+//!
+//! - [`ShardedMap`]: Iterable map that spreads its entries over a fixed number of
+//!   [`IterableMap`] shards, which can be iterated and migrated one at a time.
+//!
+//! - [`LookupMultiMap`]/[`UnorderedMultiMap`]: Maps from a key to a bucket of values, which are
+//!   added and removed individually. [`UnorderedMultiMap`] also allows iterating over the keys.
+//!
 //! Sets:
 //!
 //! - [`LookupSet`]: Non-iterable storage version of [`std::collections::HashSet`].
@@ -62,8 +83,14 @@
 //! - [`UnorderedSet`]: Analogous to [`std::collections::HashSet`], and is an iterable
 //!   version of [`LookupSet`] and persisted to storage.
 //!
+//! - [`BitSet`]: Set of `u64` indices stored as bits packed in words, with range iteration.
+//!   Cheaper than a [`LookupSet<u64>`](LookupSet) for dense indices.
+//!
 //! Basic Types:
 //!
+//! - [`Checkpointed<T>`](Checkpointed): Value which records a checkpoint at every change, and
+//!   can be looked up at any past block height.
+//!
 //! - [`Lazy<T>`](Lazy): Lazily loaded type that can be used in place of a type `T`.
 //!   Will only be loaded when interacted with and will persist on [`Drop`].
 //!
@@ -80,19 +107,38 @@ pub use lazy::Lazy;
 mod lazy_option;
 pub use lazy_option::LazyOption;
 
+mod checkpointed;
+pub use self::checkpointed::Checkpointed;
+
 pub mod vec;
 pub use vec::Vector;
 
+pub mod event_log;
+pub use self::event_log::EventLog;
+
+mod archive;
+pub use self::archive::Archive;
+
 pub mod lookup_map;
 pub use self::lookup_map::LookupMap;
 
 mod lookup_set;
 pub use self::lookup_set::LookupSet;
 
+pub mod bit_set;
+pub use self::bit_set::BitSet;
+
+mod expiring_lookup_map;
+pub use self::expiring_lookup_map::ExpiringLookupMap;
+
 pub mod iterable_map;
 pub use self::iterable_map::IterableMap;
 pub mod iterable_set;
 pub use self::iterable_set::IterableSet;
+pub mod sharded_map;
+pub use self::sharded_map::ShardedMap;
+pub mod insertion_ordered_map;
+pub use self::insertion_ordered_map::InsertionOrderedMap;
 pub mod unordered_map;
 #[allow(deprecated)]
 pub use self::unordered_map::UnorderedMap;
@@ -101,6 +147,9 @@ pub mod unordered_set;
 #[allow(deprecated)]
 pub use self::unordered_set::UnorderedSet;
 
+mod multi_map;
+pub use self::multi_map::{LookupMultiMap, UnorderedMultiMap};
+
 #[cfg(feature = "unstable")]
 pub mod tree_map;
 #[cfg(feature = "unstable")]
@@ -109,8 +158,8 @@ pub use self::tree_map::TreeMap;
 mod index_map;
 pub(crate) use self::index_map::IndexMap;
 
-pub(crate) mod free_list;
-pub(crate) use self::free_list::FreeList;
+pub mod free_list;
+pub use self::free_list::{FreeList, FreeListIndex};
 
 /// Storage key hash function types and trait to override map hash functions.
 pub mod key;
@@ -0,0 +1,601 @@
+use std::borrow::Borrow;
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use near_sdk_macros::near;
+
+use crate::store::key::{Identity, Sha256, ToKey};
+use crate::store::{iterable_map, IterableMap, LookupMap, Vector};
+use crate::IntoStorageKey;
+
+/// A non-iterable map from a key to a bucket of values, stored directly on the storage trie.
+///
+/// Each key maps to a [`Vector`] of values that is created on the first [`add`] and removed
+/// once its last value is removed, so that the buckets of different keys never share storage.
+/// The buckets are stored under a prefix derived from the map's `prefix` and the key using the
+/// map's [`ToKey`] implementation.
+///
+/// The default hash function for [`LookupMultiMap`] is [`Identity`], like for
+/// [`LookupMap`](crate::store::LookupMap). To use a custom function, use [`with_hasher`].
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::LookupMultiMap;
+///
+/// let mut listings = LookupMultiMap::new(b"l");
+///
+/// listings.add("alice".to_string(), 1u64);
+/// listings.add("alice".to_string(), 2u64);
+/// listings.add("bob".to_string(), 3u64);
+///
+/// assert_eq!(listings.count("alice"), 2);
+/// assert!(listings.contains("bob", &3));
+///
+/// assert!(listings.remove_value("alice", &1));
+/// let alice: Vec<_> = listings.get("alice").unwrap().iter().copied().collect();
+/// assert_eq!(alice, [2]);
+///
+/// assert!(listings.remove_value("bob", &3));
+/// assert!(!listings.contains_key("bob"));
+/// ```
+///
+/// [`add`]: Self::add
+/// [`with_hasher`]: Self::with_hasher
+#[near(inside_nearsdk)]
+pub struct LookupMultiMap<K, V, H = Identity>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    bucket_prefix: Box<[u8]>,
+    // ser/de is independent of `K`, `V`, `H` ser/de, `BorshSerialize`/`BorshDeserialize`/`BorshSchema` bounds removed
+    #[cfg_attr(not(feature = "abi"), borsh(bound(serialize = "", deserialize = "")))]
+    #[cfg_attr(
+        feature = "abi",
+        borsh(bound(serialize = "", deserialize = ""), schema(params = ""))
+    )]
+    buckets: LookupMap<K, Vector<V>, H>,
+}
+
+impl<K, V, H> fmt::Debug for LookupMultiMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LookupMultiMap").field("bucket_prefix", &self.bucket_prefix).finish()
+    }
+}
+
+impl<K, V> LookupMultiMap<K, V, Identity>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+{
+    /// Create a new map. Use `prefix` as a unique prefix for keys.
+    ///
+    /// This prefix can be anything that implements [`IntoStorageKey`]. The prefix is used when
+    /// storing and looking up values in storage to ensure no collisions with other collections.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::LookupMultiMap;
+    ///
+    /// let map: LookupMultiMap<String, u64> = LookupMultiMap::new(b"m");
+    /// ```
+    #[inline]
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self::with_hasher(prefix)
+    }
+}
+
+impl<K, V, H> LookupMultiMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    /// Initialize a [`LookupMultiMap`] with a custom hash function.
+    ///
+    /// # Example
+    /// ```
+    /// use near_sdk::store::{LookupMultiMap, key::Keccak256};
+    ///
+    /// let map = LookupMultiMap::<String, String, Keccak256>::with_hasher(b"m");
+    /// ```
+    pub fn with_hasher<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let mut bucket_prefix = prefix.into_storage_key();
+        let map_key = [bucket_prefix.as_slice(), b"m"].concat();
+        bucket_prefix.push(b'v');
+        Self {
+            bucket_prefix: bucket_prefix.into_boxed_slice(),
+            buckets: LookupMap::with_hasher(map_key),
+        }
+    }
+
+    /// Returns the bucket of values stored for the key, if there are any.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&Vector<V>>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.buckets.get(k)
+    }
+
+    /// Returns the number of values stored for the key.
+    pub fn count<Q: ?Sized>(&self, k: &Q) -> u32
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.get(k).map_or(0, Vector::len)
+    }
+
+    /// Returns `true` if there is at least one value stored for the key.
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.get(k).is_some()
+    }
+
+    /// Returns `true` if the bucket of the key contains the value.
+    ///
+    /// # Performance
+    ///
+    /// This iterates over the values of the key, so the cost grows linearly with the bucket size.
+    pub fn contains<Q: ?Sized>(&self, k: &Q, value: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+        V: BorshDeserialize + PartialEq,
+    {
+        self.get(k).map_or(false, |bucket| bucket.iter().any(|v| v == value))
+    }
+
+    /// Appends a value to the bucket of the key, creating the bucket if it does not exist.
+    pub fn add(&mut self, k: K, value: V)
+    where
+        K: Clone,
+    {
+        let bucket_prefix = &self.bucket_prefix;
+        let bucket =
+            self.buckets.entry(k).or_insert_with_key(|k| new_bucket::<K, V, H>(bucket_prefix, k));
+        bucket.push(value);
+        // Buckets are flushed right away, as the map only flushes their metadata.
+        bucket.flush();
+    }
+
+    /// Removes the first occurrence of the value from the bucket of the key. The last value of
+    /// the bucket takes its place, so the order of the remaining values is not preserved.
+    /// Returns `true` if the value was present.
+    ///
+    /// The bucket is removed from storage when its last value is removed.
+    ///
+    /// # Performance
+    ///
+    /// This iterates over the values of the key, so the cost grows linearly with the bucket size.
+    pub fn remove_value<Q: ?Sized>(&mut self, k: &Q, value: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+        V: BorshDeserialize + PartialEq,
+    {
+        let bucket = match self.buckets.get_mut(k) {
+            Some(bucket) => bucket,
+            None => return false,
+        };
+        if !remove_from_bucket(bucket, value) {
+            return false;
+        }
+        if bucket.is_empty() {
+            self.buckets.remove(k);
+        }
+        true
+    }
+
+    /// Removes all values stored for the key. Returns `true` if there were any.
+    pub fn remove_all<Q: ?Sized>(&mut self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        match self.buckets.remove(k) {
+            Some(mut bucket) => {
+                bucket.clear();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Flushes the intermediate values of the map before this is called when the structure is
+    /// [`Drop`]ed. This will write all modified values to storage but keep all cached values
+    /// in memory.
+    pub fn flush(&mut self) {
+        self.buckets.flush()
+    }
+}
+
+/// An iterable map from a key to a bucket of values, stored directly on the storage trie.
+///
+/// This is the iterable version of [`LookupMultiMap`]: the keys are tracked by an
+/// [`IterableMap`], so the keys that have at least one value can be iterated over.
+///
+/// The default hash function for [`UnorderedMultiMap`] is [`Sha256`], like for
+/// [`IterableMap`]. To use a custom function, use [`with_hasher`].
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::UnorderedMultiMap;
+///
+/// let mut listings = UnorderedMultiMap::new(b"l");
+///
+/// listings.add("alice".to_string(), 1u64);
+/// listings.add("alice".to_string(), 2u64);
+/// listings.add("bob".to_string(), 3u64);
+/// assert_eq!(listings.len(), 2);
+///
+/// for (owner, bucket) in listings.iter() {
+///     for listing in bucket {
+///         assert!(listings.contains(owner, listing));
+///     }
+/// }
+///
+/// listings.remove_all("alice");
+/// assert_eq!(listings.keys().collect::<Vec<_>>(), ["bob"]);
+/// ```
+///
+/// [`with_hasher`]: Self::with_hasher
+#[near(inside_nearsdk)]
+pub struct UnorderedMultiMap<K, V, H = Sha256>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    bucket_prefix: Box<[u8]>,
+    // ser/de is independent of `K`, `V`, `H` ser/de, `BorshSerialize`/`BorshDeserialize`/`BorshSchema` bounds removed
+    #[cfg_attr(not(feature = "abi"), borsh(bound(serialize = "", deserialize = "")))]
+    #[cfg_attr(
+        feature = "abi",
+        borsh(bound(serialize = "", deserialize = ""), schema(params = ""))
+    )]
+    buckets: IterableMap<K, Vector<V>, H>,
+}
+
+impl<K, V, H> fmt::Debug for UnorderedMultiMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnorderedMultiMap").field("bucket_prefix", &self.bucket_prefix).finish()
+    }
+}
+
+impl<K, V> UnorderedMultiMap<K, V, Sha256>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+{
+    /// Create a new iterable map. Use `prefix` as a unique prefix for keys.
+    ///
+    /// This prefix can be anything that implements [`IntoStorageKey`]. The prefix is used when
+    /// storing and looking up values in storage to ensure no collisions with other collections.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::UnorderedMultiMap;
+    ///
+    /// let map: UnorderedMultiMap<String, u64> = UnorderedMultiMap::new(b"m");
+    /// ```
+    #[inline]
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self::with_hasher(prefix)
+    }
+}
+
+impl<K, V, H> UnorderedMultiMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    /// Initialize a [`UnorderedMultiMap`] with a custom hash function.
+    ///
+    /// # Example
+    /// ```
+    /// use near_sdk::store::{UnorderedMultiMap, key::Keccak256};
+    ///
+    /// let map = UnorderedMultiMap::<String, String, Keccak256>::with_hasher(b"m");
+    /// ```
+    pub fn with_hasher<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let mut bucket_prefix = prefix.into_storage_key();
+        let map_key = [bucket_prefix.as_slice(), b"m"].concat();
+        bucket_prefix.push(b'v');
+        Self {
+            bucket_prefix: bucket_prefix.into_boxed_slice(),
+            buckets: IterableMap::with_hasher(map_key),
+        }
+    }
+
+    /// Returns the number of keys that have at least one value.
+    pub fn len(&self) -> u32 {
+        self.buckets.len()
+    }
+
+    /// Returns `true` if there are no values in the map.
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// An iterator visiting all keys that have at least one value, in arbitrary order.
+    pub fn keys(&self) -> iterable_map::Keys<K>
+    where
+        K: BorshDeserialize,
+    {
+        self.buckets.keys()
+    }
+
+    /// An iterator visiting all keys with their buckets of values, in arbitrary order.
+    pub fn iter(&self) -> iterable_map::Iter<K, Vector<V>, H>
+    where
+        K: BorshDeserialize,
+        V: BorshDeserialize,
+    {
+        self.buckets.iter()
+    }
+
+    /// Returns the bucket of values stored for the key, if there are any.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&Vector<V>>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.buckets.get(k)
+    }
+
+    /// Returns the number of values stored for the key.
+    pub fn count<Q: ?Sized>(&self, k: &Q) -> u32
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.get(k).map_or(0, Vector::len)
+    }
+
+    /// Returns `true` if there is at least one value stored for the key.
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.buckets.contains_key(k)
+    }
+
+    /// Returns `true` if the bucket of the key contains the value.
+    ///
+    /// # Performance
+    ///
+    /// This iterates over the values of the key, so the cost grows linearly with the bucket size.
+    pub fn contains<Q: ?Sized>(&self, k: &Q, value: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+        V: BorshDeserialize + PartialEq,
+    {
+        self.get(k).map_or(false, |bucket| bucket.iter().any(|v| v == value))
+    }
+
+    /// Appends a value to the bucket of the key, creating the bucket if it does not exist.
+    pub fn add(&mut self, k: K, value: V)
+    where
+        K: BorshDeserialize + Clone,
+    {
+        let bucket_prefix = &self.bucket_prefix;
+        let bucket =
+            self.buckets.entry(k).or_insert_with_key(|k| new_bucket::<K, V, H>(bucket_prefix, k));
+        bucket.push(value);
+        // Buckets are flushed right away, as the map only flushes their metadata.
+        bucket.flush();
+    }
+
+    /// Removes the first occurrence of the value from the bucket of the key. The last value of
+    /// the bucket takes its place, so the order of the remaining values is not preserved.
+    /// Returns `true` if the value was present.
+    ///
+    /// The key is removed from the map when its last value is removed.
+    ///
+    /// # Performance
+    ///
+    /// This iterates over the values of the key, so the cost grows linearly with the bucket size.
+    pub fn remove_value<Q: ?Sized>(&mut self, k: &Q, value: &V) -> bool
+    where
+        K: Borrow<Q> + BorshDeserialize,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+        V: BorshDeserialize + PartialEq,
+    {
+        let bucket = match self.buckets.get_mut(k) {
+            Some(bucket) => bucket,
+            None => return false,
+        };
+        if !remove_from_bucket(bucket, value) {
+            return false;
+        }
+        if bucket.is_empty() {
+            self.buckets.remove(k);
+        }
+        true
+    }
+
+    /// Removes all values stored for the key. Returns `true` if there were any.
+    pub fn remove_all<Q: ?Sized>(&mut self, k: &Q) -> bool
+    where
+        K: Borrow<Q> + BorshDeserialize,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        match self.buckets.remove(k) {
+            Some(mut bucket) => {
+                bucket.clear();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Flushes the intermediate values of the map before this is called when the structure is
+    /// [`Drop`]ed. This will write all modified values to storage but keep all cached values
+    /// in memory.
+    pub fn flush(&mut self) {
+        self.buckets.flush()
+    }
+}
+
+fn new_bucket<K, V, H>(bucket_prefix: &[u8], k: &K) -> Vector<V>
+where
+    K: BorshSerialize,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    Vector::new(H::to_key(bucket_prefix, k, &mut Vec::new()).as_ref())
+}
+
+fn remove_from_bucket<V>(bucket: &mut Vector<V>, value: &V) -> bool
+where
+    V: BorshSerialize + BorshDeserialize + PartialEq,
+{
+    match bucket.iter().position(|v| v == value) {
+        Some(index) => {
+            bucket.swap_remove(index as u32);
+            bucket.flush();
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::{LookupMultiMap, UnorderedMultiMap};
+    use crate::env;
+    use crate::store::key::{Keccak256, ToKey};
+    use borsh::{to_vec, BorshDeserialize};
+    use rand::{Rng, SeedableRng};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn buckets_are_separated() {
+        let mut map = LookupMultiMap::new(b"m");
+        map.add(1u8, 10u32);
+        map.add(1, 11);
+        map.add(2, 20);
+
+        assert_eq!(map.count(&1), 2);
+        assert_eq!(map.count(&2), 1);
+        assert_eq!(map.count(&3), 0);
+        assert!(map.contains(&1, &11));
+        assert!(!map.contains(&2, &11));
+
+        assert!(!map.remove_value(&2, &11));
+        assert!(map.remove_value(&1, &10));
+        assert_eq!(map.get(&1).unwrap().iter().copied().collect::<Vec<_>>(), [11]);
+        assert!(map.remove_value(&1, &11));
+        assert!(!map.contains_key(&1));
+        assert_eq!(map.count(&2), 1);
+    }
+
+    #[test]
+    fn remove_all_clears_storage() {
+        let mut map = LookupMultiMap::<u8, u32, Keccak256>::with_hasher(b"m");
+        for i in 0..5 {
+            map.add(1, i);
+        }
+        map.flush();
+
+        let bucket_prefix = Keccak256::to_key(b"mv", &1u8, &mut Vec::new());
+        let element_key = [bucket_prefix.as_ref(), &4u32.to_le_bytes()].concat();
+        assert!(env::storage_has_key(&element_key));
+
+        assert!(map.remove_all(&1));
+        assert!(!map.remove_all(&1));
+        map.flush();
+        assert!(!env::storage_has_key(&element_key));
+        assert_eq!(map.get(&1), None);
+    }
+
+    #[test]
+    fn persists_between_loads() {
+        let mut map = UnorderedMultiMap::new(b"m");
+        map.add("a".to_string(), 1u8);
+        map.add("a".to_string(), 2);
+        map.add("b".to_string(), 3);
+        map.flush();
+
+        let map = UnorderedMultiMap::<String, u8>::try_from_slice(&to_vec(&map).unwrap()).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.count("a"), 2);
+        assert!(map.contains("b", &3));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(2);
+        let mut map = UnorderedMultiMap::new(b"m");
+        let mut baseline = BTreeMap::<u8, Vec<u8>>::new();
+        for _ in 0..500 {
+            let key = rng.gen_range(0..8u8);
+            let value = rng.gen_range(0..16u8);
+            if rng.gen_bool(0.6) {
+                map.add(key, value);
+                baseline.entry(key).or_default().push(value);
+            } else {
+                let expected = match baseline.get_mut(&key) {
+                    Some(bucket) => match bucket.iter().position(|v| *v == value) {
+                        Some(index) => {
+                            bucket.swap_remove(index);
+                            true
+                        }
+                        None => false,
+                    },
+                    None => false,
+                };
+                baseline.retain(|_, b| !b.is_empty());
+                assert_eq!(map.remove_value(&key, &value), expected);
+            }
+        }
+
+        assert_eq!(map.len() as usize, baseline.len());
+        for (key, bucket) in map.iter() {
+            assert_eq!(bucket.iter().copied().collect::<Vec<_>>(), baseline[key]);
+        }
+    }
+}
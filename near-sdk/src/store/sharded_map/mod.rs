@@ -0,0 +1,573 @@
+use std::borrow::Borrow;
+use std::iter::{Flatten, FusedIterator};
+use std::{fmt, slice};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use near_sdk_macros::near;
+
+use crate::store::iterable_map::Entry;
+use crate::store::key::{Sha256, ToKey};
+use crate::store::IterableMap;
+use crate::{env, IntoStorageKey};
+
+/// An iterable map that distributes its entries over a fixed number of [`IterableMap`] shards,
+/// each stored under its own prefix.
+///
+/// The shard of a key is picked from the SHA-256 hash of its [`BorshSerialize`] representation,
+/// so entries are spread evenly over the shards. This keeps the bookkeeping of each shard, e.g.
+/// the vector of keys of an [`IterableMap`], small for very large maps, and allows iterating,
+/// migrating or clearing the map one shard at a time, over multiple transactions, through
+/// [`shard`] and [`shard_mut`].
+///
+/// The number of shards is set on creation and cannot be changed afterwards, as it would move
+/// entries to different shards. The metadata of all shards is loaded with the map, so the number
+/// of shards should stay small, e.g. 16 or 64.
+///
+/// Within each shard, values are stored like in an [`IterableMap`], under a hash of the shard's
+/// prefix and the key using the map's [`ToKey`] implementation, [`Sha256`] by default.
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::ShardedMap;
+///
+/// // The `b"s"` parameter is a prefix for the storage keys of the map, and 16 is the number of
+/// // shards the entries are distributed over.
+/// let mut balances = ShardedMap::new(b"s", 16);
+///
+/// balances.insert("alice".to_string(), 10u128);
+/// balances.insert("bob".to_string(), 20u128);
+/// *balances.get_mut("alice").unwrap() += 5;
+///
+/// assert_eq!(balances.get("alice"), Some(&15));
+/// assert_eq!(balances.len(), 2);
+/// assert_eq!(balances.values().sum::<u128>(), 35);
+///
+/// // Entries can be processed one shard at a time, e.g. in separate transactions.
+/// let shard = balances.shard_of("bob");
+/// assert_eq!(balances.shard(shard).unwrap().get("bob"), Some(&20));
+/// ```
+///
+/// [`shard`]: Self::shard
+/// [`shard_mut`]: Self::shard_mut
+#[near(inside_nearsdk)]
+pub struct ShardedMap<K, V, H = Sha256>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    // ser/de is independent of `K`, `V`, `H` ser/de, `BorshSerialize`/`BorshDeserialize`/`BorshSchema` bounds removed
+    #[cfg_attr(not(feature = "abi"), borsh(bound(serialize = "", deserialize = "")))]
+    #[cfg_attr(
+        feature = "abi",
+        borsh(bound(serialize = "", deserialize = ""), schema(params = ""))
+    )]
+    shards: Vec<IterableMap<K, V, H>>,
+}
+
+impl<K, V, H> Drop for ShardedMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    fn drop(&mut self) {
+        self.flush()
+    }
+}
+
+impl<K, V, H> fmt::Debug for ShardedMap<K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + fmt::Debug,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShardedMap").field("shards", &self.shards).finish()
+    }
+}
+
+impl<K, V> ShardedMap<K, V, Sha256>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+{
+    /// Create a new map with `shard_count` shards. Use `prefix` as a unique prefix for keys.
+    ///
+    /// This prefix can be anything that implements [`IntoStorageKey`]. The prefix of each shard
+    /// is this prefix followed by the index of the shard as a little-endian `u32`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shard_count` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::ShardedMap;
+    ///
+    /// let mut map: ShardedMap<String, u8> = ShardedMap::new(b"s", 8);
+    /// ```
+    #[inline]
+    pub fn new<S>(prefix: S, shard_count: u32) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self::with_hasher(prefix, shard_count)
+    }
+}
+
+impl<K, V, H> ShardedMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    /// Initialize a [`ShardedMap`] with a custom hash function for the keys of the shards. The
+    /// shard of a key is picked from its SHA-256 hash regardless of `H`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shard_count` is zero.
+    ///
+    /// # Example
+    /// ```
+    /// use near_sdk::store::{ShardedMap, key::Keccak256};
+    ///
+    /// let map = ShardedMap::<String, String, Keccak256>::with_hasher(b"s", 8);
+    /// ```
+    pub fn with_hasher<S>(prefix: S, shard_count: u32) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        if shard_count == 0 {
+            env::panic_str("A sharded map needs at least one shard");
+        }
+        let prefix = prefix.into_storage_key();
+        let shards = (0..shard_count)
+            .map(|index| {
+                IterableMap::with_hasher([prefix.as_slice(), &index.to_le_bytes()].concat())
+            })
+            .collect();
+        Self { shards }
+    }
+
+    /// Returns the number of shards of the map.
+    pub fn shard_count(&self) -> u32 {
+        self.shards.len() as u32
+    }
+
+    /// Returns the shard with the given index, or `None` if the index is out of bounds.
+    pub fn shard(&self, index: u32) -> Option<&IterableMap<K, V, H>> {
+        self.shards.get(index as usize)
+    }
+
+    /// Returns the shard with the given index mutably, or `None` if the index is out of bounds.
+    ///
+    /// Entries inserted through the shard have to belong to it, i.e. [`shard_of`](Self::shard_of)
+    /// has to return `index` for their key, otherwise they cannot be found through the map.
+    pub fn shard_mut(&mut self, index: u32) -> Option<&mut IterableMap<K, V, H>> {
+        self.shards.get_mut(index as usize)
+    }
+
+    /// Returns the index of the shard the entry of the given key is stored in.
+    ///
+    /// The key may be any borrowed form of the map's key type, but [`BorshSerialize`] on the
+    /// borrowed form *must* match that of the key type.
+    pub fn shard_of<Q: ?Sized>(&self, k: &Q) -> u32
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize,
+    {
+        if self.shards.len() == 1 {
+            return 0;
+        }
+        let key = borsh::to_vec(k).unwrap_or_else(|_| env::abort());
+        let hash = env::sha256_array(&key);
+        u32::from_le_bytes([hash[0], hash[1], hash[2], hash[3]]) % self.shard_count()
+    }
+
+    fn shard_for<Q: ?Sized>(&self, k: &Q) -> &IterableMap<K, V, H>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize,
+    {
+        &self.shards[self.shard_of(k) as usize]
+    }
+
+    fn shard_for_mut<Q: ?Sized>(&mut self, k: &Q) -> &mut IterableMap<K, V, H>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize,
+    {
+        let index = self.shard_of(k) as usize;
+        &mut self.shards[index]
+    }
+
+    /// Return the amount of elements inside of the map, summed over all shards.
+    pub fn len(&self) -> u64 {
+        self.shards.iter().map(|shard| u64::from(shard.len())).sum()
+    }
+
+    /// Returns true if there are no elements inside of the map.
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(IterableMap::is_empty)
+    }
+
+    /// Clears the map, removing all key-value pairs from all shards.
+    ///
+    /// This removes every entry of the map in the current transaction. Use
+    /// [`shard_mut`](Self::shard_mut) to clear large maps one shard at a time instead.
+    pub fn clear(&mut self)
+    where
+        K: BorshDeserialize + Clone,
+        V: BorshDeserialize,
+    {
+        for shard in self.shards.iter_mut() {
+            shard.clear();
+        }
+    }
+
+    /// An iterator visiting all key-value pairs, one shard after the other.
+    /// The iterator element type is `(&'a K, &'a V)`.
+    pub fn iter(&self) -> Iter<K, V, H>
+    where
+        K: BorshDeserialize + Clone,
+        V: BorshDeserialize,
+    {
+        Iter { inner: self.shards.iter().flatten() }
+    }
+
+    /// An iterator visiting all keys, one shard after the other.
+    /// The iterator element type is `&'a K`.
+    pub fn keys(&self) -> Keys<K, V, H>
+    where
+        K: BorshDeserialize + Clone,
+        V: BorshDeserialize,
+    {
+        Keys { inner: self.iter() }
+    }
+
+    /// An iterator visiting all values, one shard after the other.
+    /// The iterator element type is `&'a V`.
+    pub fn values(&self) -> Values<K, V, H>
+    where
+        K: BorshDeserialize + Clone,
+        V: BorshDeserialize,
+    {
+        Values { inner: self.iter() }
+    }
+
+    /// Flushes the intermediate values of all shards before this is called when the structure
+    /// is [`Drop`]ed. This will write all modified values to storage but keep all cached values
+    /// in memory.
+    pub fn flush(&mut self) {
+        for shard in self.shards.iter_mut() {
+            shard.flush();
+        }
+    }
+}
+
+impl<K, V, H> ShardedMap<K, V, H>
+where
+    K: BorshSerialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.shard_for(k).get(k)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn get_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.shard_for_mut(k).get_mut(k)
+    }
+
+    /// Inserts a key-value pair into the shard of the key.
+    ///
+    /// If the map did not have this key present, [`None`] is returned. Otherwise the value is
+    /// updated and the old value is returned.
+    pub fn insert(&mut self, k: K, value: V) -> Option<V>
+    where
+        K: BorshDeserialize,
+    {
+        self.shard_for_mut(&k).insert(k, value)
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K> + Ord,
+    {
+        self.shard_for(k).contains_key(k)
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn remove<Q: ?Sized>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q> + BorshDeserialize,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.shard_for_mut(k).remove(k)
+    }
+
+    /// Gets the given key's corresponding entry in its shard for in-place manipulation.
+    ///
+    /// ```
+    /// use near_sdk::store::ShardedMap;
+    ///
+    /// let mut count = ShardedMap::new(b"s", 4);
+    ///
+    /// for ch in [7, 2, 4, 7, 4, 1, 7] {
+    ///     *count.entry(ch).or_insert(0) += 1;
+    /// }
+    ///
+    /// assert_eq!(count.get(&4), Some(&2));
+    /// assert_eq!(count.get(&7), Some(&3));
+    /// assert_eq!(count.get(&8), None);
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<K, V, H> {
+        let index = self.shard_of(&key) as usize;
+        self.shards[index].entry(key)
+    }
+}
+
+impl<K, V, H> Extend<(K, V)> for ShardedMap<K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+/// An iterator over elements of a [`ShardedMap`], one shard after the other.
+///
+/// This `struct` is created by the `iter` method on [`ShardedMap`].
+pub struct Iter<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    inner: Flatten<slice::Iter<'a, IterableMap<K, V, H>>>,
+}
+
+impl<'a, K, V, H> Iterator for Iter<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<'a, K, V, H> FusedIterator for Iter<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+}
+
+impl<'a, K, V, H> IntoIterator for &'a ShardedMap<K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, H>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the keys of a [`ShardedMap`], one shard after the other.
+///
+/// This `struct` is created by the `keys` method on [`ShardedMap`].
+pub struct Keys<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    inner: Iter<'a, K, V, H>,
+}
+
+impl<'a, K, V, H> Iterator for Keys<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+}
+
+impl<'a, K, V, H> FusedIterator for Keys<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+}
+
+/// An iterator over the values of a [`ShardedMap`], one shard after the other.
+///
+/// This `struct` is created by the `values` method on [`ShardedMap`].
+pub struct Values<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    inner: Iter<'a, K, V, H>,
+}
+
+impl<'a, K, V, H> Iterator for Values<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+}
+
+impl<'a, K, V, H> FusedIterator for Values<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::ShardedMap;
+    use crate::test_utils::test_env::setup_free;
+    use borsh::{to_vec, BorshDeserialize};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn basic_functionality() {
+        let mut map = ShardedMap::new(b"s", 4);
+        assert!(map.is_empty());
+        assert!(map.insert("test".to_string(), 5u8).is_none());
+        assert_eq!(map.get("test"), Some(&5));
+        assert!(map.contains_key("test"));
+        *map.get_mut("test").unwrap() = 6;
+        assert_eq!(map.insert("test".to_string(), 7), Some(6));
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.remove("test"), Some(7));
+        assert_eq!(map.remove("test"), None);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn entries_are_spread_over_shards() {
+        let mut map = ShardedMap::new(b"s", 4);
+        let mut expected = BTreeMap::new();
+        for i in 0..200u32 {
+            map.insert(i, i * 2);
+            expected.insert(i, i * 2);
+        }
+
+        assert_eq!(map.len(), 200);
+        for index in 0..map.shard_count() {
+            let shard = map.shard(index).unwrap();
+            assert!(shard.len() > 20, "shard {} only has {} entries", index, shard.len());
+            assert!(shard.keys().all(|key| map.shard_of(key) == index));
+        }
+        assert!(map.shard(4).is_none());
+
+        let mut actual: Vec<_> = map.iter().map(|(k, v)| (*k, *v)).collect();
+        actual.sort();
+        assert_eq!(actual, expected.into_iter().collect::<Vec<_>>());
+        assert_eq!(map.keys().count(), 200);
+        assert_eq!(map.values().map(|v| u64::from(*v)).sum::<u64>(), 199 * 200);
+    }
+
+    #[test]
+    fn clear_shard_by_shard() {
+        let mut map = ShardedMap::new(b"s", 3);
+        map.extend((0..30u8).map(|i| (i, i)));
+        for index in 0..map.shard_count() {
+            let before = map.len();
+            let shard_len = map.shard(index).unwrap().len();
+            map.shard_mut(index).unwrap().clear();
+            assert_eq!(map.len(), before - u64::from(shard_len));
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.get(&5), None);
+    }
+
+    #[test]
+    fn persisted_state() {
+        setup_free();
+        let mut map = ShardedMap::new(b"s", 8);
+        map.extend((0..50u8).map(|i| (i, i)));
+        map.flush();
+
+        let map = ShardedMap::<u8, u8>::try_from_slice(&to_vec(&map).unwrap()).unwrap();
+        assert_eq!(map.shard_count(), 8);
+        assert_eq!(map.len(), 50);
+        for i in 0..50 {
+            assert_eq!(map.get(&i), Some(&i));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "A sharded map needs at least one shard")]
+    fn zero_shards() {
+        let _ = ShardedMap::<u8, u8>::new(b"s", 0);
+    }
+}
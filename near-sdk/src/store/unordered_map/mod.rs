@@ -629,6 +629,48 @@ where
     {
         Entry::new(self.values.entry(key), &mut self.keys)
     }
+
+    /// Retains only the entries for which `f` returns `true`, removing the others.
+    ///
+    /// The slots of the removed keys are reused on subsequent [`insert`](Self::insert)
+    /// operations, as with [`remove`](Self::remove).
+    ///
+    /// # Performance
+    ///
+    /// Every key and value of the map is loaded from storage. As `f` can modify the values, the
+    /// retained values are also written back to storage when the map is flushed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::UnorderedMap;
+    ///
+    /// let mut expiries: UnorderedMap<String, u64> = UnorderedMap::new(b"m");
+    /// expiries.insert("alice".to_string(), 10);
+    /// expiries.insert("bob".to_string(), 30);
+    /// expiries.insert("carol".to_string(), 20);
+    ///
+    /// let now = 20;
+    /// expiries.retain(|_, expires_at| *expires_at > now);
+    /// assert_eq!(expiries.len(), 1);
+    /// assert_eq!(expiries.get("bob"), Some(&30));
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        K: BorshDeserialize + Clone,
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let values = &mut self.values;
+        self.keys.retain(|key| {
+            let entry =
+                values.get_mut(key).unwrap_or_else(|| env::panic_str(ERR_INCONSISTENT_STATE));
+            let keep = f(key, &mut entry.value);
+            if !keep {
+                values.set(key.clone(), None);
+            }
+            keep
+        });
+    }
 }
 
 impl<K, V, H> UnorderedMap<K, V, H>
@@ -688,12 +730,49 @@ where
             }
         });
     }
+
+    /// Incrementally removes empty placeholders leftover from calling [`remove`](Self::remove),
+    /// reading at most `max_entries` slots of the underlying vector per call.
+    ///
+    /// Unlike [`defrag`](Self::defrag), the gas cost of a single call is bounded, so a heavily
+    /// fragmented map can be compacted across multiple transactions. Entries from the back of
+    /// the map are moved into the empty slots and the trailing slots are removed from storage.
+    /// The map stays fully usable between calls.
+    ///
+    /// Returns `true` when there are no empty slots left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::UnorderedMap;
+    ///
+    /// let mut map = UnorderedMap::new(b"b");
+    ///
+    /// for i in 0..8 {
+    ///     map.insert(i, i);
+    /// }
+    ///
+    /// for i in 0..6 {
+    ///     map.remove(&i);
+    /// }
+    ///
+    /// while !map.defrag_partial(2) {}
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    pub fn defrag_partial(&mut self, max_entries: u32) -> bool {
+        self.keys.defrag_partial(max_entries, |key, new_index| {
+            if let Some(existing) = self.values.get_mut(key) {
+                existing.key_index = FreeListIndex(new_index);
+            }
+        })
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
     use super::UnorderedMap;
+    use crate::store::free_list::FreeListIndex;
     use crate::test_utils::test_env::setup_free;
     use arbitrary::{Arbitrary, Unstructured};
     use borsh::{to_vec, BorshDeserialize};
@@ -716,6 +795,26 @@ mod tests {
         assert_eq!(map.len(), 0);
     }
 
+    #[test]
+    fn retain() {
+        let mut map = UnorderedMap::new(b"b");
+        map.extend((0..10u8).map(|i| (i, u32::from(i))));
+
+        map.retain(|k, v| {
+            *v += 1;
+            k % 2 == 0
+        });
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.get(&3), None);
+        assert!(map.iter().map(|(k, v)| (*k, *v)).eq([(0, 1), (2, 3), (4, 5), (6, 7), (8, 9)]));
+
+        // Removed slots are reused for new keys.
+        map.insert(10, 10);
+        map.flush();
+        assert_eq!(map.keys.len(), 6);
+        assert_eq!(map.values.get(&10).unwrap().key_index, FreeListIndex(9));
+    }
+
     #[test]
     fn entry_api() {
         let mut map = UnorderedMap::new(b"b");
@@ -850,6 +949,50 @@ mod tests {
         assert_eq!(map.remove_entry(&3).unwrap(), (3, 3));
     }
 
+    #[test]
+    fn defrag_partial() {
+        let mut map = UnorderedMap::new(b"b");
+
+        for i in 0..=8 {
+            map.insert(i, i);
+        }
+
+        let removed = [0, 2, 4, 6, 8];
+        let existing = [1, 3, 5, 7];
+
+        for id in removed {
+            map.remove(&id);
+        }
+
+        let mut calls = 0;
+        while !map.defrag_partial(2) {
+            calls += 1;
+            // Map is consistent between partial defrags.
+            for i in existing {
+                assert_eq!(map.get(&i), Some(&i));
+            }
+            map.insert(100, 100);
+            assert_eq!(map.remove(&100), Some(100));
+        }
+        assert!(calls > 1);
+
+        for i in removed {
+            assert_eq!(map.get(&i), None);
+        }
+        for i in existing {
+            assert_eq!(map.get(&i), Some(&i));
+        }
+
+        let mut keys: Vec<_> = map.keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, existing);
+
+        for i in existing {
+            assert_eq!(map.remove_entry(&i).unwrap(), (i, i));
+        }
+        assert!(map.is_empty());
+    }
+
     #[cfg(feature = "abi")]
     #[test]
     fn test_borsh_schema() {
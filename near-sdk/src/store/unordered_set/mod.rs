@@ -482,6 +482,27 @@ where
         self.index.contains_key(value)
     }
 
+    /// Returns whether the set contains each of the specified values, in the same order.
+    ///
+    /// Lookups are cached, so values that appear multiple times in `values`, or that were already
+    /// looked up, are only read from storage once.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::store::UnorderedSet;
+    ///
+    /// let mut set = UnorderedSet::new(b"s");
+    /// set.insert(1u32);
+    /// set.insert(4u32);
+    /// assert_eq!(set.contains_many(&[4, 2, 1]), [true, false, true]);
+    /// ```
+    pub fn contains_many(&self, values: &[T]) -> Vec<bool>
+    where
+        T: Clone,
+    {
+        values.iter().map(|value| self.index.contains_key(value)).collect()
+    }
+
     /// Adds a value to the set.
     ///
     /// If the set did not have this value present, true is returned.
@@ -532,6 +553,37 @@ where
         }
     }
 
+    /// Retains only the values for which `f` returns `true`, removing the others.
+    ///
+    /// The slots of the removed values are reused on subsequent [`insert`](Self::insert)
+    /// operations, as with [`remove`](Self::remove). Every value of the set is loaded from
+    /// storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::UnorderedSet;
+    ///
+    /// let mut set = UnorderedSet::new(b"s");
+    /// set.extend(1..=6u32);
+    /// set.retain(|v| v % 2 == 0);
+    /// assert!(set.iter().eq([2, 4, 6].iter()));
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        T: BorshDeserialize + Clone,
+        F: FnMut(&T) -> bool,
+    {
+        let index = &mut self.index;
+        self.elements.retain(|value| {
+            let keep = f(value);
+            if !keep {
+                index.set(value.clone(), None);
+            }
+            keep
+        });
+    }
+
     /// Flushes the intermediate values of the map before this is called when the structure is
     /// [`Drop`]ed. This will write all modified values to storage but keep all cached values
     /// in memory.
@@ -627,6 +679,26 @@ mod tests {
         assert!(set.is_empty());
     }
 
+    #[test]
+    fn retain() {
+        let mut set = UnorderedSet::new(b"b");
+        set.extend(0..10u8);
+
+        set.retain(|v| v % 3 == 0);
+        assert_eq!(set.len(), 4);
+        assert!(!set.contains(&4));
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), [0, 3, 6, 9]);
+
+        // Removed slots are reused for new values.
+        set.insert(10);
+        assert_eq!(set.index.get(&10), Some(&FreeListIndex(8)));
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), [0, 3, 6, 10, 9]);
+        set.flush();
+        set.insert(4);
+        assert!(set.contains(&4));
+        assert_eq!(set.len(), 6);
+    }
+
     #[test]
     fn test_drain() {
         let mut s = UnorderedSet::new(b"m");
@@ -844,6 +916,17 @@ mod tests {
         assert!(b.is_superset(&a));
     }
 
+    #[test]
+    fn test_contains_many() {
+        let mut set = UnorderedSet::new(b"m");
+        set.extend([1, 3, 5]);
+        set.flush();
+
+        let set = UnorderedSet::<i32>::new(b"m");
+        assert_eq!(set.contains_many(&[5, 4, 3, 5]), [true, false, true, true]);
+        assert!(set.contains_many(&[]).is_empty());
+    }
+
     #[test]
     fn test_disjoint() {
         let mut xs = UnorderedSet::new(b"m");
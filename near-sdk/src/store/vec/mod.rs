@@ -56,6 +56,7 @@ mod impls;
 mod iter;
 
 use std::{
+    cmp::Ordering,
     fmt,
     ops::{Bound, Range, RangeBounds},
 };
@@ -278,6 +279,40 @@ where
             self.len.checked_add(1).unwrap_or_else(|| env::panic_str(ERR_INDEX_OUT_OF_BOUNDS));
         self.set(last_idx, element)
     }
+
+    /// Appends all elements of a slice to the back of the collection.
+    ///
+    /// Unlike [`push`](Vector::push) and [`Extend`], which stage every element in the cache until
+    /// the vector is flushed, the elements are serialized and written to storage in a single pass
+    /// over the slice, with the key and value buffers reused between elements. This avoids an
+    /// allocation and a cache insertion per element, which adds up when pushing thousands of
+    /// elements in a single call, and does not require the elements to be cloned. Elements
+    /// appended this way are not kept in memory, and are read from storage if accessed later.
+    ///
+    /// # Panics
+    ///
+    /// Panics if new length exceeds `u32::MAX`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::Vector;
+    ///
+    /// let mut vec = Vector::new(b"v");
+    /// vec.push(1);
+    /// vec.extend_from_slice(&[2, 3, 4]);
+    ///
+    /// assert_eq!(vec.len(), 4);
+    /// assert_eq!(vec.get(3), Some(&4));
+    /// ```
+    pub fn extend_from_slice(&mut self, elements: &[T]) {
+        let start = self.len();
+        self.len = u32::try_from(elements.len())
+            .ok()
+            .and_then(|added| self.len.checked_add(added))
+            .unwrap_or_else(|| env::panic_str(ERR_INDEX_OUT_OF_BOUNDS));
+        self.values.write_from(start, elements);
+    }
 }
 
 impl<T> Vector<T>
@@ -504,6 +539,117 @@ where
         // This will also cap the max length at the length of the vector.
         Drain::new(self, Range { start, end: core::cmp::min(end, self.len()) })
     }
+
+    /// Binary searches this sorted vector with a comparator function, which returns the ordering
+    /// of an element relative to the target. Only the `O(log n)` probed elements are loaded from
+    /// storage.
+    ///
+    /// If a matching element is found, [`Result::Ok`] is returned with its index. If there are
+    /// multiple matches, any one of them can be returned. If no element matches,
+    /// [`Result::Err`] is returned with the index where a matching element could be inserted
+    /// while maintaining the sorted order.
+    ///
+    /// The vector is expected to be sorted consistently with the comparator, otherwise the
+    /// returned result is unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::Vector;
+    ///
+    /// let mut vec = Vector::new(b"v");
+    /// vec.extend([1, 2, 3, 5, 8, 13]);
+    ///
+    /// assert_eq!(vec.binary_search_by(|probe| probe.cmp(&5)), Ok(3));
+    /// assert_eq!(vec.binary_search_by(|probe| probe.cmp(&4)), Err(3));
+    /// assert_eq!(vec.binary_search_by(|probe| probe.cmp(&100)), Err(6));
+    /// ```
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<u32, u32>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        let mut left = 0;
+        let mut right = self.len();
+        while left < right {
+            let mid = left + (right - left) / 2;
+            match f(expect_consistent_state(self.get(mid))) {
+                Ordering::Less => left = mid + 1,
+                Ordering::Greater => right = mid,
+                Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(left)
+    }
+
+    /// Binary searches this sorted vector for an element. See
+    /// [`binary_search_by`](Vector::binary_search_by) for the returned result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::Vector;
+    ///
+    /// let mut vec = Vector::new(b"v");
+    /// vec.extend([1, 2, 3, 5, 8, 13]);
+    ///
+    /// assert_eq!(vec.binary_search(&8), Ok(4));
+    /// assert_eq!(vec.binary_search(&0), Err(0));
+    /// ```
+    pub fn binary_search(&self, x: &T) -> Result<u32, u32>
+    where
+        T: Ord,
+    {
+        self.binary_search_by(|probe| probe.cmp(x))
+    }
+
+    /// Binary searches this vector, sorted by the key extracted by `f`, for an element with the
+    /// key `b`. See [`binary_search_by`](Vector::binary_search_by) for the returned result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::Vector;
+    ///
+    /// // Checkpoints of (block height, balance), sorted by block height.
+    /// let mut vec = Vector::new(b"v");
+    /// vec.extend([(10u64, 100u128), (25, 80), (40, 120)]);
+    ///
+    /// assert_eq!(vec.binary_search_by_key(&25, |&(height, _)| height), Ok(1));
+    /// assert_eq!(vec.binary_search_by_key(&30, |&(height, _)| height), Err(2));
+    /// ```
+    pub fn binary_search_by_key<B, F>(&self, b: &B, mut f: F) -> Result<u32, u32>
+    where
+        F: FnMut(&T) -> B,
+        B: Ord,
+    {
+        self.binary_search_by(|probe| f(probe).cmp(b))
+    }
+
+    /// Returns the index of the partition point of this vector according to the predicate, i.e.
+    /// the index of the first element for which the predicate returns `false`.
+    ///
+    /// The vector is expected to be partitioned, with all the elements for which the predicate
+    /// returns `true` before all the elements for which it returns `false`. Only the
+    /// `O(log n)` probed elements are loaded from storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::Vector;
+    ///
+    /// let mut vec = Vector::new(b"v");
+    /// vec.extend([1, 2, 3, 3, 5, 6, 7]);
+    ///
+    /// assert_eq!(vec.partition_point(|&x| x < 5), 4);
+    /// assert_eq!(vec.partition_point(|&x| x < 100), 7);
+    /// ```
+    pub fn partition_point<P>(&self, mut pred: P) -> u32
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.binary_search_by(|x| if pred(x) { Ordering::Less } else { Ordering::Greater })
+            .unwrap_or_else(|i| i)
+    }
 }
 
 impl<T> fmt::Debug for Vector<T>
@@ -696,6 +842,48 @@ mod tests {
         assert_eq!(actual, baseline);
     }
 
+    #[test]
+    pub fn test_extend_from_slice() {
+        let mut vec = Vector::new(b"v".to_vec());
+        vec.extend([1u64, 2, 3]);
+        vec.flush();
+        // Popped elements are pending removal in the cache, the new elements must replace them.
+        assert_eq!(vec.pop(), Some(3));
+        assert_eq!(vec.pop(), Some(2));
+        vec.extend_from_slice(&[4, 5, 6]);
+        vec.push(7);
+        assert_eq!(vec.len(), 5);
+        assert_eq!(vec.iter().copied().collect::<Vec<_>>(), [1, 4, 5, 6, 7]);
+
+        vec.flush();
+        let reloaded = Vector::<u64> { len: vec.len(), values: IndexMap::new(b"v".to_vec()) };
+        assert_eq!(reloaded.iter().copied().collect::<Vec<_>>(), [1, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    pub fn test_binary_search() {
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(2);
+        let mut baseline: Vec<u32> = (0..200).map(|_| rng.gen_range(0..100)).collect();
+        baseline.sort();
+        let mut vec = Vector::new(b"v".to_vec());
+        vec.extend(baseline.iter().copied());
+
+        for x in 0..=100 {
+            match vec.binary_search(&x) {
+                Ok(i) => assert_eq!(baseline[i as usize], x),
+                Err(i) => assert_eq!(baseline.binary_search(&x), Err(i as usize)),
+            }
+            assert_eq!(
+                vec.partition_point(|&y| y < x) as usize,
+                baseline.partition_point(|&y| y < x)
+            );
+        }
+
+        let empty: Vector<u32> = Vector::new(b"e".to_vec());
+        assert_eq!(empty.binary_search(&1), Err(0));
+        assert_eq!(empty.partition_point(|_| true), 0);
+    }
+
     #[test]
     fn test_debug() {
         let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(4);
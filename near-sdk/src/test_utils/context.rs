@@ -6,9 +6,15 @@ use near_parameters::RuntimeFeesConfig;
 use near_primitives_core::config::ViewConfig;
 use std::convert::TryInto;
 
-/// Returns a pre-defined account_id from a list of 6.
+const NAMED_ACCOUNTS: [&str; 6] = ["alice", "bob", "charlie", "danny", "eugene", "fargo"];
+
+/// Returns a deterministic account_id for tests. The first 6 are named `alice`, `bob`,
+/// `charlie`, `danny`, `eugene` and `fargo`, the following ones are `account-6`, `account-7`, etc.
 pub fn accounts(id: usize) -> AccountId {
-    ["alice", "bob", "charlie", "danny", "eugene", "fargo"][id].parse().unwrap()
+    match NAMED_ACCOUNTS.get(id) {
+        Some(name) => name.parse().unwrap(),
+        None => format!("account-{}", id).parse().unwrap(),
+    }
 }
 
 /// Simple VMContext builder that allows to quickly create custom context in tests.
@@ -3,9 +3,11 @@
 pub mod test_env;
 
 pub(crate) mod context;
+mod receipt_ordering;
 use crate::mock::Receipt;
 #[allow(deprecated)]
 pub use context::{accounts, testing_env_with_promise_results, VMContextBuilder};
+pub use receipt_ordering::ReceiptScenario;
 
 /// Initializes a testing environment to mock interactions which would otherwise go through a
 /// validator node. This macro will initialize or overwrite the [`MockedBlockchain`]
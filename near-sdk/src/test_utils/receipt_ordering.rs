@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+
+use borsh::{to_vec, BorshDeserialize, BorshSerialize};
+
+use crate::mock::with_mocked_blockchain;
+
+/// Contract state and storage after executing all receipts of a [`ReceiptScenario`].
+type FinalState = (Vec<u8>, HashMap<Vec<u8>, Vec<u8>>);
+
+/// Scenario of receipts executed against a contract, used to check that the contract ends up in
+/// the same state whatever order the receipts are executed in.
+///
+/// The protocol does not guarantee an execution order for sibling receipts, such as promises
+/// joined with [`Promise::and`](crate::Promise::and), and only guarantees that a callback
+/// attached with [`Promise::then`](crate::Promise::then) is executed after the receipts it
+/// depends on. [`assert_order_independent`](Self::assert_order_independent) executes the
+/// scenario in every order allowed by these rules and compares the final states, so that
+/// contracts relying on a specific callback order fail in unit tests.
+///
+/// Each receipt is a closure executed on the contract state, like the contract method the
+/// receipt calls. The state is serialized and deserialized between receipts, as it would be
+/// written to and read from storage between executions, which also flushes the cached values of
+/// [`store`](crate::store) collections. Final states are compared by their Borsh serialization
+/// and by the storage of the mocked blockchain, so collections that preserve insertion order,
+/// like [`store::IterableMap`](crate::store::IterableMap), end up in different states if
+/// sibling receipts insert into them.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::near;
+/// use near_sdk::store::Vector;
+/// use near_sdk::test_utils::ReceiptScenario;
+///
+/// #[near]
+/// struct Contract {
+///     total: u64,
+///     totals: Vector<u64>,
+/// }
+///
+/// let scenario = ReceiptScenario::new()
+///     .receipt("deposit_a", |contract: &mut Contract| contract.total += 2)
+///     .receipt("deposit_b", |contract: &mut Contract| contract.total += 3)
+///     .callback("resolve", &["deposit_a", "deposit_b"], |contract: &mut Contract| {
+///         contract.totals.push(contract.total)
+///     });
+///
+/// assert_eq!(
+///     scenario.orderings(),
+///     [["deposit_a", "deposit_b", "resolve"], ["deposit_b", "deposit_a", "resolve"]]
+/// );
+/// scenario.assert_order_independent(|| Contract { total: 0, totals: Vector::new(b"t") });
+/// ```
+pub struct ReceiptScenario<'a, S> {
+    receipts: Vec<ScenarioReceipt<'a, S>>,
+}
+
+struct ScenarioReceipt<'a, S> {
+    name: String,
+    /// Indices of the receipts that have to be executed before this one.
+    dependencies: Vec<usize>,
+    execute: Box<dyn Fn(&mut S) + 'a>,
+}
+
+impl<'a, S> Default for ReceiptScenario<'a, S> {
+    fn default() -> Self {
+        Self { receipts: Vec::new() }
+    }
+}
+
+impl<'a, S> ReceiptScenario<'a, S>
+where
+    S: BorshSerialize + BorshDeserialize,
+{
+    /// Creates a scenario without receipts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a receipt that can be executed before or after any other receipt of the scenario,
+    /// apart from the callbacks depending on it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scenario already has a receipt named `name`.
+    pub fn receipt<F>(self, name: &str, execute: F) -> Self
+    where
+        F: Fn(&mut S) + 'a,
+    {
+        self.callback(name, &[], execute)
+    }
+
+    /// Adds a receipt that is executed after all the receipts named in `after`, like a callback
+    /// attached with [`Promise::then`](crate::Promise::then).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scenario already has a receipt named `name`, or if it has no receipt with
+    /// one of the names in `after`.
+    pub fn callback<F>(mut self, name: &str, after: &[&str], execute: F) -> Self
+    where
+        F: Fn(&mut S) + 'a,
+    {
+        assert!(
+            self.index_of(name).is_none(),
+            "receipt `{}` is already part of the scenario",
+            name
+        );
+        let dependencies = after
+            .iter()
+            .map(|dependency| {
+                self.index_of(dependency).unwrap_or_else(|| {
+                    panic!("receipt `{}` is not part of the scenario", dependency)
+                })
+            })
+            .collect();
+        self.receipts.push(ScenarioReceipt {
+            name: name.to_string(),
+            dependencies,
+            execute: Box::new(execute),
+        });
+        self
+    }
+
+    /// Returns the names of the receipts in every order the protocol may execute them in.
+    ///
+    /// The number of orders grows factorially with the number of sibling receipts.
+    pub fn orderings(&self) -> Vec<Vec<&str>> {
+        self.index_orderings().iter().map(|ordering| self.names(ordering)).collect()
+    }
+
+    /// Executes the receipts in every order returned by [`orderings`](Self::orderings), each
+    /// time starting from the state returned by `init` and the current storage of the mocked
+    /// blockchain. The storage is restored once all orders have been executed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two orders end with a different contract state or storage, or if a receipt
+    /// panics.
+    pub fn assert_order_independent<F>(&self, init: F)
+    where
+        F: Fn() -> S,
+    {
+        let initial_storage = with_mocked_blockchain(|b| b.storage().clone());
+        let mut expected: Option<(Vec<usize>, FinalState)> = None;
+        for ordering in self.index_orderings() {
+            with_mocked_blockchain(|b| b.set_storage(initial_storage.clone()));
+            let final_state = self.execute(&ordering, init());
+            match &expected {
+                Some((expected_ordering, expected_state)) => {
+                    if expected_state != &final_state {
+                        with_mocked_blockchain(|b| b.set_storage(initial_storage.clone()));
+                        panic!(
+                            "executing the receipts in the order {:?} ends in a different state than in the order {:?}",
+                            self.names(&ordering),
+                            self.names(expected_ordering)
+                        );
+                    }
+                }
+                None => expected = Some((ordering, final_state)),
+            }
+        }
+        with_mocked_blockchain(|b| b.set_storage(initial_storage));
+    }
+
+    fn execute(&self, ordering: &[usize], contract: S) -> FinalState {
+        let mut state = to_vec(&contract).expect("failed to serialize the contract state");
+        drop(contract);
+        for &index in ordering {
+            let receipt = &self.receipts[index];
+            let mut contract =
+                S::try_from_slice(&state).expect("failed to deserialize the contract state");
+            if let Err(err) =
+                panic::catch_unwind(AssertUnwindSafe(|| (receipt.execute)(&mut contract)))
+            {
+                eprintln!(
+                    "receipt `{}` panicked when executing the receipts in the order {:?}",
+                    receipt.name,
+                    self.names(ordering)
+                );
+                panic::resume_unwind(err);
+            }
+            state = to_vec(&contract).expect("failed to serialize the contract state");
+        }
+        (state, with_mocked_blockchain(|b| b.storage().clone()))
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.receipts.iter().position(|receipt| receipt.name == name)
+    }
+
+    fn names(&self, ordering: &[usize]) -> Vec<&str> {
+        ordering.iter().map(|&index| self.receipts[index].name.as_str()).collect()
+    }
+
+    fn index_orderings(&self) -> Vec<Vec<usize>> {
+        let mut orderings = Vec::new();
+        self.collect_orderings(
+            &mut Vec::with_capacity(self.receipts.len()),
+            &mut vec![false; self.receipts.len()],
+            &mut orderings,
+        );
+        orderings
+    }
+
+    fn collect_orderings(
+        &self,
+        current: &mut Vec<usize>,
+        executed: &mut [bool],
+        orderings: &mut Vec<Vec<usize>>,
+    ) {
+        if current.len() == self.receipts.len() {
+            orderings.push(current.clone());
+            return;
+        }
+        for (index, receipt) in self.receipts.iter().enumerate() {
+            if !executed[index]
+                && receipt.dependencies.iter().all(|&dependency| executed[dependency])
+            {
+                executed[index] = true;
+                current.push(index);
+                self.collect_orderings(current, executed, orderings);
+                current.pop();
+                executed[index] = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReceiptScenario;
+    use crate::near;
+    use crate::store::LookupMap;
+
+    #[near(inside_nearsdk)]
+    struct Contract {
+        last_writer: Option<String>,
+        balances: LookupMap<String, u64>,
+    }
+
+    fn init() -> Contract {
+        Contract { last_writer: None, balances: LookupMap::new(b"b") }
+    }
+
+    #[test]
+    fn orderings_respect_callbacks() {
+        let scenario = ReceiptScenario::<Contract>::new()
+            .receipt("a", |_| {})
+            .receipt("b", |_| {})
+            .callback("a_done", &["a"], |_| {})
+            .callback("all_done", &["a_done", "b"], |_| {});
+        assert_eq!(
+            scenario.orderings(),
+            [
+                ["a", "b", "a_done", "all_done"],
+                ["a", "a_done", "b", "all_done"],
+                ["b", "a", "a_done", "all_done"],
+            ]
+        );
+
+        let scenario = ReceiptScenario::<Contract>::new()
+            .receipt("a", |_| {})
+            .receipt("b", |_| {})
+            .receipt("c", |_| {});
+        assert_eq!(scenario.orderings().len(), 6);
+    }
+
+    #[test]
+    fn commutative_updates_are_order_independent() {
+        ReceiptScenario::new()
+            .receipt("credit_alice", |contract: &mut Contract| {
+                *contract.balances.entry("alice".to_string()).or_insert(0) += 5;
+            })
+            .receipt("credit_bob", |contract: &mut Contract| {
+                *contract.balances.entry("bob".to_string()).or_insert(0) += 7;
+            })
+            .receipt("credit_alice_again", |contract: &mut Contract| {
+                *contract.balances.entry("alice".to_string()).or_insert(0) += 1;
+            })
+            .assert_order_independent(init);
+    }
+
+    #[test]
+    #[should_panic(expected = "ends in a different state than in the order")]
+    fn last_writer_depends_on_order() {
+        ReceiptScenario::new()
+            .receipt("a", |contract: &mut Contract| contract.last_writer = Some("a".to_string()))
+            .receipt("b", |contract: &mut Contract| contract.last_writer = Some("b".to_string()))
+            .assert_order_independent(init);
+    }
+
+    #[test]
+    #[should_panic(expected = "ends in a different state than in the order")]
+    fn storage_writes_depend_on_order() {
+        ReceiptScenario::new()
+            .receipt("a", |contract: &mut Contract| {
+                contract.balances.insert("last".to_string(), 1);
+            })
+            .receipt("b", |contract: &mut Contract| {
+                contract.balances.insert("last".to_string(), 2);
+            })
+            .assert_order_independent(init);
+    }
+
+    #[test]
+    #[should_panic(expected = "receipt `c` is not part of the scenario")]
+    fn unknown_dependency() {
+        let _ = ReceiptScenario::<Contract>::new().callback("a", &["c"], |_| {});
+    }
+}
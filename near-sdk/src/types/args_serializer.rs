@@ -0,0 +1,61 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Format of the arguments and results of contract methods other than JSON and Borsh, e.g. CBOR,
+/// named with `#[serializer(with = Type)]` on the arguments of a method and
+/// `#[result_serializer(with = Type)]` on the method.
+///
+/// The arguments of a method are (de)serialized together, as a struct with a field per argument
+/// deriving the serde traits, so formats are implemented on top of serde. The schemas of the
+/// arguments and results in the ABI are their JSON schemas, and the `serializers` extension of
+/// the root schema of the ABI marks them with the [`FORMAT`](Self::FORMAT) of the serializer.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::serde::{de::DeserializeOwned, Serialize};
+/// use near_sdk::{near, ArgsSerializer};
+///
+/// /// JSON with the arguments in a `"args"` envelope, e.g. `{"args":{"value":1}}`.
+/// pub struct Enveloped;
+///
+/// impl ArgsSerializer for Enveloped {
+///     const FORMAT: &'static str = "enveloped-json";
+///     type Error = near_sdk::serde_json::Error;
+///
+///     fn serialize<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Self::Error> {
+///         near_sdk::serde_json::to_vec(&near_sdk::serde_json::json!({ "args": value }))
+///     }
+///
+///     fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Self::Error> {
+///         let mut envelope: near_sdk::serde_json::Value = near_sdk::serde_json::from_slice(bytes)?;
+///         near_sdk::serde_json::from_value(envelope["args"].take())
+///     }
+/// }
+///
+/// #[near(contract_state)]
+/// #[derive(Default)]
+/// pub struct Counter {
+///     value: u64,
+/// }
+///
+/// #[near]
+/// impl Counter {
+///     #[result_serializer(with = Enveloped)]
+///     pub fn add(&mut self, #[serializer(with = Enveloped)] value: u64) -> u64 {
+///         self.value += value;
+///         self.value
+///     }
+/// }
+/// ```
+pub trait ArgsSerializer {
+    /// Name of the format in the ABI, e.g. `"cbor"`.
+    const FORMAT: &'static str;
+
+    /// Error of the (de)serialization, displayed in the panic message of the method.
+    type Error: core::fmt::Display;
+
+    fn serialize<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Self::Error>;
+
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Self::Error>;
+}
@@ -0,0 +1,455 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+use near_sdk_macros::near;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::env;
+
+const NANOS_PER_MILLI: u64 = 1_000_000;
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Block timestamp in nanoseconds since the Unix epoch, as returned by
+/// [`env::block_timestamp`].
+///
+/// Unlike a raw `u64`, the unit of the timestamp is part of its type, and the arithmetic only
+/// mixes it with a [`BlockDuration`]. The timestamp is serialized in Borsh as a `u64`, and in
+/// JSON as a base-10 string of nanoseconds, like [`U64`](crate::json_types::U64).
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{BlockDuration, BlockTimestamp};
+///
+/// let created_at = BlockTimestamp::now();
+/// let expires_at = created_at + BlockDuration::from_days(7);
+/// assert!(!expires_at.has_passed());
+/// assert_eq!(expires_at - created_at, BlockDuration::from_hours(7 * 24));
+/// ```
+#[near(inside_nearsdk)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct BlockTimestamp(u64);
+
+impl BlockTimestamp {
+    /// Returns the timestamp of the current block, [`env::block_timestamp`].
+    pub fn now() -> Self {
+        Self(env::block_timestamp())
+    }
+
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Self(nanos)
+    }
+
+    /// Returns the timestamp `millis` milliseconds after the Unix epoch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the timestamp doesn't fit in nanoseconds.
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(BlockDuration::from_millis(millis).0)
+    }
+
+    /// Returns the timestamp `secs` seconds after the Unix epoch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the timestamp doesn't fit in nanoseconds.
+    pub const fn from_secs(secs: u64) -> Self {
+        Self(BlockDuration::from_secs(secs).0)
+    }
+
+    pub const fn as_nanos(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the number of whole milliseconds since the Unix epoch.
+    pub const fn as_millis(&self) -> u64 {
+        self.0 / NANOS_PER_MILLI
+    }
+
+    /// Returns the number of whole seconds since the Unix epoch.
+    pub const fn as_secs(&self) -> u64 {
+        self.0 / NANOS_PER_SEC
+    }
+
+    /// Whether the timestamp is not after the timestamp of the current block.
+    pub fn has_passed(&self) -> bool {
+        *self <= Self::now()
+    }
+
+    /// Returns the duration from the timestamp to the current block, or zero if the timestamp is
+    /// in the future.
+    pub fn elapsed(&self) -> BlockDuration {
+        Self::now().saturating_duration_since(*self)
+    }
+
+    /// Returns the duration from `earlier` to the timestamp, or `None` if `earlier` is later.
+    pub const fn checked_duration_since(&self, earlier: BlockTimestamp) -> Option<BlockDuration> {
+        match self.0.checked_sub(earlier.0) {
+            Some(nanos) => Some(BlockDuration(nanos)),
+            None => None,
+        }
+    }
+
+    /// Returns the duration from `earlier` to the timestamp, or zero if `earlier` is later.
+    pub const fn saturating_duration_since(&self, earlier: BlockTimestamp) -> BlockDuration {
+        BlockDuration(self.0.saturating_sub(earlier.0))
+    }
+
+    pub const fn checked_add(self, duration: BlockDuration) -> Option<Self> {
+        match self.0.checked_add(duration.0) {
+            Some(nanos) => Some(Self(nanos)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_sub(self, duration: BlockDuration) -> Option<Self> {
+        match self.0.checked_sub(duration.0) {
+            Some(nanos) => Some(Self(nanos)),
+            None => None,
+        }
+    }
+
+    pub const fn saturating_add(self, duration: BlockDuration) -> Self {
+        Self(self.0.saturating_add(duration.0))
+    }
+
+    pub const fn saturating_sub(self, duration: BlockDuration) -> Self {
+        Self(self.0.saturating_sub(duration.0))
+    }
+}
+
+/// Duration between block timestamps, in nanoseconds.
+///
+/// The duration is serialized in Borsh as a `u64`, and in JSON as a base-10 string of
+/// nanoseconds, like [`U64`](crate::json_types::U64). It converts from and into a
+/// [`std::time::Duration`], e.g. for durations computed off-chain.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::BlockDuration;
+///
+/// const LOCKUP: BlockDuration = BlockDuration::from_days(30);
+/// assert_eq!(LOCKUP.as_secs(), 30 * 24 * 60 * 60);
+/// assert_eq!(BlockDuration::from_mins(90), BlockDuration::from_hours(1) * 3 / 2);
+/// ```
+#[near(inside_nearsdk)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct BlockDuration(u64);
+
+impl BlockDuration {
+    pub const ZERO: Self = Self(0);
+
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Self(nanos)
+    }
+
+    /// Returns a duration of `millis` milliseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duration doesn't fit in `u64` nanoseconds, about 584 years, as do the
+    /// constructors from larger units.
+    pub const fn from_millis(millis: u64) -> Self {
+        Self::from_units(millis, NANOS_PER_MILLI)
+    }
+
+    /// Returns a duration of `secs` seconds.
+    pub const fn from_secs(secs: u64) -> Self {
+        Self::from_units(secs, NANOS_PER_SEC)
+    }
+
+    /// Returns a duration of `mins` minutes.
+    pub const fn from_mins(mins: u64) -> Self {
+        Self::from_units(mins, 60 * NANOS_PER_SEC)
+    }
+
+    /// Returns a duration of `hours` hours.
+    pub const fn from_hours(hours: u64) -> Self {
+        Self::from_units(hours, 60 * 60 * NANOS_PER_SEC)
+    }
+
+    /// Returns a duration of `days` days of 24 hours.
+    pub const fn from_days(days: u64) -> Self {
+        Self::from_units(days, 24 * 60 * 60 * NANOS_PER_SEC)
+    }
+
+    const fn from_units(count: u64, nanos_per_unit: u64) -> Self {
+        match count.checked_mul(nanos_per_unit) {
+            Some(nanos) => Self(nanos),
+            None => panic!("duration overflow"),
+        }
+    }
+
+    pub const fn as_nanos(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the number of whole milliseconds of the duration.
+    pub const fn as_millis(&self) -> u64 {
+        self.0 / NANOS_PER_MILLI
+    }
+
+    /// Returns the number of whole seconds of the duration.
+    pub const fn as_secs(&self) -> u64 {
+        self.0 / NANOS_PER_SEC
+    }
+
+    pub const fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_add(rhs.0) {
+            Some(nanos) => Some(Self(nanos)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_sub(rhs.0) {
+            Some(nanos) => Some(Self(nanos)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_mul(self, rhs: u64) -> Option<Self> {
+        match self.0.checked_mul(rhs) {
+            Some(nanos) => Some(Self(nanos)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_div(self, rhs: u64) -> Option<Self> {
+        match self.0.checked_div(rhs) {
+            Some(nanos) => Some(Self(nanos)),
+            None => None,
+        }
+    }
+
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+macro_rules! impl_nanos_type {
+    ($iden: ident) => {
+        impl From<u64> for $iden {
+            fn from(nanos: u64) -> Self {
+                Self(nanos)
+            }
+        }
+
+        impl From<$iden> for u64 {
+            fn from(v: $iden) -> u64 {
+                v.0
+            }
+        }
+
+        impl fmt::Display for $iden {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}ns", self.0)
+            }
+        }
+
+        impl Serialize for $iden {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(&self.0.to_string())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $iden {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let s: String = Deserialize::deserialize(deserializer)?;
+                s.parse().map(Self).map_err(|err| serde::de::Error::custom(err.to_string()))
+            }
+        }
+
+        #[cfg(feature = "abi")]
+        impl schemars::JsonSchema for $iden {
+            fn is_referenceable() -> bool {
+                false
+            }
+
+            fn schema_name() -> String {
+                String::schema_name()
+            }
+
+            fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+                String::json_schema(gen)
+            }
+        }
+    };
+}
+
+impl_nanos_type!(BlockTimestamp);
+impl_nanos_type!(BlockDuration);
+
+impl From<std::time::Duration> for BlockDuration {
+    /// # Panics
+    ///
+    /// Panics if the duration doesn't fit in `u64` nanoseconds, about 584 years.
+    fn from(duration: std::time::Duration) -> Self {
+        Self(u64::try_from(duration.as_nanos()).unwrap_or_else(|_| overflow()))
+    }
+}
+
+impl From<BlockDuration> for std::time::Duration {
+    fn from(duration: BlockDuration) -> Self {
+        std::time::Duration::from_nanos(duration.0)
+    }
+}
+
+fn overflow() -> ! {
+    env::panic_str("Block time overflow")
+}
+
+impl Add<BlockDuration> for BlockTimestamp {
+    type Output = Self;
+
+    fn add(self, rhs: BlockDuration) -> Self {
+        self.checked_add(rhs).unwrap_or_else(|| overflow())
+    }
+}
+
+impl AddAssign<BlockDuration> for BlockTimestamp {
+    fn add_assign(&mut self, rhs: BlockDuration) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<BlockDuration> for BlockTimestamp {
+    type Output = Self;
+
+    fn sub(self, rhs: BlockDuration) -> Self {
+        self.checked_sub(rhs).unwrap_or_else(|| overflow())
+    }
+}
+
+impl SubAssign<BlockDuration> for BlockTimestamp {
+    fn sub_assign(&mut self, rhs: BlockDuration) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sub for BlockTimestamp {
+    type Output = BlockDuration;
+
+    /// Returns the duration between the timestamps, panicking if `rhs` is later, see
+    /// [`BlockTimestamp::saturating_duration_since`].
+    fn sub(self, rhs: Self) -> BlockDuration {
+        self.checked_duration_since(rhs).unwrap_or_else(|| overflow())
+    }
+}
+
+impl Add for BlockDuration {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).unwrap_or_else(|| overflow())
+    }
+}
+
+impl AddAssign for BlockDuration {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for BlockDuration {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.checked_sub(rhs).unwrap_or_else(|| overflow())
+    }
+}
+
+impl SubAssign for BlockDuration {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<u64> for BlockDuration {
+    type Output = Self;
+
+    fn mul(self, rhs: u64) -> Self {
+        self.checked_mul(rhs).unwrap_or_else(|| overflow())
+    }
+}
+
+impl std::ops::Div<u64> for BlockDuration {
+    type Output = Self;
+
+    fn div(self, rhs: u64) -> Self {
+        self.checked_div(rhs).unwrap_or_else(|| env::panic_str("Division by zero"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::VMContextBuilder;
+    use crate::testing_env;
+
+    #[test]
+    fn units() {
+        assert_eq!(BlockDuration::from_secs(2).as_nanos(), 2_000_000_000);
+        assert_eq!(BlockDuration::from_days(1), BlockDuration::from_hours(24));
+        assert_eq!(BlockDuration::from_mins(1).as_millis(), 60_000);
+        assert_eq!(BlockTimestamp::from_millis(1_500).as_secs(), 1);
+        assert_eq!(
+            BlockDuration::from(std::time::Duration::from_millis(5)),
+            BlockDuration::from_millis(5)
+        );
+        assert_eq!(std::time::Duration::from(BlockDuration::from_secs(3)).as_secs(), 3);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let start = BlockTimestamp::from_secs(100);
+        let end = start + BlockDuration::from_secs(20);
+        assert_eq!(end - start, BlockDuration::from_secs(20));
+        assert_eq!(end - BlockDuration::from_secs(20), start);
+        assert_eq!(start.checked_duration_since(end), None);
+        assert_eq!(start.saturating_duration_since(end), BlockDuration::ZERO);
+        assert_eq!(BlockTimestamp::from_nanos(u64::MAX).checked_add(BlockDuration(1)), None);
+        assert_eq!(BlockDuration::from_secs(3) * 2 / 3, BlockDuration::from_secs(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Block time overflow")]
+    fn negative_duration() {
+        let _ = BlockTimestamp::from_secs(1) - BlockTimestamp::from_secs(2);
+    }
+
+    #[test]
+    fn block_timestamp() {
+        testing_env!(VMContextBuilder::new().block_timestamp(1_000).build());
+        assert_eq!(BlockTimestamp::now(), BlockTimestamp::from_nanos(1_000));
+        assert!(BlockTimestamp::from_nanos(1_000).has_passed());
+        assert!(!BlockTimestamp::from_nanos(1_001).has_passed());
+        assert_eq!(BlockTimestamp::from_nanos(400).elapsed(), BlockDuration::from_nanos(600));
+        assert_eq!(BlockTimestamp::from_nanos(2_000).elapsed(), BlockDuration::ZERO);
+    }
+
+    #[test]
+    fn serialization() {
+        let timestamp = BlockTimestamp::from_secs(1);
+        assert_eq!(serde_json::to_string(&timestamp).unwrap(), "\"1000000000\"");
+        assert_eq!(serde_json::from_str::<BlockTimestamp>("\"1000000000\"").unwrap(), timestamp);
+        assert_eq!(borsh::to_vec(&BlockDuration::from_nanos(7)).unwrap(), 7u64.to_le_bytes());
+        assert_eq!(BlockDuration::from_millis(1).to_string(), "1000000ns");
+    }
+}
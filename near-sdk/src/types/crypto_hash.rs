@@ -0,0 +1,180 @@
+use std::array::TryFromSliceError;
+use std::fmt;
+
+use near_sdk_macros::near;
+use serde::{de, ser, Deserialize};
+
+use crate::json_types::{Base58CryptoHash, ParseCryptoHashError};
+
+/// 32-byte hash, e.g. a code hash, the ID of an attestation or the data ID of a yielded promise.
+///
+/// The hash is serialized in Borsh as its 32 bytes, the same as [`CryptoHashBytes`], and in JSON
+/// as a base58 string, the same as [`Base58CryptoHash`]. It is displayed in base58 with
+/// [`Display`](fmt::Display), and in hex with [`LowerHex`](fmt::LowerHex), e.g. `{:x}`.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{env, CryptoHash};
+///
+/// let hash = CryptoHash::from(env::sha256_array(b"code"));
+/// assert_eq!(hash.to_string(), "6pyg7gr1Mhg5kyMrgc5UWb6uGSMUwdHJHYQeb1DWGTbg");
+/// assert_eq!(
+///     format!("{:x}", hash),
+///     "5694d08a2e53ffcae0c3103e5ad6f6076abd960eb1f8a56577040bc1028f702b"
+/// );
+/// assert_eq!(hash.to_string().parse::<CryptoHash>().unwrap(), hash);
+/// assert_eq!(serde_json::to_string(&hash).unwrap(), format!("\"{}\"", hash));
+/// ```
+#[near(inside_nearsdk)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct CryptoHash(pub [u8; 32]);
+
+/// Bytes of a [`CryptoHash`], which `CryptoHash` was an alias of before it became a newtype.
+/// Code which keeps using the bytes can use this alias, and convert them with
+/// [`CryptoHash::from`].
+pub type CryptoHashBytes = [u8; 32];
+
+impl CryptoHash {
+    /// Returns the bytes of the hash.
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for CryptoHash {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<CryptoHash> for [u8; 32] {
+    fn from(hash: CryptoHash) -> Self {
+        hash.0
+    }
+}
+
+impl TryFrom<&[u8]> for CryptoHash {
+    type Error = TryFromSliceError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        bytes.try_into().map(Self)
+    }
+}
+
+impl TryFrom<Vec<u8>> for CryptoHash {
+    type Error = Vec<u8>;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        bytes.try_into().map(Self)
+    }
+}
+
+impl AsRef<[u8]> for CryptoHash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for CryptoHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&bs58::encode(&self.0).into_string())
+    }
+}
+
+impl fmt::Debug for CryptoHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CryptoHash({})", self)
+    }
+}
+
+impl fmt::LowerHex for CryptoHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for CryptoHash {
+    type Err = ParseCryptoHashError;
+
+    /// Parses the hash from base58.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value.parse::<Base58CryptoHash>().map(Self::from)
+    }
+}
+
+impl ser::Serialize for CryptoHash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> de::Deserialize<'de> for CryptoHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        s.parse::<Self>().map_err(|err| de::Error::custom(err.to_string()))
+    }
+}
+
+#[cfg(feature = "abi")]
+impl schemars::JsonSchema for CryptoHash {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        String::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let bytes = [7; 32];
+        let hash = CryptoHash::from(bytes);
+        assert_eq!(<[u8; 32]>::from(hash), bytes);
+        assert_eq!(CryptoHash::try_from(&bytes[..]).unwrap(), hash);
+        assert!(CryptoHash::try_from(&bytes[1..]).is_err());
+        assert_eq!(CryptoHash::try_from(bytes.to_vec()).unwrap(), hash);
+        assert_eq!(CryptoHash::try_from(vec![7; 31]).unwrap_err(), vec![7; 31]);
+        assert_eq!(hash.as_ref(), &bytes[..]);
+        assert_eq!(String::from(&Base58CryptoHash::from(hash)), hash.to_string());
+    }
+
+    #[test]
+    fn format() {
+        let hash = CryptoHash::default();
+        assert_eq!(hash.to_string(), "11111111111111111111111111111111");
+        assert_eq!(format!("{:x}", hash), "0".repeat(64));
+        assert_eq!(format!("{:?}", hash), "CryptoHash(11111111111111111111111111111111)");
+        assert_eq!(
+            "1111".parse::<CryptoHash>().unwrap_err().to_string(),
+            "invalid length of the crypto hash, expected 32 got 4"
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let hash = CryptoHash([1; 32]);
+        assert_eq!(borsh::to_vec(&hash).unwrap(), [1; 32]);
+        let json = serde_json::to_string(&hash).unwrap();
+        assert_eq!(json, "\"4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi\"");
+        assert_eq!(serde_json::from_str::<CryptoHash>(&json).unwrap(), hash);
+    }
+}
@@ -1,3 +1,5 @@
+use super::CurveType;
+
 /// Enables contract runtime to panic with the given type. Any error type used in conjunction
 /// with `#[handle_result]` has to implement this trait.
 ///
@@ -47,6 +49,41 @@ where
     }
 }
 
+/// Error with a stable code, which front-ends can match on instead of the message. It is emitted
+/// by [`env::panic_error`](crate::env::panic_error) as a JSON payload:
+/// `{"code":"<error_code>","message":"<Display of the error>"}`.
+///
+/// It is usually implemented with `#[derive(FunctionError)]`, setting the code with
+/// `#[function_error(code = "...")]` on the struct or on each variant of the enum, so that the
+/// error is emitted with its code when returned by a `#[handle_result]` method.
+///
+/// ```
+/// use near_sdk::{ErrorCode, FunctionError};
+///
+/// #[derive(Debug, FunctionError)]
+/// pub enum TransferError {
+///     #[function_error(code = "NOT_ENOUGH_BALANCE")]
+///     NotEnoughBalance { balance: u128 },
+///     #[function_error(code = "UNKNOWN_ACCOUNT")]
+///     UnknownAccount,
+/// }
+///
+/// impl std::fmt::Display for TransferError {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         match self {
+///             Self::NotEnoughBalance { balance } => write!(f, "The balance is only {}", balance),
+///             Self::UnknownAccount => write!(f, "The account is not registered"),
+///         }
+///     }
+/// }
+///
+/// assert_eq!(TransferError::UnknownAccount.error_code(), "UNKNOWN_ACCOUNT");
+/// ```
+pub trait ErrorCode: std::fmt::Display {
+    /// Returns the code of the error, which should not change between versions of the contract.
+    fn error_code(&self) -> &'static str;
+}
+
 /// A simple type used in conjunction with [FunctionError] representing that the function should
 /// abort without a custom message.
 ///
@@ -77,3 +114,79 @@ impl FunctionError for Abort {
         crate::env::abort()
     }
 }
+
+/// Error of an `#[init]` method called once the contract has already been initialized, emitted
+/// by the generated code of init methods not marked with `#[init(ignore_state)]` with the
+/// `ALREADY_INITIALIZED` code, see [`ErrorCode`].
+///
+/// ```
+/// use near_sdk::{AlreadyInitialized, ErrorCode};
+///
+/// assert_eq!(AlreadyInitialized.error_code(), "ALREADY_INITIALIZED");
+/// assert_eq!(AlreadyInitialized.to_string(), "The contract has already been initialized");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadyInitialized;
+
+impl std::fmt::Display for AlreadyInitialized {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The contract has already been initialized")
+    }
+}
+
+impl std::error::Error for AlreadyInitialized {}
+
+impl ErrorCode for AlreadyInitialized {
+    fn error_code(&self) -> &'static str {
+        "ALREADY_INITIALIZED"
+    }
+}
+
+impl FunctionError for AlreadyInitialized {
+    fn panic(&self) -> ! {
+        crate::env::panic_error(self)
+    }
+}
+
+/// Error returned by [`env::secp256k1_recover`](crate::env::secp256k1_recover) and
+/// [`env::eth_recover_address`](crate::env::eth_recover_address).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EcrecoverError {
+    /// The recovery byte is not in `0..=3`, or `27..=28` for Ethereum signatures.
+    InvalidRecoveryId(u8),
+    /// No public key could be recovered from the signature, or the signature is malleable.
+    RecoveryFailed,
+}
+
+impl std::fmt::Display for EcrecoverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidRecoveryId(v) => write!(f, "invalid recovery id {}", v),
+            Self::RecoveryFailed => write!(f, "failed to recover the public key"),
+        }
+    }
+}
+
+impl std::error::Error for EcrecoverError {}
+
+/// Error returned by [`env::verify_ed25519`](crate::env::verify_ed25519).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ed25519VerifyError {
+    /// The public key is not an ed25519 key.
+    UnsupportedKey(CurveType),
+    /// The signature is not the one of the message by the public key.
+    InvalidSignature,
+}
+
+impl std::fmt::Display for Ed25519VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedKey(curve_type) => {
+                write!(f, "expected an ed25519 public key, got a {:?} key", curve_type)
+            }
+            Self::InvalidSignature => write!(f, "invalid ed25519 signature"),
+        }
+    }
+}
+
+impl std::error::Error for Ed25519VerifyError {}
@@ -1,8 +1,10 @@
+use near_sdk_macros::near;
+
 mod vm_types;
 pub use self::vm_types::*;
 
 mod public_key;
-pub use self::public_key::{CurveType, PublicKey};
+pub use self::public_key::{CurveType, ParsePublicKeyError, PublicKey};
 
 mod primitives;
 pub use self::primitives::*;
@@ -13,19 +15,32 @@ pub use near_gas::NearGas as Gas;
 /// A wrapper struct for `u128` that represents tokens. And provides helpful methods to convert with a proper precision.
 pub use near_token::NearToken;
 
+mod block_time;
+pub use self::block_time::{BlockDuration, BlockTimestamp};
+
+mod crypto_hash;
+pub use self::crypto_hash::{CryptoHash, CryptoHashBytes};
+
+mod token_format;
+pub use self::token_format::{ParseTokenError, TokenFormat, TokenUnit};
+
+mod unit_literals;
+pub use self::unit_literals::{parse_gas, parse_near_token};
+
+mod args_serializer;
+pub use self::args_serializer::ArgsSerializer;
+
 mod error;
 pub use self::error::Abort;
 pub use self::error::FunctionError;
+pub use self::error::{AlreadyInitialized, EcrecoverError, Ed25519VerifyError, ErrorCode};
 
-/// Raw type for duration in nanoseconds
+/// Raw type for duration in nanoseconds, see [`BlockDuration`] for a typed duration.
 pub type Duration = u64;
 
-/// Raw type for timestamp in nanoseconds
+/// Raw type for timestamp in nanoseconds, see [`BlockTimestamp`] for a typed timestamp.
 pub type Timestamp = u64;
 
-/// Raw type for 32 bytes of the hash.
-pub type CryptoHash = [u8; 32];
-
 /// Weight of unused gas to use with [`promise_batch_action_function_call_weight`].
 ///
 /// This weight will be used relative to other weights supplied in the function to distribute
@@ -36,7 +51,8 @@ pub type CryptoHash = [u8; 32];
 /// using up all remaining available gas.
 ///
 /// [`promise_batch_action_function_call_weight`]: `crate::env::promise_batch_action_function_call_weight`
-#[derive(Debug, PartialEq, Eq)]
+#[near(inside_nearsdk, serializers=[borsh, json])]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct GasWeight(pub u64);
 
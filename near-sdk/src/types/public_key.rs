@@ -1,7 +1,9 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use bs58::decode::Error as B58Error;
 use near_sdk_macros::near;
-use std::{convert::TryFrom, io};
+use std::{convert::TryFrom, fmt, io};
+
+use crate::{env, AccountId, AccountIdRef};
 
 /// PublicKey curve
 #[near(inside_nearsdk, serializers=[borsh(use_discriminant = true)])]
@@ -17,12 +19,22 @@ impl CurveType {
         match val {
             0 => Ok(CurveType::ED25519),
             1 => Ok(CurveType::SECP256K1),
-            _ => Err(ParsePublicKeyError { kind: ParsePublicKeyErrorKind::UnknownCurve }),
+            _ => Err(ParsePublicKeyError {
+                kind: ParsePublicKeyErrorKind::UnknownCurve(val.to_string()),
+            }),
+        }
+    }
+
+    /// Returns the name of the curve, as in the string format of the keys.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            CurveType::ED25519 => "ed25519",
+            CurveType::SECP256K1 => "secp256k1",
         }
     }
 
     /// Get the length of bytes associated to this CurveType
-    const fn data_len(&self) -> usize {
+    pub const fn data_len(&self) -> usize {
         match self {
             CurveType::ED25519 => 32,
             CurveType::SECP256K1 => 64,
@@ -39,11 +51,19 @@ impl std::str::FromStr for CurveType {
         } else if value.eq_ignore_ascii_case("secp256k1") {
             Ok(CurveType::SECP256K1)
         } else {
-            Err(ParsePublicKeyError { kind: ParsePublicKeyErrorKind::UnknownCurve })
+            Err(ParsePublicKeyError {
+                kind: ParsePublicKeyErrorKind::UnknownCurve(value.to_string()),
+            })
         }
     }
 }
 
+impl fmt::Display for CurveType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 #[cfg(all(not(target_arch = "wasm32"), feature = "unit-testing"))]
 #[cfg(test)]
 impl TryFrom<PublicKey> for near_crypto::PublicKey {
@@ -56,7 +76,10 @@ impl TryFrom<PublicKey> for near_crypto::PublicKey {
         let key_bytes = public_key.into_bytes();
         if key_bytes.len() != expected_len + 1 {
             return Err(ParsePublicKeyError {
-                kind: ParsePublicKeyErrorKind::InvalidLength(key_bytes.len()),
+                kind: ParsePublicKeyErrorKind::InvalidLength {
+                    curve: curve_type,
+                    len: key_bytes.len() - 1,
+                },
             });
         }
 
@@ -117,7 +140,7 @@ impl PublicKey {
         let expected_length = curve.data_len();
         if data.len() != expected_length {
             return Err(ParsePublicKeyError {
-                kind: ParsePublicKeyErrorKind::InvalidLength(data.len()),
+                kind: ParsePublicKeyErrorKind::InvalidLength { curve, len: data.len() },
             });
         }
         let mut bytes = Vec::with_capacity(1 + expected_length);
@@ -139,7 +162,94 @@ impl PublicKey {
 
     /// Get info about the CurveType for this public key
     pub fn curve_type(&self) -> CurveType {
-        CurveType::from_u8(self.data[0]).unwrap_or_else(|_| crate::env::abort())
+        CurveType::from_u8(self.data[0]).unwrap_or_else(|_| env::abort())
+    }
+
+    /// Returns the bytes of the key, without the curve type.
+    pub fn key_data(&self) -> &[u8] {
+        &self.data[1..]
+    }
+
+    /// Returns the bytes of the key if it is an ed25519 key.
+    pub fn as_ed25519(&self) -> Option<&[u8; 32]> {
+        match self.curve_type() {
+            CurveType::ED25519 => self.key_data().try_into().ok(),
+            CurveType::SECP256K1 => None,
+        }
+    }
+
+    /// Returns the bytes of the uncompressed key if it is a secp256k1 key.
+    pub fn as_secp256k1(&self) -> Option<&[u8; 64]> {
+        match self.curve_type() {
+            CurveType::ED25519 => None,
+            CurveType::SECP256K1 => self.key_data().try_into().ok(),
+        }
+    }
+
+    /// Returns the implicit account controlled by the key, which exists once it receives NEAR:
+    /// - for an ed25519 key, the NEAR-implicit account, the 64 hex digits of the key;
+    /// - for a secp256k1 key, the ETH-implicit account of
+    ///   [NEP-518](https://github.com/near/NEPs/issues/518), `0x` followed by the 40 hex digits
+    ///   of the Ethereum address of the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::PublicKey;
+    ///
+    /// let key: PublicKey = "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp".parse().unwrap();
+    /// let account_id = key.to_implicit_account_id();
+    /// assert_eq!(
+    ///     account_id.as_str(),
+    ///     "4da7e0f4096aaf2ce55e371657cd3089ba1e9f59f4d6e27bd02e472a16a61dc1"
+    /// );
+    /// assert_eq!(PublicKey::from_implicit_account_id(&account_id), Some(key));
+    /// ```
+    pub fn to_implicit_account_id(&self) -> AccountId {
+        let account_id = match self.curve_type() {
+            CurveType::ED25519 => to_hex(self.key_data()),
+            CurveType::SECP256K1 => {
+                ["0x", &to_hex(&env::keccak256_array(self.key_data())[12..])].concat()
+            }
+        };
+        account_id.parse().unwrap_or_else(|_| env::abort())
+    }
+
+    /// Returns the ed25519 key of a NEAR-implicit account, i.e. an account ID made of 64
+    /// lowercase hex digits, see [`to_implicit_account_id`](Self::to_implicit_account_id).
+    ///
+    /// The key of an ETH-implicit account can't be derived from its ID, which is a hash of the
+    /// key, so `None` is returned for them, as for named accounts.
+    pub fn from_implicit_account_id(account_id: &AccountIdRef) -> Option<Self> {
+        let hex = account_id.as_str().as_bytes();
+        if hex.len() != 64 {
+            return None;
+        }
+        let mut data = Vec::with_capacity(1 + 32);
+        data.push(CurveType::ED25519 as u8);
+        for pair in hex.chunks(2) {
+            data.push((from_hex_digit(pair[0])? << 4) | from_hex_digit(pair[1])?);
+        }
+        Some(Self { data })
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(DIGITS[(byte >> 4) as usize] as char);
+        hex.push(DIGITS[(byte & 0xf) as usize] as char);
+    }
+    hex
+}
+
+/// Returns the value of a lowercase hex digit, as implicit account IDs can't be uppercase.
+fn from_hex_digit(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        _ => None,
     }
 }
 
@@ -154,15 +264,13 @@ impl TryFrom<Vec<u8>> for PublicKey {
 
     fn try_from(data: Vec<u8>) -> Result<Self, Self::Error> {
         if data.is_empty() {
-            return Err(ParsePublicKeyError {
-                kind: ParsePublicKeyErrorKind::InvalidLength(data.len()),
-            });
+            return Err(ParsePublicKeyError { kind: ParsePublicKeyErrorKind::Empty });
         }
 
         let curve = CurveType::from_u8(data[0])?;
         if data.len() != curve.data_len() + 1 {
             return Err(ParsePublicKeyError {
-                kind: ParsePublicKeyErrorKind::InvalidLength(data.len()),
+                kind: ParsePublicKeyErrorKind::InvalidLength { curve, len: data.len() - 1 },
             });
         }
         Ok(Self { data })
@@ -213,14 +321,18 @@ impl schemars::JsonSchema for PublicKey {
 
 impl From<&PublicKey> for String {
     fn from(str_public_key: &PublicKey) -> Self {
-        match str_public_key.curve_type() {
-            CurveType::ED25519 => {
-                ["ed25519:", &bs58::encode(&str_public_key.data[1..]).into_string()].concat()
-            }
-            CurveType::SECP256K1 => {
-                ["secp256k1:", &bs58::encode(&str_public_key.data[1..]).into_string()].concat()
-            }
-        }
+        [
+            str_public_key.curve_type().name(),
+            ":",
+            &bs58::encode(str_public_key.key_data()).into_string(),
+        ]
+        .concat()
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from(self))
     }
 }
 
@@ -233,6 +345,8 @@ impl std::str::FromStr for PublicKey {
         Self::from_parts(curve, data)
     }
 }
+
+/// Error returned when parsing a [`PublicKey`] from a string or from bytes.
 #[derive(Debug)]
 pub struct ParsePublicKeyError {
     kind: ParsePublicKeyErrorKind,
@@ -240,19 +354,27 @@ pub struct ParsePublicKeyError {
 
 #[derive(Debug)]
 enum ParsePublicKeyErrorKind {
-    InvalidLength(usize),
+    Empty,
+    InvalidLength { curve: CurveType, len: usize },
     Base58(B58Error),
-    UnknownCurve,
+    UnknownCurve(String),
 }
 
-impl std::fmt::Display for ParsePublicKeyError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.kind {
-            ParsePublicKeyErrorKind::InvalidLength(l) => {
-                write!(f, "invalid length of the public key, expected 32 got {}", l)
-            }
+impl fmt::Display for ParsePublicKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParsePublicKeyErrorKind::Empty => write!(f, "empty public key"),
+            ParsePublicKeyErrorKind::InvalidLength { curve, len } => write!(
+                f,
+                "invalid length of the {} public key, expected {} bytes got {}",
+                curve,
+                curve.data_len(),
+                len
+            ),
             ParsePublicKeyErrorKind::Base58(e) => write!(f, "base58 decoding error: {}", e),
-            ParsePublicKeyErrorKind::UnknownCurve => write!(f, "unknown curve kind"),
+            ParsePublicKeyErrorKind::UnknownCurve(curve) => {
+                write!(f, "unknown curve `{}`, expected `ed25519` or `secp256k1`", curve)
+            }
         }
     }
 }
@@ -335,4 +457,73 @@ mod tests {
         let decoded_key = PublicKey::try_from_slice(&new_encoded_key).unwrap();
         assert_eq!(decoded_key, new_key);
     }
+
+    #[test]
+    fn test_public_key_parse_errors() {
+        let error = |value: &str| value.parse::<PublicKey>().unwrap_err().to_string();
+        assert_eq!(
+            error("ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKE"),
+            "invalid length of the ed25519 public key, expected 32 bytes got 31"
+        );
+        assert_eq!(
+            error("secp256k1:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"),
+            "invalid length of the secp256k1 public key, expected 64 bytes got 32"
+        );
+        assert_eq!(
+            error("ed448:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"),
+            "unknown curve `ed448`, expected `ed25519` or `secp256k1`"
+        );
+        assert!(error("ed25519:0OIl").starts_with("base58 decoding error"));
+        assert_eq!(PublicKey::try_from(vec![]).unwrap_err().to_string(), "empty public key");
+    }
+
+    #[test]
+    fn test_public_key_curve_accessors() {
+        let key = expected_key();
+        assert_eq!(key.curve_type().to_string(), "ed25519");
+        assert_eq!(key.key_data(), &key.as_bytes()[1..]);
+        assert_eq!(key.as_ed25519().map(|data| &data[..]), Some(key.key_data()));
+        assert_eq!(key.as_secp256k1(), None);
+        assert_eq!(key.to_string(), "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp");
+
+        let key = PublicKey::from_parts(CurveType::SECP256K1, vec![1; 64]).unwrap();
+        assert_eq!(key.as_secp256k1(), Some(&[1; 64]));
+        assert_eq!(key.as_ed25519(), None);
+    }
+
+    #[test]
+    fn test_implicit_account_id() {
+        let key = expected_key();
+        let account_id = key.to_implicit_account_id();
+        assert_eq!(account_id.as_str(), to_hex(key.key_data()));
+        assert_eq!(PublicKey::from_implicit_account_id(&account_id), Some(key));
+
+        let named: AccountId = "alice.near".parse().unwrap();
+        assert_eq!(PublicKey::from_implicit_account_id(&named), None);
+        let not_hex: AccountId = "g".repeat(64).parse().unwrap();
+        assert_eq!(PublicKey::from_implicit_account_id(&not_hex), None);
+    }
+
+    #[test]
+    fn test_eth_implicit_account_id() {
+        // Uncompressed key of the private key 1, whose Ethereum address is well known.
+        let key: PublicKey = PublicKey::from_parts(
+            CurveType::SECP256K1,
+            [
+                "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+                "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+            ]
+            .concat()
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| (from_hex_digit(pair[0]).unwrap() << 4) | from_hex_digit(pair[1]).unwrap())
+            .collect(),
+        )
+        .unwrap();
+        assert_eq!(
+            key.to_implicit_account_id().as_str(),
+            "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf"
+        );
+        assert_eq!(PublicKey::from_implicit_account_id(&key.to_implicit_account_id()), None);
+    }
 }
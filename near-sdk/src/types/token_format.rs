@@ -0,0 +1,297 @@
+use crate::NearToken;
+
+/// Unit a [`NearToken`] amount is displayed in, or parsed from, by [`TokenFormat`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenUnit {
+    /// 10^24 yoctoNEAR, displayed as `NEAR`.
+    Near,
+    /// 10^21 yoctoNEAR, displayed as `mNEAR`.
+    MilliNear,
+    /// The smallest unit of NEAR, displayed as `yoctoNEAR`.
+    YoctoNear,
+}
+
+impl TokenUnit {
+    /// Returns the number of decimal places between the unit and yoctoNEAR.
+    pub const fn decimals(&self) -> u32 {
+        match self {
+            TokenUnit::Near => 24,
+            TokenUnit::MilliNear => 21,
+            TokenUnit::YoctoNear => 0,
+        }
+    }
+
+    /// Returns the symbol the unit is displayed with.
+    pub const fn symbol(&self) -> &'static str {
+        match self {
+            TokenUnit::Near => "NEAR",
+            TokenUnit::MilliNear => "mNEAR",
+            TokenUnit::YoctoNear => "yoctoNEAR",
+        }
+    }
+
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        // `mNEAR` has to be matched before the case-insensitive comparisons, which would not
+        // tell it apart from `MNEAR`.
+        if symbol == "mNEAR" {
+            return Some(TokenUnit::MilliNear);
+        }
+        match symbol.to_ascii_lowercase().as_str() {
+            "near" | "n" => Some(TokenUnit::Near),
+            "millinear" => Some(TokenUnit::MilliNear),
+            "yoctonear" | "yocto" => Some(TokenUnit::YoctoNear),
+            _ => None,
+        }
+    }
+}
+
+/// Human-readable [`Display`](std::fmt::Display) of a [`NearToken`] amount, e.g. `1.2345 NEAR`
+/// instead of `1234500000000000000000000`, for logs and error messages.
+///
+/// The output does not depend on the locale: the decimal separator is always a `.`, digits are
+/// not grouped and trailing zeros of the fractional part are omitted. Amounts are displayed with
+/// at most [`precision`](Self::precision) decimal places, 4 by default, and are rounded down,
+/// unless [`round_up`](Self::round_up) is set. Non-zero amounts that would be displayed as zero
+/// are displayed as the smallest displayable amount prefixed with `<`, e.g. `<0.0001 NEAR`.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{NearToken, TokenFormat, TokenUnit};
+///
+/// let amount = NearToken::from_yoctonear(1_234_567_000_000_000_000_000_000);
+/// assert_eq!(TokenFormat::new(amount).to_string(), "1.2345 NEAR");
+/// assert_eq!(TokenFormat::new(amount).precision(2).round_up().to_string(), "1.24 NEAR");
+/// assert_eq!(TokenFormat::new(amount).unit(TokenUnit::MilliNear).to_string(), "1234.567 mNEAR");
+/// assert_eq!(TokenFormat::new(NearToken::from_yoctonear(1)).to_string(), "<0.0001 NEAR");
+///
+/// assert_eq!(TokenFormat::parse("1.234567 NEAR").unwrap(), amount);
+/// assert_eq!(TokenFormat::parse("5 mNEAR").unwrap(), NearToken::from_millinear(5));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenFormat {
+    amount: NearToken,
+    unit: TokenUnit,
+    precision: u32,
+    round_up: bool,
+}
+
+impl TokenFormat {
+    /// Creates a display of `amount` in NEAR with 4 decimal places.
+    pub fn new(amount: NearToken) -> Self {
+        Self { amount, unit: TokenUnit::Near, precision: 4, round_up: false }
+    }
+
+    /// Sets the unit the amount is displayed in.
+    pub fn unit(self, unit: TokenUnit) -> Self {
+        Self { unit, ..self }
+    }
+
+    /// Sets the maximum number of decimal places displayed. Precisions beyond the decimals of the
+    /// unit display the exact amount.
+    pub fn precision(self, precision: u32) -> Self {
+        Self { precision, ..self }
+    }
+
+    /// Rounds the displayed amount up instead of down, so that it is never less than the actual
+    /// amount. Useful for deposits a user has to attach.
+    pub fn round_up(self) -> Self {
+        Self { round_up: true, ..self }
+    }
+
+    /// Parses an amount followed by its unit, such as `1.5 NEAR`, `250 mNEAR` or
+    /// `1 yoctoNEAR`. The space before the unit is optional. The units are case-insensitive,
+    /// apart from `mNEAR`, and `N`, `millinear` and `yocto` are also accepted.
+    pub fn parse(value: &str) -> Result<NearToken, ParseTokenError> {
+        let value = value.trim();
+        let number_len =
+            value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+        let (number, symbol) = value.split_at(number_len);
+        let symbol = symbol.trim_start();
+        if symbol.is_empty() {
+            return Err(ParseTokenError { kind: ParseTokenErrorKind::MissingUnit });
+        }
+        let unit = TokenUnit::from_symbol(symbol).ok_or_else(|| ParseTokenError {
+            kind: ParseTokenErrorKind::UnknownUnit(symbol.to_string()),
+        })?;
+
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if (whole.is_empty() && fraction.is_empty()) || fraction.contains('.') {
+            return Err(ParseTokenError { kind: ParseTokenErrorKind::InvalidNumber });
+        }
+        let decimals = unit.decimals();
+        if fraction.len() > decimals as usize {
+            return Err(ParseTokenError { kind: ParseTokenErrorKind::TooManyDecimals(unit) });
+        }
+
+        let overflow = || ParseTokenError { kind: ParseTokenErrorKind::Overflow };
+        let parse_digits = |digits: &str| {
+            digits.bytes().try_fold(0u128, |acc, digit| {
+                acc.checked_mul(10)?.checked_add(u128::from(digit - b'0'))
+            })
+        };
+        let whole = parse_digits(whole).ok_or_else(overflow)?;
+        let fraction = parse_digits(fraction).ok_or_else(overflow)?
+            * 10u128.pow(decimals - fraction.len() as u32);
+        whole
+            .checked_mul(10u128.pow(decimals))
+            .and_then(|whole| whole.checked_add(fraction))
+            .map(NearToken::from_yoctonear)
+            .ok_or_else(overflow)
+    }
+}
+
+impl From<NearToken> for TokenFormat {
+    fn from(amount: NearToken) -> Self {
+        Self::new(amount)
+    }
+}
+
+impl std::fmt::Display for TokenFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let yocto = self.amount.as_yoctonear();
+        let decimals = self.unit.decimals();
+        let precision = self.precision.min(decimals);
+        let step = 10u128.pow(decimals - precision);
+
+        let mut displayed = yocto / step;
+        let truncated = yocto % step != 0;
+        if truncated && self.round_up {
+            // `step` is at least 10 when the amount is truncated, so this cannot overflow.
+            displayed += 1;
+        } else if truncated && displayed == 0 {
+            f.write_str("<")?;
+            displayed = 1;
+        }
+
+        let scale = 10u128.pow(precision);
+        write!(f, "{}", displayed / scale)?;
+        let fraction = displayed % scale;
+        if fraction != 0 {
+            let fraction = format!("{:0width$}", fraction, width = precision as usize);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        write!(f, " {}", self.unit.symbol())
+    }
+}
+
+/// Error returned by [`TokenFormat::parse`].
+#[derive(Debug)]
+pub struct ParseTokenError {
+    kind: ParseTokenErrorKind,
+}
+
+#[derive(Debug)]
+enum ParseTokenErrorKind {
+    InvalidNumber,
+    MissingUnit,
+    UnknownUnit(String),
+    TooManyDecimals(TokenUnit),
+    Overflow,
+}
+
+impl std::fmt::Display for ParseTokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ParseTokenErrorKind::InvalidNumber => write!(f, "invalid token amount"),
+            ParseTokenErrorKind::MissingUnit => write!(f, "missing unit of the token amount"),
+            ParseTokenErrorKind::UnknownUnit(unit) => write!(f, "unknown token unit `{}`", unit),
+            ParseTokenErrorKind::TooManyDecimals(unit) => write!(
+                f,
+                "too many decimal places for {}, at most {} are allowed",
+                unit.symbol(),
+                unit.decimals()
+            ),
+            ParseTokenErrorKind::Overflow => write!(f, "token amount is too large"),
+        }
+    }
+}
+
+impl std::error::Error for ParseTokenError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn near(amount: &str) -> NearToken {
+        TokenFormat::parse(amount).unwrap()
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(TokenFormat::new(NearToken::from_near(0)).to_string(), "0 NEAR");
+        assert_eq!(TokenFormat::new(NearToken::from_near(25)).to_string(), "25 NEAR");
+        assert_eq!(TokenFormat::new(near("1.23456789 NEAR")).to_string(), "1.2345 NEAR");
+        assert_eq!(TokenFormat::new(near("0.05 NEAR")).to_string(), "0.05 NEAR");
+        assert_eq!(TokenFormat::new(near("0.00001 NEAR")).to_string(), "<0.0001 NEAR");
+        assert_eq!(TokenFormat::new(near("0.00001 NEAR")).round_up().to_string(), "0.0001 NEAR");
+        assert_eq!(TokenFormat::new(near("1.99999 NEAR")).round_up().to_string(), "2 NEAR");
+        assert_eq!(TokenFormat::new(near("1.5 NEAR")).precision(0).to_string(), "1 NEAR");
+        assert_eq!(
+            TokenFormat::new(NearToken::from_yoctonear(1)).precision(30).to_string(),
+            "0.000000000000000000000001 NEAR"
+        );
+        assert_eq!(
+            TokenFormat::new(NearToken::from_yoctonear(u128::MAX)).round_up().to_string(),
+            "340282366920938.4635 NEAR"
+        );
+    }
+
+    #[test]
+    fn display_units() {
+        let amount = near("1.5 NEAR");
+        assert_eq!(TokenFormat::new(amount).unit(TokenUnit::MilliNear).to_string(), "1500 mNEAR");
+        assert_eq!(
+            TokenFormat::new(amount).unit(TokenUnit::YoctoNear).to_string(),
+            "1500000000000000000000000 yoctoNEAR"
+        );
+        assert_eq!(
+            TokenFormat::new(NearToken::from_yoctonear(7)).unit(TokenUnit::YoctoNear).to_string(),
+            "7 yoctoNEAR"
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(near("1 NEAR"), NearToken::from_near(1));
+        assert_eq!(near("  2.5near "), NearToken::from_millinear(2500));
+        assert_eq!(near(".5 N"), NearToken::from_millinear(500));
+        assert_eq!(near("3. NEAR"), NearToken::from_near(3));
+        assert_eq!(near("250 mNEAR"), NearToken::from_millinear(250));
+        assert_eq!(near("0.001 MilliNear"), NearToken::from_yoctonear(10u128.pow(18)));
+        assert_eq!(near("1 yoctoNEAR"), NearToken::from_yoctonear(1));
+        assert_eq!(near("0.000000000000000000000001 NEAR"), NearToken::from_yoctonear(1));
+        assert_eq!(
+            near("340282366920938463463374607431768211455 yocto"),
+            NearToken::from_yoctonear(u128::MAX)
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |value| TokenFormat::parse(value).unwrap_err().to_string();
+        assert_eq!(error("1"), "missing unit of the token amount");
+        assert_eq!(error("1 MNEAR"), "unknown token unit `MNEAR`");
+        assert_eq!(error("1,5 NEAR"), "unknown token unit `,5 NEAR`");
+        assert_eq!(error(". NEAR"), "invalid token amount");
+        assert_eq!(error("1.2.3 NEAR"), "invalid token amount");
+        assert_eq!(
+            error("0.5 yoctoNEAR"),
+            "too many decimal places for yoctoNEAR, at most 0 are allowed"
+        );
+        assert_eq!(error("340282366920939.5 NEAR"), "token amount is too large");
+        assert_eq!(
+            error("340282366920938463463374607431768211456 yocto"),
+            "token amount is too large"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for yocto in [0, 1, 10u128.pow(21) + 7, 10u128.pow(24) * 3 / 2, u128::MAX] {
+            let amount = NearToken::from_yoctonear(yocto);
+            let display = TokenFormat::new(amount).precision(24).to_string();
+            assert_eq!(TokenFormat::parse(&display).unwrap(), amount);
+        }
+    }
+}
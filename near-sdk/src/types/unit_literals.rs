@@ -0,0 +1,297 @@
+//! Parsing of [`NearToken`] and [`Gas`] amounts written with their unit, in const contexts, so
+//! that constants don't have to be written as 24-digit literals.
+//!
+//! Outside of const contexts, [`TokenFormat::parse`](crate::TokenFormat::parse) and the
+//! [`FromStr`](std::str::FromStr) implementation of [`Gas`] return an error instead of panicking,
+//! and [`TokenFormat`](crate::TokenFormat) and the [`Display`](std::fmt::Display) implementation
+//! of [`Gas`] display the amounts with their unit.
+
+use crate::{Gas, NearToken};
+
+/// Unit accepted by [`parse_amount`], with the number of decimal places between it and the
+/// smallest unit.
+struct Unit {
+    symbol: &'static str,
+    decimals: u32,
+    case_sensitive: bool,
+}
+
+const fn unit(symbol: &'static str, decimals: u32, case_sensitive: bool) -> Unit {
+    Unit { symbol, decimals, case_sensitive }
+}
+
+// `mNEAR` comes before the case-insensitive units, which would not tell it apart from `MNEAR`.
+const TOKEN_UNITS: &[Unit] = &[
+    unit("mNEAR", 21, true),
+    unit("near", 24, false),
+    unit("n", 24, false),
+    unit("millinear", 21, false),
+    unit("yoctonear", 0, false),
+    unit("yocto", 0, false),
+];
+
+const GAS_UNITS: &[Unit] = &[
+    unit("pgas", 15, false),
+    unit("petagas", 15, false),
+    unit("tgas", 12, false),
+    unit("teragas", 12, false),
+    unit("ggas", 9, false),
+    unit("gigagas", 9, false),
+    unit("gas", 0, false),
+];
+
+/// Parses a [`NearToken`] amount followed by its unit, such as `1.5 NEAR`, `10 mNEAR` or
+/// `1 yocto`, accepting the same formats as [`TokenFormat::parse`](crate::TokenFormat::parse).
+///
+/// This is a `const fn`, meant for constants, see [`near_token!`](crate::near_token).
+///
+/// # Panics
+///
+/// Panics if the amount is invalid, which fails the compilation in const contexts.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{parse_near_token, NearToken};
+///
+/// const STORAGE_DEPOSIT: NearToken = parse_near_token("0.0125 NEAR");
+/// assert_eq!(STORAGE_DEPOSIT, NearToken::from_yoctonear(12_500 * 10u128.pow(18)));
+/// ```
+pub const fn parse_near_token(value: &str) -> NearToken {
+    NearToken::from_yoctonear(parse_amount(value, TOKEN_UNITS))
+}
+
+/// Parses a [`Gas`] amount followed by its unit, such as `30 Tgas`, `2.5 Ggas` or `100 gas`. The
+/// units are case-insensitive, and `Pgas` and the long names, e.g. `teragas`, are also accepted.
+///
+/// This is a `const fn`, meant for constants, see [`gas!`](crate::gas).
+///
+/// # Panics
+///
+/// Panics if the amount is invalid, which fails the compilation in const contexts.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{parse_gas, Gas};
+///
+/// const GAS_FOR_CALLBACK: Gas = parse_gas("10 Tgas");
+/// assert_eq!(GAS_FOR_CALLBACK, Gas::from_tgas(10));
+/// ```
+pub const fn parse_gas(value: &str) -> Gas {
+    let gas = parse_amount(value, GAS_UNITS);
+    if gas > u64::MAX as u128 {
+        panic!("gas amount is too large");
+    }
+    Gas::from_gas(gas as u64)
+}
+
+/// Parses a decimal number followed by one of `units`, optionally separated by spaces, into an
+/// amount of the smallest unit.
+const fn parse_amount(value: &str, units: &[Unit]) -> u128 {
+    let bytes = value.as_bytes();
+    let (mut start, mut end) = (0, bytes.len());
+    while start < end && bytes[start].is_ascii_whitespace() {
+        start += 1;
+    }
+    while end > start && bytes[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+    let mut number_end = start;
+    while number_end < end && (bytes[number_end].is_ascii_digit() || bytes[number_end] == b'.') {
+        number_end += 1;
+    }
+    let mut symbol_start = number_end;
+    while symbol_start < end && bytes[symbol_start].is_ascii_whitespace() {
+        symbol_start += 1;
+    }
+    if symbol_start == end {
+        panic!("missing unit of the amount");
+    }
+    let decimals = match find_unit(bytes, symbol_start, end, units) {
+        Some(decimals) => decimals,
+        None => panic!("unknown unit of the amount"),
+    };
+
+    let mut amount = 0u128;
+    let mut digits = 0;
+    let mut fraction_digits = None;
+    let mut i = start;
+    while i < number_end {
+        let byte = bytes[i];
+        i += 1;
+        if byte == b'.' {
+            if fraction_digits.is_some() {
+                panic!("invalid amount");
+            }
+            fraction_digits = Some(0);
+            continue;
+        }
+        if let Some(count) = fraction_digits {
+            if count == decimals {
+                panic!("too many decimal places for the unit of the amount");
+            }
+            fraction_digits = Some(count + 1);
+        }
+        digits += 1;
+        amount = match amount.checked_mul(10) {
+            Some(amount) => match amount.checked_add((byte - b'0') as u128) {
+                Some(amount) => amount,
+                None => panic!("amount is too large"),
+            },
+            None => panic!("amount is too large"),
+        };
+    }
+    if digits == 0 {
+        panic!("invalid amount");
+    }
+    let fraction_digits = match fraction_digits {
+        Some(count) => count,
+        None => 0,
+    };
+    match amount.checked_mul(10u128.pow(decimals - fraction_digits)) {
+        Some(amount) => amount,
+        None => panic!("amount is too large"),
+    }
+}
+
+/// Returns the decimals of the unit of `units` whose symbol is `bytes[start..end]`.
+const fn find_unit(bytes: &[u8], start: usize, end: usize, units: &[Unit]) -> Option<u32> {
+    let mut u = 0;
+    'units: while u < units.len() {
+        let unit = &units[u];
+        u += 1;
+        let symbol = unit.symbol.as_bytes();
+        if symbol.len() != end - start {
+            continue;
+        }
+        let mut i = 0;
+        while i < symbol.len() {
+            let byte = bytes[start + i];
+            let matches = if unit.case_sensitive {
+                byte == symbol[i]
+            } else {
+                byte.eq_ignore_ascii_case(&symbol[i])
+            };
+            if !matches {
+                continue 'units;
+            }
+            i += 1;
+        }
+        return Some(unit.decimals);
+    }
+    None
+}
+
+/// Creates a [`NearToken`] constant from an amount followed by its unit, such as `1.5 NEAR`,
+/// `10 mNEAR` or `1 yocto`, with [`parse_near_token`]. Invalid amounts fail the compilation.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{near_token, NearToken};
+///
+/// assert_eq!(near_token!("1.5 NEAR"), NearToken::from_millinear(1_500));
+/// assert_eq!(near_token!("1 yocto"), NearToken::from_yoctonear(1));
+/// ```
+///
+/// ```compile_fail
+/// let amount = near_sdk::near_token!("1.5 yoctoNEAR");
+/// ```
+#[macro_export]
+macro_rules! near_token {
+    ($amount:expr) => {{
+        const AMOUNT: $crate::NearToken = $crate::parse_near_token($amount);
+        AMOUNT
+    }};
+}
+
+/// Creates a [`Gas`] constant from an amount followed by its unit, such as `30 Tgas`, with
+/// [`parse_gas`]. Invalid amounts fail the compilation.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{gas, Gas};
+///
+/// assert_eq!(gas!("30 Tgas"), Gas::from_tgas(30));
+/// assert_eq!(gas!("0.5 Tgas"), Gas::from_ggas(500));
+/// ```
+#[macro_export]
+macro_rules! gas {
+    ($amount:expr) => {{
+        const AMOUNT: $crate::Gas = $crate::parse_gas($amount);
+        AMOUNT
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TokenFormat;
+
+    #[test]
+    fn near_token() {
+        assert_eq!(parse_near_token("1.5 NEAR"), NearToken::from_millinear(1_500));
+        assert_eq!(parse_near_token(" 10mNEAR "), NearToken::from_millinear(10));
+        assert_eq!(parse_near_token("1 yocto"), NearToken::from_yoctonear(1));
+        assert_eq!(parse_near_token(".25 n"), NearToken::from_millinear(250));
+        assert_eq!(parse_near_token("3. MilliNear"), NearToken::from_millinear(3));
+        assert_eq!(
+            parse_near_token("340282366920938463463374607431768211455 yoctoNEAR"),
+            NearToken::from_yoctonear(u128::MAX)
+        );
+        for amount in ["0 NEAR", "1.234567 NEAR", "0.000000000000000000000001 NEAR", "7 mNEAR"] {
+            assert_eq!(parse_near_token(amount), TokenFormat::parse(amount).unwrap());
+        }
+        const DEPOSIT: NearToken = crate::near_token!("0.01 NEAR");
+        assert_eq!(DEPOSIT, NearToken::from_millinear(10));
+    }
+
+    #[test]
+    fn gas() {
+        assert_eq!(parse_gas("30 Tgas"), Gas::from_tgas(30));
+        assert_eq!(parse_gas("1 PGAS"), Gas::from_pgas(1));
+        assert_eq!(parse_gas("2.5ggas"), Gas::from_gas(2_500_000_000));
+        assert_eq!(parse_gas("5 teragas"), Gas::from_tgas(5));
+        assert_eq!(parse_gas("100 gas"), Gas::from_gas(100));
+        assert_eq!(parse_gas("18446744073709551615 gas"), Gas::from_gas(u64::MAX));
+        assert_eq!(crate::gas!("0.3 Pgas"), Gas::from_tgas(300));
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown unit of the amount")]
+    fn mega_near() {
+        parse_near_token("1 MNEAR");
+    }
+
+    #[test]
+    #[should_panic(expected = "missing unit of the amount")]
+    fn missing_unit() {
+        parse_gas("30");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid amount")]
+    fn two_points() {
+        parse_near_token("1.2.3 NEAR");
+    }
+
+    #[test]
+    #[should_panic(expected = "too many decimal places for the unit of the amount")]
+    fn fractional_gas() {
+        parse_gas("1.5 gas");
+    }
+
+    #[test]
+    #[should_panic(expected = "gas amount is too large")]
+    fn gas_overflow() {
+        parse_gas("18446744073709551616 gas");
+    }
+
+    #[test]
+    #[should_panic(expected = "amount is too large")]
+    fn token_overflow() {
+        parse_near_token("340282366920939 NEAR");
+    }
+}
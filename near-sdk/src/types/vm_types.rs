@@ -1,6 +1,10 @@
 #[cfg(all(not(target_arch = "wasm32"), feature = "unit-testing"))]
 pub use near_vm_runner::logic::types::{PromiseResult as VmPromiseResult, ReturnData};
 
+use near_sdk_macros::near;
+
+use crate::NearToken;
+
 //* Types from near_vm_logic
 /// Promise index that is computed only once. It is an internal index that identifies a specific promise (or a sequence of promises) created during the execution of a smart contract.
 /// Returned by [`promise_create`](crate::env::promise_create) and can be used to refer this promise in `promise_then`, `promise_batch_create`, and other functions.
@@ -52,3 +56,14 @@ pub enum PromiseError {
     /// Promise result failed.
     Failed,
 }
+
+/// Height and total stake of the current epoch, returned by
+/// [`env::epoch_info`](crate::env::epoch_info).
+#[near(inside_nearsdk, serializers=[borsh, json])]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpochInfo {
+    /// Height of the epoch, see [`env::epoch_height`](crate::env::epoch_height).
+    pub epoch_height: u64,
+    /// Total stake of the validators of the epoch.
+    pub total_stake: NearToken,
+}
@@ -0,0 +1,301 @@
+//! Typed wrappers of the host functions of the `alt_bn128` curve, also known as BN254, used by
+//! zero-knowledge proof verifiers, e.g. of Groth16 proofs.
+//!
+//! The points and field elements have the Borsh layout expected by the host functions, so that
+//! they don't have to be packed by hand, and are serialized in JSON as base-10 strings, as
+//! produced by tools like `snarkjs`.
+//!
+//! Invalid points, e.g. not on the curve, make the host functions fail, which aborts the
+//! execution.
+//!
+//! # Examples
+//!
+//! ```
+//! use near_sdk::utils::alt_bn128::{self, G1Point, G2Point};
+//!
+//! /// Whether e(a, b) == e(c, d), given -a.
+//! fn pairings_equal(neg_a: G1Point, b: G2Point, c: G1Point, d: G2Point) -> bool {
+//!     alt_bn128::pairing_check(&[(neg_a, b), (c, d)])
+//! }
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk_macros::near;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::env;
+
+macro_rules! impl_field_element {
+    ($(#[$attr:meta])* $iden: ident) => {
+        $(#[$attr])*
+        #[near(inside_nearsdk)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+        pub struct $iden(pub [u8; 32]);
+
+        impl From<u64> for $iden {
+            fn from(v: u64) -> Self {
+                let mut bytes = [0; 32];
+                bytes[..8].copy_from_slice(&v.to_le_bytes());
+                Self(bytes)
+            }
+        }
+
+        impl FromStr for $iden {
+            type Err = ParseFieldElementError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                parse_decimal(s).map(Self)
+            }
+        }
+
+        impl fmt::Display for $iden {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&to_decimal(&self.0))
+            }
+        }
+
+        impl Serialize for $iden {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(&self.to_string())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $iden {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let s: String = Deserialize::deserialize(deserializer)?;
+                s.parse().map_err(|err: ParseFieldElementError| {
+                    serde::de::Error::custom(err.to_string())
+                })
+            }
+        }
+
+        #[cfg(feature = "abi")]
+        impl schemars::JsonSchema for $iden {
+            fn is_referenceable() -> bool {
+                false
+            }
+
+            fn schema_name() -> String {
+                String::schema_name()
+            }
+
+            fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+                String::json_schema(gen)
+            }
+        }
+    };
+}
+
+impl_field_element!(
+    /// Element of the base field of the curve, in little-endian.
+    Fq
+);
+impl_field_element!(
+    /// Element of the scalar field of the curve, in little-endian.
+    Fr
+);
+
+/// Element of the quadratic extension of the base field, `c0 + c1 * u`, serialized in JSON as
+/// `[c0, c1]`.
+#[near(inside_nearsdk, serializers=[borsh, json])]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Fq2(pub Fq, pub Fq);
+
+/// Point of the G1 group, in affine coordinates. The point at infinity is `(0, 0)`.
+#[near(inside_nearsdk, serializers=[borsh, json])]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct G1Point {
+    pub x: Fq,
+    pub y: Fq,
+}
+
+/// Point of the G2 group, in affine coordinates. The point at infinity is `(0, 0)`.
+#[near(inside_nearsdk, serializers=[borsh, json])]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct G2Point {
+    pub x: Fq2,
+    pub y: Fq2,
+}
+
+/// Returns the sum of the `points` multiplied by their scalar, with
+/// [`env::alt_bn128_g1_multiexp`].
+pub fn g1_multiexp(points: &[(G1Point, Fr)]) -> G1Point {
+    decode_g1(&env::alt_bn128_g1_multiexp(&encode(points)))
+}
+
+/// Returns the sum of the `points`, each negated if its flag is `true`, with
+/// [`env::alt_bn128_g1_sum`].
+pub fn g1_sum(points: &[(bool, G1Point)]) -> G1Point {
+    decode_g1(&env::alt_bn128_g1_sum(&encode(points)))
+}
+
+/// Whether the product of the pairings of the `pairs` is the identity, with
+/// [`env::alt_bn128_pairing_check`].
+pub fn pairing_check(pairs: &[(G1Point, G2Point)]) -> bool {
+    env::alt_bn128_pairing_check(&encode(pairs))
+}
+
+/// Concatenates the Borsh serialization of the `items`, without the length prefix of a slice.
+fn encode<T: BorshSerialize>(items: &[T]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for item in items {
+        item.serialize(&mut bytes).unwrap_or_else(|_| env::abort());
+    }
+    bytes
+}
+
+fn decode_g1(bytes: &[u8]) -> G1Point {
+    G1Point::try_from_slice(bytes).unwrap_or_else(|_| env::abort())
+}
+
+fn parse_decimal(s: &str) -> Result<[u8; 32], ParseFieldElementError> {
+    if s.is_empty() {
+        return Err(ParseFieldElementError { kind: ParseFieldElementErrorKind::Empty });
+    }
+    let mut value = [0u8; 32];
+    for c in s.chars() {
+        let mut carry = c
+            .to_digit(10)
+            .ok_or(ParseFieldElementError { kind: ParseFieldElementErrorKind::InvalidDigit(c) })?;
+        for byte in value.iter_mut() {
+            let v = *byte as u32 * 10 + carry;
+            *byte = v as u8;
+            carry = v >> 8;
+        }
+        if carry != 0 {
+            return Err(ParseFieldElementError { kind: ParseFieldElementErrorKind::Overflow });
+        }
+    }
+    Ok(value)
+}
+
+fn to_decimal(value: &[u8; 32]) -> String {
+    let mut value = *value;
+    let mut digits = Vec::new();
+    loop {
+        let mut remainder = 0u32;
+        for byte in value.iter_mut().rev() {
+            let v = (remainder << 8) | *byte as u32;
+            *byte = (v / 10) as u8;
+            remainder = v % 10;
+        }
+        digits.push(char::from(b'0' + remainder as u8));
+        if value.iter().all(|byte| *byte == 0) {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+/// Error returned when parsing an [`Fq`] or [`Fr`] from a base-10 string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFieldElementError {
+    kind: ParseFieldElementErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParseFieldElementErrorKind {
+    Empty,
+    InvalidDigit(char),
+    Overflow,
+}
+
+impl fmt::Display for ParseFieldElementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseFieldElementErrorKind::Empty => write!(f, "empty field element"),
+            ParseFieldElementErrorKind::InvalidDigit(c) => {
+                write!(f, "invalid digit {:?} in the field element", c)
+            }
+            ParseFieldElementErrorKind::Overflow => {
+                write!(f, "field element doesn't fit in 256 bits")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseFieldElementError {}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    fn g1() -> G1Point {
+        G1Point { x: Fq::from(1), y: Fq::from(2) }
+    }
+
+    fn g2() -> G2Point {
+        serde_json::from_str(
+            r#"{
+                "x": [
+                    "10857046999023057135944570762232829481370756359578518086990519993285655852781",
+                    "11559732032986387107991004021392285783925812861821192530917403151452391805634"
+                ],
+                "y": [
+                    "8495653923123431417604973247489272438418190587263600148770280649306958101930",
+                    "4082367875863433681332203403145435568316851327593401208105741076214120093531"
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn decimal() {
+        for s in ["0", "1", "255", "256", "18446744073709551616"] {
+            assert_eq!(s.parse::<Fr>().unwrap().to_string(), s);
+        }
+        assert_eq!("256".parse::<Fq>().unwrap(), Fq::from(256));
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(max.parse::<Fr>().unwrap(), Fr([u8::MAX; 32]));
+        assert_eq!(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+                .parse::<Fr>()
+                .unwrap_err()
+                .to_string(),
+            "field element doesn't fit in 256 bits"
+        );
+        assert!("".parse::<Fr>().is_err());
+        assert!("-1".parse::<Fr>().is_err());
+    }
+
+    #[test]
+    fn layout() {
+        assert_eq!(borsh::to_vec(&g1()).unwrap().len(), 64);
+        assert_eq!(borsh::to_vec(&g2()).unwrap().len(), 128);
+        assert_eq!(encode(&[(true, g1()), (false, g1())]).len(), 2 * 65);
+        assert_eq!(serde_json::to_string(&g1()).unwrap(), r#"{"x":"1","y":"2"}"#);
+    }
+
+    #[test]
+    fn sum_and_multiexp() {
+        test_env::setup_free();
+        let doubled = g1_sum(&[(false, g1()), (false, g1())]);
+        assert_eq!(g1_multiexp(&[(g1(), Fr::from(2))]), doubled);
+        assert_eq!(g1_sum(&[(false, doubled), (true, g1())]), g1());
+        assert_eq!(g1_sum(&[(false, g1()), (true, g1())]), G1Point::default());
+    }
+
+    #[test]
+    fn pairing() {
+        test_env::setup_free();
+        let neg_g1 = g1_sum(&[(true, g1())]);
+        assert!(pairing_check(&[]));
+        assert!(pairing_check(&[(g1(), g2()), (neg_g1, g2())]));
+        assert!(!pairing_check(&[(g1(), g2()), (g1(), g2())]));
+
+        // e(2 * g1, g2) == e(g1, g2) ^ 2
+        let doubled = g1_multiexp(&[(g1(), Fr::from(2))]);
+        assert!(pairing_check(&[(doubled, g2()), (neg_g1, g2()), (neg_g1, g2())]));
+    }
+}
@@ -0,0 +1,281 @@
+//! Meta transactions of [NEP-366](https://github.com/near/NEPs/blob/master/neps/nep-0366.md),
+//! for contracts receiving the actions signed by users and submitted by a relayer.
+//!
+//! When a relayer submits a [`SignedDelegateAction`] in a transaction, the protocol verifies it
+//! and the receiver sees the user as the predecessor. Contracts can also receive the signed
+//! delegate action as an argument, e.g. to pay for the execution on behalf of the user, and
+//! verify it with [`SignedDelegateAction::verify`]. The account that signed the actions, which
+//! the contract should act for rather than the relayer calling it, is then the
+//! [`sender_id`](DelegateAction::sender_id) of the verified action.
+//!
+//! The types have the Borsh layout of the protocol, so that the delegate actions created by
+//! wallets and relayers, e.g. with `near-api-js`, can be deserialized as is.
+
+use std::{fmt, io};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{env, AccountId, CryptoHash, Gas, NearToken, PublicKey};
+
+/// Prefix of the hash signed in a [`SignedDelegateAction`], as defined by
+/// [NEP-461](https://github.com/near/NEPs/blob/master/neps/nep-0461.md).
+const DELEGATE_ACTION_PREFIX: u32 = (1 << 30) + 366;
+
+/// Action of a [`DelegateAction`]. Delegate actions can't be nested.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    CreateAccount,
+    DeployContract {
+        code: Vec<u8>,
+    },
+    FunctionCall {
+        method_name: String,
+        args: Vec<u8>,
+        gas: Gas,
+        deposit: NearToken,
+    },
+    Transfer {
+        deposit: NearToken,
+    },
+    Stake {
+        stake: NearToken,
+        #[borsh(serialize_with = "serialize_key", deserialize_with = "deserialize_key")]
+        public_key: PublicKey,
+    },
+    AddKey {
+        #[borsh(serialize_with = "serialize_key", deserialize_with = "deserialize_key")]
+        public_key: PublicKey,
+        access_key: AccessKey,
+    },
+    DeleteKey {
+        #[borsh(serialize_with = "serialize_key", deserialize_with = "deserialize_key")]
+        public_key: PublicKey,
+    },
+    DeleteAccount {
+        beneficiary_id: AccountId,
+    },
+}
+
+/// Access key added by an [`Action::AddKey`].
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccessKey {
+    pub nonce: u64,
+    pub permission: AccessKeyPermission,
+}
+
+/// Permission of an [`AccessKey`].
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum AccessKeyPermission {
+    /// Calls of `method_names` of `receiver_id`, or of all its methods if `method_names` is
+    /// empty, paying for the gas with up to `allowance`.
+    FunctionCall {
+        allowance: Option<NearToken>,
+        receiver_id: AccountId,
+        method_names: Vec<String>,
+    },
+    FullAccess,
+}
+
+/// Actions that `sender_id` signed to be executed on `receiver_id`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DelegateAction {
+    /// Account that signed the actions.
+    pub sender_id: AccountId,
+    pub receiver_id: AccountId,
+    pub actions: Vec<Action>,
+    /// Nonce of the access key of `sender_id`, which must be higher than the nonce of the
+    /// delegate actions signed with the key before.
+    pub nonce: u64,
+    /// Last block height at which the actions can be executed.
+    pub max_block_height: u64,
+    /// Key that signed the actions.
+    #[borsh(serialize_with = "serialize_key", deserialize_with = "deserialize_key")]
+    pub public_key: PublicKey,
+}
+
+impl DelegateAction {
+    /// Returns the hash signed by the sender: the sha256 hash of the NEP-461 prefix followed by
+    /// the action, serialized with Borsh.
+    pub fn signable_hash(&self) -> CryptoHash {
+        let mut bytes = DELEGATE_ACTION_PREFIX.to_le_bytes().to_vec();
+        self.serialize(&mut bytes).unwrap_or_else(|_| env::abort());
+        env::sha256_array(&bytes).into()
+    }
+}
+
+/// Signature of a [`SignedDelegateAction`], with the Borsh layout of the protocol.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Signature {
+    Ed25519([u8; 64]),
+    /// Signature followed by the recovery byte.
+    Secp256k1([u8; 65]),
+}
+
+/// [`DelegateAction`] signed by its sender.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SignedDelegateAction {
+    pub delegate_action: DelegateAction,
+    pub signature: Signature,
+}
+
+impl SignedDelegateAction {
+    /// Whether the signature is the one of the public key of the action over its
+    /// [`signable_hash`](DelegateAction::signable_hash).
+    pub fn is_signature_valid(&self) -> bool {
+        let hash = self.delegate_action.signable_hash();
+        let public_key = &self.delegate_action.public_key;
+        match &self.signature {
+            Signature::Ed25519(signature) => {
+                env::verify_ed25519(signature, hash.as_bytes(), public_key).is_ok()
+            }
+            Signature::Secp256k1(signature) => {
+                let (rs, v) = signature.split_at(64);
+                let rs: &[u8; 64] = rs.try_into().unwrap_or_else(|_| env::abort());
+                matches!(
+                    env::secp256k1_recover(hash.as_bytes(), rs, v[0], false),
+                    Ok(recovered) if &recovered == public_key
+                )
+            }
+        }
+    }
+
+    /// Verifies that the action is signed by the key of its sender, that it has not expired,
+    /// and that its nonce is higher than `last_nonce`, the nonce of the last delegate action of
+    /// the same key accepted by the contract, if any. Returns the verified action, whose
+    /// [`sender_id`](DelegateAction::sender_id) signed it.
+    ///
+    /// The contract should store the nonce of the accepted action for its key, so that it can't
+    /// be replayed. Whether the key is an access key of the sender can't be checked by a
+    /// contract, so the sender should register its keys with the contract beforehand, unless the
+    /// sender is the signer of the transaction and the key is [`env::signer_account_pk`].
+    pub fn verify(&self, last_nonce: Option<u64>) -> Result<&DelegateAction, DelegateActionError> {
+        let action = &self.delegate_action;
+        if env::block_height() > action.max_block_height {
+            return Err(DelegateActionError {
+                kind: DelegateActionErrorKind::Expired {
+                    max_block_height: action.max_block_height,
+                },
+            });
+        }
+        if let Some(last_nonce) = last_nonce.filter(|last_nonce| action.nonce <= *last_nonce) {
+            return Err(DelegateActionError {
+                kind: DelegateActionErrorKind::InvalidNonce { nonce: action.nonce, last_nonce },
+            });
+        }
+        if !self.is_signature_valid() {
+            return Err(DelegateActionError { kind: DelegateActionErrorKind::InvalidSignature });
+        }
+        Ok(action)
+    }
+}
+
+/// Error returned by [`SignedDelegateAction::verify`] if the action can't be accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DelegateActionError {
+    kind: DelegateActionErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DelegateActionErrorKind {
+    Expired { max_block_height: u64 },
+    InvalidNonce { nonce: u64, last_nonce: u64 },
+    InvalidSignature,
+}
+
+impl fmt::Display for DelegateActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            DelegateActionErrorKind::Expired { max_block_height } => {
+                write!(f, "delegate action expired at block height {}", max_block_height)
+            }
+            DelegateActionErrorKind::InvalidNonce { nonce, last_nonce } => {
+                write!(f, "delegate action nonce {} is not higher than {}", nonce, last_nonce)
+            }
+            DelegateActionErrorKind::InvalidSignature => {
+                write!(f, "invalid signature of the delegate action")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DelegateActionError {}
+
+/// Serializes `public_key` as the protocol does: the curve type followed by the key, without
+/// the length prefix of [`PublicKey`]'s own Borsh layout.
+fn serialize_key<W: io::Write>(public_key: &PublicKey, writer: &mut W) -> io::Result<()> {
+    writer.write_all(public_key.as_bytes())
+}
+
+fn deserialize_key<R: io::Read>(reader: &mut R) -> io::Result<PublicKey> {
+    let curve_type = u8::deserialize_reader(reader)?;
+    let len = match curve_type {
+        0 => 32,
+        1 => 64,
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "unknown curve type")),
+    };
+    let mut bytes = vec![0; 1 + len];
+    bytes[0] = curve_type;
+    reader.read_exact(&mut bytes[1..])?;
+    PublicKey::try_from(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::test_utils::VMContextBuilder;
+    use crate::testing_env;
+
+    fn signed_transfer() -> SignedDelegateAction {
+        let public_key: PublicKey =
+            "ed25519:FAe4sisG95oZ42w7buUn5qEE4TAnfTTFPiguZUHmhiF".parse().unwrap();
+        SignedDelegateAction {
+            delegate_action: DelegateAction {
+                sender_id: "alice.near".parse().unwrap(),
+                receiver_id: "bob.near".parse().unwrap(),
+                actions: vec![Action::Transfer { deposit: NearToken::from_near(1) }],
+                nonce: 5,
+                max_block_height: 100,
+                public_key,
+            },
+            signature: Signature::Ed25519(
+                hex::decode("7fb3ed1bad456eb583e81da514632c88c462e7eee80dfd4cf90caa5037c43506e6691e02de1b0521c64497d693d8ff656ab74c3c38c1b8aa595a792465ecc007")
+                    .unwrap()
+                    .try_into()
+                    .unwrap(),
+            ),
+        }
+    }
+
+    #[test]
+    fn protocol_layout() {
+        let signed = signed_transfer();
+        let bytes = borsh::to_vec(&signed).unwrap();
+        // The public key is serialized without a length prefix.
+        assert_eq!(bytes.len(), (4 + 10) + (4 + 8) + (4 + 1 + 16) + 8 + 8 + 33 + (1 + 64));
+        assert_eq!(SignedDelegateAction::try_from_slice(&bytes).unwrap(), signed);
+    }
+
+    #[test]
+    fn verify() {
+        testing_env!(VMContextBuilder::new().block_height(50).build());
+        let signed = signed_transfer();
+        assert_eq!(signed.verify(Some(4)).unwrap().sender_id.as_str(), "alice.near");
+        assert_eq!(
+            signed.verify(Some(5)).unwrap_err().to_string(),
+            "delegate action nonce 5 is not higher than 5"
+        );
+
+        let mut tampered = signed.clone();
+        tampered.delegate_action.nonce = 6;
+        assert_eq!(
+            tampered.verify(None).unwrap_err().to_string(),
+            "invalid signature of the delegate action"
+        );
+
+        testing_env!(VMContextBuilder::new().block_height(101).build());
+        assert_eq!(
+            signed.verify(None).unwrap_err().to_string(),
+            "delegate action expired at block height 100"
+        );
+    }
+}
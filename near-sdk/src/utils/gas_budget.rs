@@ -0,0 +1,155 @@
+use crate::{env, require, Gas};
+
+/// Planner for the gas attached to the function calls scheduled by the current method.
+///
+/// The budget starts from the gas available to the current method, usually through
+/// [`from_remaining`](Self::from_remaining). Gas needed by the current method itself, e.g. to
+/// finish its execution and create the receipts, is set aside with [`reserve`](Self::reserve),
+/// and the rest is divided between the scheduled calls with
+/// [`split_weighted`](Self::split_weighted). Each step panics with a message describing the
+/// shortfall if the budget cannot cover it, so that an infeasible plan fails before any call is
+/// scheduled rather than in one of the calls.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{Gas, GasBudget};
+/// # use near_sdk::{testing_env, test_utils::VMContextBuilder};
+/// # testing_env!(VMContextBuilder::new().prepaid_gas(Gas::from_tgas(300)).build());
+///
+/// let gas = GasBudget::from_remaining()
+///     .reserve(Gas::from_tgas(20))
+///     .min_per_call(Gas::from_tgas(10))
+///     .split_weighted(&[2, 1, 1]);
+///
+/// assert_eq!(gas.len(), 3);
+/// assert_eq!(gas[1], gas[2]);
+/// assert!(gas.iter().map(|gas| gas.as_gas()).sum::<u64>() <= Gas::from_tgas(280).as_gas());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasBudget {
+    available: Gas,
+    min_per_call: Gas,
+}
+
+impl GasBudget {
+    /// Creates a budget of `available` gas.
+    pub fn new(available: Gas) -> Self {
+        Self { available, min_per_call: Gas::from_gas(0) }
+    }
+
+    /// Creates a budget of the gas left to the current method, i.e. its prepaid gas minus the gas
+    /// used so far.
+    pub fn from_remaining() -> Self {
+        Self::new(env::prepaid_gas().saturating_sub(env::used_gas()))
+    }
+
+    /// Sets `gas` aside from the budget, e.g. for the completion of the current method.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the budget has less than `gas` available.
+    pub fn reserve(self, gas: Gas) -> Self {
+        match self.available.as_gas().checked_sub(gas.as_gas()) {
+            Some(available) => Self { available: Gas::from_gas(available), ..self },
+            None => env::panic_str(&format!(
+                "Not enough gas to reserve {} for the current method, only {} is available",
+                gas, self.available
+            )),
+        }
+    }
+
+    /// Sets the minimum amount of gas each call has to get when the budget is split.
+    pub fn min_per_call(self, gas: Gas) -> Self {
+        Self { min_per_call: gas, ..self }
+    }
+
+    /// Returns the gas available to split between the calls.
+    pub fn available(&self) -> Gas {
+        self.available
+    }
+
+    /// Splits the available gas between calls proportionally to their `weights`, and returns the
+    /// gas to attach to each call, in the same order. Shares are rounded down, so at most one gas
+    /// unit per call stays unassigned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the weights are all zero, or if a call gets less than the minimum set with
+    /// [`min_per_call`](Self::min_per_call).
+    pub fn split_weighted(&self, weights: &[u64]) -> Vec<Gas> {
+        let total: u128 = weights.iter().map(|&weight| u128::from(weight)).sum();
+        require!(total > 0, "Gas can only be split between calls with a non-zero total weight");
+
+        weights
+            .iter()
+            .map(|&weight| {
+                // The share is at most the available gas, so it fits in a `u64`.
+                let share = u128::from(self.available.as_gas()) * u128::from(weight) / total;
+                let gas = Gas::from_gas(share as u64);
+                if gas < self.min_per_call {
+                    env::panic_str(&format!(
+                        "Not enough gas to split {} between calls with weights {:?}, each call needs at least {}",
+                        self.available, weights, self.min_per_call
+                    ));
+                }
+                gas
+            })
+            .collect()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::GasBudget;
+    use crate::test_utils::VMContextBuilder;
+    use crate::{testing_env, Gas};
+
+    #[test]
+    fn split_after_reserve() {
+        let budget = GasBudget::new(Gas::from_tgas(100)).reserve(Gas::from_tgas(20));
+        assert_eq!(budget.available(), Gas::from_tgas(80));
+        assert_eq!(
+            budget.split_weighted(&[2, 1, 1]),
+            [Gas::from_tgas(40), Gas::from_tgas(20), Gas::from_tgas(20)]
+        );
+        assert_eq!(
+            GasBudget::new(Gas::from_gas(10)).split_weighted(&[1, 1, 1]),
+            [Gas::from_gas(3); 3]
+        );
+        assert_eq!(
+            GasBudget::new(Gas::from_gas(u64::MAX)).split_weighted(&[u64::MAX]),
+            [Gas::from_gas(u64::MAX)]
+        );
+    }
+
+    #[test]
+    fn from_remaining() {
+        testing_env!(VMContextBuilder::new().prepaid_gas(Gas::from_tgas(300)).build());
+        let budget = GasBudget::from_remaining();
+        assert!(budget.available() <= Gas::from_tgas(300));
+        assert!(budget.available() > Gas::from_tgas(299));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough gas to reserve")]
+    fn reserve_more_than_available() {
+        GasBudget::new(Gas::from_tgas(10)).reserve(Gas::from_tgas(11));
+    }
+
+    #[test]
+    #[should_panic(expected = "each call needs at least")]
+    fn share_below_minimum() {
+        GasBudget::new(Gas::from_tgas(30))
+            .reserve(Gas::from_tgas(5))
+            .min_per_call(Gas::from_tgas(10))
+            .split_weighted(&[2, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero total weight")]
+    fn zero_weights() {
+        GasBudget::new(Gas::from_tgas(30)).split_weighted(&[0, 0]);
+    }
+}
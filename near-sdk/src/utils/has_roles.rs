@@ -0,0 +1,49 @@
+use crate::AccountId;
+
+/// Role of the owner of a contract, checked by the methods marked with
+/// [`#[only_owner]`](crate::near#only_owner-annotates-methods-of-a-type-in-its-impl-block).
+pub const OWNER_ROLE: &str = "owner";
+
+/// Role membership of the accounts calling a contract, checked by the methods marked with
+/// [`#[only(role = "...")]`](crate::near#only-annotates-methods-of-a-type-in-its-impl-block)
+/// or [`#[only_owner]`](crate::near#only_owner-annotates-methods-of-a-type-in-its-impl-block).
+///
+/// The roles are usually stored by a component of the contract, such as the `AccessControl` of
+/// `near_contract_standards::access_control` or the `Ownable` of
+/// `near_contract_standards::ownable`, which the implementation delegates to.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{near, AccountId, HasRoles};
+///
+/// #[near(contract_state)]
+/// #[derive(Default)]
+/// pub struct Contract {
+///     minter: Option<AccountId>,
+///     supply: u64,
+/// }
+///
+/// impl HasRoles for Contract {
+///     fn has_role(&self, role: &str, account_id: &AccountId) -> bool {
+///         role == "minter" && self.minter.as_ref() == Some(account_id)
+///     }
+/// }
+///
+/// #[near]
+/// impl Contract {
+///     #[only(role = "minter")]
+///     pub fn mint(&mut self, amount: u64) {
+///         self.supply += amount;
+///     }
+/// }
+/// ```
+pub trait HasRoles {
+    /// Whether `account_id` has `role`.
+    fn has_role(&self, role: &str, account_id: &AccountId) -> bool;
+
+    /// Whether `account_id` is the owner of the contract, i.e. has [`OWNER_ROLE`].
+    fn is_owner(&self, account_id: &AccountId) -> bool {
+        self.has_role(OWNER_ROLE, account_id)
+    }
+}
@@ -6,6 +6,20 @@ mod stable_map;
 pub(crate) use self::stable_map::StableMap;
 mod cache_entry;
 pub(crate) use cache_entry::{CacheEntry, EntryState};
+mod gas_budget;
+pub use self::gas_budget::GasBudget;
+pub mod alt_bn128;
+pub mod delegate_action;
+mod has_roles;
+pub use self::has_roles::{HasRoles, OWNER_ROLE};
+mod non_reentrant;
+pub use self::non_reentrant::{clear_non_reentrant, is_non_reentrant_set, non_reentrant_key};
+mod self_recurse;
+pub use self::self_recurse::self_recurse;
+mod signed_message;
+pub use self::signed_message::{verify_signed_message, verify_signed_message_by_signer};
+mod transfer_call_msg;
+pub use self::transfer_call_msg::{TransferCallMsg, TransferCallMsgError, DEFAULT_MAX_MSG_LEN};
 
 use crate::{env, NearToken, PromiseResult};
 
@@ -75,6 +89,49 @@ macro_rules! require {
     };
 }
 
+/// Checks at compile time that the storage keys of the given enums deriving
+/// [`BorshStorageKey`](crate::BorshStorageKey) cannot collide, i.e. that no variant of one enum
+/// has a key prefix that is a prefix of the key of a variant of another enum.
+///
+/// Enums that do not set explicit prefixes start their keys with the index of the variant, so at
+/// most one of the registered enums can use the default prefixes. Compilation fails with an error
+/// naming the registered enums if the prefixes collide.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{assert_unique_storage_keys, near, BorshStorageKey};
+///
+/// #[near(serializers=[borsh])]
+/// #[derive(BorshStorageKey)]
+/// enum StorageKey {
+///     Accounts,
+///     Balances,
+/// }
+///
+/// #[near(serializers=[borsh])]
+/// #[derive(BorshStorageKey)]
+/// enum ExtensionKey {
+///     #[storage_key(prefix = b"ext:r")]
+///     Rewards,
+///     #[storage_key(prefix = b"ext:c")]
+///     Claims { epoch: u64 },
+/// }
+///
+/// assert_unique_storage_keys!(StorageKey, ExtensionKey);
+/// ```
+#[macro_export]
+macro_rules! assert_unique_storage_keys {
+    ($($key:ty),+ $(,)?) => {
+        const _: () = assert!(
+            !$crate::__private::storage_key_prefixes_collide(&[
+                $(<$key as $crate::__private::StorageKeyPrefixes>::PREFIXES),+
+            ]),
+            concat!("storage keys of ", stringify!($($key),+), " collide")
+        );
+    };
+}
+
 /// Assert that predecessor_account_id == current_account_id, meaning contract called itself.
 pub fn assert_self() {
     require!(env::predecessor_account_id() == env::current_account_id(), "Method is private");
@@ -0,0 +1,125 @@
+use borsh::BorshSerialize;
+
+use crate::env;
+
+/// Prefix of the storage keys of the reentrancy guards of `#[non_reentrant]` methods.
+const NON_REENTRANT_PREFIX: &[u8] = b"__NON_REENTRANT";
+
+/// Returns the storage key of the reentrancy guard of `#[non_reentrant]` methods for `key`, by
+/// default the account calling the guarded method.
+pub fn non_reentrant_key<K: BorshSerialize + ?Sized>(key: &K) -> Vec<u8> {
+    let mut storage_key = NON_REENTRANT_PREFIX.to_vec();
+    key.serialize(&mut storage_key).unwrap_or_else(|_| env::abort());
+    storage_key
+}
+
+/// Returns whether the reentrancy guard of `#[non_reentrant]` methods is set for `key`, i.e.
+/// whether a guarded method called with this key, or the promise it returned, is executing.
+pub fn is_non_reentrant_set<K: BorshSerialize + ?Sized>(key: &K) -> bool {
+    env::storage_has_key(&non_reentrant_key(key))
+}
+
+/// Clears the reentrancy guard of `#[non_reentrant]` methods for `key`, returning whether it was
+/// set.
+///
+/// The guard of a method returning a promise is cleared by its `#[non_reentrant(callback)]`
+/// callback. A callback which panics or runs out of gas has its changes to the storage reverted,
+/// so the guard stays set and the guarded methods fail for `key` until this is called, typically
+/// from a method restricted to the owner of the contract.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::utils::clear_non_reentrant;
+/// use near_sdk::{near, AccountId};
+///
+/// #[near(contract_state)]
+/// #[derive(Default)]
+/// pub struct Vault {}
+///
+/// #[near]
+/// impl Vault {
+///     #[only_owner]
+///     pub fn clear_withdrawal_guard(&mut self, account_id: AccountId) -> bool {
+///         clear_non_reentrant(&account_id)
+///     }
+/// }
+/// ```
+pub fn clear_non_reentrant<K: BorshSerialize + ?Sized>(key: &K) -> bool {
+    env::storage_remove(&non_reentrant_key(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::__private::{check_non_reentrant_callback, enter_non_reentrant, exit_non_reentrant};
+    use crate::test_utils::{accounts, VMContextBuilder};
+    use crate::testing_env;
+
+    #[test]
+    fn guard_per_key() {
+        let (alice, bob) = (accounts(0), accounts(1));
+        enter_non_reentrant("withdraw", &non_reentrant_key(&alice));
+        assert!(is_non_reentrant_set(&alice));
+        assert!(!is_non_reentrant_set(&bob));
+        enter_non_reentrant("withdraw", &non_reentrant_key(&bob));
+
+        exit_non_reentrant(&non_reentrant_key(&alice));
+        assert!(!is_non_reentrant_set(&alice));
+        enter_non_reentrant("withdraw", &non_reentrant_key(&alice));
+    }
+
+    #[test]
+    #[should_panic(expected = "Method withdraw cannot be reentered")]
+    fn reentered() {
+        let key = non_reentrant_key(&accounts(0));
+        enter_non_reentrant("withdraw", &key);
+        enter_non_reentrant("withdraw", &key);
+    }
+
+    /// The callback clearing the guard can only be called by the contract, the predecessor of the
+    /// promise of the guarded method, so that an outside caller can't clear the guard of a key.
+    #[test]
+    #[should_panic(expected = "Method on_withdraw is private")]
+    fn outside_caller_cannot_clear() {
+        let key = non_reentrant_key(&accounts(0));
+        enter_non_reentrant("withdraw", &key);
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(2))
+            .predecessor_account_id(accounts(1))
+            .build());
+        check_non_reentrant_callback("on_withdraw");
+        exit_non_reentrant(&key);
+    }
+
+    #[test]
+    fn contract_clears_in_callback() {
+        let key = non_reentrant_key(&accounts(0));
+        enter_non_reentrant("withdraw", &key);
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(2))
+            .predecessor_account_id(accounts(2))
+            .build());
+        check_non_reentrant_callback("on_withdraw");
+        exit_non_reentrant(&key);
+        assert!(!is_non_reentrant_set(&accounts(0)));
+    }
+
+    /// A callback which panics or runs out of gas has its changes reverted, including the exit
+    /// of the guard, which then stays set for its key only, until it is cleared.
+    #[test]
+    fn failed_callback_recovered() {
+        let (alice, bob) = (accounts(0), accounts(1));
+        let key = non_reentrant_key(&alice);
+        enter_non_reentrant("withdraw", &key);
+
+        assert!(is_non_reentrant_set(&alice));
+        enter_non_reentrant("withdraw", &non_reentrant_key(&bob));
+
+        assert!(clear_non_reentrant(&alice));
+        assert!(!clear_non_reentrant(&alice));
+        enter_non_reentrant("withdraw", &key);
+    }
+}
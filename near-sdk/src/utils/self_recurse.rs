@@ -0,0 +1,130 @@
+use serde::Serialize;
+
+use crate::{env, Gas, GasBudget, GasWeight, NearToken, Promise};
+
+/// Gas set aside for scheduling the next call and finishing the current method, on top of the
+/// gas the next call needs.
+const SELF_RECURSE_RESERVE: Gas = Gas::from_tgas(5);
+
+#[derive(Serialize)]
+struct WorkIncomplete<'a, T: ?Sized> {
+    standard: &'static str,
+    version: &'static str,
+    event: &'static str,
+    data: WorkIncompleteData<'a, T>,
+}
+
+#[derive(Serialize)]
+struct WorkIncompleteData<'a, T: ?Sized> {
+    method: &'a str,
+    cursor: &'a T,
+}
+
+/// Schedules a call of `method` on the current contract with the JSON-serialized `args`, if the
+/// gas left to the current method can cover `min_gas_to_continue` for the next call. This is the
+/// usual way to process a workload that does not fit in the gas of a single call, such as mass
+/// refunds, garbage collection or state migrations, in chunks: each call processes as much as it
+/// can, then calls itself with the cursor to resume from.
+///
+/// The next call gets `min_gas_to_continue` and all the gas left unused by the current method.
+/// 5 Tgas are kept on top of `min_gas_to_continue` for scheduling the call and finishing the
+/// current method.
+///
+/// If there is not enough gas left, no call is scheduled and `None` is returned. A
+/// `work_incomplete` event is emitted instead, with the method and the `args` it would have been
+/// called with, so that the work can be resumed by calling it again with more gas:
+///
+/// ```text
+/// EVENT_JSON:{"standard":"near_sdk","version":"1.0.0","event":"work_incomplete","data":{"method":"refund","cursor":{"from_index":200}}}
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::{near, self_recurse, Gas};
+/// # use near_sdk::{testing_env, test_utils::VMContextBuilder};
+/// # testing_env!(VMContextBuilder::new().prepaid_gas(Gas::from_tgas(300)).build());
+///
+/// #[near(serializers = [json])]
+/// pub struct RefundCursor {
+///     from_index: u64,
+/// }
+///
+/// const REFUNDS_PER_CALL: u64 = 100;
+///
+/// // Body of a `refund(from_index: u64)` contract method.
+/// # let (from_index, accounts_to_refund) = (0, 250);
+/// // Refund the accounts from `from_index` to `from_index + REFUNDS_PER_CALL`...
+/// let from_index = from_index + REFUNDS_PER_CALL;
+/// if from_index < accounts_to_refund {
+///     self_recurse("refund", &RefundCursor { from_index }, Gas::from_tgas(50));
+/// }
+/// ```
+pub fn self_recurse<T>(method: &str, args: &T, min_gas_to_continue: Gas) -> Option<Promise>
+where
+    T: Serialize + ?Sized,
+{
+    let required = min_gas_to_continue.saturating_add(SELF_RECURSE_RESERVE);
+    if GasBudget::from_remaining().available() < required {
+        let event = WorkIncomplete {
+            standard: "near_sdk",
+            version: "1.0.0",
+            event: "work_incomplete",
+            data: WorkIncompleteData { method, cursor: args },
+        };
+        let event = serde_json::to_string(&event).unwrap_or_else(|_| env::abort());
+        env::log_str(&format!("EVENT_JSON:{}", event));
+        return None;
+    }
+
+    let args = serde_json::to_vec(args).unwrap_or_else(|_| env::abort());
+    Some(Promise::new(env::current_account_id()).function_call_weight(
+        method.to_string(),
+        args,
+        NearToken::from_yoctonear(0),
+        min_gas_to_continue,
+        GasWeight(1),
+    ))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::self_recurse;
+    use crate::mock::MockAction;
+    use crate::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+    use crate::{testing_env, Gas};
+
+    #[test]
+    fn schedules_call_with_enough_gas() {
+        testing_env!(VMContextBuilder::new().prepaid_gas(Gas::from_tgas(100)).build());
+        drop(self_recurse("migrate", &serde_json::json!({ "from": 10 }), Gas::from_tgas(50)));
+
+        let receipts = get_created_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, crate::env::current_account_id());
+        match &receipts[0].actions[..] {
+            [MockAction::FunctionCallWeight { method_name, args, prepaid_gas, .. }] => {
+                assert_eq!(method_name, b"migrate");
+                assert_eq!(args, br#"{"from":10}"#);
+                assert_eq!(*prepaid_gas, Gas::from_tgas(50));
+            }
+            actions => panic!("unexpected actions {:?}", actions),
+        }
+        assert!(get_logs().is_empty());
+    }
+
+    #[test]
+    fn emits_event_without_enough_gas() {
+        testing_env!(VMContextBuilder::new().prepaid_gas(Gas::from_tgas(54)).build());
+        assert!(self_recurse("migrate", &[1, 2], Gas::from_tgas(50)).is_none());
+
+        assert!(get_created_receipts().is_empty());
+        assert_eq!(
+            get_logs(),
+            [
+                r#"EVENT_JSON:{"standard":"near_sdk","version":"1.0.0","event":"work_incomplete","data":{"method":"migrate","cursor":[1,2]}}"#
+            ]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use borsh::BorshSerialize;
+
+use crate::{env, PublicKey};
+
+/// Prefix of the hash signed in a message, as defined by
+/// [NEP-461](https://github.com/near/NEPs/blob/master/neps/nep-0461.md).
+const SIGNED_MESSAGE_PREFIX: u32 = (1 << 31) + 413;
+
+/// Payload of a message signed by a wallet, as serialized for the signature.
+#[derive(BorshSerialize)]
+struct Payload<'a> {
+    message: &'a str,
+    nonce: &'a [u8; 32],
+    recipient: &'a str,
+    callback_url: Option<&'a str>,
+}
+
+/// Whether `signature` is the signature of `message` by `public_key`, following
+/// [NEP-413](https://github.com/near/NEPs/blob/master/neps/nep-0413.md), i.e. as signed by a
+/// wallet with `signMessage`.
+///
+/// `nonce` must be the 32 bytes passed to the wallet, which the contract should check are not
+/// reused, e.g. by storing them or by deriving them from the current state, and `recipient` the
+/// account the message is for, usually [`env::current_account_id`]. Messages signed with a
+/// `callbackUrl` are not supported. Only ed25519 keys can sign messages.
+///
+/// This only checks that the message is signed by `public_key`, whose owner must be checked
+/// separately, e.g. with [`verify_signed_message_by_signer`].
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::utils::verify_signed_message;
+/// use near_sdk::{env, PublicKey};
+///
+/// fn login(message: &str, signature: &[u8; 64], public_key: &PublicKey, nonce: &[u8; 32]) {
+///     let recipient = env::current_account_id();
+///     if !verify_signed_message(message, signature, public_key, nonce, recipient.as_str()) {
+///         env::panic_str("Invalid signature");
+///     }
+/// }
+/// ```
+pub fn verify_signed_message(
+    message: &str,
+    signature: &[u8; 64],
+    public_key: &PublicKey,
+    nonce: &[u8; 32],
+    recipient: &str,
+) -> bool {
+    let mut bytes = SIGNED_MESSAGE_PREFIX.to_le_bytes().to_vec();
+    Payload { message, nonce, recipient, callback_url: None }
+        .serialize(&mut bytes)
+        .unwrap_or_else(|_| env::abort());
+    env::verify_ed25519(signature, &env::sha256_array(&bytes), public_key).is_ok()
+}
+
+/// Whether `signature` is the signature of `message` by the key which signed the current
+/// transaction, [`env::signer_account_pk`], see [`verify_signed_message`].
+///
+/// The key is an access key of [`env::signer_account_id`], so the message is signed by that
+/// account.
+pub fn verify_signed_message_by_signer(
+    message: &str,
+    signature: &[u8; 64],
+    nonce: &[u8; 32],
+    recipient: &str,
+) -> bool {
+    verify_signed_message(message, signature, &env::signer_account_pk(), nonce, recipient)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::test_utils::VMContextBuilder;
+    use crate::testing_env;
+
+    const NONCE: [u8; 32] = [7; 32];
+
+    fn public_key() -> PublicKey {
+        "ed25519:FAe4sisG95oZ42w7buUn5qEE4TAnfTTFPiguZUHmhiF".parse().unwrap()
+    }
+
+    fn signature() -> [u8; 64] {
+        hex::decode("79488bbcc7d24d532f91a99962e3eef005bddea66615c0a28953a2f908b9e37bed6f8888c10aae9830ff3b981441c94f48a62c0774e4e1b72997535d36d3910b")
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn verify() {
+        let public_key = public_key();
+        let signature = signature();
+        assert!(verify_signed_message(
+            "Login to app.near",
+            &signature,
+            &public_key,
+            &NONCE,
+            "app.near"
+        ));
+        assert!(!verify_signed_message(
+            "Login to app.near",
+            &signature,
+            &public_key,
+            &NONCE,
+            "evil.near"
+        ));
+        assert!(!verify_signed_message(
+            "Login to app.near",
+            &signature,
+            &public_key,
+            &[8; 32],
+            "app.near"
+        ));
+    }
+
+    #[test]
+    fn verify_by_signer() {
+        testing_env!(VMContextBuilder::new().signer_account_pk(public_key()).build());
+        assert!(verify_signed_message_by_signer(
+            "Login to app.near",
+            &signature(),
+            &NONCE,
+            "app.near"
+        ));
+
+        testing_env!(VMContextBuilder::new().build());
+        assert!(!verify_signed_message_by_signer(
+            "Login to app.near",
+            &signature(),
+            &NONCE,
+            "app.near"
+        ));
+    }
+}
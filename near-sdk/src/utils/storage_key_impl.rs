@@ -1,3 +1,7 @@
+use borsh::BorshSerialize;
+
+use crate::env;
+
 /// Converts Self into a [`Vec<u8>`] that is used for a storage key through [`into_storage_key`].
 ///
 /// [`into_storage_key`]: IntoStorageKey::into_storage_key
@@ -35,3 +39,62 @@ impl IntoStorageKey for u8 {
         vec![self]
     }
 }
+
+/// Storage key prefix of a collection nested in an entry of another collection, e.g. of the
+/// `Vector` of orders of an account in a `LookupMap<AccountId, Vector<Order>>`.
+///
+/// [`StorageKey::nested`] appends the Borsh serialization of the key of the entry to the prefix
+/// of the parent, which should be unique to the nested collections, e.g. a variant of the
+/// [`BorshStorageKey`](crate::BorshStorageKey) enum of the contract. The Borsh serialization of a
+/// key is never a prefix of the serialization of another key of the same type, so the nested
+/// collections of different entries cannot overlap. The `#[storage_key(nested)]` attribute of
+/// [`BorshStorageKey`](crate::BorshStorageKey) generates a constructor of these prefixes for a
+/// variant.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::store::{LookupMap, Vector};
+/// use near_sdk::{near, AccountId, BorshStorageKey, StorageKey};
+///
+/// #[near(serializers=[borsh])]
+/// #[derive(BorshStorageKey)]
+/// enum Prefix {
+///     Orders,
+///     OrdersOf,
+/// }
+///
+/// let mut orders: LookupMap<AccountId, Vector<u64>> = LookupMap::new(Prefix::Orders);
+/// orders
+///     .entry("alice.near".parse().unwrap())
+///     .or_insert_with_key(|account_id| Vector::new(StorageKey::nested(Prefix::OrdersOf, account_id)))
+///     .push(7);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StorageKey(Vec<u8>);
+
+impl StorageKey {
+    /// Returns the prefix of the collection nested in the entry `key` of a collection, made of
+    /// `parent_prefix` followed by the Borsh serialization of `key`.
+    pub fn nested<P, K>(parent_prefix: P, key: &K) -> Self
+    where
+        P: IntoStorageKey,
+        K: BorshSerialize + ?Sized,
+    {
+        let mut prefix = parent_prefix.into_storage_key();
+        key.serialize(&mut prefix).unwrap_or_else(|_| env::panic_str("Cannot serialize key"));
+        Self(prefix)
+    }
+
+    /// Returns the bytes of the prefix.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl IntoStorageKey for StorageKey {
+    #[inline]
+    fn into_storage_key(self) -> Vec<u8> {
+        self.0
+    }
+}
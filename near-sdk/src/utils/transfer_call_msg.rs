@@ -0,0 +1,190 @@
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+
+use crate::env;
+
+/// Typed `msg` payload of the `ft_on_transfer` and `nft_on_transfer` receiver methods of the
+/// fungible and non-fungible token standards.
+///
+/// The `msg` of a transfer call is an arbitrary string chosen by the sender, so it has to be
+/// validated before acting on the transferred tokens. This trait parses it as JSON into `Self`,
+/// after checking its length against [`MAX_LEN`](Self::MAX_LEN) and, if the type sets a
+/// [`VERSION`](Self::VERSION), that the JSON object has a matching `"version"` field.
+///
+/// When the `msg` is invalid, receivers should not panic but return the full amount (or `true`
+/// for non-fungible tokens) so that the tokens are refunded by the resolve step of the token
+/// contract, which is what [`parse_msg_or_refund`](Self::parse_msg_or_refund) helps with.
+///
+/// The trait is implemented with `#[derive(TransferCallMsg)]`, which accepts the
+/// `#[transfer_call_msg(max_len = ..., version = ...)]` attribute to override the constants.
+///
+/// # Size limits
+///
+/// The `msg` is a function call argument, so it is bounded by the maximum arguments length of
+/// the protocol (4 MiB), but parsing it costs gas in the receiver. [`MAX_LEN`](Self::MAX_LEN)
+/// defaults to [`DEFAULT_MAX_MSG_LEN`] bytes, which is enough for typical instructions while
+/// keeping the parsing cost of a malicious `msg` bounded.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::json_types::U128;
+/// use near_sdk::serde::Deserialize;
+/// use near_sdk::{AccountId, PromiseOrValue, TransferCallMsg};
+///
+/// #[derive(Deserialize, TransferCallMsg)]
+/// #[serde(crate = "near_sdk::serde", tag = "action", rename_all = "snake_case")]
+/// #[transfer_call_msg(max_len = 256, version = 1)]
+/// enum DepositMsg {
+///     Stake { pool_id: u64 },
+///     Swap { min_amount_out: U128 },
+/// }
+///
+/// fn ft_on_transfer(sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+///     let msg = match DepositMsg::parse_msg_or_refund(&msg) {
+///         Some(msg) => msg,
+///         // Refund all the tokens.
+///         None => return PromiseOrValue::Value(amount),
+///     };
+///     match msg {
+///         DepositMsg::Stake { pool_id } => { /* ... */ }
+///         DepositMsg::Swap { min_amount_out } => { /* ... */ }
+///     }
+///     PromiseOrValue::Value(U128(0))
+/// }
+///
+/// let msg = r#"{"version":1,"action":"stake","pool_id":7}"#;
+/// assert!(matches!(DepositMsg::parse_msg(msg), Ok(DepositMsg::Stake { pool_id: 7 })));
+/// assert!(DepositMsg::parse_msg(r#"{"action":"stake","pool_id":7}"#).is_err());
+/// ```
+pub trait TransferCallMsg: DeserializeOwned {
+    /// Maximum length of the `msg`, in bytes.
+    const MAX_LEN: usize = DEFAULT_MAX_MSG_LEN;
+
+    /// Version the `"version"` field of the `msg` has to match, if any. The field is removed from
+    /// the JSON object before it is deserialized into `Self`.
+    const VERSION: Option<u64> = None;
+
+    /// Parses the `msg` of a transfer call.
+    fn parse_msg(msg: &str) -> Result<Self, TransferCallMsgError> {
+        if msg.len() > Self::MAX_LEN {
+            return Err(TransferCallMsgError {
+                kind: TransferCallMsgErrorKind::TooLong { len: msg.len(), max_len: Self::MAX_LEN },
+            });
+        }
+        let invalid = |error: serde_json::Error| TransferCallMsgError {
+            kind: TransferCallMsgErrorKind::InvalidJson(error.to_string()),
+        };
+        let version = match Self::VERSION {
+            Some(version) => version,
+            None => return serde_json::from_str(msg).map_err(invalid),
+        };
+
+        let mut value: serde_json::Value = serde_json::from_str(msg).map_err(invalid)?;
+        let found = value.as_object_mut().and_then(|object| object.remove("version"));
+        match found.as_ref().and_then(serde_json::Value::as_u64) {
+            Some(found) if found == version => serde_json::from_value(value).map_err(invalid),
+            found => Err(TransferCallMsgError {
+                kind: TransferCallMsgErrorKind::UnsupportedVersion { found, expected: version },
+            }),
+        }
+    }
+
+    /// Parses the `msg` of a transfer call, logging the error and returning `None` if it is
+    /// invalid, in which case the receiver should refund all the transferred tokens.
+    fn parse_msg_or_refund(msg: &str) -> Option<Self> {
+        Self::parse_msg(msg)
+            .map_err(|error| env::log_str(&format!("Refunding transfer: {}", error)))
+            .ok()
+    }
+}
+
+/// Default [`TransferCallMsg::MAX_LEN`], in bytes.
+pub const DEFAULT_MAX_MSG_LEN: usize = 1024;
+
+/// Error returned by [`TransferCallMsg::parse_msg`] if the `msg` of a transfer call is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferCallMsgError {
+    kind: TransferCallMsgErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TransferCallMsgErrorKind {
+    TooLong { len: usize, max_len: usize },
+    InvalidJson(String),
+    UnsupportedVersion { found: Option<u64>, expected: u64 },
+}
+
+impl fmt::Display for TransferCallMsgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            TransferCallMsgErrorKind::TooLong { len, max_len } => {
+                write!(f, "msg is {} bytes long, the maximum is {}", len, max_len)
+            }
+            TransferCallMsgErrorKind::InvalidJson(error) => write!(f, "invalid msg: {}", error),
+            TransferCallMsgErrorKind::UnsupportedVersion { found: Some(found), expected } => {
+                write!(f, "unsupported msg version {}, expected {}", found, expected)
+            }
+            TransferCallMsgErrorKind::UnsupportedVersion { found: None, expected } => {
+                write!(f, "msg has no version, expected {}", expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TransferCallMsgError {}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::test_utils::get_logs;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(tag = "action")]
+    enum Msg {
+        Stake { pool_id: u64 },
+    }
+
+    impl TransferCallMsg for Msg {}
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(tag = "action")]
+    enum VersionedMsg {
+        Stake { pool_id: u64 },
+    }
+
+    impl TransferCallMsg for VersionedMsg {
+        const MAX_LEN: usize = 64;
+        const VERSION: Option<u64> = Some(2);
+    }
+
+    #[test]
+    fn parse_msg() {
+        assert_eq!(
+            Msg::parse_msg(r#"{"action":"Stake","pool_id":1}"#),
+            Ok(Msg::Stake { pool_id: 1 })
+        );
+        assert_eq!(
+            VersionedMsg::parse_msg(r#"{"version":2,"action":"Stake","pool_id":1}"#),
+            Ok(VersionedMsg::Stake { pool_id: 1 })
+        );
+
+        let error = |msg: &str| VersionedMsg::parse_msg(msg).unwrap_err().to_string();
+        assert_eq!(
+            error(r#"{"version":1,"action":"Stake","pool_id":1}"#),
+            "unsupported msg version 1, expected 2"
+        );
+        assert_eq!(error(r#"{"action":"Stake","pool_id":1}"#), "msg has no version, expected 2");
+        assert_eq!(error(&" ".repeat(65)), "msg is 65 bytes long, the maximum is 64");
+        assert!(error(r#"{"version":2,"action":"Unstake"}"#).starts_with("invalid msg: "));
+    }
+
+    #[test]
+    fn parse_msg_or_refund() {
+        assert_eq!(Msg::parse_msg_or_refund(""), None);
+        assert_eq!(get_logs().len(), 1);
+        assert!(get_logs()[0].starts_with("Refunding transfer: invalid msg: "));
+    }
+}
@@ -0,0 +1,40 @@
+use near_sdk::test_utils::{accounts, VMContextBuilder};
+use near_sdk::{serde_json, testing_env, NearToken, PromiseAction, PromiseDescription};
+
+near_sdk::abi_client!("tests/abi/status_message.json");
+
+#[test]
+fn abi_client_call() {
+    testing_env!(VMContextBuilder::new().build());
+
+    let promise = status_message::ext(accounts(1))
+        .with_attached_deposit(NearToken::from_millinear(1))
+        .set_status("hello".to_string(), status_message::Mood::Happy);
+    let PromiseDescription::Single { receiver_id, actions, .. } = promise.describe() else {
+        panic!("expected a single promise");
+    };
+    assert_eq!(receiver_id, accounts(1));
+    let [PromiseAction::FunctionCallWeight { function_name, arguments, amount, .. }] =
+        actions.as_slice()
+    else {
+        panic!("expected a function call");
+    };
+    assert_eq!(function_name, "set_status");
+    assert_eq!(arguments.as_slice(), br#"{"message":"hello","mood":"happy"}"#);
+    assert_eq!(*amount, NearToken::from_millinear(1));
+}
+
+#[test]
+fn abi_client_types() {
+    let status: status_message::Status =
+        serde_json::from_str(r#"{"message":"hi","mood":"sad"}"#).unwrap();
+    assert_eq!(
+        status,
+        status_message::Status {
+            message: "hi".to_string(),
+            mood: status_message::Mood::Sad,
+            updated_at: None,
+        }
+    );
+    assert_eq!(serde_json::to_string(&status).unwrap(), r#"{"message":"hi","mood":"sad"}"#);
+}
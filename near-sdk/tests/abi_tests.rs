@@ -47,6 +47,11 @@ fn ensure_abi_for_prepended_functions() {
             method
         );
     });
+
+    assert!(
+        near_abi_symbols.contains("__near_abi___contract"),
+        "ABI should contain the metadata of the contract"
+    );
 }
 
 const fn dylib_extension() -> &'static str {
@@ -0,0 +1,87 @@
+use near_sdk::__private::{storage_key_prefixes_collide, StorageKeyPrefixes};
+use near_sdk::{assert_unique_storage_keys, near, BorshStorageKey, IntoStorageKey, StorageKey};
+
+#[near(serializers=[borsh])]
+#[derive(BorshStorageKey)]
+enum DefaultKey {
+    Accounts,
+    Balances { account_hash: Vec<u8> },
+}
+
+#[near(serializers=[borsh])]
+#[derive(BorshStorageKey)]
+#[borsh(use_discriminant = true)]
+#[repr(u8)]
+enum DiscriminantKey {
+    Accounts = 3,
+    Balances,
+}
+
+#[derive(BorshStorageKey)]
+enum PrefixedKey<'a, T> {
+    #[storage_key(prefix = b"acc")]
+    Accounts,
+    #[storage_key(prefix = "bal")]
+    Balances(&'a str, T),
+    #[storage_key(prefix = b"\xff\x00")]
+    Nested { id: u32 },
+    #[storage_key(prefix = b"ord", nested)]
+    OrdersOf,
+}
+
+#[near(serializers=[borsh])]
+#[derive(BorshStorageKey)]
+enum NestedKey {
+    Orders,
+    #[storage_key(nested)]
+    OrdersOf,
+}
+
+assert_unique_storage_keys!(DefaultKey, PrefixedKey<'static, u8>);
+
+#[test]
+fn default_prefixes() {
+    assert_eq!(DefaultKey::PREFIXES, [b"\x00", b"\x01"]);
+    assert_eq!(DiscriminantKey::PREFIXES, [b"\x03", b"\x04"]);
+    assert_eq!(
+        DefaultKey::Balances { account_hash: vec![7] }.into_storage_key(),
+        [1, 1, 0, 0, 0, 7]
+    );
+    assert_eq!(DiscriminantKey::Balances.into_storage_key(), [4]);
+}
+
+#[test]
+fn explicit_prefixes() {
+    assert_eq!(
+        PrefixedKey::<u8>::PREFIXES,
+        [b"acc".as_slice(), b"bal".as_slice(), b"\xff\x00".as_slice(), b"ord".as_slice()]
+    );
+    assert_eq!(PrefixedKey::<u8>::Accounts.into_storage_key(), b"acc");
+    assert_eq!(
+        PrefixedKey::Balances("a", 2u8).into_storage_key(),
+        [b'b', b'a', b'l', 1, 0, 0, 0, b'a', 2]
+    );
+    assert_eq!(PrefixedKey::<u8>::Nested { id: 1 }.into_storage_key(), [255, 0, 1, 0, 0, 0]);
+}
+
+#[test]
+fn colliding_prefixes() {
+    assert!(!storage_key_prefixes_collide(&[
+        DefaultKey::PREFIXES,
+        DiscriminantKey::PREFIXES,
+        PrefixedKey::<u8>::PREFIXES
+    ]));
+    assert!(storage_key_prefixes_collide(&[DefaultKey::PREFIXES, &[b"\x01abc"]]));
+    assert!(storage_key_prefixes_collide(&[&[b"ab"], &[b"c", b"abc"]]));
+    assert!(!storage_key_prefixes_collide(&[&[b"ab"], &[b"ac", b"b"]]));
+}
+
+#[test]
+fn nested_prefixes() {
+    assert_eq!(StorageKey::nested(NestedKey::Orders, "a").as_bytes(), [0, 1, 0, 0, 0, b'a']);
+    assert_eq!(NestedKey::orders_of("a"), StorageKey::nested(NestedKey::OrdersOf, "a"));
+    assert_eq!(NestedKey::orders_of(&7u8).into_storage_key(), [1, 7]);
+    assert_eq!(PrefixedKey::<u8>::orders_of(&[1u8, 2]).into_storage_key(), b"ord\x01\x02");
+    // Nested prefixes can be nested again.
+    assert_eq!(StorageKey::nested(NestedKey::orders_of(&1u8), &2u8).into_storage_key(), [1, 1, 2]);
+}
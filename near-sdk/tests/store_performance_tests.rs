@@ -433,6 +433,41 @@ async fn iterable_vs_unordered() -> anyhow::Result<()> {
     Ok(())
 }
 
+// This test demonstrates the gas saved by `Vector::extend_from_slice` over pushing the same
+// elements one by one.
+#[tokio::test]
+async fn vector_extend_from_slice() -> anyhow::Result<()> {
+    let element_number = 1000;
+    let (account, contract_id) = setup(Contract::StoreContract).await?;
+
+    let pushed_gas = account
+        .call(&contract_id, "insert")
+        .args_json((Collection::Vector, DEFAULT_INDEX_OFFSET, element_number))
+        .max_gas()
+        .transact()
+        .await?
+        .unwrap()
+        .total_gas_burnt;
+
+    let extended_gas = account
+        .call(&contract_id, "vec_extend_from_slice")
+        .args_json((DEFAULT_INDEX_OFFSET, element_number))
+        .max_gas()
+        .transact()
+        .await?
+        .unwrap()
+        .total_gas_burnt;
+
+    assert!(
+        extended_gas < pushed_gas,
+        "extend_from_slice is not cheaper than push: {} >= {}",
+        extended_gas,
+        pushed_gas
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_lazy() -> anyhow::Result<()> {
     let (account, contract_id) = setup(Contract::LazyContract).await?;
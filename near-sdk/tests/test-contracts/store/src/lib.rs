@@ -77,6 +77,18 @@ impl StoreContract {
         }
     }
 
+    #[payable]
+    pub fn vec_extend_from_slice(&mut self, index_offset: usize, iterations: usize) {
+        let mut insertable = self.insertable();
+        let mut elements = Vec::with_capacity(iterations + 1);
+        for iter in 0..=iterations {
+            insertable.index = iter as u32;
+            insertable.index += index_offset as u32;
+            elements.push(insertable.clone());
+        }
+        self.vec.extend_from_slice(&elements);
+    }
+
     #[payable]
     pub fn remove(&mut self, col: Collection, iterations: usize) {
         let mut insertable = self.insertable();
@@ -0,0 +1,48 @@
+use near_sdk::test_utils::VMContextBuilder;
+use near_sdk::{env, near, testing_env};
+
+#[near(serializers=[borsh])]
+pub struct StateV1 {
+    greeting: String,
+}
+
+#[near(serializers=[borsh])]
+pub struct StateV2 {
+    greeting: String,
+    count: u32,
+}
+
+#[near(contract_state(versioned(StateV1, StateV2)))]
+pub struct State {
+    greeting: String,
+    count: u64,
+    #[borsh(skip)]
+    cached: bool,
+}
+
+impl From<StateV1> for StateV2 {
+    fn from(state: StateV1) -> Self {
+        Self { greeting: state.greeting, count: 1 }
+    }
+}
+
+impl From<StateV2> for State {
+    fn from(state: StateV2) -> Self {
+        Self { greeting: state.greeting, count: state.count.into(), cached: false }
+    }
+}
+
+#[test]
+fn versioned_state_upgrade() {
+    testing_env!(VMContextBuilder::new().build());
+
+    env::state_write(&StateVersionedState::V1(StateV1 { greeting: "hello".to_string() }));
+    let state: State = env::state_read().unwrap();
+    assert_eq!(state.greeting, "hello");
+    assert_eq!(state.count, 1);
+
+    env::state_write(&State { greeting: "hi".to_string(), count: 5, cached: true });
+    assert!(matches!(env::state_read().unwrap(), StateVersionedState::V3(_)));
+    let state: State = env::state_read().unwrap();
+    assert_eq!((state.greeting.as_str(), state.count, state.cached), ("hi", 5, false));
+}
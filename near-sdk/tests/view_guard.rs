@@ -0,0 +1,51 @@
+use near_sdk::store::LookupMap;
+use near_sdk::test_utils::VMContextBuilder;
+use near_sdk::{near, testing_env};
+
+#[near(contract_state)]
+pub struct Contract {
+    counts: LookupMap<String, u64>,
+}
+
+impl Default for Contract {
+    fn default() -> Self {
+        Self { counts: LookupMap::new(b"c") }
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn increment(&mut self, key: String) -> u64 {
+        let count = self.get_count(key.clone()) + 1;
+        self.counts.insert(key, count);
+        self.counts.flush();
+        count
+    }
+
+    pub fn get_count(&self, key: String) -> u64 {
+        self.counts.get(&key).copied().unwrap_or_default()
+    }
+
+    pub fn get_count_cached(&self, key: String) -> u64 {
+        let mut cache = LookupMap::new(b"cache".to_vec());
+        let count = self.get_count(key.clone());
+        cache.insert(key, count);
+        count
+    }
+}
+
+#[test]
+fn view_guard_allows_reads() {
+    testing_env!(VMContextBuilder::new().build());
+    let mut contract = Contract::default();
+    assert_eq!(contract.increment("a".to_string()), 1);
+    assert_eq!(contract.increment("a".to_string()), 2);
+    assert_eq!(contract.get_count("a".to_string()), 2);
+}
+
+#[test]
+#[should_panic(expected = "View method get_count_cached cannot write to the storage")]
+fn view_guard_rejects_writes() {
+    testing_env!(VMContextBuilder::new().build());
+    Contract::default().get_count_cached("a".to_string());
+}
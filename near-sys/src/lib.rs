@@ -146,6 +146,26 @@ extern "C" {
         beneficiary_id_len: u64,
         beneficiary_id_ptr: u64,
     );
+    pub fn promise_batch_action_deploy_global_contract(
+        promise_index: u64,
+        code_len: u64,
+        code_ptr: u64,
+    );
+    pub fn promise_batch_action_deploy_global_contract_by_account_id(
+        promise_index: u64,
+        code_len: u64,
+        code_ptr: u64,
+    );
+    pub fn promise_batch_action_use_global_contract(
+        promise_index: u64,
+        code_hash_len: u64,
+        code_hash_ptr: u64,
+    );
+    pub fn promise_batch_action_use_global_contract_by_account_id(
+        promise_index: u64,
+        account_id_len: u64,
+        account_id_ptr: u64,
+    );
     pub fn promise_yield_create(
         function_name_len: u64,
         function_name_ptr: u64,
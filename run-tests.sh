@@ -4,5 +4,5 @@ set -e
 
 cargo fmt --check --all
 cargo clippy --tests --all-features -- -Dclippy::all
-cargo test --all --features unstable,legacy
+cargo test --all --features unstable,legacy-collections
 ./examples/test_all.sh